@@ -5,12 +5,12 @@
 
 use pyo3::prelude::*;
 use riscv_core::config::Config;
-use serde_json;
 
 /// Converts a Python dict to a simulator `Config`.
 ///
 /// The dict is serialized to JSON and then deserialized into `Config`. Keys must match
-/// the Rust config structure (e.g., `general`, `system`, `memory`, `cache`, `pipeline`).
+/// the Rust config structure (e.g., `general`, `system`, `memory`, `cache`, `pipeline`),
+/// and the result must pass [`Config::validate`].
 ///
 /// # Arguments
 ///
@@ -19,16 +19,13 @@ use serde_json;
 ///
 /// # Returns
 ///
-/// The deserialized `Config`, or a `PyErr` if the dict is invalid.
+/// The deserialized `Config`, or a `PyValueError` listing why the dict is invalid.
 pub fn py_dict_to_config(py: Python, dict: &Bound<'_, PyAny>) -> PyResult<Config> {
     let json = py.import("json")?;
     let dumps = json.getattr("dumps")?;
     let json_str_obj = dumps.call1((dict,))?;
     let json_str: String = json_str_obj.extract()?;
 
-    let config: Config = serde_json::from_str(&json_str).map_err(|e| {
-        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid config: {}", e))
-    })?;
-
-    Ok(config)
+    Config::from_json(&json_str)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
 }
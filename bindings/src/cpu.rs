@@ -2,21 +2,30 @@
 //!
 //! Exposes the simulator CPU to Python: create from config dict, tick, run until exit,
 //! load kernel, and retrieve stats. Handles Python signal checks and stdout flush for UART visibility.
+//! With `system.harts` above 1 the harts tick round-robin; accessors report hart 0.
 
 use crate::conversion::py_dict_to_config;
 use crate::stats::PyStats;
 use crate::system::PySystem;
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use riscv_core::core::Cpu;
 use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::smp::Smp;
 use riscv_core::sim::loader;
 use std::io::Write;
 
-/// Python-exposed CPU: wraps the core `Cpu` for stepping and running from Python.
+/// Python-exposed CPU: wraps the core harts for stepping and running from Python.
 #[pyclass]
 pub struct PyCpu {
-    pub inner: Cpu,
+    pub harts: Smp,
+}
+
+impl PyCpu {
+    /// Returns hart 0, which loading, registers, and statistics refer to.
+    fn cpu(&mut self) -> &mut Cpu {
+        self.harts.hart(0)
+    }
 }
 
 #[pymethods]
@@ -33,7 +42,8 @@ impl PyCpu {
     /// * `config_dict` - A Python dictionary containing configuration parameters.
     ///
     /// # Errors
-    /// Returns a `PyRuntimeError` if the system instance has already been attached to a CPU.
+    /// Returns a `PyRuntimeError` if the system instance has already been attached to a CPU,
+    /// or a `PyValueError` if the configuration is invalid.
     #[new]
     fn new(py: Python, system: &mut PySystem, config_dict: &Bound<'_, PyAny>) -> PyResult<Self> {
         let sys = system.inner.take().ok_or_else(|| {
@@ -42,9 +52,8 @@ impl PyCpu {
 
         let config = py_dict_to_config(py, config_dict)?;
 
-        let cpu = Cpu::new(sys, &config);
-
-        Ok(PyCpu { inner: cpu })
+        let harts = Smp::new(sys, &config).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyCpu { harts })
     }
 
     /// Loads a kernel into memory and prepares the CPU for execution.
@@ -71,8 +80,10 @@ impl PyCpu {
     ) -> PyResult<()> {
         let config = py_dict_to_config(py, config_dict)?;
 
-        loader::setup_kernel_load(&mut self.inner, &config, "", dtb_path, Some(kernel_path));
-        self.inner.direct_mode = false;
+        let cpu = self.cpu();
+        loader::setup_kernel_load(cpu, &config, "", dtb_path, Some(kernel_path));
+        cpu.direct_mode = false;
+        self.harts.sync_boot_state();
         Ok(())
     }
 
@@ -84,20 +95,22 @@ impl PyCpu {
     ///
     /// Returns a `PyRuntimeError` if the underlying CPU operation fails.
     pub fn tick(&mut self) -> PyResult<()> {
-        self.inner.tick().map_err(|e| PyRuntimeError::new_err(e))
+        self.harts
+            .tick()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
 
     /// Returns a snapshot of the current CPU statistics.
     ///
     /// This method clones the internal statistics and converts them into a [`PyStats`]
     /// object, typically for exposure to Python.
-    pub fn get_stats(&self) -> PyStats {
-        PyStats::from(self.inner.stats.clone())
+    pub fn get_stats(&mut self) -> PyStats {
+        PyStats::from(self.cpu().stats.clone())
     }
 
     /// Returns the current value of the program counter (PC).
-    pub fn get_pc(&self) -> u64 {
-        self.inner.pc
+    pub fn get_pc(&mut self) -> u64 {
+        self.cpu().pc
     }
 
     /// Runs the simulation until the program exits (e.g., via SysCon power-off).
@@ -110,21 +123,22 @@ impl PyCpu {
     /// The exit code returned by the simulated program.
     pub fn run(&mut self, py: Python) -> PyResult<u64> {
         loop {
-            if self.inner.stats.cycles % 10000 == 0 {
+            if self.cpu().stats.cycles % 10000 == 0 {
                 if let Err(e) = py.check_signals() {
                     return Err(e);
                 }
                 let _ = std::io::stdout().flush();
             }
 
-            match self.inner.tick() {
+            match self.harts.tick() {
                 Ok(_) => {
-                    if let Some(code) = self.inner.take_exit() {
+                    if let Some(code) = self.harts.take_exit() {
+                        self.cpu().bus.shutdown();
                         let _ = std::io::stdout().flush();
                         return Ok(code);
                     }
                 }
-                Err(e) => return Err(PyRuntimeError::new_err(e)),
+                Err(e) => return Err(PyRuntimeError::new_err(e.to_string())),
             }
         }
     }
@@ -132,51 +146,57 @@ impl PyCpu {
     /// Run until exit or until max_cycles is reached. Returns exit code if the program exited, None if cycle limit hit.
     /// Flushes stdout before returning so UART output is visible when called from Python.
     pub fn run_with_limit(&mut self, py: Python, max_cycles: u64) -> PyResult<Option<u64>> {
-        let start_cycles = self.inner.stats.cycles;
-        while self.inner.stats.cycles - start_cycles < max_cycles {
-            if (self.inner.stats.cycles - start_cycles) % 10000 == 0 {
+        let start_cycles = self.cpu().stats.cycles;
+        while self.cpu().stats.cycles - start_cycles < max_cycles {
+            if (self.cpu().stats.cycles - start_cycles) % 10000 == 0 {
                 if let Err(e) = py.check_signals() {
                     return Err(e);
                 }
             }
-            match self.inner.tick() {
+            match self.harts.tick() {
                 Ok(_) => {
-                    if let Some(code) = self.inner.take_exit() {
+                    if let Some(code) = self.harts.take_exit() {
+                        self.cpu().bus.shutdown();
                         let _ = std::io::stdout().flush();
                         return Ok(Some(code));
                     }
                 }
-                Err(e) => return Err(PyRuntimeError::new_err(e)),
+                Err(e) => return Err(PyRuntimeError::new_err(e.to_string())),
             }
         }
         let _ = std::io::stdout().flush();
         Ok(None)
     }
 
-    /// Enable or disable direct (bare-metal) mode. When enabled, traps cause exit instead of jumping to trap handler.
+    /// Enable or disable direct (bare-metal) mode on every hart. When enabled, traps cause exit instead of jumping to trap handler.
     pub fn set_direct_mode(&mut self, enabled: bool) {
-        self.inner.direct_mode = enabled;
-        if enabled {
-            self.inner.privilege = PrivilegeMode::User;
+        for id in 0..self.harts.len() {
+            let cpu = self.harts.hart(id);
+            cpu.direct_mode = enabled;
+            if enabled {
+                cpu.privilege = PrivilegeMode::User;
+            }
         }
     }
 
-    /// Set the program counter.
+    /// Set the program counter of every hart.
     pub fn set_pc(&mut self, pc: u64) {
-        self.inner.pc = pc;
+        for id in 0..self.harts.len() {
+            self.harts.hart(id).pc = pc;
+        }
     }
 
     /// Write a general-purpose register (0–31). x0 is read-only and ignored.
     pub fn write_register(&mut self, reg: u8, value: u64) {
         if reg < 32 {
-            self.inner.regs.write(reg as usize, value);
+            self.cpu().regs.write(reg as usize, value);
         }
     }
 
     /// Read a general-purpose register (0–31).
-    pub fn read_register(&self, reg: u8) -> u64 {
+    pub fn read_register(&mut self, reg: u8) -> u64 {
         if reg < 32 {
-            self.inner.regs.read(reg as usize)
+            self.cpu().regs.read(reg as usize)
         } else {
             0
         }
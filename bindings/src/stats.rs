@@ -2,7 +2,8 @@
 //!
 //! Exposes simulation statistics to Python: getters for cycles, cache hits/misses,
 //! branch accuracy, and instruction mix; `print` / `print_sections` for human-readable
-//! output; `to_dict` for JSON-serializable export (multisim, scripting).
+//! output; `to_dict` for JSON-serializable export (multisim, scripting) and `to_json`
+//! for the sectioned report as a JSON string.
 
 use pyo3::prelude::*;
 use riscv_core::stats::SimStats;
@@ -31,12 +32,17 @@ impl PyStats {
         self.inner.print();
     }
 
-    /// Print only the given sections. Options: "summary", "core", "instruction_mix", "branch", "memory".
+    /// Print only the given sections. Options: "summary", "core", "instruction_mix", "branch", "memory", "mmu".
     /// Pass an empty list for full dump. Example: print_sections(["summary", "memory"]) for cycles + caches.
     fn print_sections(&self, sections: Vec<String>) {
         self.inner.print_sections(&sections);
     }
 
+    /// Every counter and derived metric as a JSON string, keyed like the text report.
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
     #[getter]
     fn cycles(&self) -> u64 {
         self.inner.cycles
@@ -70,6 +76,14 @@ impl PyStats {
         self.inner.l2_misses
     }
     #[getter]
+    fn l2_i_hits(&self) -> u64 {
+        self.inner.l2_i_hits
+    }
+    #[getter]
+    fn l2_i_misses(&self) -> u64 {
+        self.inner.l2_i_misses
+    }
+    #[getter]
     fn l3_hits(&self) -> u64 {
         self.inner.l3_hits
     }
@@ -78,6 +92,38 @@ impl PyStats {
         self.inner.l3_misses
     }
     #[getter]
+    fn mshr_overlapped_misses(&self) -> u64 {
+        self.inner.mshr_overlapped_misses
+    }
+    #[getter]
+    fn mshr_merged_misses(&self) -> u64 {
+        self.inner.mshr_merged_misses
+    }
+    #[getter]
+    fn stalls_mshr_full(&self) -> u64 {
+        self.inner.stalls_mshr_full
+    }
+    #[getter]
+    fn itlb_hits(&self) -> u64 {
+        self.inner.itlb_hits
+    }
+    #[getter]
+    fn itlb_misses(&self) -> u64 {
+        self.inner.itlb_misses
+    }
+    #[getter]
+    fn dtlb_hits(&self) -> u64 {
+        self.inner.dtlb_hits
+    }
+    #[getter]
+    fn dtlb_misses(&self) -> u64 {
+        self.inner.dtlb_misses
+    }
+    #[getter]
+    fn page_walks(&self) -> u64 {
+        self.inner.page_walks
+    }
+    #[getter]
     fn stalls_mem(&self) -> u64 {
         self.inner.stalls_mem
     }
@@ -90,6 +136,14 @@ impl PyStats {
         self.inner.stalls_data
     }
     #[getter]
+    fn stalls_fpu(&self) -> u64 {
+        self.inner.stalls_fpu
+    }
+    #[getter]
+    fn stalls_div(&self) -> u64 {
+        self.inner.stalls_div
+    }
+    #[getter]
     fn branch_predictions(&self) -> u64 {
         self.inner.branch_predictions
     }
@@ -110,16 +164,55 @@ impl PyStats {
         d.set_item("dcache_misses", s.dcache_misses)?;
         d.set_item("l2_hits", s.l2_hits)?;
         d.set_item("l2_misses", s.l2_misses)?;
+        d.set_item("l2_i_hits", s.l2_i_hits)?;
+        d.set_item("l2_i_misses", s.l2_i_misses)?;
         d.set_item("l3_hits", s.l3_hits)?;
         d.set_item("l3_misses", s.l3_misses)?;
+        d.set_item("icache_mpki", s.mpki(s.icache_misses))?;
+        d.set_item("dcache_mpki", s.mpki(s.dcache_misses))?;
+        d.set_item("l2_mpki", s.mpki(s.l2_misses))?;
+        d.set_item("l3_mpki", s.mpki(s.l3_misses))?;
+        let amat = s.amat();
+        let mut amat_by_level = std::collections::BTreeMap::new();
+        for (level, cycles) in [
+            ("l1_i", amat.l1_i),
+            ("l1_d", amat.l1_d),
+            ("l2", amat.l2),
+            ("l2_i", amat.l2_i),
+            ("l3", amat.l3),
+        ] {
+            if let Some(cycles) = cycles {
+                amat_by_level.insert(level, cycles);
+            }
+        }
+        d.set_item("amat", amat_by_level)?;
+        d.set_item("mem_accesses", s.mem_accesses)?;
+        d.set_item("mem_avg_latency", s.mem_latency())?;
+        d.set_item("icache_snoop_invalidations", s.icache_snoop_invalidations)?;
+        d.set_item("mshr_overlapped_misses", s.mshr_overlapped_misses)?;
+        d.set_item("mshr_merged_misses", s.mshr_merged_misses)?;
+        d.set_item("stalls_mshr_full", s.stalls_mshr_full)?;
+        d.set_item("victim_hits", s.victim_hits)?;
+        d.set_item("itlb_hits", s.itlb_hits)?;
+        d.set_item("itlb_misses", s.itlb_misses)?;
+        d.set_item("dtlb_hits", s.dtlb_hits)?;
+        d.set_item("dtlb_misses", s.dtlb_misses)?;
+        d.set_item("page_walks", s.page_walks)?;
         d.set_item("stalls_mem", s.stalls_mem)?;
         d.set_item("stalls_control", s.stalls_control)?;
         d.set_item("stalls_data", s.stalls_data)?;
+        d.set_item("stalls_fpu", s.stalls_fpu)?;
+        d.set_item("stalls_div", s.stalls_div)?;
 
         d.set_item("cycles_user", s.cycles_user)?;
         d.set_item("cycles_kernel", s.cycles_kernel)?;
         d.set_item("cycles_machine", s.cycles_machine)?;
+        d.set_item("cycles_idle", s.cycles_idle)?;
         d.set_item("traps_taken", s.traps_taken)?;
+        d.set_item("traps_by_cause", s.traps_by_cause.clone())?;
+        d.set_item("branches_by_type", s.branches_by_type.clone())?;
+        d.set_item("mispredicts_by_type", s.mispredicts_by_type.clone())?;
+        d.set_item("fused_pairs", s.fused_pairs)?;
 
         d.set_item("branch_predictions", s.branch_predictions)?;
         d.set_item("branch_mispredictions", s.branch_mispredictions)?;
@@ -1,7 +1,7 @@
 //! RISC-V cycle-accurate simulator CLI.
 //!
 //! This binary provides a single entry point for all simulation modes. It performs:
-//! 1. **Direct run:** Execute a bare-metal binary (default config, no kernel).
+//! 1. **Direct run:** Execute a bare-metal flat binary or ELF executable (default config, no kernel).
 //! 2. **Kernel boot:** Load kernel image and optional disk/DTB; run in supervisor mode.
 //! 3. **Script run:** Execute a Python script (gem5-style) with `riscv_emulator` injected; supports P550System, multisim, and custom sweeps.
 
@@ -10,12 +10,26 @@ use pyo3::prelude::*;
 use pyo3::types::PyList;
 use std::ffi::CString;
 use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use std::{fs, process};
 
+use riscv_core::common::SimError;
 use riscv_core::config::Config;
 use riscv_core::core::Cpu;
+use riscv_core::core::cpu::profile::PROFILE_TOP_N;
+use riscv_core::core::smp::Smp;
+use riscv_core::sim::gdbstub::{self, SessionEnd};
 use riscv_core::sim::loader;
+use riscv_core::sim::perfetto::{DEFAULT_SAMPLE_INTERVAL, PerfettoTracer};
 use riscv_core::soc::System;
+use riscv_core::stats::{ProgressMeter, SimStats};
+
+/// Number of simulated cycles between host-clock checks for `--progress`.
+const PROGRESS_CHECK_CYCLES: u64 = 1 << 20;
+
+/// Minimum host time between `--progress` readouts.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Parser, Debug)]
 #[command(
@@ -30,14 +44,47 @@ struct Cli {
     command: Option<Commands>,
 }
 
+/// Bare-metal program requested on the command line.
+#[derive(Debug)]
+enum Program {
+    /// Flat binary loaded at the RAM base and entered there.
+    Flat(String),
+    /// ELF executable loaded at its segment addresses and entered at its entry point.
+    Elf(String),
+}
+
+/// Interactive run options requested on the command line.
+#[derive(Debug)]
+struct RunOptions {
+    /// Periodically print instantaneous MIPS to stderr.
+    progress: bool,
+    /// Wait for a GDB connection on this TCP port before running.
+    gdb: Option<u16>,
+}
+
+/// Trace outputs requested on the command line.
+#[derive(Debug)]
+struct TraceOutputs {
+    /// Trap trace destination: `"-"` for stderr, otherwise a file path.
+    traps: Option<String>,
+    /// Perfetto timeline file path.
+    perfetto: Option<String>,
+    /// Spike-format commit log file path.
+    commit_log: Option<String>,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Run a single binary (bare-metal) or kernel (OS boot).
     Run {
         /// Bare-metal binary to execute (direct mode).
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "elf")]
         file: Option<String>,
 
+        /// Bare-metal ELF executable to execute (direct mode).
+        #[arg(long, value_name = "FILE")]
+        elf: Option<String>,
+
         /// Kernel image for OS boot (disables direct mode).
         #[arg(long)]
         kernel: Option<String>,
@@ -49,6 +96,42 @@ enum Commands {
         /// Device tree blob for OS boot.
         #[arg(long)]
         dtb: Option<String>,
+
+        /// Periodically print instantaneous (rolling-window) MIPS to stderr.
+        #[arg(long)]
+        progress: bool,
+
+        /// Wait for a GDB remote connection on PORT (`target remote :PORT`) before running.
+        #[arg(long, value_name = "PORT")]
+        gdb: Option<u16>,
+
+        /// Log every taken trap to stderr, or to FILE if given.
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "-")]
+        trace_traps: Option<String>,
+
+        /// Write a Chrome tracing (Perfetto) JSON timeline of the pipeline to FILE.
+        #[arg(long, value_name = "FILE")]
+        perfetto: Option<String>,
+
+        /// Write a Spike-compatible log of committed instructions to FILE.
+        #[arg(long, value_name = "FILE")]
+        commit_log: Option<String>,
+
+        /// In direct mode, treat WFI as fatal: dump state and exit with code 103.
+        #[arg(long)]
+        trap_on_wfi: bool,
+
+        /// Count committed instructions and stall cycles per PC and print the hottest PCs at exit.
+        #[arg(long)]
+        profile: bool,
+
+        /// Symbol file (ELF or `nm` output) for `function+offset` annotations in reports.
+        #[arg(long, value_name = "FILE")]
+        symbols: Option<String>,
+
+        /// Number of harts sharing the system; all start at the program's entry point.
+        #[arg(long, default_value_t = 1)]
+        harts: usize,
     },
 
     /// Run a Python script (gem5-style). Script gets argv as sys.argv. Use this for P550System, multisim, or any custom sweep.
@@ -68,10 +151,39 @@ fn main() {
     match cli.command {
         Some(Commands::Run {
             file,
+            elf,
             kernel,
             disk,
             dtb,
-        }) => cmd_run(file, kernel, disk, dtb),
+            progress,
+            gdb,
+            trace_traps,
+            perfetto,
+            commit_log,
+            trap_on_wfi,
+            profile,
+            symbols,
+            harts,
+        }) => {
+            let mut config = Config::default();
+            config.general.trap_on_wfi = trap_on_wfi;
+            config.general.profile = profile;
+            config.general.symbols = symbols;
+            config.system.harts = harts;
+            // The defaults are valid; this rejects bad overrides such as `--harts 0`.
+            if let Err(e) = config.validate() {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+            let traces = TraceOutputs {
+                traps: trace_traps,
+                perfetto,
+                commit_log,
+            };
+            let program = elf.map(Program::Elf).or(file.map(Program::Flat));
+            let options = RunOptions { progress, gdb };
+            cmd_run(config, program, kernel, disk, dtb, options, traces)
+        }
         Some(Commands::Script { path, args }) => run_python_script(&path, args),
         None => {
             let args: Vec<String> = std::env::args().skip(1).collect();
@@ -85,6 +197,7 @@ fn main() {
             eprintln!("RISC-V Simulator — pass a subcommand or a .py script");
             eprintln!();
             eprintln!("  sim run -f <binary>        Bare-metal run");
+            eprintln!("  sim run --elf <a.out>      Bare-metal run of an ELF executable");
             eprintln!("  sim run --kernel <Image>   OS boot");
             eprintln!("  sim <script.py> [args...]  Run script (e.g. sim scripts/p550/run.py)");
             eprintln!("  sim script <script.py>     Same, explicit subcommand");
@@ -97,13 +210,75 @@ fn main() {
 
 /// Runs the simulator: loads kernel or bare-metal binary, then loops on `tick` until exit or trap.
 ///
-/// Uses default config; loads kernel image and optional DTB if `kernel` is set, otherwise
-/// loads the bare-metal binary at RAM base and sets PC. On trap, dumps state and exits with code 1.
-fn cmd_run(file: Option<String>, kernel: Option<String>, disk: String, dtb: Option<String>) {
-    let config = Config::default();
-
+/// `config` is the default config with command-line overrides applied (`--trap-on-wfi`,
+/// `--symbols`, `--profile`, `--harts`). Loads kernel image and optional DTB if `kernel` is
+/// set, otherwise loads the bare-metal program (a flat binary at RAM base, or an ELF at its
+/// segment addresses) and sets PC; every hart starts where hart 0 does, and traces, the
+/// report, and the final state are those of hart 0. On trap, dumps state and exits with code 1.
+/// Host-time measurement starts only once loading is done, so reported MIPS exclude setup; with
+/// `options.progress`, a rolling-window MIPS readout is printed to stderr about once per second;
+/// with `options.gdb`, the CPU is halted at its start PC until a debugger attaches and resumes it.
+/// With `traces.traps`, every taken trap is logged to stderr (`"-"`) or the named file; with
+/// `traces.perfetto`, a pipeline timeline is written to the named file and closed at exit;
+/// with `traces.commit_log`, every committed instruction is logged to the named file in
+/// Spike's `--log-commits` format.
+fn cmd_run(
+    config: Config,
+    program: Option<Program>,
+    kernel: Option<String>,
+    disk: String,
+    dtb: Option<String>,
+    options: RunOptions,
+    traces: TraceOutputs,
+) {
+    if options.gdb.is_some() && config.system.harts > 1 {
+        eprintln!("Error: --gdb debugs a single hart; drop --harts");
+        process::exit(1);
+    }
     let system = System::new(&config, &disk);
-    let mut cpu = Cpu::new(system, &config);
+    let mut harts = Smp::new(system, &config).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    });
+    let cpu = harts.hart(0);
+
+    match traces.traps.as_deref() {
+        None => {}
+        Some("-") => cpu.set_trap_trace(Box::new(std::io::stderr())),
+        Some(path) => match fs::File::create(path) {
+            Ok(f) => cpu.set_trap_trace(Box::new(f)),
+            Err(e) => {
+                eprintln!("Error: cannot create trap trace file {}: {}", path, e);
+                process::exit(1);
+            }
+        },
+    }
+
+    if let Some(path) = traces.commit_log.as_deref() {
+        match fs::File::create(path) {
+            Ok(f) => cpu.set_commit_log(Box::new(std::io::BufWriter::new(f))),
+            Err(e) => {
+                eprintln!("Error: cannot create commit log file {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let perfetto = traces.perfetto.map(|path| {
+        let f = fs::File::create(&path).unwrap_or_else(|e| {
+            eprintln!("Error: cannot create Perfetto trace file {}: {}", path, e);
+            process::exit(1);
+        });
+        let tracer = Arc::new(Mutex::new(PerfettoTracer::new(
+            Box::new(std::io::BufWriter::new(f)),
+            DEFAULT_SAMPLE_INTERVAL,
+        )));
+        let hook_tracer = Arc::clone(&tracer);
+        cpu.set_cycle_hook(Box::new(move |cpu| {
+            hook_tracer.lock().unwrap().observe(cpu)
+        }));
+        tracer
+    });
 
     println!("Configuration: default (Python-first config: use riscv_sim.config.SimConfig)");
     println!(
@@ -122,34 +297,145 @@ fn cmd_run(file: Option<String>, kernel: Option<String>, disk: String, dtb: Opti
         if let Some(ref d) = dtb {
             println!("    dtb={}", d);
         }
-        loader::setup_kernel_load(&mut cpu, &config, &disk, dtb, Some(kernel_path));
+        loader::setup_kernel_load(cpu, &config, &disk, dtb, Some(kernel_path));
         cpu.direct_mode = false;
-    } else if let Some(bin_path) = file {
+    } else if let Some(Program::Flat(bin_path)) = program {
         println!("[*] Direct execution: {}", bin_path);
         let bin_data = loader::load_binary(&bin_path);
         let load_addr = config.system.ram_base;
         cpu.bus.load_binary_at(&bin_data, load_addr);
         cpu.pc = load_addr;
+    } else if let Some(Program::Elf(elf_path)) = program {
+        println!("[*] Direct execution: {}", elf_path);
+        let elf_data = loader::load_binary(&elf_path);
+        cpu.pc = loader::load_elf(&mut cpu.bus.bus, &elf_data).unwrap_or_else(|e| {
+            eprintln!("Error: cannot load ELF {}: {}", elf_path, e);
+            process::exit(1);
+        });
     } else {
-        eprintln!("Error: specify --file <binary> or --kernel <Image>");
+        eprintln!("Error: specify --file <binary>, --elf <executable> or --kernel <Image>");
         eprintln!("  sim run -f software/bin/benchmarks/qsort.bin");
+        eprintln!("  sim run --elf a.out");
         eprintln!("  sim run --kernel Image [--disk rootfs.img]");
         process::exit(1);
     }
+    harts.sync_boot_state();
+
+    let cpu = harts.hart(0);
+    cpu.start_measurement();
+    let mut meter = ProgressMeter::new(&cpu.stats, PROGRESS_INTERVAL);
+
+    if let Some(port) = options.gdb {
+        println!(
+            "[*] Waiting for GDB on port {} (target remote :{})",
+            port, port
+        );
+        match gdbstub::serve(cpu, port) {
+            Ok(SessionEnd::Detached) => println!("[*] GDB detached; continuing"),
+            Ok(SessionEnd::Killed) => {
+                println!("\n[*] Killed by GDB");
+                finish_exit(cpu, perfetto.as_ref(), 0);
+            }
+            Ok(SessionEnd::Exited(code)) => finish_exit(cpu, perfetto.as_ref(), code),
+            Ok(SessionEnd::Faulted(e)) => finish_error(cpu, perfetto.as_ref(), e),
+            Err(e) => {
+                eprintln!("Error: GDB connection on port {}: {}", port, e);
+                process::exit(1);
+            }
+        }
+    }
 
     loop {
-        if let Err(e) = cpu.tick() {
-            eprintln!("\n[!] FATAL TRAP: {}", e);
-            cpu.dump_state();
-            cpu.stats.print();
-            process::exit(1);
+        if let Err(e) = harts.tick() {
+            finish_error(harts.hart(0), perfetto.as_ref(), e);
+        }
+        if let Some(code) = harts.take_exit() {
+            finish_exit(harts.hart(0), perfetto.as_ref(), code);
         }
-        if let Some(code) = cpu.take_exit() {
-            println!("\n[*] Exit code {}", code);
-            cpu.stats.print();
-            std::io::stdout().flush().ok();
-            process::exit(code as i32);
+        let stats = &harts.hart(0).stats;
+        if options.progress && stats.cycles.is_multiple_of(PROGRESS_CHECK_CYCLES) {
+            report_progress(&mut meter, stats);
+        }
+    }
+}
+
+/// Ends a run whose program exited: prints statistics and exits with the program's code.
+///
+/// # Arguments
+///
+/// * `cpu` - The CPU that ran the program.
+/// * `perfetto` - Timeline to close, if one is being written.
+/// * `code` - The program's exit code.
+fn finish_exit(cpu: &mut Cpu, perfetto: Option<&Arc<Mutex<PerfettoTracer>>>, code: u64) -> ! {
+    cpu.stop_measurement();
+    println!("\n[*] Exit code {}", code);
+    cpu.stats.print();
+    cpu.print_profile(PROFILE_TOP_N);
+    cpu.bus.shutdown();
+    finish_perfetto(perfetto);
+    finish_commit_log(cpu);
+    std::io::stdout().flush().ok();
+    process::exit(code as i32);
+}
+
+/// Ends a run that stopped with an error: dumps state and statistics and exits with code 1.
+///
+/// # Arguments
+///
+/// * `cpu` - The CPU that ran the program.
+/// * `perfetto` - Timeline to close, if one is being written.
+/// * `err` - The error that ended the simulation.
+fn finish_error(cpu: &mut Cpu, perfetto: Option<&Arc<Mutex<PerfettoTracer>>>, err: SimError) -> ! {
+    cpu.stop_measurement();
+    match err {
+        SimError::UnhandledTrap(trap, pc) => {
+            eprintln!("\n[!] Unhandled trap: {} at PC {}", trap, cpu.symbolize(pc))
         }
+        e => eprintln!("\n[!] FATAL: {}", e),
+    }
+    cpu.dump_state();
+    cpu.stats.print();
+    cpu.print_profile(PROFILE_TOP_N);
+    cpu.bus.shutdown();
+    finish_perfetto(perfetto);
+    finish_commit_log(cpu);
+    process::exit(1);
+}
+
+/// Closes the Perfetto timeline, if one is being written, reporting any write error.
+///
+/// # Arguments
+///
+/// * `tracer` - Tracer shared with the CPU's cycle hook.
+fn finish_perfetto(tracer: Option<&Arc<Mutex<PerfettoTracer>>>) {
+    if let Some(Err(e)) = tracer.map(|t| t.lock().unwrap().finish()) {
+        eprintln!("Error: writing Perfetto trace: {}", e);
+    }
+}
+
+/// Flushes and closes the commit log, if one is being written, reporting any write error.
+///
+/// # Arguments
+///
+/// * `cpu` - The CPU holding the commit log sink.
+fn finish_commit_log(cpu: &mut Cpu) {
+    if let Some(Err(e)) = cpu.clear_commit_log().map(|mut log| log.flush()) {
+        eprintln!("Error: writing commit log: {}", e);
+    }
+}
+
+/// Prints a rolling-window throughput line to stderr if the meter's interval has elapsed.
+///
+/// # Arguments
+///
+/// * `meter` - Progress meter holding the previous sample.
+/// * `stats` - Current simulation statistics.
+fn report_progress(meter: &mut ProgressMeter, stats: &SimStats) {
+    if let Some((mips, khz)) = meter.sample(stats) {
+        eprintln!(
+            "[progress] cycles={} insts={} mips={:.2} freq={:.2} kHz",
+            stats.cycles, stats.instructions_retired, mips, khz
+        );
     }
 }
 
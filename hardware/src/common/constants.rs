@@ -13,7 +13,10 @@ pub const PAGE_SIZE: u64 = 4096;
 pub const PAGE_SHIFT: u64 = 12;
 
 /// Mask for extracting the virtual page number (VPN) from an address.
-pub const VPN_MASK: u64 = 0x7FFFFFF;
+///
+/// Wide enough for the four 9-bit VPN fields of SV48; SV39 addresses are
+/// sign-extended above bit 38, so their tags remain unique.
+pub const VPN_MASK: u64 = 0xF_FFFF_FFFF;
 
 /// Mask for extracting the page offset from an address.
 pub const PAGE_OFFSET_MASK: u64 = PAGE_SIZE - 1;
@@ -80,3 +83,6 @@ pub const WFI_INSTRUCTION: u32 = 0x10500073;
 
 /// Number of cycles between status update messages during simulation.
 pub const STATUS_UPDATE_INTERVAL: u64 = 5_000_000;
+
+/// Exit code reported when a direct-mode run reaches WFI with `general.trap_on_wfi` set.
+pub const UNEXPECTED_WFI_EXIT_CODE: u64 = 103;
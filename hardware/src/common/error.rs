@@ -4,6 +4,7 @@
 //! 1. **Trap Representation:** Encompassing all synchronous exceptions and asynchronous interrupts.
 //! 2. **Translation Results:** Reporting the outcome of virtual-to-physical address translation.
 //! 3. **Error Handling:** Integrating with standard Rust error traits for system-level reporting.
+//! 4. **Simulation Errors:** Typed failures returned by `Cpu::tick`.
 
 use std::fmt;
 
@@ -96,6 +97,24 @@ pub enum Trap {
     /// The associated value is the faulting virtual address.
     StorePageFault(u64),
 
+    /// Instruction TLB miss exception (software-managed TLB only).
+    ///
+    /// Raised when an instruction fetch misses in the TLB.
+    /// The associated value is the faulting virtual address.
+    InstructionTlbMiss(u64),
+
+    /// Load TLB miss exception (software-managed TLB only).
+    ///
+    /// Raised when a load misses in the TLB.
+    /// The associated value is the faulting virtual address.
+    LoadTlbMiss(u64),
+
+    /// Store TLB miss exception (software-managed TLB only).
+    ///
+    /// Raised when a store or AMO misses in the TLB.
+    /// The associated value is the faulting virtual address.
+    StoreTlbMiss(u64),
+
     /// User software interrupt.
     ///
     /// Software interrupt intended for user mode.
@@ -148,6 +167,45 @@ pub enum Trap {
     DoubleFault(u64),
 }
 
+impl Trap {
+    /// Returns the variant name without its payload.
+    ///
+    /// # Returns
+    ///
+    /// A static name such as `"LoadPageFault"`, used to group traps by cause.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Trap::InstructionAddressMisaligned(_) => "InstructionAddressMisaligned",
+            Trap::InstructionAccessFault(_) => "InstructionAccessFault",
+            Trap::IllegalInstruction(_) => "IllegalInstruction",
+            Trap::Breakpoint(_) => "Breakpoint",
+            Trap::LoadAddressMisaligned(_) => "LoadAddressMisaligned",
+            Trap::LoadAccessFault(_) => "LoadAccessFault",
+            Trap::StoreAddressMisaligned(_) => "StoreAddressMisaligned",
+            Trap::StoreAccessFault(_) => "StoreAccessFault",
+            Trap::EnvironmentCallFromUMode => "EnvironmentCallFromUMode",
+            Trap::EnvironmentCallFromSMode => "EnvironmentCallFromSMode",
+            Trap::EnvironmentCallFromMMode => "EnvironmentCallFromMMode",
+            Trap::InstructionPageFault(_) => "InstructionPageFault",
+            Trap::LoadPageFault(_) => "LoadPageFault",
+            Trap::StorePageFault(_) => "StorePageFault",
+            Trap::InstructionTlbMiss(_) => "InstructionTlbMiss",
+            Trap::LoadTlbMiss(_) => "LoadTlbMiss",
+            Trap::StoreTlbMiss(_) => "StoreTlbMiss",
+            Trap::UserSoftwareInterrupt => "UserSoftwareInterrupt",
+            Trap::SupervisorSoftwareInterrupt => "SupervisorSoftwareInterrupt",
+            Trap::MachineSoftwareInterrupt => "MachineSoftwareInterrupt",
+            Trap::MachineTimerInterrupt => "MachineTimerInterrupt",
+            Trap::SupervisorTimerInterrupt => "SupervisorTimerInterrupt",
+            Trap::MachineExternalInterrupt => "MachineExternalInterrupt",
+            Trap::SupervisorExternalInterrupt => "SupervisorExternalInterrupt",
+            Trap::UserExternalInterrupt => "UserExternalInterrupt",
+            Trap::RequestedTrap(_) => "RequestedTrap",
+            Trap::DoubleFault(_) => "DoubleFault",
+        }
+    }
+}
+
 impl fmt::Display for Trap {
     /// Formats the trap for display.
     ///
@@ -180,6 +238,9 @@ impl fmt::Display for Trap {
             Trap::InstructionPageFault(addr) => write!(f, "InstructionPageFault({:#x})", addr),
             Trap::LoadPageFault(addr) => write!(f, "LoadPageFault({:#x})", addr),
             Trap::StorePageFault(addr) => write!(f, "StorePageFault({:#x})", addr),
+            Trap::InstructionTlbMiss(addr) => write!(f, "InstructionTlbMiss({:#x})", addr),
+            Trap::LoadTlbMiss(addr) => write!(f, "LoadTlbMiss({:#x})", addr),
+            Trap::StoreTlbMiss(addr) => write!(f, "StoreTlbMiss({:#x})", addr),
             Trap::UserSoftwareInterrupt => write!(f, "UserSoftwareInterrupt"),
             Trap::SupervisorSoftwareInterrupt => write!(f, "SupervisorSoftwareInterrupt"),
             Trap::MachineSoftwareInterrupt => write!(f, "MachineSoftwareInterrupt"),
@@ -196,6 +257,47 @@ impl fmt::Display for Trap {
 
 impl std::error::Error for Trap {}
 
+/// Fatal simulation failure returned by `Cpu::tick`.
+///
+/// Embedders can match on the variant instead of parsing a message; `Display`
+/// gives a one-line human-readable description.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimError {
+    /// A trap that cannot be delivered to a handler (e.g. any fault in direct mode).
+    ///
+    /// Carries the trap and the PC it was raised at.
+    UnhandledTrap(Trap, u64),
+
+    /// The retire-order checker (`general.verify_retire_order`) found a violation.
+    RetireCheck(String),
+
+    /// An internal simulator invariant was broken.
+    InternalError(String),
+}
+
+impl fmt::Display for SimError {
+    /// Formats the error for display.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The formatter to write to.
+    ///
+    /// # Returns
+    ///
+    /// A formatting result indicating success or failure.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimError::UnhandledTrap(trap, pc) => {
+                write!(f, "unhandled trap {} at PC {:#x}", trap, pc)
+            }
+            SimError::RetireCheck(msg) => write!(f, "retire check failed: {}", msg),
+            SimError::InternalError(msg) => write!(f, "internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
 /// Result of a virtual-to-physical address translation operation.
 ///
 /// This structure encapsulates the outcome of an MMU walk, including performance
@@ -5,7 +5,7 @@
 //! 1. **Address Types:** Strong types for virtual and physical addresses.
 //! 2. **Constants:** System-wide constants for memory, instructions, and simulation.
 //! 3. **Memory Access:** Definitions for categorizing memory operations (Fetch/Read/Write).
-//! 4. **Error Handling:** Trap representations and address translation result types.
+//! 4. **Error Handling:** Trap representations, simulation errors, and address translation result types.
 //! 5. **Register Management:** A unified interface for GPR and FPR access.
 
 /// Address type definitions (physical and virtual addresses).
@@ -26,5 +26,5 @@ pub mod reg;
 pub use addr::{PhysAddr, VirtAddr};
 pub use constants::{PAGE_SHIFT, VPN_MASK};
 pub use data::AccessType;
-pub use error::{TranslationResult, Trap};
+pub use error::{SimError, TranslationResult, Trap};
 pub use reg::RegisterFile;
@@ -3,18 +3,24 @@
 //! This module defines all configuration structures and enums used to parameterize
 //! the simulator. It provides:
 //! 1. **Defaults:** Baseline hardware constants (RAM, MMIO, cache, branch predictor).
-//! 2. **Structures:** Hierarchical config for general, system, memory, cache, and pipeline.
+//! 2. **Structures:** Hierarchical config for general, system, memory, cache, and pipeline, plus CSR reset overrides.
 //! 3. **Enums:** Memory controller, replacement policy, prefetcher, and branch predictor types.
+//! 4. **Validation:** Cross-field invariants checked once at load time ([`Config::validate`]).
 //!
 //! Configuration is supplied via JSON from the Python API (`SimConfig`) or use `Config::default()` for the CLI.
 
-use serde::Deserialize;
+use crate::core::arch::csr::Csrs;
+use crate::core::units::cache::CacheSim;
+use serde::de::{Error as _, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fmt;
 
 /// Default configuration constants for the simulator.
 ///
 /// These values define the baseline hardware configuration when not
 /// explicitly overridden in TOML configuration files.
-mod defaults {
+pub(crate) mod defaults {
     /// Base address of main system RAM (2 GiB).
     ///
     /// This is the physical address where the main memory region begins.
@@ -45,6 +51,21 @@ mod defaults {
     /// Base address of system controller (power/reset) MMIO region.
     pub const SYSCON_BASE: u64 = 0x0010_0000;
 
+    /// Base address of the Goldfish real-time clock MMIO region.
+    pub const RTC_BASE: u64 = 0x0010_1000;
+
+    /// Whether the Goldfish real-time clock is mapped.
+    pub const RTC_ENABLED: bool = true;
+
+    /// Nanoseconds the real-time clock advances per simulated cycle.
+    ///
+    /// Matches a 100 MHz core: the device tree's 10 MHz timebase with the
+    /// default CLINT divider of 10.
+    pub const RTC_NS_PER_TICK: u64 = 10;
+
+    /// Base address of the NVRAM region (mapped only when its size is nonzero).
+    pub const NVRAM_BASE: u64 = 0x0011_0000;
+
     /// System bus width in bytes (8 bytes = 64-bit bus).
     ///
     /// Determines the maximum transfer size per bus transaction.
@@ -60,6 +81,12 @@ mod defaults {
     /// Divides the simulation cycle counter to produce the machine timer value.
     pub const CLINT_DIVIDER: u64 = 10;
 
+    /// Number of harts sharing the system bus.
+    pub const HARTS: usize = 1;
+
+    /// Most harts the CLINT can address (its `mtimecmp` array ends at `mtime`).
+    pub const MAX_HARTS: usize = 4095;
+
     /// CAS (Column Access Strobe) latency in DRAM cycles.
     ///
     /// Time from column address assertion to data availability for reads.
@@ -86,6 +113,44 @@ mod defaults {
     /// Number of virtual-to-physical address translations cached in the TLB.
     pub const TLB_SIZE: usize = 32;
 
+    /// Main memory fill bandwidth in bytes per cycle.
+    ///
+    /// Zero disables the bandwidth model so every miss pays only its fixed latency.
+    pub const MEM_BANDWIDTH_BYTES_PER_CYCLE: u64 = 0;
+
+    /// Number of independent memory channels.
+    pub const MEM_CHANNELS: usize = 1;
+
+    /// Number of DRAM banks, each with its own open row.
+    pub const DRAM_BANKS: usize = 8;
+
+    /// DRAM row (page) size in bytes.
+    pub const DRAM_ROW_BYTES: u64 = 2048;
+
+    /// Cycles between DRAM refresh commands (0 disables refresh).
+    pub const T_REFI: u64 = 7800;
+
+    /// Cycles a DRAM refresh blocks the controller.
+    pub const T_RFC: u64 = 350;
+
+    /// Number of HBM pseudo-channels.
+    pub const HBM_CHANNELS: usize = 8;
+
+    /// Cycles one cache-line transfer occupies an HBM pseudo-channel.
+    pub const HBM_BURST_CYCLES: u64 = 4;
+
+    /// Size of the naturally aligned LR/SC reservation set in bytes (one cache line).
+    pub const RESERVATION_BYTES: u64 = 64;
+
+    /// Base virtual-memory page size in bytes (4 KiB).
+    pub const PAGE_SIZE: u64 = 4096;
+
+    /// Largest configurable base page size in bytes (64 KiB).
+    pub const MAX_PAGE_SIZE: u64 = 65536;
+
+    /// Whether PMP lets every access through until software enables an entry.
+    pub const PMP_OPEN_WHEN_INACTIVE: bool = true;
+
     /// Default cache size in bytes (4 KiB).
     pub const CACHE_SIZE: usize = 4096;
 
@@ -100,6 +165,17 @@ mod defaults {
     /// Default cache access latency in cycles.
     pub const CACHE_LATENCY: u64 = 1;
 
+    /// Default seed for the random replacement policy.
+    pub const CACHE_RNG_SEED: u64 = 123_456_789;
+
+    /// Number of L3 access ports.
+    ///
+    /// Zero disables the port model so concurrent L3 accesses never contend.
+    pub const L3_PORTS: usize = 0;
+
+    /// Cycles each L3 access holds its port.
+    pub const L3_PORT_CYCLES: u64 = 1;
+
     /// Default prefetcher pattern table size (64 entries).
     pub const PREFETCH_TABLE_SIZE: usize = 64;
 
@@ -109,6 +185,18 @@ mod defaults {
     /// Default pipeline width (1 instruction per cycle).
     pub const PIPELINE_WIDTH: usize = 1;
 
+    /// Whether the Zba address generation extension is implemented.
+    pub const ZBA_ENABLED: bool = true;
+
+    /// Whether the Zbb basic bit-manipulation extension is implemented.
+    pub const ZBB_ENABLED: bool = true;
+
+    /// Whether the Zbc carry-less multiply extension is implemented.
+    pub const ZBC_ENABLED: bool = true;
+
+    /// Default integer divide/remainder latency in cycles.
+    pub const DIV_LATENCY: u64 = 30;
+
     /// Default Branch Target Buffer size (256 entries).
     pub const BTB_SIZE: usize = 256;
 
@@ -141,6 +229,18 @@ mod defaults {
 
     /// Default Tournament predictor local prediction table size (log2, 1024 entries).
     pub const TOURNAMENT_LOCAL_PRED_BITS: usize = 10;
+
+    /// Default FDIV.S/FDIV.D latency in cycles.
+    pub const FDIV_LATENCY: u64 = 20;
+
+    /// Default FSQRT.S/FSQRT.D latency in cycles.
+    pub const FSQRT_LATENCY: u64 = 24;
+
+    /// Default FMUL.S/FMUL.D latency in cycles.
+    pub const FMUL_LATENCY: u64 = 4;
+
+    /// Default fused multiply-add (FMADD/FMSUB/FNMADD/FNMSUB) latency in cycles.
+    pub const FMA_LATENCY: u64 = 5;
 }
 
 /// Memory controller implementation types.
@@ -162,6 +262,80 @@ pub enum MemoryController {
     /// and row buffer hit/miss penalties for more accurate timing.
     #[serde(alias = "DRAM")]
     Dram,
+    /// HBM-style stacked memory with hashed pseudo-channels.
+    ///
+    /// Each pseudo-channel has its own row buffer and transfer queue,
+    /// so misses to different channels are serviced in parallel.
+    #[serde(alias = "HBM")]
+    Hbm,
+}
+
+/// Granularity at which physical addresses interleave across memory channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum ChannelInterleave {
+    /// Consecutive cache lines map to consecutive channels.
+    #[default]
+    Line,
+    /// Consecutive pages (`memory.page_size`) map to consecutive channels.
+    Page,
+}
+
+/// Priority of load/store/AMO address-misaligned exceptions.
+///
+/// The privileged spec lets the platform decide whether a misaligned access
+/// that would also page-fault or access-fault reports the misalignment or the fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum MisalignedPriority {
+    /// Alignment is checked before address translation.
+    ///
+    /// A misaligned access reports address-misaligned even if the page is unmapped.
+    #[default]
+    BeforeTranslation,
+    /// Alignment is checked after address translation.
+    ///
+    /// Page faults and access faults outrank address-misaligned.
+    AfterTranslation,
+}
+
+/// How ordinary loads and stores that are not naturally aligned are handled.
+///
+/// Atomics must always be naturally aligned and raise address-misaligned regardless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum MisalignedAccess {
+    /// The access completes in hardware as a sequence of byte accesses.
+    ///
+    /// Each page it touches is translated separately, and only a faulting piece traps.
+    #[default]
+    Emulate,
+    /// The access raises load/store address-misaligned for software to handle.
+    Trap,
+}
+
+/// How a TLB miss under SV39 is refilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum TlbRefill {
+    /// The hardware page-table walker refills the TLB, stalling the pipeline.
+    #[default]
+    Hardware,
+    /// A miss raises a TLB-miss exception; the guest handler refills the TLB
+    /// through the `stlbva`/`stlbw` CSRs and returns to retry the access.
+    Software,
+}
+
+/// How the page-table walker treats a leaf PTE whose A bit (or D bit, for a
+/// store) is clear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum AdUpdate {
+    /// The walker sets A (and D on a store) and writes the PTE back (Svadu).
+    #[default]
+    Hardware,
+    /// The walker raises a page fault and leaves the update to software (Svade).
+    Fault,
 }
 
 /// Cache replacement policy algorithms.
@@ -190,7 +364,8 @@ pub enum ReplacementPolicy {
     Fifo,
     /// Random replacement policy.
     ///
-    /// Evicts a randomly selected cache line from the set.
+    /// Evicts a randomly selected cache line from the set, drawn from a
+    /// PRNG seeded by `CacheConfig::rng_seed`.
     #[serde(alias = "Random")]
     Random,
     /// Most Recently Used replacement policy.
@@ -201,6 +376,41 @@ pub enum ReplacementPolicy {
     Mru,
 }
 
+/// Cache write-hit policy.
+///
+/// Selects whether stores update only the cache (marking the line dirty)
+/// or are also propagated to the next level immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum WritePolicy {
+    /// Write-back policy.
+    ///
+    /// Stores mark the line dirty; the next level is written on eviction.
+    #[default]
+    WriteBack,
+    /// Write-through policy.
+    ///
+    /// Every store pays the next-level write latency and lines never become dirty.
+    WriteThrough,
+}
+
+/// Cache write-miss policy.
+///
+/// Selects whether a store that misses brings its line into the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum AllocPolicy {
+    /// Write-allocate policy.
+    ///
+    /// A store miss installs the line, like a load miss.
+    #[default]
+    WriteAllocate,
+    /// No-write-allocate policy.
+    ///
+    /// A store miss is sent to the next level without installing the line.
+    NoWriteAllocate,
+}
+
 /// Hardware prefetcher types for cache prefetching.
 ///
 /// Prefetchers predict future memory accesses and fetch data
@@ -301,7 +511,8 @@ pub enum BranchPredictor {
 ///         "t_ras": 14,
 ///         "t_pre": 14,
 ///         "row_miss_latency": 120,
-///         "tlb_size": 32
+///         "tlb_size": 32,
+///         "bandwidth_bytes_per_cycle": 0
 ///     },
 ///     "cache": {
 ///         "l1_d": {
@@ -364,6 +575,13 @@ pub struct Config {
     pub cache: CacheHierarchyConfig,
     /// Pipeline and branch predictor configuration
     pub pipeline: PipelineConfig,
+    /// Floating-point unit latencies
+    #[serde(default)]
+    pub fpu: FpuConfig,
+    /// CSR reset-value overrides keyed by CSR address (e.g. `{"0x305": 2147483648}`),
+    /// legalized as an M-mode `csrw` of the value would be
+    #[serde(default, deserialize_with = "deserialize_csr_reset")]
+    pub csr_reset: BTreeMap<u32, u64>,
 }
 
 impl Default for Config {
@@ -374,8 +592,240 @@ impl Default for Config {
             memory: MemoryConfig::default(),
             cache: CacheHierarchyConfig::default(),
             pipeline: PipelineConfig::default(),
+            fpu: FpuConfig::default(),
+            csr_reset: BTreeMap::new(),
+        }
+    }
+}
+
+/// Reasons a configuration cannot be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The JSON is malformed or names an unknown field value (e.g. branch predictor).
+    Parse(String),
+    /// The configuration parsed but breaks one or more invariants, all listed.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(msg) => write!(f, "invalid configuration: {msg}"),
+            Self::Invalid(violations) => {
+                write!(f, "invalid configuration:")?;
+                for v in violations {
+                    write!(f, "\n  - {v}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Parses a JSON configuration and validates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Configuration in the `SimConfig.to_dict()` schema.
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or the parse error or every violated invariant.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let config: Self =
+            serde_json::from_str(json).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks cross-field invariants that would otherwise panic or misbehave later.
+    ///
+    /// Covers the kernel load offset, TLB, page and pipeline sizes, BTB and TAGE table
+    /// geometry, cache geometry, and that line sizes never shrink moving away
+    /// from the core (so an outer line always covers an inner one).
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or [`ConfigError::Invalid`] listing every violation.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut violations = Vec::new();
+
+        if self.system.kernel_offset >= self.memory.ram_size as u64 {
+            violations.push(format!(
+                "system.kernel_offset ({:#x}) must lie within memory.ram_size ({:#x})",
+                self.system.kernel_offset, self.memory.ram_size
+            ));
+        }
+        if self.memory.tlb_size == 0 {
+            violations.push("memory.tlb_size must be at least 1".to_string());
+        }
+        let page_size = self.memory.page_size;
+        if !page_size.is_power_of_two()
+            || !(defaults::PAGE_SIZE..=defaults::MAX_PAGE_SIZE).contains(&page_size)
+        {
+            violations.push(format!(
+                "memory.page_size ({page_size}) must be a power of two from {} to {}",
+                defaults::PAGE_SIZE,
+                defaults::MAX_PAGE_SIZE
+            ));
+        }
+        if self.pipeline.width == 0 {
+            violations.push("pipeline.width must be at least 1".to_string());
+        }
+        if !(1..=defaults::MAX_HARTS).contains(&self.system.harts) {
+            violations.push(format!(
+                "system.harts ({}) must be from 1 to {}",
+                self.system.harts,
+                defaults::MAX_HARTS
+            ));
+        }
+        if !self.pipeline.btb_size.is_power_of_two() {
+            violations.push(format!(
+                "pipeline.btb_size ({}) must be a non-zero power of two",
+                self.pipeline.btb_size
+            ));
+        }
+        if self.pipeline.branch_predictor == BranchPredictor::Tage {
+            self.pipeline.tage.check(&mut violations);
+        }
+
+        let cache = &self.cache;
+        let mut levels = vec![
+            ("l1_i", &cache.l1_i),
+            ("l1_d", &cache.l1_d),
+            ("l2", &cache.l2),
+        ];
+        if cache.l2_split {
+            levels.push(("l2_i", &cache.l2_i));
+        }
+        levels.push(("l3", &cache.l3));
+        for (name, level) in levels.iter().filter(|(_, l)| l.enabled) {
+            if let Err(e) = CacheSim::num_sets(level) {
+                violations.push(format!("cache.{name}: {e}"));
+            }
+        }
+
+        let mut nesting = vec![
+            (("l1_d", &cache.l1_d), ("l2", &cache.l2)),
+            (("l2", &cache.l2), ("l3", &cache.l3)),
+        ];
+        if cache.l2_split {
+            nesting.push((("l1_i", &cache.l1_i), ("l2_i", &cache.l2_i)));
+            nesting.push((("l2_i", &cache.l2_i), ("l3", &cache.l3)));
+        } else {
+            nesting.push((("l1_i", &cache.l1_i), ("l2", &cache.l2)));
+        }
+        for ((inner, i), (outer, o)) in nesting {
+            if i.enabled && o.enabled && o.line_bytes < i.line_bytes {
+                violations.push(format!(
+                    "cache.{outer}.line_bytes ({}) is smaller than cache.{inner}.line_bytes ({})",
+                    o.line_bytes, i.line_bytes
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(violations))
+        }
+    }
+}
+
+/// Deserializes the `csr_reset` table, parsing hex (`"0x305"`) or decimal keys.
+///
+/// Rejects keys that are not valid 12-bit addresses of CSRs held in
+/// [`Csrs`](crate::core::arch::csr::Csrs), so typos fail at config load.
+fn deserialize_csr_reset<'de, D>(deserializer: D) -> Result<BTreeMap<u32, u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = BTreeMap::<String, u64>::deserialize(deserializer)?;
+    let mut table = BTreeMap::new();
+    for (key, val) in raw {
+        let parsed = match key.strip_prefix("0x").or_else(|| key.strip_prefix("0X")) {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => key.parse::<u32>(),
+        };
+        let addr = parsed
+            .map_err(|_| D::Error::custom(format!("csr_reset: invalid CSR address {key:?}")))?;
+        if !Csrs::is_known(addr) {
+            return Err(D::Error::custom(format!(
+                "csr_reset: unknown CSR address {addr:#x}"
+            )));
+        }
+        table.insert(addr, val);
+    }
+    Ok(table)
+}
+
+/// Parses a byte count: plain decimal, `0x` hex, or decimal with a `K`, `M`, or `G`
+/// suffix (powers of 1024, optionally followed by `B` or `iB`, e.g. `"128M"`, `"2GiB"`).
+///
+/// # Examples
+///
+/// ```
+/// use riscv_core::config::parse_size;
+///
+/// assert_eq!(parse_size("128M"), Ok(128 << 20));
+/// assert_eq!(parse_size("0x8000000"), Ok(128 << 20));
+/// assert!(parse_size("128Q").is_err());
+/// ```
+///
+/// # Returns
+///
+/// The byte count, or a message naming the value that could not be parsed.
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid size {text:?}: expected bytes, 0x hex, or a K/M/G suffix");
+    let trimmed = text.trim();
+    if let Some(hex) = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+    {
+        return u64::from_str_radix(hex, 16).map_err(|_| invalid());
+    }
+    let unit = trimmed
+        .strip_suffix("iB")
+        .or_else(|| trimmed.strip_suffix('B'))
+        .unwrap_or(trimmed);
+    let (digits, shift) = match unit.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&unit[..i], 10),
+        Some((i, 'M' | 'm')) => (&unit[..i], 20),
+        Some((i, 'G' | 'g')) => (&unit[..i], 30),
+        _ => (unit, 0),
+    };
+    let count: u64 = digits.trim().parse().map_err(|_| invalid())?;
+    count.checked_mul(1 << shift).ok_or_else(invalid)
+}
+
+/// Deserializes a byte count from a JSON integer or a [`parse_size`] string.
+fn deserialize_size<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct SizeVisitor;
+
+    impl Visitor<'_> for SizeVisitor {
+        type Value = u64;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a byte count such as 134217728, \"0x8000000\", or \"128M\"")
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<u64, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<u64, E> {
+            parse_size(v).map_err(E::custom)
         }
     }
+
+    let bytes = deserializer.deserialize_any(SizeVisitor)?;
+    usize::try_from(bytes).map_err(|_| D::Error::custom(format!("size {bytes} is too large")))
 }
 
 /// General simulation settings and options.
@@ -400,6 +850,44 @@ pub struct GeneralConfig {
     /// Initial stack pointer (only used when direct_mode is true). Defaults to ram_base + 16MiB if not set.
     #[serde(default)]
     pub initial_sp: Option<u64>,
+
+    /// Number of committed instructions `Cpu::step_back` can undo (0 disables history capture)
+    #[serde(default)]
+    pub undo_depth: usize,
+
+    /// Log every taken trap (cause, epc, tval, target privilege, handler) to stderr
+    #[serde(default)]
+    pub trace_traps: bool,
+
+    /// Check that instructions commit in program order and that the retired count
+    /// matches the committed tally; a violation stops the simulation with an error
+    #[serde(default)]
+    pub verify_retire_order: bool,
+
+    /// In direct mode, stop with a state dump and a distinct exit code when WFI commits
+    /// instead of sleeping (catches test binaries that idle unexpectedly)
+    #[serde(default)]
+    pub trap_on_wfi: bool,
+
+    /// Count committed instructions and stall cycles per PC for a hotspot report
+    #[serde(default)]
+    pub profile: bool,
+
+    /// Machine monitor: an exception taken to M-mode while `mtvec` is 0 prints a
+    /// decoded report (cause, instruction, registers) and halts instead of jumping to 0.
+    /// Applies in `direct_mode` too, to every fault that is not a semihosting call or exit
+    #[serde(default)]
+    pub monitor_mode: bool,
+
+    /// Service S-mode `ecall`s with a built-in SBI (legacy v0.1 calls and the v0.2 BASE
+    /// and TIME extensions) so a kernel can boot without M-mode firmware
+    #[serde(default)]
+    pub builtin_sbi: bool,
+
+    /// Symbol file (ELF image or `nm` output) used to print `function+offset` in traces
+    /// and fatal reports
+    #[serde(default)]
+    pub symbols: Option<String>,
 }
 
 impl GeneralConfig {
@@ -421,6 +909,14 @@ impl Default for GeneralConfig {
             start_pc: defaults::RAM_BASE,
             direct_mode: true,
             initial_sp: None,
+            undo_depth: 0,
+            trace_traps: false,
+            verify_retire_order: false,
+            trap_on_wfi: false,
+            profile: false,
+            monitor_mode: false,
+            builtin_sbi: false,
+            symbols: None,
         }
     }
 }
@@ -467,9 +963,49 @@ pub struct SystemConfig {
     #[serde(default = "SystemConfig::default_clint_divider")]
     pub clint_divider: u64,
 
+    /// Minimum CLINT ticks between timer interrupts (0 disables coalescing).
+    ///
+    /// A performance-study knob: timer expirations inside the window are merged
+    /// into a single interrupt delivered when it closes, so a small divider or a
+    /// handler that re-arms an already-expired `mtimecmp` cannot cause a storm.
+    #[serde(default)]
+    pub clint_min_interval: u64,
+
+    /// Number of harts (cores) sharing the bus; each gets its own CLINT MSIP and MTIMECMP.
+    #[serde(default = "SystemConfig::default_harts")]
+    pub harts: usize,
+
     /// When true, UART output goes to stderr (for visibility when run from Python).
     #[serde(default)]
     pub uart_to_stderr: bool,
+
+    /// Whether the Goldfish RTC is mapped
+    #[serde(default = "SystemConfig::default_rtc_enabled")]
+    pub rtc_enabled: bool,
+
+    /// Goldfish RTC MMIO base address
+    #[serde(default = "SystemConfig::default_rtc_base")]
+    pub rtc_base: u64,
+
+    /// Nanoseconds the RTC advances per simulated cycle
+    #[serde(default = "SystemConfig::default_rtc_ns_per_tick")]
+    pub rtc_ns_per_tick: u64,
+
+    /// NVRAM MMIO base address
+    #[serde(default = "SystemConfig::default_nvram_base")]
+    pub nvram_base: u64,
+
+    /// NVRAM size in bytes (0 disables the device)
+    #[serde(default)]
+    pub nvram_size: usize,
+
+    /// Host file holding NVRAM contents; loaded at startup when present
+    #[serde(default)]
+    pub nvram_path: Option<String>,
+
+    /// When true, modified NVRAM contents are written back to `nvram_path` on shutdown
+    #[serde(default)]
+    pub nvram_writeback: bool,
 }
 
 impl SystemConfig {
@@ -517,6 +1053,31 @@ impl SystemConfig {
     fn default_clint_divider() -> u64 {
         defaults::CLINT_DIVIDER
     }
+
+    /// Returns the default number of harts.
+    fn default_harts() -> usize {
+        defaults::HARTS
+    }
+
+    /// Returns whether the RTC is mapped by default.
+    fn default_rtc_enabled() -> bool {
+        defaults::RTC_ENABLED
+    }
+
+    /// Returns the default RTC MMIO base address.
+    fn default_rtc_base() -> u64 {
+        defaults::RTC_BASE
+    }
+
+    /// Returns the default RTC advance per cycle in nanoseconds.
+    fn default_rtc_ns_per_tick() -> u64 {
+        defaults::RTC_NS_PER_TICK
+    }
+
+    /// Returns the default NVRAM MMIO base address.
+    fn default_nvram_base() -> u64 {
+        defaults::NVRAM_BASE
+    }
 }
 
 impl Default for SystemConfig {
@@ -535,7 +1096,16 @@ impl Default for SystemConfig {
             bus_width: defaults::BUS_WIDTH,
             bus_latency: defaults::BUS_LATENCY,
             clint_divider: defaults::CLINT_DIVIDER,
+            clint_min_interval: 0,
+            harts: defaults::HARTS,
             uart_to_stderr: false,
+            rtc_enabled: defaults::RTC_ENABLED,
+            rtc_base: defaults::RTC_BASE,
+            rtc_ns_per_tick: defaults::RTC_NS_PER_TICK,
+            nvram_base: defaults::NVRAM_BASE,
+            nvram_size: 0,
+            nvram_path: None,
+            nvram_writeback: false,
         }
     }
 }
@@ -546,8 +1116,11 @@ impl Default for SystemConfig {
 /// and TLB configuration.
 #[derive(Debug, Clone, Deserialize)]
 pub struct MemoryConfig {
-    /// RAM size in bytes
-    #[serde(default = "MemoryConfig::default_ram_size")]
+    /// RAM size in bytes (an integer, or a string such as `"128M"`; see [`parse_size`])
+    #[serde(
+        default = "MemoryConfig::default_ram_size",
+        deserialize_with = "deserialize_size"
+    )]
     pub ram_size: usize,
 
     /// Memory controller type
@@ -570,9 +1143,78 @@ pub struct MemoryConfig {
     #[serde(default = "MemoryConfig::default_row_miss")]
     pub row_miss_latency: u64,
 
+    /// Number of banks, each with its own open row (DRAM controller only)
+    #[serde(default = "MemoryConfig::default_dram_banks")]
+    pub dram_banks: usize,
+
+    /// Row (page) size in bytes (DRAM controller only)
+    #[serde(default = "MemoryConfig::default_dram_row_bytes")]
+    pub dram_row_bytes: u64,
+
+    /// Cycles between refresh commands, 0 = no refresh (DRAM controller only)
+    #[serde(default = "MemoryConfig::default_t_refi")]
+    pub t_refi: u64,
+
+    /// Cycles each refresh blocks the controller (DRAM controller only)
+    #[serde(default = "MemoryConfig::default_t_rfc")]
+    pub t_rfc: u64,
+
     /// TLB entry count
     #[serde(default = "MemoryConfig::default_tlb_size")]
     pub tlb_size: usize,
+
+    /// Bytes per cycle the memory channel can transfer (0 = unlimited)
+    #[serde(default = "MemoryConfig::default_bandwidth")]
+    pub bandwidth_bytes_per_cycle: u64,
+
+    /// Number of independent memory channels, each with its own controller
+    #[serde(default = "MemoryConfig::default_channels")]
+    pub channels: usize,
+
+    /// How physical addresses interleave across `channels`
+    #[serde(default)]
+    pub channel_interleave: ChannelInterleave,
+
+    /// Number of pseudo-channels (HBM controller only)
+    #[serde(default = "MemoryConfig::default_hbm_channels")]
+    pub hbm_channels: usize,
+
+    /// Cycles a line transfer occupies one pseudo-channel (HBM controller only)
+    #[serde(default = "MemoryConfig::default_hbm_burst_cycles")]
+    pub hbm_burst_cycles: u64,
+
+    /// Whether address-misaligned exceptions outrank page and access faults
+    #[serde(default)]
+    pub misaligned_priority: MisalignedPriority,
+
+    /// Whether misaligned loads and stores complete in hardware or trap
+    #[serde(default)]
+    pub misaligned_access: MisalignedAccess,
+
+    /// Size of the aligned LR/SC reservation set in bytes (rounded up to a power of two)
+    #[serde(default = "MemoryConfig::default_reservation_bytes")]
+    pub reservation_bytes: u64,
+
+    /// Base page size in bytes for SV39 translation (power of two, 4 KiB to 64 KiB)
+    #[serde(default = "MemoryConfig::default_page_size")]
+    pub page_size: u64,
+
+    /// Whether TLB misses are refilled by the hardware walker or a software handler
+    #[serde(default)]
+    pub tlb_refill: TlbRefill,
+
+    /// Whether clear PTE A/D bits are set by the walker or raise a page fault
+    #[serde(default)]
+    pub ad_update: AdUpdate,
+
+    /// Whether PMP passes every access until software enables an entry.
+    ///
+    /// The spec fails S- and U-mode accesses that match no entry, so with all 64
+    /// entries off nothing below M-mode can run. Leaving this set keeps programs
+    /// that drop to S- or U-mode without configuring PMP working; clear it for
+    /// spec behaviour from reset.
+    #[serde(default = "MemoryConfig::default_pmp_open_when_inactive")]
+    pub pmp_open_when_inactive: bool,
 }
 
 impl MemoryConfig {
@@ -601,10 +1243,65 @@ impl MemoryConfig {
         defaults::ROW_MISS_LATENCY
     }
 
+    /// Returns the default DRAM bank count.
+    fn default_dram_banks() -> usize {
+        defaults::DRAM_BANKS
+    }
+
+    /// Returns the default DRAM row size in bytes.
+    fn default_dram_row_bytes() -> u64 {
+        defaults::DRAM_ROW_BYTES
+    }
+
+    /// Returns the default refresh interval in cycles.
+    fn default_t_refi() -> u64 {
+        defaults::T_REFI
+    }
+
+    /// Returns the default refresh duration in cycles.
+    fn default_t_rfc() -> u64 {
+        defaults::T_RFC
+    }
+
     /// Returns the default TLB entry count.
     fn default_tlb_size() -> usize {
         defaults::TLB_SIZE
     }
+
+    /// Returns the default memory bandwidth (unlimited).
+    fn default_bandwidth() -> u64 {
+        defaults::MEM_BANDWIDTH_BYTES_PER_CYCLE
+    }
+
+    /// Returns the default memory channel count.
+    fn default_channels() -> usize {
+        defaults::MEM_CHANNELS
+    }
+
+    /// Returns the default HBM pseudo-channel count.
+    fn default_hbm_channels() -> usize {
+        defaults::HBM_CHANNELS
+    }
+
+    /// Returns the default HBM per-line channel occupancy in cycles.
+    fn default_hbm_burst_cycles() -> u64 {
+        defaults::HBM_BURST_CYCLES
+    }
+
+    /// Returns the default LR/SC reservation set size.
+    fn default_reservation_bytes() -> u64 {
+        defaults::RESERVATION_BYTES
+    }
+
+    /// Returns the default base page size.
+    fn default_page_size() -> u64 {
+        defaults::PAGE_SIZE
+    }
+
+    /// Returns whether PMP is open until configured by default.
+    fn default_pmp_open_when_inactive() -> bool {
+        defaults::PMP_OPEN_WHEN_INACTIVE
+    }
 }
 
 impl Default for MemoryConfig {
@@ -620,7 +1317,23 @@ impl Default for MemoryConfig {
             t_ras: defaults::T_RAS,
             t_pre: defaults::T_PRE,
             row_miss_latency: defaults::ROW_MISS_LATENCY,
+            dram_banks: defaults::DRAM_BANKS,
+            dram_row_bytes: defaults::DRAM_ROW_BYTES,
+            t_refi: defaults::T_REFI,
+            t_rfc: defaults::T_RFC,
             tlb_size: defaults::TLB_SIZE,
+            bandwidth_bytes_per_cycle: defaults::MEM_BANDWIDTH_BYTES_PER_CYCLE,
+            channels: defaults::MEM_CHANNELS,
+            channel_interleave: ChannelInterleave::default(),
+            hbm_channels: defaults::HBM_CHANNELS,
+            hbm_burst_cycles: defaults::HBM_BURST_CYCLES,
+            misaligned_priority: MisalignedPriority::default(),
+            misaligned_access: MisalignedAccess::default(),
+            reservation_bytes: defaults::RESERVATION_BYTES,
+            page_size: defaults::PAGE_SIZE,
+            tlb_refill: TlbRefill::default(),
+            ad_update: AdUpdate::default(),
+            pmp_open_when_inactive: defaults::PMP_OPEN_WHEN_INACTIVE,
         }
     }
 }
@@ -632,10 +1345,40 @@ pub struct CacheHierarchyConfig {
     pub l1_i: CacheConfig,
     /// L1 data cache
     pub l1_d: CacheConfig,
-    /// Unified L2 cache
+    /// Unified L2 cache (data half when `l2_split` is set)
     pub l2: CacheConfig,
     /// Unified L3 cache (optional)
     pub l3: CacheConfig,
+    /// Let squashed wrong-path loads allocate into the data caches
+    #[serde(default)]
+    pub wrong_path_pollution: bool,
+    /// Split the L2 into instruction (`l2_i`) and data (`l2`) halves
+    #[serde(default)]
+    pub l2_split: bool,
+    /// Invalidate the matching L1-I line on every data store (self-modifying code snoop)
+    #[serde(default)]
+    pub icache_snoop: bool,
+    /// Instruction half of the L2, used only when `l2_split` is set
+    #[serde(default)]
+    pub l2_i: CacheConfig,
+    /// Concurrent L3 accesses accepted before requests queue (0 = unlimited)
+    #[serde(default = "CacheHierarchyConfig::default_l3_ports")]
+    pub l3_ports: usize,
+    /// Cycles each L3 access occupies its port
+    #[serde(default = "CacheHierarchyConfig::default_l3_port_cycles")]
+    pub l3_port_cycles: u64,
+}
+
+impl CacheHierarchyConfig {
+    /// Returns the default L3 port count (unlimited).
+    fn default_l3_ports() -> usize {
+        defaults::L3_PORTS
+    }
+
+    /// Returns the default L3 port occupancy in cycles.
+    fn default_l3_port_cycles() -> u64 {
+        defaults::L3_PORT_CYCLES
+    }
 }
 
 impl Default for CacheHierarchyConfig {
@@ -645,6 +1388,12 @@ impl Default for CacheHierarchyConfig {
             l1_d: CacheConfig::default(),
             l2: CacheConfig::default(),
             l3: CacheConfig::default(),
+            wrong_path_pollution: false,
+            l2_split: false,
+            icache_snoop: false,
+            l2_i: CacheConfig::default(),
+            l3_ports: defaults::L3_PORTS,
+            l3_port_cycles: defaults::L3_PORT_CYCLES,
         }
     }
 }
@@ -656,12 +1405,18 @@ pub struct CacheConfig {
     #[serde(default)]
     pub enabled: bool,
 
-    /// Total cache size in bytes
-    #[serde(default = "CacheConfig::default_size")]
+    /// Total cache size in bytes (an integer or a [`parse_size`] string such as `"32K"`)
+    #[serde(
+        default = "CacheConfig::default_size",
+        deserialize_with = "deserialize_size"
+    )]
     pub size_bytes: usize,
 
-    /// Cache line size in bytes
-    #[serde(default = "CacheConfig::default_line")]
+    /// Cache line size in bytes (an integer or a [`parse_size`] string)
+    #[serde(
+        default = "CacheConfig::default_line",
+        deserialize_with = "deserialize_size"
+    )]
     pub line_bytes: usize,
 
     /// Associativity (number of ways)
@@ -672,6 +1427,28 @@ pub struct CacheConfig {
     #[serde(default)]
     pub policy: ReplacementPolicy,
 
+    /// Miss status holding registers; misses to distinct lines overlap up
+    /// to this many (0 = blocking). Used by the L1 data cache.
+    #[serde(default)]
+    pub mshrs: usize,
+
+    /// Lines held in a fully-associative victim cache behind this level
+    /// (0 = none).
+    #[serde(default)]
+    pub victim_entries: usize,
+
+    /// Seed for the `Random` replacement policy, for reproducible runs
+    #[serde(default = "CacheConfig::default_rng_seed")]
+    pub rng_seed: u64,
+
+    /// Write-hit policy (write-back or write-through)
+    #[serde(default)]
+    pub write_policy: WritePolicy,
+
+    /// Write-miss policy (write-allocate or no-write-allocate)
+    #[serde(default)]
+    pub alloc_policy: AllocPolicy,
+
     /// Access latency in cycles
     #[serde(default = "CacheConfig::default_latency")]
     pub latency: u64,
@@ -705,6 +1482,11 @@ impl CacheConfig {
         defaults::CACHE_WAYS
     }
 
+    /// Returns the default random replacement seed.
+    fn default_rng_seed() -> u64 {
+        defaults::CACHE_RNG_SEED
+    }
+
     /// Returns the default cache access latency in cycles.
     fn default_latency() -> u64 {
         defaults::CACHE_LATENCY
@@ -725,7 +1507,8 @@ impl Default for CacheConfig {
     /// Creates a default cache configuration.
     ///
     /// Cache is disabled by default, uses direct-mapped associativity,
-    /// LRU replacement, no prefetching, and minimal size.
+    /// LRU replacement, write-back with write-allocate, no prefetching,
+    /// and minimal size.
     fn default() -> Self {
         Self {
             enabled: false,
@@ -733,6 +1516,11 @@ impl Default for CacheConfig {
             line_bytes: defaults::CACHE_LINE,
             ways: defaults::CACHE_WAYS,
             policy: ReplacementPolicy::default(),
+            mshrs: 0,
+            victim_entries: 0,
+            rng_seed: defaults::CACHE_RNG_SEED,
+            write_policy: WritePolicy::default(),
+            alloc_policy: AllocPolicy::default(),
             latency: defaults::CACHE_LATENCY,
             prefetcher: Prefetcher::default(),
             prefetch_table_size: defaults::PREFETCH_TABLE_SIZE,
@@ -764,6 +1552,41 @@ pub struct PipelineConfig {
     #[serde(default)]
     pub misa_override: Option<String>,
 
+    /// Implement the Zba address generation extension
+    #[serde(default = "PipelineConfig::default_zba")]
+    pub zba: bool,
+
+    /// Implement the Zbb basic bit-manipulation extension
+    #[serde(default = "PipelineConfig::default_zbb")]
+    pub zbb: bool,
+
+    /// Implement the Zbc carry-less multiply extension
+    #[serde(default = "PipelineConfig::default_zbc")]
+    pub zbc: bool,
+
+    /// Fuse common instruction pairs (e.g. `auipc`+`jalr`) into one pipeline slot
+    #[serde(default)]
+    pub macro_op_fusion: bool,
+
+    /// Honor Zihintntl non-temporal hints: the hinted access does not allocate
+    /// into (or promote lines in) the hinted cache levels
+    #[serde(default)]
+    pub zihintntl: bool,
+
+    /// Flush-to-zero / denormals-are-zero FP mode: subnormal inputs read as zero
+    /// and subnormal results are flushed to zero (raising underflow)
+    #[serde(default)]
+    pub flush_subnormals: bool,
+
+    /// Integer DIV/DIVU/REM/REMU latency in cycles (0 or 1 is single-cycle)
+    #[serde(default = "PipelineConfig::default_div_latency")]
+    pub div_latency: u64,
+
+    /// Early-exit divider: scale `div_latency` by the number of quotient bits
+    /// the operands can produce, so small quotients finish sooner
+    #[serde(default)]
+    pub div_early_exit: bool,
+
     /// TAGE predictor configuration
     #[serde(default)]
     pub tage: TageConfig,
@@ -792,6 +1615,26 @@ impl PipelineConfig {
     fn default_ras_size() -> usize {
         defaults::RAS_SIZE
     }
+
+    /// Returns whether Zba is enabled by default.
+    fn default_zba() -> bool {
+        defaults::ZBA_ENABLED
+    }
+
+    /// Returns whether Zbb is enabled by default.
+    fn default_zbb() -> bool {
+        defaults::ZBB_ENABLED
+    }
+
+    /// Returns whether Zbc is enabled by default.
+    fn default_zbc() -> bool {
+        defaults::ZBC_ENABLED
+    }
+
+    /// Returns the default integer divide latency in cycles.
+    fn default_div_latency() -> u64 {
+        defaults::DIV_LATENCY
+    }
 }
 
 impl Default for PipelineConfig {
@@ -806,6 +1649,14 @@ impl Default for PipelineConfig {
             btb_size: defaults::BTB_SIZE,
             ras_size: defaults::RAS_SIZE,
             misa_override: None,
+            zba: defaults::ZBA_ENABLED,
+            zbb: defaults::ZBB_ENABLED,
+            zbc: defaults::ZBC_ENABLED,
+            macro_op_fusion: false,
+            zihintntl: false,
+            flush_subnormals: false,
+            div_latency: defaults::DIV_LATENCY,
+            div_early_exit: false,
             tage: TageConfig::default(),
             perceptron: PerceptronConfig::default(),
             tournament: TournamentConfig::default(),
@@ -813,6 +1664,64 @@ impl Default for PipelineConfig {
     }
 }
 
+/// Floating-point unit latency configuration.
+///
+/// Each latency is the total number of cycles the operation occupies the
+/// execute stage; the pipeline stalls for all but the first. A latency of
+/// 0 or 1 makes the operation single-cycle. Other FP operations always take
+/// one cycle.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct FpuConfig {
+    /// FDIV latency in cycles
+    #[serde(default = "FpuConfig::default_fdiv_latency")]
+    pub fdiv_latency: u64,
+
+    /// FSQRT latency in cycles
+    #[serde(default = "FpuConfig::default_fsqrt_latency")]
+    pub fsqrt_latency: u64,
+
+    /// FMUL latency in cycles
+    #[serde(default = "FpuConfig::default_fmul_latency")]
+    pub fmul_latency: u64,
+
+    /// Fused multiply-add latency in cycles
+    #[serde(default = "FpuConfig::default_fma_latency")]
+    pub fma_latency: u64,
+}
+
+impl FpuConfig {
+    /// Returns the default FDIV latency in cycles.
+    fn default_fdiv_latency() -> u64 {
+        defaults::FDIV_LATENCY
+    }
+
+    /// Returns the default FSQRT latency in cycles.
+    fn default_fsqrt_latency() -> u64 {
+        defaults::FSQRT_LATENCY
+    }
+
+    /// Returns the default FMUL latency in cycles.
+    fn default_fmul_latency() -> u64 {
+        defaults::FMUL_LATENCY
+    }
+
+    /// Returns the default fused multiply-add latency in cycles.
+    fn default_fma_latency() -> u64 {
+        defaults::FMA_LATENCY
+    }
+}
+
+impl Default for FpuConfig {
+    fn default() -> Self {
+        Self {
+            fdiv_latency: defaults::FDIV_LATENCY,
+            fsqrt_latency: defaults::FSQRT_LATENCY,
+            fmul_latency: defaults::FMUL_LATENCY,
+            fma_latency: defaults::FMA_LATENCY,
+        }
+    }
+}
+
 /// TAGE (Tagged Geometric) predictor configuration.
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct TageConfig {
@@ -875,6 +1784,34 @@ impl TageConfig {
     fn default_tag_widths() -> Vec<usize> {
         vec![9, 9, 10, 10]
     }
+
+    /// Appends the TAGE geometry rules this configuration breaks to `violations`.
+    fn check(&self, violations: &mut Vec<String>) {
+        if !self.table_size.is_power_of_two() {
+            violations.push(format!(
+                "pipeline.tage.table_size ({}) must be a non-zero power of two",
+                self.table_size
+            ));
+        }
+        if !self.loop_table_size.is_power_of_two() {
+            violations.push(format!(
+                "pipeline.tage.loop_table_size ({}) must be a non-zero power of two",
+                self.loop_table_size
+            ));
+        }
+        // Empty history lengths select the built-in four-bank geometry.
+        if !self.history_lengths.is_empty()
+            && (self.history_lengths.len() != self.num_banks
+                || self.tag_widths.len() != self.num_banks)
+        {
+            violations.push(format!(
+                "pipeline.tage.history_lengths ({}) and tag_widths ({}) must both have num_banks ({}) entries",
+                self.history_lengths.len(),
+                self.tag_widths.len(),
+                self.num_banks
+            ));
+        }
+    }
 }
 
 /// Perceptron branch predictor configuration.
@@ -6,6 +6,19 @@
 //! 3. **Register Storage:** The `Csrs` struct for maintaining architectural state.
 //! 4. **Access Logic:** Standardized read and write operations for register interaction.
 
+use serde::{Deserialize, Serialize};
+
+use crate::core::units::mmu::pmp::Pmp;
+
+/// Floating-point accrued exceptions CSR address (alias of `fcsr[4:0]`).
+pub const FFLAGS: u32 = 0x001;
+
+/// Floating-point dynamic rounding mode CSR address (alias of `fcsr[7:5]`).
+pub const FRM: u32 = 0x002;
+
+/// Floating-point control and status register CSR address.
+pub const FCSR: u32 = 0x003;
+
 /// Machine vendor ID CSR address.
 pub const MVENDORID: u32 = 0xF11;
 
@@ -39,6 +52,12 @@ pub const MTVEC: u32 = 0x305;
 /// Machine counter enable register CSR address.
 pub const MCOUNTEREN: u32 = 0x306;
 
+/// First machine performance-monitoring event selector CSR address (`mhpmevent3`).
+pub const MHPMEVENT3: u32 = 0x323;
+
+/// Last machine performance-monitoring event selector CSR address (`mhpmevent31`).
+pub const MHPMEVENT31: u32 = 0x33F;
+
 /// Machine scratch register CSR address.
 pub const MSCRATCH: u32 = 0x340;
 
@@ -54,6 +73,18 @@ pub const MTVAL: u32 = 0x343;
 /// Machine interrupt pending register CSR address.
 pub const MIP: u32 = 0x344;
 
+/// First PMP configuration register CSR address (`pmpcfg0`).
+pub const PMPCFG0: u32 = 0x3A0;
+
+/// Last PMP configuration register CSR address (`pmpcfg15`).
+pub const PMPCFG15: u32 = 0x3AF;
+
+/// First PMP address register CSR address (`pmpaddr0`).
+pub const PMPADDR0: u32 = 0x3B0;
+
+/// Last PMP address register CSR address (`pmpaddr63`).
+pub const PMPADDR63: u32 = 0x3EF;
+
 /// Supervisor status register CSR address.
 pub const SSTATUS: u32 = 0x100;
 
@@ -87,6 +118,17 @@ pub const SATP: u32 = 0x180;
 /// Supervisor timer compare register CSR address.
 pub const STIMECMP: u32 = 0x14D;
 
+/// Software-TLB virtual address CSR address (custom supervisor read/write).
+///
+/// Holds the virtual address whose translation the next `stlbw` write installs.
+pub const STLBVA: u32 = 0x5C0;
+
+/// Software-TLB write CSR address (custom supervisor read/write).
+///
+/// Writing a PTE-format value (`ppn << 10 | flags`) installs a base-page mapping
+/// for `stlbva` in both TLBs, or removes it if V is clear. Reads as zero.
+pub const STLBW: u32 = 0x5C1;
+
 /// Cycle counter CSR address (read-only, user mode accessible).
 pub const CYCLE: u32 = 0xC00;
 
@@ -96,12 +138,69 @@ pub const TIME: u32 = 0xC01;
 /// Instructions retired counter CSR address (read-only, user mode accessible).
 pub const INSTRET: u32 = 0xC02;
 
+/// First user-level hardware performance counter CSR address (`hpmcounter3`).
+pub const HPMCOUNTER3: u32 = 0xC03;
+
+/// Last user-level hardware performance counter CSR address (`hpmcounter31`).
+pub const HPMCOUNTER31: u32 = 0xC1F;
+
 /// Machine cycle counter CSR address.
 pub const MCYCLE: u32 = 0xB00;
 
 /// Machine instructions retired counter CSR address.
 pub const MINSTRET: u32 = 0xB02;
 
+/// First machine hardware performance counter CSR address (`mhpmcounter3`).
+pub const MHPMCOUNTER3: u32 = 0xB03;
+
+/// Last machine hardware performance counter CSR address (`mhpmcounter31`).
+pub const MHPMCOUNTER31: u32 = 0xB1F;
+
+/// Number of programmable hardware performance counters (`mhpmcounter3`–`31`).
+pub const HPM_COUNTERS: usize = 29;
+
+/// Upper 32 bits of the cycle counter (RV32 only; read-only, user mode accessible).
+pub const CYCLEH: u32 = 0xC80;
+
+/// Upper 32 bits of the real-time counter (RV32 only; read-only, user mode accessible).
+pub const TIMEH: u32 = 0xC81;
+
+/// Upper 32 bits of the instructions retired counter (RV32 only; read-only, user mode accessible).
+pub const INSTRETH: u32 = 0xC82;
+
+/// First user-level hardware performance counter high half (`hpmcounter3h`).
+pub const HPMCOUNTER3H: u32 = 0xC83;
+
+/// Last user-level hardware performance counter high half (`hpmcounter31h`).
+pub const HPMCOUNTER31H: u32 = 0xC9F;
+
+/// Upper 32 bits of the machine cycle counter (RV32 only).
+pub const MCYCLEH: u32 = 0xB80;
+
+/// Upper 32 bits of the machine instructions retired counter (RV32 only).
+pub const MINSTRETH: u32 = 0xB82;
+
+/// First machine hardware performance counter high half (`mhpmcounter3h`).
+pub const MHPMCOUNTER3H: u32 = 0xB83;
+
+/// Last machine hardware performance counter high half (`mhpmcounter31h`).
+pub const MHPMCOUNTER31H: u32 = 0xB9F;
+
+/// Shift that selects the upper half of a 64-bit counter for the `*h` CSRs.
+pub const COUNTER_HIGH_SHIFT: u32 = 32;
+
+/// Counter-enable bit for `cycle` in `mcounteren`/`scounteren`.
+pub const COUNTEREN_CY: u64 = 1 << 0;
+
+/// Counter-enable bit for `time` in `mcounteren`/`scounteren`.
+pub const COUNTEREN_TM: u64 = 1 << 1;
+
+/// Counter-enable bit for `instret` in `mcounteren`/`scounteren`.
+pub const COUNTEREN_IR: u64 = 1 << 2;
+
+/// Implemented bits of `mcounteren`/`scounteren`: one per user counter CSR.
+pub const COUNTEREN_MASK: u64 = 0xFFFF_FFFF;
+
 /// User interrupt enable bit in `mstatus` register.
 pub const MSTATUS_UIE: u64 = 1 << 0;
 
@@ -165,6 +264,40 @@ pub const MIP_SEIP: u64 = 1 << 9;
 /// Machine external interrupt pending bit in `mip` register.
 pub const MIP_MEIP: u64 = 1 << 11;
 
+/// WARL mask of implemented `mie` bits (S- and M-mode software, timer, and external enables).
+pub const MIE_WRITABLE: u64 = MIE_SSIP | MIE_MSIP | MIE_STIE | MIE_MTIE | MIE_SEIP | MIE_MEIP;
+
+/// Software-writable `mip` bits; MTIP, MSIP, and MEIP are driven by the CLINT and PLIC.
+pub const MIP_WRITABLE: u64 = MIP_SSIP | MIP_STIP | MIP_SEIP;
+
+/// Delegatable `medeleg` bits: the standard exceptions (causes 0-9, 12, 13, 15) and the
+/// software TLB-miss causes (24-26). ECALL from M-mode (11) always traps to M-mode.
+pub const MEDELEG_WRITABLE: u64 = 0x0700_B3FF;
+
+/// Delegatable `mideleg` bits: the supervisor software, timer, and external interrupts.
+pub const MIDELEG_WRITABLE: u64 = MIP_SSIP | MIP_STIP | MIP_SEIP;
+
+/// Accrued exception flags field of `fcsr` (NV, DZ, OF, UF, NX).
+pub const FCSR_FFLAGS_MASK: u64 = 0x1F;
+
+/// Bit offset of the rounding mode field in `fcsr`.
+pub const FCSR_FRM_SHIFT: u64 = 5;
+
+/// Rounding mode field width mask (after shifting).
+pub const FCSR_FRM_MASK: u64 = 0x7;
+
+/// Implemented bits of `fcsr`.
+pub const FCSR_MASK: u64 = FCSR_FFLAGS_MASK | (FCSR_FRM_MASK << FCSR_FRM_SHIFT);
+
+/// MODE field of `mtvec`/`stvec` (bits 1:0).
+pub const TVEC_MODE_MASK: u64 = 0b11;
+
+/// `xtvec` MODE: every trap jumps to BASE.
+pub const TVEC_MODE_DIRECT: u64 = 0;
+
+/// `xtvec` MODE: interrupts jump to BASE + 4 * cause, exceptions to BASE.
+pub const TVEC_MODE_VECTORED: u64 = 1;
+
 /// Simulation panic CSR address (custom, for debugging).
 pub const CSR_SIM_PANIC: u32 = 0x8FF;
 
@@ -216,15 +349,27 @@ pub const SATP_MODE_BARE: u64 = 0;
 /// SV39 (39-bit virtual address) mode value for `satp` register.
 pub const SATP_MODE_SV39: u64 = 8;
 
+/// SV48 (48-bit virtual address) mode value for `satp` register.
+pub const SATP_MODE_SV48: u64 = 9;
+
 /// Bit mask for address translation mode field in `satp` register.
 pub const SATP_MODE_MASK: u64 = 0xF;
 
 /// Physical page number mask in `satp` register.
 pub const SATP_PPN_MASK: u64 = 0xFFF_FFFF_FFFF;
 
+/// Bit shift for the address-space identifier field in `satp` register.
+pub const SATP_ASID_SHIFT: u64 = 44;
+
+/// Bit mask for the address-space identifier field in `satp` register.
+pub const SATP_ASID_MASK: u64 = 0xFFFF;
+
 /// MISA extension bit for atomic operations (A extension).
 pub const MISA_EXT_A: u64 = 1 << 0;
 
+/// MISA extension bit for bit-manipulation (B extension).
+pub const MISA_EXT_B: u64 = 1 << 1;
+
 /// MISA extension bit for compressed instructions (C extension).
 pub const MISA_EXT_C: u64 = 1 << 2;
 
@@ -265,7 +410,7 @@ pub const MISA_DEFAULT_RV64IMAFDC: u64 = 0x8000_0000_0014_1101;
 ///
 /// Contains all machine-level and supervisor-level CSRs that control processor state,
 /// interrupt handling, memory management, and performance counters.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Csrs {
     /// Machine status register.
     pub mstatus: u64,
@@ -297,6 +442,10 @@ pub struct Csrs {
     pub stvec: u64,
     /// Supervisor scratch register.
     pub sscratch: u64,
+    /// Machine counter enable: bit `i` lets S-mode read user counter `i`.
+    pub mcounteren: u64,
+    /// Supervisor counter enable: bit `i` lets U-mode read user counter `i`.
+    pub scounteren: u64,
     /// Supervisor exception program counter.
     pub sepc: u64,
     /// Supervisor trap cause.
@@ -319,9 +468,62 @@ pub struct Csrs {
     pub minstret: u64,
     /// Supervisor timer compare (for timer interrupt).
     pub stimecmp: u64,
+    /// Software-TLB virtual address (custom `stlbva`).
+    pub stlbva: u64,
+    /// Floating-point control and status (`frm` in bits 7:5, `fflags` in bits 4:0).
+    pub fcsr: u64,
+    /// Event selectors `mhpmevent3`–`mhpmevent31`.
+    pub mhpmevent: [u64; HPM_COUNTERS],
+    /// Per-counter offsets: `mhpmcounter<3 + i>` reads the selected event's count minus
+    /// `mhpm_offset[i]`.
+    pub mhpm_offset: [u64; HPM_COUNTERS],
+    /// Physical memory protection entries (`pmpcfg0`–`pmpcfg15`, `pmpaddr0`–`pmpaddr63`).
+    pub pmp: Pmp,
 }
 
 impl Csrs {
+    /// Returns `true` if `addr` names a CSR whose value this register file holds.
+    ///
+    /// Excludes the counters, which are read from the statistics and CLINT, and
+    /// `sie`/`sip`, which are views of `mie`/`mip`; [`read`](Self::read) and
+    /// [`write`](Self::write) accept those too but the CPU never reads them back.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The 12-bit CSR address.
+    pub fn is_known(addr: u32) -> bool {
+        matches!(
+            addr,
+            FFLAGS
+                | FRM
+                | FCSR
+                | MSTATUS
+                | MISA
+                | MEDELEG
+                | MIDELEG
+                | MIE
+                | MTVEC
+                | MCOUNTEREN
+                | MSCRATCH
+                | MEPC
+                | MCAUSE
+                | MTVAL
+                | MIP
+                | SSTATUS
+                | STVEC
+                | SCOUNTEREN
+                | SSCRATCH
+                | SEPC
+                | SCAUSE
+                | STVAL
+                | SATP
+                | STLBVA
+                | MHPMEVENT3..=MHPMEVENT31
+                | PMPCFG0..=PMPCFG15
+                | PMPADDR0..=PMPADDR63
+        )
+    }
+
     /// Reads a CSR value by its address.
     ///
     /// # Arguments
@@ -333,12 +535,16 @@ impl Csrs {
     /// The 64-bit value stored in the specified CSR, or 0 if the address is not recognized.
     pub fn read(&self, addr: u32) -> u64 {
         match addr {
+            FFLAGS => self.fcsr & FCSR_FFLAGS_MASK,
+            FRM => (self.fcsr >> FCSR_FRM_SHIFT) & FCSR_FRM_MASK,
+            FCSR => self.fcsr,
             MSTATUS => self.mstatus,
             MISA => self.misa,
             MEDELEG => self.medeleg,
             MIDELEG => self.mideleg,
             MIE => self.mie,
             MTVEC => self.mtvec,
+            MCOUNTEREN => self.mcounteren,
             MSCRATCH => self.mscratch,
             MEPC => self.mepc,
             MCAUSE => self.mcause,
@@ -347,17 +553,22 @@ impl Csrs {
             SSTATUS => self.sstatus,
             SIE => self.sie,
             STVEC => self.stvec,
+            SCOUNTEREN => self.scounteren,
             SSCRATCH => self.sscratch,
             SEPC => self.sepc,
             SCAUSE => self.scause,
             STVAL => self.stval,
             SIP => self.sip,
             SATP => self.satp,
+            STLBVA => self.stlbva,
             CYCLE => self.cycle,
             TIME => self.time,
             INSTRET => self.instret,
             MCYCLE => self.mcycle,
             MINSTRET => self.minstret,
+            MHPMEVENT3..=MHPMEVENT31 => self.mhpmevent[(addr - MHPMEVENT3) as usize],
+            PMPCFG0..=PMPCFG15 => self.pmp.read_cfg_reg((addr - PMPCFG0) as usize),
+            PMPADDR0..=PMPADDR63 => self.pmp.get_addr((addr - PMPADDR0) as usize),
             _ => 0,
         }
     }
@@ -370,12 +581,19 @@ impl Csrs {
     /// * `val` - The 64-bit value to write.
     pub fn write(&mut self, addr: u32, val: u64) {
         match addr {
+            FFLAGS => self.fcsr = (self.fcsr & !FCSR_FFLAGS_MASK) | (val & FCSR_FFLAGS_MASK),
+            FRM => {
+                self.fcsr =
+                    (self.fcsr & FCSR_FFLAGS_MASK) | ((val & FCSR_FRM_MASK) << FCSR_FRM_SHIFT)
+            }
+            FCSR => self.fcsr = val & FCSR_MASK,
             MSTATUS => self.mstatus = val,
             MISA => self.misa = val,
             MEDELEG => self.medeleg = val,
             MIDELEG => self.mideleg = val,
             MIE => self.mie = val,
             MTVEC => self.mtvec = val,
+            MCOUNTEREN => self.mcounteren = val & COUNTEREN_MASK,
             MSCRATCH => self.mscratch = val,
             MEPC => self.mepc = val,
             MCAUSE => self.mcause = val,
@@ -384,15 +602,17 @@ impl Csrs {
             SSTATUS => self.sstatus = val,
             SIE => self.sie = val,
             STVEC => self.stvec = val,
+            SCOUNTEREN => self.scounteren = val & COUNTEREN_MASK,
             SSCRATCH => self.sscratch = val,
             SEPC => self.sepc = val,
             SCAUSE => self.scause = val,
             STVAL => self.stval = val,
             SIP => self.sip = val,
+            STLBVA => self.stlbva = val,
             SATP => {
                 let mode = (val >> SATP_MODE_SHIFT) & SATP_MODE_MASK;
-                let new_mode = if mode == SATP_MODE_SV39 {
-                    SATP_MODE_SV39
+                let new_mode = if mode == SATP_MODE_SV39 || mode == SATP_MODE_SV48 {
+                    mode
                 } else {
                     SATP_MODE_BARE
                 };
@@ -404,6 +624,9 @@ impl Csrs {
             INSTRET => self.instret = val,
             MCYCLE => self.mcycle = val,
             MINSTRET => self.minstret = val,
+            MHPMEVENT3..=MHPMEVENT31 => self.mhpmevent[(addr - MHPMEVENT3) as usize] = val,
+            PMPCFG0..=PMPCFG15 => self.pmp.write_cfg_reg((addr - PMPCFG0) as usize, val),
+            PMPADDR0..=PMPADDR63 => self.pmp.write_addr_reg((addr - PMPADDR0) as usize, val),
             _ => {}
         }
     }
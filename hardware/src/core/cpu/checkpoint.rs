@@ -0,0 +1,276 @@
+//! Checkpoint and Restore.
+//!
+//! This module saves a running system to a file so a later run can resume from it. It provides:
+//! 1. **Architectural state:** Registers, CSRs, PC, privilege, the LR reservation, and the
+//!    cycle and instret counters.
+//! 2. **System state:** RAM contents and the registers of every device on the bus.
+//! 3. **Predictor and TLB state:** Saved so branch and translation behavior resume unchanged.
+//! 4. **Quiescing:** Saving first drains the pipeline. Saving and restoring both empty the
+//!    caches, MSHRs, and memory-controller queues, so a restored run follows the same timing
+//!    as the run that continued after the save.
+//!
+//! Files start with a magic string and a format version. A checkpoint can only be loaded into
+//! a CPU built from the same configuration.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::Cpu;
+use crate::core::arch::csr::Csrs;
+use crate::core::arch::mode::PrivilegeMode;
+use crate::core::units::bru::BranchPredictorWrapper;
+use crate::core::units::mmu::tlb::Tlb;
+use crate::soc::traits::DeviceSnapshot;
+
+/// Leading bytes of every checkpoint file.
+const MAGIC: [u8; 8] = *b"RVCKPT\0\0";
+
+/// Checkpoint format version; bump whenever [`CpuState`] changes.
+pub const CHECKPOINT_VERSION: u32 = 4;
+
+/// Cycles the pipeline may take to drain before a save gives up.
+const DRAIN_LIMIT: u64 = 100_000;
+
+/// Fixed-layout prefix identifying a checkpoint file.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    magic: [u8; 8],
+    version: u32,
+}
+
+/// Everything a checkpoint records.
+#[derive(Serialize, Deserialize)]
+struct CpuState {
+    pc: u64,
+    privilege: u8,
+    gprs: Vec<u64>,
+    fprs: Vec<u64>,
+    csrs: Csrs,
+    load_reservation: Option<u64>,
+    cycles: u64,
+    instructions_retired: u64,
+    branch_predictor: BranchPredictorWrapper,
+    itlb: Tlb,
+    dtlb: Tlb,
+    devices: Vec<DeviceSnapshot>,
+}
+
+/// Reasons a checkpoint cannot be saved or restored.
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// The checkpoint file could not be created, read, or written.
+    Io(io::Error),
+    /// The file is not a checkpoint, or is truncated or corrupt.
+    Format(String),
+    /// The file was written with a different format version.
+    Version(u32),
+    /// The checkpoint does not fit this system (e.g. a different RAM size or device set).
+    Mismatch(String),
+    /// The pipeline could not be drained before saving.
+    Drain(String),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "checkpoint I/O error: {e}"),
+            Self::Format(msg) => write!(f, "invalid checkpoint: {msg}"),
+            Self::Version(v) => write!(
+                f,
+                "unsupported checkpoint version {v} (expected {CHECKPOINT_VERSION})"
+            ),
+            Self::Mismatch(msg) => write!(f, "checkpoint does not match this system: {msg}"),
+            Self::Drain(msg) => write!(f, "cannot drain the pipeline: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<bincode::Error> for CheckpointError {
+    fn from(e: bincode::Error) -> Self {
+        match *e {
+            bincode::ErrorKind::Io(e) if e.kind() != io::ErrorKind::UnexpectedEof => Self::Io(e),
+            other => Self::Format(other.to_string()),
+        }
+    }
+}
+
+impl Cpu {
+    /// Saves the architectural, device, and memory state to `path`.
+    ///
+    /// Fetch is stopped and the instructions in flight commit first, then the caches and
+    /// memory-controller queues are emptied. Simulation continues from here exactly as it
+    /// would after [`load_checkpoint`](Self::load_checkpoint) of the same file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to create or overwrite.
+    pub fn save_checkpoint(&mut self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        self.quiesce()?;
+
+        let state = CpuState {
+            pc: self.pc,
+            privilege: self.privilege.to_u8(),
+            gprs: (0..32).map(|i| self.regs.read(i)).collect(),
+            fprs: (0..32).map(|i| self.regs.read_f(i)).collect(),
+            csrs: self.csrs.clone(),
+            load_reservation: self.load_reservation,
+            cycles: self.stats.cycles,
+            instructions_retired: self.stats.instructions_retired,
+            branch_predictor: self.branch_predictor.clone(),
+            itlb: self.mmu.itlb.clone(),
+            dtlb: self.mmu.dtlb.clone(),
+            devices: self.bus.bus.save_device_states(),
+        };
+
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = Header {
+            magic: MAGIC,
+            version: CHECKPOINT_VERSION,
+        };
+        bincode::serialize_into(&mut out, &header)?;
+        bincode::serialize_into(&mut out, &state)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Restores a checkpoint written by [`save_checkpoint`](Self::save_checkpoint).
+    ///
+    /// The pipeline, caches, MSHRs, undo history, and memory-controller queues start empty.
+    /// Statistics other than the cycle and instret counters keep accumulating.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Checkpoint file to read.
+    ///
+    /// # Returns
+    ///
+    /// An error if the file cannot be read or was saved from a different system. The whole
+    /// file is decoded and every device snapshot (names, bases, RAM size and chunks) checked
+    /// before anything is restored, so an error leaves the CPU, RAM, and devices untouched.
+    pub fn load_checkpoint(&mut self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let mut input = BufReader::new(File::open(path)?);
+        let header: Header = bincode::deserialize_from(&mut input)?;
+        if header.magic != MAGIC {
+            return Err(CheckpointError::Format("not a checkpoint file".to_string()));
+        }
+        if header.version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::Version(header.version));
+        }
+        let state: CpuState = bincode::deserialize_from(&mut input)?;
+        if state.gprs.len() != 32 || state.fprs.len() != 32 {
+            return Err(CheckpointError::Format(
+                "register file must hold 32 entries".to_string(),
+            ));
+        }
+
+        self.bus
+            .bus
+            .load_device_states(&state.devices)
+            .map_err(CheckpointError::Mismatch)?;
+
+        for (i, &val) in state.gprs.iter().enumerate() {
+            self.regs.write(i, val);
+        }
+        for (i, &val) in state.fprs.iter().enumerate() {
+            self.regs.write_f(i, val);
+        }
+        self.pc = state.pc;
+        self.privilege = PrivilegeMode::from_u8(state.privilege);
+        self.csrs = state.csrs;
+        self.load_reservation = state.load_reservation;
+        self.stats.cycles = state.cycles;
+        self.stats.instructions_retired = state.instructions_retired;
+        self.branch_predictor = state.branch_predictor;
+        self.mmu.itlb = state.itlb;
+        self.mmu.dtlb = state.dtlb;
+
+        self.exit_code = None;
+        self.pending_error = None;
+        self.bus.exit_request.store(u64::MAX, Ordering::Relaxed);
+        self.reset_microarch();
+        Ok(())
+    }
+
+    /// Stops fetch and ticks until every instruction in flight has committed.
+    ///
+    /// A hart parked in WFI is rewound to the WFI, which executes again on resume.
+    fn quiesce(&mut self) -> Result<(), CheckpointError> {
+        let control = self.run_control.clone();
+        self.request_halt();
+
+        let mut drained = Ok(());
+        let mut cycles = 0;
+        while !self.is_halted() {
+            if cycles == DRAIN_LIMIT {
+                drained = Err(CheckpointError::Drain(format!(
+                    "instructions still in flight after {DRAIN_LIMIT} cycles"
+                )));
+                break;
+            }
+            if let Err(e) = self.tick() {
+                drained = Err(CheckpointError::Drain(e.to_string()));
+                break;
+            }
+            if let Some(code) = self.exit_code {
+                drained = Err(CheckpointError::Drain(format!(
+                    "program exited with code {code}"
+                )));
+                break;
+            }
+            cycles += 1;
+        }
+
+        self.run_control = control;
+        drained?;
+        self.pc = self.next_commit_pc();
+        self.reset_microarch();
+        Ok(())
+    }
+
+    /// Empties the pipeline and the timing-only state a checkpoint does not record.
+    fn reset_microarch(&mut self) {
+        self.if_id = Default::default();
+        self.id_ex = Default::default();
+        self.ex_mem = Default::default();
+        self.mem_wb = Default::default();
+        self.wb_latch = Default::default();
+        self.wfi_waiting = false;
+        self.interrupt_inhibit_one_cycle = false;
+        self.stall_cycles = 0;
+        self.alu_timer = 0;
+        self.ntl_pending = None;
+        self.last_pc = 0;
+        self.same_pc_count = 0;
+
+        self.mshrs.clear();
+        for cache in [
+            &mut self.l1_i_cache,
+            &mut self.l1_d_cache,
+            &mut self.l2_cache,
+            &mut self.l2_i_cache,
+            &mut self.l3_cache,
+        ] {
+            cache.invalidate_all();
+        }
+        self.l3_ports.reset();
+        self.bus.mem_controller.reset();
+        self.bus.mem_bandwidth.reset();
+
+        self.undo.clear();
+        self.write_watches.clear_pending();
+        self.retire_check.resync();
+    }
+}
@@ -0,0 +1,121 @@
+//! Instruction Commit Hooks.
+//!
+//! This module lets an embedder observe every committed instruction (for coverage,
+//! profiling, or taint tracking) without touching the pipeline. It provides:
+//! 1. **Commit records:** [`CommitInfo`] describes one retired instruction: its PC and
+//!    encoding, the register it wrote, and the memory it accessed.
+//! 2. **Callback:** A hook installed with [`Cpu::set_commit_hook`] runs from the writeback
+//!    stage, oldest instruction first. Squashed (speculative) instructions and instructions
+//!    that trap never reach it.
+
+use super::Cpu;
+use crate::core::cpu::history::width_bytes;
+use crate::core::pipeline::latches::MemWbEntry;
+
+/// Destination register written by a committed instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegWrite {
+    /// Integer register index and the value written.
+    Int(usize, u64),
+    /// Floating-point register index and the raw bits written.
+    Fp(usize, u64),
+}
+
+/// Memory access performed by a committed load, store, or AMO.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemAccess {
+    /// Virtual address accessed.
+    pub addr: u64,
+    /// Access size in bytes (1, 2, 4, or 8).
+    pub size: u8,
+    /// The access read memory (loads, LR, and AMOs).
+    pub read: bool,
+    /// Value written to memory, or `None` if nothing was stored (e.g. a failed SC).
+    pub stored: Option<u64>,
+}
+
+/// One committed instruction, as passed to a [`CommitHook`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommitInfo {
+    /// Address of the instruction.
+    pub pc: u64,
+    /// Instruction bits; compressed instructions appear in their expanded 32-bit form.
+    pub inst: u32,
+    /// Encoded length in bytes (2 for compressed instructions).
+    pub inst_size: u64,
+    /// Register written, if any. Writes to `x0` are omitted.
+    pub rd: Option<RegWrite>,
+    /// Memory accessed, if any.
+    pub mem: Option<MemAccess>,
+}
+
+impl CommitInfo {
+    /// Builds the commit record of a writeback entry.
+    pub(crate) fn from_entry(wb: &MemWbEntry) -> Self {
+        let val = if wb.ctrl.mem_read {
+            wb.load_data
+        } else if wb.ctrl.jump {
+            wb.pc.wrapping_add(wb.inst_size)
+        } else {
+            wb.alu
+        };
+        let rd = if wb.ctrl.fp_reg_write {
+            Some(RegWrite::Fp(wb.rd, val))
+        } else if wb.ctrl.reg_write && wb.rd != 0 {
+            Some(RegWrite::Int(wb.rd, val))
+        } else {
+            None
+        };
+        let mem = (wb.ctrl.mem_read || wb.ctrl.mem_write).then(|| MemAccess {
+            addr: wb.alu,
+            size: width_bytes(wb.ctrl.width),
+            read: wb.ctrl.mem_read,
+            stored: wb.stored,
+        });
+
+        Self {
+            pc: wb.pc,
+            inst: wb.inst,
+            inst_size: wb.inst_size,
+            rd,
+            mem,
+        }
+    }
+}
+
+/// Callback invoked for every committed instruction.
+///
+/// Must be `Send` for the same reason as [`CycleHook`](super::CycleHook).
+pub type CommitHook = Box<dyn FnMut(CommitInfo) + Send>;
+
+impl Cpu {
+    /// Installs a callback run for every instruction that commits.
+    ///
+    /// Replaces any previously installed hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Callback receiving each committed instruction, in program order.
+    pub fn set_commit_hook(&mut self, hook: CommitHook) {
+        self.commit_hook = Some(hook);
+    }
+
+    /// Removes the commit hook, returning it if one was installed.
+    pub fn clear_commit_hook(&mut self) -> Option<CommitHook> {
+        self.commit_hook.take()
+    }
+
+    /// Passes the instructions committed by the current writeback to the commit hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed` - Entries that committed this cycle, oldest first.
+    pub(crate) fn run_commit_hook(&mut self, committed: &[MemWbEntry]) {
+        let Some(hook) = self.commit_hook.as_mut() else {
+            return;
+        };
+        for wb in committed.iter().filter(|wb| wb.inst != 0) {
+            hook(CommitInfo::from_entry(wb));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Spike-Compatible Commit Log.
+//!
+//! This module writes one line per committed instruction in the format of Spike's
+//! `--log-commits`, so a run can be diffed against the reference simulator. It provides:
+//! 1. **Commit order:** Lines are written from the writeback stage, so squashed
+//!    (speculative) instructions and instructions that trap never appear.
+//! 2. **Writebacks:** Each line lists the destination register and its new value, the
+//!    address of a load, and the address and value of a store.
+//!
+//! A line looks like `core   0: 3 0x0000000080000000 (0x00500293) x5  0x0000000000000005`;
+//! compressed instructions show their 16-bit encoding.
+
+use super::Cpu;
+use crate::core::arch::csr;
+use crate::core::arch::mode::PrivilegeMode;
+use crate::core::cpu::commit_hook::{CommitInfo, RegWrite};
+use crate::core::pipeline::latches::MemWbEntry;
+use crate::core::units::mmu::satp_asid;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// Destination for the commit log enabled by [`Cpu::set_commit_log`].
+pub type CommitLogSink = Box<dyn Write + Send>;
+
+impl Cpu {
+    /// Routes the commit log to `sink`, replacing any previous destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Writer receiving one line per committed instruction (e.g. a file).
+    pub fn set_commit_log(&mut self, sink: CommitLogSink) {
+        self.commit_log = Some(sink);
+    }
+
+    /// Stops commit logging, returning the sink if one was installed.
+    pub fn clear_commit_log(&mut self) -> Option<CommitLogSink> {
+        self.commit_log.take()
+    }
+
+    /// Writes the commit log lines for the instructions committed by the current writeback.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed` - Entries that committed this cycle, oldest first.
+    pub(crate) fn log_commits(&mut self, committed: &[MemWbEntry]) {
+        let Some(mut sink) = self.commit_log.take() else {
+            return;
+        };
+        for wb in committed.iter().filter(|wb| wb.inst != 0) {
+            let line = self.commit_line(wb);
+            let _ = writeln!(sink, "{}", line);
+        }
+        self.commit_log = Some(sink);
+    }
+
+    /// Formats the commit log line of one committed instruction.
+    fn commit_line(&mut self, wb: &MemWbEntry) -> String {
+        let mut line = format!("core   0: {} {:#018x} ", self.privilege.to_u8(), wb.pc);
+        match self.compressed_parcel(wb) {
+            Some(bits) => {
+                let _ = write!(line, "({:#06x})", bits);
+            }
+            None => {
+                let _ = write!(line, "({:#010x})", wb.inst);
+            }
+        }
+
+        let info = CommitInfo::from_entry(wb);
+        match info.rd {
+            Some(RegWrite::Fp(rd, val)) => {
+                let _ = write!(line, " f{:<2} {:#018x}", rd, val);
+            }
+            Some(RegWrite::Int(rd, val)) => {
+                let _ = write!(line, " x{:<2} {:#018x}", rd, val);
+            }
+            None => {}
+        }
+
+        if let Some(mem) = info.mem {
+            let _ = write!(line, " mem {:#018x}", mem.addr);
+            if let Some(data) = mem.stored {
+                let digits = 2 * mem.size as usize;
+                let mask = if digits >= 16 {
+                    u64::MAX
+                } else {
+                    (1u64 << (4 * digits)) - 1
+                };
+                let _ = write!(line, " {:#0w$x}", data & mask, w = digits + 2);
+            }
+        }
+        line
+    }
+
+    /// Reads the original 16-bit encoding of a committed compressed instruction.
+    ///
+    /// The pipeline carries compressed instructions in expanded form, so the parcel is
+    /// read back from memory. Paged fetches resolve through the I-TLB only, leaving
+    /// translation state and statistics untouched.
+    ///
+    /// # Returns
+    ///
+    /// The parcel, or `None` for a 32-bit instruction or one whose page is not cached.
+    fn compressed_parcel(&mut self, wb: &MemWbEntry) -> Option<u16> {
+        if wb.inst_size != 2 {
+            return None;
+        }
+        let mode = (self.csrs.satp >> csr::SATP_MODE_SHIFT) & csr::SATP_MODE_MASK;
+        let paddr = if self.direct_mode
+            || self.privilege == PrivilegeMode::Machine
+            || mode == csr::SATP_MODE_BARE
+        {
+            wb.pc
+        } else {
+            self.mmu.itlb_paddr(wb.pc, satp_asid(self.csrs.satp))?
+        };
+        Some(self.bus.bus.read_u16(paddr))
+    }
+}
@@ -3,16 +3,83 @@
 //! This module implements the Control and Status Register (CSR) access mechanisms for the CPU.
 //! It performs the following:
 //! 1. **Read Operations:** Retrieves CSR values while handling architectural side effects.
-//! 2. **Write Operations:** Updates CSR state and triggers necessary system updates (e.g., TLB flushes).
+//! 2. **Write Operations:** Updates CSR state and triggers necessary system updates (e.g., TLB flushes
+//!    or software TLB refills).
 //! 3. **Side Effect Management:** Handles interrupt inhibition and status bit synchronization.
 
 use super::Cpu;
 use crate::common::Trap;
 use crate::core::arch::csr;
+use crate::core::arch::mode::PrivilegeMode;
+use crate::core::units::mmu::satp_asid;
+
+/// Applies the WARL rule for `mtvec`/`stvec`: reserved MODE values (2, 3) read back as direct.
+fn legal_tvec(val: u64) -> u64 {
+    if (val & csr::TVEC_MODE_MASK) > csr::TVEC_MODE_VECTORED {
+        (val & !csr::TVEC_MODE_MASK) | csr::TVEC_MODE_DIRECT
+    } else {
+        val
+    }
+}
 
 impl Cpu {
+    /// Returns the platform timer seen through the `time` CSR.
+    ///
+    /// This is the CLINT's `mtime`, so `time` agrees with the `mtimecmp` values software
+    /// programs. Without a CLINT on the bus it falls back to the cycle count scaled by
+    /// `clint_divider`.
+    pub(crate) fn mtime(&self) -> u64 {
+        self.bus
+            .bus
+            .clint_mtime()
+            .unwrap_or(self.stats.cycles / self.clint_divider)
+    }
+
+    /// Returns `true` if the current privilege may access CSR `addr`.
+    ///
+    /// The user counters (`cycle`, `time`, `instret`, `hpmcounter3`–`31` and their
+    /// `*h` halves) are restricted: S-mode needs the counter's bit in `mcounteren`, and
+    /// U-mode needs it in both `mcounteren` and `scounteren`. The floating-point CSRs
+    /// are inaccessible while `mstatus.FS` is Off. Other CSRs are not checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The 12-bit CSR address.
+    pub(crate) fn csr_accessible(&self, addr: u32) -> bool {
+        if matches!(addr, csr::FFLAGS | csr::FRM | csr::FCSR) {
+            return !self.fp_disabled();
+        }
+        if !matches!(addr, csr::CYCLE..=csr::HPMCOUNTER31 | csr::CYCLEH..=csr::HPMCOUNTER31H) {
+            return true;
+        }
+        let bit = 1u64 << (addr & 0x1F);
+        match self.privilege {
+            PrivilegeMode::Machine => true,
+            PrivilegeMode::Supervisor => self.csrs.mcounteren & bit != 0,
+            PrivilegeMode::User => self.csrs.mcounteren & self.csrs.scounteren & bit != 0,
+        }
+    }
+
+    /// Returns whether `mstatus.FS` is Off, making every floating-point instruction illegal.
+    pub(crate) fn fp_disabled(&self) -> bool {
+        self.csrs.mstatus & csr::MSTATUS_FS == csr::MSTATUS_FS_OFF
+    }
+
+    /// Sets `mstatus.FS` (and its `sstatus` view) to Dirty after floating-point state changes,
+    /// telling the OS the FP registers must be saved on the next context switch.
+    pub(crate) fn mark_fs_dirty(&mut self) {
+        self.csrs.mstatus |= csr::MSTATUS_FS_DIRTY;
+        self.csrs.sstatus |= csr::MSTATUS_FS_DIRTY;
+    }
+
     /// Reads a value from a Control and Status Register (CSR).
     ///
+    /// The `cycle`/`instret` counters are full 64-bit values backed by
+    /// [`SimStats`](crate::stats::SimStats) and `time` reads the CLINT's
+    /// `mtime`; all wrap modulo 2^64, as the privileged spec requires. The
+    /// RV32 `*h` aliases return the upper 32 bits of the same counters so
+    /// that `(hi << 32) | lo` recombines them.
+    ///
     /// # Arguments
     ///
     /// * `addr` - The 12-bit address of the CSR to read.
@@ -22,15 +89,17 @@ impl Cpu {
     /// The current 64-bit value of the specified CSR.
     pub(crate) fn csr_read(&self, addr: u32) -> u64 {
         match addr {
+            csr::FFLAGS | csr::FRM | csr::FCSR => self.csrs.read(addr),
             csr::MVENDORID => 0,
             csr::MARCHID => 0,
             csr::MIMPID => 0,
-            csr::MHARTID => 0,
+            csr::MHARTID => self.hart_id as u64,
             csr::MSTATUS => self.csrs.mstatus,
             csr::MEDELEG => self.csrs.medeleg,
             csr::MIDELEG => self.csrs.mideleg,
             csr::MIE => self.csrs.mie,
             csr::MTVEC => self.csrs.mtvec,
+            csr::MCOUNTEREN | csr::SCOUNTEREN => self.csrs.read(addr),
             csr::MISA => self.csrs.misa,
             csr::MSCRATCH => self.csrs.mscratch,
             csr::MEPC => self.csrs.mepc,
@@ -47,9 +116,29 @@ impl Cpu {
             csr::SIP => self.csrs.mip & self.csrs.mideleg,
             csr::STIMECMP => self.csrs.stimecmp,
             csr::SATP => self.csrs.satp,
+            csr::STLBVA => self.csrs.stlbva,
             csr::CYCLE | csr::MCYCLE => self.stats.cycles,
-            csr::TIME => self.stats.cycles / self.clint_divider,
+            csr::TIME => self.mtime(),
             csr::INSTRET | csr::MINSTRET => self.stats.instructions_retired,
+            csr::CYCLEH | csr::MCYCLEH => self.stats.cycles >> csr::COUNTER_HIGH_SHIFT,
+            csr::TIMEH => self.mtime() >> csr::COUNTER_HIGH_SHIFT,
+            csr::PMPCFG0..=csr::PMPCFG15 | csr::PMPADDR0..=csr::PMPADDR63 => self.csrs.read(addr),
+            csr::INSTRETH | csr::MINSTRETH => {
+                self.stats.instructions_retired >> csr::COUNTER_HIGH_SHIFT
+            }
+            csr::HPMCOUNTER3..=csr::HPMCOUNTER31 => {
+                self.hpm_counter((addr - csr::HPMCOUNTER3) as usize)
+            }
+            csr::MHPMCOUNTER3..=csr::MHPMCOUNTER31 => {
+                self.hpm_counter((addr - csr::MHPMCOUNTER3) as usize)
+            }
+            csr::HPMCOUNTER3H..=csr::HPMCOUNTER31H => {
+                self.hpm_counter((addr - csr::HPMCOUNTER3H) as usize) >> csr::COUNTER_HIGH_SHIFT
+            }
+            csr::MHPMCOUNTER3H..=csr::MHPMCOUNTER31H => {
+                self.hpm_counter((addr - csr::MHPMCOUNTER3H) as usize) >> csr::COUNTER_HIGH_SHIFT
+            }
+            csr::MHPMEVENT3..=csr::MHPMEVENT31 => self.csrs.read(addr),
             _ => 0,
         }
     }
@@ -65,6 +154,10 @@ impl Cpu {
             csr::CSR_SIM_PANIC => {
                 self.trap(Trap::RequestedTrap(val), self.pc);
             }
+            csr::FFLAGS | csr::FRM | csr::FCSR => {
+                self.csrs.write(addr, val);
+                self.mark_fs_dirty();
+            }
             csr::MSTATUS => {
                 self.csrs.mstatus = val;
 
@@ -77,20 +170,21 @@ impl Cpu {
                 self.csrs.sstatus = val & mask;
                 self.interrupt_inhibit_one_cycle = true;
             }
-            csr::MEDELEG => self.csrs.medeleg = val,
-            csr::MIDELEG => self.csrs.mideleg = val,
+            csr::MEDELEG => self.csrs.medeleg = val & csr::MEDELEG_WRITABLE,
+            csr::MIDELEG => self.csrs.mideleg = val & csr::MIDELEG_WRITABLE,
             csr::MIE => {
-                self.csrs.mie = val;
+                self.csrs.mie = val & csr::MIE_WRITABLE;
                 self.interrupt_inhibit_one_cycle = true;
             }
-            csr::MTVEC => self.csrs.mtvec = val,
+            csr::MTVEC => self.csrs.mtvec = legal_tvec(val),
+            csr::MCOUNTEREN | csr::SCOUNTEREN => self.csrs.write(addr, val),
             csr::MISA => self.csrs.misa = val,
             csr::MSCRATCH => self.csrs.mscratch = val,
             csr::MEPC => self.csrs.mepc = val & !1,
             csr::MCAUSE => self.csrs.mcause = val,
             csr::MTVAL => self.csrs.mtval = val,
             csr::MIP => {
-                let mask = csr::MIP_SSIP | csr::MIP_STIP | csr::MIP_SEIP;
+                let mask = csr::MIP_WRITABLE;
                 self.csrs.mip = (self.csrs.mip & !mask) | (val & mask);
             }
             csr::SSTATUS => {
@@ -106,12 +200,12 @@ impl Cpu {
                 self.interrupt_inhibit_one_cycle = true;
             }
             csr::SIE => {
-                let mask = self.csrs.mideleg;
+                let mask = self.csrs.mideleg & csr::MIE_WRITABLE;
                 self.csrs.mie = (self.csrs.mie & !mask) | (val & mask);
                 self.interrupt_inhibit_one_cycle = true;
             }
             csr::STVEC => {
-                self.csrs.stvec = val;
+                self.csrs.stvec = legal_tvec(val);
             }
             csr::SSCRATCH => self.csrs.sscratch = val,
             csr::SEPC => self.csrs.sepc = val & !1,
@@ -128,19 +222,40 @@ impl Cpu {
             csr::SATP => {
                 let mode = (val >> csr::SATP_MODE_SHIFT) & csr::SATP_MODE_MASK;
 
-                let new_val = if mode == csr::SATP_MODE_SV39 || mode == csr::SATP_MODE_BARE {
+                let new_val = if mode == csr::SATP_MODE_SV39
+                    || mode == csr::SATP_MODE_SV48
+                    || mode == csr::SATP_MODE_BARE
+                {
                     val
                 } else {
                     val & !(csr::SATP_MODE_MASK << csr::SATP_MODE_SHIFT)
                 };
 
+                let old_asid = satp_asid(self.csrs.satp);
                 self.csrs.satp = new_val;
                 self.flush_pipeline_stores();
                 self.l1_d_cache.flush();
 
-                self.mmu.dtlb.flush();
-                self.mmu.itlb.flush();
+                // TLB entries are ASID-tagged, so switching to another address
+                // space keeps them; reusing the ASID for new tables does not.
+                if satp_asid(new_val) == old_asid {
+                    self.mmu.dtlb.flush();
+                    self.mmu.itlb.flush();
+                }
+            }
+            csr::PMPCFG0..=csr::PMPCFG15 | csr::PMPADDR0..=csr::PMPADDR63 => {
+                self.csrs.write(addr, val)
+            }
+            csr::MHPMCOUNTER3..=csr::MHPMCOUNTER31 => {
+                self.set_hpm_counter((addr - csr::MHPMCOUNTER3) as usize, val)
+            }
+            csr::MHPMEVENT3..=csr::MHPMEVENT31 => {
+                self.set_hpm_event((addr - csr::MHPMEVENT3) as usize, val)
             }
+            csr::STLBVA => self.csrs.stlbva = val,
+            csr::STLBW => self
+                .mmu
+                .write_tlb(self.csrs.stlbva, val, satp_asid(self.csrs.satp)),
             _ => {}
         }
     }
@@ -0,0 +1,122 @@
+//! Debugger Run Control.
+//!
+//! This module lets an external debugger (see [`crate::sim::gdbstub`]) stop the hart
+//! between instructions. It provides:
+//! 1. **Breakpoints:** Fetch stops in front of a breakpoint PC, so the instruction never
+//!    enters the pipeline until the debugger resumes past it.
+//! 2. **Single-step:** Fetch issues exactly one instruction, then stops.
+//! 3. **Halt:** Once fetch has stopped and the instructions ahead of it have committed,
+//!    the hart is halted with `pc` at the next instruction to execute.
+
+use super::Cpu;
+use std::collections::HashSet;
+
+/// Breakpoints and fetch limits installed by a debugger.
+///
+/// The default value has no breakpoints and never stops fetch.
+#[derive(Clone, Debug, Default)]
+pub struct RunControl {
+    breakpoints: HashSet<u64>,
+    /// Breakpoint fetched past once when resuming from it.
+    resume_from: Option<u64>,
+    /// Instructions fetch may still issue before stopping; `None` is unlimited.
+    fetch_budget: Option<usize>,
+}
+
+impl RunControl {
+    /// Returns `true` if fetch must stop before the instruction at `pc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Address of the next instruction to fetch.
+    /// * `fetched` - Instructions already fetched this cycle.
+    #[inline]
+    pub(crate) fn blocks_fetch(&self, pc: u64, fetched: usize) -> bool {
+        if self.fetch_budget.is_some_and(|budget| fetched >= budget) {
+            return true;
+        }
+        !self.breakpoints.is_empty()
+            && self.breakpoints.contains(&pc)
+            && self.resume_from != Some(pc)
+    }
+
+    /// Charges the instructions fetched this cycle against the single-step budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `pcs` - PCs of the instructions fetched this cycle.
+    pub(crate) fn note_fetched(&mut self, pcs: impl Iterator<Item = u64>) {
+        for pc in pcs {
+            if self.resume_from == Some(pc) {
+                self.resume_from = None;
+            }
+            if let Some(budget) = &mut self.fetch_budget {
+                *budget = budget.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl Cpu {
+    /// Installs a software breakpoint; fetch stops before the instruction at `pc`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the breakpoint was not already installed.
+    pub fn set_breakpoint(&mut self, pc: u64) -> bool {
+        self.run_control.breakpoints.insert(pc)
+    }
+
+    /// Removes a software breakpoint.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the breakpoint was installed.
+    pub fn clear_breakpoint(&mut self, pc: u64) -> bool {
+        self.run_control.breakpoints.remove(&pc)
+    }
+
+    /// Stops fetch so the hart halts once the instructions in flight have committed.
+    pub fn request_halt(&mut self) {
+        self.run_control.fetch_budget = Some(0);
+    }
+
+    /// Lets a halted hart run again.
+    ///
+    /// A breakpoint at the current PC is stepped over once.
+    ///
+    /// # Arguments
+    ///
+    /// * `single_step` - Fetch only the next instruction, then halt again.
+    pub fn resume(&mut self, single_step: bool) {
+        self.run_control.resume_from = Some(self.pc);
+        self.run_control.fetch_budget = single_step.then_some(1);
+    }
+
+    /// Returns `true` if fetch is stopped and every older instruction has committed.
+    ///
+    /// A hart parked in WFI also counts as halted once the instructions ahead of the
+    /// WFI have committed, since the fetched ones behind it cannot drain until it wakes.
+    pub fn is_halted(&self) -> bool {
+        let fetched = self.if_id.entries.len() + self.id_ex.entries.len();
+        let front_empty = fetched == 0 || self.wfi_waiting;
+        self.run_control.blocks_fetch(self.pc, 0)
+            && front_empty
+            && self.ex_mem.entries.is_empty()
+            && self.mem_wb.entries.is_empty()
+    }
+
+    /// Returns the PC of the next instruction to commit.
+    ///
+    /// Equal to `pc` once [`is_halted`](Self::is_halted) reports a drained pipeline.
+    pub fn next_commit_pc(&self) -> u64 {
+        self.mem_wb
+            .entries
+            .first()
+            .map(|e| e.pc)
+            .or_else(|| self.ex_mem.entries.first().map(|e| e.pc))
+            .or_else(|| self.id_ex.entries.first().map(|e| e.pc))
+            .or_else(|| self.if_id.entries.first().map(|e| e.pc))
+            .unwrap_or(self.pc)
+    }
+}
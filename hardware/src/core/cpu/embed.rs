@@ -0,0 +1,219 @@
+//! Embedding API.
+//!
+//! This module gives library users (fuzzers, test harnesses, custom front ends) direct access
+//! to the hart without going through a debugger. It provides:
+//! 1. **Memory:** Reads and writes at virtual addresses, translated as the hart would, and
+//!    side-effect-free peeks and pokes for debuggers.
+//! 2. **Registers:** Integer register and CSR accessors.
+//! 3. **Stepping:** Advancing the pipeline until the next instruction retires.
+//!
+//! # Examples
+//!
+//! ```
+//! use riscv_core::{Config, Cpu, System};
+//!
+//! let config = Config::default();
+//! let mut cpu = Cpu::new(System::new(&config, ""), &config).unwrap();
+//!
+//! // addi x5, x0, 42; addi x6, x5, 1
+//! let program = [0x02a0_0293u32, 0x0012_8313];
+//! let bytes: Vec<u8> = program.iter().flat_map(|i| i.to_le_bytes()).collect();
+//! cpu.write_mem(config.general.start_pc, &bytes).unwrap();
+//!
+//! let step = cpu.step().unwrap();
+//! assert!(step.retired >= 1);
+//! assert_eq!(cpu.read_reg(5), 42);
+//! ```
+
+use super::Cpu;
+use crate::common::{AccessType, SimError, TranslationResult, Trap, VirtAddr};
+
+/// Cycles [`Cpu::step`] waits for an instruction to retire before giving up.
+const STEP_CYCLE_LIMIT: u64 = 100_000;
+
+/// Outcome of [`Cpu::step`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepResult {
+    /// Instructions retired during the step (more than one on a superscalar pipeline).
+    pub retired: u64,
+    /// Cycles the step took.
+    pub cycles: u64,
+    /// Trap taken during the step, if any.
+    pub trap: Option<Trap>,
+}
+
+impl Cpu {
+    /// Translates every page of `[vaddr, vaddr + len)` for `access` with `translate`,
+    /// which checks each page's run of bytes as one access.
+    ///
+    /// # Returns
+    ///
+    /// The physical address of each byte, or the first translation fault.
+    fn translate_range(
+        &mut self,
+        vaddr: u64,
+        len: usize,
+        access: AccessType,
+        translate: fn(&mut Self, VirtAddr, AccessType, u64) -> TranslationResult,
+    ) -> Result<Vec<u64>, Trap> {
+        let page_mask = (1u64 << self.mmu.page_shift()) - 1;
+        let mut paddrs = Vec::with_capacity(len);
+        let mut base = 0;
+        for i in 0..len as u64 {
+            let va = vaddr.wrapping_add(i);
+            if i == 0 || va & page_mask == 0 {
+                let run = (page_mask + 1 - (va & page_mask)).min(len as u64 - i);
+                let result = translate(self, VirtAddr::new(va), access, run);
+                if let Some(trap) = result.trap {
+                    return Err(trap);
+                }
+                base = result.paddr.val().wrapping_sub(va);
+            }
+            paddrs.push(va.wrapping_add(base));
+        }
+        Ok(paddrs)
+    }
+
+    /// Reads `len` bytes of memory starting at virtual address `vaddr`.
+    ///
+    /// Addresses are translated with the current privilege and `satp`, as a load would be;
+    /// TLB fills and MMU statistics are updated. Caches are not modeled.
+    ///
+    /// # Returns
+    ///
+    /// The bytes read, or the load fault of the first untranslatable or unmapped page.
+    pub fn read_mem(&mut self, vaddr: u64, len: usize) -> Result<Vec<u8>, Trap> {
+        let paddrs = self.translate_range(vaddr, len, AccessType::Read, Self::translate)?;
+        Ok(paddrs
+            .into_iter()
+            .map(|pa| self.bus.bus.read_u8(pa))
+            .collect())
+    }
+
+    /// Writes `data` to memory starting at virtual address `vaddr`.
+    ///
+    /// Every page is translated before any byte is written, so a fault leaves memory
+    /// unchanged. Instructions already fetched from the written range are not refetched.
+    ///
+    /// # Returns
+    ///
+    /// The store fault of the first untranslatable or unmapped page, if any.
+    pub fn write_mem(&mut self, vaddr: u64, data: &[u8]) -> Result<(), Trap> {
+        let paddrs = self.translate_range(vaddr, data.len(), AccessType::Write, Self::translate)?;
+        for (pa, &byte) in paddrs.into_iter().zip(data) {
+            self.bus.bus.write_u8(pa, byte);
+        }
+        Ok(())
+    }
+
+    /// Reads `len` bytes of memory at virtual address `vaddr` on behalf of a debugger.
+    ///
+    /// Translates like [`Cpu::read_mem`], but through [`Cpu::probe`]: the TLBs, MMU
+    /// statistics, and PTE A/D bits are left as the guest set them.
+    ///
+    /// # Returns
+    ///
+    /// The bytes read, or the load fault of the first untranslatable or unmapped page.
+    pub fn peek_mem(&mut self, vaddr: u64, len: usize) -> Result<Vec<u8>, Trap> {
+        let paddrs = self.translate_range(vaddr, len, AccessType::Read, Self::probe)?;
+        Ok(paddrs
+            .into_iter()
+            .map(|pa| self.bus.bus.read_u8(pa))
+            .collect())
+    }
+
+    /// Writes `data` to virtual address `vaddr` on behalf of a debugger.
+    ///
+    /// All or nothing, like [`Cpu::write_mem`], but translated through [`Cpu::probe`]
+    /// so no TLB, statistic, or PTE A/D bit changes.
+    ///
+    /// # Returns
+    ///
+    /// The store fault of the first untranslatable or unmapped page, if any.
+    pub fn poke_mem(&mut self, vaddr: u64, data: &[u8]) -> Result<(), Trap> {
+        let paddrs = self.translate_range(vaddr, data.len(), AccessType::Write, Self::probe)?;
+        for (pa, &byte) in paddrs.into_iter().zip(data) {
+            self.bus.bus.write_u8(pa, byte);
+        }
+        Ok(())
+    }
+
+    /// Returns the value of integer register `x{idx}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not below 32.
+    pub fn read_reg(&self, idx: usize) -> u64 {
+        self.regs.read(idx)
+    }
+
+    /// Sets integer register `x{idx}`; writes to `x0` are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not below 32.
+    pub fn write_reg(&mut self, idx: usize, val: u64) {
+        self.regs.write(idx, val);
+    }
+
+    /// Reads a CSR as a `csrr` instruction would, without privilege checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The 12-bit CSR address.
+    pub fn read_csr(&self, addr: u32) -> u64 {
+        self.csr_read(addr)
+    }
+
+    /// Writes a CSR as a `csrw` instruction would, including side effects such as
+    /// TLB flushes on `satp`, without privilege checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The 12-bit CSR address.
+    /// * `val` - Value to write.
+    pub fn write_csr(&mut self, addr: u32, val: u64) {
+        self.csr_write(addr, val);
+    }
+
+    /// Advances the pipeline until at least one instruction retires or a trap is taken.
+    ///
+    /// Stops early if the program exits, and after `STEP_CYCLE_LIMIT` cycles (for example
+    /// while waiting in WFI) with `retired == 0`.
+    ///
+    /// # Returns
+    ///
+    /// What happened during the step, or the error [`tick`](Self::tick) reported. A trap
+    /// that cannot be delivered (any trap in direct mode) is reported in
+    /// [`StepResult::trap`] rather than as an error.
+    pub fn step(&mut self) -> Result<StepResult, SimError> {
+        let start_cycles = self.stats.cycles;
+        let start_retired = self.stats.instructions_retired;
+        let start_traps = self.stats.traps_taken;
+        let mut trap = None;
+
+        for _ in 0..STEP_CYCLE_LIMIT {
+            match self.tick() {
+                Ok(()) => {}
+                Err(SimError::UnhandledTrap(t, _)) => {
+                    trap = Some(t);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+            if self.stats.traps_taken != start_traps {
+                trap = self.last_trap.clone();
+                break;
+            }
+            if self.stats.instructions_retired != start_retired || self.exit_code.is_some() {
+                break;
+            }
+        }
+
+        Ok(StepResult {
+            retired: self.stats.instructions_retired - start_retired,
+            cycles: self.stats.cycles - start_cycles,
+            trap,
+        })
+    }
+}
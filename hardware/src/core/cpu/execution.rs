@@ -7,9 +7,9 @@
 //! 4. **Observability:** Provides tracing and pipeline visualization for debugging.
 
 use super::Cpu;
+use crate::common::SimError;
 use crate::common::constants::{
-    DEBUG_PC_END, DEBUG_PC_START, HANG_DETECTION_THRESHOLD, PAGE_OFFSET_MASK, PAGE_SHIFT,
-    STATUS_UPDATE_INTERVAL, VPN_MASK, WFI_INSTRUCTION,
+    DEBUG_PC_END, DEBUG_PC_START, HANG_DETECTION_THRESHOLD, STATUS_UPDATE_INTERVAL, WFI_INSTRUCTION,
 };
 use crate::core::arch::csr;
 use crate::core::arch::mode::PrivilegeMode;
@@ -17,18 +17,26 @@ use crate::core::pipeline::{
     hazards,
     stages::{decode_stage, execute_stage, fetch_stage, mem_stage, wb_stage},
 };
+use crate::core::units::mmu::satp_asid;
 use crate::isa::abi;
 
 impl Cpu {
     /// Advances the CPU state by one clock cycle.
     ///
-    /// This function executes all pipeline stages, handles pending interrupts, updates
-    /// timers, and manages stall cycles.
+    /// This function runs the embedder cycle hook (if installed), executes all pipeline
+    /// stages, handles pending interrupts, updates timers, and manages stall cycles.
     ///
     /// # Returns
     ///
-    /// `Ok(())` on success or an error string on failure.
-    pub fn tick(&mut self) -> Result<(), String> {
+    /// `Ok(())` on success, or the [`SimError`] that ended the simulation this cycle
+    /// (e.g. [`SimError::UnhandledTrap`] for a fault in direct mode).
+    pub fn tick(&mut self) -> Result<(), SimError> {
+        self.run_cycle_hook();
+
+        if !self.stats.is_measuring() {
+            self.stats.start_measurement();
+        }
+
         if let Some(code) = self.bus.check_exit() {
             self.exit_code = Some(code);
             return Ok(());
@@ -48,14 +56,12 @@ impl Cpu {
         if self.pc == self.last_pc {
             self.same_pc_count += 1;
             if self.same_pc_count == HANG_DETECTION_THRESHOLD {
-                let inst = if let Some((ppn, _, _, _, _)) =
-                    self.mmu.dtlb.lookup((self.pc >> PAGE_SHIFT) & VPN_MASK)
-                {
-                    let paddr = (ppn << PAGE_SHIFT) | (self.pc & PAGE_OFFSET_MASK);
-                    self.bus.bus.read_u32(paddr)
-                } else {
-                    0
-                };
+                let inst =
+                    if let Some(paddr) = self.mmu.dtlb_paddr(self.pc, satp_asid(self.csrs.satp)) {
+                        self.bus.bus.read_u32(paddr)
+                    } else {
+                        0
+                    };
 
                 if self.trace {
                     if inst == WFI_INSTRUCTION {
@@ -65,8 +71,9 @@ impl Cpu {
                         );
                     } else {
                         println!(
-                            "\n[CPU] POTENTIAL HANG: Stuck at PC {:#x} (Inst: {:#010x})",
-                            self.pc, inst
+                            "\n[CPU] POTENTIAL HANG: Stuck at PC {} (Inst: {:#010x})",
+                            self.symbolize(self.pc),
+                            inst
                         );
                     }
                 }
@@ -78,7 +85,15 @@ impl Cpu {
 
         let prev_priv = self.privilege;
 
-        let (timer_irq, meip, seip) = self.bus.tick();
+        // Devices advance once per system cycle, on hart 0's tick. PLIC contexts
+        // 0 and 1 are wired to hart 0 only.
+        let (meip, seip) = if self.hart_id == 0 {
+            let (_, meip, seip) = self.bus.tick();
+            (meip, seip)
+        } else {
+            (false, false)
+        };
+        let (timer_irq, software_irq) = self.bus.bus.clint_lines(self.hart_id);
 
         let mut mip = self.csrs.mip;
 
@@ -87,6 +102,11 @@ impl Cpu {
         } else {
             mip &= !csr::MIP_MTIP;
         }
+        if software_irq {
+            mip |= csr::MIP_MSIP;
+        } else {
+            mip &= !csr::MIP_MSIP;
+        }
 
         if meip {
             mip |= csr::MIP_MEIP;
@@ -99,7 +119,7 @@ impl Cpu {
             mip &= !csr::MIP_SEIP;
         }
 
-        let mtime = self.stats.cycles / self.clint_divider;
+        let mtime = self.mtime();
         if self.csrs.stimecmp > 0 {
             if mtime >= self.csrs.stimecmp {
                 mip |= csr::MIP_STIP;
@@ -116,22 +136,42 @@ impl Cpu {
 
         if self.stall_cycles > 0 {
             self.stall_cycles -= 1;
-            self.stats.cycles += 1;
+            self.stats.cycles = self.stats.cycles.wrapping_add(1);
             self.stats.stalls_mem += 1;
             self.track_mode_cycles();
             return Ok(());
         }
         if self.alu_timer > 0 {
             self.alu_timer -= 1;
-            self.stats.cycles += 1;
+            self.stats.cycles = self.stats.cycles.wrapping_add(1);
             self.track_mode_cycles();
             return Ok(());
         }
 
-        self.stats.cycles += 1;
+        // A hart parked in WFI idles once the instructions ahead of it have
+        // committed: devices keep ticking above, but no stage runs until an
+        // enabled interrupt is pending and writeback can wake the hart.
+        if self.wfi_waiting
+            && self.csrs.mip & self.csrs.mie == 0
+            && self.ex_mem.entries.is_empty()
+            && self.mem_wb.entries.is_empty()
+        {
+            self.stats.cycles = self.stats.cycles.wrapping_add(1);
+            self.stats.cycles_idle += 1;
+            self.track_mode_cycles();
+            return Ok(());
+        }
+
+        self.stats.cycles = self.stats.cycles.wrapping_add(1);
         self.track_mode_cycles();
 
         wb_stage(self);
+        if let Some(msg) = self.retire_check.take_violation() {
+            return Err(SimError::RetireCheck(msg));
+        }
+        if let Some(err) = self.pending_error.take() {
+            return Err(err);
+        }
         if self.exit_code.is_some() {
             return Ok(());
         }
@@ -141,11 +181,16 @@ impl Cpu {
 
         if !self.wfi_waiting {
             let is_load_use_hazard = hazards::need_stall_load_use(&self.id_ex, &self.if_id);
+            // Operands still in flight from a non-blocking miss hold decode.
+            let waits_on_miss =
+                hazards::pending_operand_wait(&self.if_id, &self.mshrs, self.stats.cycles) > 0;
 
             execute_stage(self);
 
             if is_load_use_hazard {
                 self.stats.stalls_data += 1;
+            } else if waits_on_miss {
+                self.stats.stalls_mem += 1;
             } else {
                 decode_stage(self);
 
@@ -157,6 +202,10 @@ impl Cpu {
 
         self.regs.write(abi::REG_ZERO, 0);
 
+        if let Some(err) = self.pending_error.take() {
+            return Err(err);
+        }
+
         if self.trace {
             if self.privilege != prev_priv {
                 println!(
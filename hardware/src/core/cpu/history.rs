@@ -0,0 +1,227 @@
+//! Reverse-Execution History.
+//!
+//! This module implements a bounded undo log for interactive debugging. It provides:
+//! 1. **Delta capture:** One record per committed instruction holding its PC, the old
+//!    destination register value, and the old bytes of any RAM it wrote (one run per
+//!    physical frame for a misaligned store that crosses a page).
+//! 2. **Pending stores:** Old RAM contents for stores performed in MEM but not yet committed.
+//! 3. **Step back:** `Cpu::step_back` reverts the most recently committed instruction.
+//!
+//! Only register and RAM state is restored. CSRs, device state, and statistics are not
+//! rolled back, and MMIO stores are never captured (reading them could have side effects).
+
+use std::collections::VecDeque;
+
+use super::Cpu;
+use crate::core::pipeline::signals::MemWidth;
+
+/// Old contents of a RAM location overwritten by a store or AMO.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemUndo {
+    /// Physical address of the write.
+    pub addr: u64,
+    /// Value stored at `addr` before the write (zero-extended).
+    pub old: u64,
+    /// Access size in bytes (1 to 8; a split store's runs can be any length).
+    pub size: u8,
+}
+
+/// Old contents of the destination register of a committed instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegUndo {
+    /// Integer register index and its previous value.
+    Int(usize, u64),
+    /// Floating-point register index and its previous raw bits.
+    Fp(usize, u64),
+}
+
+/// State delta of one committed instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoRecord {
+    /// PC of the committed instruction; restored as the next PC on undo.
+    pub pc: u64,
+    /// Destination register overwritten by the instruction, if any.
+    pub reg: Option<RegUndo>,
+    /// RAM runs overwritten by the instruction, in the order they were written.
+    pub mem: Vec<MemUndo>,
+}
+
+/// Bounded ring buffer of committed-instruction deltas.
+///
+/// A capacity of zero disables capture entirely.
+#[derive(Default)]
+pub struct UndoLog {
+    capacity: usize,
+    records: VecDeque<UndoRecord>,
+    pending_stores: VecDeque<(u64, MemUndo)>,
+}
+
+impl UndoLog {
+    /// Creates an undo log that retains at most `capacity` instructions.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of undoable instructions (`0` disables capture).
+    ///
+    /// # Returns
+    ///
+    /// An empty `UndoLog`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+            pending_stores: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if deltas are being captured.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the number of instructions that can currently be undone.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if there is nothing to undo.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns the record that the next [`Cpu::step_back`] would revert.
+    pub fn last(&self) -> Option<&UndoRecord> {
+        self.records.back()
+    }
+
+    /// Discards all history, e.g. after the state it refers to was replaced.
+    pub(crate) fn clear(&mut self) {
+        self.records.clear();
+        self.pending_stores.clear();
+    }
+
+    /// Records the pre-store contents of a RAM write performed in the MEM stage.
+    ///
+    /// A store split across non-contiguous frames records one entry per run.
+    pub(crate) fn record_store(&mut self, pc: u64, undo: MemUndo) {
+        self.pending_stores.push_back((pc, undo));
+    }
+
+    /// Commits one instruction, pairing it with its pending stores when `wrote_mem` is set.
+    ///
+    /// Pending stores older than `pc` belong to instructions that were flushed and are dropped.
+    pub(crate) fn commit(&mut self, pc: u64, reg: Option<RegUndo>, wrote_mem: bool) {
+        let mem = if wrote_mem {
+            self.take_stores(pc)
+        } else {
+            Vec::new()
+        };
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(UndoRecord { pc, reg, mem });
+    }
+
+    /// Forgets pending stores of instructions squashed by a trap.
+    pub(crate) fn discard_pending(&mut self) {
+        self.pending_stores.clear();
+    }
+
+    /// Pops the pending stores for `pc`, discarding stale entries ahead of them.
+    fn take_stores(&mut self, pc: u64) -> Vec<MemUndo> {
+        while self.pending_stores.front().is_some_and(|&(p, _)| p != pc) {
+            self.pending_stores.pop_front();
+        }
+        let mut stores = Vec::new();
+        while let Some(&(_, undo)) = self.pending_stores.front().filter(|&&(p, _)| p == pc) {
+            stores.push(undo);
+            self.pending_stores.pop_front();
+        }
+        stores
+    }
+}
+
+/// Returns the access size in bytes for a memory width.
+pub(crate) fn width_bytes(width: MemWidth) -> u8 {
+    match width {
+        MemWidth::Byte => 1,
+        MemWidth::Half => 2,
+        MemWidth::Word => 4,
+        MemWidth::Double => 8,
+        MemWidth::Nop => 0,
+    }
+}
+
+impl Cpu {
+    /// Reverts the most recently committed instruction.
+    ///
+    /// Stores performed by in-flight (uncommitted) instructions are reverted first, then
+    /// the last record's memory and register deltas are undone. The pipeline is flushed,
+    /// any LR reservation is dropped, and execution resumes at the undone instruction.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an instruction was undone, `false` if the history is empty or disabled.
+    pub fn step_back(&mut self) -> bool {
+        let Some(record) = self.undo.records.pop_back() else {
+            return false;
+        };
+
+        while let Some((_, pending)) = self.undo.pending_stores.pop_back() {
+            self.restore_mem(pending);
+        }
+        for &mem in record.mem.iter().rev() {
+            self.restore_mem(mem);
+        }
+        match record.reg {
+            Some(RegUndo::Int(rd, old)) => self.regs.write(rd, old),
+            Some(RegUndo::Fp(rd, old)) => self.regs.write_f(rd, old),
+            None => {}
+        }
+
+        self.pc = record.pc;
+        self.retire_check.resync();
+        self.if_id = Default::default();
+        self.id_ex = Default::default();
+        self.ex_mem = Default::default();
+        self.mem_wb = Default::default();
+        self.wb_latch = Default::default();
+        self.load_reservation = None;
+        self.wfi_waiting = false;
+        self.stall_cycles = 0;
+        self.alu_timer = 0;
+        self.mshrs.clear();
+        true
+    }
+
+    /// Reads `size` bytes of physical memory for undo capture.
+    pub(crate) fn read_undo_value(&mut self, addr: u64, size: u8) -> u64 {
+        match size {
+            1 => self.bus.bus.read_u8(addr) as u64,
+            2 => self.bus.bus.read_u16(addr) as u64,
+            4 => self.bus.bus.read_u32(addr) as u64,
+            8 => self.bus.bus.read_u64(addr),
+            _ => (0..size as u64).fold(0, |acc, i| {
+                acc | (u64::from(self.bus.bus.read_u8(addr + i)) << (8 * i))
+            }),
+        }
+    }
+
+    /// Writes a captured memory value back to physical memory.
+    fn restore_mem(&mut self, undo: MemUndo) {
+        match undo.size {
+            1 => self.bus.bus.write_u8(undo.addr, undo.old as u8),
+            2 => self.bus.bus.write_u16(undo.addr, undo.old as u16),
+            4 => self.bus.bus.write_u32(undo.addr, undo.old as u32),
+            8 => self.bus.bus.write_u64(undo.addr, undo.old),
+            _ => {
+                for i in 0..undo.size as u64 {
+                    self.bus
+                        .bus
+                        .write_u8(undo.addr + i, (undo.old >> (8 * i)) as u8);
+                }
+            }
+        }
+    }
+}
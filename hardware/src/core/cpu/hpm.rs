@@ -0,0 +1,118 @@
+//! Hardware Performance-Monitoring Counters.
+//!
+//! This module backs `mhpmcounter3`–`mhpmcounter31` (and their user-mode `hpmcounter`
+//! aliases) with the simulator's own statistics. It provides:
+//! 1. **Event selection:** Each `mhpmevent` register names a [`SimStats`] counter, using
+//!    the SBI PMU event encodings that Linux `perf` programs through OpenSBI.
+//! 2. **Counting:** A counter reads its event's count since it was last written, so it
+//!    advances exactly when the statistic does. Unknown events count nothing.
+//!
+//! Supported selectors:
+//! - `0x1`–`0x6`: cycles, instructions, L1-D references and misses, branches, and branch
+//!   mispredictions (SBI hardware general events).
+//! - `0x1_0000 | cache << 3 | op << 1 | result`: accesses (`result` 0) or misses (1) of the
+//!   L1-D (`cache` 0), L1-I (1), last-level cache (2), DTLB (3), ITLB (4), and branch
+//!   predictor (5). Reads and writes are not told apart, so `op` is ignored.
+
+use super::Cpu;
+use crate::stats::SimStats;
+
+/// SBI hardware general events.
+const EVENT_CPU_CYCLES: u64 = 0x1;
+const EVENT_INSTRUCTIONS: u64 = 0x2;
+const EVENT_CACHE_REFERENCES: u64 = 0x3;
+const EVENT_CACHE_MISSES: u64 = 0x4;
+const EVENT_BRANCH_INSTRUCTIONS: u64 = 0x5;
+const EVENT_BRANCH_MISSES: u64 = 0x6;
+
+/// Type field of SBI hardware cache events.
+const EVENT_TYPE_CACHE: u64 = 0x1_0000;
+/// Mask of the type field.
+const EVENT_TYPE_MASK: u64 = 0xF_0000;
+
+/// SBI cache identifiers (bits 15:3 of a cache event).
+const CACHE_L1D: u64 = 0;
+const CACHE_L1I: u64 = 1;
+const CACHE_LL: u64 = 2;
+const CACHE_DTLB: u64 = 3;
+const CACHE_ITLB: u64 = 4;
+const CACHE_BPU: u64 = 5;
+
+/// Returns the current count of the statistic `event` selects, or `None` if unsupported.
+///
+/// # Arguments
+///
+/// * `stats` - Statistics to read.
+/// * `event` - An `mhpmevent` value.
+pub fn event_count(stats: &SimStats, event: u64) -> Option<u64> {
+    let count = match event {
+        EVENT_CPU_CYCLES => stats.cycles,
+        EVENT_INSTRUCTIONS => stats.instructions_retired,
+        EVENT_CACHE_REFERENCES => stats.dcache_hits + stats.dcache_misses,
+        EVENT_CACHE_MISSES => stats.dcache_misses,
+        EVENT_BRANCH_INSTRUCTIONS => stats.inst_branch,
+        EVENT_BRANCH_MISSES => stats.branch_mispredictions,
+        _ if event & EVENT_TYPE_MASK == EVENT_TYPE_CACHE => {
+            let (hits, misses) = match (event >> 3) & 0x1FFF {
+                CACHE_L1D => (stats.dcache_hits, stats.dcache_misses),
+                CACHE_L1I => (stats.icache_hits, stats.icache_misses),
+                CACHE_LL => (stats.l3_hits, stats.l3_misses),
+                CACHE_DTLB => (stats.dtlb_hits, stats.dtlb_misses),
+                CACHE_ITLB => (stats.itlb_hits, stats.itlb_misses),
+                CACHE_BPU => (
+                    stats
+                        .branch_predictions
+                        .saturating_sub(stats.branch_mispredictions),
+                    stats.branch_mispredictions,
+                ),
+                _ => return None,
+            };
+            if event & 1 == 0 {
+                hits + misses
+            } else {
+                misses
+            }
+        }
+        _ => return None,
+    };
+    Some(count)
+}
+
+impl Cpu {
+    /// Reads `mhpmcounter<3 + idx>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Counter index relative to `mhpmcounter3` (0–28).
+    pub(crate) fn hpm_counter(&self, idx: usize) -> u64 {
+        let event = self.csrs.mhpmevent[idx];
+        event_count(&self.stats, event).map_or(self.csrs.mhpm_offset[idx], |count| {
+            count.wrapping_sub(self.csrs.mhpm_offset[idx])
+        })
+    }
+
+    /// Writes `mhpmcounter<3 + idx>`; the counter continues from `val`.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Counter index relative to `mhpmcounter3` (0–28).
+    /// * `val` - New counter value.
+    pub(crate) fn set_hpm_counter(&mut self, idx: usize, val: u64) {
+        let event = self.csrs.mhpmevent[idx];
+        // A counter with no event holds its value in the offset.
+        self.csrs.mhpm_offset[idx] =
+            event_count(&self.stats, event).map_or(val, |count| count.wrapping_sub(val));
+    }
+
+    /// Writes `mhpmevent<3 + idx>`; the counter keeps its value and counts the new event.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Counter index relative to `mhpmcounter3` (0–28).
+    /// * `event` - New event selector.
+    pub(crate) fn set_hpm_event(&mut self, idx: usize, event: u64) {
+        let current = self.hpm_counter(idx);
+        self.csrs.mhpmevent[idx] = event;
+        self.set_hpm_counter(idx, current);
+    }
+}
@@ -3,13 +3,30 @@
 //! This module provides the interface between the CPU and the memory subsystem.
 //! It performs the following:
 //! 1. **Address Translation:** Interfaces with the MMU to convert virtual to physical addresses.
-//! 2. **Cache Simulation:** Models the behavior of L1, L2, and L3 caches during memory access.
-//! 3. **Pipeline Synchronization:** Handles the flushing of pending store operations to memory.
+//! 2. **Cache Simulation:** Models the behavior of L1, L2, and L3 caches during memory access,
+//!    including non-temporal (Zihintntl) accesses that do not allocate and misses served
+//!    from an L1 victim cache.
+//! 3. **Pipeline Synchronization:** Drains executed-but-unwritten stores to memory (fences, SATP writes).
 //! 4. **Latency Modeling:** Calculates timing penalties for cache hits, misses, and bus transit.
+//! 5. **Wrong-Path Pollution:** Replays squashed speculative loads into the data caches.
+//! 6. **Reservations:** Tracks the LR/SC reservation set and drops it when its L1-D line is evicted.
+//! 7. **Physical Memory Protection:** Faults physical accesses the PMP entries do not grant.
 
 use super::Cpu;
+use super::history::width_bytes;
 use crate::common::{AccessType, PhysAddr, TranslationResult, Trap, VirtAddr};
+use crate::core::pipeline::latches::ExMemEntry;
 use crate::core::pipeline::signals;
+use crate::core::units::mmu::satp_asid;
+use crate::isa::decode::decode;
+use crate::isa::rv64f::opcodes as f_opcodes;
+use crate::isa::rv64i::opcodes as i_opcodes;
+
+/// Bytes moved from main memory per cache line fill.
+const LINE_FILL_BYTES: usize = 64;
+
+/// Cycles to swap a line back into an L1 from its victim cache.
+const VICTIM_SWAP_CYCLES: u64 = 1;
 
 impl Cpu {
     /// Translates a virtual address to a physical address using the MMU.
@@ -18,26 +35,104 @@ impl Cpu {
     ///
     /// * `vaddr` - The virtual address to translate.
     /// * `access` - The type of memory access (Fetch/Read/Write).
+    /// * `size` - Bytes accessed from `vaddr`, all within one page; PMP checks all of them.
     ///
     /// # Returns
     ///
-    /// A `TranslationResult` containing the physical address or a trap if translation fails.
-    pub fn translate(&mut self, vaddr: VirtAddr, access: AccessType) -> TranslationResult {
-        if self.direct_mode {
-            let paddr = vaddr.val();
-            if !self.bus.bus.is_valid_address(paddr) {
-                let trap = match access {
-                    AccessType::Fetch => Trap::InstructionAccessFault(paddr),
-                    AccessType::Read => Trap::LoadAccessFault(paddr),
-                    AccessType::Write => Trap::StoreAccessFault(paddr),
-                };
-                return TranslationResult::fault(trap, 0);
-            }
-            return TranslationResult::success(PhysAddr::new(paddr), 0);
+    /// A `TranslationResult` containing the physical address, or a trap if translation
+    /// fails or the physical address is not backed by any device (access fault).
+    pub fn translate(
+        &mut self,
+        vaddr: VirtAddr,
+        access: AccessType,
+        size: u64,
+    ) -> TranslationResult {
+        let result = if self.direct_mode {
+            TranslationResult::success(PhysAddr::new(vaddr.val()), 0)
+        } else {
+            let result =
+                self.mmu
+                    .translate(vaddr, access, self.privilege, &self.csrs, &mut self.bus.bus);
+            self.stats.record_mmu(&self.mmu.stats);
+            result
+        };
+        self.check_physical(vaddr, access, size, result)
+    }
+
+    /// Translates a virtual address for a debugger, without side effects.
+    ///
+    /// Faults exactly as [`Cpu::translate`] would, but leaves the TLBs, MMU statistics,
+    /// and PTE A/D bits untouched and charges no cycles.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - The virtual address to translate.
+    /// * `access` - The type of memory access (Fetch/Read/Write).
+    /// * `size` - Bytes accessed from `vaddr`, all within one page.
+    pub fn probe(&mut self, vaddr: VirtAddr, access: AccessType, size: u64) -> TranslationResult {
+        let result = if self.direct_mode {
+            Ok(vaddr.val())
+        } else {
+            self.mmu
+                .probe(vaddr, access, self.privilege, &self.csrs, &mut self.bus.bus)
+        };
+        let result = match result {
+            Ok(paddr) => TranslationResult::success(PhysAddr::new(paddr), 0),
+            Err(trap) => TranslationResult::fault(trap, 0),
+        };
+        self.check_physical(vaddr, access, size, result)
+    }
+
+    /// Faults a successful translation whose physical address is unmapped or PMP-denied.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - The virtual address that was translated.
+    /// * `access` - The type of memory access.
+    /// * `size` - Bytes accessed from the translated address.
+    /// * `result` - The translation to check.
+    pub(crate) fn check_physical(
+        &self,
+        vaddr: VirtAddr,
+        access: AccessType,
+        size: u64,
+        mut result: TranslationResult,
+    ) -> TranslationResult {
+        // Unmapped physical memory faults instead of reading as zero, as do accesses
+        // the PMP entries deny.
+        if result.trap.is_none()
+            && (!self.bus.bus.is_valid_address(result.paddr.val())
+                || !self.pmp_permits(result.paddr.val(), size, access))
+        {
+            let va = vaddr.val();
+            result.trap = Some(match access {
+                AccessType::Fetch => Trap::InstructionAccessFault(va),
+                AccessType::Read => Trap::LoadAccessFault(va),
+                AccessType::Write => Trap::StoreAccessFault(va),
+            });
         }
+        result
+    }
 
-        self.mmu
-            .translate(vaddr, access, self.privilege, &self.csrs, &mut self.bus.bus)
+    /// Checks a physical access at the current privilege against the PMP entries.
+    ///
+    /// Every byte must pass (see [`Pmp::permits`](crate::core::units::mmu::pmp::Pmp::permits)).
+    /// With `memory.pmp_open_when_inactive` set, accesses pass until software enables
+    /// an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `paddr` - Physical address of the access.
+    /// * `size` - Number of bytes accessed.
+    /// * `access` - The type of memory access.
+    fn pmp_permits(&self, paddr: u64, size: u64, access: AccessType) -> bool {
+        self.csrs.pmp.permits(
+            paddr,
+            size,
+            access,
+            self.privilege,
+            self.mmu.pmp_open_when_inactive(),
+        )
     }
 
     /// Simulates a memory access through the cache hierarchy.
@@ -51,6 +146,29 @@ impl Cpu {
     ///
     /// The total latency penalty in cycles for the memory operation.
     pub fn simulate_memory_access(&mut self, addr: PhysAddr, access: AccessType) -> u64 {
+        self.simulate_memory_access_hinted(addr, access, 0)
+    }
+
+    /// Simulates a memory access whose innermost `ntl_levels` caches are non-temporal.
+    ///
+    /// Hinted levels are probed but neither allocate nor update replacement state
+    /// (see [`CacheSim::access_hinted`](crate::core::units::cache::CacheSim::access_hinted)).
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The physical address to access.
+    /// * `access` - The type of memory access.
+    /// * `ntl_levels` - Number of data-cache levels (L1, L2, L3) to treat as non-temporal.
+    ///
+    /// # Returns
+    ///
+    /// The total latency penalty in cycles for the memory operation.
+    pub fn simulate_memory_access_hinted(
+        &mut self,
+        addr: PhysAddr,
+        access: AccessType,
+        ntl_levels: u8,
+    ) -> u64 {
         let mut total_penalty = 0;
         let raw_addr = addr.val();
         let ram_latency = self.bus.mem_controller.access_latency(raw_addr);
@@ -58,6 +176,11 @@ impl Cpu {
         let is_inst = matches!(access, AccessType::Fetch);
         let is_write = matches!(access, AccessType::Write);
 
+        // Stores snoop the L1-I so modified instructions are refetched.
+        if is_write && self.icache_snoop && self.l1_i_cache.invalidate(raw_addr) {
+            self.stats.icache_snoop_invalidations += 1;
+        }
+
         let (l1_hit, l1_pen) = if is_inst {
             if self.l1_i_cache.enabled {
                 self.l1_i_cache.access(raw_addr, false, next_lat)
@@ -65,7 +188,8 @@ impl Cpu {
                 (false, 0)
             }
         } else if self.l1_d_cache.enabled {
-            self.l1_d_cache.access(raw_addr, is_write, next_lat)
+            self.l1_d_cache
+                .access_hinted(raw_addr, is_write, next_lat, ntl_levels >= 1)
         } else {
             (false, 0)
         };
@@ -77,28 +201,58 @@ impl Cpu {
                 return total_penalty;
             }
             self.stats.icache_misses += 1;
+            if self.l1_i_cache.take_victim_hit() {
+                self.stats.victim_hits += 1;
+                return total_penalty + VICTIM_SWAP_CYCLES;
+            }
         } else if !is_inst && self.l1_d_cache.enabled {
             if l1_hit {
                 self.stats.dcache_hits += 1;
                 return total_penalty;
             }
             self.stats.dcache_misses += 1;
+            if self.l1_d_cache.take_victim_hit() {
+                self.stats.victim_hits += 1;
+                return total_penalty + VICTIM_SWAP_CYCLES;
+            }
         }
 
-        if self.l2_cache.enabled {
-            total_penalty += self.l2_cache.latency;
-            let (l2_hit, l2_pen) = self.l2_cache.access(raw_addr, is_write, next_lat);
+        // A split L2 serves instruction-origin misses from its own half.
+        let (l2, l2_hits, l2_misses) = if is_inst && self.l2_split {
+            (
+                &mut self.l2_i_cache,
+                &mut self.stats.l2_i_hits,
+                &mut self.stats.l2_i_misses,
+            )
+        } else {
+            (
+                &mut self.l2_cache,
+                &mut self.stats.l2_hits,
+                &mut self.stats.l2_misses,
+            )
+        };
+        if l2.enabled {
+            total_penalty += l2.latency;
+            let (l2_hit, l2_pen) = l2.access_hinted(raw_addr, is_write, next_lat, ntl_levels >= 2);
             total_penalty += l2_pen;
             if l2_hit {
-                self.stats.l2_hits += 1;
+                *l2_hits += 1;
                 return total_penalty;
             }
-            self.stats.l2_misses += 1;
+            *l2_misses += 1;
         }
 
         if self.l3_cache.enabled {
+            let port_delay = self.l3_ports.request(self.stats.cycles);
+            if port_delay > 0 {
+                self.stats.l3_port_stall_cycles += port_delay;
+                self.stats.l3_port_stalls += 1;
+            }
+            total_penalty += port_delay;
             total_penalty += self.l3_cache.latency;
-            let (l3_hit, l3_pen) = self.l3_cache.access(raw_addr, is_write, next_lat);
+            let (l3_hit, l3_pen) =
+                self.l3_cache
+                    .access_hinted(raw_addr, is_write, next_lat, ntl_levels >= 3);
             total_penalty += l3_pen;
             if l3_hit {
                 self.stats.l3_hits += 1;
@@ -107,10 +261,122 @@ impl Cpu {
             self.stats.l3_misses += 1;
         }
 
-        total_penalty += self.bus.bus.calculate_transit_time(8);
-        total_penalty += ram_latency;
-        total_penalty += self.bus.bus.calculate_transit_time(64);
-        total_penalty
+        let queue_delay = self
+            .bus
+            .mem_bandwidth
+            .request(self.stats.cycles, LINE_FILL_BYTES as u64)
+            + self
+                .bus
+                .mem_controller
+                .queue_delay(raw_addr, self.stats.cycles);
+        if queue_delay > 0 {
+            self.stats.mem_queue_cycles += queue_delay;
+            self.stats.mem_queued_requests += 1;
+        }
+
+        let mem_cycles = queue_delay
+            + self.bus.bus.calculate_transit_time(8)
+            + ram_latency
+            + self.bus.bus.calculate_transit_time(LINE_FILL_BYTES);
+        self.stats.mem_accesses += 1;
+        self.stats.mem_access_cycles += mem_cycles;
+        total_penalty + mem_cycles
+    }
+
+    /// Replays a squashed wrong-path instruction against the data caches.
+    ///
+    /// Loads compute their address from the current register file and, if the
+    /// address translates without a page walk, allocate through L1-D, L2, and L3.
+    /// No latency is charged and hit/miss statistics are left untouched; only
+    /// `wrong_path_dcache_fills` records the pollution. Non-loads are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `inst` - Raw (expanded) encoding of the squashed instruction.
+    pub(crate) fn wrong_path_load(&mut self, inst: u32) {
+        let d = decode(inst);
+        if d.opcode != i_opcodes::OP_LOAD && d.opcode != f_opcodes::OP_LOAD_FP {
+            return;
+        }
+        let vaddr = self.regs.read(d.rs1).wrapping_add(d.imm as u64);
+        let Some(paddr) = self.wrong_path_paddr(vaddr) else {
+            return;
+        };
+        if paddr < self.mmio_base || !self.l1_d_cache.enabled {
+            return;
+        }
+        let (l1_hit, _) = self.l1_d_cache.access(paddr, false, 0);
+        if l1_hit {
+            return;
+        }
+        self.drop_reservation_on_eviction();
+        self.stats.wrong_path_dcache_fills += 1;
+        if self.l2_cache.access(paddr, false, 0).0 {
+            return;
+        }
+        let _ = self.l3_cache.access(paddr, false, 0);
+    }
+
+    /// Records an LR reservation on `paddr` and watches its L1-D line for eviction.
+    ///
+    /// # Arguments
+    ///
+    /// * `paddr` - Physical address of the load-reserved access.
+    pub(crate) fn reserve(&mut self, paddr: u64) {
+        self.load_reservation = Some(paddr);
+        self.l1_d_cache.watch_line(Some(paddr));
+    }
+
+    /// Returns whether `paddr` lies in the current reservation set.
+    ///
+    /// # Arguments
+    ///
+    /// * `paddr` - Physical address of an SC, AMO, or store.
+    pub(crate) fn reservation_covers(&self, paddr: u64) -> bool {
+        let granule = !(self.reservation_bytes - 1);
+        self.load_reservation
+            .is_some_and(|r| r & granule == paddr & granule)
+    }
+
+    /// Accounts for a store performed at `paddr`.
+    ///
+    /// A store into the reservation set breaks this hart's reservation; under SMP the
+    /// address is also queued so that the other harts drop theirs.
+    ///
+    /// # Arguments
+    ///
+    /// * `paddr` - Physical address of a store, a successful SC, or an AMO.
+    pub(crate) fn store_performed(&mut self, paddr: u64) {
+        if self.reservation_covers(paddr) {
+            self.load_reservation = None;
+        }
+        if let Some(stores) = self.remote_stores.as_mut() {
+            stores.push(paddr);
+        }
+    }
+
+    /// Drops the reservation if its line has been evicted from L1-D.
+    ///
+    /// Real harts tie the reservation to a cache line, so losing the line loses
+    /// the reservation and a later SC fails.
+    pub(crate) fn drop_reservation_on_eviction(&mut self) {
+        if self.l1_d_cache.take_watched_eviction() {
+            self.load_reservation = None;
+        }
+    }
+
+    /// Translates a wrong-path address without side effects.
+    ///
+    /// Uses only the data TLB (never a page walk, which could set A/D bits).
+    ///
+    /// # Returns
+    ///
+    /// The physical address, or `None` if it is not mapped or not cached in the TLB.
+    fn wrong_path_paddr(&self, vaddr: u64) -> Option<u64> {
+        if self.direct_mode {
+            return self.bus.bus.is_valid_address(vaddr).then_some(vaddr);
+        }
+        self.mmu.dtlb_paddr(vaddr, satp_asid(self.csrs.satp))
     }
 
     /// Flushes pending stores in the pipeline to memory.
@@ -120,36 +386,46 @@ impl Cpu {
     pub(crate) fn flush_pipeline_stores(&mut self) {
         // Take entries out so we can access other fields of self for translation.
         let mut entries = std::mem::take(&mut self.ex_mem.entries);
+        self.drain_stores(&mut entries);
+        self.ex_mem.entries = entries;
+    }
 
-        for entry in &mut entries {
+    /// Performs the stores in `entries` immediately and clears their `mem_write`.
+    ///
+    /// Used for stores that have executed but not yet reached the memory stage,
+    /// such as older entries in the bundle currently being executed. Write watches
+    /// see these stores just as they see stores performed by the memory stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Executed entries; non-stores are left untouched.
+    pub(crate) fn drain_stores(&mut self, entries: &mut [ExMemEntry]) {
+        for entry in entries {
             if entry.ctrl.mem_write {
                 let vaddr = entry.alu;
                 let src = entry.store_data;
                 let width = entry.ctrl.width;
 
-                // Translate virtual address to physical address
-                let paddr = if self.direct_mode {
-                    vaddr
-                } else {
-                    let result = self.mmu.translate(
-                        VirtAddr::new(vaddr),
-                        AccessType::Write,
-                        self.privilege,
-                        &self.csrs,
-                        &mut self.bus.bus,
-                    );
-                    if result.trap.is_some() {
-                        if self.trace {
-                            println!(
-                                "[Pipeline Flush] Translation failed for store to vaddr={:#x}, skipping",
-                                vaddr
-                            );
-                        }
-                        entry.ctrl.mem_write = false;
-                        continue;
+                let result = self.translate(
+                    VirtAddr::new(vaddr),
+                    AccessType::Write,
+                    u64::from(width_bytes(width)),
+                );
+                if result.trap.is_some() {
+                    if self.trace {
+                        println!(
+                            "[Pipeline Flush] Translation failed for store to vaddr={:#x}, skipping",
+                            vaddr
+                        );
                     }
-                    result.paddr.val()
-                };
+                    entry.ctrl.mem_write = false;
+                    continue;
+                }
+                let paddr = result.paddr.val();
+
+                if !self.write_watches.is_empty() {
+                    self.capture_write_watches(paddr, width_bytes(width));
+                }
 
                 if paddr >= self.ram_start && paddr < self.ram_end {
                     let offset = (paddr - self.ram_start) as usize;
@@ -190,6 +466,10 @@ impl Cpu {
                 }
 
                 entry.ctrl.mem_write = false;
+                self.store_performed(paddr);
+                if self.write_watches.has_pending() {
+                    self.fire_write_watches(entry.pc);
+                }
 
                 if self.trace {
                     println!(
@@ -199,7 +479,5 @@ impl Cpu {
                 }
             }
         }
-
-        self.ex_mem.entries = entries;
     }
 }
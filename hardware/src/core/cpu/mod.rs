@@ -5,22 +5,65 @@
 //! 1. **State Management:** Maintains registers, program counter, and privilege modes.
 //! 2. **Pipeline Control:** Manages latches and shadow buffers for five-stage execution.
 //! 3. **Memory Hierarchy:** Integrates MMU, TLBs, and multi-level cache simulations.
-//! 4. **System Integration:** Interfaces with the system bus, devices, and RAM.
+//! 4. **System Integration:** Interfaces with the system bus, devices, and RAM, with an
+//!    optional built-in SBI for supervisor-mode kernels.
+//! 5. **Debugging:** Optional per-cycle and per-commit hooks, bounded reverse-step history,
+//!    retire-order checks, a Spike-compatible commit log, data write watches, debugger
+//!    breakpoints and single-step, and a machine monitor for programs without a trap vector.
+//! 6. **Checkpointing:** Saving and restoring the full system state to a file.
+
+/// Saving and restoring system state to checkpoint files.
+pub mod checkpoint;
+
+/// Callbacks observing each committed instruction.
+pub mod commit_hook;
+
+/// Spike-compatible log of committed instructions.
+pub mod commit_log;
 
 /// Control and Status Register access and management.
 pub mod csr;
 
+/// Breakpoints and single-step control for an attached debugger.
+pub mod debug;
+
+/// Memory, register, and stepping accessors for library embedders.
+pub mod embed;
+
 /// Instruction execution orchestration and pipeline coordination.
 pub mod execution;
 
+/// Bounded undo history for reverse stepping.
+pub mod history;
+
+/// Hardware performance-monitoring counters backed by simulator statistics.
+pub mod hpm;
+
 /// Memory access handling and load/store operations.
 pub mod memory;
 
+/// Exception reports for bare-metal programs without a trap vector.
+pub mod monitor;
+
+/// Per-PC hotspot profiling of committed instructions and stalls.
+pub mod profile;
+
+/// Optional verification of commit order and retired-instruction counts.
+pub mod retire;
+
+/// Built-in SBI servicing supervisor-mode `ecall`s.
+pub mod sbi;
+
 /// Trap and exception handling logic.
 pub mod trap;
 
-use crate::common::RegisterFile;
-use crate::config::Config;
+/// Callbacks on stores to watched memory locations.
+pub mod watch;
+
+use crate::common::{RegisterFile, SimError, Trap};
+use crate::config::{
+    CacheConfig, Config, ConfigError, FpuConfig, MisalignedAccess, MisalignedPriority,
+};
 use crate::core::arch::csr::Csrs;
 use crate::core::arch::mode::PrivilegeMode;
 use crate::core::pipeline::latches::{
@@ -28,9 +71,29 @@ use crate::core::pipeline::latches::{
 };
 use crate::core::units::bru::BranchPredictorWrapper;
 use crate::core::units::cache::CacheSim;
+use crate::core::units::cache::mshr::MshrFile;
+use crate::core::units::cache::ports::CachePorts;
 use crate::core::units::mmu::Mmu;
+use crate::sim::semihosting::Semihost;
+use crate::sim::symbols::{SymbolTable, SymbolizedAddr};
 use crate::soc::System;
-use crate::stats::SimStats;
+use crate::stats::{CacheLatencies, SimStats};
+use commit_hook::CommitHook;
+use commit_log::CommitLogSink;
+use debug::RunControl;
+use history::UndoLog;
+use profile::Profiler;
+use retire::RetireCheck;
+use std::io::Write;
+use watch::WriteWatches;
+
+/// Callback invoked at the start of every [`Cpu::tick`] with the pre-cycle CPU state.
+///
+/// Must be `Send` because `Cpu` is shared with the Python bindings across threads.
+pub type CycleHook = Box<dyn FnMut(&Cpu) + Send>;
+
+/// Destination for the trap trace enabled by `general.trace_traps` or [`Cpu::set_trap_trace`].
+pub type TrapTraceSink = Box<dyn Write + Send>;
 
 /// Main CPU structure containing all processor state and components.
 ///
@@ -47,6 +110,8 @@ pub struct Cpu {
     pub privilege: PrivilegeMode,
     /// Load Reservation address (for LR/SC).
     pub load_reservation: Option<u64>,
+    /// Size of the aligned reservation set around `load_reservation` (power of two).
+    pub reservation_bytes: u64,
 
     /// System Bus and Devices.
     pub bus: System,
@@ -56,10 +121,20 @@ pub struct Cpu {
     pub l1_i_cache: CacheSim,
     /// L1 Data Cache.
     pub l1_d_cache: CacheSim,
-    /// L2 Unified Cache.
+    /// L2 Unified Cache (data half when the L2 is split).
     pub l2_cache: CacheSim,
+    /// L2 Instruction Cache (instruction half when the L2 is split).
+    pub l2_i_cache: CacheSim,
+    /// Route instruction-origin L2 traffic to `l2_i_cache`.
+    pub l2_split: bool,
+    /// Invalidate the matching L1-I line on every data store.
+    pub icache_snoop: bool,
     /// L3 Unified Cache.
     pub l3_cache: CacheSim,
+    /// Access ports in front of the L3; busy ports queue further L3 accesses.
+    pub l3_ports: CachePorts,
+    /// L1-D miss status holding registers; empty when the data cache blocks on a miss.
+    pub mshrs: MshrFile,
     /// Base address for MMIO (used to bypass cache).
     pub mmio_base: u64,
 
@@ -77,15 +152,62 @@ pub struct Cpu {
     pub branch_predictor: BranchPredictorWrapper,
     /// Pipeline width (superscalar degree).
     pub pipeline_width: usize,
+    /// Zba address generation instructions decode (otherwise they trap as illegal).
+    pub zba_enabled: bool,
+    /// Zbb bit-manipulation instructions decode (otherwise they trap as illegal).
+    pub zbb_enabled: bool,
+    /// Zbc carry-less multiply instructions decode (otherwise they trap as illegal).
+    pub zbc_enabled: bool,
+    /// Fusible instruction pairs share one fetch/decode slot.
+    pub fusion_enabled: bool,
+    /// Zihintntl hints mark the following memory access as non-temporal.
+    pub ntl_enabled: bool,
+    /// FP operations flush subnormal inputs and results to zero (FTZ/DAZ).
+    pub ftz_enabled: bool,
+    /// Cycles charged to multi-cycle FP operations.
+    pub fpu_latency: FpuConfig,
+    /// Cycles charged to integer divide and remainder operations.
+    pub div_latency: u64,
+    /// Scale the divide latency by the quotient width of its operands.
+    pub div_early_exit: bool,
+    /// Pending non-temporal hint: PC of the instruction it applies to and its level count.
+    pub ntl_pending: Option<(u64, u8)>,
+    /// Squashed wrong-path loads allocate into the data caches.
+    pub wrong_path_pollution: bool,
+    /// Whether address-misaligned exceptions outrank page and access faults.
+    pub misaligned_priority: MisalignedPriority,
+    /// Whether misaligned loads and stores are split into byte accesses or trap.
+    pub misaligned_access: MisalignedAccess,
+    /// PCs of fetched, not yet executed instructions whose fetch allocated an L1-I line.
+    pub fetch_fill_pcs: Vec<u64>,
 
     /// Enable instruction tracing.
     pub trace: bool,
+    /// Index of this hart, reported by `mhartid` and selecting its CLINT registers.
+    pub hart_id: usize,
+    /// Physical addresses stored to since the last drain, kept only when other harts
+    /// share memory so that their reservations can be broken.
+    pub(crate) remote_stores: Option<Vec<u64>>,
     /// Exit code if simulation finished.
     pub exit_code: Option<u64>,
+    /// Fatal error raised this cycle, returned by the next [`tick`](Self::tick).
+    pending_error: Option<SimError>,
+    /// Most recent trap taken, reported by [`step`](Self::step).
+    pub(crate) last_trap: Option<Trap>,
     /// Performance statistics.
     pub stats: SimStats,
     /// Direct mode (no translation, flat memory).
     pub direct_mode: bool,
+    /// In direct mode, a committed WFI ends the run as a fatal diagnostic instead of sleeping.
+    pub trap_on_wfi: bool,
+    /// Report and halt on an exception taken to M-mode while `mtvec` is zero.
+    pub monitor_mode: bool,
+    /// Service S-mode `ecall`s with the built-in SBI instead of trapping to M-mode.
+    pub builtin_sbi: bool,
+    /// UART base address (console of the built-in SBI).
+    pub uart_base: u64,
+    /// Host stdio and heap serving `ecall`s in direct mode.
+    pub(crate) semihost: Semihost,
     /// Stall counter.
     pub stall_cycles: u64,
     /// ALU operation timer (for multi-cycle ops).
@@ -100,7 +222,7 @@ pub struct Cpu {
     pub wfi_waiting: bool,
     /// PC when WFI was entered.
     pub wfi_pc: u64,
-    /// Interrupt inhibit flag (for one cycle after CSR write).
+    /// Interrupt inhibit flag (for one cycle after a CSR write or a performed device load).
     pub interrupt_inhibit_one_cycle: bool,
 
     /// Raw pointer to the start of simulated RAM.
@@ -135,11 +257,47 @@ pub struct Cpu {
     pub pc_trace: Vec<(u64, u32)>,
     /// Last invalid PC we printed debug for (avoid duplicate dumps).
     pub last_invalid_pc_debug: Option<u64>,
+
+    /// Optional embedder callback run at the top of each tick.
+    cycle_hook: Option<CycleHook>,
+
+    /// Optional sink receiving one line per taken trap.
+    trap_trace: Option<TrapTraceSink>,
+
+    /// Optional sink receiving one line per committed instruction.
+    pub(crate) commit_log: Option<CommitLogSink>,
+
+    /// Optional embedder callback run for each committed instruction.
+    pub(crate) commit_hook: Option<CommitHook>,
+
+    /// Committed-instruction history used by [`step_back`](Self::step_back).
+    pub undo: UndoLog,
+
+    /// Commit-order checker enabled by `general.verify_retire_order`.
+    pub retire_check: RetireCheck,
+
+    /// Per-PC hotspot profile, present only when `general.profile` is set.
+    pub(crate) profiler: Option<Profiler>,
+
+    /// Symbols used to annotate PCs in traces and fatal reports (empty if none loaded).
+    pub symbols: SymbolTable,
+
+    /// Callbacks installed by [`watch_write`](Self::watch_write).
+    pub(crate) write_watches: WriteWatches,
+
+    /// Breakpoints and fetch limits installed by [`set_breakpoint`](Self::set_breakpoint)
+    /// and [`resume`](Self::resume).
+    pub(crate) run_control: RunControl,
 }
 
 /// Maximum number of (pc, inst) entries kept for invalid-PC debug trace.
 pub const PC_TRACE_MAX: usize = 32;
 
+/// Builds one cache level, naming the level in the error as [`Config::validate`] does.
+fn build_cache(name: &str, level: &CacheConfig) -> Result<CacheSim, ConfigError> {
+    CacheSim::try_new(level).map_err(|e| ConfigError::Invalid(vec![format!("cache.{name}: {e}")]))
+}
+
 unsafe impl Send for Cpu {}
 unsafe impl Sync for Cpu {}
 
@@ -153,11 +311,13 @@ impl Cpu {
     ///
     /// # Returns
     ///
-    /// A new `Cpu` instance initialized according to the provided configuration.
-    pub fn new(mut system: System, config: &Config) -> Self {
+    /// A new `Cpu` instance initialized according to the provided configuration, or
+    /// [`ConfigError::Invalid`] if an enabled cache has a degenerate geometry.
+    pub fn new(mut system: System, config: &Config) -> Result<Self, ConfigError> {
         use crate::core::arch::csr::{
-            MISA_DEFAULT_RV64IMAFDC, MISA_EXT_A, MISA_EXT_C, MISA_EXT_D, MISA_EXT_F, MISA_EXT_I,
-            MISA_EXT_M, MISA_EXT_S, MISA_EXT_U, MISA_XLEN_64, MSTATUS_DEFAULT_RV64,
+            COUNTEREN_MASK, MISA_DEFAULT_RV64IMAFDC, MISA_EXT_A, MISA_EXT_B, MISA_EXT_C,
+            MISA_EXT_D, MISA_EXT_F, MISA_EXT_I, MISA_EXT_M, MISA_EXT_S, MISA_EXT_U, MISA_XLEN_64,
+            MSTATUS_DEFAULT_RV64, MSTATUS_FS_INIT,
         };
         use crate::isa::abi;
 
@@ -174,16 +334,37 @@ impl Cpu {
             val |= MISA_EXT_M;
             val |= MISA_EXT_S;
             val |= MISA_EXT_U;
+            if config.pipeline.zba && config.pipeline.zbb {
+                val |= MISA_EXT_B;
+            }
             val
         };
 
-        let csrs = Csrs {
+        let mut csrs = Csrs {
             mstatus: MSTATUS_DEFAULT_RV64,
             misa: configured_misa,
             ..Default::default()
         };
-
+        // Without a kernel or firmware to enable the counters, expose them to the
+        // program as M-mode firmware such as OpenSBI would.
+        // The same firmware would also turn on the FPU (`mstatus.FS` = Initial).
+        if config.general.direct_mode {
+            csrs.mcounteren = COUNTEREN_MASK;
+            csrs.scounteren = COUNTEREN_MASK;
+            csrs.mstatus |= MSTATUS_FS_INIT;
+        } else if config.general.builtin_sbi {
+            csrs.mcounteren = COUNTEREN_MASK;
+            csrs.mstatus |= MSTATUS_FS_INIT;
+        }
         let bp = BranchPredictorWrapper::new(config);
+        let mut stats = SimStats::default();
+        stats.cache_latencies = CacheLatencies::from_config(&config.cache);
+
+        let mut mmu = Mmu::new(config.memory.tlb_size);
+        mmu.set_page_size(config.memory.page_size);
+        mmu.set_refill(config.memory.tlb_refill);
+        mmu.set_ad_update(config.memory.ad_update);
+        mmu.set_pmp_open_when_inactive(config.memory.pmp_open_when_inactive);
 
         let (ram_ptr, ram_start, ram_end) =
             system
@@ -192,44 +373,76 @@ impl Cpu {
                 .unwrap_or((std::ptr::null_mut(), 0, 0));
 
         let direct_mode = config.general.direct_mode;
+        let initial_sp = config
+            .general
+            .initial_sp
+            .unwrap_or(config.system.ram_base + 0x100_0000);
         let (privilege, regs) = if direct_mode {
-            let sp = config
-                .general
-                .initial_sp
-                .unwrap_or(config.system.ram_base + 0x100_0000);
             let mut r = RegisterFile::new();
-            r.write(abi::REG_SP, sp);
+            r.write(abi::REG_SP, initial_sp);
             (PrivilegeMode::User, r)
         } else {
             (PrivilegeMode::Machine, RegisterFile::new())
         };
 
-        Self {
+        let mut cpu = Self {
             regs,
             pc: config.general.start_pc,
             trace: config.general.trace_instructions,
             bus: system,
+            hart_id: 0,
+            remote_stores: None,
             exit_code: None,
+            pending_error: None,
+            last_trap: None,
             csrs,
             privilege,
             direct_mode,
+            trap_on_wfi: config.general.trap_on_wfi,
+            monitor_mode: config.general.monitor_mode,
+            builtin_sbi: config.general.builtin_sbi,
+            uart_base: config.system.uart_base,
+            semihost: Semihost::new(
+                initial_sp,
+                config.system.ram_base + config.memory.ram_size as u64,
+            ),
             mmio_base: config.system.ram_base,
             if_id: IfId::default(),
             id_ex: IdEx::default(),
             ex_mem: ExMem::default(),
             mem_wb: MemWb::default(),
             wb_latch: MemWb::default(),
-            stats: SimStats::default(),
+            stats,
             branch_predictor: bp,
-            l1_i_cache: CacheSim::new(&config.cache.l1_i),
-            l1_d_cache: CacheSim::new(&config.cache.l1_d),
-            l2_cache: CacheSim::new(&config.cache.l2),
-            l3_cache: CacheSim::new(&config.cache.l3),
+            l1_i_cache: build_cache("l1_i", &config.cache.l1_i)?,
+            l1_d_cache: build_cache("l1_d", &config.cache.l1_d)?,
+            l2_cache: build_cache("l2", &config.cache.l2)?,
+            l2_i_cache: build_cache("l2_i", &config.cache.l2_i)?,
+            l2_split: config.cache.l2_split,
+            icache_snoop: config.cache.icache_snoop,
+            l3_cache: build_cache("l3", &config.cache.l3)?,
+            l3_ports: CachePorts::new(config.cache.l3_ports, config.cache.l3_port_cycles),
+            mshrs: MshrFile::new(config.cache.l1_d.mshrs),
             stall_cycles: 0,
             alu_timer: 0,
-            mmu: Mmu::new(config.memory.tlb_size),
+            mmu,
             load_reservation: None,
+            reservation_bytes: config.memory.reservation_bytes.max(1).next_power_of_two(),
             pipeline_width: config.pipeline.width,
+            zba_enabled: config.pipeline.zba,
+            zbb_enabled: config.pipeline.zbb,
+            zbc_enabled: config.pipeline.zbc,
+            fusion_enabled: config.pipeline.macro_op_fusion,
+            ntl_enabled: config.pipeline.zihintntl,
+            ftz_enabled: config.pipeline.flush_subnormals,
+            fpu_latency: config.fpu,
+            div_latency: config.pipeline.div_latency,
+            div_early_exit: config.pipeline.div_early_exit,
+            ntl_pending: None,
+            wrong_path_pollution: config.cache.wrong_path_pollution,
+            misaligned_priority: config.memory.misaligned_priority,
+            misaligned_access: config.memory.misaligned_access,
+            fetch_fill_pcs: Vec::with_capacity(config.pipeline.width),
             clint_divider: config.system.clint_divider,
             last_pc: 0,
             same_pc_count: 0,
@@ -245,7 +458,92 @@ impl Cpu {
             mem_wb_shadow: Vec::with_capacity(config.pipeline.width),
             pc_trace: Vec::with_capacity(PC_TRACE_MAX),
             last_invalid_pc_debug: None,
+            cycle_hook: None,
+            trap_trace: config
+                .general
+                .trace_traps
+                .then(|| Box::new(std::io::stderr()) as TrapTraceSink),
+            commit_log: None,
+            commit_hook: None,
+            undo: UndoLog::new(config.general.undo_depth),
+            retire_check: RetireCheck::new(config.general.verify_retire_order),
+            profiler: config.general.profile.then(|| Profiler::new(0)),
+            symbols: config
+                .general
+                .symbols
+                .as_deref()
+                .map(|path| {
+                    SymbolTable::load(path).unwrap_or_else(|e| {
+                        eprintln!("[Symbols] Cannot load {}", e);
+                        SymbolTable::default()
+                    })
+                })
+                .unwrap_or_default(),
+            write_watches: WriteWatches::default(),
+            run_control: RunControl::default(),
+        };
+        // Overrides go through the same WARL legalization as a `csrw`.
+        for (&addr, &val) in &config.csr_reset {
+            cpu.csr_write(addr, val);
         }
+        Ok(cpu)
+    }
+
+    /// Starts the host-time measurement window used for MIPS/kHz reporting.
+    ///
+    /// Call after loading binaries so setup time is excluded. If not called,
+    /// the window opens automatically on the first [`tick`](Self::tick).
+    pub fn start_measurement(&mut self) {
+        self.stats.start_measurement();
+    }
+
+    /// Stops the host-time measurement window; later reports use its frozen length.
+    pub fn stop_measurement(&mut self) {
+        self.stats.stop_measurement();
+    }
+
+    /// Installs a callback run at the top of every [`tick`](Self::tick).
+    ///
+    /// Replaces any previously installed hook. When no hook is set the check is a
+    /// single branch per cycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Callback receiving the CPU state before the cycle executes.
+    pub fn set_cycle_hook(&mut self, hook: CycleHook) {
+        self.cycle_hook = Some(hook);
+    }
+
+    /// Removes the cycle hook, returning it if one was installed.
+    pub fn clear_cycle_hook(&mut self) -> Option<CycleHook> {
+        self.cycle_hook.take()
+    }
+
+    /// Runs the installed cycle hook, if any.
+    ///
+    /// The hook is taken out for the call so it can borrow the CPU immutably.
+    pub(crate) fn run_cycle_hook(&mut self) {
+        if let Some(mut hook) = self.cycle_hook.take() {
+            hook(self);
+            self.cycle_hook = Some(hook);
+        }
+    }
+
+    /// Routes the trap trace to `sink`, replacing any previous destination.
+    ///
+    /// Each taken trap (exception or interrupt) produces one line with its cause,
+    /// `epc`, `tval`, target privilege, and handler address.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Writer receiving the trace (e.g. stderr or a file).
+    pub fn set_trap_trace(&mut self, sink: TrapTraceSink) {
+        self.trap_trace = Some(sink);
+    }
+
+    /// Stops trap tracing, returning the sink if one was installed.
+    pub fn clear_trap_trace(&mut self) -> Option<TrapTraceSink> {
+        self.trap_trace.take()
     }
 
     /// Retrieves the exit code if the simulation has finished.
@@ -257,9 +555,21 @@ impl Cpu {
         self.exit_code.take()
     }
 
+    /// Formats `pc` with its containing symbol, e.g. `0x80000010 <main+0x10>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Address to annotate; printed as plain hex when no symbol covers it.
+    pub fn symbolize(&self, pc: u64) -> SymbolizedAddr<'_> {
+        self.symbols.format(pc)
+    }
+
     /// Dumps the current CPU state (PC and registers) to stdout.
     pub fn dump_state(&self) {
         println!("PC = {:#018x}", self.pc);
+        if let Some((sym, off)) = self.symbols.lookup(self.pc) {
+            println!("     {}+{:#x}", sym.name, off);
+        }
         self.regs.dump();
     }
 }
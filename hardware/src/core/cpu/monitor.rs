@@ -0,0 +1,125 @@
+//! Machine Monitor.
+//!
+//! This module implements an optional safety net for bare-metal programs that never
+//! install a trap vector. When `general.monitor_mode` is set and an exception would
+//! be taken to machine mode while `mtvec` is zero, the CPU does not jump to address 0.
+//! Instead it:
+//! 1. **Reports:** Prints the cause, `mtval`, faulting PC (with symbol), the faulting
+//!    instruction and its disassembly, and every integer register by ABI name.
+//! 2. **Halts:** Ends the run with an [`SimError::UnhandledTrap`] from `Cpu::tick`.
+//!
+//! The monitor also applies in `general.direct_mode`, which never vectors: faults that
+//! are not semihosting calls or the exit convention get the same report before halting.
+
+use super::Cpu;
+use crate::common::{AccessType, SimError, Trap, VirtAddr};
+use crate::core::arch::csr;
+use crate::isa::disasm::disassemble;
+use crate::isa::rvc::expand::expand;
+use std::fmt::Write;
+
+/// ABI register names for x0–x31, in the order printed by the report.
+const ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// Registers printed per report line.
+const REGS_PER_LINE: usize = 4;
+
+impl Cpu {
+    /// Builds the monitor's report for an exception with no handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `cause` - The exception being reported.
+    /// * `code` - Its `mcause` exception code.
+    /// * `tval` - The value that would be written to `mtval`.
+    /// * `epc` - PC of the faulting instruction.
+    ///
+    /// # Returns
+    ///
+    /// A multi-line, human-readable report.
+    pub fn monitor_report(&mut self, cause: &Trap, code: u64, tval: u64, epc: u64) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "[monitor] Unhandled exception with no trap vector (mtvec = 0)"
+        );
+        let _ = writeln!(out, "  cause : {} (mcause {})", cause, code);
+        let _ = writeln!(out, "  mtval : {:#x}", tval);
+        let _ = writeln!(out, "  pc    : {}", self.symbolize(epc));
+        let _ = writeln!(out, "  priv  : {}", self.privilege);
+        match self.fetch_for_report(epc) {
+            Some((bits, 2)) => {
+                let _ = writeln!(
+                    out,
+                    "  inst  : {:#06x}  {}",
+                    bits,
+                    disassemble(expand(bits as u16))
+                );
+            }
+            Some((bits, _)) => {
+                let _ = writeln!(out, "  inst  : {:#010x}  {}", bits, disassemble(bits));
+            }
+            None => {
+                let _ = writeln!(out, "  inst  : <unreadable>");
+            }
+        }
+        for (row, names) in ABI_NAMES.chunks(REGS_PER_LINE).enumerate() {
+            out.push(' ');
+            for (i, name) in names.iter().enumerate() {
+                let reg = row * REGS_PER_LINE + i;
+                let _ = write!(out, " {:>4}={:#018x}", name, self.regs.read(reg));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Returns whether the monitor intercepts a trap instead of letting it be taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_interrupt` - Whether the trap is an interrupt; interrupts are never caught.
+    /// * `delegate_to_s` - Whether the trap is delegated to S-mode via `stvec`.
+    pub(crate) fn monitor_catches(&self, is_interrupt: bool, delegate_to_s: bool) -> bool {
+        self.monitor_mode
+            && !is_interrupt
+            && !delegate_to_s
+            && (self.csrs.mtvec & !csr::TVEC_MODE_MASK) == 0
+    }
+
+    /// Reports an exception with no handler and halts the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `cause` - The exception being reported.
+    /// * `code` - Its `mcause` exception code.
+    /// * `tval` - The value that would be written to `mtval`.
+    /// * `epc` - PC of the faulting instruction.
+    pub(crate) fn halt_in_monitor(&mut self, cause: Trap, code: u64, tval: u64, epc: u64) {
+        eprint!("\n{}", self.monitor_report(&cause, code, tval, epc));
+        self.exit_code = Some(1);
+        self.pending_error = Some(SimError::UnhandledTrap(cause, epc));
+    }
+
+    /// Reads the instruction at `pc` without raising a trap.
+    ///
+    /// # Returns
+    ///
+    /// The instruction bits and their size in bytes, or `None` if `pc` cannot be read.
+    fn fetch_for_report(&mut self, pc: u64) -> Option<(u32, u64)> {
+        let result = self.translate(VirtAddr::new(pc), AccessType::Fetch, 2);
+        if result.trap.is_some() {
+            return None;
+        }
+        let paddr = result.paddr.val();
+        let low = self.bus.bus.read_u16(paddr);
+        if low & 0b11 != 0b11 {
+            return Some((u32::from(low), 2));
+        }
+        Some((self.bus.bus.read_u32(paddr), 4))
+    }
+}
@@ -0,0 +1,158 @@
+//! Per-PC Hotspot Profiling.
+//!
+//! This module implements the optional profiler enabled by `general.profile`. It provides:
+//! 1. **Instruction counts:** Each committed instruction increments a counter keyed by its PC.
+//! 2. **Stall attribution:** Cycles in which nothing committed are charged to the oldest
+//!    instruction of the next committing bundle, the one the commit stream waited on.
+//! 3. **Reports:** The top PCs by either measure, symbolized when a symbol file is loaded.
+//!
+//! A disabled profiler is never allocated, so the writeback stage pays one `Option` check.
+
+use std::collections::HashMap;
+
+use super::Cpu;
+use crate::core::pipeline::latches::MemWbEntry;
+
+/// Number of PCs listed per ranking by [`Cpu::print_profile`] in the CLI.
+pub const PROFILE_TOP_N: usize = 10;
+
+/// Counters of one static instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PcProfile {
+    /// Times the instruction committed.
+    pub instructions: u64,
+    /// Cycles the commit stream waited on the instruction before it committed.
+    pub stall_cycles: u64,
+}
+
+/// Per-PC commit and stall counters.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    pcs: HashMap<u64, PcProfile>,
+    /// Cycle of the previous writeback that committed an instruction.
+    last_commit_cycle: u64,
+}
+
+impl Profiler {
+    /// Creates an empty profile starting at `cycle`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle` - Current simulated cycle; stalls are measured from here.
+    pub fn new(cycle: u64) -> Self {
+        Self {
+            pcs: HashMap::new(),
+            last_commit_cycle: cycle,
+        }
+    }
+
+    /// Records the instructions committed by one writeback.
+    ///
+    /// NOPs and zero padding are skipped, as they are by `instructions_retired`.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed` - Entries that committed this cycle, oldest first.
+    /// * `cycle` - Current simulated cycle.
+    pub(crate) fn record(&mut self, committed: &[MemWbEntry], cycle: u64) {
+        let mut retiring = committed
+            .iter()
+            .filter(|wb| wb.inst != 0 && wb.inst != 0x13);
+        let Some(oldest) = retiring.next() else {
+            return;
+        };
+        let waited = cycle
+            .saturating_sub(self.last_commit_cycle)
+            .saturating_sub(1);
+        self.last_commit_cycle = cycle;
+
+        let entry = self.pcs.entry(oldest.pc).or_default();
+        entry.instructions += 1;
+        entry.stall_cycles += waited;
+        for wb in retiring {
+            self.pcs.entry(wb.pc).or_default().instructions += 1;
+        }
+    }
+
+    /// Returns the counters of `pc`, or `None` if it never committed.
+    pub fn get(&self, pc: u64) -> Option<PcProfile> {
+        self.pcs.get(&pc).copied()
+    }
+
+    /// Returns the number of distinct PCs that committed.
+    pub fn len(&self) -> usize {
+        self.pcs.len()
+    }
+
+    /// Returns `true` if nothing has committed yet.
+    pub fn is_empty(&self) -> bool {
+        self.pcs.is_empty()
+    }
+
+    /// Returns the `n` PCs that committed most often, most frequent first.
+    pub fn top_by_instructions(&self, n: usize) -> Vec<(u64, PcProfile)> {
+        self.top(n, |p| p.instructions)
+    }
+
+    /// Returns the `n` PCs with the most stall cycles, largest first; PCs that never
+    /// stalled are omitted.
+    pub fn top_by_stalls(&self, n: usize) -> Vec<(u64, PcProfile)> {
+        self.top(n, |p| p.stall_cycles)
+    }
+
+    /// Ranks PCs by `key` (descending, ties by ascending PC), dropping zero keys.
+    fn top(&self, n: usize, key: impl Fn(&PcProfile) -> u64) -> Vec<(u64, PcProfile)> {
+        let mut ranked: Vec<_> = self
+            .pcs
+            .iter()
+            .filter(|(_, p)| key(p) > 0)
+            .map(|(&pc, &p)| (pc, p))
+            .collect();
+        ranked.sort_by(|a, b| key(&b.1).cmp(&key(&a.1)).then(a.0.cmp(&b.0)));
+        ranked.truncate(n);
+        ranked
+    }
+}
+
+impl Cpu {
+    /// Returns the hotspot profile, or `None` unless `general.profile` is set.
+    pub fn profile(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Prints the top `n` PCs by committed instructions and by stall cycles to stdout.
+    ///
+    /// Does nothing when profiling is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of PCs listed per ranking.
+    pub fn print_profile(&self, n: usize) {
+        let Some(profiler) = &self.profiler else {
+            return;
+        };
+        let instr = self.stats.instructions_retired.max(1) as f64;
+        let cycles = self.stats.cycles.max(1) as f64;
+        println!("\n==========================================================");
+        println!("HOTSPOTS ({} distinct PCs)", profiler.len());
+        println!("  by instructions:");
+        for (pc, p) in profiler.top_by_instructions(n) {
+            println!(
+                "    {:<40} {:>12} ({:.2}%)",
+                self.symbolize(pc).to_string(),
+                p.instructions,
+                100.0 * p.instructions as f64 / instr
+            );
+        }
+        println!("  by stall cycles:");
+        for (pc, p) in profiler.top_by_stalls(n) {
+            println!(
+                "    {:<40} {:>12} ({:.2}%)",
+                self.symbolize(pc).to_string(),
+                p.stall_cycles,
+                100.0 * p.stall_cycles as f64 / cycles
+            );
+        }
+        println!("==========================================================");
+    }
+}
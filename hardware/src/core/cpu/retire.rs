@@ -0,0 +1,236 @@
+//! Retire-Order Verification.
+//!
+//! This module implements an optional debug check of the commit stream. It provides:
+//! 1. **Program order:** Every committed PC must be the architectural successor of the
+//!    previous commit (fall-through, jump/branch target, xRET target, or trap handler).
+//! 2. **Skipped padding:** Gaps are tolerated only when they hold NOPs or zero padding,
+//!    which decode consumes without retiring.
+//! 3. **Retire count:** `instructions_retired` is reconciled against an independent
+//!    tally of committed instructions after every writeback.
+//!
+//! The first violation is latched and surfaced as an error from `Cpu::tick`.
+
+use super::Cpu;
+use crate::common::{AccessType, VirtAddr};
+use crate::core::pipeline::latches::MemWbEntry;
+use crate::isa::decode::decode;
+use crate::isa::rv64i::opcodes;
+
+/// Canonical NOP encoding (`addi x0, x0, 0`); dropped by decode without retiring.
+const NOP: u32 = 0x0000_0013;
+
+/// Compressed NOP encoding (`c.nop`); expands to [`NOP`].
+const C_NOP: u16 = 0x0001;
+
+/// Longest run of skipped padding accepted between two commits, in bytes.
+const MAX_SKIP_BYTES: u64 = 64;
+
+/// Next PC(s) the commit stream may legally continue at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Successor {
+    /// No constraint (check just enabled, or state changed outside the pipeline).
+    Any,
+    /// Exactly one legal next PC.
+    One(u64),
+    /// A conditional branch: fall-through or taken target.
+    Either(u64, u64),
+}
+
+/// Commit-stream checker enabled by `general.verify_retire_order`.
+///
+/// A disabled checker records nothing and never reports a violation.
+#[derive(Debug)]
+pub struct RetireCheck {
+    enabled: bool,
+    expected: Successor,
+    committed: u64,
+    retired_base: u64,
+    violation: Option<String>,
+}
+
+impl Default for RetireCheck {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl RetireCheck {
+    /// Creates a checker.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether commits are verified.
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            expected: Successor::Any,
+            committed: 0,
+            retired_base: 0,
+            violation: None,
+        }
+    }
+
+    /// Returns `true` if commits are being verified.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Number of retiring instructions committed since the checker was enabled.
+    pub fn committed(&self) -> u64 {
+        self.committed
+    }
+
+    /// Takes the first recorded violation, if any.
+    pub fn take_violation(&mut self) -> Option<String> {
+        self.violation.take()
+    }
+
+    /// Forgets the expected next PC, e.g. after the PC is changed from outside the pipeline.
+    pub fn resync(&mut self) {
+        self.expected = Successor::Any;
+    }
+
+    /// Records a violation unless an earlier one is still pending.
+    fn fail(&mut self, msg: String) {
+        if self.violation.is_none() {
+            self.violation = Some(msg);
+        }
+    }
+}
+
+/// Returns the legal successor(s) of a committed instruction.
+fn successor_of(wb: &MemWbEntry) -> Successor {
+    let fallthrough = wb.pc.wrapping_add(wb.inst_size);
+    if wb.ctrl.is_mret || wb.ctrl.is_sret {
+        // Execute records the xRET target in `alu`.
+        return Successor::One(wb.alu);
+    }
+    if wb.ctrl.jump {
+        if wb.inst & 0x7F == opcodes::OP_JALR {
+            return Successor::One(wb.alu & !1);
+        }
+        return Successor::One(wb.pc.wrapping_add(decode(wb.inst).imm as u64));
+    }
+    if wb.ctrl.branch {
+        return Successor::Either(fallthrough, wb.pc.wrapping_add(decode(wb.inst).imm as u64));
+    }
+    Successor::One(fallthrough)
+}
+
+impl Cpu {
+    /// Enables or disables retire-order verification at runtime.
+    ///
+    /// Enabling starts a fresh tally from the current `instructions_retired`.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to verify subsequent commits.
+    pub fn set_retire_check(&mut self, enabled: bool) {
+        self.retire_check = RetireCheck::new(enabled);
+        self.retire_check.retired_base = self.stats.instructions_retired;
+    }
+
+    /// Verifies the instructions committed by the current writeback.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed` - Entries that committed this cycle, oldest first.
+    pub(crate) fn verify_commits(&mut self, committed: &[MemWbEntry]) {
+        for wb in committed {
+            self.verify_next_pc(wb.pc, "commit");
+            if wb.inst != 0 && wb.inst != NOP {
+                self.retire_check.committed += 1;
+            }
+            self.retire_check.expected = successor_of(wb);
+        }
+
+        let retired = self
+            .stats
+            .instructions_retired
+            .wrapping_sub(self.retire_check.retired_base);
+        if retired != self.retire_check.committed {
+            let msg = format!(
+                "retire count mismatch: instructions_retired advanced by {} but {} instructions committed",
+                retired, self.retire_check.committed
+            );
+            self.retire_check.fail(msg);
+            self.retire_check.retired_base = self
+                .stats
+                .instructions_retired
+                .wrapping_sub(self.retire_check.committed);
+        }
+    }
+
+    /// Verifies the `epc` of a trap taken in writeback and expects the handler next.
+    ///
+    /// Must be called after [`Cpu::trap`] has redirected `pc` to the handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `epc` - PC of the first uncommitted instruction the trap was taken on.
+    pub(crate) fn verify_trap(&mut self, epc: u64) {
+        self.verify_next_pc(epc, "trap epc");
+        self.retire_check.expected = Successor::One(self.pc);
+    }
+
+    /// Checks `pc` against the expected successor of the previous commit.
+    fn verify_next_pc(&mut self, pc: u64, what: &str) {
+        let ok = match self.retire_check.expected {
+            Successor::Any => true,
+            Successor::One(next) => self.reaches_over_padding(next, pc),
+            Successor::Either(a, b) => {
+                self.reaches_over_padding(a, pc) || self.reaches_over_padding(b, pc)
+            }
+        };
+        if !ok {
+            let msg = format!(
+                "out-of-order {} at pc={:#x} (cycle {}): expected {:?}",
+                what, pc, self.stats.cycles, self.retire_check.expected
+            );
+            self.retire_check.fail(msg);
+        }
+    }
+
+    /// Returns whether `to` is `from`, or follows it across NOP/zero padding only.
+    ///
+    /// Padding that cannot be read (e.g. untranslated) is given the benefit of the doubt.
+    fn reaches_over_padding(&mut self, from: u64, to: u64) -> bool {
+        if from == to {
+            return true;
+        }
+        if to < from || to - from > MAX_SKIP_BYTES {
+            return false;
+        }
+        let mut addr = from;
+        while addr < to {
+            let Some(half) = self.peek_u16(addr) else {
+                return true;
+            };
+            if half & 0b11 != 0b11 {
+                if half != C_NOP && half != 0 {
+                    return false;
+                }
+                addr += 2;
+                continue;
+            }
+            let Some(hi) = self.peek_u16(addr + 2) else {
+                return true;
+            };
+            if (u32::from(hi) << 16 | u32::from(half)) != NOP {
+                return false;
+            }
+            addr += 4;
+        }
+        addr == to
+    }
+
+    /// Reads a halfword of instruction memory without raising a trap.
+    fn peek_u16(&mut self, vaddr: u64) -> Option<u16> {
+        let result = self.translate(VirtAddr::new(vaddr), AccessType::Fetch, 2);
+        if result.trap.is_some() {
+            return None;
+        }
+        Some(self.bus.bus.read_u16(result.paddr.val()))
+    }
+}
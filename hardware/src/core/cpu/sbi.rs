@@ -0,0 +1,138 @@
+//! Built-in Supervisor Binary Interface.
+//!
+//! This module implements a minimal SBI in the simulator, so a supervisor-mode kernel can
+//! run without M-mode firmware. When `general.builtin_sbi` is set, every `ecall` from
+//! S-mode is serviced here instead of trapping to machine mode. Calls are dispatched on `a7`:
+//! 1. **Legacy (v0.1):** `a7` in `0x00..=0x0F` is the function; the result is returned in `a0`
+//!    only (set_timer, console putchar/getchar, shutdown).
+//! 2. **Extensions (v0.2+):** Any other `a7` is an extension id with the function in `a6`;
+//!    `a0` receives an `SBI_SUCCESS`/error code and `a1` the value (BASE and TIME).
+//!
+//! Unknown calls return `SBI_ERR_NOT_SUPPORTED`. Execution resumes after the `ecall`.
+
+use super::Cpu;
+use crate::core::arch::csr;
+use crate::isa::abi;
+
+/// Highest `a7` value handled as a legacy v0.1 function number.
+const LEGACY_MAX: u64 = 0x0F;
+
+/// Legacy `sbi_set_timer(stime_value)`.
+const LEGACY_SET_TIMER: u64 = 0x00;
+/// Legacy `sbi_console_putchar(ch)`.
+const LEGACY_CONSOLE_PUTCHAR: u64 = 0x01;
+/// Legacy `sbi_console_getchar()`.
+const LEGACY_CONSOLE_GETCHAR: u64 = 0x02;
+/// Legacy `sbi_shutdown()`.
+const LEGACY_SHUTDOWN: u64 = 0x08;
+
+/// Base extension id.
+pub const EXT_BASE: u64 = 0x10;
+/// Timer extension id (`"TIME"`).
+pub const EXT_TIME: u64 = 0x5449_4D45;
+
+/// BASE functions: spec version, implementation id/version, probe, machine ids.
+const BASE_GET_SPEC_VERSION: u64 = 0;
+const BASE_GET_IMPL_ID: u64 = 1;
+const BASE_GET_IMPL_VERSION: u64 = 2;
+const BASE_PROBE_EXTENSION: u64 = 3;
+const BASE_GET_MVENDORID: u64 = 4;
+const BASE_GET_MARCHID: u64 = 5;
+const BASE_GET_MIMPID: u64 = 6;
+
+/// TIME `sbi_set_timer(stime_value)`.
+const TIME_SET_TIMER: u64 = 0;
+
+/// Implemented specification version (major in bits 30:24, minor in 23:0): v0.2.
+const SPEC_VERSION: u64 = 2;
+/// Implementation id reported by BASE (outside the range assigned to known firmware).
+const IMPL_ID: u64 = 0x5253_494D;
+/// Implementation version reported by BASE.
+const IMPL_VERSION: u64 = 1;
+
+/// Call completed successfully.
+pub const SBI_SUCCESS: i64 = 0;
+/// Extension or function not implemented.
+pub const SBI_ERR_NOT_SUPPORTED: i64 = -2;
+
+/// UART Line Status Register offset and its data-ready bit, for console getchar.
+const UART_LSR: u64 = 5;
+const UART_LSR_DATA_READY: u8 = 0x01;
+
+impl Cpu {
+    /// Services an S-mode `ecall` and resumes at the following instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `epc` - PC of the `ecall`.
+    pub(crate) fn sbi_call(&mut self, epc: u64) {
+        let a7 = self.regs.read(abi::REG_A7);
+        if a7 <= LEGACY_MAX {
+            let ret = self.sbi_legacy(a7);
+            self.regs.write(abi::REG_A0, ret as u64);
+        } else {
+            let fid = self.regs.read(abi::REG_A6);
+            let (error, value) = match self.sbi_extension(a7, fid) {
+                Some(value) => (SBI_SUCCESS, value),
+                None => (SBI_ERR_NOT_SUPPORTED, 0),
+            };
+            self.regs.write(abi::REG_A0, error as u64);
+            self.regs.write(abi::REG_A1, value);
+        }
+        self.pc = epc.wrapping_add(4);
+    }
+
+    /// Runs a legacy v0.1 function, returning the value placed in `a0`.
+    fn sbi_legacy(&mut self, function: u64) -> i64 {
+        let a0 = self.regs.read(abi::REG_A0);
+        match function {
+            LEGACY_SET_TIMER => {
+                self.sbi_set_timer(a0);
+                0
+            }
+            LEGACY_CONSOLE_PUTCHAR => {
+                self.bus.bus.write_u8(self.uart_base, a0 as u8);
+                0
+            }
+            LEGACY_CONSOLE_GETCHAR => {
+                if self.bus.bus.read_u8(self.uart_base + UART_LSR) & UART_LSR_DATA_READY != 0 {
+                    i64::from(self.bus.bus.read_u8(self.uart_base))
+                } else {
+                    -1
+                }
+            }
+            LEGACY_SHUTDOWN => {
+                self.exit_code = Some(0);
+                0
+            }
+            _ => SBI_ERR_NOT_SUPPORTED,
+        }
+    }
+
+    /// Runs a v0.2 extension function, returning its value or `None` if unsupported.
+    fn sbi_extension(&mut self, ext: u64, fid: u64) -> Option<u64> {
+        let a0 = self.regs.read(abi::REG_A0);
+        match (ext, fid) {
+            (EXT_BASE, BASE_GET_SPEC_VERSION) => Some(SPEC_VERSION),
+            (EXT_BASE, BASE_GET_IMPL_ID) => Some(IMPL_ID),
+            (EXT_BASE, BASE_GET_IMPL_VERSION) => Some(IMPL_VERSION),
+            (EXT_BASE, BASE_PROBE_EXTENSION) => {
+                let known = a0 <= LEGACY_MAX || a0 == EXT_BASE || a0 == EXT_TIME;
+                Some(u64::from(known))
+            }
+            (EXT_BASE, BASE_GET_MVENDORID) => Some(self.csr_read(csr::MVENDORID)),
+            (EXT_BASE, BASE_GET_MARCHID) => Some(self.csr_read(csr::MARCHID)),
+            (EXT_BASE, BASE_GET_MIMPID) => Some(self.csr_read(csr::MIMPID)),
+            (EXT_TIME, TIME_SET_TIMER) => {
+                self.sbi_set_timer(a0);
+                Some(0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Programs the next supervisor timer interrupt (also clears a pending one).
+    fn sbi_set_timer(&mut self, stime_value: u64) {
+        self.csr_write(csr::STIMECMP, stime_value);
+    }
+}
@@ -6,15 +6,114 @@
 //! 2. **Delegation:** Handles the delegation of traps from Machine mode to Supervisor mode.
 //! 3. **Context Saving:** Updates CSRs (`mepc`, `mcause`, `mtval`, etc.) and modifies privilege state.
 //! 4. **Return Handling:** Implements `MRET` and `SRET` instructions for returning from trap handlers.
+//! 5. **Trap Tracing:** Logs every taken trap to an optional sink and counts traps per cause.
+//! 6. **Machine Monitor:** Optionally halts with a report instead of vectoring to a zero `mtvec`.
+//! 7. **Built-in SBI:** Optionally services S-mode `ecall`s without M-mode firmware.
 
 use super::Cpu;
-use crate::common::Trap;
-use crate::common::constants::CAUSE_INTERRUPT_BIT;
+use crate::common::constants::{CAUSE_INTERRUPT_BIT, UNEXPECTED_WFI_EXIT_CODE};
+use crate::common::{SimError, Trap};
 use crate::core::arch::csr;
 use crate::core::arch::mode::PrivilegeMode;
 use crate::isa::privileged::cause::{exception, interrupt};
+use std::io::Write;
+
+/// Returns the `xtval` value recorded for a trap (faulting address, `ebreak` PC, or instruction bits).
+fn trap_value(cause: &Trap) -> u64 {
+    match *cause {
+        Trap::InstructionAddressMisaligned(a)
+        | Trap::InstructionAccessFault(a)
+        | Trap::LoadAddressMisaligned(a)
+        | Trap::LoadAccessFault(a)
+        | Trap::StoreAddressMisaligned(a)
+        | Trap::StoreAccessFault(a)
+        | Trap::InstructionPageFault(a)
+        | Trap::LoadPageFault(a)
+        | Trap::StorePageFault(a)
+        | Trap::InstructionTlbMiss(a)
+        | Trap::LoadTlbMiss(a)
+        | Trap::StoreTlbMiss(a)
+        | Trap::Breakpoint(a) => a,
+        Trap::IllegalInstruction(i) => i as u64,
+        _ => 0,
+    }
+}
+
+/// Returns whether a trap is an interrupt, and its `xcause` code without the interrupt bit.
+fn trap_cause(cause: &Trap) -> (bool, u64) {
+    match *cause {
+        Trap::InstructionAddressMisaligned(_) => (false, exception::INSTRUCTION_ADDRESS_MISALIGNED),
+        Trap::InstructionAccessFault(_) => (false, exception::INSTRUCTION_ACCESS_FAULT),
+        Trap::IllegalInstruction(_) => (false, exception::ILLEGAL_INSTRUCTION),
+        Trap::Breakpoint(_) => (false, exception::BREAKPOINT),
+        Trap::LoadAddressMisaligned(_) => (false, exception::LOAD_ADDRESS_MISALIGNED),
+        Trap::LoadAccessFault(_) => (false, exception::LOAD_ACCESS_FAULT),
+        Trap::StoreAddressMisaligned(_) => (false, exception::STORE_ADDRESS_MISALIGNED),
+        Trap::StoreAccessFault(_) => (false, exception::STORE_ACCESS_FAULT),
+        Trap::EnvironmentCallFromUMode => (false, exception::ENVIRONMENT_CALL_FROM_U_MODE),
+        Trap::EnvironmentCallFromSMode => (false, exception::ENVIRONMENT_CALL_FROM_S_MODE),
+        Trap::EnvironmentCallFromMMode => (false, exception::ENVIRONMENT_CALL_FROM_M_MODE),
+        Trap::InstructionPageFault(_) => (false, exception::INSTRUCTION_PAGE_FAULT),
+        Trap::LoadPageFault(_) => (false, exception::LOAD_PAGE_FAULT),
+        Trap::StorePageFault(_) => (false, exception::STORE_PAGE_FAULT),
+        Trap::InstructionTlbMiss(_) => (false, exception::INSTRUCTION_TLB_MISS),
+        Trap::LoadTlbMiss(_) => (false, exception::LOAD_TLB_MISS),
+        Trap::StoreTlbMiss(_) => (false, exception::STORE_TLB_MISS),
+        Trap::UserSoftwareInterrupt => (true, interrupt::USER_SOFTWARE & !CAUSE_INTERRUPT_BIT),
+        Trap::SupervisorSoftwareInterrupt => {
+            (true, interrupt::SUPERVISOR_SOFTWARE & !CAUSE_INTERRUPT_BIT)
+        }
+        Trap::MachineSoftwareInterrupt => {
+            (true, interrupt::MACHINE_SOFTWARE & !CAUSE_INTERRUPT_BIT)
+        }
+        Trap::SupervisorTimerInterrupt => {
+            (true, interrupt::SUPERVISOR_TIMER & !CAUSE_INTERRUPT_BIT)
+        }
+        Trap::MachineTimerInterrupt => (true, interrupt::MACHINE_TIMER & !CAUSE_INTERRUPT_BIT),
+        Trap::UserExternalInterrupt => (true, interrupt::USER_EXTERNAL & !CAUSE_INTERRUPT_BIT),
+        Trap::SupervisorExternalInterrupt => {
+            (true, interrupt::SUPERVISOR_EXTERNAL & !CAUSE_INTERRUPT_BIT)
+        }
+        Trap::MachineExternalInterrupt => {
+            (true, interrupt::MACHINE_EXTERNAL & !CAUSE_INTERRUPT_BIT)
+        }
+        Trap::RequestedTrap(c) => (false, c),
+        Trap::DoubleFault(_) => (false, exception::HARDWARE_ERROR),
+    }
+}
+
+/// Returns the handler address selected by an `mtvec`/`stvec` value.
+///
+/// In vectored mode interrupts jump to `base + 4 * code`; exceptions, and every
+/// trap in direct mode, jump to `base`.
+fn trap_vector(tvec: u64, is_interrupt: bool, code: u64) -> u64 {
+    let base = tvec & !csr::TVEC_MODE_MASK;
+    if is_interrupt && (tvec & csr::TVEC_MODE_MASK) == csr::TVEC_MODE_VECTORED {
+        base + 4 * code
+    } else {
+        base
+    }
+}
 
 impl Cpu {
+    /// Ends a direct-mode run at a committed WFI when `trap_on_wfi` is set.
+    ///
+    /// Prints a diagnostic and the register state, then requests exit with
+    /// [`UNEXPECTED_WFI_EXIT_CODE`] so the run fails instead of idling forever.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Address of the WFI instruction.
+    pub(crate) fn halt_on_wfi(&mut self, pc: u64) {
+        eprintln!(
+            "\n[!] Unexpected WFI in direct mode at PC {}",
+            self.symbolize(pc)
+        );
+        self.dump_state();
+        self.wfi_waiting = false;
+        self.exit_code = Some(UNEXPECTED_WFI_EXIT_CODE);
+    }
+
     /// Handles a trap (exception or interrupt).
     ///
     /// # Arguments
@@ -23,20 +122,37 @@ impl Cpu {
     /// * `epc` - The Exception Program Counter (PC where the trap occurred).
     pub fn trap(&mut self, cause: Trap, epc: u64) {
         self.load_reservation = None;
+        let from_priv = self.privilege;
+
+        if self.builtin_sbi && matches!(cause, Trap::EnvironmentCallFromSMode) {
+            self.sbi_call(epc);
+            return;
+        }
 
         if self.direct_mode {
-            if !matches!(cause, Trap::EnvironmentCallFromUMode) {
-                if matches!(cause, Trap::IllegalInstruction(0)) {
-                    self.exit_code = Some(0);
-                    return;
-                }
-                eprintln!(
-                    "\n[!] Fatal trap in direct mode: {:?} at PC {:#x}",
-                    cause, epc
-                );
-                self.exit_code = Some(1);
+            if matches!(
+                cause,
+                Trap::EnvironmentCallFromUMode
+                    | Trap::EnvironmentCallFromSMode
+                    | Trap::EnvironmentCallFromMMode
+            ) {
+                self.semihost_call(epc);
+                return;
+            }
+            if matches!(cause, Trap::IllegalInstruction(0)) {
+                self.exit_code = Some(0);
                 return;
             }
+            // Direct mode never vectors, so the monitor reports the fault here.
+            let (is_interrupt, code) = trap_cause(&cause);
+            if self.monitor_catches(is_interrupt, false) {
+                let tval = trap_value(&cause);
+                self.halt_in_monitor(cause, code, tval, epc);
+                return;
+            }
+            self.exit_code = Some(1);
+            self.pending_error = Some(SimError::UnhandledTrap(cause, epc));
+            return;
         }
 
         let is_timer = matches!(
@@ -64,75 +180,26 @@ impl Cpu {
             }
         }
 
-        let (is_interrupt, code) = match cause {
-            Trap::InstructionAddressMisaligned(_) => {
-                (false, exception::INSTRUCTION_ADDRESS_MISALIGNED)
-            }
-            Trap::InstructionAccessFault(_) => (false, exception::INSTRUCTION_ACCESS_FAULT),
-            Trap::IllegalInstruction(_) => (false, exception::ILLEGAL_INSTRUCTION),
-            Trap::Breakpoint(_) => (false, exception::BREAKPOINT),
-            Trap::LoadAddressMisaligned(_) => (false, exception::LOAD_ADDRESS_MISALIGNED),
-            Trap::LoadAccessFault(_) => (false, exception::LOAD_ACCESS_FAULT),
-            Trap::StoreAddressMisaligned(_) => (false, exception::STORE_ADDRESS_MISALIGNED),
-            Trap::StoreAccessFault(_) => (false, exception::STORE_ACCESS_FAULT),
-            Trap::EnvironmentCallFromUMode => (false, exception::ENVIRONMENT_CALL_FROM_U_MODE),
-            Trap::EnvironmentCallFromSMode => (false, exception::ENVIRONMENT_CALL_FROM_S_MODE),
-            Trap::EnvironmentCallFromMMode => (false, exception::ENVIRONMENT_CALL_FROM_M_MODE),
-            Trap::InstructionPageFault(_) => (false, exception::INSTRUCTION_PAGE_FAULT),
-            Trap::LoadPageFault(_) => (false, exception::LOAD_PAGE_FAULT),
-            Trap::StorePageFault(_) => (false, exception::STORE_PAGE_FAULT),
-            Trap::UserSoftwareInterrupt => (true, interrupt::USER_SOFTWARE & !CAUSE_INTERRUPT_BIT),
-            Trap::SupervisorSoftwareInterrupt => {
-                (true, interrupt::SUPERVISOR_SOFTWARE & !CAUSE_INTERRUPT_BIT)
-            }
-            Trap::MachineSoftwareInterrupt => {
-                (true, interrupt::MACHINE_SOFTWARE & !CAUSE_INTERRUPT_BIT)
-            }
-            Trap::SupervisorTimerInterrupt => {
-                (true, interrupt::SUPERVISOR_TIMER & !CAUSE_INTERRUPT_BIT)
-            }
-            Trap::MachineTimerInterrupt => (true, interrupt::MACHINE_TIMER & !CAUSE_INTERRUPT_BIT),
-            Trap::UserExternalInterrupt => (true, interrupt::USER_EXTERNAL & !CAUSE_INTERRUPT_BIT),
-            Trap::SupervisorExternalInterrupt => {
-                (true, interrupt::SUPERVISOR_EXTERNAL & !CAUSE_INTERRUPT_BIT)
-            }
-            Trap::MachineExternalInterrupt => {
-                (true, interrupt::MACHINE_EXTERNAL & !CAUSE_INTERRUPT_BIT)
-            }
-            Trap::RequestedTrap(c) => (false, c),
-            Trap::DoubleFault(_) => (false, exception::HARDWARE_ERROR),
-        };
+        let (is_interrupt, code) = trap_cause(&cause);
 
         let deleg_mask = if is_interrupt {
             self.csrs.mideleg
         } else {
             self.csrs.medeleg
         };
-        let mut delegate_to_s =
+        // Only traps taken below M-mode can be delegated; `medeleg`/`mideleg`
+        // select S-mode per cause code, everything else goes to M-mode.
+        let delegate_to_s =
             (self.privilege <= PrivilegeMode::Supervisor) && ((deleg_mask >> code) & 1) != 0;
 
-        if self.privilege == PrivilegeMode::User
-            && !is_interrupt
-            && !delegate_to_s
-            && (self.csrs.stvec & !3) != 0
-        {
-            if !is_ecall {
-                eprintln!(
-                    "[TRAP DEBUG] User mode trap not delegated but STVEC is set. Forcing delegation. Cause={:?} Code={} MEDELEG={:#x} STVEC={:#x}",
-                    cause, code, self.csrs.medeleg, self.csrs.stvec
-                );
-            }
-            delegate_to_s = true;
+        if self.monitor_catches(is_interrupt, delegate_to_s) {
+            let tval = trap_value(&cause);
+            self.halt_in_monitor(cause, code, tval, epc);
+            return;
         }
 
         if delegate_to_s {
-            let stvec_base = self.csrs.stvec & !3;
-            let trap_handler_pc = stvec_base
-                + (if (self.csrs.stvec & 1) != 0 && is_interrupt {
-                    4 * code
-                } else {
-                    0
-                });
+            let trap_handler_pc = trap_vector(self.csrs.stvec, is_interrupt, code);
 
             if epc == trap_handler_pc {
                 let fault = Trap::DoubleFault(epc);
@@ -144,13 +211,7 @@ impl Cpu {
                 return;
             }
         } else {
-            let mtvec_base = self.csrs.mtvec & !3;
-            let trap_handler_pc = mtvec_base
-                + (if (self.csrs.mtvec & 1) != 0 && is_interrupt {
-                    4 * code
-                } else {
-                    0
-                });
+            let trap_handler_pc = trap_vector(self.csrs.mtvec, is_interrupt, code);
 
             if epc == trap_handler_pc {
                 let fault = Trap::DoubleFault(epc);
@@ -163,19 +224,7 @@ impl Cpu {
             }
         }
 
-        let tval = match cause {
-            Trap::InstructionAddressMisaligned(a)
-            | Trap::InstructionAccessFault(a)
-            | Trap::LoadAddressMisaligned(a)
-            | Trap::LoadAccessFault(a)
-            | Trap::StoreAddressMisaligned(a)
-            | Trap::StoreAccessFault(a)
-            | Trap::InstructionPageFault(a)
-            | Trap::LoadPageFault(a)
-            | Trap::StorePageFault(a) => a,
-            Trap::IllegalInstruction(i) => i as u64,
-            _ => 0,
-        };
+        let tval = trap_value(&cause);
 
         if delegate_to_s {
             self.csrs.scause = if is_interrupt {
@@ -238,13 +287,7 @@ impl Cpu {
             self.csrs.mstatus = (self.csrs.mstatus & !sstatus_mask) | (sstatus & sstatus_mask);
 
             self.privilege = PrivilegeMode::Supervisor;
-            let stvec_base = self.csrs.stvec & !3;
-            let trap_handler_pc = stvec_base
-                + (if (self.csrs.stvec & 1) != 0 && is_interrupt {
-                    4 * code
-                } else {
-                    0
-                });
+            let trap_handler_pc = trap_vector(self.csrs.stvec, is_interrupt, code);
 
             self.pc = trap_handler_pc;
         } else {
@@ -268,36 +311,77 @@ impl Cpu {
             self.csrs.mstatus = mstatus;
 
             self.privilege = PrivilegeMode::Machine;
-            let mtvec_base = self.csrs.mtvec & !3;
+            let mtvec_base = self.csrs.mtvec & !csr::TVEC_MODE_MASK;
 
             let target_pc = if mtvec_base == 0 {
                 eprintln!(
                     "[WARNING] Trap to machine mode but MTVEC is 0! This indicates missing trap handler setup."
                 );
-                let stvec_base = self.csrs.stvec & !3;
+                let stvec_base = self.csrs.stvec & !csr::TVEC_MODE_MASK;
                 if stvec_base != 0 {
                     stvec_base
                 } else {
                     mtvec_base
                 }
             } else {
-                mtvec_base
-                    + (if (self.csrs.mtvec & 1) != 0 && is_interrupt {
-                        4 * code
-                    } else {
-                        0
-                    })
+                trap_vector(self.csrs.mtvec, is_interrupt, code)
             };
             self.pc = target_pc;
         }
 
+        let cause_val = if is_interrupt {
+            CAUSE_INTERRUPT_BIT | code
+        } else {
+            code
+        };
+        self.log_trap(&cause, cause_val, epc, tval, from_priv);
+
         self.stats.traps_taken += 1;
+        self.last_trap = Some(cause.clone());
+        *self.stats.traps_by_cause.entry(cause.name()).or_insert(0) += 1;
         self.if_id = Default::default();
         self.id_ex = Default::default();
         self.ex_mem = Default::default();
         self.mem_wb = Default::default();
     }
 
+    /// Writes one trap trace line, if a trap trace sink is installed.
+    ///
+    /// Called after the handler state is set up, so `self.privilege` and
+    /// `self.pc` are the target privilege and handler address.
+    ///
+    /// # Arguments
+    ///
+    /// * `cause` - The trap being taken.
+    /// * `cause_val` - Value written to `mcause`/`scause`.
+    /// * `epc` - PC saved in `mepc`/`sepc`.
+    /// * `tval` - Value written to `mtval`/`stval`.
+    /// * `from_priv` - Privilege mode the trap was taken from.
+    fn log_trap(
+        &mut self,
+        cause: &Trap,
+        cause_val: u64,
+        epc: u64,
+        tval: u64,
+        from_priv: PrivilegeMode,
+    ) {
+        let Some(sink) = self.trap_trace.as_mut() else {
+            return;
+        };
+        let _ = writeln!(
+            sink,
+            "[trap] cycle={} cause={} code={:#x} epc={:#x} tval={:#x} priv={}->{} handler={:#x}",
+            self.stats.cycles,
+            cause.name(),
+            cause_val,
+            epc,
+            tval,
+            from_priv,
+            self.privilege,
+            self.pc
+        );
+    }
+
     /// Executes the `MRET` instruction (Return from Machine Mode).
     pub(crate) fn do_mret(&mut self) {
         self.pc = self.csrs.mepc & !1;
@@ -0,0 +1,167 @@
+//! Data Write Watches.
+//!
+//! This module lets an embedder observe every store to a chosen memory location. It provides:
+//! 1. **Registration:** `Cpu::watch_write` installs a callback on a physical byte range.
+//! 2. **Value capture:** The watched bytes are read before and after each overlapping store,
+//!    so partial-width stores report the whole watched value, not just the bytes written.
+//! 3. **Delivery:** Callbacks run from the memory stage, right after the store is performed,
+//!    with the PC of the writing instruction.
+//!
+//! Only RAM is watched; device stores are never captured (reading them could have side effects).
+
+use super::Cpu;
+
+/// Callback invoked with each store to a watched location.
+///
+/// Must be `Send` because `Cpu` is shared with the Python bindings across threads.
+pub type WriteWatchFn = Box<dyn FnMut(WriteEvent) + Send>;
+
+/// A store that touched a watched location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteEvent {
+    /// Physical address of the watched location.
+    pub addr: u64,
+    /// PC of the store or AMO that wrote it.
+    pub pc: u64,
+    /// Watched value before the store (little-endian, zero-extended).
+    pub old: u64,
+    /// Watched value after the store (little-endian, zero-extended).
+    pub new: u64,
+}
+
+/// One installed watch.
+struct WriteWatch {
+    id: usize,
+    addr: u64,
+    size: u8,
+    callback: WriteWatchFn,
+}
+
+impl WriteWatch {
+    /// Returns `true` if a `size`-byte store at `addr` writes any watched byte.
+    fn overlaps(&self, addr: u64, size: u8) -> bool {
+        addr < self.addr + u64::from(self.size) && self.addr < addr + u64::from(size)
+    }
+}
+
+/// Installed write watches and the old values captured for the store in flight.
+#[derive(Default)]
+pub struct WriteWatches {
+    watches: Vec<WriteWatch>,
+    next_id: usize,
+    pending: Vec<(usize, u64)>,
+}
+
+impl WriteWatches {
+    /// Returns `true` if no watch is installed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Returns `true` if old values were captured for a store not yet reported.
+    #[inline]
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Drops captured old values without reporting them (the store did not write).
+    pub(crate) fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+impl Cpu {
+    /// Installs a callback run whenever a store writes any byte of `[addr, addr + size)`.
+    ///
+    /// The callback receives the full watched value before and after the store, so a
+    /// byte store into a watched doubleword reports both doubleword values.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Physical address of the watched location (must lie in RAM).
+    /// * `size` - Width of the watched location in bytes (1 to 8).
+    /// * `callback` - Invoked with each [`WriteEvent`].
+    ///
+    /// # Returns
+    ///
+    /// An id for [`unwatch_write`](Self::unwatch_write).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between 1 and 8.
+    pub fn watch_write(&mut self, addr: u64, size: u8, callback: WriteWatchFn) -> usize {
+        assert!(
+            (1..=8).contains(&size),
+            "write watch size must be 1 to 8 bytes, got {}",
+            size
+        );
+        let id = self.write_watches.next_id;
+        self.write_watches.next_id += 1;
+        self.write_watches.watches.push(WriteWatch {
+            id,
+            addr,
+            size,
+            callback,
+        });
+        id
+    }
+
+    /// Removes a write watch.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id returned by [`watch_write`](Self::watch_write).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the watch was installed.
+    pub fn unwatch_write(&mut self, id: usize) -> bool {
+        let before = self.write_watches.watches.len();
+        self.write_watches.watches.retain(|w| w.id != id);
+        self.write_watches.watches.len() != before
+    }
+
+    /// Captures the old value of every watch overlapped by a store about to be performed.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Physical address of the store.
+    /// * `size` - Store width in bytes.
+    pub(crate) fn capture_write_watches(&mut self, addr: u64, size: u8) {
+        for i in 0..self.write_watches.watches.len() {
+            let watch = &self.write_watches.watches[i];
+            if watch.overlaps(addr, size) {
+                let (waddr, wsize) = (watch.addr, watch.size);
+                let old = self.read_watched(waddr, wsize);
+                self.write_watches.pending.push((i, old));
+            }
+        }
+    }
+
+    /// Reports the store captured by [`capture_write_watches`](Self::capture_write_watches).
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - PC of the store that was just performed.
+    pub(crate) fn fire_write_watches(&mut self, pc: u64) {
+        let pending = std::mem::take(&mut self.write_watches.pending);
+        for &(i, old) in &pending {
+            let (addr, size) = {
+                let watch = &self.write_watches.watches[i];
+                (watch.addr, watch.size)
+            };
+            let new = self.read_watched(addr, size);
+            (self.write_watches.watches[i].callback)(WriteEvent { addr, pc, old, new });
+        }
+        self.write_watches.pending = pending;
+        self.write_watches.pending.clear();
+    }
+
+    /// Reads a watched value byte by byte, so unaligned locations never fault.
+    fn read_watched(&mut self, addr: u64, size: u8) -> u64 {
+        (0..u64::from(size)).fold(0, |acc, i| {
+            acc | u64::from(self.bus.bus.read_u8(addr + i)) << (8 * i)
+        })
+    }
+}
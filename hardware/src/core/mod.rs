@@ -13,6 +13,9 @@ pub mod cpu;
 /// Instruction pipeline implementation (stages, latches, hazards, signals).
 pub mod pipeline;
 
+/// Several harts sharing one system.
+pub mod smp;
+
 /// Execution units (ALU, FPU, LSU, MMU, branch predictor, cache, prefetcher).
 pub mod units;
 
@@ -0,0 +1,91 @@
+//! Macro-Op Fusion.
+//!
+//! This module recognizes adjacent instruction pairs that real cores fuse into a
+//! single pipeline slot. It provides:
+//! 1. **Far Jumps:** `auipc rd` followed by `jalr _, imm(rd)` (PC-relative call/tail).
+//! 2. **Constant Building:** `lui`/`auipc rd` followed by `addi[w] rd, rd, imm`.
+//! 3. **Indexed Addressing:** `slli rd, rs, 1..=3` followed by `add rd, rd, rs2`.
+//! 4. **Compare-and-Branch:** `slt[i][u] rd` followed by `beq`/`bne rd, x0`.
+//!
+//! In every idiom the second instruction consumes the first's destination, so
+//! without fusion the pair would be split across bundles by the intra-bundle
+//! hazard check. Fetch places a fused pair in one slot and decode issues both
+//! together, with the value forwarded inside the bundle by execute.
+
+use crate::isa::decode::decode;
+use crate::isa::instruction::Decoded;
+use crate::isa::rv64i::{funct3, funct7, opcodes};
+
+/// Largest `slli` shift amount fused with a following `add` (scaled index).
+const MAX_INDEX_SHIFT: i64 = 3;
+
+/// Mask selecting the 6-bit shift amount of an RV64 `slli` immediate.
+const SHAMT_MASK: i64 = 0x3F;
+
+/// Returns whether `inst` can be the first half of a fused pair.
+///
+/// A cheap opcode filter used by fetch before looking at the next instruction.
+///
+/// # Arguments
+///
+/// * `inst` - Raw (expanded) encoding of the candidate first instruction.
+pub fn starts_pair(inst: u32) -> bool {
+    matches!(
+        inst & 0x7F,
+        opcodes::OP_AUIPC | opcodes::OP_LUI | opcodes::OP_IMM | opcodes::OP_REG
+    )
+}
+
+/// Returns whether `first` followed by `second` forms a fusible pair.
+///
+/// # Arguments
+///
+/// * `first` - Raw (expanded) encoding of the older instruction.
+/// * `second` - Raw (expanded) encoding of the instruction immediately after it.
+///
+/// # Returns
+///
+/// `true` if the pair matches one of the idioms listed in the module docs.
+pub fn is_fusible(first: u32, second: u32) -> bool {
+    if !starts_pair(first) {
+        return false;
+    }
+    let a = decode(first);
+    let b = decode(second);
+    if a.rd == 0 {
+        return false;
+    }
+
+    match (a.opcode, b.opcode) {
+        (opcodes::OP_AUIPC, opcodes::OP_JALR) => b.rs1 == a.rd,
+        (opcodes::OP_LUI | opcodes::OP_AUIPC, opcodes::OP_IMM) => {
+            b.funct3 == funct3::ADD_SUB && writes_back_in_place(&a, &b)
+        }
+        (opcodes::OP_LUI, opcodes::OP_IMM_32) => {
+            b.funct3 == funct3::ADD_SUB && writes_back_in_place(&a, &b)
+        }
+        (opcodes::OP_IMM, opcodes::OP_REG) => {
+            let shamt = a.imm & SHAMT_MASK;
+            a.funct3 == funct3::SLL
+                && (1..=MAX_INDEX_SHIFT).contains(&shamt)
+                && b.funct3 == funct3::ADD_SUB
+                && b.funct7 == funct7::DEFAULT
+                && b.rd == a.rd
+                && (b.rs1 == a.rd || b.rs2 == a.rd)
+        }
+        (opcodes::OP_IMM | opcodes::OP_REG, opcodes::OP_BRANCH) => {
+            let is_compare = matches!(a.funct3, funct3::SLT | funct3::SLTU)
+                && (a.opcode == opcodes::OP_IMM || a.funct7 == funct7::DEFAULT);
+            is_compare
+                && matches!(b.funct3, funct3::BEQ | funct3::BNE)
+                && b.rs1 == a.rd
+                && b.rs2 == 0
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether `second` reads and overwrites the register `first` wrote.
+fn writes_back_in_place(first: &Decoded, second: &Decoded) -> bool {
+    second.rd == first.rd && second.rs1 == first.rd
+}
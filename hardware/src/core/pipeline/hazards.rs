@@ -8,6 +8,7 @@
 
 use crate::core::pipeline::latches::{ExMem, ExMemEntry};
 use crate::core::pipeline::latches::{IdEx, IdExEntry, IfId, MemWb};
+use crate::core::units::cache::mshr::MshrFile;
 
 /// Checks if a pipeline stall is needed due to a load-use data hazard.
 ///
@@ -65,6 +66,47 @@ pub fn need_stall_load_use(id_ex: &IdEx, if_id: &IfId) -> bool {
     false
 }
 
+/// Returns how many cycles decode must wait for operands still being loaded.
+///
+/// With a non-blocking data cache, a load that misses leaves the memory stage
+/// before its data arrives. An instruction reading that register is held in
+/// decode, like a load-use hazard, until the miss completes. As in
+/// [`need_stall_load_use`], source fields are taken from the raw encoding and
+/// checked against both register files.
+///
+/// # Arguments
+///
+/// * `if_id` - The IF/ID pipeline latch containing instructions being decoded.
+/// * `mshrs` - The data cache MSHRs and their pending destination registers.
+/// * `now` - Current cycle.
+///
+/// # Returns
+///
+/// The number of cycles until every source operand in `if_id` is available.
+pub fn pending_operand_wait(if_id: &IfId, mshrs: &MshrFile, now: u64) -> u64 {
+    if_id
+        .entries
+        .iter()
+        .flat_map(|e| {
+            [
+                (e.inst >> 15) & 0x1f,
+                (e.inst >> 20) & 0x1f,
+                (e.inst >> 27) & 0x1f,
+            ]
+        })
+        .map(|reg| {
+            let reg = reg as usize;
+            let int_wait = if reg != 0 {
+                mshrs.operand_wait(reg, false, now)
+            } else {
+                0
+            };
+            int_wait.max(mshrs.operand_wait(reg, true, now))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 /// Forwards register values from later pipeline stages to resolve data hazards.
 ///
 /// This function implements the register forwarding (bypassing) logic. It prioritizes the
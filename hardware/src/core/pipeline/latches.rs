@@ -7,6 +7,7 @@
 
 use crate::common::error::Trap;
 use crate::core::pipeline::signals::ControlSignals;
+use crate::core::units::bru::ras::RasCheckpoint;
 
 /// Entry in the IF/ID pipeline latch (Fetch to Decode stage).
 ///
@@ -26,6 +27,8 @@ pub struct IfIdEntry {
     pub pred_target: u64,
     /// Trap that occurred during fetch, if any.
     pub trap: Option<Trap>,
+    /// Return address stack state before this instruction's speculative update.
+    pub ras_checkpoint: RasCheckpoint,
 }
 
 /// Entry in the ID/EX pipeline latch (Decode to Execute stage).
@@ -64,6 +67,8 @@ pub struct IdExEntry {
     pub pred_taken: bool,
     /// Predicted target address for branch/jump instructions.
     pub pred_target: u64,
+    /// Return address stack state before this instruction's speculative update.
+    pub ras_checkpoint: RasCheckpoint,
 }
 
 /// Entry in the EX/MEM pipeline latch (Execute to Memory stage).
@@ -87,6 +92,8 @@ pub struct ExMemEntry {
     pub ctrl: ControlSignals,
     /// Trap that occurred during execute, if any.
     pub trap: Option<Trap>,
+    /// Return address stack state before this instruction's speculative update.
+    pub ras_checkpoint: RasCheckpoint,
 }
 
 /// Entry in the MEM/WB pipeline latch (Memory to Writeback stage).
@@ -106,10 +113,15 @@ pub struct MemWbEntry {
     pub alu: u64,
     /// Data loaded from memory (for load instructions).
     pub load_data: u64,
+    /// Value written to memory, if the instruction performed a store (including
+    /// a successful SC or an AMO).
+    pub stored: Option<u64>,
     /// Control signals for the writeback stage.
     pub ctrl: ControlSignals,
     /// Trap that occurred during memory access, if any.
     pub trap: Option<Trap>,
+    /// Return address stack state before this instruction's speculative update.
+    pub ras_checkpoint: RasCheckpoint,
 }
 
 /// IF/ID pipeline latch (Fetch to Decode stage).
@@ -7,6 +7,10 @@
 //! 3. **Signals:** Control signals generated during instruction decoding.
 //! 4. **Stages:** Implementation of Fetch, Decode, Execute, Memory, and Writeback stages.
 //! 5. **Traits:** Common interfaces for pipeline components and stages.
+//! 6. **Fusion:** Detection of macro-op fusible instruction pairs.
+
+/// Macro-op fusion pair detection.
+pub mod fusion;
 
 /// Pipeline hazard detection and forwarding logic.
 pub mod hazards;
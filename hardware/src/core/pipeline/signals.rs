@@ -64,6 +64,66 @@ pub enum AluOp {
     /// Integer remainder (unsigned).
     Remu,
 
+    /// Carry-less multiply (low bits).
+    Clmul,
+
+    /// Carry-less multiply (high bits).
+    Clmulh,
+
+    /// Carry-less multiply (reversed, bits 126..63).
+    Clmulr,
+
+    /// Shift left by 1 and add (Zba).
+    Sh1Add,
+
+    /// Shift left by 2 and add (Zba).
+    Sh2Add,
+
+    /// Shift left by 3 and add (Zba).
+    Sh3Add,
+
+    /// Add zero-extended low word of `rs1` (Zba).
+    AddUw,
+
+    /// Shift left zero-extended low word of `rs1` (Zba).
+    SlliUw,
+
+    /// Count leading zeros (Zbb; CLZW when `is_rv32`).
+    Clz,
+
+    /// Count trailing zeros (Zbb; CTZW when `is_rv32`).
+    Ctz,
+
+    /// Population count (Zbb; CPOPW when `is_rv32`).
+    Cpop,
+
+    /// Signed minimum (Zbb).
+    Min,
+
+    /// Signed maximum (Zbb).
+    Max,
+
+    /// Unsigned minimum (Zbb).
+    Minu,
+
+    /// Unsigned maximum (Zbb).
+    Maxu,
+
+    /// Bitwise OR-combine within each byte (Zbb).
+    OrcB,
+
+    /// Byte-reverse the register (Zbb).
+    Rev8,
+
+    /// Sign-extend the low byte (Zbb).
+    SextB,
+
+    /// Sign-extend the low halfword (Zbb).
+    SextH,
+
+    /// Zero-extend the low halfword (Zbb).
+    ZextH,
+
     /// Floating-point addition.
     FAdd,
 
@@ -109,6 +169,18 @@ pub enum AluOp {
     /// Convert single-precision float to long (signed).
     FCvtSL,
 
+    /// Convert float to unsigned word (FCVT.WU.S / FCVT.WU.D).
+    FCvtWuS,
+
+    /// Convert float to unsigned long (FCVT.LU.S / FCVT.LU.D).
+    FCvtLuS,
+
+    /// Convert unsigned word to float (FCVT.S.WU / FCVT.D.WU).
+    FCvtSWu,
+
+    /// Convert unsigned long to float (FCVT.S.LU / FCVT.D.LU).
+    FCvtSLu,
+
     /// Convert single-precision to double-precision float.
     FCvtSD,
 
@@ -308,4 +380,15 @@ pub struct ControlSignals {
     pub atomic_op: AtomicOp,
     /// Instruction is `FENCE.I`.
     pub is_fence_i: bool,
+    /// Atomic has acquire ordering (`aq` bit): no later memory access may
+    /// be performed before it.
+    pub aq: bool,
+    /// Atomic has release ordering (`rl` bit): every earlier store must be
+    /// drained before it is performed.
+    pub rl: bool,
+    /// Second half of a macro-op fused pair; shares the previous instruction's slot.
+    pub fused: bool,
+    /// Cache levels (innermost first) this memory access should not allocate
+    /// into, set by a preceding Zihintntl hint; `0` for a normal access.
+    pub ntl_levels: u8,
 }
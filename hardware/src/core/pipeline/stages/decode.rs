@@ -5,9 +5,12 @@
 //! 2. **Hazard Detection:** Checks for intra-bundle dependencies (in superscalar configurations).
 //! 3. **Register Read:** Reads source operands (rs1, rs2, rs3) from the Register File.
 //! 4. **Control Generation:** Generates ALU, Memory, and CSR control signals for the Execute stage.
+//! 5. **Macro-Op Fusion:** Marks fusible pairs so the dependent half issues in the same bundle.
+//! 6. **Non-Temporal Hints:** Tags the memory access following a Zihintntl hint with its level count.
 
 use crate::common::error::Trap;
 use crate::core::Cpu;
+use crate::core::pipeline::fusion;
 use crate::core::pipeline::latches::IdExEntry;
 use crate::core::pipeline::signals::{
     AluOp, AtomicOp, ControlSignals, CsrOp, MemWidth, OpASrc, OpBSrc,
@@ -21,6 +24,10 @@ use crate::isa::rv64d::{funct7 as d_funct7, opcodes as d_opcodes};
 use crate::isa::rv64f::{funct3 as f_funct3, funct7 as f_funct7, opcodes as f_opcodes};
 use crate::isa::rv64i::{funct3 as i_funct3, funct7 as i_funct7, opcodes as i_opcodes};
 use crate::isa::rv64m::{funct3 as m_funct3, opcodes as m_opcodes};
+use crate::isa::zba::{funct3 as zba_funct3, opcodes as zba_opcodes};
+use crate::isa::zbb::{funct3 as zbb_funct3, opcodes as zbb_opcodes};
+use crate::isa::zbc::{funct3 as zbc_funct3, opcodes as zbc_opcodes};
+use crate::isa::zihintntl;
 
 /// ADDI x0, x0, 0 instruction encoding (canonical NOP).
 ///
@@ -74,6 +81,10 @@ pub fn decode_stage(cpu: &mut Cpu) {
 
     let mut consumed_count = 0;
     let mut bundle_writes: Vec<(usize, bool)> = Vec::with_capacity(cpu.pipeline_width);
+    let zba_enabled = cpu.zba_enabled;
+    let zbb_enabled = cpu.zbb_enabled;
+    let zbc_enabled = cpu.zbc_enabled;
+    let fusion_enabled = cpu.fusion_enabled;
 
     for if_entry in &if_entries {
         if let Some(trap) = &if_entry.trap {
@@ -82,6 +93,7 @@ pub fn decode_stage(cpu: &mut Cpu) {
                 inst: if_entry.inst,
                 inst_size: if_entry.inst_size,
                 trap: Some(trap.clone()),
+                ras_checkpoint: if_entry.ras_checkpoint,
                 ..Default::default()
             });
             consumed_count += 1;
@@ -159,6 +171,7 @@ pub fn decode_stage(cpu: &mut Cpu) {
                 i_opcodes::OP_IMM | i_opcodes::OP_IMM_32 => {
                     c.reg_write = true;
                     c.is_rv32 = d.opcode == i_opcodes::OP_IMM_32;
+                    let imm12 = d.raw >> 20;
                     c.alu = match d.funct3 {
                         i_funct3::ADD_SUB => AluOp::Add,
                         i_funct3::SLT => AluOp::Slt,
@@ -166,6 +179,41 @@ pub fn decode_stage(cpu: &mut Cpu) {
                         i_funct3::XOR => AluOp::Xor,
                         i_funct3::OR => AluOp::Or,
                         i_funct3::AND => AluOp::And,
+                        zba_funct3::SLLI_UW if c.is_rv32 && imm12 >> 6 == zba_opcodes::SLLI_UW => {
+                            if !zba_enabled {
+                                return Err(Trap::IllegalInstruction(inst));
+                            }
+                            // SLLI.UW lives under OP-IMM-32 but produces a full 64-bit result.
+                            c.is_rv32 = false;
+                            AluOp::SlliUw
+                        }
+                        zbb_funct3::COUNT_SEXT if d.funct7 == zbb_opcodes::COUNT_SEXT => {
+                            if !zbb_enabled {
+                                return Err(Trap::IllegalInstruction(inst));
+                            }
+                            match d.rs2 {
+                                zbb_opcodes::RS2_CLZ => AluOp::Clz,
+                                zbb_opcodes::RS2_CTZ => AluOp::Ctz,
+                                zbb_opcodes::RS2_CPOP => AluOp::Cpop,
+                                zbb_opcodes::RS2_SEXT_B if !c.is_rv32 => AluOp::SextB,
+                                zbb_opcodes::RS2_SEXT_H if !c.is_rv32 => AluOp::SextH,
+                                _ => return Err(Trap::IllegalInstruction(inst)),
+                            }
+                        }
+                        zbb_funct3::ORC_REV
+                            if !c.is_rv32
+                                && (imm12 == zbb_opcodes::ORC_B_IMM
+                                    || imm12 == zbb_opcodes::REV8_IMM) =>
+                        {
+                            if !zbb_enabled {
+                                return Err(Trap::IllegalInstruction(inst));
+                            }
+                            if imm12 == zbb_opcodes::ORC_B_IMM {
+                                AluOp::OrcB
+                            } else {
+                                AluOp::Rev8
+                            }
+                        }
                         i_funct3::SLL => AluOp::Sll,
                         i_funct3::SRL_SRA => {
                             if (d.funct7 & FUNCT7_ALT_BIT) != 0 {
@@ -194,6 +242,66 @@ pub fn decode_stage(cpu: &mut Cpu) {
                             m_funct3::REMU => AluOp::Remu,
                             _ => return Err(Trap::IllegalInstruction(inst)),
                         };
+                    } else if d.funct7 == zba_opcodes::SHADD
+                        && matches!(
+                            d.funct3,
+                            zba_funct3::SH1ADD | zba_funct3::SH2ADD | zba_funct3::SH3ADD
+                        )
+                    {
+                        if !zba_enabled || c.is_rv32 {
+                            return Err(Trap::IllegalInstruction(inst));
+                        }
+                        c.alu = match d.funct3 {
+                            zba_funct3::SH1ADD => AluOp::Sh1Add,
+                            zba_funct3::SH2ADD => AluOp::Sh2Add,
+                            _ => AluOp::Sh3Add,
+                        };
+                    } else if d.funct7 == zba_opcodes::ADD_UW
+                        && d.funct3 == zba_funct3::ADD_UW
+                        && c.is_rv32
+                    {
+                        if !zba_enabled {
+                            return Err(Trap::IllegalInstruction(inst));
+                        }
+                        // ADD.UW lives under OP-32 but produces a full 64-bit result.
+                        c.is_rv32 = false;
+                        c.alu = AluOp::AddUw;
+                    } else if d.funct7 == zbb_opcodes::MINMAX
+                        && matches!(
+                            d.funct3,
+                            zbb_funct3::MIN | zbb_funct3::MINU | zbb_funct3::MAX | zbb_funct3::MAXU
+                        )
+                    {
+                        if !zbb_enabled || c.is_rv32 {
+                            return Err(Trap::IllegalInstruction(inst));
+                        }
+                        c.alu = match d.funct3 {
+                            zbb_funct3::MIN => AluOp::Min,
+                            zbb_funct3::MINU => AluOp::Minu,
+                            zbb_funct3::MAX => AluOp::Max,
+                            _ => AluOp::Maxu,
+                        };
+                    } else if d.funct7 == zbb_opcodes::ZEXT_H
+                        && d.funct3 == zbb_funct3::ZEXT_H
+                        && d.rs2 == 0
+                        && c.is_rv32
+                    {
+                        if !zbb_enabled {
+                            return Err(Trap::IllegalInstruction(inst));
+                        }
+                        // ZEXT.H lives under OP-32 but produces a full 64-bit result.
+                        c.is_rv32 = false;
+                        c.alu = AluOp::ZextH;
+                    } else if d.funct7 == zbc_opcodes::ZBC_EXTENSION {
+                        if !zbc_enabled || c.is_rv32 {
+                            return Err(Trap::IllegalInstruction(inst));
+                        }
+                        c.alu = match d.funct3 {
+                            zbc_funct3::CLMUL => AluOp::Clmul,
+                            zbc_funct3::CLMULH => AluOp::Clmulh,
+                            zbc_funct3::CLMULR => AluOp::Clmulr,
+                            _ => return Err(Trap::IllegalInstruction(inst)),
+                        };
                     } else {
                         c.alu = match (d.funct3, d.funct7) {
                             (i_funct3::ADD_SUB, i_funct7::DEFAULT) => AluOp::Add,
@@ -218,6 +326,8 @@ pub fn decode_stage(cpu: &mut Cpu) {
                     };
 
                     let f5 = d.funct7 >> 2;
+                    c.aq = d.funct7 & 0b10 != 0;
+                    c.rl = d.funct7 & 0b01 != 0;
                     c.atomic_op = match f5 {
                         a_funct5::LR => AtomicOp::Lr,
                         a_funct5::SC => AtomicOp::Sc,
@@ -323,20 +433,24 @@ pub fn decode_stage(cpu: &mut Cpu) {
                             c.fp_reg_write = false;
                             c.reg_write = true;
                             c.rs1_fp = true;
-                            if d.rs2 == 0 || d.rs2 == 1 {
-                                AluOp::FCvtWS
-                            } else {
-                                AluOp::FCvtLS
+                            match d.rs2 {
+                                0 => AluOp::FCvtWS,
+                                1 => AluOp::FCvtWuS,
+                                2 => AluOp::FCvtLS,
+                                3 => AluOp::FCvtLuS,
+                                _ => return Err(Trap::IllegalInstruction(inst)),
                             }
                         }
                         f_funct7::FCVT_F_W | d_funct7::FCVT_D_W => {
                             c.rs1_fp = false;
                             c.fp_reg_write = true;
                             c.a_src = OpASrc::Reg1;
-                            if d.rs2 == 0 || d.rs2 == 1 {
-                                AluOp::FCvtSW
-                            } else {
-                                AluOp::FCvtSL
+                            match d.rs2 {
+                                0 => AluOp::FCvtSW,
+                                1 => AluOp::FCvtSWu,
+                                2 => AluOp::FCvtSL,
+                                3 => AluOp::FCvtSLu,
+                                _ => return Err(Trap::IllegalInstruction(inst)),
                             }
                         }
                         f_funct7::FCVT_DS => AluOp::FCvtDS,
@@ -409,25 +523,43 @@ pub fn decode_stage(cpu: &mut Cpu) {
             Ok(c)
         };
 
-        let (ctrl, trap) = match decode_result(&d) {
+        let (mut ctrl, trap) = match decode_result(&d) {
             Ok(c) => (c, None),
             Err(t) => (ControlSignals::default(), Some(t)),
         };
 
+        // The fused half consumes its partner's result, forwarded inside the bundle.
+        let fused_with = if fusion_enabled && trap.is_none() {
+            id_ex_entries
+                .last()
+                .filter(|prev| {
+                    prev.trap.is_none()
+                        && !prev.ctrl.fused
+                        && prev.pc.wrapping_add(prev.inst_size) == if_entry.pc
+                        && fusion::is_fusible(prev.inst, inst)
+                })
+                .map(|prev| (prev.rd, false))
+        } else {
+            None
+        };
+        ctrl.fused = fused_with.is_some();
+        let conflicts =
+            |reg: (usize, bool)| Some(reg) != fused_with && bundle_writes.contains(&reg);
+
         let mut hazard = false;
         if d.rs1 != 0 || ctrl.rs1_fp {
-            if bundle_writes.contains(&(d.rs1, ctrl.rs1_fp)) {
+            if conflicts((d.rs1, ctrl.rs1_fp)) {
                 hazard = true;
             }
         }
         if d.rs2 != 0 || ctrl.rs2_fp {
-            if bundle_writes.contains(&(d.rs2, ctrl.rs2_fp)) {
+            if conflicts((d.rs2, ctrl.rs2_fp)) {
                 hazard = true;
             }
         }
         let rs3_idx = inst.rs3();
         if ctrl.rs3_fp {
-            if bundle_writes.contains(&(rs3_idx, true)) {
+            if conflicts((rs3_idx, true)) {
                 hazard = true;
             }
         }
@@ -435,6 +567,19 @@ pub fn decode_stage(cpu: &mut Cpu) {
         if hazard {
             break;
         }
+        if ctrl.fused {
+            cpu.stats.fused_pairs += 1;
+        }
+
+        if cpu.ntl_enabled {
+            let hint = cpu.ntl_pending.take().filter(|&(pc, _)| pc == if_entry.pc);
+            if ctrl.mem_read || ctrl.mem_write {
+                ctrl.ntl_levels = hint.map_or(0, |(_, levels)| levels);
+            }
+            if let Some(levels) = zihintntl::hint_levels(inst) {
+                cpu.ntl_pending = Some((if_entry.pc.wrapping_add(if_entry.inst_size), levels));
+            }
+        }
 
         if ctrl.reg_write && d.rd != 0 {
             bundle_writes.push((d.rd, false));
@@ -475,6 +620,7 @@ pub fn decode_stage(cpu: &mut Cpu) {
             trap,
             pred_taken: if_entry.pred_taken,
             pred_target: if_entry.pred_target,
+            ras_checkpoint: if_entry.ras_checkpoint,
         });
 
         consumed_count += 1;
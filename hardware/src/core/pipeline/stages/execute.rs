@@ -8,13 +8,15 @@
 
 use crate::common::error::Trap;
 use crate::core::Cpu;
+use crate::core::arch::csr;
 use crate::core::pipeline::hazards;
-use crate::core::pipeline::latches::{ExMem, ExMemEntry, IfId};
-use crate::core::pipeline::signals::{AluOp, CsrOp, OpASrc, OpBSrc};
+use crate::core::pipeline::latches::{ExMem, ExMemEntry, IdExEntry, IfId};
+use crate::core::pipeline::signals::{AluOp, AtomicOp, CsrOp, OpASrc, OpBSrc};
 use crate::core::units::alu::Alu;
 use crate::core::units::bru::BranchPredictor;
+use crate::core::units::bru::btb::BranchType;
 use crate::core::units::fpu::Fpu;
-use crate::isa::abi;
+use crate::core::units::fpu::rounding_modes::RoundingMode;
 use crate::isa::privileged::opcodes as sys_ops;
 use crate::isa::rv64i::{funct3, opcodes};
 
@@ -41,10 +43,15 @@ pub fn execute_stage(cpu: &mut Cpu) {
 
     let mut ex_results = Vec::with_capacity(entries.len());
     let mut flush_remaining = false;
+    let mut wrong_path = false;
 
     for id in entries.drain(..) {
         if flush_remaining {
-            break;
+            if !wrong_path {
+                break;
+            }
+            squash_entry(cpu, id.pc, id.inst);
+            continue;
         }
 
         if let Some(trap) = id.trap.clone() {
@@ -60,6 +67,7 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 store_data: 0,
                 ctrl: id.ctrl,
                 trap: Some(trap),
+                ras_checkpoint: id.ras_checkpoint,
             });
             continue;
         }
@@ -68,6 +76,24 @@ pub fn execute_stage(cpu: &mut Cpu) {
             eprintln!("EX  pc={:#x}", id.pc);
         }
 
+        // Any instruction touching an FP register is illegal while `mstatus.FS` is Off.
+        let uses_fp = id.ctrl.fp_reg_write || id.ctrl.rs1_fp || id.ctrl.rs2_fp || id.ctrl.rs3_fp;
+        if uses_fp && cpu.fp_disabled() {
+            ex_results.push(ExMemEntry {
+                pc: id.pc,
+                inst: id.inst,
+                inst_size: id.inst_size,
+                rd: id.rd,
+                alu: 0,
+                store_data: 0,
+                ctrl: id.ctrl,
+                trap: Some(Trap::IllegalInstruction(id.inst)),
+                ras_checkpoint: id.ras_checkpoint,
+            });
+            flush_remaining = true;
+            continue;
+        }
+
         let (fwd_a, fwd_b, fwd_c) = hazards::forward_rs(
             &id,
             &cpu.ex_mem,
@@ -95,11 +121,16 @@ pub fn execute_stage(cpu: &mut Cpu) {
             if cpu.trace {
                 println!("EX  FENCE.I - Flushing Caches and Pipeline");
             }
+            // Prior stores must be visible to the refetch below: drain those still
+            // waiting in EX/MEM and any older stores from this bundle first.
+            cpu.flush_pipeline_stores();
+            cpu.drain_stores(&mut ex_results);
             cpu.l1_d_cache.flush();
             cpu.l1_i_cache.flush();
 
             cpu.if_id = IfId::default();
             cpu.pc = id.pc.wrapping_add(id.inst_size);
+            repair_ras(cpu, &id);
             flush_remaining = true;
 
             ex_results.push(ExMemEntry {
@@ -111,6 +142,7 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 store_data: 0,
                 ctrl: id.ctrl,
                 trap: None,
+                ras_checkpoint: id.ras_checkpoint,
             });
             continue;
         }
@@ -120,12 +152,36 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 cpu.do_mret();
                 flush_remaining = true;
                 cpu.if_id = IfId::default();
+                repair_ras(cpu, &id);
+                ex_results.push(ExMemEntry {
+                    pc: id.pc,
+                    inst: id.inst,
+                    inst_size: id.inst_size,
+                    rd: id.rd,
+                    alu: cpu.pc,
+                    store_data: 0,
+                    ctrl: id.ctrl,
+                    trap: None,
+                    ras_checkpoint: id.ras_checkpoint,
+                });
                 continue;
             }
             if id.ctrl.is_sret {
                 cpu.do_sret();
                 flush_remaining = true;
                 cpu.if_id = IfId::default();
+                repair_ras(cpu, &id);
+                ex_results.push(ExMemEntry {
+                    pc: id.pc,
+                    inst: id.inst,
+                    inst_size: id.inst_size,
+                    rd: id.rd,
+                    alu: cpu.pc,
+                    store_data: 0,
+                    ctrl: id.ctrl,
+                    trap: None,
+                    ras_checkpoint: id.ras_checkpoint,
+                });
                 continue;
             }
 
@@ -144,8 +200,8 @@ pub fn execute_stage(cpu: &mut Cpu) {
             // - Records `wfi_pc` as PC + instruction_size for resumption after interrupt
             // - Flushes the pipeline to prevent subsequent instructions from executing
             //
-            // The main simulation loop checks `wfi_waiting` and skips cycles until an
-            // interrupt arrives (checked via pending interrupt CSR bits).
+            // Once the older instructions commit, `Cpu::tick` idles the hart (counting
+            // `cycles_idle`) until an enabled interrupt is pending in `mip & mie`.
             if id.inst == sys_ops::WFI {
                 if cpu.trace {
                     eprintln!(
@@ -168,6 +224,7 @@ pub fn execute_stage(cpu: &mut Cpu) {
                         store_data: 0,
                         ctrl: id.ctrl,
                         trap: Some(Trap::IllegalInstruction(id.inst)),
+                        ras_checkpoint: id.ras_checkpoint,
                     });
                     flush_remaining = true;
                     continue;
@@ -177,6 +234,7 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 cpu.wfi_pc = id.pc.wrapping_add(id.inst_size);
 
                 cpu.if_id = IfId::default();
+                repair_ras(cpu, &id);
                 flush_remaining = true;
 
                 ex_results.push(ExMemEntry {
@@ -188,6 +246,7 @@ pub fn execute_stage(cpu: &mut Cpu) {
                     store_data: 0,
                     ctrl: id.ctrl,
                     trap: None,
+                    ras_checkpoint: id.ras_checkpoint,
                 });
                 continue;
             }
@@ -200,88 +259,26 @@ pub fn execute_stage(cpu: &mut Cpu) {
             // - Ensures all previous stores to the page table are visible to subsequent
             //   implicit memory accesses (page table walks)
             // - Flushes TLB entries that match the specified ASID and virtual address
-            //   (this implementation flushes all TLB entries for simplicity)
             //
             // This implementation:
             // 1. Flushes pending stores from the pipeline to ensure memory consistency
             // 2. Executes all in-flight stores immediately (early completion)
-            // 3. Flushes both instruction and data TLBs
+            // 3. Invalidates the selected instruction and data TLB entries: rs1 != x0
+            //    limits the fence to that address and rs2 != x0 to that ASID's
+            //    non-global entries; with both x0 the TLBs are flushed entirely
             // 4. Flushes both L1 instruction and data caches
-            //
-            // The aggressive flushing ensures correctness at the cost of performance,
-            // matching the conservative behavior expected for page table modifications.
             if (id.inst & 0xFE007FFF) == sys_ops::SFENCE_VMA {
                 if cpu.trace {
                     eprintln!("EX  SFENCE.VMA - Flushing TLBs");
                 }
 
-                cpu.flush_pipeline_stores();
+                let fence_vaddr = (id.rs1 != 0).then_some(fwd_a);
+                let fence_asid = (id.rs2 != 0).then_some((fwd_b & csr::SATP_ASID_MASK) as u16);
 
-                for entry in &mut ex_results {
-                    if entry.ctrl.mem_write {
-                        let vaddr = entry.alu;
-                        let result = cpu.translate(
-                            crate::common::VirtAddr::new(vaddr),
-                            crate::common::AccessType::Write,
-                        );
-                        if result.trap.is_some() {
-                            entry.ctrl.mem_write = false;
-                            continue;
-                        }
-                        let addr = result.paddr.val();
-                        let src = entry.store_data;
-                        let width = entry.ctrl.width;
-                        if addr >= cpu.ram_start && addr < cpu.ram_end {
-                            let offset = (addr - cpu.ram_start) as usize;
-                            // SAFETY: This write operation is safe because:
-                            // 1. `addr` is validated to be within RAM bounds (>= ram_start && < ram_end)
-                            // 2. `offset` is computed from validated bounds, ensuring valid memory access
-                            // 3. `ram_ptr` points to valid, mutable memory allocated during CPU construction
-                            // 4. `write_unaligned()` handles potential misalignment for multi-byte writes
-                            // 5. Each write size (1/2/4/8 bytes) is guaranteed not to overflow as offset < (ram_end - ram_start)
-                            // 6. Memory access has been validated by MMU/PMP checks prior to this point
-                            unsafe {
-                                match width {
-                                    crate::core::pipeline::signals::MemWidth::Byte => {
-                                        *cpu.ram_ptr.add(offset) = src as u8
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Half => {
-                                        (cpu.ram_ptr.add(offset) as *mut u16)
-                                            .write_unaligned(src as u16)
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Word => {
-                                        (cpu.ram_ptr.add(offset) as *mut u32)
-                                            .write_unaligned(src as u32)
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Double => {
-                                        (cpu.ram_ptr.add(offset) as *mut u64).write_unaligned(src)
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        } else {
-                            match width {
-                                crate::core::pipeline::signals::MemWidth::Byte => {
-                                    cpu.bus.bus.write_u8(addr, src as u8)
-                                }
-                                crate::core::pipeline::signals::MemWidth::Half => {
-                                    cpu.bus.bus.write_u16(addr, src as u16)
-                                }
-                                crate::core::pipeline::signals::MemWidth::Word => {
-                                    cpu.bus.bus.write_u32(addr, src as u32)
-                                }
-                                crate::core::pipeline::signals::MemWidth::Double => {
-                                    cpu.bus.bus.write_u64(addr, src)
-                                }
-                                _ => {}
-                            }
-                        }
-                        entry.ctrl.mem_write = false;
-                    }
-                }
+                cpu.flush_pipeline_stores();
+                cpu.drain_stores(&mut ex_results);
 
-                cpu.mmu.dtlb.flush();
-                cpu.mmu.itlb.flush();
+                cpu.mmu.sfence_vma(fence_vaddr, fence_asid);
                 cpu.l1_d_cache.flush();
                 cpu.l1_i_cache.flush();
 
@@ -294,57 +291,12 @@ pub fn execute_stage(cpu: &mut Cpu) {
                     store_data,
                     ctrl: id.ctrl,
                     trap: None,
+                    ras_checkpoint: id.ras_checkpoint,
                 });
                 continue;
             }
 
             if id.inst == sys_ops::ECALL {
-                let get_val = |reg: usize, cpu: &Cpu, current_results: &[ExMemEntry]| -> u64 {
-                    if reg == 0 {
-                        return 0;
-                    }
-                    for entry in current_results.iter().rev() {
-                        if entry.ctrl.reg_write && entry.rd == reg {
-                            return entry.alu;
-                        }
-                    }
-                    for entry in cpu.ex_mem.entries.iter().rev() {
-                        if entry.ctrl.reg_write && entry.rd == reg {
-                            return if entry.ctrl.jump {
-                                entry.pc.wrapping_add(entry.inst_size)
-                            } else {
-                                entry.alu
-                            };
-                        }
-                    }
-                    for entry in cpu.mem_wb.entries.iter().rev() {
-                        if entry.ctrl.reg_write && entry.rd == reg {
-                            return if entry.ctrl.mem_read {
-                                entry.load_data
-                            } else if entry.ctrl.jump {
-                                entry.pc.wrapping_add(entry.inst_size)
-                            } else {
-                                entry.alu
-                            };
-                        }
-                    }
-                    cpu.regs.read(reg)
-                };
-
-                if cpu.direct_mode {
-                    let val_a7 = get_val(abi::REG_A7, cpu, &ex_results);
-                    let val_a0 = get_val(abi::REG_A0, cpu, &ex_results);
-
-                    if val_a7 == sys_ops::SYS_EXIT {
-                        cpu.exit_code = Some(val_a0);
-                        break;
-                    } else if val_a0 == sys_ops::SYS_EXIT {
-                        let val_a1 = get_val(abi::REG_A1, cpu, &ex_results);
-                        cpu.exit_code = Some(val_a1);
-                        break;
-                    }
-                }
-
                 use crate::core::arch::mode::PrivilegeMode;
                 let trap = match cpu.privilege {
                     PrivilegeMode::User => Trap::EnvironmentCallFromUMode,
@@ -361,12 +313,29 @@ pub fn execute_stage(cpu: &mut Cpu) {
                     store_data: 0,
                     ctrl: id.ctrl,
                     trap: Some(trap),
+                    ras_checkpoint: id.ras_checkpoint,
                 });
                 flush_remaining = true;
                 continue;
             }
 
             if id.ctrl.csr_op != CsrOp::None {
+                if !cpu.csr_accessible(id.ctrl.csr_addr) {
+                    ex_results.push(ExMemEntry {
+                        pc: id.pc,
+                        inst: id.inst,
+                        inst_size: id.inst_size,
+                        rd: id.rd,
+                        alu: 0,
+                        store_data: 0,
+                        ctrl: id.ctrl,
+                        trap: Some(Trap::IllegalInstruction(id.inst)),
+                        ras_checkpoint: id.ras_checkpoint,
+                    });
+                    flush_remaining = true;
+                    continue;
+                }
+
                 let old = cpu.csr_read(id.ctrl.csr_addr);
                 let src = match id.ctrl.csr_op {
                     CsrOp::Rwi | CsrOp::Rsi | CsrOp::Rci => (id.rs1 as u64) & 0x1f,
@@ -381,68 +350,14 @@ pub fn execute_stage(cpu: &mut Cpu) {
 
                 if id.ctrl.csr_addr == crate::core::arch::csr::SATP {
                     cpu.flush_pipeline_stores();
-                    for entry in &mut ex_results {
-                        if entry.ctrl.mem_write {
-                            let vaddr = entry.alu;
-                            let result = cpu.translate(
-                                crate::common::VirtAddr::new(vaddr),
-                                crate::common::AccessType::Write,
-                            );
-                            if result.trap.is_some() {
-                                entry.ctrl.mem_write = false;
-                                continue;
-                            }
-                            let addr = result.paddr.val();
-                            let src = entry.store_data;
-                            let width = entry.ctrl.width;
-                            if addr >= cpu.ram_start && addr < cpu.ram_end {
-                                let offset = (addr - cpu.ram_start) as usize;
-                                unsafe {
-                                    match width {
-                                        crate::core::pipeline::signals::MemWidth::Byte => {
-                                            *cpu.ram_ptr.add(offset) = src as u8
-                                        }
-                                        crate::core::pipeline::signals::MemWidth::Half => {
-                                            (cpu.ram_ptr.add(offset) as *mut u16)
-                                                .write_unaligned(src as u16)
-                                        }
-                                        crate::core::pipeline::signals::MemWidth::Word => {
-                                            (cpu.ram_ptr.add(offset) as *mut u32)
-                                                .write_unaligned(src as u32)
-                                        }
-                                        crate::core::pipeline::signals::MemWidth::Double => {
-                                            (cpu.ram_ptr.add(offset) as *mut u64)
-                                                .write_unaligned(src)
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            } else {
-                                match width {
-                                    crate::core::pipeline::signals::MemWidth::Byte => {
-                                        cpu.bus.bus.write_u8(addr, src as u8)
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Half => {
-                                        cpu.bus.bus.write_u16(addr, src as u16)
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Word => {
-                                        cpu.bus.bus.write_u32(addr, src as u32)
-                                    }
-                                    crate::core::pipeline::signals::MemWidth::Double => {
-                                        cpu.bus.bus.write_u64(addr, src)
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            entry.ctrl.mem_write = false;
-                        }
-                    }
+                    cpu.drain_stores(&mut ex_results);
                 }
 
                 cpu.csr_write(id.ctrl.csr_addr, new);
 
                 cpu.if_id = IfId::default();
                 cpu.pc = id.pc.wrapping_add(id.inst_size);
+                repair_ras(cpu, &id);
                 flush_remaining = true;
 
                 ex_results.push(ExMemEntry {
@@ -454,40 +369,39 @@ pub fn execute_stage(cpu: &mut Cpu) {
                     store_data,
                     ctrl: id.ctrl,
                     trap: None,
+                    ras_checkpoint: id.ras_checkpoint,
                 });
                 continue;
             }
         }
 
-        let alu_out = if (id.ctrl.alu as i32 >= AluOp::FCvtSW as i32
-            && id.ctrl.alu as i32 <= AluOp::FCvtSL as i32)
-            || id.ctrl.alu as i32 == AluOp::FMvToF as i32
-        {
-            match id.ctrl.alu {
-                AluOp::FCvtSW => {
-                    if id.ctrl.is_rv32 {
-                        Fpu::box_f32((op_a as i32) as f32)
-                    } else {
-                        ((op_a as i32) as f64).to_bits()
-                    }
-                }
-                AluOp::FCvtSL => {
-                    if id.ctrl.is_rv32 {
-                        Fpu::box_f32((op_a as i64) as f32)
-                    } else {
-                        ((op_a as i64) as f64).to_bits()
-                    }
-                }
-                AluOp::FCvtSD => {
-                    let val_d = f64::from_bits(op_a);
-                    let val_s = val_d as f32;
-                    Fpu::box_f32(val_s)
-                }
-                AluOp::FCvtDS => {
-                    let val_s = f32::from_bits(op_a as u32);
-                    let val_d = val_s as f64;
-                    val_d.to_bits()
+        let rm = if uses_rounding_mode(id.ctrl.alu) {
+            let field = ((id.inst >> FUNCT3_SHIFT) & FUNCT3_MASK) as u8;
+            let frm = ((cpu.csrs.fcsr >> csr::FCSR_FRM_SHIFT) & csr::FCSR_FRM_MASK) as u8;
+            match RoundingMode::resolve(field, frm) {
+                Some(rm) => rm,
+                None => {
+                    ex_results.push(ExMemEntry {
+                        pc: id.pc,
+                        inst: id.inst,
+                        inst_size: id.inst_size,
+                        rd: id.rd,
+                        alu: 0,
+                        store_data: 0,
+                        ctrl: id.ctrl,
+                        trap: Some(Trap::IllegalInstruction(id.inst)),
+                        ras_checkpoint: id.ras_checkpoint,
+                    });
+                    flush_remaining = true;
+                    continue;
                 }
+            }
+        } else {
+            RoundingMode::Rne
+        };
+
+        let alu_out = if matches!(id.ctrl.alu, AluOp::FMvToF) {
+            match id.ctrl.alu {
                 AluOp::FMvToF => {
                     if id.ctrl.is_rv32 {
                         Fpu::box_f32(f32::from_bits(op_a as u32))
@@ -522,6 +436,10 @@ pub fn execute_stage(cpu: &mut Cpu) {
                     | AluOp::FCvtLS
                     | AluOp::FCvtSW
                     | AluOp::FCvtSL
+                    | AluOp::FCvtWuS
+                    | AluOp::FCvtLuS
+                    | AluOp::FCvtSWu
+                    | AluOp::FCvtSLu
                     | AluOp::FCvtSD
                     | AluOp::FCvtDS
                     | AluOp::FMvToX
@@ -529,12 +447,28 @@ pub fn execute_stage(cpu: &mut Cpu) {
             );
 
             if is_fp_op {
-                Fpu::execute(id.ctrl.alu, op_a, op_b, op_c, id.ctrl.is_rv32)
+                let (result, flags) = if cpu.ftz_enabled {
+                    Fpu::execute_ftz(id.ctrl.alu, op_a, op_b, op_c, id.ctrl.is_rv32, rm)
+                } else {
+                    Fpu::execute_rm(id.ctrl.alu, op_a, op_b, op_c, id.ctrl.is_rv32, rm)
+                };
+                if !flags.is_empty() {
+                    cpu.csrs.fcsr |= flags.bits() as u64;
+                    cpu.mark_fs_dirty();
+                }
+                result
             } else {
                 Alu::execute(id.ctrl.alu, op_a, op_b, op_c, id.ctrl.is_rv32)
             }
         };
 
+        // Multi-cycle FP and divide ops hold the pipeline for the rest of their latency.
+        cpu.stats.stalls_fpu += hold_execute(cpu, fpu_latency(cpu, id.ctrl.alu));
+        cpu.stats.stalls_div += hold_execute(
+            cpu,
+            div_latency(cpu, id.ctrl.alu, op_a, op_b, id.ctrl.is_rv32),
+        );
+
         if id.ctrl.branch {
             let taken = match (id.inst >> FUNCT3_SHIFT) & FUNCT3_MASK {
                 funct3::BEQ => op_a == op_b,
@@ -563,23 +497,23 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 if taken { Some(actual_target) } else { None },
             );
 
+            let category = if taken { "cond_taken" } else { "cond_nottaken" };
+            cpu.stats.record_branch(category, mispredicted);
+
             if mispredicted {
-                cpu.stats.branch_mispredictions += 1;
                 cpu.stats.stalls_control += 2;
 
                 cpu.pc = actual_next_pc;
-                cpu.if_id = IfId::default();
+                repair_ras(cpu, &id);
+                squash_wrong_path(cpu);
                 flush_remaining = true;
-            } else {
-                cpu.stats.branch_predictions += 1;
+                wrong_path = true;
             }
         }
 
         if id.ctrl.jump {
             use crate::common::constants::OPCODE_MASK;
             let is_jalr = (id.inst & OPCODE_MASK) == opcodes::OP_JALR;
-            let is_call = (id.inst & OPCODE_MASK) == opcodes::OP_JAL && id.rd == abi::REG_RA;
-            let is_ret = is_jalr && id.rd == abi::REG_ZERO && id.rs1 == abi::REG_RA;
 
             let actual_target = if is_jalr {
                 (fwd_a.wrapping_add(id.imm as u64)) & JALR_ALIGNMENT_MASK
@@ -593,25 +527,38 @@ pub fn execute_stage(cpu: &mut Cpu) {
                 id.pc.wrapping_add(id.inst_size)
             };
 
-            if actual_target != predicted_target {
-                cpu.stats.branch_mispredictions += 1;
+            let kind = BranchType::classify(id.inst);
+            if let Some(kind) = kind {
+                cpu.branch_predictor.update_btb(id.pc, actual_target, kind);
+                if is_jalr && kind != BranchType::Return {
+                    cpu.branch_predictor.update_indirect(id.pc, actual_target);
+                }
+            }
+
+            let mispredicted = actual_target != predicted_target;
+            let category = match kind {
+                Some(BranchType::Call) => "call",
+                Some(BranchType::Return) => "return",
+                Some(BranchType::IndirectJump) => "indirect",
+                _ => "jump",
+            };
+            cpu.stats.record_branch(category, mispredicted);
+
+            if mispredicted {
                 cpu.stats.stalls_control += 2;
                 cpu.pc = actual_target;
-                cpu.if_id = IfId::default();
+                repair_ras(cpu, &id);
+                squash_wrong_path(cpu);
                 flush_remaining = true;
-            } else {
-                cpu.stats.branch_predictions += 1;
+                wrong_path = true;
             }
+        }
 
-            if is_call {
-                cpu.branch_predictor.on_call(
-                    id.pc,
-                    id.pc.wrapping_add(id.inst_size),
-                    actual_target,
-                );
-            } else if is_ret {
-                cpu.branch_predictor.on_return();
-            }
+        let atomic = id.ctrl.atomic_op != AtomicOp::None;
+        // Release: every older store is performed before the AMO or SC is.
+        if atomic && id.ctrl.rl {
+            cpu.flush_pipeline_stores();
+            cpu.drain_stores(&mut ex_results);
         }
 
         ex_results.push(ExMemEntry {
@@ -623,7 +570,17 @@ pub fn execute_stage(cpu: &mut Cpu) {
             store_data,
             ctrl: id.ctrl,
             trap: None,
+            ras_checkpoint: id.ras_checkpoint,
         });
+
+        // Acquire: younger instructions are refetched, so none of their memory
+        // accesses can issue ahead of this one.
+        if atomic && id.ctrl.aq {
+            cpu.if_id = IfId::default();
+            cpu.pc = id.pc.wrapping_add(id.inst_size);
+            repair_ras(cpu, &id);
+            flush_remaining = true;
+        }
     }
 
     cpu.id_ex.entries = entries;
@@ -632,3 +589,126 @@ pub fn execute_stage(cpu: &mut Cpu) {
         entries: ex_results,
     };
 }
+
+/// Extends `cpu.alu_timer` so the pipeline stalls for all but the first of `cycles`.
+///
+/// Ops dispatched in the same group overlap, so only the increase beyond the
+/// current timer is returned, for attribution to a stall counter.
+fn hold_execute(cpu: &mut Cpu, cycles: u64) -> u64 {
+    let extra = cycles.saturating_sub(1).saturating_sub(cpu.alu_timer);
+    cpu.alu_timer += extra;
+    extra
+}
+
+/// Returns the latency of integer divide/remainder `op`, or 1 for other ops.
+///
+/// With `div_early_exit`, the configured latency is scaled by the number of
+/// quotient bits the operand magnitudes allow, out of the operation width.
+fn div_latency(cpu: &Cpu, op: AluOp, a: u64, b: u64, is32: bool) -> u64 {
+    let signed = match op {
+        AluOp::Div | AluOp::Rem => true,
+        AluOp::Divu | AluOp::Remu => false,
+        _ => return 1,
+    };
+    if !cpu.div_early_exit {
+        return cpu.div_latency;
+    }
+    let (a, b, width) = match (is32, signed) {
+        (true, true) => (
+            (a as i32).unsigned_abs() as u64,
+            (b as i32).unsigned_abs() as u64,
+            32,
+        ),
+        (true, false) => (a as u32 as u64, b as u32 as u64, 32),
+        (false, true) => ((a as i64).unsigned_abs(), (b as i64).unsigned_abs(), 64),
+        (false, false) => (a, b, 64),
+    };
+    if b == 0 {
+        return 1;
+    }
+    let quotient_bits = (b.leading_zeros() + 1).saturating_sub(a.leading_zeros()) as u64;
+    (cpu.div_latency * quotient_bits).div_ceil(width).max(1)
+}
+
+/// Returns the configured execute latency of `op`, or 1 for single-cycle ops.
+fn fpu_latency(cpu: &Cpu, op: AluOp) -> u64 {
+    let lat = &cpu.fpu_latency;
+    match op {
+        AluOp::FDiv => lat.fdiv_latency,
+        AluOp::FSqrt => lat.fsqrt_latency,
+        AluOp::FMul => lat.fmul_latency,
+        AluOp::FMAdd | AluOp::FMSub | AluOp::FNMAdd | AluOp::FNMSub => lat.fma_latency,
+        _ => 1,
+    }
+}
+
+/// Returns `true` if `op` carries an `rm` field in its funct3 bits.
+///
+/// Sign injection, min/max, comparisons, moves, and `FCLASS` reuse funct3 as
+/// an opcode extension and never consult the rounding mode.
+fn uses_rounding_mode(op: AluOp) -> bool {
+    matches!(
+        op,
+        AluOp::FAdd
+            | AluOp::FSub
+            | AluOp::FMul
+            | AluOp::FDiv
+            | AluOp::FSqrt
+            | AluOp::FMAdd
+            | AluOp::FMSub
+            | AluOp::FNMAdd
+            | AluOp::FNMSub
+            | AluOp::FCvtWS
+            | AluOp::FCvtLS
+            | AluOp::FCvtSW
+            | AluOp::FCvtSL
+            | AluOp::FCvtWuS
+            | AluOp::FCvtLuS
+            | AluOp::FCvtSWu
+            | AluOp::FCvtSLu
+            | AluOp::FCvtSD
+            | AluOp::FCvtDS
+    )
+}
+
+/// Repairs the return address stack when every instruction younger than `id` is squashed.
+///
+/// Fetch updates the RAS speculatively, so this restores the checkpoint taken
+/// before `id` was fetched and replays `id`'s own call or return.
+fn repair_ras(cpu: &mut Cpu, id: &IdExEntry) {
+    use crate::common::constants::OPCODE_MASK;
+    cpu.branch_predictor.ras_restore(id.ras_checkpoint);
+    let is_jal = (id.inst & OPCODE_MASK) == opcodes::OP_JAL;
+    match BranchType::classify(id.inst) {
+        Some(BranchType::Call) => cpu.branch_predictor.on_call(
+            id.pc,
+            id.pc.wrapping_add(id.inst_size),
+            is_jal.then(|| id.pc.wrapping_add(id.imm as u64)),
+        ),
+        Some(BranchType::Return) => cpu.branch_predictor.on_return(),
+        _ => {}
+    }
+}
+
+/// Squashes the wrong-path fetch group after a control-flow misprediction.
+///
+/// Every fetch down the wrong path, whether still in IF/ID or behind the branch in
+/// its ID/EX bundle, already went through the I-cache; those that allocated a line
+/// are counted as wrong-path fills. Squashed loads optionally pollute the
+/// data caches (see [`Cpu::wrong_path_pollution`]).
+fn squash_wrong_path(cpu: &mut Cpu) {
+    let squashed = std::mem::take(&mut cpu.if_id.entries);
+    for entry in squashed.iter().filter(|e| e.trap.is_none()) {
+        squash_entry(cpu, entry.pc, entry.inst);
+    }
+}
+
+/// Accounts for the cache side effects of one squashed wrong-path instruction.
+fn squash_entry(cpu: &mut Cpu, pc: u64, inst: u32) {
+    if cpu.fetch_fill_pcs.contains(&pc) {
+        cpu.stats.wrong_path_icache_fills += 1;
+    }
+    if cpu.wrong_path_pollution {
+        cpu.wrong_path_load(inst);
+    }
+}
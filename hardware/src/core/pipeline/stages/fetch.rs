@@ -7,16 +7,21 @@
 
 use crate::common::constants::{
     COMPRESSED_INSTRUCTION_MASK, COMPRESSED_INSTRUCTION_VALUE, INSTRUCTION_SIZE_16,
-    INSTRUCTION_SIZE_32, OPCODE_MASK, RD_MASK, RD_SHIFT, RS1_MASK, RS1_SHIFT,
+    INSTRUCTION_SIZE_32, OPCODE_MASK,
 };
-use crate::common::{AccessType, TranslationResult, Trap, VirtAddr};
+use crate::common::{AccessType, PhysAddr, TranslationResult, Trap, VirtAddr};
 use crate::core::Cpu;
+use crate::core::pipeline::fusion;
 use crate::core::pipeline::latches::IfIdEntry;
 use crate::core::units::bru::BranchPredictor;
-use crate::isa::abi;
+use crate::core::units::bru::btb::BranchType;
+use crate::isa::decode::decode;
 use crate::isa::rv64i::opcodes;
 use crate::isa::rvc::expand::expand;
 
+/// Bytes in one instruction parcel, the unit each fetch translation and PMP check covers.
+const PARCEL_BYTES: u64 = 2;
+
 /// Executes the instruction fetch stage of the pipeline.
 ///
 /// Fetches instructions from memory starting at the current program counter.
@@ -30,18 +35,38 @@ use crate::isa::rvc::expand::expand;
 ///
 /// # Behavior
 ///
-/// - Fetches up to `pipeline_width` instructions per cycle
+/// - Fetches up to `pipeline_width` slots per cycle; a macro-op fused pair
+///   occupies a single slot when fusion is enabled
+/// - Charges one memory access per L1-I line touched by the group, and never
+///   lets a group cross into the next page (which needs its own translation);
+///   a 32-bit instruction straddling two pages translates each half separately
 /// - Expands compressed (16-bit) instructions to 32-bit format
-/// - Performs branch prediction for control flow instructions
-/// - Stops fetching on misaligned addresses or translation faults
+/// - Performs branch prediction for control flow instructions, updating the
+///   return address stack speculatively (repaired by execute on a misprediction)
+/// - Stops fetching on misaligned addresses or translation faults, and in front of
+///   a debugger breakpoint or once a single-step has issued its instruction
 /// - Updates the program counter based on predictions
 pub fn fetch_stage(cpu: &mut Cpu) {
     let mut fetched = std::mem::take(&mut cpu.if_id_shadow);
     fetched.clear();
 
     let mut current_pc = cpu.pc;
+    // Fills by groups already decoded stay on record until those instructions
+    // leave ID/EX, so a misprediction can still attribute them to the wrong path.
+    let in_flight = &cpu.id_ex.entries;
+    cpu.fetch_fill_pcs
+        .retain(|pc| in_flight.iter().any(|e| e.pc == *pc));
+
+    let mut slots = 0;
+    let mut last_fused = false;
+    let mut fetch_line = None;
+    let page_shift = cpu.mmu.page_shift();
+    let line_bytes = cpu.l1_i_cache.line_bytes() as u64;
 
-    for _ in 0..cpu.pipeline_width {
+    while slots < cpu.pipeline_width || may_fuse_next(cpu, &fetched, last_fused) {
+        if cpu.run_control.blocks_fetch(current_pc, fetched.len()) {
+            break;
+        }
         let mut fetch_trap = None;
         if (current_pc & 1) != 0 {
             if fetched.is_empty() {
@@ -50,16 +75,22 @@ pub fn fetch_stage(cpu: &mut Cpu) {
                 break;
             }
         }
+        if fetched
+            .first()
+            .is_some_and(|first| (first.pc ^ current_pc) >> page_shift != 0)
+        {
+            break;
+        }
 
         let TranslationResult {
             paddr,
             cycles,
             trap,
         } = if fetch_trap.is_none() {
-            cpu.translate(VirtAddr::new(current_pc), AccessType::Fetch)
+            cpu.translate(VirtAddr::new(current_pc), AccessType::Fetch, PARCEL_BYTES)
         } else {
             TranslationResult {
-                paddr: crate::common::PhysAddr::new(0),
+                paddr: PhysAddr::new(0),
                 cycles: 0,
                 trap: None,
             }
@@ -79,6 +110,7 @@ pub fn fetch_stage(cpu: &mut Cpu) {
                     pred_taken: false,
                     pred_target: 0,
                     trap: Some(trap_cause.clone()),
+                    ras_checkpoint: cpu.branch_predictor.ras_checkpoint(),
                 });
                 break;
             } else {
@@ -88,21 +120,7 @@ pub fn fetch_stage(cpu: &mut Cpu) {
 
         let phys_addr = paddr.val();
 
-        let half_word = if phys_addr >= cpu.ram_start && phys_addr < cpu.ram_end {
-            let offset = (phys_addr - cpu.ram_start) as usize;
-            // SAFETY: This is safe because:
-            // 1. `phys_addr` is validated to be within RAM bounds (>= ram_start && < ram_end)
-            // 2. `offset` is computed from validated bounds, ensuring it's within allocated memory
-            // 3. `ram_ptr` points to valid, initialized memory allocated during CPU construction
-            // 4. `read_unaligned()` handles any alignment issues that may occur at arbitrary addresses
-            // 5. The u16 read cannot overflow the buffer as offset is strictly < (ram_end - ram_start)
-            unsafe {
-                let ptr = cpu.ram_ptr.add(offset) as *const u16;
-                ptr.read_unaligned()
-            }
-        } else {
-            cpu.bus.bus.read_u16(phys_addr)
-        };
+        let half_word = read_half(cpu, phys_addr);
 
         let is_compressed =
             (half_word & COMPRESSED_INSTRUCTION_MASK) != COMPRESSED_INSTRUCTION_VALUE;
@@ -129,23 +147,41 @@ pub fn fetch_stage(cpu: &mut Cpu) {
             /// Bit shift to combine upper and lower half-words into full instruction.
             const UPPER_HALF_SHIFT: u32 = 16;
 
-            let upper_half = if phys_addr + UPPER_HALF_OFFSET >= cpu.ram_start
-                && phys_addr + UPPER_HALF_OFFSET < cpu.ram_end
-            {
-                let offset = (phys_addr + UPPER_HALF_OFFSET - cpu.ram_start) as usize;
-                // SAFETY: This is safe because:
-                // 1. `phys_addr + UPPER_HALF_OFFSET` is validated to be within RAM bounds
-                // 2. `offset` represents a valid offset from ram_start within allocated memory
-                // 3. `ram_ptr` points to valid, initialized memory
-                // 4. `read_unaligned()` safely handles potential misalignment of the upper half-word
-                // 5. The u16 read at offset+2 is guaranteed within bounds by the range check
-                unsafe {
-                    let ptr = cpu.ram_ptr.add(offset) as *const u16;
-                    ptr.read_unaligned()
-                }
+            // An instruction straddling a page boundary needs its upper half translated
+            // separately; that page may map elsewhere or fault on its own. Within a page
+            // the upper half still needs its own PMP check.
+            let upper_pc = current_pc.wrapping_add(UPPER_HALF_OFFSET);
+            let upper = if (upper_pc ^ current_pc) >> page_shift != 0 {
+                let upper = cpu.translate(VirtAddr::new(upper_pc), AccessType::Fetch, PARCEL_BYTES);
+                cpu.stall_cycles += upper.cycles;
+                upper
             } else {
-                cpu.bus.bus.read_u16(phys_addr + UPPER_HALF_OFFSET)
+                cpu.check_physical(
+                    VirtAddr::new(upper_pc),
+                    AccessType::Fetch,
+                    PARCEL_BYTES,
+                    TranslationResult::success(PhysAddr::new(phys_addr + UPPER_HALF_OFFSET), 0),
+                )
             };
+            if let Some(t) = upper.trap {
+                if !fetched.is_empty() {
+                    break;
+                }
+                if cpu.trace {
+                    eprintln!("IF  pc={:#x} # TRAP: {:?}", current_pc, t);
+                }
+                fetched.push(IfIdEntry {
+                    pc: current_pc,
+                    inst: 0,
+                    inst_size: INSTRUCTION_SIZE_32,
+                    pred_taken: false,
+                    pred_target: 0,
+                    trap: Some(t),
+                    ras_checkpoint: cpu.branch_predictor.ras_checkpoint(),
+                });
+                break;
+            }
+            let upper_half = read_half(cpu, upper.paddr.val());
 
             let full_inst = (upper_half as u32) << UPPER_HALF_SHIFT | (half_word as u32);
             (full_inst, INSTRUCTION_SIZE_32, None)
@@ -162,14 +198,38 @@ pub fn fetch_stage(cpu: &mut Cpu) {
                 pred_taken: false,
                 pred_target: 0,
                 trap: Some(t),
+                ras_checkpoint: cpu.branch_predictor.ras_checkpoint(),
             });
             break;
         }
 
-        if phys_addr >= cpu.mmio_base {
-            cpu.stall_cycles += cpu.simulate_memory_access(paddr, AccessType::Fetch);
-        } else {
-            cpu.stall_cycles += cpu.bus.bus.calculate_transit_time(4);
+        let fused = cpu.fusion_enabled
+            && !last_fused
+            && fetched
+                .last()
+                .is_some_and(|prev| prev.trap.is_none() && fusion::is_fusible(prev.inst, inst));
+        if slots >= cpu.pipeline_width && !fused {
+            // Extra look-ahead for a fusion partner found none; refetch next cycle.
+            break;
+        }
+        last_fused = fused;
+        if !fused {
+            slots += 1;
+        }
+
+        // Instructions in the same line arrive with the group's first access.
+        let line = phys_addr / line_bytes;
+        if fetch_line != Some(line) {
+            fetch_line = Some(line);
+            if phys_addr >= cpu.mmio_base {
+                let misses_before = cpu.stats.icache_misses;
+                cpu.stall_cycles += cpu.simulate_memory_access(paddr, AccessType::Fetch);
+                if cpu.stats.icache_misses != misses_before {
+                    cpu.fetch_fill_pcs.push(current_pc);
+                }
+            } else {
+                cpu.stall_cycles += cpu.bus.bus.calculate_transit_time(4);
+            }
         }
 
         if cpu.trace {
@@ -177,12 +237,11 @@ pub fn fetch_stage(cpu: &mut Cpu) {
         }
 
         let opcode = inst & OPCODE_MASK;
-        let rd = ((inst >> RD_SHIFT) & RD_MASK) as usize;
-        let rs1 = ((inst >> RS1_SHIFT) & RS1_MASK) as usize;
         let mut next_pc_calc = current_pc.wrapping_add(step);
         let mut pred_taken = false;
         let mut pred_target = 0;
         let mut stop_fetch = false;
+        let ras_checkpoint = cpu.branch_predictor.ras_checkpoint();
 
         if opcode == opcodes::OP_BRANCH {
             let (taken, target) = cpu.branch_predictor.predict_branch(current_pc);
@@ -201,17 +260,38 @@ pub fn fetch_stage(cpu: &mut Cpu) {
                 pred_target = tgt;
                 stop_fetch = true;
             }
+            if BranchType::classify(inst) == Some(BranchType::Call) {
+                let target = current_pc.wrapping_add(decode(inst).imm as u64);
+                cpu.branch_predictor.on_call(
+                    current_pc,
+                    current_pc.wrapping_add(step),
+                    Some(target),
+                );
+            }
         } else if opcode == opcodes::OP_JALR {
-            if rd == abi::REG_ZERO && rs1 == abi::REG_RA {
-                if let Some(tgt) = cpu.branch_predictor.predict_return() {
-                    next_pc_calc = tgt;
-                    pred_taken = true;
-                    pred_target = tgt;
+            match jalr_type(cpu, current_pc, inst) {
+                BranchType::Return => {
+                    if let Some(tgt) = cpu.branch_predictor.predict_return() {
+                        next_pc_calc = tgt;
+                        pred_taken = true;
+                        pred_target = tgt;
+                    }
+                    cpu.branch_predictor.on_return();
+                }
+                kind => {
+                    if let Some(tgt) = cpu.branch_predictor.predict_indirect(current_pc) {
+                        next_pc_calc = tgt;
+                        pred_taken = true;
+                        pred_target = tgt;
+                    }
+                    if kind == BranchType::Call {
+                        cpu.branch_predictor.on_call(
+                            current_pc,
+                            current_pc.wrapping_add(step),
+                            None,
+                        );
+                    }
                 }
-            } else if let Some(tgt) = cpu.branch_predictor.predict_btb(current_pc) {
-                next_pc_calc = tgt;
-                pred_taken = true;
-                pred_target = tgt;
             }
             stop_fetch = true;
         }
@@ -223,6 +303,7 @@ pub fn fetch_stage(cpu: &mut Cpu) {
             pred_taken,
             pred_target,
             trap: None,
+            ras_checkpoint,
         });
 
         current_pc = next_pc_calc;
@@ -232,6 +313,49 @@ pub fn fetch_stage(cpu: &mut Cpu) {
         }
     }
 
+    cpu.run_control.note_fetched(fetched.iter().map(|e| e.pc));
     cpu.pc = current_pc;
     cpu.if_id.entries = fetched;
 }
+
+/// Returns whether fetch should look one instruction past a full group.
+///
+/// Only applies when the last fetched instruction could start a fused pair
+/// and is not itself the second half of one.
+fn may_fuse_next(cpu: &Cpu, fetched: &[IfIdEntry], last_fused: bool) -> bool {
+    cpu.fusion_enabled
+        && !last_fused
+        && fetched
+            .last()
+            .is_some_and(|prev| prev.trap.is_none() && fusion::starts_pair(prev.inst))
+}
+
+/// Returns how fetch predicts the `JALR` `inst` at `pc`.
+///
+/// Trusts the type the BTB recorded when the jump last executed, and predecodes
+/// the link-register hints on a miss. Returns go to the RAS, not the BTB.
+fn jalr_type(cpu: &Cpu, pc: u64, inst: u32) -> BranchType {
+    match cpu.branch_predictor.branch_type(pc) {
+        Some(kind @ (BranchType::Call | BranchType::Return | BranchType::IndirectJump)) => kind,
+        _ => BranchType::classify(inst).unwrap_or(BranchType::IndirectJump),
+    }
+}
+
+/// Reads one 16-bit instruction parcel from physical memory.
+fn read_half(cpu: &mut Cpu, phys_addr: u64) -> u16 {
+    if phys_addr >= cpu.ram_start && phys_addr < cpu.ram_end {
+        let offset = (phys_addr - cpu.ram_start) as usize;
+        // SAFETY: This is safe because:
+        // 1. `phys_addr` is validated to be within RAM bounds (>= ram_start && < ram_end)
+        // 2. `offset` is computed from validated bounds, ensuring it's within allocated memory
+        // 3. `ram_ptr` points to valid, initialized memory allocated during CPU construction
+        // 4. `read_unaligned()` handles any alignment issues that may occur at arbitrary addresses
+        // 5. The u16 read cannot overflow the buffer as offset is strictly < (ram_end - ram_start)
+        unsafe {
+            let ptr = cpu.ram_ptr.add(offset) as *const u16;
+            ptr.read_unaligned()
+        }
+    } else {
+        cpu.bus.bus.read_u16(phys_addr)
+    }
+}
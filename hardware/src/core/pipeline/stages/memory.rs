@@ -3,13 +3,18 @@
 //! This module implements the fourth stage of the instruction pipeline.
 //! It handles Load/Store operations, performs virtual-to-physical address
 //! translation via the MMU, and executes Atomic Memory Operations (AMOs).
-//! It also manages data alignment and access faults.
+//! It also manages data alignment and access faults (atomics aimed at a device
+//! fault), and makes device (non-RAM) loads non-speculative so their read side
+//! effects happen exactly once.
 
-use crate::common::{AccessType, TranslationResult, Trap, VirtAddr};
+use crate::common::{AccessType, PhysAddr, TranslationResult, Trap, VirtAddr};
+use crate::config::{MisalignedAccess, MisalignedPriority};
 use crate::core::Cpu;
-use crate::core::pipeline::latches::MemWbEntry;
+use crate::core::cpu::history::{MemUndo, width_bytes};
+use crate::core::pipeline::latches::{ExMemEntry, MemWbEntry};
 use crate::core::pipeline::signals::{AtomicOp, MemWidth};
 use crate::core::units::lsu::Lsu;
+use crate::core::units::lsu::priority::prioritize;
 
 /// Executes the memory stage of the pipeline.
 ///
@@ -43,12 +48,18 @@ pub fn mem_stage(cpu: &mut Cpu) {
         }
 
         let mut ld = 0;
+        let mut stored = None;
         let mut trap = ex.trap.clone();
 
         if trap.is_some() && cpu.trace {
             eprintln!("MEM pc={:#x} # TRAP: {:?}", ex.pc, trap.as_ref().unwrap());
         }
 
+        // Ordinary loads and stores are split into byte accesses unless the platform
+        // traps on misalignment; atomics (no Zam) must be naturally aligned and raise
+        // address-misaligned.
+        let mut misaligned = None;
+        let mut split = false;
         if ex.ctrl.mem_read || ex.ctrl.mem_write {
             let align_mask = match ex.ctrl.width {
                 MemWidth::Byte => 0,
@@ -59,22 +70,43 @@ pub fn mem_stage(cpu: &mut Cpu) {
             };
 
             if (ex.alu & align_mask) != 0 {
-                let potential_trap = if ex.ctrl.mem_read {
-                    Trap::LoadAddressMisaligned(ex.alu)
-                } else {
+                let potential_trap = if ex.ctrl.mem_write {
                     Trap::StoreAddressMisaligned(ex.alu)
+                } else {
+                    Trap::LoadAddressMisaligned(ex.alu)
                 };
 
-                if cpu.trace {
-                    eprintln!(
-                        "MEM pc={:#x} # WARNING: Ignored {:?}",
-                        ex.pc, potential_trap
-                    );
+                if ex.ctrl.atomic_op != AtomicOp::None
+                    || cpu.misaligned_access == MisalignedAccess::Trap
+                {
+                    misaligned = Some(potential_trap);
+                } else {
+                    split = true;
                 }
             }
         }
 
-        if trap.is_none() && (ex.ctrl.mem_read || ex.ctrl.mem_write) {
+        // A misaligned access checked before translation never walks the page table.
+        if trap.is_none() && cpu.misaligned_priority == MisalignedPriority::BeforeTranslation {
+            trap = misaligned.take();
+        }
+
+        // Loads targeting x0 still perform the access: the result is discarded in
+        // writeback, but translation faults and MMIO read side effects must occur.
+        if trap.is_none() && split {
+            match split_access(cpu, &ex) {
+                Ok((value, data)) => {
+                    ld = value;
+                    stored = data;
+                }
+                Err(t) => {
+                    if cpu.trace {
+                        eprintln!("MEM pc={:#x} # TRAP: {:?} (addr={:#x})", ex.pc, t, ex.alu);
+                    }
+                    trap = Some(t);
+                }
+            }
+        } else if trap.is_none() && (ex.ctrl.mem_read || ex.ctrl.mem_write) {
             let access_type = if ex.ctrl.mem_write {
                 AccessType::Write
             } else {
@@ -85,9 +117,15 @@ pub fn mem_stage(cpu: &mut Cpu) {
                 paddr,
                 cycles,
                 trap: fault,
-            } = cpu.translate(VirtAddr::new(ex.alu), access_type);
+            } = cpu.translate(
+                VirtAddr::new(ex.alu),
+                access_type,
+                u64::from(width_bytes(ex.ctrl.width)),
+            );
             cpu.stall_cycles += cycles;
 
+            let fault = prioritize(misaligned, fault, cpu.misaligned_priority)
+                .or_else(|| atomic_access_fault(cpu, &ex, paddr.val()));
             if let Some(t) = fault {
                 if cpu.trace {
                     eprintln!("MEM pc={:#x} # TRAP: {:?} (addr={:#x})", ex.pc, t, ex.alu);
@@ -105,15 +143,16 @@ pub fn mem_stage(cpu: &mut Cpu) {
                     }
                 }
                 if paddr.val() >= cpu.mmio_base {
-                    let lat = cpu.simulate_memory_access(paddr, access_type);
-                    cpu.stall_cycles += lat;
-                } else if ex.ctrl.mem_write {
-                    let addr = paddr.val();
-                    if addr >= 0x10001000 && addr < 0x10002000 {
-                        cpu.l1_d_cache.flush();
-                        cpu.l2_cache.flush();
-                        cpu.l3_cache.flush();
+                    let lat =
+                        cpu.simulate_memory_access_hinted(paddr, access_type, ex.ctrl.ntl_levels);
+                    if cpu.mshrs.is_enabled() && ex.ctrl.atomic_op == AtomicOp::None {
+                        issue_nonblocking(cpu, &ex, paddr.val(), lat);
+                    } else {
+                        cpu.stall_cycles += lat;
                     }
+                    cpu.drop_reservation_on_eviction();
+                } else if ex.ctrl.mem_write {
+                    flush_on_virtio_store(cpu, paddr.val(), width_bytes(ex.ctrl.width) as u64);
                 }
 
                 let raw_paddr = paddr.val();
@@ -124,6 +163,35 @@ pub fn mem_stage(cpu: &mut Cpu) {
                     0
                 };
 
+                // Device reads may have side effects (a UART RBR read pops its FIFO), so
+                // once one is performed here the bundle must commit: hold off interrupts at
+                // the next writeback instead of squashing the load and replaying the read.
+                // Wrong-path loads never get this far; execute squashes them first.
+                if ex.ctrl.mem_read && !is_ram {
+                    cpu.interrupt_inhibit_one_cycle = true;
+                }
+
+                let writes_mem = ex.ctrl.mem_write
+                    || !matches!(ex.ctrl.atomic_op, AtomicOp::None | AtomicOp::Lr);
+                if writes_mem && is_ram && cpu.undo.is_enabled() {
+                    let size = width_bytes(ex.ctrl.width);
+                    let old = cpu.read_undo_value(raw_paddr, size);
+                    cpu.undo.record_store(
+                        ex.pc,
+                        MemUndo {
+                            addr: raw_paddr,
+                            old,
+                            size,
+                        },
+                    );
+                }
+                if writes_mem && is_ram && !cpu.write_watches.is_empty() {
+                    cpu.capture_write_watches(raw_paddr, width_bytes(ex.ctrl.width));
+                }
+
+                // Ordering bits (`aq`/`rl`) are enforced in execute: a release
+                // op drains older stores before it issues, and an acquire op
+                // refetches everything younger.
                 if ex.ctrl.atomic_op != AtomicOp::None {
                     match ex.ctrl.atomic_op {
                         AtomicOp::Lr => {
@@ -134,10 +202,10 @@ pub fn mem_stage(cpu: &mut Cpu) {
                                 MemWidth::Double => cpu.bus.bus.read_u64(raw_paddr),
                                 _ => 0,
                             };
-                            cpu.load_reservation = Some(raw_paddr);
+                            cpu.reserve(raw_paddr);
                         }
                         AtomicOp::Sc => {
-                            if cpu.load_reservation == Some(raw_paddr) {
+                            if cpu.reservation_covers(raw_paddr) {
                                 match ex.ctrl.width {
                                     MemWidth::Word => {
                                         cpu.bus.bus.write_u32(raw_paddr, ex.store_data as u32)
@@ -147,7 +215,9 @@ pub fn mem_stage(cpu: &mut Cpu) {
                                     }
                                     _ => {}
                                 }
+                                stored = Some(ex.store_data);
                                 ld = 0;
+                                cpu.store_performed(raw_paddr);
                             } else {
                                 ld = 1;
                             }
@@ -175,10 +245,9 @@ pub fn mem_stage(cpu: &mut Cpu) {
                                 _ => {}
                             }
 
+                            stored = Some(new_val);
                             ld = old_val;
-                            if cpu.load_reservation == Some(raw_paddr) {
-                                cpu.load_reservation = None;
-                            }
+                            cpu.store_performed(raw_paddr);
                         }
                     }
                 } else {
@@ -247,9 +316,8 @@ pub fn mem_stage(cpu: &mut Cpu) {
                             ld |= 0xFFFF_FFFF_0000_0000;
                         }
                     } else if ex.ctrl.mem_write {
-                        if cpu.load_reservation == Some(raw_paddr) {
-                            cpu.load_reservation = None;
-                        }
+                        cpu.store_performed(raw_paddr);
+                        stored = Some(ex.store_data);
 
                         if is_ram {
                             // SAFETY: This write operation is safe because:
@@ -293,6 +361,15 @@ pub fn mem_stage(cpu: &mut Cpu) {
                         }
                     }
                 }
+
+                if cpu.write_watches.has_pending() {
+                    // A failed SC writes nothing.
+                    if ex.ctrl.atomic_op == AtomicOp::Sc && ld != 0 {
+                        cpu.write_watches.clear_pending();
+                    } else {
+                        cpu.fire_write_watches(ex.pc);
+                    }
+                }
             }
         } else if cpu.trace {
             eprintln!("MEM pc={:#x}", ex.pc);
@@ -305,8 +382,10 @@ pub fn mem_stage(cpu: &mut Cpu) {
             rd: ex.rd,
             alu: ex.alu,
             load_data: ld,
+            stored,
             ctrl: ex.ctrl,
             trap: trap.clone(),
+            ras_checkpoint: ex.ras_checkpoint,
         });
 
         if trap.is_some() {
@@ -317,3 +396,169 @@ pub fn mem_stage(cpu: &mut Cpu) {
     cpu.mem_wb.entries = mem_results;
     cpu.ex_mem_shadow = ex_entries;
 }
+
+/// Returns the access fault for an atomic that targets a device instead of RAM.
+///
+/// Devices do not support LR/SC or AMOs, so a read-modify-write of a device register
+/// faults (a load fault for LR, a store/AMO fault otherwise) instead of touching it.
+fn atomic_access_fault(cpu: &Cpu, ex: &ExMemEntry, paddr: u64) -> Option<Trap> {
+    if ex.ctrl.atomic_op == AtomicOp::None
+        || (cpu.ram_start..cpu.ram_end).contains(&paddr)
+        || cpu.bus.bus.is_memory(paddr)
+    {
+        return None;
+    }
+    Some(if ex.ctrl.atomic_op == AtomicOp::Lr {
+        Trap::LoadAccessFault(ex.alu)
+    } else {
+        Trap::StoreAccessFault(ex.alu)
+    })
+}
+
+/// Performs a misaligned load or store as a sequence of byte accesses over the bus.
+///
+/// Every page the access touches is translated before any byte moves, so a fault on
+/// the second page (reported at its first address) leaves memory unmodified. Each cache
+/// line touched is charged as a separate access, plus one cycle per byte after the first.
+///
+/// # Returns
+///
+/// The loaded value (sign-extended or NaN-boxed as for an aligned load) and the stored
+/// data, or the trap raised by one of the pieces.
+fn split_access(cpu: &mut Cpu, ex: &ExMemEntry) -> Result<(u64, Option<u64>), Trap> {
+    let size = width_bytes(ex.ctrl.width) as usize;
+    let access = if ex.ctrl.mem_write {
+        AccessType::Write
+    } else {
+        AccessType::Read
+    };
+    let page_mask = (1u64 << cpu.mmu.page_shift()) - 1;
+
+    let mut paddrs = [0u64; 8];
+    for i in 0..size {
+        let vaddr = ex.alu.wrapping_add(i as u64);
+        paddrs[i] = if i == 0 || vaddr & page_mask == 0 {
+            let run = (page_mask + 1 - (vaddr & page_mask)).min((size - i) as u64);
+            let result = cpu.translate(VirtAddr::new(vaddr), access, run);
+            cpu.stall_cycles += result.cycles;
+            if let Some(t) = result.trap {
+                return Err(t);
+            }
+            result.paddr.val()
+        } else {
+            paddrs[i - 1] + 1
+        };
+    }
+    let paddrs = &paddrs[..size];
+
+    let line_bytes = cpu.l1_d_cache.line_bytes() as u64;
+    for (i, &paddr) in paddrs.iter().enumerate() {
+        if i > 0 && paddr / line_bytes == paddrs[i - 1] / line_bytes {
+            continue;
+        }
+        if paddr >= cpu.mmio_base {
+            cpu.stall_cycles +=
+                cpu.simulate_memory_access_hinted(PhysAddr::new(paddr), access, ex.ctrl.ntl_levels);
+            cpu.drop_reservation_on_eviction();
+        }
+    }
+    cpu.stall_cycles += size as u64 - 1;
+
+    let (ram_start, ram_end) = (cpu.ram_start, cpu.ram_end);
+    let is_ram = move |paddr: u64| paddr >= ram_start && paddr < ram_end;
+    if ex.ctrl.mem_read {
+        if !paddrs.iter().all(|&p| is_ram(p)) {
+            cpu.interrupt_inhibit_one_cycle = true;
+        }
+        let raw = paddrs.iter().enumerate().fold(0u64, |acc, (i, &paddr)| {
+            acc | (u64::from(cpu.bus.bus.read_u8(paddr)) << (8 * i))
+        });
+        let bits = 8 * size as u32;
+        let mut value = if ex.ctrl.signed_load && bits < 64 {
+            (((raw << (64 - bits)) as i64) >> (64 - bits)) as u64
+        } else {
+            raw
+        };
+        if ex.ctrl.fp_reg_write && matches!(ex.ctrl.width, MemWidth::Word) {
+            value |= 0xFFFF_FFFF_0000_0000;
+        }
+        return Ok((value, None));
+    }
+
+    // Undo history, write watches, and the VirtIO flush see one access per physically
+    // contiguous run, so a store split across two frames is recorded as two.
+    let mut start = 0;
+    while start < size {
+        let paddr = paddrs[start];
+        let run = paddrs[start..]
+            .windows(2)
+            .take_while(|w| w[1] == w[0] + 1)
+            .count()
+            + 1;
+        if is_ram(paddr) && cpu.undo.is_enabled() {
+            let old = cpu.read_undo_value(paddr, run as u8);
+            cpu.undo.record_store(
+                ex.pc,
+                MemUndo {
+                    addr: paddr,
+                    old,
+                    size: run as u8,
+                },
+            );
+        }
+        if !cpu.write_watches.is_empty() {
+            cpu.capture_write_watches(paddr, run as u8);
+        }
+        if paddr < cpu.mmio_base {
+            flush_on_virtio_store(cpu, paddr, run as u64);
+        }
+        start += run;
+    }
+
+    for (i, &paddr) in paddrs.iter().enumerate() {
+        cpu.store_performed(paddr);
+        cpu.bus
+            .bus
+            .write_u8(paddr, (ex.store_data >> (8 * i)) as u8);
+    }
+    if cpu.write_watches.has_pending() {
+        cpu.fire_write_watches(ex.pc);
+    }
+    Ok((0, Some(ex.store_data)))
+}
+
+/// Flushes every cache when a store of `len` bytes at `paddr` reaches the VirtIO MMIO
+/// window, whose DMA reads RAM behind the caches.
+fn flush_on_virtio_store(cpu: &mut Cpu, paddr: u64, len: u64) {
+    if paddr < 0x10002000 && paddr + len > 0x10001000 {
+        cpu.l1_d_cache.flush();
+        cpu.l2_cache.flush();
+        cpu.l2_i_cache.flush();
+        cpu.l3_cache.flush();
+    }
+}
+
+/// Hands a cacheable access to the L1-D MSHRs instead of stalling for its miss.
+///
+/// The pipeline stalls only while every MSHR is busy. A load's destination is
+/// marked pending until the line arrives, so only a consumer of the data waits
+/// for the miss; an access to a line already in flight shares its fill.
+fn issue_nonblocking(cpu: &mut Cpu, ex: &ExMemEntry, paddr: u64, latency: u64) {
+    let line = paddr & !(cpu.l1_d_cache.line_bytes() as u64 - 1);
+    let Some(grant) = cpu.mshrs.request(line, cpu.stats.cycles, latency) else {
+        return;
+    };
+
+    cpu.stall_cycles += grant.wait;
+    cpu.stats.stalls_mshr_full += grant.wait;
+    if grant.merged {
+        cpu.stats.mshr_merged_misses += 1;
+    } else if grant.overlapped {
+        cpu.stats.mshr_overlapped_misses += 1;
+    }
+
+    if ex.ctrl.mem_read && (ex.ctrl.fp_reg_write || (ex.ctrl.reg_write && ex.rd != 0)) {
+        cpu.mshrs
+            .mark_pending(ex.rd, ex.ctrl.fp_reg_write, grant.ready);
+    }
+}
@@ -5,15 +5,85 @@
 //! handles traps and interrupts (including delegation), and updates
 //! performance statistics. It also manages pipeline flushing upon exceptions.
 
-use crate::common::constants::{
-    DELEG_MEIP_BIT, DELEG_MSIP_BIT, DELEG_MTIP_BIT, DELEG_SEIP_BIT, DELEG_SSIP_BIT, DELEG_STIP_BIT,
-};
+use crate::common::constants::WFI_INSTRUCTION;
+use crate::common::error::Trap;
 use crate::core::Cpu;
 use crate::core::arch::csr;
 use crate::core::arch::mode::PrivilegeMode;
 use crate::core::arch::trap::TrapHandler;
 use crate::core::cpu::PC_TRACE_MAX;
-use crate::core::pipeline::signals::AluOp;
+use crate::core::cpu::history::RegUndo;
+use crate::core::pipeline::signals::{AluOp, AtomicOp};
+use crate::core::units::bru::BranchPredictor;
+use crate::core::units::bru::ras::RasCheckpoint;
+
+/// Interrupt pending bits in descending priority order (privileged spec §3.1.9).
+const INTERRUPT_PRIORITY: [u64; 6] = [
+    csr::MIP_MEIP,
+    csr::MIP_MSIP,
+    csr::MIP_MTIP,
+    csr::MIP_SEIP,
+    csr::MIP_SSIP,
+    csr::MIP_STIP,
+];
+
+/// Selects the highest-priority interrupt that should be taken this cycle.
+///
+/// Each pending-and-enabled interrupt is routed by `mideleg`: delegated causes
+/// target S-mode, all others target M-mode. Interrupts destined for M-mode are
+/// considered before those destined for S-mode, and within each target the
+/// fixed order in [`INTERRUPT_PRIORITY`] applies. A target is only eligible
+/// when the hart runs below it, or at it with the matching global enable
+/// (`mstatus.MIE` / `sstatus.SIE`) set.
+///
+/// # Arguments
+///
+/// * `cpu` - Reference to the CPU state.
+///
+/// # Returns
+///
+/// The interrupt trap to raise, or `None` if nothing is deliverable.
+fn pending_interrupt(cpu: &Cpu) -> Option<Trap> {
+    let pending = cpu.csrs.mip & cpu.csrs.mie;
+    let m_pending = pending & !cpu.csrs.mideleg;
+    let s_pending = pending & cpu.csrs.mideleg;
+
+    let m_enabled =
+        cpu.privilege < PrivilegeMode::Machine || (cpu.csrs.mstatus & csr::MSTATUS_MIE) != 0;
+    let s_enabled = cpu.privilege < PrivilegeMode::Supervisor
+        || (cpu.privilege == PrivilegeMode::Supervisor
+            && (cpu.csrs.mstatus & csr::MSTATUS_SIE) != 0);
+
+    let highest = |set: u64| INTERRUPT_PRIORITY.into_iter().find(|&bit| set & bit != 0);
+
+    m_enabled
+        .then(|| highest(m_pending))
+        .flatten()
+        .or_else(|| s_enabled.then(|| highest(s_pending)).flatten())
+        .map(TrapHandler::irq_to_trap)
+}
+
+/// Returns the RAS checkpoint of the oldest instruction in ID/EX or IF/ID.
+fn front_end_ras_checkpoint(cpu: &Cpu) -> Option<RasCheckpoint> {
+    cpu.id_ex
+        .entries
+        .first()
+        .map(|e| e.ras_checkpoint)
+        .or_else(|| cpu.if_id.entries.first().map(|e| e.ras_checkpoint))
+}
+
+/// Returns the RAS checkpoint of the oldest instruction in flight.
+///
+/// Restoring it undoes the speculative call and return updates that fetch
+/// made for every instruction in the pipeline.
+fn oldest_ras_checkpoint(cpu: &Cpu) -> Option<RasCheckpoint> {
+    cpu.mem_wb
+        .entries
+        .first()
+        .map(|e| e.ras_checkpoint)
+        .or_else(|| cpu.ex_mem.entries.first().map(|e| e.ras_checkpoint))
+        .or_else(|| front_end_ras_checkpoint(cpu))
+}
 
 /// Executes the writeback stage of the pipeline.
 ///
@@ -29,64 +99,33 @@ use crate::core::pipeline::signals::AluOp;
 ///
 /// - Writes ALU results, load data, or jump targets to destination registers
 /// - Detects pending interrupts and exceptions
-/// - Updates instruction retirement statistics
+/// - Updates instruction retirement statistics, the commit log, and the hotspot profile
 /// - Handles trap processing and privilege mode transitions
 /// - Flushes pipeline on trap events
 pub fn wb_stage(cpu: &mut Cpu) {
-    let mut trap_event: Option<(crate::common::error::Trap, u64)> = None;
+    let mut trap_event: Option<(Trap, u64)> = None;
+    // RAS state before the oldest instruction a trap squashes.
+    let mut ras_repair: Option<RasCheckpoint> = None;
+
+    // An xRET has already switched privilege and PC in execute, so its bundle must
+    // commit before an interrupt can be taken; squashing it would replay the xRET.
+    let holds_xret = cpu
+        .mem_wb
+        .entries
+        .iter()
+        .any(|e| e.ctrl.is_mret || e.ctrl.is_sret);
 
     if !cpu.mem_wb.entries.is_empty() || cpu.wfi_waiting {
         if cpu.interrupt_inhibit_one_cycle {
             cpu.interrupt_inhibit_one_cycle = false;
-        } else {
+        } else if !holds_xret {
             let interrupt_pc = if !cpu.mem_wb.entries.is_empty() {
                 cpu.mem_wb.entries[0].pc
             } else {
                 0
             };
 
-            let mip = cpu.csrs.mip;
-            let mie = cpu.csrs.mie;
-            let mstatus = cpu.csrs.mstatus;
-
-            let m_global_ie = (mstatus & csr::MSTATUS_MIE) != 0;
-            let s_global_ie = (mstatus & csr::MSTATUS_SIE) != 0;
-
-            let check =
-                |bit: u64, enable_bit: u64, deleg_bit: u64| -> Option<crate::common::error::Trap> {
-                    let pending = (mip & bit) != 0;
-                    let enabled = (mie & enable_bit) != 0;
-                    if !pending || !enabled {
-                        return None;
-                    }
-
-                    let delegated = (cpu.csrs.mideleg & deleg_bit) != 0;
-                    let target_priv = if delegated {
-                        PrivilegeMode::Supervisor
-                    } else {
-                        PrivilegeMode::Machine
-                    };
-
-                    if cpu.privilege.to_u8() < target_priv.to_u8() {
-                        return Some(TrapHandler::irq_to_trap(bit));
-                    }
-                    if cpu.privilege == target_priv {
-                        if target_priv == PrivilegeMode::Machine && m_global_ie {
-                            return Some(TrapHandler::irq_to_trap(bit));
-                        }
-                        if target_priv == PrivilegeMode::Supervisor && s_global_ie {
-                            return Some(TrapHandler::irq_to_trap(bit));
-                        }
-                    }
-                    None
-                };
-
-            let interrupt = check(csr::MIP_MEIP, csr::MIE_MEIP, 1 << DELEG_MEIP_BIT)
-                .or_else(|| check(csr::MIP_MSIP, csr::MIE_MSIP, 1 << DELEG_MSIP_BIT))
-                .or_else(|| check(csr::MIP_MTIP, csr::MIE_MTIE, 1 << DELEG_MTIP_BIT))
-                .or_else(|| check(csr::MIP_SEIP, csr::MIE_SEIP, 1 << DELEG_SEIP_BIT))
-                .or_else(|| check(csr::MIP_SSIP, csr::MIE_SSIP, 1 << DELEG_SSIP_BIT))
-                .or_else(|| check(csr::MIP_STIP, csr::MIE_STIE, 1 << DELEG_STIP_BIT));
+            let interrupt = pending_interrupt(cpu);
 
             if let Some(interrupt_trap) = interrupt {
                 let epc = if cpu.wfi_waiting {
@@ -102,6 +141,7 @@ pub fn wb_stage(cpu: &mut Cpu) {
                     );
                 }
                 trap_event = Some((interrupt_trap, epc));
+                ras_repair = oldest_ras_checkpoint(cpu);
             } else if cpu.wfi_waiting {
                 // WFI Wakeup Logic (without trap)
                 // If global interrupts are disabled, WFI can still wake up if a locally enabled
@@ -111,6 +151,12 @@ pub fn wb_stage(cpu: &mut Cpu) {
                 if (pending & enabled) != 0 {
                     cpu.wfi_waiting = false;
                     cpu.pc = cpu.wfi_pc;
+                    // Fetch kept running past the WFI; refetch from the resume PC.
+                    if let Some(cp) = front_end_ras_checkpoint(cpu) {
+                        cpu.branch_predictor.ras_restore(cp);
+                    }
+                    cpu.if_id = Default::default();
+                    cpu.id_ex = Default::default();
                 }
             }
         }
@@ -127,6 +173,7 @@ pub fn wb_stage(cpu: &mut Cpu) {
                 eprintln!("WB  pc={:#x} * TRAP DETECTED: {:?}", wb.pc, trap);
             }
             trap_event = Some((trap.clone(), wb.pc));
+            ras_repair = Some(wb.ras_checkpoint);
 
             cpu.mem_wb.entries.truncate(idx);
             break;
@@ -135,7 +182,7 @@ pub fn wb_stage(cpu: &mut Cpu) {
         processed_count = idx + 1;
 
         if cpu.trace {
-            eprintln!("WB  pc={:#x}", wb.pc);
+            eprintln!("WB  pc={}", cpu.symbolize(wb.pc));
         }
 
         cpu.pc_trace.push((wb.pc, wb.inst));
@@ -144,7 +191,7 @@ pub fn wb_stage(cpu: &mut Cpu) {
         }
 
         if wb.inst != 0 && wb.inst != 0x13 {
-            cpu.stats.instructions_retired += 1;
+            cpu.stats.instructions_retired = cpu.stats.instructions_retired.wrapping_add(1);
             if wb.ctrl.mem_read {
                 if wb.ctrl.fp_reg_write {
                     cpu.stats.inst_fp_load += 1;
@@ -179,6 +226,10 @@ pub fn wb_stage(cpu: &mut Cpu) {
                     | AluOp::FCvtLS
                     | AluOp::FCvtSW
                     | AluOp::FCvtSL
+                    | AluOp::FCvtWuS
+                    | AluOp::FCvtLuS
+                    | AluOp::FCvtSWu
+                    | AluOp::FCvtSLu
                     | AluOp::FCvtSD
                     | AluOp::FCvtDS
                     | AluOp::FMvToX
@@ -208,30 +259,76 @@ pub fn wb_stage(cpu: &mut Cpu) {
             }
         }
 
+        if cpu.undo.is_enabled() {
+            let reg = if wb.ctrl.fp_reg_write {
+                Some(RegUndo::Fp(wb.rd, cpu.regs.read_f(wb.rd)))
+            } else if wb.ctrl.reg_write && wb.rd != 0 {
+                Some(RegUndo::Int(wb.rd, cpu.regs.read(wb.rd)))
+            } else {
+                None
+            };
+            let wrote_mem =
+                wb.ctrl.mem_write || !matches!(wb.ctrl.atomic_op, AtomicOp::None | AtomicOp::Lr);
+            cpu.undo.commit(wb.pc, reg, wrote_mem);
+        }
+
         if wb.ctrl.fp_reg_write {
             cpu.regs.write_f(wb.rd, val);
+            // Same as `Cpu::mark_fs_dirty`; `cpu.mem_wb` is borrowed by the loop.
+            cpu.csrs.mstatus |= csr::MSTATUS_FS_DIRTY;
+            cpu.csrs.sstatus |= csr::MSTATUS_FS_DIRTY;
         } else if wb.ctrl.reg_write && wb.rd != 0 {
             cpu.regs.write(wb.rd, val);
         }
+
+        if wb.inst == WFI_INSTRUCTION && cpu.trap_on_wfi && cpu.direct_mode {
+            cpu.halt_on_wfi(wb.pc);
+            break;
+        }
     }
 
     if processed_count < cpu.mem_wb.entries.len() {
         cpu.mem_wb.entries.truncate(processed_count);
     }
 
+    // Lend the committed entries to each consumer, then put them back.
+    let committed = std::mem::take(&mut cpu.mem_wb.entries);
+    if cpu.retire_check.is_enabled() {
+        cpu.verify_commits(&committed);
+    }
+    if cpu.commit_log.is_some() {
+        cpu.log_commits(&committed);
+    }
+    if let Some(profiler) = cpu.profiler.as_mut() {
+        profiler.record(&committed, cpu.stats.cycles);
+    }
+    if cpu.commit_hook.is_some() {
+        cpu.run_commit_hook(&committed);
+    }
+    cpu.mem_wb.entries = committed;
+
     if let Some((trap, pc)) = trap_event {
         if cpu.trace {
             eprintln!("WB  * HANDLING TRAP: {:?} at PC {:#x}", trap, pc);
         }
+        // Undo the speculative RAS updates of the trapping instruction and
+        // everything younger, all of which are squashed.
+        if let Some(cp) = ras_repair {
+            cpu.branch_predictor.ras_restore(cp);
+        }
         cpu.if_id = Default::default();
         cpu.id_ex = Default::default();
         cpu.ex_mem = Default::default();
         cpu.wb_latch = Default::default();
 
         cpu.mem_wb = Default::default();
+        cpu.undo.discard_pending();
 
         let exit_code_before = cpu.exit_code.is_some();
         cpu.trap(trap, pc);
+        if cpu.retire_check.is_enabled() {
+            cpu.verify_trap(pc);
+        }
 
         if cpu.trace && !cpu.exit_code.is_some() {
             eprintln!("WB  * TRAP HANDLED, new PC={:#x}", cpu.pc);
@@ -0,0 +1,151 @@
+//! Symmetric Multiprocessing.
+//!
+//! This module runs several harts against one shared system. It provides:
+//! 1. **Shared system:** A single bus, RAM, and device set. Each hart keeps its own
+//!    registers, pipeline, caches, and TLBs; caches are timing models, so stores are
+//!    visible to the other harts as soon as they reach memory.
+//! 2. **Round-robin ticking:** Every system cycle ticks each hart once, in hart order.
+//!    Devices advance on hart 0's tick.
+//! 3. **Interrupts:** Each hart reads its own CLINT MSIP and MTIMECMP lines, so a hart
+//!    wakes another by writing its MSIP. External (PLIC) interrupts go to hart 0 only.
+//! 4. **Reservations:** After a hart's tick, every store, SC, and AMO it performed breaks
+//!    the LR reservations of the other harts on the same reservation set.
+//!
+//! A [`Cpu`] owns the system it runs on, so the system is moved into each hart before
+//! it is ticked or handed out; the other harts hold an empty stand-in meanwhile.
+
+use crate::common::SimError;
+use crate::config::{Config, ConfigError};
+use crate::core::Cpu;
+use crate::isa::abi;
+use crate::soc::System;
+
+/// Harts sharing one system, ticked in round-robin order.
+pub struct Smp {
+    harts: Vec<Cpu>,
+    /// Index of the hart currently holding the shared system.
+    owner: usize,
+}
+
+impl Smp {
+    /// Creates `config.system.harts` harts on `system`, all starting at `general.start_pc`.
+    ///
+    /// Software tells the harts apart through `mhartid`; the usual boot parks every hart but
+    /// hart 0 in WFI until it receives a software interrupt.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system the harts share; its CLINT should serve as many harts.
+    /// * `config` - Configuration each hart is built from.
+    ///
+    /// # Returns
+    ///
+    /// The harts, or the [`ConfigError`] raised building the first of them.
+    pub fn new(system: System, config: &Config) -> Result<Self, ConfigError> {
+        let mut system = system;
+        let mut harts = Vec::with_capacity(config.system.harts.max(1));
+        let shared = config.system.harts > 1;
+        for id in 0..config.system.harts.max(1) {
+            let mut cpu = Cpu::new(system, config)?;
+            cpu.hart_id = id;
+            cpu.remote_stores = shared.then(Vec::new);
+            system = std::mem::replace(&mut cpu.bus, System::detached(config));
+            harts.push(cpu);
+        }
+        harts[0].bus = system;
+        Ok(Self { harts, owner: 0 })
+    }
+
+    /// Returns the number of harts.
+    pub fn len(&self) -> usize {
+        self.harts.len()
+    }
+
+    /// Returns `true` if there are no harts (never the case for a constructed `Smp`).
+    pub fn is_empty(&self) -> bool {
+        self.harts.is_empty()
+    }
+
+    /// Returns hart `id`, with the shared system attached so its bus can be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not below [`len`](Self::len).
+    pub fn hart(&mut self, id: usize) -> &mut Cpu {
+        self.attach(id);
+        &mut self.harts[id]
+    }
+
+    /// Starts every secondary hart where hart 0 starts.
+    ///
+    /// Call after loading software through hart 0. Each secondary takes hart 0's PC,
+    /// privilege, mode, `mepc`, and `a1`/`a2`, with its own index in `a0`, as the
+    /// OpenSBI boot protocol expects of harts entering firmware together.
+    pub fn sync_boot_state(&mut self) {
+        let (boot, secondaries) = self.harts.split_at_mut(1);
+        let boot = &boot[0];
+        for cpu in secondaries {
+            cpu.pc = boot.pc;
+            cpu.privilege = boot.privilege;
+            cpu.direct_mode = boot.direct_mode;
+            cpu.csrs.mepc = boot.csrs.mepc;
+            cpu.regs.write(abi::REG_A0, cpu.hart_id as u64);
+            cpu.regs.write(abi::REG_A1, boot.regs.read(abi::REG_A1));
+            cpu.regs.write(abi::REG_A2, boot.regs.read(abi::REG_A2));
+        }
+    }
+
+    /// Advances every hart by one cycle, hart 0 first.
+    ///
+    /// # Returns
+    ///
+    /// The first error a hart reports; harts after it are not ticked this cycle.
+    pub fn tick(&mut self) -> Result<(), SimError> {
+        for id in 0..self.harts.len() {
+            self.attach(id);
+            let result = self.harts[id].tick();
+            self.break_remote_reservations(id);
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Returns the exit code of the first hart that has finished, if any.
+    pub fn exit_code(&self) -> Option<u64> {
+        self.harts.iter().find_map(|cpu| cpu.exit_code)
+    }
+
+    /// Takes the exit code of the first hart that has finished, if any.
+    pub fn take_exit(&mut self) -> Option<u64> {
+        self.harts.iter_mut().find_map(Cpu::take_exit)
+    }
+
+    /// Drops the reservations of other harts covered by the stores of hart `id`.
+    fn break_remote_reservations(&mut self, id: usize) {
+        let Some(mut stores) = self.harts[id].remote_stores.take() else {
+            return;
+        };
+        for (other, cpu) in self.harts.iter_mut().enumerate() {
+            if other != id && stores.iter().any(|&paddr| cpu.reservation_covers(paddr)) {
+                cpu.load_reservation = None;
+            }
+        }
+        stores.clear();
+        self.harts[id].remote_stores = Some(stores);
+    }
+
+    /// Moves the shared system into hart `id`.
+    fn attach(&mut self, id: usize) {
+        if id == self.owner {
+            return;
+        }
+        let (low, high) = self.harts.split_at_mut(id.max(self.owner));
+        let (a, b) = if id < self.owner {
+            (&mut low[id], &mut high[0])
+        } else {
+            (&mut low[self.owner], &mut high[0])
+        };
+        std::mem::swap(&mut a.bus, &mut b.bus);
+        self.owner = id;
+    }
+}
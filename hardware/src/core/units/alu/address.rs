@@ -0,0 +1,30 @@
+//! ALU address generation (Zba extension).
+//!
+//! Implements the shift-and-add (`sh1add`, `sh2add`, `sh3add`) and
+//! unsigned-word (`add.uw`, `slli.uw`) instructions used for array indexing.
+//! All results are full 64-bit values; the `.uw` forms zero-extend the low
+//! 32 bits of `rs1` before operating.
+
+use crate::core::pipeline::signals::AluOp;
+
+/// Executes an address generation operation.
+///
+/// # Arguments
+///
+/// * `op` - The ALU operation to perform (must be a Zba variant).
+/// * `a`  - First operand (`rs1`, the index).
+/// * `b`  - Second operand (`rs2` base, or the shift immediate for `SlliUw`).
+///
+/// # Returns
+///
+/// The 64-bit result. Returns `0` for non-Zba opcodes.
+pub fn execute(op: AluOp, a: u64, b: u64) -> u64 {
+    match op {
+        AluOp::Sh1Add => (a << 1).wrapping_add(b),
+        AluOp::Sh2Add => (a << 2).wrapping_add(b),
+        AluOp::Sh3Add => (a << 3).wrapping_add(b),
+        AluOp::AddUw => (a as u32 as u64).wrapping_add(b),
+        AluOp::SlliUw => (a as u32 as u64) << (b & 0x3F),
+        _ => 0,
+    }
+}
@@ -0,0 +1,52 @@
+//! ALU basic bit-manipulation (Zbb extension).
+//!
+//! Implements the count (`clz`, `ctz`, `cpop`), min/max, sign/zero-extension,
+//! and byte permutation (`orc.b`, `rev8`) instructions. Only the count
+//! operations have `.W` forms; they operate on the low 32 bits of `a`.
+
+use crate::core::pipeline::signals::AluOp;
+
+/// Executes a basic bit-manipulation operation.
+///
+/// # Arguments
+///
+/// * `op`   - The ALU operation to perform (must be a Zbb variant).
+/// * `a`    - First operand (64-bit value).
+/// * `b`    - Second operand (64-bit value, used by min/max only).
+/// * `is32` - If true, count within the low 32 bits (CLZW, CTZW, CPOPW).
+///
+/// # Returns
+///
+/// The 64-bit result. Returns `0` for non-Zbb opcodes.
+pub fn execute(op: AluOp, a: u64, b: u64, is32: bool) -> u64 {
+    match op {
+        AluOp::Clz if is32 => (a as u32).leading_zeros() as u64,
+        AluOp::Clz => a.leading_zeros() as u64,
+        AluOp::Ctz if is32 => (a as u32).trailing_zeros() as u64,
+        AluOp::Ctz => a.trailing_zeros() as u64,
+        AluOp::Cpop if is32 => (a as u32).count_ones() as u64,
+        AluOp::Cpop => a.count_ones() as u64,
+        AluOp::Min => (a as i64).min(b as i64) as u64,
+        AluOp::Max => (a as i64).max(b as i64) as u64,
+        AluOp::Minu => a.min(b),
+        AluOp::Maxu => a.max(b),
+        AluOp::OrcB => orc_b(a),
+        AluOp::Rev8 => a.swap_bytes(),
+        AluOp::SextB => a as i8 as i64 as u64,
+        AluOp::SextH => a as i16 as i64 as u64,
+        AluOp::ZextH => a as u16 as u64,
+        _ => 0,
+    }
+}
+
+/// Sets each byte to `0xFF` if any of its bits are set, otherwise `0x00`.
+fn orc_b(a: u64) -> u64 {
+    (0..8).fold(0, |acc, i| {
+        let byte = (a >> (i * 8)) & 0xFF;
+        if byte != 0 {
+            acc | (0xFF << (i * 8))
+        } else {
+            acc
+        }
+    })
+}
@@ -0,0 +1,41 @@
+//! ALU carry-less multiplication (Zbc extension).
+//!
+//! Implements `clmul`, `clmulh`, and `clmulr`: polynomial multiplication over
+//! GF(2), where partial products are combined with XOR instead of addition.
+//! These are RV64-only operations and always use the full 64-bit operands.
+
+use crate::core::pipeline::signals::AluOp;
+
+/// Executes a carry-less multiply operation.
+///
+/// # Arguments
+///
+/// * `op` - The ALU operation to perform (must be a Clmul variant).
+/// * `a`  - First operand (64-bit value).
+/// * `b`  - Second operand (64-bit value).
+///
+/// # Returns
+///
+/// The selected 64-bit slice of the 128-bit carry-less product:
+/// bits 63..0 for `Clmul`, 127..64 for `Clmulh`, and 126..63 for `Clmulr`.
+/// Returns `0` for non-Zbc opcodes.
+pub fn execute(op: AluOp, a: u64, b: u64) -> u64 {
+    let product = clmul128(a, b);
+    match op {
+        AluOp::Clmul => product as u64,
+        AluOp::Clmulh => (product >> 64) as u64,
+        AluOp::Clmulr => (product >> 63) as u64,
+        _ => 0,
+    }
+}
+
+/// Computes the full 128-bit carry-less product of two 64-bit operands.
+fn clmul128(a: u64, b: u64) -> u128 {
+    let mut acc = 0u128;
+    for i in 0..64 {
+        if (b >> i) & 1 != 0 {
+            acc ^= (a as u128) << i;
+        }
+    }
+    acc
+}
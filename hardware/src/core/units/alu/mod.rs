@@ -3,16 +3,29 @@
 //! This module implements the integer ALU used in the Execute stage.
 //! It handles standard arithmetic, logical operations, and shifts
 //! for both 32-bit and 64-bit operands. It also implements the
-//! Multiply/Divide (M) extension operations.
+//! Multiply/Divide (M) extension operations, the Zba address generation and
+//! Zbb bit-manipulation instructions, and the Zbc carry-less multiplies.
 //!
 //! Operations are organized into submodules by category:
 //! - [`arithmetic`]: Add, Sub, Mul, Mulh, Mulhsu, Mulhu, Div, Divu, Rem, Remu
 //! - [`logic`]:      Or, And, Xor, Slt, Sltu
 //! - [`shifts`]:     Sll, Srl, Sra
+//! - [`address`]:    Sh1Add, Sh2Add, Sh3Add, AddUw, SlliUw
+//! - [`bitmanip`]:   Clz, Ctz, Cpop, Min, Max, Minu, Maxu, OrcB, Rev8, SextB, SextH, ZextH
+//! - [`carryless`]:  Clmul, Clmulh, Clmulr
+
+/// Address generation operations (shNadd, add.uw, slli.uw).
+pub mod address;
 
 /// Integer arithmetic operations (add, subtract, multiply, divide).
 pub mod arithmetic;
 
+/// Basic bit-manipulation operations (clz, cpop, min/max, rev8, ...).
+pub mod bitmanip;
+
+/// Carry-less multiply operations (clmul, clmulh, clmulr).
+pub mod carryless;
+
 /// Bitwise logical and comparison operations (or, and, xor, slt).
 pub mod logic;
 
@@ -95,6 +108,28 @@ impl Alu {
             // Shifts: sll, srl, sra
             AluOp::Sll | AluOp::Srl | AluOp::Sra => shifts::execute(op, a, b, is32),
 
+            // Address generation (Zba)
+            AluOp::Sh1Add | AluOp::Sh2Add | AluOp::Sh3Add | AluOp::AddUw | AluOp::SlliUw => {
+                address::execute(op, a, b)
+            }
+
+            // Basic bit-manipulation (Zbb)
+            AluOp::Clz
+            | AluOp::Ctz
+            | AluOp::Cpop
+            | AluOp::Min
+            | AluOp::Max
+            | AluOp::Minu
+            | AluOp::Maxu
+            | AluOp::OrcB
+            | AluOp::Rev8
+            | AluOp::SextB
+            | AluOp::SextH
+            | AluOp::ZextH => bitmanip::execute(op, a, b, is32),
+
+            // Carry-less multiply: clmul, clmulh, clmulr
+            AluOp::Clmul | AluOp::Clmulh | AluOp::Clmulr => carryless::execute(op, a, b),
+
             // Non-integer operations (FP, etc.) are not handled here.
             _ => 0,
         }
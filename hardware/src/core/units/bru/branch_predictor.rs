@@ -5,6 +5,9 @@
 //! predicting conditional branches, indirect jumps (via BTB), and function
 //! returns (via RAS).
 
+use super::btb::BranchType;
+use super::ras::RasCheckpoint;
+
 /// Trait for branch prediction algorithms.
 ///
 /// Defines the interface that all branch prediction implementations
@@ -47,17 +50,64 @@ pub trait BranchPredictor {
     /// The predicted target address if available in the BTB, `None` otherwise.
     fn predict_btb(&self, pc: u64) -> Option<u64>;
 
+    /// Returns the kind of control transfer the BTB recorded for a PC.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Program counter of the branch or jump instruction
+    ///
+    /// # Returns
+    ///
+    /// The recorded [`BranchType`], or `None` on a BTB miss.
+    fn branch_type(&self, pc: u64) -> Option<BranchType>;
+
+    /// Trains the BTB with a resolved jump.
+    ///
+    /// Called when a `JAL` or `JALR` executes, so later fetches of `pc` can
+    /// predict its target and know whether it is a call, return or jump.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Program counter of the jump instruction
+    /// * `target` - The resolved target address
+    /// * `kind` - The kind of jump at `pc`
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType);
+
+    /// Predicts the target of a `jalr` that is not a return.
+    ///
+    /// When the BTB types `pc` as an indirect jump or call, the indirect
+    /// predictor picks among the targets seen in the current history
+    /// context, falling back to the BTB target.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Program counter of the jump instruction
+    ///
+    /// # Returns
+    ///
+    /// The predicted target address, or `None` if neither structure has one.
+    fn predict_indirect(&self, pc: u64) -> Option<u64>;
+
+    /// Trains the indirect predictor with a resolved `jalr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Program counter of the jump instruction
+    /// * `target` - The resolved target address
+    fn update_indirect(&mut self, pc: u64, target: u64);
+
     /// Records a function call for return address prediction.
     ///
-    /// Called when a call instruction (JAL/JALR with rd=ra) is executed
-    /// to push the return address onto the return address stack.
+    /// Called when a call instruction (a jump linking through `ra` or `t0`)
+    /// is fetched to speculatively push the return address onto the return
+    /// address stack.
     ///
     /// # Arguments
     ///
     /// * `pc` - Program counter of the call instruction
     /// * `ret_addr` - Return address (pc + instruction_size)
-    /// * `target` - Target address of the call
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64);
+    /// * `target` - Target address of the call, if known at fetch
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>);
 
     /// Predicts the return address for a return instruction.
     ///
@@ -69,7 +119,17 @@ pub trait BranchPredictor {
 
     /// Records a function return for return address prediction.
     ///
-    /// Called when a return instruction (JALR with rd=zero, rs1=ra) is
-    /// executed to pop the return address from the return address stack.
+    /// Called when a return instruction (JALR reading but not writing a
+    /// link register) is fetched to speculatively pop the return address stack.
     fn on_return(&mut self);
+
+    /// Captures the return address stack state before a speculative update.
+    fn ras_checkpoint(&self) -> RasCheckpoint;
+
+    /// Repairs the return address stack after a misprediction flush.
+    ///
+    /// # Arguments
+    ///
+    /// * `cp` - Checkpoint taken before the oldest squashed instruction's update.
+    fn ras_restore(&mut self, cp: RasCheckpoint);
 }
@@ -2,20 +2,70 @@
 //!
 //! The BTB is a direct-mapped cache that stores target addresses for control flow
 //! instructions. It allows the fetch stage to predict the target of a branch or
-//! jump before the instruction is decoded.
+//! jump before the instruction is decoded. Each entry also records the kind of
+//! control transfer, so fetch can send returns to the return address stack
+//! rather than trusting a stale target.
+
+use crate::common::constants::{OPCODE_MASK, RD_MASK, RD_SHIFT, RS1_MASK, RS1_SHIFT};
+use crate::isa::rv64i::opcodes;
+use serde::{Deserialize, Serialize};
+
+/// Kind of control-flow instruction held in a BTB entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BranchType {
+    /// Conditional branch (`BEQ`, `BNE`, ...).
+    #[default]
+    Conditional,
+    /// Jump that links through `ra` or `t0` (pushes the RAS).
+    Call,
+    /// `JALR` through `ra` or `t0` that does not link (pops the RAS).
+    Return,
+    /// Any other `JALR`.
+    IndirectJump,
+    /// `JAL` that does not link.
+    DirectJump,
+}
+
+impl BranchType {
+    /// Classifies a 32-bit instruction using the RAS hints of the RISC-V spec.
+    ///
+    /// `x1` and `x5` are link registers: a jump writing one is a call, and a
+    /// `JALR` reading one without writing one is a return.
+    ///
+    /// # Returns
+    ///
+    /// The branch type, or `None` if `inst` is not a branch or jump.
+    pub fn classify(inst: u32) -> Option<Self> {
+        let is_link = |reg: u32| reg == 1 || reg == 5;
+        let rd = (inst >> RD_SHIFT) & RD_MASK;
+        let rs1 = (inst >> RS1_SHIFT) & RS1_MASK;
+        match inst & OPCODE_MASK {
+            opcodes::OP_BRANCH => Some(Self::Conditional),
+            opcodes::OP_JAL if is_link(rd) => Some(Self::Call),
+            opcodes::OP_JAL => Some(Self::DirectJump),
+            opcodes::OP_JALR if is_link(rd) => Some(Self::Call),
+            opcodes::OP_JALR if is_link(rs1) => Some(Self::Return),
+            opcodes::OP_JALR => Some(Self::IndirectJump),
+            _ => None,
+        }
+    }
+}
 
 /// An entry in the Branch Target Buffer.
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
 struct BtbEntry {
     /// The tag used to verify if this entry corresponds to the requested PC.
     tag: u64,
     /// The predicted target address.
     target: u64,
+    /// The kind of instruction at `tag`.
+    kind: BranchType,
     /// Indicates if this entry contains valid data.
     valid: bool,
 }
 
 /// Branch Target Buffer structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Btb {
     /// The table of BTB entries.
     table: Vec<BtbEntry>,
@@ -64,6 +114,16 @@ impl Btb {
         }
     }
 
+    /// Looks up the branch type recorded for the given program counter.
+    ///
+    /// # Returns
+    ///
+    /// The type stored with a valid, tag-matching entry, otherwise `None`.
+    pub fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        let e = self.table[self.index(pc)];
+        (e.valid && e.tag == pc).then_some(e.kind)
+    }
+
     /// Updates the BTB with a new target address for a specific program counter.
     ///
     /// Writes a new entry or overwrites an existing one at the calculated index.
@@ -72,11 +132,13 @@ impl Btb {
     ///
     /// * `pc` - The program counter of the branch or jump.
     /// * `target` - The resolved target address.
-    pub fn update(&mut self, pc: u64, target: u64) {
+    /// * `kind` - The kind of instruction at `pc`.
+    pub fn update(&mut self, pc: u64, target: u64, kind: BranchType) {
         let idx = self.index(pc);
         self.table[idx] = BtbEntry {
             tag: pc,
             target,
+            kind,
             valid: true,
         };
     }
@@ -14,7 +14,13 @@
 //! - **Best Case:** Correlated branches where outcome depends on recent history
 //! - **Worst Case:** Uncorrelated branches or history length too short/long for pattern
 
-use super::{BranchPredictor, btb::Btb, ras::Ras};
+use super::{
+    BranchPredictor,
+    btb::{BranchType, Btb},
+    ittage::IndirectPredictor,
+    ras::{Ras, RasCheckpoint},
+};
+use serde::{Deserialize, Serialize};
 
 /// Size of the Pattern History Table (2^12 entries).
 const TABLE_BITS: usize = 12;
@@ -22,6 +28,7 @@ const TABLE_BITS: usize = 12;
 const TABLE_SIZE: usize = 1 << TABLE_BITS;
 
 /// GShare Predictor structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GSharePredictor {
     /// Global History Register storing recent branch outcomes.
     ghr: u64,
@@ -31,6 +38,8 @@ pub struct GSharePredictor {
     btb: Btb,
     /// Return Address Stack.
     ras: Ras,
+    /// Indirect target predictor for `jalr` jumps and calls.
+    indirect: IndirectPredictor,
 }
 
 impl GSharePredictor {
@@ -41,6 +50,7 @@ impl GSharePredictor {
             pht: vec![1; TABLE_SIZE],
             btb: Btb::new(btb_size),
             ras: Ras::new(ras_size),
+            indirect: IndirectPredictor::new(),
         }
     }
 
@@ -87,7 +97,7 @@ impl BranchPredictor for GSharePredictor {
         self.ghr = ((self.ghr << 1) | if taken { 1 } else { 0 }) & ((TABLE_SIZE as u64) - 1);
 
         if let Some(tgt) = target {
-            self.btb.update(pc, tgt);
+            self.btb.update(pc, tgt, BranchType::Conditional);
         }
     }
 
@@ -96,10 +106,38 @@ impl BranchPredictor for GSharePredictor {
         self.btb.lookup(pc)
     }
 
+    /// Returns the branch type the BTB holds for `pc`.
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        self.btb.branch_type(pc)
+    }
+
+    /// Records a resolved jump target and its type in the BTB.
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        self.btb.update(pc, target, kind);
+    }
+
+    /// Predicts a `jalr` target, from the indirect predictor if the BTB
+    /// types it as an indirect jump or call.
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self.btb.branch_type(pc) {
+            Some(BranchType::IndirectJump | BranchType::Call) => {
+                self.indirect.predict(pc).or_else(|| self.btb.lookup(pc))
+            }
+            _ => self.btb.lookup(pc),
+        }
+    }
+
+    /// Trains the indirect predictor with a resolved `jalr` target.
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        self.indirect.update(pc, target);
+    }
+
     /// Handles a function call by pushing the return address to the RAS.
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         self.ras.push(ret_addr);
-        self.btb.update(pc, target);
+        if let Some(target) = target {
+            self.btb.update(pc, target, BranchType::Call);
+        }
     }
 
     /// Predicts the return address using the RAS.
@@ -111,4 +149,14 @@ impl BranchPredictor for GSharePredictor {
     fn on_return(&mut self) {
         self.ras.pop();
     }
+
+    /// Snapshots the RAS top-of-stack.
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        self.ras.checkpoint()
+    }
+
+    /// Restores the RAS top-of-stack from a snapshot.
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        self.ras.restore(cp);
+    }
 }
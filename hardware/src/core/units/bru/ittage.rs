@@ -0,0 +1,182 @@
+//! ITTAGE-style Indirect Branch Predictor.
+//!
+//! Indirect jumps (`jalr` through a computed register) often cycle through
+//! several targets, which a single-target BTB entry can never follow. This
+//! predictor keeps tagged target tables indexed by the PC XOR a folded global
+//! path history of recent indirect targets, with geometrically increasing
+//! history lengths. The longest matching table provides the prediction, so
+//! one PC can map to a different target in each history context.
+//!
+//! The history is updated when an indirect jump resolves in execute, so the
+//! history seen at fetch and at update agree unless two indirect jumps are in
+//! flight at once.
+//!
+//! # Performance
+//!
+//! - **Time Complexity:**
+//!   - `predict()`: O(T) where T is the number of tagged tables
+//!   - `update()`: O(T)
+//! - **Space Complexity:** O(T * 2^N) entries of (tag, target, counters)
+//! - **Best Case:** Targets correlated with the preceding indirect targets
+//!   (virtual dispatch, interpreter loops, switch tables in a loop)
+//! - **Worst Case:** Targets driven by data with no history correlation
+
+use serde::{Deserialize, Serialize};
+
+/// Log2 of the number of entries in each tagged table.
+const TABLE_BITS: u32 = 8;
+/// Number of entries in each tagged table.
+const TABLE_SIZE: usize = 1 << TABLE_BITS;
+/// Width of the partial tag stored in each entry.
+const TAG_BITS: u32 = 10;
+/// History bits contributed by each resolved indirect target.
+const TARGET_HIST_BITS: u32 = 4;
+/// History lengths (in bits) of the tagged tables, shortest first.
+const HIST_LENGTHS: [u32; 4] = [8, 16, 32, 64];
+/// Saturation value of the confidence and useful counters.
+const COUNTER_MAX: u8 = 3;
+
+/// An entry in a tagged target table.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct IttageEntry {
+    /// Partial tag from the PC and history.
+    tag: u16,
+    /// Predicted target address.
+    target: u64,
+    /// Confidence in `target`; a wrong prediction at zero replaces it.
+    conf: u8,
+    /// Usefulness; entries at zero may be reallocated.
+    useful: u8,
+    /// Indicates if this entry contains valid data.
+    valid: bool,
+}
+
+/// ITTAGE-style indirect target predictor.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IndirectPredictor {
+    /// Tagged tables, one per entry of [`HIST_LENGTHS`].
+    tables: Vec<Vec<IttageEntry>>,
+    /// Global path history of resolved indirect targets.
+    hist: u64,
+}
+
+impl Default for IndirectPredictor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IndirectPredictor {
+    /// Creates an empty indirect predictor.
+    pub fn new() -> Self {
+        Self {
+            tables: vec![vec![IttageEntry::default(); TABLE_SIZE]; HIST_LENGTHS.len()],
+            hist: 0,
+        }
+    }
+
+    /// Folds the low `len` bits of the history down to `bits` bits by XOR.
+    fn fold(&self, len: u32, bits: u32) -> u64 {
+        let mut h = if len >= 64 {
+            self.hist
+        } else {
+            self.hist & ((1u64 << len) - 1)
+        };
+        let mut folded = 0;
+        while h != 0 {
+            folded ^= h & ((1u64 << bits) - 1);
+            h >>= bits;
+        }
+        folded
+    }
+
+    /// Returns the index and tag of `pc` in tagged table `t`.
+    fn slot(&self, t: usize, pc: u64) -> (usize, u16) {
+        let len = HIST_LENGTHS[t];
+        let pc = pc >> 1;
+        let index =
+            (pc ^ (pc >> TABLE_BITS) ^ self.fold(len, TABLE_BITS)) as usize & (TABLE_SIZE - 1);
+        let tag = (pc ^ (self.fold(len, TAG_BITS) << 1) ^ t as u64) & ((1 << TAG_BITS) - 1);
+        (index, tag as u16)
+    }
+
+    /// Returns the longest-history table with an entry matching `pc`.
+    fn provider(&self, pc: u64) -> Option<(usize, usize)> {
+        (0..self.tables.len()).rev().find_map(|t| {
+            let (idx, tag) = self.slot(t, pc);
+            let e = &self.tables[t][idx];
+            (e.valid && e.tag == tag).then_some((t, idx))
+        })
+    }
+
+    /// Predicts the target of the indirect jump at `pc`.
+    ///
+    /// # Returns
+    ///
+    /// The target from the longest matching history, or `None` if no tagged
+    /// table holds `pc` in the current context.
+    pub fn predict(&self, pc: u64) -> Option<u64> {
+        self.provider(pc).map(|(t, idx)| self.tables[t][idx].target)
+    }
+
+    /// Trains the predictor with the resolved target of the jump at `pc`.
+    ///
+    /// Strengthens a correct provider. On a misprediction the provider loses
+    /// confidence (or its target, once confidence is exhausted), and a new
+    /// entry is allocated in a longer-history table so the context that
+    /// caused the miss gets its own target. Finally `target` is shifted into
+    /// the path history.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc` - Program counter of the indirect jump
+    /// * `target` - The resolved target address
+    pub fn update(&mut self, pc: u64, target: u64) {
+        let provider = self.provider(pc);
+        let correct = provider.is_some_and(|(t, idx)| self.tables[t][idx].target == target);
+
+        if let Some((t, idx)) = provider {
+            let e = &mut self.tables[t][idx];
+            if correct {
+                e.conf = (e.conf + 1).min(COUNTER_MAX);
+                e.useful = (e.useful + 1).min(COUNTER_MAX);
+            } else if e.conf > 0 {
+                e.conf -= 1;
+            } else {
+                e.target = target;
+                e.useful = e.useful.saturating_sub(1);
+            }
+        }
+
+        if !correct {
+            self.allocate(pc, target, provider.map_or(0, |(t, _)| t + 1));
+        }
+
+        let bits =
+            ((target >> 2) ^ (target >> (2 + TARGET_HIST_BITS))) & ((1 << TARGET_HIST_BITS) - 1);
+        self.hist = (self.hist << TARGET_HIST_BITS) | bits;
+    }
+
+    /// Allocates `target` in the first table at or above `from` whose slot is
+    /// not useful; if every candidate is useful, ages them instead.
+    fn allocate(&mut self, pc: u64, target: u64, from: usize) {
+        for t in from..self.tables.len() {
+            let (idx, tag) = self.slot(t, pc);
+            if self.tables[t][idx].useful == 0 {
+                self.tables[t][idx] = IttageEntry {
+                    tag,
+                    target,
+                    conf: 0,
+                    useful: 0,
+                    valid: true,
+                };
+                return;
+            }
+        }
+        for t in from..self.tables.len() {
+            let (idx, _) = self.slot(t, pc);
+            let e = &mut self.tables[t][idx];
+            e.useful = e.useful.saturating_sub(1);
+        }
+    }
+}
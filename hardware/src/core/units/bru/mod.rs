@@ -15,6 +15,9 @@ pub mod btb;
 /// Global history branch predictor (gshare algorithm).
 pub mod gshare;
 
+/// ITTAGE-style indirect branch target predictor.
+pub mod ittage;
+
 /// Perceptron-based neural branch predictor.
 pub mod perceptron;
 
@@ -31,13 +34,15 @@ pub mod tage;
 pub mod tournament;
 
 use self::{
-    gshare::GSharePredictor, perceptron::PerceptronPredictor, static_bp::StaticPredictor,
-    tage::TagePredictor, tournament::TournamentPredictor,
+    btb::BranchType, gshare::GSharePredictor, perceptron::PerceptronPredictor, ras::RasCheckpoint,
+    static_bp::StaticPredictor, tage::TagePredictor, tournament::TournamentPredictor,
 };
 use crate::config::{BranchPredictor as BpType, Config};
+use serde::{Deserialize, Serialize};
 
 /// Enum wrapper for static dispatch of Branch Predictors.
 /// This avoids vtable lookups in the critical fetch loop.
+#[derive(Clone, Serialize, Deserialize)]
 pub enum BranchPredictorWrapper {
     Static(StaticPredictor),
     GShare(GSharePredictor),
@@ -122,11 +127,59 @@ impl BranchPredictor for BranchPredictorWrapper {
         }
     }
 
+    /// Returns the branch type the active predictor's BTB holds for `pc`.
+    #[inline(always)]
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        match self {
+            Self::Static(bp) => bp.branch_type(pc),
+            Self::GShare(bp) => bp.branch_type(pc),
+            Self::Tournament(bp) => bp.branch_type(pc),
+            Self::Tage(bp) => bp.branch_type(pc),
+            Self::Perceptron(bp) => bp.branch_type(pc),
+        }
+    }
+
+    /// Trains the active predictor's BTB with a resolved jump.
+    #[inline(always)]
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        match self {
+            Self::Static(bp) => bp.update_btb(pc, target, kind),
+            Self::GShare(bp) => bp.update_btb(pc, target, kind),
+            Self::Tournament(bp) => bp.update_btb(pc, target, kind),
+            Self::Tage(bp) => bp.update_btb(pc, target, kind),
+            Self::Perceptron(bp) => bp.update_btb(pc, target, kind),
+        }
+    }
+
+    /// Predicts a `jalr` target with the active predictor's indirect predictor.
+    #[inline(always)]
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self {
+            Self::Static(bp) => bp.predict_indirect(pc),
+            Self::GShare(bp) => bp.predict_indirect(pc),
+            Self::Tournament(bp) => bp.predict_indirect(pc),
+            Self::Tage(bp) => bp.predict_indirect(pc),
+            Self::Perceptron(bp) => bp.predict_indirect(pc),
+        }
+    }
+
+    /// Trains the active predictor's indirect predictor with a resolved `jalr`.
+    #[inline(always)]
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        match self {
+            Self::Static(bp) => bp.update_indirect(pc, target),
+            Self::GShare(bp) => bp.update_indirect(pc, target),
+            Self::Tournament(bp) => bp.update_indirect(pc, target),
+            Self::Tage(bp) => bp.update_indirect(pc, target),
+            Self::Perceptron(bp) => bp.update_indirect(pc, target),
+        }
+    }
+
     /// Records a function call for return address prediction.
     ///
-    /// Pushes the return address onto the RAS when a call instruction is executed.
+    /// Pushes the return address onto the RAS when a call instruction is fetched.
     #[inline(always)]
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         match self {
             Self::Static(bp) => bp.on_call(pc, ret_addr, target),
             Self::GShare(bp) => bp.on_call(pc, ret_addr, target),
@@ -153,7 +206,7 @@ impl BranchPredictor for BranchPredictorWrapper {
 
     /// Records a function return for RAS management.
     ///
-    /// Pops the return address from the RAS when a return instruction is executed.
+    /// Pops the return address from the RAS when a return instruction is fetched.
     #[inline(always)]
     fn on_return(&mut self) {
         match self {
@@ -164,4 +217,28 @@ impl BranchPredictor for BranchPredictorWrapper {
             Self::Perceptron(bp) => bp.on_return(),
         }
     }
+
+    /// Snapshots the RAS top-of-stack of the active predictor.
+    #[inline(always)]
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        match self {
+            Self::Static(bp) => bp.ras_checkpoint(),
+            Self::GShare(bp) => bp.ras_checkpoint(),
+            Self::Tournament(bp) => bp.ras_checkpoint(),
+            Self::Tage(bp) => bp.ras_checkpoint(),
+            Self::Perceptron(bp) => bp.ras_checkpoint(),
+        }
+    }
+
+    /// Repairs the RAS of the active predictor from a snapshot.
+    #[inline(always)]
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        match self {
+            Self::Static(bp) => bp.ras_restore(cp),
+            Self::GShare(bp) => bp.ras_restore(cp),
+            Self::Tournament(bp) => bp.ras_restore(cp),
+            Self::Tage(bp) => bp.ras_restore(cp),
+            Self::Perceptron(bp) => bp.ras_restore(cp),
+        }
+    }
 }
@@ -4,8 +4,14 @@
 //! Instead of saturating counters, it uses a table of weight vectors. The
 //! prediction is the dot product of the weights and the history vector.
 
-use super::{BranchPredictor, btb::Btb, ras::Ras};
+use super::{
+    BranchPredictor,
+    btb::{BranchType, Btb},
+    ittage::IndirectPredictor,
+    ras::{Ras, RasCheckpoint},
+};
 use crate::config::PerceptronConfig;
+use serde::{Deserialize, Serialize};
 
 /// Coefficient used to calculate the training threshold.
 const THETA_COEFF: f64 = 1.93;
@@ -13,6 +19,7 @@ const THETA_COEFF: f64 = 1.93;
 const THETA_BIAS: f64 = 14.0;
 
 /// Perceptron Predictor structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PerceptronPredictor {
     /// Global History Register.
     ghr: u64,
@@ -30,6 +37,8 @@ pub struct PerceptronPredictor {
     btb: Btb,
     /// Return Address Stack.
     ras: Ras,
+    /// Indirect target predictor for `jalr` jumps and calls.
+    indirect: IndirectPredictor,
 }
 
 impl PerceptronPredictor {
@@ -49,6 +58,7 @@ impl PerceptronPredictor {
             threshold,
             btb: Btb::new(btb_size),
             ras: Ras::new(ras_size),
+            indirect: IndirectPredictor::new(),
         }
     }
 
@@ -127,7 +137,7 @@ impl BranchPredictor for PerceptronPredictor {
             ((self.ghr << 1) | if taken { 1 } else { 0 }) & ((1u64 << self.history_length) - 1);
 
         if let Some(tgt) = target {
-            self.btb.update(pc, tgt);
+            self.btb.update(pc, tgt, BranchType::Conditional);
         }
     }
 
@@ -136,10 +146,38 @@ impl BranchPredictor for PerceptronPredictor {
         self.btb.lookup(pc)
     }
 
+    /// Returns the branch type the BTB holds for `pc`.
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        self.btb.branch_type(pc)
+    }
+
+    /// Records a resolved jump target and its type in the BTB.
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        self.btb.update(pc, target, kind);
+    }
+
+    /// Predicts a `jalr` target, from the indirect predictor if the BTB
+    /// types it as an indirect jump or call.
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self.btb.branch_type(pc) {
+            Some(BranchType::IndirectJump | BranchType::Call) => {
+                self.indirect.predict(pc).or_else(|| self.btb.lookup(pc))
+            }
+            _ => self.btb.lookup(pc),
+        }
+    }
+
+    /// Trains the indirect predictor with a resolved `jalr` target.
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        self.indirect.update(pc, target);
+    }
+
     /// Handles a function call by pushing the return address to the RAS.
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         self.ras.push(ret_addr);
-        self.btb.update(pc, target);
+        if let Some(target) = target {
+            self.btb.update(pc, target, BranchType::Call);
+        }
     }
 
     /// Predicts the return address using the RAS.
@@ -151,4 +189,14 @@ impl BranchPredictor for PerceptronPredictor {
     fn on_return(&mut self) {
         self.ras.pop();
     }
+
+    /// Snapshots the RAS top-of-stack.
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        self.ras.checkpoint()
+    }
+
+    /// Restores the RAS top-of-stack from a snapshot.
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        self.ras.restore(cp);
+    }
 }
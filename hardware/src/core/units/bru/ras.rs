@@ -3,8 +3,26 @@
 //! The RAS is a specialized predictor for function return addresses. It operates
 //! as a hardware stack that pushes addresses on function calls and pops them
 //! on returns to predict the execution flow.
+//!
+//! The stack is updated speculatively at fetch, so calls and returns on a
+//! mispredicted path would corrupt it. A [`RasCheckpoint`] taken before each
+//! fetched instruction's update records the stack pointer and the entry it
+//! points at; restoring it when that path is squashed undoes every wrong-path
+//! push and pop, including a pop followed by a push that overwrote the top.
+
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of the RAS top-of-stack taken before a speculative update.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RasCheckpoint {
+    /// Stack pointer at the time of the snapshot.
+    ptr: usize,
+    /// Entry at `ptr - 1` (the predicted return), or `0` if the stack was empty.
+    top: u64,
+}
 
 /// Return Address Stack structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Ras {
     /// The stack storage.
     stack: Vec<u64>,
@@ -70,4 +88,24 @@ impl Ras {
             Some(self.stack[self.ptr - 1])
         }
     }
+
+    /// Captures the top-of-stack state for later repair.
+    pub fn checkpoint(&self) -> RasCheckpoint {
+        RasCheckpoint {
+            ptr: self.ptr,
+            top: self.top().unwrap_or(0),
+        }
+    }
+
+    /// Restores the top-of-stack state captured by [`Ras::checkpoint`].
+    ///
+    /// # Arguments
+    ///
+    /// * `cp` - Snapshot taken before the squashed speculative updates.
+    pub fn restore(&mut self, cp: RasCheckpoint) {
+        self.ptr = cp.ptr.min(self.capacity);
+        if self.ptr > 0 {
+            self.stack[self.ptr - 1] = cp.top;
+        }
+    }
 }
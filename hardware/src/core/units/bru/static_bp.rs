@@ -4,14 +4,23 @@
 //! It utilizes a BTB for unconditional jumps and a RAS for function returns, but
 //! assumes all conditional branches will fall through.
 
-use super::{BranchPredictor, btb::Btb, ras::Ras};
+use super::{
+    BranchPredictor,
+    btb::{BranchType, Btb},
+    ittage::IndirectPredictor,
+    ras::{Ras, RasCheckpoint},
+};
+use serde::{Deserialize, Serialize};
 
 /// Static Branch Predictor structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StaticPredictor {
     /// Branch Target Buffer for jump targets.
     btb: Btb,
     /// Return Address Stack for function returns.
     ras: Ras,
+    /// Indirect target predictor for `jalr` jumps and calls.
+    indirect: IndirectPredictor,
 }
 
 impl StaticPredictor {
@@ -25,6 +34,7 @@ impl StaticPredictor {
         Self {
             btb: Btb::new(btb_size),
             ras: Ras::new(ras_size),
+            indirect: IndirectPredictor::new(),
         }
     }
 }
@@ -43,7 +53,7 @@ impl BranchPredictor for StaticPredictor {
     /// Does not maintain any direction history.
     fn update_branch(&mut self, pc: u64, _taken: bool, target: Option<u64>) {
         if let Some(tgt) = target {
-            self.btb.update(pc, tgt);
+            self.btb.update(pc, tgt, BranchType::Conditional);
         }
     }
 
@@ -52,10 +62,38 @@ impl BranchPredictor for StaticPredictor {
         self.btb.lookup(pc)
     }
 
+    /// Returns the branch type the BTB holds for `pc`.
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        self.btb.branch_type(pc)
+    }
+
+    /// Records a resolved jump target and its type in the BTB.
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        self.btb.update(pc, target, kind);
+    }
+
+    /// Predicts a `jalr` target, from the indirect predictor if the BTB
+    /// types it as an indirect jump or call.
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self.btb.branch_type(pc) {
+            Some(BranchType::IndirectJump | BranchType::Call) => {
+                self.indirect.predict(pc).or_else(|| self.btb.lookup(pc))
+            }
+            _ => self.btb.lookup(pc),
+        }
+    }
+
+    /// Trains the indirect predictor with a resolved `jalr` target.
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        self.indirect.update(pc, target);
+    }
+
     /// Handles a function call by pushing the return address to the RAS.
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         self.ras.push(ret_addr);
-        self.btb.update(pc, target);
+        if let Some(target) = target {
+            self.btb.update(pc, target, BranchType::Call);
+        }
     }
 
     /// Predicts the return address using the RAS.
@@ -67,4 +105,14 @@ impl BranchPredictor for StaticPredictor {
     fn on_return(&mut self) {
         self.ras.pop();
     }
+
+    /// Snapshots the RAS top-of-stack.
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        self.ras.checkpoint()
+    }
+
+    /// Restores the RAS top-of-stack from a snapshot.
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        self.ras.restore(cp);
+    }
 }
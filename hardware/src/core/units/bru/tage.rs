@@ -5,6 +5,11 @@
 //! matching long history patterns while falling back to shorter histories
 //! or the base predictor when necessary.
 //!
+//! A loop predictor sits in front of the banks. It learns the trip count of
+//! branches that go one way a fixed number of times and then the other (loop
+//! back-edges), and once confident overrides TAGE so the final iteration's
+//! exit is predicted even when the trip count exceeds the global history.
+//!
 //! # Performance
 //!
 //! - **Time Complexity:**
@@ -15,11 +20,17 @@
 //! - **Best Case:** Complex history-correlated patterns with varying lengths
 //! - **Worst Case:** Random or completely uncorrelated branches (~50% accuracy)
 
-use super::{BranchPredictor, btb::Btb, ras::Ras};
+use super::{
+    BranchPredictor,
+    btb::{BranchType, Btb},
+    ittage::IndirectPredictor,
+    ras::{Ras, RasCheckpoint},
+};
 use crate::config::TageConfig;
+use serde::{Deserialize, Serialize};
 
 /// An entry in a TAGE bank.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct TageEntry {
     /// Tag for matching the history/PC hash.
     tag: u16,
@@ -29,29 +40,37 @@ struct TageEntry {
     u: u8,
 }
 
+/// Confidence at which the loop predictor overrides the TAGE prediction.
+const LOOP_CONF_MAX: u8 = 3;
+
 /// Loop Predictor Entry for handling loop exit branches.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct LoopEntry {
     /// Tag for matching the branch PC.
     tag: u16,
-    /// Confidence counter.
+    /// Number of consecutive trips that ended exactly at `limit`.
     conf: u8,
-    /// Current iteration count.
+    /// Outcomes in `dir` since the last exit.
     count: u16,
-    /// Iteration limit detected.
+    /// Outcomes in `dir` per trip, once a full trip has been seen.
     limit: u16,
-    /// Age/Usefulness counter.
+    /// Replacement age; the entry may be reallocated once this reaches zero.
     age: u8,
-    /// Predicted direction.
+    /// Direction of the loop body; the exit goes the other way.
     dir: bool,
+    /// Whether `limit` holds a measured trip count.
+    trained: bool,
 }
 
 /// TAGE Predictor structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TagePredictor {
     /// Branch Target Buffer.
     btb: Btb,
     /// Return Address Stack.
     ras: Ras,
+    /// Indirect target predictor for `jalr` jumps and calls.
+    indirect: IndirectPredictor,
     /// Global History Register.
     ghr: u64,
     /// Path History Register.
@@ -126,6 +145,7 @@ impl TagePredictor {
         Self {
             btb: Btb::new(btb_size),
             ras: Ras::new(ras_size),
+            indirect: IndirectPredictor::new(),
             ghr: 0,
             phr: 0,
             base: vec![0; config.table_size],
@@ -174,20 +194,78 @@ impl TagePredictor {
         (tag as u16) & ((1 << width) - 1)
     }
 
-    /// Checks the loop predictor for a matching entry.
+    /// Returns the loop table index and tag of `pc`.
+    fn loop_slot(&self, pc: u64) -> (usize, u16) {
+        let idx = ((pc >> 2) as usize) & self.loop_mask;
+        let tag = ((pc >> 10) & 0xFFFF) as u16;
+        (idx, tag)
+    }
+
+    /// Checks the loop predictor for a confident matching entry.
+    ///
+    /// Predicts the body direction until `limit` outcomes have been seen in
+    /// the current trip, then the exit.
     fn get_loop_pred(&self, pc: u64) -> Option<bool> {
-        let idx = (pc as usize) & self.loop_mask;
+        let (idx, tag) = self.loop_slot(pc);
         let e = &self.loops[idx];
-        let tag = ((pc >> 8) & 0xFFFF) as u16;
 
-        if e.tag == tag && e.conf == 3 {
-            if e.count < e.limit {
-                return Some(e.dir);
+        if e.age > 0 && e.tag == tag && e.conf >= LOOP_CONF_MAX {
+            return Some(if e.count < e.limit { e.dir } else { !e.dir });
+        }
+        None
+    }
+
+    /// Trains the loop predictor with a resolved branch.
+    ///
+    /// A matching entry counts outcomes in its body direction and, at each
+    /// exit, gains confidence if the trip matched the learned count or
+    /// relearns the count otherwise. Two exits in a row mean the branch is
+    /// not a loop of that direction, which frees the entry. Entries are
+    /// allocated when TAGE mispredicts, taking the opposite of the
+    /// mispredicted outcome as the body direction.
+    fn update_loop(&mut self, pc: u64, taken: bool, tage_mispredicted: bool) {
+        let (idx, tag) = self.loop_slot(pc);
+        let e = &mut self.loops[idx];
+
+        if e.age > 0 && e.tag == tag {
+            if taken == e.dir {
+                match e.count.checked_add(1) {
+                    Some(count) if !e.trained || count <= e.limit => e.count = count,
+                    // The trip ran past the learned count (or overflowed).
+                    _ => {
+                        e.count = e.count.saturating_add(1);
+                        e.trained = false;
+                        e.conf = 0;
+                    }
+                }
+            } else if e.count == 0 {
+                e.age = 0;
             } else {
-                return Some(!e.dir);
+                if e.trained && e.count == e.limit {
+                    e.conf = (e.conf + 1).min(LOOP_CONF_MAX);
+                    e.age = e.age.saturating_add(1);
+                } else {
+                    e.limit = e.count;
+                    e.trained = true;
+                    e.conf = 0;
+                }
+                e.count = 0;
             }
+        } else if e.age == 0 {
+            if tage_mispredicted {
+                *e = LoopEntry {
+                    tag,
+                    conf: 0,
+                    count: 0,
+                    limit: 0,
+                    age: u8::MAX,
+                    dir: !taken,
+                    trained: false,
+                };
+            }
+        } else {
+            e.age -= 1;
         }
-        None
     }
 }
 
@@ -277,39 +355,7 @@ impl BranchPredictor for TagePredictor {
 
         let mispredicted = pred_taken != taken;
 
-        let l_idx = (pc as usize) & self.loop_mask;
-        let l_tag = ((pc >> 8) & 0xFFFF) as u16;
-        let loop_entry = &mut self.loops[l_idx];
-
-        if loop_entry.tag == l_tag {
-            if loop_entry.age < 255 {
-                loop_entry.age += 1;
-            }
-
-            if taken == loop_entry.dir {
-                loop_entry.count += 1;
-            } else {
-                if loop_entry.count == loop_entry.limit {
-                    if loop_entry.conf < 3 {
-                        loop_entry.conf += 1;
-                    }
-                } else {
-                    loop_entry.limit = loop_entry.count;
-                    loop_entry.conf = 0;
-                    loop_entry.age = 0;
-                }
-                loop_entry.count = 0;
-            }
-        } else if loop_entry.age == 0 {
-            loop_entry.tag = l_tag;
-            loop_entry.limit = 0;
-            loop_entry.count = 0;
-            loop_entry.conf = 0;
-            loop_entry.age = 255;
-            loop_entry.dir = taken;
-        } else {
-            loop_entry.age -= 1;
-        }
+        self.update_loop(pc, taken, mispredicted);
 
         if self.provider_bank > 0 {
             let bank_idx = self.provider_bank - 1;
@@ -377,7 +423,7 @@ impl BranchPredictor for TagePredictor {
         self.phr = (self.phr << 1) | (pc & 1);
 
         if let Some(tgt) = target {
-            self.btb.update(pc, tgt);
+            self.btb.update(pc, tgt, BranchType::Conditional);
         }
     }
 
@@ -386,10 +432,38 @@ impl BranchPredictor for TagePredictor {
         self.btb.lookup(pc)
     }
 
+    /// Returns the branch type the BTB holds for `pc`.
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        self.btb.branch_type(pc)
+    }
+
+    /// Records a resolved jump target and its type in the BTB.
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        self.btb.update(pc, target, kind);
+    }
+
+    /// Predicts a `jalr` target, from the indirect predictor if the BTB
+    /// types it as an indirect jump or call.
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self.btb.branch_type(pc) {
+            Some(BranchType::IndirectJump | BranchType::Call) => {
+                self.indirect.predict(pc).or_else(|| self.btb.lookup(pc))
+            }
+            _ => self.btb.lookup(pc),
+        }
+    }
+
+    /// Trains the indirect predictor with a resolved `jalr` target.
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        self.indirect.update(pc, target);
+    }
+
     /// Handles a function call by pushing the return address to the RAS.
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         self.ras.push(ret_addr);
-        self.btb.update(pc, target);
+        if let Some(target) = target {
+            self.btb.update(pc, target, BranchType::Call);
+        }
     }
 
     /// Predicts the return address using the RAS.
@@ -401,4 +475,14 @@ impl BranchPredictor for TagePredictor {
     fn on_return(&mut self) {
         self.ras.pop();
     }
+
+    /// Snapshots the RAS top-of-stack.
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        self.ras.checkpoint()
+    }
+
+    /// Restores the RAS top-of-stack from a snapshot.
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        self.ras.restore(cp);
+    }
 }
@@ -4,15 +4,24 @@
 //! between a Global predictor (GShare-like) and a Local predictor (PAg/PAp).
 //! This allows the predictor to adapt to different types of branch behaviors.
 
-use super::{BranchPredictor, btb::Btb, ras::Ras};
+use super::{
+    BranchPredictor,
+    btb::{BranchType, Btb},
+    ittage::IndirectPredictor,
+    ras::{Ras, RasCheckpoint},
+};
 use crate::config::TournamentConfig;
+use serde::{Deserialize, Serialize};
 
 /// Tournament Predictor structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TournamentPredictor {
     /// Branch Target Buffer.
     btb: Btb,
     /// Return Address Stack.
     ras: Ras,
+    /// Indirect target predictor for `jalr` jumps and calls.
+    indirect: IndirectPredictor,
     /// Global History Register.
     ghr: u64,
 
@@ -46,6 +55,7 @@ impl TournamentPredictor {
         Self {
             btb: Btb::new(btb_size),
             ras: Ras::new(ras_size),
+            indirect: IndirectPredictor::new(),
             ghr: 0,
 
             global_pht: vec![1; global_size],
@@ -151,7 +161,7 @@ impl BranchPredictor for TournamentPredictor {
             ((pattern << 1) | (taken as u16)) & (self.local_pred_mask as u16);
 
         if let Some(tgt) = target {
-            self.btb.update(pc, tgt);
+            self.btb.update(pc, tgt, BranchType::Conditional);
         }
     }
 
@@ -160,10 +170,38 @@ impl BranchPredictor for TournamentPredictor {
         self.btb.lookup(pc)
     }
 
+    /// Returns the branch type the BTB holds for `pc`.
+    fn branch_type(&self, pc: u64) -> Option<BranchType> {
+        self.btb.branch_type(pc)
+    }
+
+    /// Records a resolved jump target and its type in the BTB.
+    fn update_btb(&mut self, pc: u64, target: u64, kind: BranchType) {
+        self.btb.update(pc, target, kind);
+    }
+
+    /// Predicts a `jalr` target, from the indirect predictor if the BTB
+    /// types it as an indirect jump or call.
+    fn predict_indirect(&self, pc: u64) -> Option<u64> {
+        match self.btb.branch_type(pc) {
+            Some(BranchType::IndirectJump | BranchType::Call) => {
+                self.indirect.predict(pc).or_else(|| self.btb.lookup(pc))
+            }
+            _ => self.btb.lookup(pc),
+        }
+    }
+
+    /// Trains the indirect predictor with a resolved `jalr` target.
+    fn update_indirect(&mut self, pc: u64, target: u64) {
+        self.indirect.update(pc, target);
+    }
+
     /// Handles a function call by pushing the return address to the RAS.
-    fn on_call(&mut self, pc: u64, ret_addr: u64, target: u64) {
+    fn on_call(&mut self, pc: u64, ret_addr: u64, target: Option<u64>) {
         self.ras.push(ret_addr);
-        self.btb.update(pc, target);
+        if let Some(target) = target {
+            self.btb.update(pc, target, BranchType::Call);
+        }
     }
 
     /// Predicts the return address using the RAS.
@@ -175,4 +213,14 @@ impl BranchPredictor for TournamentPredictor {
     fn on_return(&mut self) {
         self.ras.pop();
     }
+
+    /// Snapshots the RAS top-of-stack.
+    fn ras_checkpoint(&self) -> RasCheckpoint {
+        self.ras.checkpoint()
+    }
+
+    /// Restores the RAS top-of-stack from a snapshot.
+    fn ras_restore(&mut self, cp: RasCheckpoint) {
+        self.ras.restore(cp);
+    }
 }
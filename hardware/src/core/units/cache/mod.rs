@@ -3,18 +3,37 @@
 //! This module implements a configurable set-associative cache simulator.
 //! It supports various replacement policies (LRU, FIFO, Random, etc.) and
 //! hardware prefetchers. It models cache hits, misses, and write-back
-//! penalties to simulate memory hierarchy latency.
+//! penalties to simulate memory hierarchy latency, and can report the
+//! eviction of one watched line (used to drop an LR/SC reservation).
+//! Non-temporal accesses bypass allocation and replacement updates.
+//! Stores follow the configured write policy (write-back or write-through)
+//! and write-miss policy (write-allocate or no-write-allocate). An optional
+//! victim cache catches evicted lines and hands them back on a later miss.
 
 /// Cache replacement policy implementations (FIFO, LRU, MRU, PLRU, Random).
 pub mod policies;
 
+/// Miss status holding registers for a non-blocking data cache.
+pub mod mshr;
+
+/// Access-port arbiter for shared cache levels.
+pub mod ports;
+
+/// Fully-associative victim buffer for evicted lines.
+pub mod victim;
+
 use self::policies::{
     FifoPolicy, LruPolicy, MruPolicy, PlruPolicy, RandomPolicy, ReplacementPolicy,
 };
-use crate::config::{CacheConfig, Prefetcher as PrefetcherType, ReplacementPolicy as PolicyType};
+use self::victim::{VictimCache, VictimLine};
+use crate::config::{
+    AllocPolicy, CacheConfig, Prefetcher as PrefetcherType, ReplacementPolicy as PolicyType,
+    WritePolicy,
+};
 use crate::core::units::prefetch::{
     NextLinePrefetcher, Prefetcher, StreamPrefetcher, StridePrefetcher, TaggedPrefetcher,
 };
+use std::fmt;
 
 /// Cache line entry containing tag, validity, and dirty bits.
 #[derive(Clone, Default)]
@@ -40,38 +59,138 @@ pub struct CacheSim {
     ways: usize,
     line_bytes: usize,
     policy: Box<dyn ReplacementPolicy + Send + Sync>,
+    /// Write-hit policy; write-through stores pay next-level latency every time.
+    write_policy: WritePolicy,
+    /// Write-miss policy; no-write-allocate store misses are not installed.
+    alloc_policy: AllocPolicy,
+    /// Line-aligned address whose eviction is reported by [`CacheSim::take_watched_eviction`].
+    watched_line: Option<u64>,
+    /// Set when the watched line is evicted or flushed.
+    watched_evicted: bool,
+    /// Lines evicted from this cache, checked on a miss before the next level.
+    victim: VictimCache,
+    /// Set when a miss was served from the victim cache.
+    victim_hit: bool,
+}
+
+/// Reasons a cache configuration cannot be turned into a working simulator.
+///
+/// The set index is computed as `(addr / line_bytes) % num_sets`, so the
+/// geometry must divide evenly into a power-of-two number of sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheConfigError {
+    /// Associativity is zero.
+    ZeroWays,
+    /// Line size is zero or not a power of two.
+    InvalidLineSize(usize),
+    /// A single line does not fit in the cache.
+    LineLargerThanCache {
+        /// Configured line size in bytes.
+        line_bytes: usize,
+        /// Configured total size in bytes.
+        size_bytes: usize,
+    },
+    /// `size_bytes / (line_bytes * ways)` is not a whole power of two.
+    InvalidSetCount {
+        /// Configured total size in bytes.
+        size_bytes: usize,
+        /// Configured line size in bytes.
+        line_bytes: usize,
+        /// Configured associativity.
+        ways: usize,
+    },
+}
+
+impl fmt::Display for CacheConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroWays => write!(f, "ways must be at least 1"),
+            Self::InvalidLineSize(line) => {
+                write!(f, "line_bytes ({line}) must be a non-zero power of two")
+            }
+            Self::LineLargerThanCache {
+                line_bytes,
+                size_bytes,
+            } => write!(
+                f,
+                "line_bytes ({line_bytes}) exceeds size_bytes ({size_bytes})"
+            ),
+            Self::InvalidSetCount {
+                size_bytes,
+                line_bytes,
+                ways,
+            } => write!(
+                f,
+                "size_bytes ({size_bytes}) / (line_bytes ({line_bytes}) * ways ({ways})) \
+                 must be a power-of-two number of sets"
+            ),
+        }
+    }
 }
 
+impl std::error::Error for CacheConfigError {}
+
 impl CacheSim {
-    /// Creates a new cache simulator with the specified configuration.
+    /// Computes the number of sets for a cache configuration.
     ///
     /// # Arguments
     ///
-    /// * `config` - Cache configuration specifying size, associativity,
-    ///   line size, replacement policy, and prefetcher
+    /// * `config` - Cache configuration to check
     ///
     /// # Returns
     ///
-    /// A new `CacheSim` instance initialized according to the configuration.
-    pub fn new(config: &CacheConfig) -> Self {
-        let safe_ways = if config.ways == 0 { 1 } else { config.ways };
-        let safe_line = if config.line_bytes == 0 {
-            64
-        } else {
-            config.line_bytes
-        };
-        let safe_size = if config.size_bytes == 0 {
-            4096
+    /// The set count, or the first geometry rule the configuration violates.
+    pub fn num_sets(config: &CacheConfig) -> Result<usize, CacheConfigError> {
+        if config.ways == 0 {
+            return Err(CacheConfigError::ZeroWays);
+        }
+        if !config.line_bytes.is_power_of_two() {
+            return Err(CacheConfigError::InvalidLineSize(config.line_bytes));
+        }
+        if config.line_bytes > config.size_bytes {
+            return Err(CacheConfigError::LineLargerThanCache {
+                line_bytes: config.line_bytes,
+                size_bytes: config.size_bytes,
+            });
+        }
+        let set_bytes = config.line_bytes.saturating_mul(config.ways);
+        let num_sets = config.size_bytes / set_bytes;
+        if !config.size_bytes.is_multiple_of(set_bytes) || !num_sets.is_power_of_two() {
+            return Err(CacheConfigError::InvalidSetCount {
+                size_bytes: config.size_bytes,
+                line_bytes: config.line_bytes,
+                ways: config.ways,
+            });
+        }
+        Ok(num_sets)
+    }
+
+    /// Creates a new cache simulator, rejecting degenerate geometries.
+    ///
+    /// Disabled caches are never indexed, so their geometry is not checked
+    /// and they are built as a single line.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Cache configuration to build
+    ///
+    /// # Returns
+    ///
+    /// The simulator, or the geometry error for an enabled cache.
+    pub fn try_new(config: &CacheConfig) -> Result<Self, CacheConfigError> {
+        let (num_sets, safe_ways, safe_line) = if config.enabled {
+            (Self::num_sets(config)?, config.ways, config.line_bytes)
         } else {
-            config.size_bytes
+            (1, 1, config.line_bytes.max(1).next_power_of_two())
         };
 
-        let num_lines = safe_size / safe_line;
-        let num_sets = num_lines / safe_ways;
-
         let policy: Box<dyn ReplacementPolicy + Send + Sync> = match config.policy {
             PolicyType::Fifo => Box::new(FifoPolicy::new(num_sets, safe_ways)),
-            PolicyType::Random => Box::new(RandomPolicy::new(num_sets, safe_ways)),
+            PolicyType::Random => Box::new(RandomPolicy::with_seed(
+                num_sets,
+                safe_ways,
+                config.rng_seed,
+            )),
             PolicyType::Plru => Box::new(PlruPolicy::new(num_sets, safe_ways)),
             PolicyType::Lru => Box::new(LruPolicy::new(num_sets, safe_ways)),
             PolicyType::Mru => Box::new(MruPolicy::new(num_sets, safe_ways)),
@@ -98,7 +217,7 @@ impl CacheSim {
             PrefetcherType::None => None,
         };
 
-        Self {
+        Ok(Self {
             lines: vec![CacheLine::default(); num_sets * safe_ways],
             num_sets,
             ways: safe_ways,
@@ -106,10 +225,55 @@ impl CacheSim {
             latency: config.latency,
             enabled: config.enabled,
             policy,
+            write_policy: config.write_policy,
+            alloc_policy: config.alloc_policy,
             prefetcher,
+            watched_line: None,
+            watched_evicted: false,
+            victim: VictimCache::new(config.victim_entries),
+            victim_hit: false,
+        })
+    }
+
+    /// Watches the line containing `addr` for eviction, replacing any previous watch.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Any address in the line to watch, or `None` to stop watching.
+    pub fn watch_line(&mut self, addr: Option<u64>) {
+        let line = self.line_bytes as u64;
+        self.watched_line = addr.map(|a| a & !(line - 1));
+        self.watched_evicted = false;
+    }
+
+    /// Reports (and clears) whether the watched line has left the cache since it was watched.
+    pub fn take_watched_eviction(&mut self) -> bool {
+        std::mem::take(&mut self.watched_evicted)
+    }
+
+    /// Reports (and clears) whether the last miss was served from the victim cache.
+    pub fn take_victim_hit(&mut self) -> bool {
+        std::mem::take(&mut self.victim_hit)
+    }
+
+    /// Returns the line-aligned address of the line at `set_index` with `tag`.
+    fn line_addr(&self, set_index: usize, tag: u64) -> u64 {
+        (tag * self.num_sets as u64 + set_index as u64) * self.line_bytes as u64
+    }
+
+    /// Records that the line at `line_addr` left the cache if it is the watched line.
+    fn note_eviction(&mut self, line_addr: u64) {
+        if self.watched_line == Some(line_addr) {
+            self.watched_evicted = true;
         }
     }
 
+    /// Returns the line size in bytes.
+    #[inline]
+    pub fn line_bytes(&self) -> usize {
+        self.line_bytes
+    }
+
     /// Checks if the cache contains the specified address.
     ///
     /// # Arguments
@@ -144,11 +308,49 @@ impl CacheSim {
         false
     }
 
+    /// Invalidates the line containing `addr`, if present.
+    ///
+    /// Used to snoop stores from another cache: the line is dropped without a
+    /// write-back, so it must not hold dirty data the caller cares about.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Any address in the line to invalidate
+    ///
+    /// # Returns
+    ///
+    /// `true` if a valid line was invalidated.
+    pub fn invalidate(&mut self, addr: u64) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let set_index = ((addr as usize) / self.line_bytes) % self.num_sets;
+        let tag = addr / (self.line_bytes * self.num_sets) as u64;
+        let base_idx = set_index * self.ways;
+
+        let line_addr = self.line_addr(set_index, tag);
+        for i in 0..self.ways {
+            let line = &mut self.lines[base_idx + i];
+            if line.valid && line.tag == tag {
+                line.valid = false;
+                line.dirty = false;
+                self.note_eviction(line_addr);
+                return true;
+            }
+        }
+        if self.victim.take(line_addr).is_some() {
+            self.note_eviction(line_addr);
+            return true;
+        }
+        false
+    }
+
     /// Installs a cache line for the specified address.
     ///
-    /// Selects a victim line using the replacement policy and installs
-    /// the new line. Returns the penalty for write-back if the victim
-    /// line was dirty.
+    /// Fills an empty way if the set has one; otherwise the replacement policy
+    /// selects a victim line, which moves to the victim cache if there is one.
+    /// Returns the penalty for writing back a dirty line that leaves this level.
     ///
     /// # Arguments
     ///
@@ -158,18 +360,30 @@ impl CacheSim {
     ///
     /// # Returns
     ///
-    /// The penalty in cycles for writing back a dirty victim line.
+    /// The penalty in cycles for writing back a dirty evicted line.
     fn install_line(&mut self, addr: u64, is_write: bool, next_level_latency: u64) -> u64 {
         let set_index = ((addr as usize) / self.line_bytes) % self.num_sets;
         let tag = addr / (self.line_bytes * self.num_sets) as u64;
         let base_idx = set_index * self.ways;
 
-        let victim_way = self.policy.get_victim(set_index);
+        // Fill an empty way first; the policy only chooses among valid lines.
+        let victim_way = (0..self.ways)
+            .find(|&w| !self.lines[base_idx + w].valid)
+            .unwrap_or_else(|| self.policy.get_victim(set_index));
         let victim_idx = base_idx + victim_way;
         let mut penalty = 0;
 
-        if self.lines[victim_idx].valid && self.lines[victim_idx].dirty {
-            penalty += next_level_latency;
+        if self.lines[victim_idx].valid {
+            let evicted = VictimLine {
+                addr: self.line_addr(set_index, self.lines[victim_idx].tag),
+                dirty: self.lines[victim_idx].dirty,
+            };
+            if let Some(out) = self.victim.insert(evicted) {
+                if out.dirty {
+                    penalty += next_level_latency;
+                }
+                self.note_eviction(out.addr);
+            }
         }
 
         self.lines[victim_idx] = CacheLine {
@@ -186,7 +400,10 @@ impl CacheSim {
     ///
     /// Performs a cache lookup, updates replacement policy on hit,
     /// installs the line on miss, and triggers prefetcher. Returns
-    /// hit status and penalty cycles.
+    /// hit status and penalty cycles. A write-through store adds
+    /// `next_level_latency` whether it hits or misses, and a
+    /// no-write-allocate store miss leaves the set unchanged and pays
+    /// `next_level_latency` for the write performed at the next level.
     ///
     /// # Arguments
     ///
@@ -198,8 +415,35 @@ impl CacheSim {
     ///
     /// A tuple `(hit, penalty)` where `hit` indicates a cache hit
     /// and `penalty` is the number of penalty cycles (0 on hit,
-    /// miss penalty + write-back penalty on miss).
+    /// miss penalty + write-back penalty on miss, plus the write-through
+    /// latency for stores to a write-through cache).
     pub fn access(&mut self, addr: u64, is_write: bool, next_level_latency: u64) -> (bool, u64) {
+        self.access_hinted(addr, is_write, next_level_latency, false)
+    }
+
+    /// Accesses the cache, optionally as a non-temporal (streaming) access.
+    ///
+    /// A non-temporal access neither allocates on a miss nor promotes the line
+    /// in the replacement order on a hit, and is not shown to the prefetcher,
+    /// so it cannot displace lines with temporal locality.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to access
+    /// * `is_write` - Whether this is a write operation
+    /// * `next_level_latency` - Latency of the next cache level
+    /// * `non_temporal` - Whether the access carries a non-temporal hint
+    ///
+    /// # Returns
+    ///
+    /// A tuple `(hit, penalty)` as for [`CacheSim::access`].
+    pub fn access_hinted(
+        &mut self,
+        addr: u64,
+        is_write: bool,
+        next_level_latency: u64,
+        non_temporal: bool,
+    ) -> (bool, u64) {
         if !self.enabled {
             return (false, 0);
         }
@@ -208,14 +452,18 @@ impl CacheSim {
         let tag = addr / (self.line_bytes * self.num_sets) as u64;
         let base_idx = set_index * self.ways;
 
+        let write_through = is_write && self.write_policy == WritePolicy::WriteThrough;
+        let mark_dirty = is_write && !write_through;
         let mut hit = false;
-        let mut penalty = 0;
+        let mut penalty = if write_through { next_level_latency } else { 0 };
 
         for i in 0..self.ways {
             let idx = base_idx + i;
             if self.lines[idx].valid && self.lines[idx].tag == tag {
-                self.policy.update(set_index, i);
-                if is_write {
+                if !non_temporal {
+                    self.policy.update(set_index, i);
+                }
+                if mark_dirty {
                     self.lines[idx].dirty = true;
                 }
                 hit = true;
@@ -223,8 +471,24 @@ impl CacheSim {
             }
         }
 
+        if non_temporal {
+            return (hit, penalty);
+        }
+
+        let allocate = !is_write || self.alloc_policy == AllocPolicy::WriteAllocate;
         if !hit {
-            penalty += self.install_line(addr, is_write, next_level_latency);
+            // A line found in the victim cache is swapped back in, even by a
+            // store that would not otherwise allocate.
+            let line_addr = self.line_addr(set_index, tag);
+            let from_victim = self.victim.take(line_addr);
+            self.victim_hit = from_victim.is_some();
+            if allocate || from_victim.is_some() {
+                let dirty = mark_dirty || from_victim.is_some_and(|v| v.dirty);
+                penalty += self.install_line(addr, dirty, next_level_latency);
+            } else if !write_through {
+                // The store bypasses this level and is performed at the next one.
+                penalty += next_level_latency;
+            }
         }
 
         let mut prefetches = Vec::new();
@@ -233,7 +497,8 @@ impl CacheSim {
         }
 
         for target in prefetches {
-            if !self.contains(target) {
+            let target_line = target & !(self.line_bytes as u64 - 1);
+            if !self.contains(target) && !self.victim.contains(target_line) {
                 self.install_line(target, false, next_level_latency);
             }
         }
@@ -249,11 +514,36 @@ impl CacheSim {
         if !self.enabled {
             return;
         }
-        for line in &mut self.lines {
+        for idx in 0..self.lines.len() {
+            let line = &mut self.lines[idx];
             if line.valid && line.dirty {
                 line.dirty = false;
                 line.valid = false;
+                let tag = line.tag;
+                let line_addr = self.line_addr(idx / self.ways, tag);
+                self.note_eviction(line_addr);
             }
         }
+        for line_addr in self.victim.flush_dirty() {
+            self.note_eviction(line_addr);
+        }
+    }
+
+    /// Invalidates every line, leaving the cache (and its victim buffer) empty.
+    ///
+    /// Used when a checkpoint is taken or restored; the cache models timing only,
+    /// so no data is lost.
+    pub fn invalidate_all(&mut self) {
+        for idx in 0..self.lines.len() {
+            if self.lines[idx].valid {
+                self.lines[idx].valid = false;
+                self.lines[idx].dirty = false;
+                let line_addr = self.line_addr(idx / self.ways, self.lines[idx].tag);
+                self.note_eviction(line_addr);
+            }
+        }
+        while let Some(line) = self.victim.pop() {
+            self.note_eviction(line.addr);
+        }
     }
 }
@@ -0,0 +1,152 @@
+//! Miss Status Holding Registers.
+//!
+//! This module models the MSHRs of a non-blocking data cache. It provides:
+//! 1. **Overlap:** A miss allocates an MSHR and completes in the background, so
+//!    independent misses to distinct lines are serviced in parallel.
+//! 2. **Merging:** An access to a line that is already in flight joins its MSHR
+//!    and receives the data when that miss completes.
+//! 3. **Back-pressure:** A miss arriving while every MSHR is busy waits for the
+//!    earliest one to complete.
+//! 4. **Scoreboard:** Destination registers of outstanding loads, so a consumer
+//!    waits only when it actually needs the data.
+
+/// Timing of a miss accepted by [`MshrFile::request`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MshrGrant {
+    /// Cycles the access waits for a free MSHR before it can issue.
+    pub wait: u64,
+    /// Cycle at which the line's data becomes available.
+    pub ready: u64,
+    /// The access joined a miss already in flight to the same line.
+    pub merged: bool,
+    /// Another miss was outstanding when this one issued.
+    pub overlapped: bool,
+}
+
+/// MSHR pool and load scoreboard for a non-blocking cache.
+///
+/// Zero MSHRs disables the model (blocking cache).
+#[derive(Clone, Debug, Default)]
+pub struct MshrFile {
+    capacity: usize,
+    /// `(line address, ready cycle)` of each outstanding miss.
+    inflight: Vec<(u64, u64)>,
+    /// `(register, is_fp, ready cycle)` of each load waiting on a miss.
+    pending_regs: Vec<(usize, bool, u64)>,
+}
+
+impl MshrFile {
+    /// Creates an MSHR file with every entry free.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of misses that may be outstanding at once; `0` disables the model.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inflight: Vec::with_capacity(capacity),
+            pending_regs: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if misses are non-blocking.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the number of misses still outstanding at `now`.
+    pub fn outstanding(&self, now: u64) -> usize {
+        self.inflight
+            .iter()
+            .filter(|&&(_, ready)| ready > now)
+            .count()
+    }
+
+    /// Frees the MSHRs and scoreboard entries whose data has arrived by `now`.
+    fn retire(&mut self, now: u64) {
+        self.inflight.retain(|&(_, ready)| ready > now);
+        self.pending_regs.retain(|&(_, _, ready)| ready > now);
+    }
+
+    /// Looks up or allocates an MSHR for an access to `line`.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - Line-aligned physical address of the access.
+    /// * `now` - Cycle at which the access reaches the cache.
+    /// * `latency` - Penalty the hierarchy charged for the access; `0` means it hit.
+    ///
+    /// # Returns
+    ///
+    /// The grant for a miss or for a hit on a line still in flight, or `None` for a
+    /// plain hit.
+    pub fn request(&mut self, line: u64, now: u64, latency: u64) -> Option<MshrGrant> {
+        self.retire(now);
+
+        if let Some(&(_, ready)) = self.inflight.iter().find(|&&(l, _)| l == line) {
+            return Some(MshrGrant {
+                wait: 0,
+                ready,
+                merged: true,
+                overlapped: true,
+            });
+        }
+        if latency == 0 {
+            return None;
+        }
+
+        let overlapped = !self.inflight.is_empty();
+        let mut wait = 0;
+        if self.inflight.len() >= self.capacity {
+            let (idx, &(_, earliest)) = self
+                .inflight
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &(_, ready))| ready)
+                .expect("a full MSHR file has an entry");
+            wait = earliest - now;
+            self.inflight.swap_remove(idx);
+        }
+        let ready = now + wait + latency;
+        self.inflight.push((line, ready));
+        Some(MshrGrant {
+            wait,
+            ready,
+            merged: false,
+            overlapped,
+        })
+    }
+
+    /// Records that register `reg` receives data at cycle `ready`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reg` - Destination register index.
+    /// * `is_fp` - Whether `reg` names a floating-point register.
+    /// * `ready` - Cycle at which the load data arrives.
+    pub fn mark_pending(&mut self, reg: usize, is_fp: bool, ready: u64) {
+        self.pending_regs.push((reg, is_fp, ready));
+    }
+
+    /// Returns how many cycles a consumer of `reg` must still wait at `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reg` - Source register index.
+    /// * `is_fp` - Whether `reg` names a floating-point register.
+    /// * `now` - Current cycle.
+    pub fn operand_wait(&self, reg: usize, is_fp: bool, now: u64) -> u64 {
+        self.pending_regs
+            .iter()
+            .filter(|&&(r, fp, _)| r == reg && fp == is_fp)
+            .map(|&(_, _, ready)| ready.saturating_sub(now))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Drops every outstanding miss and pending register.
+    pub fn clear(&mut self) {
+        self.inflight.clear();
+        self.pending_regs.clear();
+    }
+}
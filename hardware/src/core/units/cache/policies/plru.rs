@@ -7,11 +7,15 @@
 //! recently used subtree. To find a victim, the tree is traversed following the
 //! arrows (bits) to a leaf node.
 //!
+//! Nodes are stored heap-style (root at bit 1, children of node `n` at `2n` and
+//! `2n + 1`) over the ways rounded up to a power of two. With a non-power-of-two
+//! associativity, the walk never enters a subtree holding only padding leaves.
+//!
 //! # Performance
 //!
 //! - **Time Complexity:**
-//!   - `update()`: O(1)
-//!   - `get_victim()`: O(1)
+//!   - `update()`: O(log W)
+//!   - `get_victim()`: O(log W)
 //! - **Space Complexity:** O(S × W) bits where S is sets, W is ways (much less than LRU)
 //! - **Hardware Cost:** Low - simple bit operations
 //! - **Best Case:** Similar to LRU for most access patterns
@@ -21,10 +25,12 @@ use super::ReplacementPolicy;
 
 /// PLRU Policy state.
 pub struct PlruPolicy {
-    /// Bitmask representing the tree state for each set.
+    /// Tree bits for each set; bit `n` is node `n`, set when the victim lies right.
     usage: Vec<u64>,
     /// Number of ways in the cache.
     ways: usize,
+    /// Number of tree leaves (`ways` rounded up to a power of two).
+    leaves: usize,
 }
 
 impl PlruPolicy {
@@ -33,11 +39,12 @@ impl PlruPolicy {
     /// # Arguments
     ///
     /// * `sets` - The number of sets in the cache.
-    /// * `ways` - The associativity (number of ways) of the cache.
+    /// * `ways` - The associativity (number of ways) of the cache, at most 64.
     pub fn new(sets: usize, ways: usize) -> Self {
         Self {
             usage: vec![0; sets],
             ways,
+            leaves: ways.max(1).next_power_of_two(),
         }
     }
 }
@@ -48,12 +55,18 @@ impl ReplacementPolicy for PlruPolicy {
     /// Sets the bits along the path to the accessed way to point away from it,
     /// protecting it from immediate eviction.
     fn update(&mut self, set: usize, way: usize) {
-        let mask = 1 << way;
-        self.usage[set] |= mask;
-
-        let all_ones = (1 << self.ways) - 1;
-        if (self.usage[set] & all_ones) == all_ones {
-            self.usage[set] = mask;
+        let bits = &mut self.usage[set];
+        let (mut node, mut lo, mut span) = (1, 0, self.leaves);
+        while span > 1 {
+            span /= 2;
+            let right = way >= lo + span;
+            if right {
+                *bits &= !(1 << node);
+                lo += span;
+            } else {
+                *bits |= 1 << node;
+            }
+            node = 2 * node + usize::from(right);
         }
     }
 
@@ -61,11 +74,16 @@ impl ReplacementPolicy for PlruPolicy {
     ///
     /// Traverses the tree bits to find the pseudo-least-recently-used way.
     fn get_victim(&mut self, set: usize) -> usize {
-        for i in 0..self.ways {
-            if (self.usage[set] >> i) & 1 == 0 {
-                return i;
+        let bits = self.usage[set];
+        let (mut node, mut lo, mut span) = (1, 0, self.leaves);
+        while span > 1 {
+            span /= 2;
+            let right = (bits >> node) & 1 == 1 && lo + span < self.ways;
+            if right {
+                lo += span;
             }
+            node = 2 * node + usize::from(right);
         }
-        0
+        lo
     }
 }
@@ -1,10 +1,11 @@
 //! Random Replacement Policy.
 //!
-//! This policy evicts a random cache line from the set. It uses a simple
-//! Linear Feedback Shift Register (LFSR) to generate pseudo-random numbers,
+//! This policy evicts a random cache line from the set. It uses a xorshift64
+//! generator with a configurable seed, so a run is reproducible while still
 //! avoiding the overhead of a complex RNG.
 
 use super::ReplacementPolicy;
+use crate::config::defaults::CACHE_RNG_SEED;
 
 /// Random Policy state.
 pub struct RandomPolicy {
@@ -21,10 +22,23 @@ impl RandomPolicy {
     ///
     /// * `sets` - The number of sets (unused in this policy but required by interface).
     /// * `ways` - The associativity (number of ways) of the cache.
-    pub fn new(_sets: usize, ways: usize) -> Self {
+    pub fn new(sets: usize, ways: usize) -> Self {
+        Self::with_seed(sets, ways, CACHE_RNG_SEED)
+    }
+
+    /// Creates a new Random policy instance with an explicit PRNG seed.
+    ///
+    /// A zero seed is replaced with 1, since xorshift never leaves zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `sets` - The number of sets (unused in this policy but required by interface).
+    /// * `ways` - The associativity (number of ways) of the cache.
+    /// * `seed` - Initial generator state.
+    pub fn with_seed(_sets: usize, ways: usize, seed: u64) -> Self {
         Self {
             ways,
-            state: 123456789,
+            state: seed.max(1),
         }
     }
 }
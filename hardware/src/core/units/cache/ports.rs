@@ -0,0 +1,58 @@
+//! Cache Access Ports.
+//!
+//! This module models a fixed number of access ports in front of a shared cache. It provides:
+//! 1. **Occupancy:** Each access holds one port for a configurable number of cycles.
+//! 2. **Queueing:** An access arriving while every port is busy waits for the earliest one
+//!    to free up, so bursts of misses from different inner levels contend for bandwidth.
+
+/// Port arbiter for a shared cache level.
+///
+/// Zero ports disables the model (unlimited bandwidth).
+#[derive(Clone, Debug, Default)]
+pub struct CachePorts {
+    occupancy: u64,
+    busy_until: Vec<u64>,
+}
+
+impl CachePorts {
+    /// Creates an arbiter with all ports idle.
+    ///
+    /// # Arguments
+    ///
+    /// * `ports` - Number of accesses the cache accepts concurrently; `0` disables the model.
+    /// * `occupancy` - Cycles each access holds its port.
+    pub fn new(ports: usize, occupancy: u64) -> Self {
+        Self {
+            occupancy,
+            busy_until: vec![0; ports],
+        }
+    }
+
+    /// Returns `true` if the port limit is modeled.
+    pub fn is_enabled(&self) -> bool {
+        !self.busy_until.is_empty()
+    }
+
+    /// Claims the earliest free port for an access.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Cycle at which the access reaches the cache.
+    ///
+    /// # Returns
+    ///
+    /// Cycles the access waits for a port (`0` when one is free or the model is disabled).
+    pub fn request(&mut self, now: u64) -> u64 {
+        let Some(port) = self.busy_until.iter_mut().min() else {
+            return 0;
+        };
+        let start = (*port).max(now);
+        *port = start + self.occupancy;
+        start - now
+    }
+
+    /// Frees every port.
+    pub fn reset(&mut self) {
+        self.busy_until.fill(0);
+    }
+}
@@ -0,0 +1,106 @@
+//! Victim Cache.
+//!
+//! This module models a small fully-associative buffer that catches lines evicted
+//! from a cache. It provides:
+//! 1. **Capture:** Every line displaced from the cache is inserted, most recent first.
+//! 2. **Swap-back:** A cache miss that finds its line here takes it back out, so the
+//!    cache can reinstall it without going to the next level.
+//! 3. **LRU replacement:** When full, the least recently inserted line leaves the
+//!    buffer (and is written back if dirty).
+
+use std::collections::VecDeque;
+
+/// A line held in the victim cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VictimLine {
+    /// Line-aligned address.
+    pub addr: u64,
+    /// The line holds data not yet written to the next level.
+    pub dirty: bool,
+}
+
+/// Fully-associative victim buffer.
+///
+/// Zero entries disables the model.
+#[derive(Clone, Debug, Default)]
+pub struct VictimCache {
+    capacity: usize,
+    /// Resident lines, most recently inserted first.
+    lines: VecDeque<VictimLine>,
+}
+
+impl VictimCache {
+    /// Creates an empty victim cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Number of lines the buffer holds; `0` disables it.
+    pub fn new(entries: usize) -> Self {
+        Self {
+            capacity: entries,
+            lines: VecDeque::with_capacity(entries),
+        }
+    }
+
+    /// Returns `true` if the victim cache holds any entries.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns `true` if the line at `addr` is resident.
+    pub fn contains(&self, addr: u64) -> bool {
+        self.lines.iter().any(|l| l.addr == addr)
+    }
+
+    /// Removes and returns the line at `addr`, if resident.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Line-aligned address to look up.
+    pub fn take(&mut self, addr: u64) -> Option<VictimLine> {
+        let idx = self.lines.iter().position(|l| l.addr == addr)?;
+        self.lines.remove(idx)
+    }
+
+    /// Inserts a line evicted from the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The evicted line.
+    ///
+    /// # Returns
+    ///
+    /// The least recently inserted line, if the buffer was full and it had to leave.
+    pub fn insert(&mut self, line: VictimLine) -> Option<VictimLine> {
+        if !self.is_enabled() {
+            return Some(line);
+        }
+        self.lines.push_front(line);
+        if self.lines.len() > self.capacity {
+            self.lines.pop_back()
+        } else {
+            None
+        }
+    }
+
+    /// Removes and returns the least recently inserted line.
+    pub fn pop(&mut self) -> Option<VictimLine> {
+        self.lines.pop_back()
+    }
+
+    /// Drops every dirty line, as a cache flush does.
+    ///
+    /// # Returns
+    ///
+    /// The addresses of the dropped lines.
+    pub fn flush_dirty(&mut self) -> Vec<u64> {
+        let dropped = self
+            .lines
+            .iter()
+            .filter(|l| l.dirty)
+            .map(|l| l.addr)
+            .collect();
+        self.lines.retain(|l| !l.dirty);
+        dropped
+    }
+}
@@ -10,11 +10,9 @@
 //! |  1  | UF   | Underflow           |
 //! |  0  | NX   | Inexact             |
 //!
-//! This module will provide flag accumulation and the `execute_full` API
-//! that returns both the result and the set of raised exception flags.
-//!
-//! **Status:** Stub — implementation pending as part of Phase 1.2 exception
-//! flag verification.
+//! [`Fpu::execute_full`](super::Fpu::execute_full) returns these alongside each
+//! result; the execute stage ORs them into `fcsr`, where they stay set until
+//! software clears them through `fflags` or `fcsr`.
 
 use std::ops::BitOr;
 
@@ -7,8 +7,8 @@
 //!
 //! Operations are organized into submodules:
 //! - [`nan_handling`]: NaN boxing/unboxing and canonical NaN propagation.
-//! - [`rounding_modes`]: Rounding mode types (stub, pending implementation).
-//! - [`exception_flags`]: Exception flag types (stub, pending implementation).
+//! - [`rounding_modes`]: Rounding mode encodings and dynamic (`frm`) resolution.
+//! - [`exception_flags`]: Exception flags accrued into `fcsr.fflags` by [`Fpu::execute_full`].
 
 /// NaN boxing, unboxing, and canonical NaN propagation.
 pub mod nan_handling;
@@ -125,6 +125,30 @@ impl Fpu {
     pub fn execute_full(op: AluOp, a: u64, b: u64, c: u64, is32: bool) -> (u64, FpFlags) {
         let mut flags = FpFlags::NONE;
 
+        // Sign injection, moves, and FCLASS never raise exceptions.
+        if matches!(
+            op,
+            AluOp::FSgnJ
+                | AluOp::FSgnJN
+                | AluOp::FSgnJX
+                | AluOp::FMvToX
+                | AluOp::FMvToF
+                | AluOp::FClass
+        ) {
+            return (Self::execute(op, a, b, c, is32), flags);
+        }
+        // Integer-source conversions have no FP operand; they can only round.
+        if matches!(
+            op,
+            AluOp::FCvtSW | AluOp::FCvtSL | AluOp::FCvtSWu | AluOp::FCvtSLu
+        ) {
+            return Self::convert_from_int(op, a, is32);
+        }
+        // Precision conversions read one format and write the other.
+        if matches!(op, AluOp::FCvtSD | AluOp::FCvtDS) {
+            return Self::convert_precision(op, a, RoundingMode::Rne);
+        }
+
         if is32 {
             let fa = unbox_f32(a);
             let fb = unbox_f32(b);
@@ -137,6 +161,10 @@ impl Fpu {
             }
 
             match op {
+                // Signaling comparisons: any NaN operand is invalid.
+                AluOp::FLt | AluOp::FLe if fa.is_nan() || fb.is_nan() => {
+                    flags = flags | FpFlags::NV;
+                }
                 AluOp::FDiv => {
                     if fb == 0.0 && !fa.is_nan() {
                         if fa == 0.0 {
@@ -174,6 +202,12 @@ impl Fpu {
                 }
                 _ => {}
             }
+            if Self::inexact_f32(op, fa, fb, unbox_f32(c), res_f32) {
+                flags = flags | FpFlags::NX;
+                if res_f32 == 0.0 || res_f32.is_subnormal() {
+                    flags = flags | FpFlags::UF;
+                }
+            }
 
             (result, flags)
         } else {
@@ -187,6 +221,9 @@ impl Fpu {
             }
 
             match op {
+                AluOp::FLt | AluOp::FLe if fa.is_nan() || fb.is_nan() => {
+                    flags = flags | FpFlags::NV;
+                }
                 AluOp::FDiv => {
                     if fb == 0.0 && !fa.is_nan() {
                         if fa == 0.0 {
@@ -221,6 +258,12 @@ impl Fpu {
                 }
                 _ => {}
             }
+            if Self::inexact_f64(op, fa, fb, f64::from_bits(c), res_f64) {
+                flags = flags | FpFlags::NX;
+                if res_f64 == 0.0 || res_f64.is_subnormal() {
+                    flags = flags | FpFlags::UF;
+                }
+            }
 
             (result, flags)
         }
@@ -228,9 +271,12 @@ impl Fpu {
 
     /// Executes a floating-point operation with an explicit rounding mode.
     ///
-    /// The rounding mode affects arithmetic operations, conversions, and
-    /// fused multiply-add. For this software implementation, the host FPU
-    /// rounding mode is simulated by rounding the result after computation.
+    /// The rounding mode affects arithmetic operations and fused multiply-add;
+    /// other operations are delegated to [`Fpu::execute`]. The host computes in
+    /// round-to-nearest, so the result is rerounded from the sign of its exact
+    /// rounding error (see [`Fpu::residual_f64`]). Single-precision operations are
+    /// computed in double precision, rounded to odd, and then rounded to single
+    /// precision under `rm`.
     ///
     /// # Arguments
     ///
@@ -246,54 +292,542 @@ impl Fpu {
     /// The 64-bit result of the floating-point operation with the specified
     /// rounding mode applied.
     pub fn execute_with_rm(op: AluOp, a: u64, b: u64, c: u64, is32: bool, rm: RoundingMode) -> u64 {
-        // For conversions that produce an integer, we can directly
-        // implement rounding. For arithmetic ops we compute at higher
-        // precision (f64) and round the result to f32.
+        if matches!(op, AluOp::FCvtSD | AluOp::FCvtDS) {
+            return Self::convert_precision(op, a, rm).0;
+        }
+        if !matches!(
+            op,
+            AluOp::FAdd
+                | AluOp::FSub
+                | AluOp::FMul
+                | AluOp::FDiv
+                | AluOp::FSqrt
+                | AluOp::FMAdd
+                | AluOp::FMSub
+                | AluOp::FNMAdd
+                | AluOp::FNMSub
+        ) {
+            // Rounding does not affect comparisons, sign injection, etc.
+            return Self::execute(op, a, b, c, is32);
+        }
+
         if is32 {
-            let fa = unbox_f32(a) as f64;
-            let fb = unbox_f32(b) as f64;
-
-            // Compute in f64 to get extra precision for rounding
-            let exact = match op {
-                AluOp::FAdd => fa + fb,
-                AluOp::FSub => fa - fb,
-                AluOp::FMul => fa * fb,
-                AluOp::FDiv => fa / fb,
-                AluOp::FSqrt => fa.sqrt(),
-                _ => {
-                    // For operations where rounding mode doesn't affect the
-                    // result (comparisons, sign injection, etc.), delegate.
-                    return Self::execute(op, a, b, c, is32);
+            let (x, y, z) = (
+                unbox_f32(a) as f64,
+                unbox_f32(b) as f64,
+                unbox_f32(c) as f64,
+            );
+            // Every single-precision operand is exact in double precision, and so
+            // is the product of two, so only the final rounding loses information.
+            let wide = f64::from_bits(Self::execute_f64(op, x.to_bits(), y.to_bits(), z.to_bits()));
+            let odd = Self::round_to_odd(wide, Self::residual_f64(op, x, y, z, wide));
+            box_f32(canonicalize_f32(Self::apply_rounding_f32(odd, rm)))
+        } else {
+            let (x, y, z) = (f64::from_bits(a), f64::from_bits(b), f64::from_bits(c));
+            let rne = f64::from_bits(Self::execute_f64(op, a, b, c));
+            canonicalize_f64(Self::apply_rounding_f64(op, x, y, z, rne, rm)).to_bits()
+        }
+    }
+
+    /// Executes a floating-point operation under a resolved rounding mode.
+    ///
+    /// This is the execute-stage entry point: `rm` is the instruction's static
+    /// rounding mode or `fcsr.frm` when the instruction selects the dynamic mode.
+    /// FP-to-integer conversions round with `rm` and saturate out-of-range and
+    /// NaN inputs (raising NV); arithmetic uses [`Fpu::execute_with_rm`] for
+    /// directed modes. Flags are computed as for [`Fpu::execute_full`], except
+    /// that OF and UF follow the result actually delivered under `rm`.
+    ///
+    /// # Arguments
+    ///
+    /// * `op`   - The floating-point operation to perform.
+    /// * `a`    - First operand.
+    /// * `b`    - Second operand.
+    /// * `c`    - Third operand for FMA operations.
+    /// * `is32` - If true, perform single-precision operation.
+    /// * `rm`   - The effective rounding mode.
+    ///
+    /// # Returns
+    ///
+    /// A tuple `(result, flags)` as for [`Fpu::execute_full`].
+    pub fn execute_rm(
+        op: AluOp,
+        a: u64,
+        b: u64,
+        c: u64,
+        is32: bool,
+        rm: RoundingMode,
+    ) -> (u64, FpFlags) {
+        match op {
+            AluOp::FCvtWS | AluOp::FCvtLS | AluOp::FCvtWuS | AluOp::FCvtLuS => {
+                let x = if is32 {
+                    unbox_f32(a) as f64
+                } else {
+                    f64::from_bits(a)
+                };
+                Self::convert_to_int(
+                    x,
+                    matches!(op, AluOp::FCvtWS | AluOp::FCvtWuS),
+                    matches!(op, AluOp::FCvtWS | AluOp::FCvtLS),
+                    rm,
+                )
+            }
+            AluOp::FCvtSD | AluOp::FCvtDS => Self::convert_precision(op, a, rm),
+            _ => {
+                let (result, flags) = Self::execute_full(op, a, b, c, is32);
+                if rm == RoundingMode::Rne {
+                    (result, flags)
+                } else {
+                    let directed = Self::execute_with_rm(op, a, b, c, is32, rm);
+                    (
+                        directed,
+                        Self::directed_flags(flags, result, directed, is32),
+                    )
                 }
+            }
+        }
+    }
+
+    /// Rederives the rounding-dependent flags for a result rounded under a directed mode.
+    ///
+    /// Whether a result is inexact does not depend on the rounding mode, so NX,
+    /// NV, and DZ carry over from the round-to-nearest flags. Overflow does: a
+    /// directed mode may round a result just above the largest finite value up
+    /// to infinity, or saturate an overflow at that value instead. UF is raised
+    /// for an inexact zero or subnormal result as in [`Fpu::execute_full`].
+    ///
+    /// # Arguments
+    ///
+    /// * `rne_flags`  - Flags of the round-to-nearest computation.
+    /// * `rne_result` - The round-to-nearest result.
+    /// * `directed`   - The result rounded under the directed mode.
+    /// * `is32`       - If true, the results are NaN-boxed single precision.
+    fn directed_flags(rne_flags: FpFlags, rne_result: u64, directed: u64, is32: bool) -> FpFlags {
+        let decode = |bits: u64| {
+            if is32 {
+                let f = unbox_f32(bits);
+                (
+                    f.is_infinite(),
+                    f.abs() == f32::MAX,
+                    f == 0.0 || f.is_subnormal(),
+                )
+            } else {
+                let f = f64::from_bits(bits);
+                (
+                    f.is_infinite(),
+                    f.abs() == f64::MAX,
+                    f == 0.0 || f.is_subnormal(),
+                )
+            }
+        };
+        let (rne_inf, _, _) = decode(rne_result);
+        let (inf, saturated, tiny) = decode(directed);
+
+        let mut flags = FpFlags::NONE;
+        for kept in [FpFlags::NV, FpFlags::DZ] {
+            if rne_flags.contains(kept) {
+                flags = flags | kept;
+            }
+        }
+        let overflow = if rne_flags.contains(FpFlags::OF) {
+            inf || saturated
+        } else {
+            inf && !rne_inf
+        };
+        if overflow {
+            flags = flags | FpFlags::OF | FpFlags::NX;
+        }
+        if rne_flags.contains(FpFlags::NX) {
+            flags = flags | FpFlags::NX;
+            if tiny {
+                flags = flags | FpFlags::UF;
+            }
+        }
+        flags
+    }
+
+    /// Converts a float to a 32- or 64-bit integer with RISC-V semantics.
+    ///
+    /// Rounds with `rm`, raises NX when the value had a fractional part, and
+    /// saturates (raising NV) on overflow; NaN converts to the maximum value.
+    /// 32-bit results, signed or unsigned, are sign-extended to 64 bits.
+    fn convert_to_int(x: f64, word: bool, signed: bool, rm: RoundingMode) -> (u64, FpFlags) {
+        let width = if word { 32 } else { 64 };
+        // Inclusive lower and exclusive upper bound of the representable range.
+        let (lo, hi) = if signed {
+            (-(2f64.powi(width - 1)), 2f64.powi(width - 1))
+        } else {
+            (0.0, 2f64.powi(width))
+        };
+        let (min, max) = match (word, signed) {
+            (true, true) => (i32::MIN as i64 as u64, i32::MAX as u64),
+            (true, false) => (0, u32::MAX as i32 as i64 as u64),
+            (false, true) => (i64::MIN as u64, i64::MAX as u64),
+            (false, false) => (0, u64::MAX),
+        };
+        if x.is_nan() {
+            return (max, FpFlags::NV);
+        }
+        let r = match rm {
+            RoundingMode::Rne => x.round_ties_even(),
+            RoundingMode::Rtz => x.trunc(),
+            RoundingMode::Rdn => x.floor(),
+            RoundingMode::Rup => x.ceil(),
+            RoundingMode::Rmm => x.round(),
+        };
+        if r >= hi {
+            return (max, FpFlags::NV);
+        }
+        if r < lo {
+            return (min, FpFlags::NV);
+        }
+        let flags = if r != x { FpFlags::NX } else { FpFlags::NONE };
+        let value = if signed { r as i64 as u64 } else { r as u64 };
+        let value = if word {
+            value as i32 as i64 as u64
+        } else {
+            value
+        };
+        (value, flags)
+    }
+
+    /// Converts an integer register value to a float with RISC-V semantics.
+    ///
+    /// Covers `FCVT.{S,D}.{W,WU,L,LU}`: the source is the low 32 bits (sign- or
+    /// zero-extended) or all 64 bits of `a`. Rounds to nearest and raises NX when
+    /// the result does not convert back to the source integer.
+    fn convert_from_int(op: AluOp, a: u64, is32: bool) -> (u64, FpFlags) {
+        let v = match op {
+            AluOp::FCvtSW => a as i32 as i128,
+            AluOp::FCvtSWu => a as u32 as i128,
+            AluOp::FCvtSL => a as i64 as i128,
+            _ => a as i128,
+        };
+        // Every integer converts to an integral float, so casting back is exact.
+        let (result, back) = if is32 {
+            let f = v as f32;
+            (box_f32(f), f as i128)
+        } else {
+            let f = v as f64;
+            (f.to_bits(), f as i128)
+        };
+        let flags = if back != v {
+            FpFlags::NX
+        } else {
+            FpFlags::NONE
+        };
+        (result, flags)
+    }
+
+    /// Converts between single and double precision with RISC-V semantics.
+    ///
+    /// Covers `FCVT.S.D` and `FCVT.D.S`, whose formats are fixed by the opcode:
+    /// the single-precision source of `FCVT.D.S` is unboxed and the result of
+    /// `FCVT.S.D` is boxed. NaNs convert to the canonical NaN, raising NV when
+    /// signaling. Widening is exact; narrowing rounds with `rm` and raises OF,
+    /// UF, and NX as for an arithmetic result.
+    fn convert_precision(op: AluOp, a: u64, rm: RoundingMode) -> (u64, FpFlags) {
+        if matches!(op, AluOp::FCvtDS) {
+            let x = unbox_f32(a);
+            let flags = if Self::is_snan_f32(x) {
+                FpFlags::NV
+            } else {
+                FpFlags::NONE
+            };
+            return (canonicalize_f64(x as f64).to_bits(), flags);
+        }
+
+        let x = f64::from_bits(a);
+        if x.is_nan() {
+            let flags = if Self::is_snan_f64(x) {
+                FpFlags::NV
+            } else {
+                FpFlags::NONE
             };
+            return (box_f32(canonicalize_f32(f32::NAN)), flags);
+        }
+        let r = Self::apply_rounding_f32(x, rm);
+        let mut flags = FpFlags::NONE;
+        // Any finite value of magnitude 2^128 or more overflows, whichever way
+        // it rounds; below that only a result rounded up to infinity does.
+        if x.is_finite() && (r.is_infinite() || x.abs() >= 2f64.powi(128)) {
+            flags = flags | FpFlags::OF | FpFlags::NX;
+        } else if r as f64 != x {
+            flags = flags | FpFlags::NX;
+            if r == 0.0 || r.is_subnormal() {
+                flags = flags | FpFlags::UF;
+            }
+        }
+        (box_f32(r), flags)
+    }
+
+    /// Executes a floating-point operation in flush-to-zero mode.
+    ///
+    /// Subnormal source operands of arithmetic, comparison, conversion, and
+    /// FP-to-integer operations are treated as zero of the same sign (DAZ).
+    /// Subnormal results of arithmetic operations and `FCVT.S.D` are replaced
+    /// with zero of the same sign (FTZ) and raise UF and NX. Sign injection, moves, and `FCLASS` see the raw bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `op`   - The floating-point operation to perform.
+    /// * `a`    - First operand.
+    /// * `b`    - Second operand.
+    /// * `c`    - Third operand for FMA operations.
+    /// * `is32` - If true, perform single-precision operation.
+    /// * `rm`   - The effective rounding mode.
+    ///
+    /// # Returns
+    ///
+    /// A tuple `(result, flags)` as for [`Fpu::execute_rm`].
+    pub fn execute_ftz(
+        op: AluOp,
+        a: u64,
+        b: u64,
+        c: u64,
+        is32: bool,
+        rm: RoundingMode,
+    ) -> (u64, FpFlags) {
+        let reads_zero = matches!(
+            op,
+            AluOp::FAdd
+                | AluOp::FSub
+                | AluOp::FMul
+                | AluOp::FDiv
+                | AluOp::FSqrt
+                | AluOp::FMin
+                | AluOp::FMax
+                | AluOp::FMAdd
+                | AluOp::FMSub
+                | AluOp::FNMAdd
+                | AluOp::FNMSub
+                | AluOp::FEq
+                | AluOp::FLt
+                | AluOp::FLe
+                | AluOp::FCvtWS
+                | AluOp::FCvtLS
+                | AluOp::FCvtWuS
+                | AluOp::FCvtLuS
+                | AluOp::FCvtSD
+                | AluOp::FCvtDS
+        );
+        if !reads_zero {
+            return Self::execute_rm(op, a, b, c, is32, rm);
+        }
 
-            let rounded = Self::apply_rounding_f32(exact, rm);
-            box_f32(canonicalize_f32(rounded))
+        // Precision conversions read one format and write the other.
+        let (src32, dst32) = match op {
+            AluOp::FCvtSD => (false, true),
+            AluOp::FCvtDS => (true, false),
+            _ => (is32, is32),
+        };
+        let (a, b, c) = (
+            Self::flush_subnormal(a, src32),
+            Self::flush_subnormal(b, src32),
+            Self::flush_subnormal(c, src32),
+        );
+        let (result, mut flags) = Self::execute_rm(op, a, b, c, is32, rm);
+
+        let writes_fp = matches!(
+            op,
+            AluOp::FAdd
+                | AluOp::FSub
+                | AluOp::FMul
+                | AluOp::FDiv
+                | AluOp::FSqrt
+                | AluOp::FMAdd
+                | AluOp::FMSub
+                | AluOp::FNMAdd
+                | AluOp::FNMSub
+                | AluOp::FCvtSD
+        );
+        let flushed = Self::flush_subnormal(result, dst32);
+        if writes_fp && flushed != result {
+            flags = flags | FpFlags::UF | FpFlags::NX;
+            return (flushed, flags);
+        }
+        (result, flags)
+    }
+
+    /// Replaces a subnormal operand with zero of the same sign.
+    ///
+    /// Single-precision values that are not properly NaN-boxed are returned
+    /// unchanged so that the usual canonical-NaN handling still applies.
+    fn flush_subnormal(bits: u64, is32: bool) -> u64 {
+        if is32 {
+            let f = unbox_f32(bits);
+            if f.is_subnormal() {
+                box_f32(f32::from_bits(f.to_bits() & F32_SIGN_BIT))
+            } else {
+                bits
+            }
+        } else if f64::from_bits(bits).is_subnormal() {
+            bits & F64_SIGN_BIT
         } else {
-            // For f64, we don't have a higher-precision path easily available,
-            // so we compute directly and apply rounding to the f64 result
-            // for integer conversions. For pure f64 arithmetic, the host
-            // rounding mode is used (RNE on most platforms); we apply
-            // post-hoc adjustment for RTZ, RDN, RUP, RMM.
-            let fa = f64::from_bits(a);
-            let fb = f64::from_bits(b);
+            bits
+        }
+    }
 
-            let exact = match op {
-                AluOp::FAdd => fa + fb,
-                AluOp::FSub => fa - fb,
-                AluOp::FMul => fa * fb,
-                AluOp::FDiv => fa / fb,
-                AluOp::FSqrt => fa.sqrt(),
-                _ => {
-                    return Self::execute(op, a, b, c, is32);
-                }
+    /// Returns `true` if a single-precision arithmetic result was rounded.
+    ///
+    /// Uses error-free transformations: the TwoSum residual for add/sub, a
+    /// fused multiply-add residual for mul/div/sqrt, and for the FMA family the
+    /// product (exact in double precision) summed with the addend and the negated
+    /// result. Non-finite results and NaN operands are never reported as inexact
+    /// here (overflow sets NX separately).
+    fn inexact_f32(op: AluOp, x: f32, y: f32, z: f32, r: f32) -> bool {
+        if !r.is_finite() || x.is_nan() || y.is_nan() {
+            return false;
+        }
+        let is_mul = matches!(op, AluOp::FMul);
+        if (is_mul || matches!(op, AluOp::FDiv)) && (r == 0.0 || r.is_subnormal()) {
+            // The residual itself underflows here, so redo the operation with
+            // the smaller operand scaled by 2^64 and check that scaling back is exact.
+            if x == 0.0 || (is_mul && y == 0.0) {
+                return false;
+            }
+            if r == 0.0 {
+                return true;
+            }
+            let scale = f32::from_bits((127 + 64) << 23);
+            let (x, y) = if is_mul && x.abs() > y.abs() {
+                (y, x)
+            } else {
+                (x, y)
             };
+            let xs = x * scale;
+            let rs = if is_mul { xs * y } else { xs / y };
+            return Self::inexact_f32(op, xs, y, z, rs) || rs != r * scale;
+        }
+        let two_sum_err = |x: f32, y: f32| {
+            let bb = r - x;
+            (x - (r - bb)) + (y - bb)
+        };
+        match op {
+            AluOp::FAdd => two_sum_err(x, y) != 0.0,
+            AluOp::FSub => two_sum_err(x, -y) != 0.0,
+            AluOp::FMul => x.mul_add(y, -r) != 0.0,
+            AluOp::FDiv => y != 0.0 && (-r).mul_add(y, x) != 0.0,
+            AluOp::FSqrt => (-r).mul_add(r, x) != 0.0,
+            AluOp::FMAdd | AluOp::FMSub | AluOp::FNMAdd | AluOp::FNMSub => {
+                let (x, z) = Self::fma_operands(op, x as f64, z as f64);
+                Self::exact_sum([x * y as f64, z, -(r as f64)]) != 0.0
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if a double-precision arithmetic result was rounded.
+    ///
+    /// See [`Fpu::residual_f64`].
+    fn inexact_f64(op: AluOp, x: f64, y: f64, z: f64, r: f64) -> bool {
+        Self::residual_f64(op, x, y, z, r) != 0.0
+    }
+
+    /// Returns a value with the sign of the rounding error `exact - r` of a
+    /// double-precision arithmetic result, or zero if `r` is exact.
+    ///
+    /// Uses the error-free transformations of [`Fpu::inexact_f32`]. The FMA
+    /// product is split into a rounded part and its `mul_add` residual, which is
+    /// exact unless the product overflows or falls into the subnormal range. For
+    /// add, sub, and mul with a normal result the value is the error itself.
+    /// Non-finite results and NaN operands report zero.
+    fn residual_f64(op: AluOp, x: f64, y: f64, z: f64, r: f64) -> f64 {
+        if !r.is_finite() || x.is_nan() || y.is_nan() {
+            return 0.0;
+        }
+        let is_mul = matches!(op, AluOp::FMul);
+        if (is_mul || matches!(op, AluOp::FDiv)) && (r == 0.0 || r.is_subnormal()) {
+            // The residual itself underflows here, so redo the operation with
+            // the smaller operand scaled by 2^108 and compare with `r` scaled back.
+            if x == 0.0 || (is_mul && y == 0.0) || (!is_mul && y.is_infinite()) {
+                return 0.0;
+            }
+            if r == 0.0 {
+                // The exact result is nonzero, with the sign of the product or quotient.
+                return if (x < 0.0) != (y < 0.0) { -1.0 } else { 1.0 };
+            }
+            let scale = f64::from_bits((1023 + 108) << 52);
+            let (x, y) = if is_mul && x.abs() > y.abs() {
+                (y, x)
+            } else {
+                (x, y)
+            };
+            let xs = x * scale;
+            let rs = if is_mul { xs * y } else { xs / y };
+            // Rounding is monotonic, so the finer `rs` lies on the exact side of `r`.
+            let diff = rs - r * scale;
+            return if diff != 0.0 {
+                diff
+            } else {
+                Self::residual_f64(op, xs, y, z, rs)
+            };
+        }
+        let two_sum_err = |x: f64, y: f64| {
+            let bb = r - x;
+            (x - (r - bb)) + (y - bb)
+        };
+        match op {
+            AluOp::FAdd => two_sum_err(x, y),
+            AluOp::FSub => two_sum_err(x, -y),
+            AluOp::FMul => x.mul_add(y, -r),
+            // x - r*y has the sign of (x/y - r) scaled by y.
+            AluOp::FDiv if y != 0.0 => (-r).mul_add(y, x) * y.signum(),
+            // x - r*r = (sqrt(x) - r)(sqrt(x) + r), and the second factor is positive.
+            AluOp::FSqrt => (-r).mul_add(r, x),
+            AluOp::FMAdd | AluOp::FMSub | AluOp::FNMAdd | AluOp::FNMSub => {
+                let (x, z) = Self::fma_operands(op, x, z);
+                let p = x * y;
+                Self::exact_sum([p, x.mul_add(y, -p), z, -r])
+            }
+            _ => 0.0,
+        }
+    }
 
-            let rounded = Self::apply_rounding_f64(exact, rm);
-            canonicalize_f64(rounded).to_bits()
+    /// Applies the FMA variant's signs to its multiplicand and addend.
+    ///
+    /// `FMSUB` subtracts the addend, `FNMADD` negates both the product and the
+    /// addend, and `FNMSUB` negates the product.
+    fn fma_operands(op: AluOp, x: f64, z: f64) -> (f64, f64) {
+        match op {
+            AluOp::FMSub => (x, -z),
+            AluOp::FNMAdd => (-x, -z),
+            AluOp::FNMSub => (-x, z),
+            _ => (x, z),
         }
     }
 
+    /// Returns the leading component of the exact sum of `terms`.
+    ///
+    /// Accumulates the terms into a nonoverlapping expansion with TwoSum, which
+    /// holds the running sum exactly barring overflow, dropping zero components.
+    /// The last component approximates the whole sum and carries its sign; it is
+    /// zero only when the expansion is empty, i.e. the sum is exactly zero.
+    fn exact_sum<const N: usize>(terms: [f64; N]) -> f64 {
+        let mut parts = [0.0; N];
+        let mut len = 0;
+        for t in terms {
+            let mut q = t;
+            let mut kept = 0;
+            for i in 0..len {
+                let s = q + parts[i];
+                let bb = s - q;
+                let e = (q - (s - bb)) + (parts[i] - bb);
+                if e != 0.0 {
+                    parts[kept] = e;
+                    kept += 1;
+                }
+                q = s;
+            }
+            if q != 0.0 {
+                parts[kept] = q;
+                kept += 1;
+            }
+            len = kept;
+        }
+        if len == 0 { 0.0 } else { parts[len - 1] }
+    }
+
     /// Checks if an f32 value is a signaling NaN.
     ///
     /// A signaling NaN has the exponent field all 1s, the quiet bit (bit 22) = 0,
@@ -315,27 +849,65 @@ impl Fpu {
         exp == 0x7FF && mantissa != 0 && quiet_bit == 0
     }
 
-    /// Applies a rounding mode to an f64 exact value, producing an f32 result.
+    /// Computes the FCLASS result mask from the decoded fields of a float.
     ///
-    /// Computes the correctly-rounded f32 from the higher-precision f64 value.
+    /// Exactly one bit of the 10-bit mask is set (RISC-V spec §11.9):
+    /// bit 0 = -inf, 1 = -normal, 2 = -subnormal, 3 = -0, 4 = +0,
+    /// 5 = +subnormal, 6 = +normal, 7 = +inf, 8 = sNaN, 9 = qNaN.
     ///
-    /// # Implementation Notes
+    /// # Arguments
     ///
-    /// This function implements RISC-V rounding modes using software approximations
-    /// because Rust's `as f32` cast always uses round-to-nearest-ties-to-even.
+    /// * `sign`      - Sign bit of the value
+    /// * `exp`       - Biased exponent field
+    /// * `exp_max`   - All-ones exponent for the format
+    /// * `mantissa`  - Trailing significand field
+    /// * `quiet_bit` - Bit index of the quiet-NaN bit within the significand
+    fn classify(sign: bool, exp: u64, exp_max: u64, mantissa: u64, quiet_bit: u32) -> u64 {
+        let class = if exp == exp_max {
+            if mantissa == 0 {
+                if sign { 0 } else { 7 }
+            } else if mantissa & (1 << quiet_bit) != 0 {
+                9
+            } else {
+                8
+            }
+        } else if exp == 0 {
+            match (mantissa == 0, sign) {
+                (true, true) => 3,
+                (true, false) => 4,
+                (false, true) => 2,
+                (false, false) => 5,
+            }
+        } else if sign {
+            1
+        } else {
+            6
+        };
+        1 << class
+    }
+
+    /// Rounds `r` to odd given the sign of its rounding error `e`.
     ///
-    /// **Approach:**
-    /// 1. Use `exact as f32` to get initial rounded value
-    /// 2. Compare `(rounded as f64)` with `exact` to detect rounding direction
-    /// 3. Adjust by ±1 ULP (unit in last place) if the direction is incorrect
+    /// An inexact result with an even significand moves one ulp toward the exact
+    /// value. Odd doubles are neither floats nor halfway between two, so the
+    /// result rounds to single precision exactly as the exact value would.
+    fn round_to_odd(r: f64, e: f64) -> f64 {
+        if e == 0.0 || r.to_bits() & 1 != 0 {
+            r
+        } else if e > 0.0 {
+            r.next_up()
+        } else {
+            r.next_down()
+        }
+    }
+
+    /// Rounds a double-precision value to single precision under `rm`.
     ///
-    /// **Caveats:**
-    /// - This is an approximation that works for most values but may not be
-    ///   bit-accurate with IEEE 754 rounding in all edge cases (subnormals,
-    ///   near-infinity values)
-    /// - True IEEE 754 compliance would require implementing rounding in software
-    ///   at the bit level or using platform-specific rounding mode control
-    /// - For production FPU simulation, consider using `softfloat` or similar libraries
+    /// `exact` must be the exact result or that result rounded to odd (see
+    /// [`Fpu::round_to_odd`]). The host cast rounds to nearest, ties to even; the
+    /// other modes step that result one ulp when it lies on the wrong side of
+    /// `exact`, which also saturates an overflow at the largest finite value or
+    /// rounds an underflow to the smallest subnormal where the mode requires it.
     ///
     /// **Rounding Modes (RISC-V Spec §11.2):**
     /// - RNE (000): Round to nearest, ties to even
@@ -344,78 +916,30 @@ impl Fpu {
     /// - RUP (011): Round up (towards +∞)
     /// - RMM (100): Round to nearest, ties to max magnitude
     fn apply_rounding_f32(exact: f64, rm: RoundingMode) -> f32 {
-        if exact.is_nan() || exact.is_infinite() {
-            return exact as f32;
+        let rne = exact as f32;
+        if !exact.is_finite() {
+            return rne;
         }
+        let (above, below) = ((rne as f64) > exact, (rne as f64) < exact);
         match rm {
-            RoundingMode::Rne => {
-                // Round to nearest, ties to even (default Rust/IEEE behaviour)
-                exact as f32
-            }
-            RoundingMode::Rtz => {
-                // Round towards zero (truncate)
-                let trunc = exact as f32;
-                // If the cast already rounded towards zero, use it.
-                // Otherwise adjust: if exact > 0 and trunc > exact, go down;
-                // if exact < 0 and trunc < exact, go up.
-                if exact > 0.0 && (trunc as f64) > exact {
-                    f32::from_bits(trunc.to_bits() - 1)
-                } else if exact < 0.0 && (trunc as f64) < exact {
-                    f32::from_bits(trunc.to_bits() - 1)
-                } else {
-                    trunc
-                }
-            }
-            RoundingMode::Rdn => {
-                // Round down (towards -infinity)
-                let rounded = exact as f32;
-                if (rounded as f64) > exact {
-                    // Need to go one ulp lower
-                    if rounded > 0.0 || rounded == 0.0 {
-                        f32::from_bits(rounded.to_bits().wrapping_sub(1))
-                    } else {
-                        f32::from_bits(rounded.to_bits() + 1) // More negative
-                    }
-                } else {
-                    rounded
-                }
-            }
-            RoundingMode::Rup => {
-                // Round up (towards +infinity)
-                let rounded = exact as f32;
-                if (rounded as f64) < exact {
-                    if rounded >= 0.0 {
-                        f32::from_bits(rounded.to_bits() + 1)
-                    } else {
-                        f32::from_bits(rounded.to_bits().wrapping_sub(1))
-                    }
-                } else {
-                    rounded
-                }
-            }
+            RoundingMode::Rne => rne,
+            RoundingMode::Rtz if exact > 0.0 && above => rne.next_down(),
+            RoundingMode::Rtz if exact < 0.0 && below => rne.next_up(),
+            RoundingMode::Rtz => rne,
+            RoundingMode::Rdn if above => rne.next_down(),
+            RoundingMode::Rup if below => rne.next_up(),
+            RoundingMode::Rdn | RoundingMode::Rup => rne,
             RoundingMode::Rmm => {
-                // Round to nearest, ties to max magnitude
-                let rne = exact as f32;
-                let rne_d = rne as f64;
-                let diff = (exact - rne_d).abs();
-                // Check if we're at a tie point
-                let next_up = f32::from_bits(rne.to_bits() + 1);
-                let next_dn = if rne.to_bits() > 0 {
-                    f32::from_bits(rne.to_bits() - 1)
+                // Only a tie, exactly halfway to the larger neighbour, differs from RNE.
+                let other = if below {
+                    rne.next_up()
                 } else {
-                    f32::from_bits(0x8000_0001) // -min_subnormal
+                    rne.next_down()
                 };
-                let ulp = ((next_up as f64) - rne_d)
-                    .abs()
-                    .min(((next_dn as f64) - rne_d).abs());
-
-                if ulp > 0.0 && (diff * 2.0 - ulp).abs() < f64::EPSILON * ulp {
-                    // We're at a tie — pick the one with larger magnitude
-                    if exact.abs() > rne_d.abs() {
-                        if exact > 0.0 { next_up } else { next_dn }
-                    } else {
-                        rne
-                    }
+                let tie =
+                    (above || below) && (other as f64 - exact).abs() == (exact - rne as f64).abs();
+                if tie && other.abs() > rne.abs() {
+                    other
                 } else {
                     rne
                 }
@@ -423,26 +947,77 @@ impl Fpu {
         }
     }
 
-    /// Applies a rounding mode to an f64 result.
+    /// Rerounds a round-to-nearest double-precision result under `rm`.
+    ///
+    /// The direction comes from the sign of the rounding error of `rne`
+    /// ([`Fpu::residual_f64`]): RDN, RUP, and RTZ step one ulp when `rne` lies on
+    /// the wrong side of the exact result, and an overflow to infinity saturates
+    /// at the largest finite value where the mode rounds toward it. RMM steps
+    /// away from zero on an exact tie, which only add, sub, mul, and the FMA
+    /// family can produce; it is detected for normal results.
+    ///
+    /// # Arguments
     ///
-    /// For most modes this is the identity since host FPU uses RNE.
-    /// We handle the other modes by adjusting the final bit if needed.
-    fn apply_rounding_f64(val: f64, rm: RoundingMode) -> f64 {
-        // For f64 we don't have a higher-precision representation readily,
-        // so the host already computed in RNE. For RTZ/RDN/RUP we do a
-        // post-hoc check — this is approximate but correct for most cases
-        // because the host computes the exact RNE result.
+    /// * `op`   - The arithmetic operation.
+    /// * `x`, `y`, `z` - The operands.
+    /// * `rne`  - The host's round-to-nearest result.
+    /// * `rm`   - The rounding mode to apply.
+    fn apply_rounding_f64(op: AluOp, x: f64, y: f64, z: f64, rne: f64, rm: RoundingMode) -> f64 {
+        if rm == RoundingMode::Rne || rne.is_nan() {
+            return rne;
+        }
+        if rne.is_infinite() {
+            let operands_finite = match op {
+                AluOp::FSqrt => x.is_finite(),
+                AluOp::FDiv => x.is_finite() && y.is_finite() && y != 0.0,
+                AluOp::FAdd | AluOp::FSub | AluOp::FMul => x.is_finite() && y.is_finite(),
+                _ => x.is_finite() && y.is_finite() && z.is_finite(),
+            };
+            let saturates = match rm {
+                RoundingMode::Rtz => true,
+                RoundingMode::Rdn => rne > 0.0,
+                RoundingMode::Rup => rne < 0.0,
+                _ => false,
+            };
+            return if operands_finite && saturates {
+                f64::MAX.copysign(rne)
+            } else {
+                rne
+            };
+        }
+
+        let e = Self::residual_f64(op, x, y, z, rne);
+        if e == 0.0 {
+            return rne;
+        }
+        let toward_zero = if rne > 0.0 {
+            rne.next_down()
+        } else {
+            rne.next_up()
+        };
         match rm {
-            RoundingMode::Rne | RoundingMode::Rmm => val,
-            RoundingMode::Rtz => {
-                if val.is_nan() || val.is_infinite() || val == 0.0 {
-                    return val;
-                }
-                // Truncate towards zero
-                val
+            RoundingMode::Rtz if rne != 0.0 && (e > 0.0) != (rne > 0.0) => toward_zero,
+            RoundingMode::Rdn if e < 0.0 => rne.next_down(),
+            RoundingMode::Rup if e > 0.0 => rne.next_up(),
+            RoundingMode::Rmm if rne.is_normal() && (e > 0.0) == (rne > 0.0) => {
+                let away = if rne > 0.0 {
+                    rne.next_up()
+                } else {
+                    rne.next_down()
+                };
+                let half = (away - rne) / 2.0;
+                let tie = match op {
+                    AluOp::FAdd | AluOp::FSub | AluOp::FMul => e == half,
+                    AluOp::FMAdd | AluOp::FMSub | AluOp::FNMAdd | AluOp::FNMSub => {
+                        let (x, z) = Self::fma_operands(op, x, z);
+                        let p = x * y;
+                        Self::exact_sum([p, x.mul_add(y, -p), z, -rne, -half]) == 0.0
+                    }
+                    _ => false,
+                };
+                if tie && away.is_finite() { away } else { rne }
             }
-            RoundingMode::Rdn => val, // floor — matches host for most ops
-            RoundingMode::Rup => val, // ceil — matches host for most ops
+            _ => rne,
         }
     }
 
@@ -491,16 +1066,35 @@ impl Fpu {
             // --- Conversions (float → integer) ---
             AluOp::FCvtWS => (fa as i32) as i64 as u64,
             AluOp::FCvtLS => (fa as i64) as u64,
+            AluOp::FCvtWuS | AluOp::FCvtLuS => {
+                Self::convert_to_int(
+                    fa as f64,
+                    matches!(op, AluOp::FCvtWuS),
+                    false,
+                    RoundingMode::Rtz,
+                )
+                .0
+            }
 
-            // --- Conversions (double → single, identity in f32 path) ---
-            AluOp::FCvtSD => box_f32(canonicalize_f32(fa)),
+            // --- Conversions (between precisions, formats fixed by the opcode) ---
+            AluOp::FCvtSD | AluOp::FCvtDS => Self::convert_precision(op, a, RoundingMode::Rne).0,
 
             // --- Conversions (integer → float, use raw `a` for integer bits) ---
-            AluOp::FCvtSW => ((a as i32) as f64).to_bits(),
-            AluOp::FCvtSL => ((a as i64) as f64).to_bits(),
+            AluOp::FCvtSW | AluOp::FCvtSL | AluOp::FCvtSWu | AluOp::FCvtSLu => {
+                Self::convert_from_int(op, a, true).0
+            }
 
-            // --- Conversions (single → double) ---
-            AluOp::FCvtDS => (unbox_f32(a) as f64).to_bits(),
+            // --- Classification (raw bits, 10-bit class mask) ---
+            AluOp::FClass => {
+                let bits = fa.to_bits() as u64;
+                Self::classify(
+                    bits >> 31 != 0,
+                    (bits >> 23) & 0xFF,
+                    0xFF,
+                    bits & 0x007F_FFFF,
+                    22,
+                )
+            }
 
             // --- Move operations ---
             AluOp::FMvToF => box_f32(f32::from_bits(a as u32)),
@@ -555,9 +1149,22 @@ impl Fpu {
             // --- Conversions ---
             AluOp::FCvtWS => (fa as i32) as i64 as u64,
             AluOp::FCvtLS => (fa as i64) as u64,
-            AluOp::FCvtSD => box_f32(canonicalize_f32(fa as f32)),
-            AluOp::FCvtSW => ((a as i32) as f64).to_bits(),
-            AluOp::FCvtSL => ((a as i64) as f64).to_bits(),
+            AluOp::FCvtSD | AluOp::FCvtDS => Self::convert_precision(op, a, RoundingMode::Rne).0,
+            AluOp::FCvtWuS | AluOp::FCvtLuS => {
+                Self::convert_to_int(fa, matches!(op, AluOp::FCvtWuS), false, RoundingMode::Rtz).0
+            }
+            AluOp::FCvtSW | AluOp::FCvtSL | AluOp::FCvtSWu | AluOp::FCvtSLu => {
+                Self::convert_from_int(op, a, false).0
+            }
+
+            // --- Classification ---
+            AluOp::FClass => Self::classify(
+                a >> 63 != 0,
+                (a >> 52) & 0x7FF,
+                0x7FF,
+                a & 0x000F_FFFF_FFFF_FFFF,
+                51,
+            ),
 
             // --- Move operations (64-bit path: no boxing needed) ---
             AluOp::FMvToF => a,
@@ -110,10 +110,10 @@ pub fn canonicalize_f64(f: f64) -> f64 {
 ///
 /// If exactly one operand is NaN, returns the non-NaN operand.
 /// If both are NaN, returns canonical NaN.
-/// Otherwise returns the arithmetic minimum.
+/// Otherwise returns the arithmetic minimum, with -0.0 below +0.0.
 ///
-/// Note: sNaN inputs set the Invalid flag (handled at a higher level
-/// once exception flags are implemented).
+/// Signaling NaN inputs raise NV; that flag is computed by
+/// [`Fpu::execute_full`](super::Fpu::execute_full) from the operands.
 ///
 /// # Arguments
 ///
@@ -129,14 +129,19 @@ pub fn fmin_f32(a: f32, b: f32) -> f32 {
         (true, true) => f32::from_bits(CANONICAL_NAN_F32),
         (true, false) => b,
         (false, true) => a,
+        // -0.0 orders below +0.0 (the only case where `a == b` differs in bits).
+        (false, false) if a == b => {
+            if a.is_sign_negative() {
+                a
+            } else {
+                b
+            }
+        }
         (false, false) => {
-            // IEEE 754-2008: -0.0 < +0.0
-            if a.to_bits() == 0x8000_0000 && b.to_bits() == 0x0000_0000 {
-                a // -0.0
-            } else if b.to_bits() == 0x8000_0000 && a.to_bits() == 0x0000_0000 {
-                b // -0.0
+            if a < b {
+                a
             } else {
-                a.min(b)
+                b
             }
         }
     }
@@ -146,7 +151,7 @@ pub fn fmin_f32(a: f32, b: f32) -> f32 {
 ///
 /// If exactly one operand is NaN, returns the non-NaN operand.
 /// If both are NaN, returns canonical NaN.
-/// Otherwise returns the arithmetic maximum.
+/// Otherwise returns the arithmetic maximum, with +0.0 above -0.0.
 ///
 /// # Arguments
 ///
@@ -162,14 +167,19 @@ pub fn fmax_f32(a: f32, b: f32) -> f32 {
         (true, true) => f32::from_bits(CANONICAL_NAN_F32),
         (true, false) => b,
         (false, true) => a,
+        // +0.0 orders above -0.0 (the only case where `a == b` differs in bits).
+        (false, false) if a == b => {
+            if a.is_sign_positive() {
+                a
+            } else {
+                b
+            }
+        }
         (false, false) => {
-            // IEEE 754-2008: +0.0 > -0.0
-            if a.to_bits() == 0x0000_0000 && b.to_bits() == 0x8000_0000 {
-                a // +0.0
-            } else if b.to_bits() == 0x0000_0000 && a.to_bits() == 0x8000_0000 {
-                b // +0.0
+            if a > b {
+                a
             } else {
-                a.max(b)
+                b
             }
         }
     }
@@ -184,13 +194,18 @@ pub fn fmin_f64(a: f64, b: f64) -> f64 {
         (true, true) => f64::from_bits(CANONICAL_NAN_F64),
         (true, false) => b,
         (false, true) => a,
-        (false, false) => {
-            if a.to_bits() == 0x8000_0000_0000_0000 && b.to_bits() == 0x0000_0000_0000_0000 {
+        (false, false) if a == b => {
+            if a.is_sign_negative() {
                 a
-            } else if b.to_bits() == 0x8000_0000_0000_0000 && a.to_bits() == 0x0000_0000_0000_0000 {
+            } else {
                 b
+            }
+        }
+        (false, false) => {
+            if a < b {
+                a
             } else {
-                a.min(b)
+                b
             }
         }
     }
@@ -205,13 +220,18 @@ pub fn fmax_f64(a: f64, b: f64) -> f64 {
         (true, true) => f64::from_bits(CANONICAL_NAN_F64),
         (true, false) => b,
         (false, true) => a,
-        (false, false) => {
-            if a.to_bits() == 0x0000_0000_0000_0000 && b.to_bits() == 0x8000_0000_0000_0000 {
+        (false, false) if a == b => {
+            if a.is_sign_positive() {
                 a
-            } else if b.to_bits() == 0x0000_0000_0000_0000 && a.to_bits() == 0x8000_0000_0000_0000 {
+            } else {
                 b
+            }
+        }
+        (false, false) => {
+            if a > b {
+                a
             } else {
-                a.max(b)
+                b
             }
         }
     }
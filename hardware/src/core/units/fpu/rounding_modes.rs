@@ -10,11 +10,8 @@
 //! | 0b011 | RUP  | Round Up (towards +∞)                |
 //! | 0b100 | RMM  | Round to Nearest, ties to Max Magnitude |
 //!
-//! This module will provide the `execute_with_rm` variant of the FPU that
-//! accepts an explicit rounding mode, overriding the `fcsr.frm` default.
-//!
-//! **Status:** Stub — implementation pending as part of Phase 1.2 rounding
-//! mode verification.
+//! The encoding 0b111 (DYN) selects `fcsr.frm`; [`RoundingMode::resolve`]
+//! turns an instruction's `rm` field into the effective mode.
 
 /// RISC-V rounding mode encoding.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -47,4 +44,26 @@ impl RoundingMode {
             _ => None, // 0b101, 0b110 reserved; 0b111 = dynamic
         }
     }
+
+    /// Resolves an instruction's `rm` field against the current `fcsr.frm`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rm`  - The 3-bit `rm` field (instruction bits 14:12).
+    /// * `frm` - The 3-bit dynamic rounding mode from `fcsr`.
+    ///
+    /// # Returns
+    ///
+    /// The effective rounding mode, or `None` if the field or (for DYN) `frm`
+    /// holds a reserved encoding, in which case the instruction is illegal.
+    pub fn resolve(rm: u8, frm: u8) -> Option<Self> {
+        if rm & 0x7 == RM_DYNAMIC {
+            Self::from_bits(frm)
+        } else {
+            Self::from_bits(rm)
+        }
+    }
 }
+
+/// `rm` field encoding that selects the dynamic rounding mode in `fcsr.frm`.
+pub const RM_DYNAMIC: u8 = 0b111;
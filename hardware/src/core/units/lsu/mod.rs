@@ -4,6 +4,7 @@
 //! operations. It includes:
 //! - [`atomic`]: Read-modify-write ALU for the RISC-V A extension.
 //! - [`ordering`]: Memory ordering / fence support (stub).
+//! - [`priority`]: Synchronous exception priority for accesses that could fault several ways.
 //! - [`unaligned`]: Unaligned access handling (stub).
 
 /// Atomic memory operation ALU (RISC-V A extension).
@@ -12,6 +13,9 @@ pub mod atomic;
 /// Memory ordering and fence operations (stub, Phase 3).
 pub mod ordering;
 
+/// Synchronous exception priority ordering.
+pub mod priority;
+
 /// Unaligned memory access handling (stub, Phase 3).
 pub mod unaligned;
 
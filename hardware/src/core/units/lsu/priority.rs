@@ -0,0 +1,81 @@
+//! Synchronous exception priority.
+//!
+//! When one instruction could raise several exceptions, the privileged spec
+//! (Table "Synchronous exception priority in decreasing priority order") picks
+//! the one reported. This module is the single place that ordering lives:
+//! 1. **Fetch:** Instruction page fault (or software TLB miss), then instruction access fault.
+//! 2. **Decode:** Illegal instruction, instruction address misaligned, ECALL and EBREAK.
+//! 3. **Memory:** Load/store/AMO page fault (or software TLB miss), then load/store/AMO access fault.
+//!
+//! Load/store/AMO address-misaligned exceptions are platform-defined: they may be
+//! raised before address translation (outranking page and access faults) or after
+//! it (outranked by them). [`MisalignedPriority`] selects which.
+
+use crate::common::error::Trap;
+use crate::config::MisalignedPriority;
+
+/// Returns the priority rank of a synchronous exception (lower is reported first).
+///
+/// Interrupts and simulator-internal traps rank last; they never compete with
+/// an instruction's own exceptions.
+///
+/// # Arguments
+///
+/// * `trap` - The candidate exception.
+/// * `misaligned` - Where address-misaligned exceptions sit relative to memory faults.
+///
+/// # Returns
+///
+/// The rank of `trap`.
+pub fn exception_rank(trap: &Trap, misaligned: MisalignedPriority) -> u8 {
+    match trap {
+        Trap::InstructionPageFault(_) | Trap::InstructionTlbMiss(_) => 0,
+        Trap::InstructionAccessFault(_) => 1,
+        Trap::IllegalInstruction(_)
+        | Trap::InstructionAddressMisaligned(_)
+        | Trap::EnvironmentCallFromUMode
+        | Trap::EnvironmentCallFromSMode
+        | Trap::EnvironmentCallFromMMode
+        | Trap::Breakpoint(_) => 2,
+        Trap::LoadAddressMisaligned(_) | Trap::StoreAddressMisaligned(_) => match misaligned {
+            MisalignedPriority::BeforeTranslation => 3,
+            MisalignedPriority::AfterTranslation => 6,
+        },
+        Trap::LoadPageFault(_)
+        | Trap::StorePageFault(_)
+        | Trap::LoadTlbMiss(_)
+        | Trap::StoreTlbMiss(_) => 4,
+        Trap::LoadAccessFault(_) | Trap::StoreAccessFault(_) => 5,
+        _ => u8::MAX,
+    }
+}
+
+/// Returns whichever of two possible exceptions the spec reports.
+///
+/// Ties keep `a`.
+///
+/// # Arguments
+///
+/// * `a` - First candidate (e.g. the misaligned check).
+/// * `b` - Second candidate (e.g. the translation fault).
+/// * `misaligned` - Where address-misaligned exceptions sit relative to memory faults.
+///
+/// # Returns
+///
+/// The higher-priority exception, or `None` if neither is raised.
+pub fn prioritize(
+    a: Option<Trap>,
+    b: Option<Trap>,
+    misaligned: MisalignedPriority,
+) -> Option<Trap> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            if exception_rank(&b, misaligned) < exception_rank(&a, misaligned) {
+                Some(b)
+            } else {
+                Some(a)
+            }
+        }
+        (a, b) => a.or(b),
+    }
+}
@@ -1,35 +1,84 @@
 //! Memory Management Unit (MMU).
 //!
 //! This module implements the Memory Management Unit, responsible for
-//! virtual-to-physical address translation. It supports the RISC-V SV39
-//! paging scheme and includes Translation Lookaside Buffers (TLBs) for
+//! virtual-to-physical address translation. It supports the RISC-V SV39 and
+//! SV48 paging schemes and includes Translation Lookaside Buffers (TLBs) for
 //! caching translations.
+//!
+//! The base page size defaults to 4KB and may be raised to any larger power of
+//! two (`memory.page_size`) for research studies. Every level of the walk and the
+//! TLB granule then scale with it: the VPN fields start at the page shift, PPNs
+//! count base pages, and the canonical virtual-address width grows to
+//! `page_shift + 27` bits (SV39) or `page_shift + 36` bits (SV48).
+//!
+//! With a software-managed TLB (`memory.tlb_refill = "Software"`) a miss never
+//! walks: it raises an instruction/load/store TLB-miss exception, and the guest
+//! handler installs the translation through the `stlbva`/`stlbw` CSRs (see
+//! [`Mmu::write_tlb`]) before returning to retry the access.
 
 /// Physical Memory Protection (PMP).
 pub mod pmp;
 
-/// Page table walker implementation for SV39 virtual memory.
+/// Page table walker implementation for SV39/SV48 virtual memory.
 pub mod ptw;
 
 /// Translation Lookaside Buffer (TLB) for caching virtual-to-physical address translations.
 pub mod tlb;
 
+use crate::common::constants::{PAGE_SHIFT, VPN_MASK};
 use crate::common::{AccessType, PhysAddr, TranslationResult, Trap, VirtAddr};
-use crate::core::arch::csr::Csrs;
+use crate::config::{AdUpdate, TlbRefill};
+use crate::core::arch::csr::{Csrs, SATP_ASID_MASK, SATP_ASID_SHIFT, SATP_PPN_MASK};
 use crate::core::arch::mode::PrivilegeMode;
 use crate::soc::interconnect::Bus;
 
 use self::tlb::Tlb;
 
+/// Extracts the address-space identifier from a `satp` value.
+///
+/// # Arguments
+///
+/// * `satp` - Raw `satp` register value.
+#[inline]
+pub fn satp_asid(satp: u64) -> u16 {
+    ((satp >> SATP_ASID_SHIFT) & SATP_ASID_MASK) as u16
+}
+
+/// TLB and page-walk event counters, mirrored into
+/// [`SimStats`](crate::stats::SimStats) by the CPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MmuStats {
+    /// Instruction fetches translated by an ITLB hit.
+    pub itlb_hits: u64,
+    /// Instruction fetches that missed the ITLB.
+    pub itlb_misses: u64,
+    /// Loads and stores translated by a DTLB hit.
+    pub dtlb_hits: u64,
+    /// Loads and stores that missed the DTLB.
+    pub dtlb_misses: u64,
+    /// Hardware page-table walks started on a TLB miss.
+    pub page_walks: u64,
+}
+
 /// Memory Management Unit (MMU) for virtual-to-physical address translation.
 ///
-/// Implements RISC-V SV39 page-based virtual memory with separate instruction
+/// Implements RISC-V SV39/SV48 page-based virtual memory with separate instruction
 /// and data translation lookaside buffers (TLBs) and page table walker.
 pub struct Mmu {
     /// Data TLB for load/store address translation.
     pub dtlb: Tlb,
     /// Instruction TLB for fetch address translation.
     pub itlb: Tlb,
+    /// log2 of the base page size in bytes.
+    page_shift: u64,
+    /// Whether misses are refilled by the page-table walker or by guest software.
+    refill: TlbRefill,
+    /// Whether the walker sets clear A/D bits or faults on them.
+    ad_update: AdUpdate,
+    /// Whether PMP passes every access until software enables an entry.
+    pmp_open_when_inactive: bool,
+    /// TLB hit/miss and page-walk counters.
+    pub stats: MmuStats,
 }
 
 impl Mmu {
@@ -46,14 +95,168 @@ impl Mmu {
         Self {
             dtlb: Tlb::new(tlb_size),
             itlb: Tlb::new(tlb_size),
+            page_shift: PAGE_SHIFT,
+            refill: TlbRefill::Hardware,
+            ad_update: AdUpdate::Hardware,
+            pmp_open_when_inactive: true,
+            stats: MmuStats::default(),
+        }
+    }
+
+    /// Selects hardware page walks or software-managed refills on a TLB miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `refill` - Refill mode.
+    pub fn set_refill(&mut self, refill: TlbRefill) {
+        self.refill = refill;
+    }
+
+    /// Selects hardware A/D updates or page faults on clear A/D bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `ad_update` - A/D update mode.
+    pub fn set_ad_update(&mut self, ad_update: AdUpdate) {
+        self.ad_update = ad_update;
+    }
+
+    /// Selects whether PMP checks pass while no entry is enabled.
+    ///
+    /// Applies to the walker's page-table accesses and, through
+    /// [`Mmu::pmp_open_when_inactive`], to the CPU's own accesses.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Let every access pass until software enables an entry.
+    pub fn set_pmp_open_when_inactive(&mut self, open: bool) {
+        self.pmp_open_when_inactive = open;
+    }
+
+    /// Returns whether PMP checks pass while no entry is enabled.
+    pub fn pmp_open_when_inactive(&self) -> bool {
+        self.pmp_open_when_inactive
+    }
+
+    /// Installs or removes a base-page translation on behalf of software (`stlbw`).
+    ///
+    /// The mapping goes into both TLBs; the permission bits are checked on each
+    /// access as for a walked entry. A/D bits are not maintained in this mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Virtual address to map (`stlbva`).
+    /// * `pte` - Leaf PTE (`ppn << 10 | flags`); a clear V bit removes the mapping.
+    /// * `asid` - Address-space identifier the mapping belongs to.
+    pub fn write_tlb(&mut self, vaddr: u64, pte: u64, asid: u16) {
+        /// Valid bit of a PTE.
+        const PTE_V: u64 = 1;
+        /// Bit shift of the PPN field in a PTE.
+        const PTE_PPN_SHIFT: u64 = 10;
+
+        let vpn = self.vpn(vaddr);
+        if pte & PTE_V == 0 {
+            self.itlb.invalidate(vpn);
+            self.dtlb.invalidate(vpn);
+            return;
         }
+        let ppn = (pte >> PTE_PPN_SHIFT) & SATP_PPN_MASK;
+        self.itlb.insert(vpn, ppn, pte, asid, 0);
+        self.dtlb.insert(vpn, ppn, pte, asid, 0);
+    }
+
+    /// Invalidates the translations selected by an `SFENCE.VMA` in both TLBs.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Virtual address from `rs1`, or `None` for all addresses.
+    /// * `asid` - Address-space identifier from `rs2`, or `None` for all address spaces.
+    pub fn sfence_vma(&mut self, vaddr: Option<u64>, asid: Option<u16>) {
+        let vpn = vaddr.map(|va| self.vpn(va));
+        self.itlb.sfence(vpn, asid);
+        self.dtlb.sfence(vpn, asid);
+    }
+
+    /// Sets the base page size and flushes both TLBs.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_size` - Base page size in bytes; a power of two of at least 4KB.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is not a power of two or is smaller than 4KB.
+    pub fn set_page_size(&mut self, page_size: u64) {
+        assert!(
+            page_size.is_power_of_two() && page_size >= 1 << PAGE_SHIFT,
+            "page size {page_size} must be a power of two of at least 4KB"
+        );
+        self.page_shift = u64::from(page_size.trailing_zeros());
+        self.dtlb.flush();
+        self.itlb.flush();
+    }
+
+    /// Returns log2 of the base page size.
+    #[inline]
+    pub fn page_shift(&self) -> u64 {
+        self.page_shift
+    }
+
+    /// Returns the TLB tag (virtual page number) of an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Raw virtual address.
+    #[inline]
+    pub fn vpn(&self, vaddr: u64) -> u64 {
+        (vaddr >> self.page_shift) & VPN_MASK
+    }
+
+    /// Returns the byte offset of an address within its base page.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Raw virtual address.
+    #[inline]
+    pub fn page_offset(&self, vaddr: u64) -> u64 {
+        vaddr & ((1 << self.page_shift) - 1)
+    }
+
+    /// Resolves an address through the data TLB alone, with no walk or permission check.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Raw virtual address.
+    /// * `asid` - Current address-space identifier.
+    ///
+    /// # Returns
+    ///
+    /// The physical address, or `None` if the page is not cached in the data TLB.
+    pub fn dtlb_paddr(&self, vaddr: u64, asid: u16) -> Option<u64> {
+        let (ppn, ..) = self.dtlb.lookup(self.vpn(vaddr), asid)?;
+        Some((ppn << self.page_shift) | self.page_offset(vaddr))
+    }
+
+    /// Resolves an address through the instruction TLB alone, with no walk or permission check.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Raw virtual address.
+    /// * `asid` - Current address-space identifier.
+    ///
+    /// # Returns
+    ///
+    /// The physical address, or `None` if the page is not cached in the instruction TLB.
+    pub fn itlb_paddr(&self, vaddr: u64, asid: u16) -> Option<u64> {
+        let (ppn, ..) = self.itlb.lookup(self.vpn(vaddr), asid)?;
+        Some((ppn << self.page_shift) | self.page_offset(vaddr))
     }
 
     /// Translates a virtual address to a physical address.
     ///
     /// Performs address translation using the page table walker and TLBs,
-    /// checking permissions and handling page faults. Supports SV39 paging
-    /// and bare mode (no translation).
+    /// checking permissions and handling page faults. Supports SV39 and SV48
+    /// paging and bare mode (no translation).
     ///
     /// # Arguments
     ///
@@ -100,81 +303,148 @@ impl Mmu {
         bus: &mut Bus,
     ) -> TranslationResult {
         let satp = csrs.satp;
-        use crate::core::arch::csr::{
-            SATP_MODE_BARE, SATP_MODE_MASK, SATP_MODE_SHIFT, SATP_MODE_SV39,
+        let va = vaddr.val();
+        match self.is_translated(va, access, privilege, satp) {
+            Ok(true) => {}
+            Ok(false) => return TranslationResult::success(PhysAddr::new(va), 0),
+            Err(trap) => return TranslationResult::fault(trap, 0),
+        }
+
+        let vpn = self.vpn(va);
+        let asid = satp_asid(satp);
+
+        let tlb_entry = if access == AccessType::Fetch {
+            self.itlb.lookup(vpn, asid)
+        } else {
+            self.dtlb.lookup(vpn, asid)
         };
-        let mode = (satp >> SATP_MODE_SHIFT) & SATP_MODE_MASK;
 
-        if privilege == PrivilegeMode::Machine || mode == SATP_MODE_BARE {
-            return TranslationResult::success(PhysAddr::new(vaddr.val()), 0);
+        let software = self.refill == TlbRefill::Software;
+
+        // Clean pages are cached without W; a store re-walks so the walker
+        // can set D or raise the fault against the in-memory PTE. Software
+        // refill has no walker, so a read-only entry faults instead.
+        let tlb_entry =
+            tlb_entry.filter(|&(_, _, w, _, _)| software || access != AccessType::Write || w);
+
+        let (hits, misses) = if access == AccessType::Fetch {
+            (&mut self.stats.itlb_hits, &mut self.stats.itlb_misses)
+        } else {
+            (&mut self.stats.dtlb_hits, &mut self.stats.dtlb_misses)
+        };
+        if tlb_entry.is_some() {
+            *hits += 1;
+        } else {
+            *misses += 1;
         }
 
-        if mode != SATP_MODE_SV39 {
-            return TranslationResult::fault(Trap::InstructionAccessFault(vaddr.val()), 0);
+        if let Some((ppn, r, w, x, u)) = tlb_entry {
+            if !ptw::permits((r, w, x, u), access, privilege, csrs) {
+                return TranslationResult::fault(page_fault(va, access), 0);
+            }
+
+            let paddr = (ppn << self.page_shift) | self.page_offset(va);
+            return TranslationResult::success(PhysAddr::new(paddr), 0);
         }
 
-        let va = vaddr.val();
-        let bit_38 = (va >> 38) & 1;
-        let top_bits = va >> 39;
-        let expected_top = if bit_38 == 1 { 0x1FFFFFF } else { 0 };
-        if top_bits != expected_top {
-            return TranslationResult::fault(
-                match access {
-                    AccessType::Fetch => Trap::InstructionAccessFault(va),
-                    AccessType::Read => Trap::LoadAccessFault(va),
-                    AccessType::Write => Trap::StoreAccessFault(va),
-                },
-                0,
-            );
+        if software {
+            return TranslationResult::fault(tlb_miss(va, access), 0);
         }
 
-        use crate::common::constants::{PAGE_SHIFT, VPN_MASK};
-        let vpn = (vaddr.val() >> PAGE_SHIFT) & VPN_MASK;
+        self.stats.page_walks += 1;
+        ptw::page_table_walk(self, vaddr, access, privilege, csrs, bus)
+    }
 
-        let tlb_entry = if access == AccessType::Fetch {
-            self.itlb.lookup(vpn)
+    /// Translates an address for a debugger, leaving the TLBs, statistics, and PTEs untouched.
+    ///
+    /// A TLB entry that permits the access is used as is; otherwise the page tables are
+    /// walked without setting A/D bits or faulting on them. With software refill a TLB
+    /// miss still faults, since there is no walker.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaddr` - Virtual address to translate
+    /// * `access` - Type of access (Fetch, Read, Write)
+    /// * `privilege` - Current privilege mode
+    /// * `csrs` - Control and status registers (for SATP, SSTATUS)
+    /// * `bus` - System bus for page table reads
+    ///
+    /// # Returns
+    ///
+    /// The physical address, or the fault the access would raise.
+    pub fn probe(
+        &self,
+        vaddr: VirtAddr,
+        access: AccessType,
+        privilege: PrivilegeMode,
+        csrs: &Csrs,
+        bus: &mut Bus,
+    ) -> Result<u64, Trap> {
+        let va = vaddr.val();
+        if !self.is_translated(va, access, privilege, csrs.satp)? {
+            return Ok(va);
+        }
+
+        let tlb = if access == AccessType::Fetch {
+            &self.itlb
         } else {
-            self.dtlb.lookup(vpn)
+            &self.dtlb
         };
-
-        if let Some((ppn, r, w, x, u)) = tlb_entry {
-            if access == AccessType::Write && !w {
-                return TranslationResult::fault(Trap::StorePageFault(vaddr.val()), 0);
-            }
-            if access == AccessType::Fetch && !x {
-                return TranslationResult::fault(Trap::InstructionPageFault(vaddr.val()), 0);
-            }
-            if access == AccessType::Read {
-                /// Bit position of MXR (Make eXecutable Readable) bit in sstatus register.
-                const SSTATUS_MXR_SHIFT: u64 = 19;
-                let mxr = (csrs.sstatus >> SSTATUS_MXR_SHIFT) & 1 != 0;
-                let readable = r || (x && mxr);
-                if !readable {
-                    return TranslationResult::fault(Trap::LoadPageFault(vaddr.val()), 0);
-                }
+        let software = self.refill == TlbRefill::Software;
+        match tlb.lookup(self.vpn(va), satp_asid(csrs.satp)) {
+            Some((ppn, r, w, x, u)) if ptw::permits((r, w, x, u), access, privilege, csrs) => {
+                Ok((ppn << self.page_shift) | self.page_offset(va))
             }
+            Some(_) if software => Err(page_fault(va, access)),
+            None if software => Err(tlb_miss(va, access)),
+            _ => ptw::probe_walk(self, vaddr, access, privilege, csrs, bus),
+        }
+    }
 
-            if privilege == PrivilegeMode::User && !u {
-                return TranslationResult::fault(page_fault(vaddr.val(), access), 0);
-            }
-            if privilege == PrivilegeMode::Supervisor && u {
-                /// Bit position of SUM (Supervisor User Memory access) bit in sstatus register.
-                const SSTATUS_SUM_SHIFT: u64 = 18;
-                let sum = (csrs.sstatus >> SSTATUS_SUM_SHIFT) & 1 != 0;
-                if !sum {
-                    return TranslationResult::fault(page_fault(vaddr.val(), access), 0);
-                }
-                if access == AccessType::Fetch {
-                    return TranslationResult::fault(Trap::InstructionPageFault(vaddr.val()), 0);
-                }
-            }
+    /// Checks whether an access is translated at all, and that its address is canonical.
+    ///
+    /// # Returns
+    ///
+    /// `false` when the address is used untranslated (M-mode or bare `satp`), or the
+    /// access fault for an unsupported mode or a non-canonical address.
+    fn is_translated(
+        &self,
+        va: u64,
+        access: AccessType,
+        privilege: PrivilegeMode,
+        satp: u64,
+    ) -> Result<bool, Trap> {
+        use crate::core::arch::csr::{SATP_MODE_BARE, SATP_MODE_MASK, SATP_MODE_SHIFT};
+        let mode = (satp >> SATP_MODE_SHIFT) & SATP_MODE_MASK;
 
-            use crate::common::constants::PAGE_SHIFT;
-            let paddr = (ppn << PAGE_SHIFT) | vaddr.page_offset();
-            return TranslationResult::success(PhysAddr::new(paddr), 0);
+        if privilege == PrivilegeMode::Machine || mode == SATP_MODE_BARE {
+            return Ok(false);
         }
 
-        ptw::page_table_walk(self, vaddr, access, privilege, csrs, bus)
+        let Some(levels) = ptw::levels(mode) else {
+            return Err(Trap::InstructionAccessFault(va));
+        };
+
+        // Bits above the translated width must all equal its top bit.
+        let va_bits = self.page_shift + levels * ptw::VPN_BITS_PER_LEVEL;
+        let top_bits = ((va as i64) >> (va_bits - 1)) as u64;
+        if top_bits != 0 && top_bits != u64::MAX {
+            return Err(match access {
+                AccessType::Fetch => Trap::InstructionAccessFault(va),
+                AccessType::Read => Trap::LoadAccessFault(va),
+                AccessType::Write => Trap::StoreAccessFault(va),
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// Creates the TLB-miss trap raised under software refill.
+fn tlb_miss(addr: u64, access: AccessType) -> Trap {
+    match access {
+        AccessType::Fetch => Trap::InstructionTlbMiss(addr),
+        AccessType::Read => Trap::LoadTlbMiss(addr),
+        AccessType::Write => Trap::StoreTlbMiss(addr),
     }
 }
 
@@ -9,9 +9,30 @@
 //! - **TOR** (Top of Range): region is `[pmpaddr[i-1], pmpaddr[i])`.
 //! - **NA4**: Naturally aligned 4-byte region.
 //! - **NAPOT**: Naturally aligned power-of-two region.
+//!
+//! The entries are exposed to software through the RV64 CSR layout: eight
+//! configuration bytes per even-numbered `pmpcfg` register and one `pmpaddr`
+//! register per entry holding physical address bits 55:2.
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::AccessType;
+use crate::core::arch::mode::PrivilegeMode;
+
+/// Number of PMP entries (the maximum the RISC-V spec allows).
+pub const PMP_COUNT: usize = 64;
+
+/// Number of `pmpcfg` CSRs; on RV64 only the even-numbered ones exist.
+pub const PMPCFG_COUNT: usize = 16;
+
+/// Configuration bytes packed into one RV64 `pmpcfg` register.
+const CFG_PER_REG: usize = 8;
+
+/// Reserved bits 6:5 of a configuration byte (read as zero).
+const CFG_RESERVED: u8 = 0x60;
 
-/// Maximum number of PMP entries (RISC-V spec allows up to 64).
-pub const PMP_COUNT: usize = 16;
+/// Implemented bits of a `pmpaddr` register (physical address bits 55:2).
+const ADDR_MASK: u64 = (1 << 54) - 1;
 
 /// PMP address-matching mode field (bits 4:3 of pmpcfg).
 const A_SHIFT: u8 = 3;
@@ -61,7 +82,7 @@ pub enum PmpResult {
 }
 
 /// Decoded PMP entry with precomputed range.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PmpEntry {
     /// Raw configuration byte from pmpcfg.
     pub cfg: u8,
@@ -101,18 +122,32 @@ impl PmpEntry {
 /// Maintains the PMP configuration and address registers and provides
 /// a `check` method that determines whether an access at a given
 /// physical address is permitted.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Pmp {
     /// PMP entries (up to `PMP_COUNT`).
     entries: Vec<PmpEntry>,
+    /// Whether any entry has an address-matching mode other than OFF.
+    active: bool,
+}
+
+impl Default for Pmp {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Pmp {
     /// Creates a new PMP unit with all entries disabled.
     pub fn new() -> Self {
-        let entries = (0..PMP_COUNT)
-            .map(|_| PmpEntry { cfg: 0, addr: 0 })
-            .collect();
-        Self { entries }
+        Self {
+            entries: vec![PmpEntry::default(); PMP_COUNT],
+            active: false,
+        }
+    }
+
+    /// Returns `true` once software has enabled at least one entry.
+    pub fn is_active(&self) -> bool {
+        self.active
     }
 
     /// Returns a reference to the entries slice for inspection.
@@ -128,6 +163,10 @@ impl Pmp {
                 return;
             }
             self.entries[idx].cfg = cfg;
+            self.active = self
+                .entries
+                .iter()
+                .any(|e| e.match_mode() != PmpAddrMatch::Off);
         }
     }
 
@@ -161,6 +200,63 @@ impl Pmp {
         }
     }
 
+    /// Reads CSR `pmpcfg<reg>`, packing the configuration bytes of its eight entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `reg` - Register number (0–15); odd numbers do not exist on RV64 and read as zero.
+    pub fn read_cfg_reg(&self, reg: usize) -> u64 {
+        if !reg.is_multiple_of(2) {
+            return 0;
+        }
+        let first = reg / 2 * CFG_PER_REG;
+        (0..CFG_PER_REG).fold(0, |acc, i| {
+            acc | (u64::from(self.get_cfg(first + i)) << (8 * i))
+        })
+    }
+
+    /// Writes CSR `pmpcfg<reg>`, applying the WARL rules to each configuration byte.
+    ///
+    /// Reserved bits are dropped, the reserved `R=0, W=1` combination loses its W bit, and
+    /// locked bytes keep their old value.
+    ///
+    /// # Arguments
+    ///
+    /// * `reg` - Register number (0–15); writes to odd numbers are ignored.
+    /// * `val` - Eight configuration bytes, entry `8 * (reg / 2)` in the low byte.
+    pub fn write_cfg_reg(&mut self, reg: usize, val: u64) {
+        if !reg.is_multiple_of(2) {
+            return;
+        }
+        let first = reg / 2 * CFG_PER_REG;
+        for i in 0..CFG_PER_REG {
+            let mut cfg = (val >> (8 * i)) as u8 & !CFG_RESERVED;
+            if cfg & PMP_R == 0 {
+                cfg &= !PMP_W;
+            }
+            self.set_cfg(first + i, cfg);
+        }
+    }
+
+    /// Writes CSR `pmpaddr<idx>`, keeping only the implemented address bits.
+    ///
+    /// The write is ignored if entry `idx` is locked, or if the entry above it is a locked
+    /// TOR entry, whose region starts at this address.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Entry number (0–63).
+    /// * `val` - New register value (physical address >> 2).
+    pub fn write_addr_reg(&mut self, idx: usize, val: u64) {
+        let locks_base = self
+            .entries
+            .get(idx + 1)
+            .is_some_and(|e| e.is_locked() && e.match_mode() == PmpAddrMatch::Tor);
+        if !locks_base {
+            self.set_addr(idx, val & ADDR_MASK);
+        }
+    }
+
     /// Computes the byte-address range `[lo, hi)` for a NAPOT entry.
     ///
     /// The pmpaddr encoding for NAPOT: trailing ones determine region size.
@@ -183,6 +279,42 @@ impl Pmp {
         (base, base + 4)
     }
 
+    /// Returns whether an access passes the PMP entries.
+    ///
+    /// S- and U-mode accesses must fall wholly inside an entry that grants them,
+    /// so with every entry off they all fail (spec §3.7.1). M-mode is only bound
+    /// by locked entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte_addr` - Physical byte address of the access.
+    /// * `size` - Number of bytes being accessed.
+    /// * `access` - The type of memory access.
+    /// * `privilege` - Privilege mode the access is checked at.
+    /// * `open_when_inactive` - Let every access pass until software enables an
+    ///   entry, for programs that run below M-mode without configuring PMP.
+    pub fn permits(
+        &self,
+        byte_addr: u64,
+        size: u64,
+        access: AccessType,
+        privilege: PrivilegeMode,
+        open_when_inactive: bool,
+    ) -> bool {
+        if open_when_inactive && !self.active {
+            return true;
+        }
+        let result = self.check(
+            byte_addr,
+            size,
+            access == AccessType::Read,
+            access == AccessType::Write,
+            access == AccessType::Fetch,
+            privilege == PrivilegeMode::Machine,
+        );
+        result == PmpResult::Allow
+    }
+
     /// Checks whether an access at `byte_addr` is permitted.
     ///
     /// # Arguments
@@ -232,8 +364,14 @@ impl Pmp {
                 PmpAddrMatch::Off => continue,
             };
 
-            // Check if the access range overlaps with this entry
-            if byte_addr >= lo && access_end <= hi {
+            // An entry covering only some of the accessed bytes fails the access
+            // outright, whatever its permissions and the privilege mode.
+            let overlaps = byte_addr < hi && access_end > lo;
+            if overlaps && !(byte_addr >= lo && access_end <= hi) {
+                return PmpResult::Deny;
+            }
+
+            if overlaps {
                 // M-mode: if the entry is NOT locked, M-mode bypasses PMP.
                 if is_machine_mode && !entry.is_locked() {
                     return PmpResult::Allow;
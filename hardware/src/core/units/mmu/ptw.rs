@@ -1,13 +1,15 @@
-//! Hardware Page Table Walker (PTW) for RISC-V SV39.
+//! Hardware Page Table Walker (PTW) for RISC-V SV39 and SV48.
 //!
 //! This module implements the hardware page table walking algorithm. It traverses
-//! the three-level page table structure defined by the SV39 virtual memory scheme
-//! to translate virtual addresses to physical addresses.
-
-use crate::common::{
-    AccessType, PAGE_SHIFT, PhysAddr, TranslationResult, Trap, VPN_MASK, VirtAddr,
+//! the three-level (SV39) or four-level (SV48) page table structure selected by
+//! `satp.MODE` to translate virtual addresses to physical addresses.
+
+use crate::common::{AccessType, PhysAddr, TranslationResult, Trap, VirtAddr};
+use crate::config::AdUpdate;
+use crate::core::arch::csr::{
+    Csrs, MSTATUS_MXR, MSTATUS_SUM, SATP_MODE_MASK, SATP_MODE_SHIFT, SATP_MODE_SV39,
+    SATP_MODE_SV48, SATP_PPN_MASK,
 };
-use crate::core::arch::csr::{Csrs, SATP_PPN_MASK};
 use crate::core::arch::mode::PrivilegeMode;
 use crate::core::units::mmu::Mmu;
 use crate::soc::interconnect::Bus;
@@ -27,6 +29,9 @@ const PTE_EXEC_BIT: u64 = 1 << 3;
 /// Page Table Entry user mode access bit (bit 4).
 const PTE_USER_BIT: u64 = 1 << 4;
 
+/// Global mapping bit in page table entry.
+const PTE_GLOBAL_BIT: u64 = 1 << 5;
+
 /// Page Table Entry accessed bit (bit 6).
 const PTE_ACCESSED_BIT: u64 = 1 << 6;
 
@@ -36,7 +41,24 @@ const PTE_DIRTY_BIT: u64 = 1 << 7;
 /// Bit shift to extract Physical Page Number from PTE (bits 10-53).
 const PTE_PPN_SHIFT: u64 = 10;
 
-/// A strongly-typed wrapper around a raw 64-bit SV39 Page Table Entry.
+/// Number of bits used for VPN indexing at each level (9 bits per level).
+pub(crate) const VPN_BITS_PER_LEVEL: u64 = 9;
+
+/// Returns the number of page table levels for a `satp.MODE` value.
+///
+/// # Returns
+///
+/// `Some(3)` for SV39, `Some(4)` for SV48, and `None` for modes that do not
+/// translate through this walker.
+pub(crate) fn levels(mode: u64) -> Option<u64> {
+    match mode {
+        SATP_MODE_SV39 => Some(3),
+        SATP_MODE_SV48 => Some(4),
+        _ => None,
+    }
+}
+
+/// A strongly-typed wrapper around a raw 64-bit SV39/SV48 Page Table Entry.
 #[derive(Clone, Copy, Debug)]
 struct PageTableEntry(u64);
 
@@ -76,6 +98,11 @@ impl PageTableEntry {
         self.0 & PTE_USER_BIT != 0
     }
 
+    /// Returns true if the Global (G) bit is set.
+    fn is_global(&self) -> bool {
+        self.0 & PTE_GLOBAL_BIT != 0
+    }
+
     /// Returns true if the Accessed (A) bit is set.
     fn is_accessed(&self) -> bool {
         self.0 & PTE_ACCESSED_BIT != 0
@@ -93,7 +120,7 @@ impl PageTableEntry {
 
     /// Determines if this entry is a pointer to the next level page table.
     ///
-    /// In SV39, an entry is a pointer if it is Valid but has R=0, W=0, and X=0.
+    /// In SV39/SV48, an entry is a pointer if it is Valid but has R=0, W=0, and X=0.
     fn is_pointer(&self) -> bool {
         !self.can_read() && !self.can_write() && !self.can_exec()
     }
@@ -109,147 +136,246 @@ impl PageTableEntry {
     }
 }
 
-/// Performs a hardware page table walk for SV39.
+/// Size of a Page Table Entry in bytes (8 bytes for 64-bit PTE).
+const PTE_SIZE: u64 = 8;
+
+/// A permitted leaf PTE located by [`find_leaf`].
+struct Leaf {
+    /// Physical address the PTE was read from.
+    pte_addr: u64,
+    /// The leaf entry as read from memory.
+    pte: PageTableEntry,
+    /// Shift of the VPN field the leaf maps; the page spans `1 << vpn_shift` bytes.
+    vpn_shift: u64,
+    /// Table level of the leaf (0 for a base page).
+    level: u64,
+    /// Whether the leaf or any pointer above it has the G bit set.
+    global: bool,
+}
+
+/// Walks the page tables to the leaf for `vaddr` and checks its permissions.
 ///
-/// Traverses the page table tree starting from the root PPN in the SATP register.
-/// It supports 4KB pages, 2MB megapages, and 1GB gigapages.
+/// Reads PTEs only: A/D bits, the TLBs, and the MMU statistics are left to the caller.
+/// Each PTE read adds its bus transit time to `cycles` and is checked against PMP as
+/// an S-mode load, whatever the privilege of the original access.
 ///
-/// # Arguments
+/// # Returns
 ///
-/// * `mmu` - Mutable reference to the MMU for TLB updates.
-/// * `vaddr` - The virtual address to translate.
-/// * `access` - The type of memory access (Fetch, Read, Write).
-/// * `privilege` - The current privilege mode of the processor.
-/// * `csrs` - System CSRs (specifically SATP and STATUS).
-/// * `bus` - System bus for reading PTEs from memory.
-pub fn page_table_walk(
-    mmu: &mut Mmu,
+/// The leaf, the page fault for `access` at `vaddr`, or its access fault when PMP
+/// denies a PTE read.
+fn find_leaf(
+    mmu: &Mmu,
     vaddr: VirtAddr,
     access: AccessType,
     privilege: PrivilegeMode,
     csrs: &Csrs,
     bus: &mut Bus,
-) -> TranslationResult {
-    /// Number of page table levels in SV39 (3 levels: L2, L1, L0).
-    const SV39_LEVELS: usize = 3;
-
-    /// Number of bits used for VPN indexing at each level (9 bits per level).
-    const VPN_BITS_PER_LEVEL: u64 = 9;
-
+    cycles: &mut u64,
+) -> Result<Leaf, Trap> {
     /// Bit mask to extract VPN index from virtual address (9 bits: 0x1FF).
     const VPN_ENTRY_MASK: u64 = 0x1FF;
 
-    /// Size of a Page Table Entry in bytes (8 bytes for 64-bit PTE).
-    const PTE_SIZE: u64 = 8;
-
-    /// Cycles required to update a PTE's accessed/dirty bits in memory.
-    const PTE_UPDATE_CYCLES: u64 = 10;
-
+    let page_shift = mmu.page_shift;
+    let fault = page_fault(vaddr.val(), access);
     let satp = csrs.satp;
     let mut ppn = satp & SATP_PPN_MASK;
-    let mut cycles = 0;
-
-    for level in (0..SV39_LEVELS).rev() {
-        let vpn_shift = PAGE_SHIFT + level as u64 * VPN_BITS_PER_LEVEL;
+    // A G bit on any pointer makes every mapping below it global.
+    let mut global = false;
+    let Some(levels) = levels((satp >> SATP_MODE_SHIFT) & SATP_MODE_MASK) else {
+        return Err(fault);
+    };
+
+    for level in (0..levels).rev() {
+        let vpn_shift = page_shift + level * VPN_BITS_PER_LEVEL;
         let vpn_i = (vaddr.val() >> vpn_shift) & VPN_ENTRY_MASK;
-        let pte_addr = (ppn << PAGE_SHIFT) + (vpn_i * PTE_SIZE);
+        let pte_addr = (ppn << page_shift) + (vpn_i * PTE_SIZE);
 
-        cycles += bus.calculate_transit_time(8);
-        let raw_pte = bus.read_u64(pte_addr);
-        let pte = PageTableEntry::new(raw_pte);
+        if !pte_access_permitted(mmu, csrs, pte_addr, AccessType::Read) {
+            return Err(access_fault(vaddr.val(), access));
+        }
+        *cycles += bus.calculate_transit_time(8);
+        let pte = PageTableEntry::new(bus.read_u64(pte_addr));
 
         if !pte.is_valid() {
-            return TranslationResult::fault(page_fault(vaddr.val(), access), cycles);
+            return Err(fault);
         }
 
+        global |= pte.is_global();
+
         if pte.is_pointer() {
             if level == 0 {
-                return TranslationResult::fault(page_fault(vaddr.val(), access), cycles);
+                return Err(fault);
             }
             ppn = pte.ppn();
             continue;
         }
 
         if level > 0 {
-            let ppn_mask = (1 << (level as u64 * VPN_BITS_PER_LEVEL)) - 1;
+            let ppn_mask = (1 << (level * VPN_BITS_PER_LEVEL)) - 1;
             if (pte.ppn() & ppn_mask) != 0 {
-                return TranslationResult::fault(page_fault(vaddr.val(), access), cycles);
+                return Err(fault);
             }
         }
 
-        if let Err(_) = check_permissions(&pte, access, privilege, csrs) {
-            return TranslationResult::fault(page_fault(vaddr.val(), access), cycles);
+        let perms = (
+            pte.can_read(),
+            pte.can_write(),
+            pte.can_exec(),
+            pte.is_user(),
+        );
+        if !permits(perms, access, privilege, csrs) {
+            return Err(fault);
         }
 
-        let (new_pte, updated) = update_access_bits(pte, access);
+        return Ok(Leaf {
+            pte_addr,
+            pte,
+            vpn_shift,
+            level,
+            global,
+        });
+    }
 
-        if updated {
-            bus.write_u64(pte_addr, new_pte.raw());
-            cycles += PTE_UPDATE_CYCLES;
-        }
+    Err(fault)
+}
 
-        let final_ppn = new_pte.ppn();
+/// Performs a hardware page table walk for SV39 or SV48.
+///
+/// Traverses the page table tree starting from the root PPN in the SATP register.
+/// A leaf whose A bit (or D bit, for a store) is clear is updated in memory, or
+/// raises a page fault when the MMU is configured for [`AdUpdate::Fault`].
+/// It supports 4KB pages, 2MB megapages, 1GB gigapages, and (SV48 only) 512GB
+/// terapages. With a larger base page every level scales by the same factor
+/// (e.g. 16KB, 8MB and 4GB pages).
+///
+/// # Arguments
+///
+/// * `mmu` - Mutable reference to the MMU for TLB updates.
+/// * `vaddr` - The virtual address to translate.
+/// * `access` - The type of memory access (Fetch, Read, Write).
+/// * `privilege` - The current privilege mode of the processor.
+/// * `csrs` - System CSRs (specifically SATP and STATUS).
+/// * `bus` - System bus for reading PTEs from memory.
+pub fn page_table_walk(
+    mmu: &mut Mmu,
+    vaddr: VirtAddr,
+    access: AccessType,
+    privilege: PrivilegeMode,
+    csrs: &Csrs,
+    bus: &mut Bus,
+) -> TranslationResult {
+    /// Cycles required to update a PTE's accessed/dirty bits in memory.
+    const PTE_UPDATE_CYCLES: u64 = 10;
 
-        let offset_mask = (1u64 << vpn_shift) - 1;
-        let final_paddr = (final_ppn << PAGE_SHIFT) | (vaddr.val() & offset_mask);
+    let page_shift = mmu.page_shift();
+    let mut cycles = 0;
+    let leaf = match find_leaf(mmu, vaddr, access, privilege, csrs, bus, &mut cycles) {
+        Ok(leaf) => leaf,
+        Err(trap) => return TranslationResult::fault(trap, cycles),
+    };
 
-        let specific_4kb_ppn = final_paddr >> PAGE_SHIFT;
-        let vpn = (vaddr.val() >> PAGE_SHIFT) & VPN_MASK;
+    let (new_pte, updated) = update_access_bits(leaf.pte, access);
 
-        if access == AccessType::Fetch {
-            mmu.itlb.insert(vpn, specific_4kb_ppn, new_pte.raw());
-        } else {
-            mmu.dtlb.insert(vpn, specific_4kb_ppn, new_pte.raw());
-        }
+    if updated && mmu.ad_update == AdUpdate::Fault {
+        return TranslationResult::fault(page_fault(vaddr.val(), access), cycles);
+    }
 
-        return TranslationResult::success(PhysAddr::new(final_paddr), cycles);
+    if updated && !pte_access_permitted(mmu, csrs, leaf.pte_addr, AccessType::Write) {
+        return TranslationResult::fault(access_fault(vaddr.val(), access), cycles);
     }
 
-    TranslationResult::fault(page_fault(vaddr.val(), access), cycles)
+    if updated {
+        bus.write_u64(leaf.pte_addr, new_pte.raw());
+        cycles += PTE_UPDATE_CYCLES;
+    }
+
+    let final_ppn = new_pte.ppn();
+
+    let offset_mask = (1u64 << leaf.vpn_shift) - 1;
+    let final_paddr = (final_ppn << page_shift) | (vaddr.val() & offset_mask);
+
+    let specific_base_ppn = final_paddr >> page_shift;
+    let vpn = mmu.vpn(vaddr.val());
+
+    // Cache a clean leaf without write permission so the first store
+    // re-walks and sets D on this PTE, which for a superpage is the
+    // level-1, level-2 or level-3 entry at `pte_addr`.
+    let mut tlb_pte = if new_pte.is_dirty() {
+        new_pte.raw()
+    } else {
+        new_pte.raw() & !PTE_WRITE_BIT
+    };
+    if leaf.global {
+        tlb_pte |= PTE_GLOBAL_BIT;
+    }
+    let asid = super::satp_asid(csrs.satp);
+    let span = (1 << (leaf.level * VPN_BITS_PER_LEVEL)) - 1;
+
+    if access == AccessType::Fetch {
+        mmu.itlb.insert(vpn, specific_base_ppn, tlb_pte, asid, span);
+    } else {
+        mmu.dtlb.insert(vpn, specific_base_ppn, tlb_pte, asid, span);
+    }
+
+    TranslationResult::success(PhysAddr::new(final_paddr), cycles)
 }
 
-/// Validates access permissions for a leaf PTE.
+/// Walks the page tables for a debugger access, without side effects.
 ///
-/// Checks R/W/X bits, User bit, and status register flags (MXR, SUM).
-/// Returns `Ok(())` if access is allowed, `Err(())` otherwise.
-fn check_permissions(
-    pte: &PageTableEntry,
+/// Permissions are checked as for [`page_table_walk`], but clear A/D bits are neither
+/// set nor faulted on, and nothing is cached in the TLBs.
+///
+/// # Returns
+///
+/// The physical address, or the fault [`page_table_walk`] would raise.
+pub(crate) fn probe_walk(
+    mmu: &Mmu,
+    vaddr: VirtAddr,
     access: AccessType,
     privilege: PrivilegeMode,
     csrs: &Csrs,
-) -> Result<(), ()> {
-    if access == AccessType::Write && !pte.can_write() {
-        return Err(());
-    }
-    if access == AccessType::Fetch && !pte.can_exec() {
-        return Err(());
-    }
-
-    /// Bit position of MXR (Make eXecutable Readable) bit in sstatus register.
-    const SSTATUS_MXR_SHIFT: u64 = 19;
-    let mxr = (csrs.sstatus >> SSTATUS_MXR_SHIFT) & 1 != 0;
-
-    if access == AccessType::Read && !(pte.can_read() || (pte.can_exec() && mxr)) {
-        return Err(());
-    }
+    bus: &mut Bus,
+) -> Result<u64, Trap> {
+    let leaf = find_leaf(mmu, vaddr, access, privilege, csrs, bus, &mut 0)?;
+    let offset_mask = (1u64 << leaf.vpn_shift) - 1;
+    Ok((leaf.pte.ppn() << mmu.page_shift) | (vaddr.val() & offset_mask))
+}
 
-    if privilege == PrivilegeMode::User && !pte.is_user() {
-        return Err(());
+/// Validates access permissions for a leaf translation.
+///
+/// Shared by the walker and the TLB-hit path. Checks the R/W/X and U bits
+/// against the access and privilege, with `sstatus.MXR` making executable
+/// pages readable and `sstatus.SUM` letting supervisor loads and stores (but
+/// never fetches) reach user pages.
+///
+/// # Arguments
+///
+/// * `r`, `w`, `x`, `u` - Leaf permission bits.
+/// * `access` - Type of access.
+/// * `privilege` - Effective privilege mode.
+/// * `csrs` - Control and status registers (for `sstatus`).
+pub(crate) fn permits(
+    (r, w, x, u): (bool, bool, bool, bool),
+    access: AccessType,
+    privilege: PrivilegeMode,
+    csrs: &Csrs,
+) -> bool {
+    let allowed = match access {
+        AccessType::Fetch => x,
+        AccessType::Read => r || (x && csrs.sstatus & MSTATUS_MXR != 0),
+        AccessType::Write => w,
+    };
+    if !allowed {
+        return false;
     }
 
-    if privilege == PrivilegeMode::Supervisor && pte.is_user() {
-        /// Bit position of SUM (Supervisor User Memory access) bit in sstatus register.
-        const SSTATUS_SUM_SHIFT: u64 = 18;
-        let sum = (csrs.sstatus >> SSTATUS_SUM_SHIFT) & 1 != 0;
-        if !sum {
-            return Err(());
-        }
-        if access == AccessType::Fetch {
-            return Err(());
+    match privilege {
+        PrivilegeMode::User => u,
+        PrivilegeMode::Supervisor if u => {
+            access != AccessType::Fetch && csrs.sstatus & MSTATUS_SUM != 0
         }
+        _ => true,
     }
-
-    Ok(())
 }
 
 /// Updates the Accessed (A) and Dirty (D) bits of a PTE.
@@ -280,3 +406,25 @@ fn page_fault(addr: u64, access: AccessType) -> Trap {
         AccessType::Write => Trap::StorePageFault(addr),
     }
 }
+
+/// Returns the access fault for `access` at `addr`, raised when PMP denies a PTE access.
+fn access_fault(addr: u64, access: AccessType) -> Trap {
+    match access {
+        AccessType::Fetch => Trap::InstructionAccessFault(addr),
+        AccessType::Read => Trap::LoadAccessFault(addr),
+        AccessType::Write => Trap::StoreAccessFault(addr),
+    }
+}
+
+/// Checks an implicit page-table access against PMP.
+///
+/// The walker's reads and A/D updates are checked as S-mode accesses to the 8-byte PTE.
+fn pte_access_permitted(mmu: &Mmu, csrs: &Csrs, pte_addr: u64, access: AccessType) -> bool {
+    csrs.pmp.permits(
+        pte_addr,
+        PTE_SIZE,
+        access,
+        PrivilegeMode::Supervisor,
+        mmu.pmp_open_when_inactive,
+    )
+}
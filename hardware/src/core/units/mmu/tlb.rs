@@ -3,16 +3,30 @@
 //! A fully associative cache for page table entries. It stores the mapping
 //! between Virtual Page Numbers (VPN) and Physical Page Numbers (PPN), along
 //! with permission bits (R/W/X/U) to speed up address translation.
+//!
+//! Entries are tagged with the `satp.ASID` they were filled under, so switching
+//! address spaces does not require a flush; entries from global (G-bit) PTEs
+//! match every ASID. Superpages are cached one base page at a time, and each
+//! entry remembers the span of its leaf so an address-specific `SFENCE.VMA`
+//! evicts every base page of the superpage.
+
+use serde::{Deserialize, Serialize};
 
 /// A single entry in the TLB.
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
 struct TlbEntry {
     /// Virtual Page Number (Tag).
     vpn: u64,
     /// Physical Page Number (Data).
     ppn: u64,
+    /// VPN bits covered by the leaf PTE (0 for a base page).
+    span: u64,
+    /// Address-space identifier the entry was filled under.
+    asid: u16,
     /// Entry validity flag.
     valid: bool,
+    /// Global mapping, matching every ASID.
+    global: bool,
     /// Read permission.
     r: bool,
     /// Write permission.
@@ -24,6 +38,7 @@ struct TlbEntry {
 }
 
 /// Translation Lookaside Buffer structure.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Tlb {
     /// Vector of TLB entries.
     entries: Vec<TlbEntry>,
@@ -55,6 +70,7 @@ impl Tlb {
     /// # Arguments
     ///
     /// * `vpn` - The Virtual Page Number to look up.
+    /// * `asid` - Current address-space identifier.
     ///
     /// # Returns
     ///
@@ -66,7 +82,7 @@ impl Tlb {
     /// - `idx = vpn & self.mask` where `mask = size - 1` (size is power of 2)
     /// - This ensures `idx` is always `< size` and within bounds of `entries`
     #[inline(always)]
-    pub fn lookup(&self, vpn: u64) -> Option<(u64, bool, bool, bool, bool)> {
+    pub fn lookup(&self, vpn: u64, asid: u16) -> Option<(u64, bool, bool, bool, bool)> {
         let idx = (vpn as usize) & self.mask;
 
         // SAFETY: idx is guaranteed to be < entries.len() by the mask operation above.
@@ -74,7 +90,7 @@ impl Tlb {
         // ensuring idx is always a valid index.
         let entry = unsafe { self.entries.get_unchecked(idx) };
 
-        if entry.valid && entry.vpn == vpn {
+        if entry.valid && entry.vpn == vpn && (entry.global || entry.asid == asid) {
             return Some((entry.ppn, entry.r, entry.w, entry.x, entry.u));
         }
         None
@@ -86,19 +102,25 @@ impl Tlb {
     ///
     /// * `vpn` - Virtual Page Number.
     /// * `ppn` - Physical Page Number.
-    /// * `pte` - Raw Page Table Entry (used to extract permissions).
-    pub fn insert(&mut self, vpn: u64, ppn: u64, pte: u64) {
+    /// * `pte` - Raw Page Table Entry (used to extract permissions and the G bit).
+    /// * `asid` - Address-space identifier the translation belongs to.
+    /// * `span` - Mask of the VPN bits covered by the leaf (0 for a base page).
+    pub fn insert(&mut self, vpn: u64, ppn: u64, pte: u64, asid: u16, span: u64) {
         let r = (pte >> 1) & 1 != 0;
         let w = (pte >> 2) & 1 != 0;
         let x = (pte >> 3) & 1 != 0;
         let u = (pte >> 4) & 1 != 0;
+        let global = (pte >> 5) & 1 != 0;
 
         let idx = (vpn as usize) & self.mask;
 
         self.entries[idx] = TlbEntry {
             vpn,
             ppn,
+            span,
+            asid,
             valid: true,
+            global,
             r,
             w,
             x,
@@ -106,12 +128,45 @@ impl Tlb {
         };
     }
 
+    /// Removes the mapping for a VPN, if present.
+    ///
+    /// # Arguments
+    ///
+    /// * `vpn` - Virtual Page Number.
+    pub fn invalidate(&mut self, vpn: u64) {
+        let idx = (vpn as usize) & self.mask;
+        let entry = &mut self.entries[idx];
+        if entry.vpn == vpn {
+            entry.valid = false;
+        }
+    }
+
     /// Flushes all entries from the TLB.
     ///
-    /// Called on `SFENCE.VMA` instructions or SATP writes.
+    /// Called on `SFENCE.VMA x0, x0` and on SATP writes that keep the ASID.
     pub fn flush(&mut self) {
         for e in &mut self.entries {
             e.valid = false;
         }
     }
+
+    /// Invalidates the entries selected by an `SFENCE.VMA`.
+    ///
+    /// An address selects every entry whose leaf maps that page; an ASID
+    /// selects that address space's non-global entries. With neither, the
+    /// whole TLB is flushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `vpn` - Virtual Page Number from `rs1`, or `None` when `rs1` is `x0`.
+    /// * `asid` - Address-space identifier from `rs2`, or `None` when `rs2` is `x0`.
+    pub fn sfence(&mut self, vpn: Option<u64>, asid: Option<u16>) {
+        for e in &mut self.entries {
+            let vpn_hit = vpn.is_none_or(|vpn| (e.vpn ^ vpn) & !e.span == 0);
+            let asid_hit = asid.is_none_or(|asid| !e.global && e.asid == asid);
+            if vpn_hit && asid_hit {
+                e.valid = false;
+            }
+        }
+    }
 }
@@ -15,5 +15,7 @@ pub const REG_A0: usize = 10;
 pub const REG_A1: usize = 11;
 /// Register x12 (third argument, a2).
 pub const REG_A2: usize = 12;
+/// Register x16 (SBI function ID, a6).
+pub const REG_A6: usize = 16;
 /// Register x17 (system call number, a7).
 pub const REG_A7: usize = 17;
@@ -10,6 +10,9 @@
 //! - RV64A (atomic)
 //! - RV64F (single-precision float)
 //! - RV64D (double-precision float)
+//! - Zba (address generation)
+//! - Zbb (basic bit-manipulation)
+//! - Zbc (carry-less multiply)
 //! - Privileged (ECALL, EBREAK, xRET, CSR, FENCE, WFI)
 //!
 //! # Usage
@@ -27,6 +30,9 @@ use crate::isa::rv64d::funct7 as d_f7;
 use crate::isa::rv64f::{funct3 as f_f3, funct7 as f_f7, opcodes as f_op};
 use crate::isa::rv64i::{funct3 as i_f3, funct7 as i_f7, opcodes as i_op};
 use crate::isa::rv64m::{funct3 as m_f3, opcodes as m_op};
+use crate::isa::zba::{funct3 as zba_f3, opcodes as zba_op};
+use crate::isa::zbb::{funct3 as zbb_f3, opcodes as zbb_op};
+use crate::isa::zbc::{funct3 as zbc_f3, opcodes as zbc_op};
 
 /// ABI register names for x0–x31.
 const REG_NAMES: [&str; 32] = [
@@ -257,6 +263,48 @@ fn disasm_op_reg(rd: usize, rs1: usize, rs2: usize, f3: u32, f7: u32, is_w: bool
         return format!("{mn}{suffix} {}, {}, {}", xreg(rd), xreg(rs1), xreg(rs2));
     }
 
+    // Zba shift-and-add and add.uw
+    if f7 == zba_op::SHADD
+        && !is_w
+        && matches!(f3, zba_f3::SH1ADD | zba_f3::SH2ADD | zba_f3::SH3ADD)
+    {
+        return format!(
+            "sh{}add {}, {}, {}",
+            f3 >> 1,
+            xreg(rd),
+            xreg(rs1),
+            xreg(rs2)
+        );
+    }
+    if f7 == zba_op::ADD_UW && is_w && f3 == zba_f3::ADD_UW {
+        return format!("add.uw {}, {}, {}", xreg(rd), xreg(rs1), xreg(rs2));
+    }
+
+    // Zbb min/max and zext.h
+    if f7 == zbb_op::MINMAX && !is_w && f3 >= zbb_f3::MIN {
+        let mn = match f3 {
+            zbb_f3::MIN => "min",
+            zbb_f3::MINU => "minu",
+            zbb_f3::MAX => "max",
+            _ => "maxu",
+        };
+        return format!("{mn} {}, {}, {}", xreg(rd), xreg(rs1), xreg(rs2));
+    }
+    if f7 == zbb_op::ZEXT_H && is_w && f3 == zbb_f3::ZEXT_H && rs2 == 0 {
+        return format!("zext.h {}, {}", xreg(rd), xreg(rs1));
+    }
+
+    // Zbc carry-less multiply
+    if f7 == zbc_op::ZBC_EXTENSION && !is_w {
+        let mn = match f3 {
+            zbc_f3::CLMUL => "clmul",
+            zbc_f3::CLMULH => "clmulh",
+            zbc_f3::CLMULR => "clmulr",
+            _ => "r??",
+        };
+        return format!("{mn} {}, {}, {}", xreg(rd), xreg(rs1), xreg(rs2));
+    }
+
     let mn = match (f3, f7) {
         (i_f3::ADD_SUB, i_f7::DEFAULT) => "add",
         (i_f3::ADD_SUB, i_f7::SUB) => "sub",
@@ -277,6 +325,32 @@ fn disasm_op_reg(rd: usize, rs1: usize, rs2: usize, f3: u32, f7: u32, is_w: bool
 fn disasm_op_imm(rd: usize, rs1: usize, f3: u32, imm: i64, is_w: bool) -> String {
     let suffix = if is_w { "w" } else { "" };
     let shamt = imm & 0x3F;
+    let imm12 = (imm as u32) & 0xFFF;
+
+    // Zba slli.uw
+    if f3 == zba_f3::SLLI_UW && is_w && (imm12 >> 6) == zba_op::SLLI_UW {
+        return format!("slli.uw {}, {}, {shamt}", xreg(rd), xreg(rs1));
+    }
+
+    // Zbb unary operations
+    if f3 == zbb_f3::COUNT_SEXT && (imm12 >> 5) == zbb_op::COUNT_SEXT {
+        let mn = match ((imm12 & 0x1F) as usize, is_w) {
+            (zbb_op::RS2_CLZ, _) => "clz",
+            (zbb_op::RS2_CTZ, _) => "ctz",
+            (zbb_op::RS2_CPOP, _) => "cpop",
+            (zbb_op::RS2_SEXT_B, false) => return format!("sext.b {}, {}", xreg(rd), xreg(rs1)),
+            (zbb_op::RS2_SEXT_H, false) => return format!("sext.h {}, {}", xreg(rd), xreg(rs1)),
+            _ => "b??",
+        };
+        return format!("{mn}{suffix} {}, {}", xreg(rd), xreg(rs1));
+    }
+    if f3 == zbb_f3::ORC_REV && !is_w && imm12 == zbb_op::ORC_B_IMM {
+        return format!("orc.b {}, {}", xreg(rd), xreg(rs1));
+    }
+    if f3 == zbb_f3::ORC_REV && !is_w && imm12 == zbb_op::REV8_IMM {
+        return format!("rev8 {}, {}", xreg(rd), xreg(rs1));
+    }
+
     let mn = match f3 {
         i_f3::ADD_SUB => "addi",
         i_f3::SLT => "slti",
@@ -11,6 +11,10 @@
 //! * `rv64f`: Standard Extension for Single-Precision Floating-Point.
 //! * `rv64d`: Standard Extension for Double-Precision Floating-Point.
 //! * `rvc`: Standard Extension for Compressed Instructions.
+//! * `zba`: Address Generation Extension.
+//! * `zbb`: Basic Bit-Manipulation Extension.
+//! * `zbc`: Carry-Less Multiplication Extension.
+//! * `zihintntl`: Non-Temporal Locality Hints.
 //! * `privileged`: Privileged Architecture (CSRs, Traps).
 
 /// Application Binary Interface (ABI) register name mappings.
@@ -45,3 +49,15 @@ pub mod rv64m;
 
 /// Compressed instruction extension (16-bit instruction encoding).
 pub mod rvc;
+
+/// Address generation extension (SH1ADD, SH2ADD, SH3ADD, ADD.UW, SLLI.UW instructions).
+pub mod zba;
+
+/// Basic bit-manipulation extension (CLZ, CPOP, MIN/MAX, REV8, ... instructions).
+pub mod zbb;
+
+/// Carry-less multiplication extension (CLMUL, CLMULH, CLMULR instructions).
+pub mod zbc;
+
+/// Non-temporal locality hints (NTL.P1, NTL.PALL, NTL.S1, NTL.ALL).
+pub mod zihintntl;
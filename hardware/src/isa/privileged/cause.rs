@@ -69,4 +69,10 @@ pub mod exception {
     pub const STORE_PAGE_FAULT: u64 = 15;
     /// Hardware error (18) - Reserved in standard, often used for bus errors.
     pub const HARDWARE_ERROR: u64 = 18;
+    /// Instruction TLB miss (24, custom) - raised only with a software-managed TLB.
+    pub const INSTRUCTION_TLB_MISS: u64 = 24;
+    /// Load TLB miss (25, custom) - raised only with a software-managed TLB.
+    pub const LOAD_TLB_MISS: u64 = 25;
+    /// Store/AMO TLB miss (26, custom) - raised only with a software-managed TLB.
+    pub const STORE_TLB_MISS: u64 = 26;
 }
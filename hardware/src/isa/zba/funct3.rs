@@ -0,0 +1,19 @@
+//! RISC-V Zba-Extension Function Codes (funct3).
+//!
+//! Identifies the specific address generation operation within the
+//! selector groups in [`super::opcodes`].
+
+/// Shift left by 1 and add (`OP_REG`, `funct7 == SHADD`).
+pub const SH1ADD: u32 = 0b010;
+
+/// Shift left by 2 and add (`OP_REG`, `funct7 == SHADD`).
+pub const SH2ADD: u32 = 0b100;
+
+/// Shift left by 3 and add (`OP_REG`, `funct7 == SHADD`).
+pub const SH3ADD: u32 = 0b110;
+
+/// Add unsigned word (`OP_REG_32`, `funct7 == ADD_UW`).
+pub const ADD_UW: u32 = 0b000;
+
+/// Shift left unsigned word immediate (`OP_IMM_32`, `funct6 == SLLI_UW`).
+pub const SLLI_UW: u32 = 0b001;
@@ -0,0 +1,17 @@
+//! RISC-V Address Generation Extension (Zba).
+//!
+//! The 'Zba' extension accelerates array indexing with shift-and-add and
+//! unsigned-word instructions. They share the `OP_REG`, `OP_REG_32`, and
+//! `OP_IMM_32` opcodes with base integer arithmetic and are distinguished
+//! by `funct7` (or the upper immediate bits for SLLI.UW).
+//!
+//! # Structure
+//!
+//! - `opcodes`: funct7 / funct6 selectors.
+//! - `funct3`: Function codes identifying SH1ADD, SH2ADD, SH3ADD, ADD.UW, and SLLI.UW.
+
+/// Function code 3 definitions for address generation operations.
+pub mod funct3;
+
+/// Address generation extension opcodes.
+pub mod opcodes;
@@ -0,0 +1,14 @@
+//! RISC-V Address Generation Extension (Zba) Opcodes.
+//!
+//! Zba shares the `OP_REG`, `OP_REG_32`, and `OP_IMM_32` opcodes with base
+//! integer instructions. It is distinguished by the `funct7` field, or by
+//! `funct6` (immediate bits 11:6) for the shift-immediate form.
+
+/// SHnADD selector in funct7 field (`OP_REG`).
+pub const SHADD: u32 = 0b0010000;
+
+/// ADD.UW selector in funct7 field (`OP_REG_32`).
+pub const ADD_UW: u32 = 0b0000100;
+
+/// SLLI.UW selector in funct6 field (`OP_IMM_32`, immediate bits 11:6).
+pub const SLLI_UW: u32 = 0b000010;
@@ -0,0 +1,25 @@
+//! RISC-V Zbb-Extension Function Codes (funct3).
+//!
+//! Identifies the specific bit-manipulation operation within the funct7
+//! (or fixed-immediate) group selected in [`super::opcodes`].
+
+/// Signed minimum (`funct7 == MINMAX`).
+pub const MIN: u32 = 0b100;
+
+/// Unsigned minimum (`funct7 == MINMAX`).
+pub const MINU: u32 = 0b101;
+
+/// Signed maximum (`funct7 == MINMAX`).
+pub const MAX: u32 = 0b110;
+
+/// Unsigned maximum (`funct7 == MINMAX`).
+pub const MAXU: u32 = 0b111;
+
+/// Unary count / sign-extend group (CLZ, CTZ, CPOP, SEXT.B, SEXT.H).
+pub const COUNT_SEXT: u32 = 0b001;
+
+/// Byte permutation group (ORC.B, REV8).
+pub const ORC_REV: u32 = 0b101;
+
+/// Zero-extend halfword (`OP_REG_32`, `funct7 == ZEXT_H`).
+pub const ZEXT_H: u32 = 0b100;
@@ -0,0 +1,17 @@
+//! RISC-V Basic Bit-Manipulation Extension (Zbb).
+//!
+//! The 'Zbb' extension adds count, min/max, sign/zero-extension, and byte
+//! permutation instructions. The register forms share `OP_REG`/`OP_REG_32`
+//! with base integer arithmetic; the unary forms are encoded under
+//! `OP_IMM`/`OP_IMM_32` with a fixed upper immediate selecting the operation.
+//!
+//! # Structure
+//!
+//! - `opcodes`: funct7 selectors, unary `rs2` selectors, and fixed immediates.
+//! - `funct3`: Function codes identifying MIN/MAX, the unary groups, and ZEXT.H.
+
+/// Function code 3 definitions for basic bit-manipulation operations.
+pub mod funct3;
+
+/// Basic bit-manipulation extension opcodes and selectors.
+pub mod opcodes;
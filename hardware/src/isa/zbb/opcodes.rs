@@ -0,0 +1,36 @@
+//! RISC-V Basic Bit-Manipulation Extension (Zbb) Opcodes.
+//!
+//! Zbb shares the `OP_REG`, `OP_REG_32`, `OP_IMM`, and `OP_IMM_32` opcodes
+//! with base integer instructions and is distinguished by `funct7`, the
+//! `rs2` field, or the full 12-bit immediate.
+
+/// MIN/MAX selector in funct7 field.
+/// Shared with Zbc (`ZBC_EXTENSION`); `funct3` tells the two apart.
+pub const MINMAX: u32 = 0b0000101;
+
+/// Unary count / sign-extend selector in funct7 field (`OP_IMM`, `OP_IMM_32`).
+pub const COUNT_SEXT: u32 = 0b0110000;
+
+/// ZEXT.H selector in funct7 field (`OP_REG_32` with `rs2 == 0`).
+pub const ZEXT_H: u32 = 0b0000100;
+
+/// `rs2` selector for CLZ / CLZW.
+pub const RS2_CLZ: usize = 0b00000;
+
+/// `rs2` selector for CTZ / CTZW.
+pub const RS2_CTZ: usize = 0b00001;
+
+/// `rs2` selector for CPOP / CPOPW.
+pub const RS2_CPOP: usize = 0b00010;
+
+/// `rs2` selector for SEXT.B.
+pub const RS2_SEXT_B: usize = 0b00100;
+
+/// `rs2` selector for SEXT.H.
+pub const RS2_SEXT_H: usize = 0b00101;
+
+/// Full 12-bit immediate encoding ORC.B (`OP_IMM`, `funct3 == ORC_REV`).
+pub const ORC_B_IMM: u32 = 0x287;
+
+/// Full 12-bit immediate encoding REV8 on RV64 (`OP_IMM`, `funct3 == ORC_REV`).
+pub const REV8_IMM: u32 = 0x6B8;
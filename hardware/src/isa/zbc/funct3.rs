@@ -0,0 +1,13 @@
+//! RISC-V Zbc-Extension Function Codes (funct3).
+//!
+//! Identifies the specific carry-less multiply when `opcode == OP_REG`
+//! and `funct7 == 5`.
+
+/// Carry-less multiply -> lower 64 bits of the 128-bit product.
+pub const CLMUL: u32 = 0b001;
+
+/// Carry-less multiply reversed -> bits 126..63 of the product.
+pub const CLMULR: u32 = 0b010;
+
+/// Carry-less multiply high -> upper 64 bits of the product.
+pub const CLMULH: u32 = 0b011;
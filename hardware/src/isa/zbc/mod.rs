@@ -0,0 +1,16 @@
+//! RISC-V Carry-Less Multiplication Extension (Zbc).
+//!
+//! The 'Zbc' extension adds carry-less multiply instructions used by CRC and
+//! GCM implementations. They share the `OP_REG` opcode with base integer
+//! arithmetic and are distinguished by `funct7` being `ZBC_EXTENSION`.
+//!
+//! # Structure
+//!
+//! - `opcodes`: Zbc funct7 selector.
+//! - `funct3`: Function codes identifying CLMUL, CLMULH, and CLMULR.
+
+/// Function code 3 definitions for carry-less multiply operations.
+pub mod funct3;
+
+/// Carry-less multiply extension opcodes.
+pub mod opcodes;
@@ -0,0 +1,9 @@
+//! RISC-V Carry-Less Multiplication Extension (Zbc) Opcodes.
+//!
+//! Zbc shares the `OP_REG` opcode with base integer instructions.
+//! It is distinguished by the `funct7` field having the value 5.
+
+/// Zbc selector in funct7 field.
+/// When `opcode` is `OP_REG` and `funct7` is `ZBC_EXTENSION`, the instruction
+/// is a carry-less multiply.
+pub const ZBC_EXTENSION: u32 = 0b0000101;
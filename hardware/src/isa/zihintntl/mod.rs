@@ -0,0 +1,46 @@
+//! RISC-V Non-Temporal Locality Hints (Zihintntl).
+//!
+//! The 'Zihintntl' extension encodes hints as `add x0, x0, xN` (or the
+//! compressed `c.add x0, xN`, which expands to the same word). A hint says the
+//! memory access of the immediately following instruction has no temporal
+//! locality at some level of the cache hierarchy; it has no architectural effect.
+//!
+//! # Levels
+//!
+//! The simulator maps each hint to the number of cache levels, innermost first,
+//! that the following access should not allocate into:
+//!
+//! - `ntl.p1`: the innermost private cache (L1).
+//! - `ntl.pall`: all private caches (L1 and L2).
+//! - `ntl.s1`: up to the innermost shared cache (L1, L2, and L3).
+//! - `ntl.all`: every level (L1, L2, and L3).
+
+/// `ntl.p1` (`add x0, x0, x2`).
+pub const NTL_P1: u32 = 0x0020_0033;
+
+/// `ntl.pall` (`add x0, x0, x3`).
+pub const NTL_PALL: u32 = 0x0030_0033;
+
+/// `ntl.s1` (`add x0, x0, x4`).
+pub const NTL_S1: u32 = 0x0040_0033;
+
+/// `ntl.all` (`add x0, x0, x5`).
+pub const NTL_ALL: u32 = 0x0050_0033;
+
+/// Returns the number of cache levels a hint marks as non-temporal.
+///
+/// # Arguments
+///
+/// * `inst` - 32-bit (or expanded compressed) instruction encoding.
+///
+/// # Returns
+///
+/// `Some(levels)` if `inst` is a non-temporal hint, otherwise `None`.
+pub fn hint_levels(inst: u32) -> Option<u8> {
+    match inst {
+        NTL_P1 => Some(1),
+        NTL_PALL => Some(2),
+        NTL_S1 | NTL_ALL => Some(3),
+        _ => None,
+    }
+}
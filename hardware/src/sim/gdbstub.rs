@@ -0,0 +1,440 @@
+//! GDB Remote Serial Protocol Stub.
+//!
+//! This module lets `gdb` (or `lldb`) attach to the simulator over TCP with
+//! `target remote :PORT`. It implements the subset of the remote protocol those
+//! debuggers need:
+//! 1. **Registers:** `g`/`G` transfer x0–x31 and `pc`; `p`/`P` also reach f0–f31 and
+//!    CSRs, using GDB's RISC-V numbering (x0–x31 = 0–31, pc = 32, f0–f31 = 33–64,
+//!    CSR `n` = 65 + `n`).
+//! 2. **Memory:** `m`/`M` read and write through the bus, translating addresses with
+//!    the current privilege mode and `satp`.
+//! 3. **Run control:** `c` continues and `s` steps one instruction; `Z0`/`z0` (and the
+//!    hardware-breakpoint `Z1`/`z1`) install breakpoints checked by the fetch stage.
+//!    A `^C` from the debugger halts a running target.
+//!
+//! The hart only stops between instructions: fetch stops, the instructions already in
+//! the pipeline commit, and `pc` then names the next instruction to execute.
+
+use crate::common::SimError;
+use crate::core::Cpu;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Cycles simulated between checks for a `^C` from the debugger.
+const INTERRUPT_POLL_CYCLES: u64 = 4096;
+
+/// GDB register number of the program counter.
+const REG_PC: usize = 32;
+
+/// GDB register number of f0.
+const REG_F0: usize = 33;
+
+/// GDB register number of the first CSR (CSR `n` is `REG_CSR0 + n`).
+const REG_CSR0: usize = 65;
+
+/// Number of CSR addresses.
+const CSR_COUNT: usize = 4096;
+
+/// Largest packet the stub accepts, advertised in `qSupported`.
+const PACKET_SIZE: usize = 0x4000;
+
+/// Out-of-band byte the debugger sends to interrupt a running target.
+const INTERRUPT: u8 = 0x03;
+
+/// Stop signal reported for breakpoints and completed steps (`SIGTRAP`).
+const SIGTRAP: u8 = 5;
+
+/// Stop signal reported when the debugger interrupts the target (`SIGINT`).
+const SIGINT: u8 = 2;
+
+/// Signal reported when the simulation ends with an error (`SIGSEGV`).
+const SIGSEGV: u8 = 11;
+
+/// How a debugging session ended.
+#[derive(Debug)]
+pub enum SessionEnd {
+    /// The debugger detached or disconnected; the target runs on without it.
+    Detached,
+    /// The debugger killed the target.
+    Killed,
+    /// The program exited with this code.
+    Exited(u64),
+    /// The simulation stopped with an error.
+    Faulted(SimError),
+}
+
+/// Why the target stopped running.
+enum Stop {
+    /// Halted at a breakpoint, after a step, or on a `^C` (with this signal).
+    Halted(u8),
+    /// The session is over.
+    Ended(SessionEnd),
+}
+
+/// A GDB connection controlling one CPU.
+pub struct GdbStub {
+    stream: TcpStream,
+}
+
+/// Waits for a debugger on `127.0.0.1:port` and serves it until the session ends.
+///
+/// The CPU is halted at its current PC when the debugger connects.
+///
+/// # Arguments
+///
+/// * `cpu` - The CPU to debug.
+/// * `port` - TCP port to listen on.
+///
+/// # Returns
+///
+/// How the session ended, or an I/O error from the connection.
+pub fn serve(cpu: &mut Cpu, port: u16) -> io::Result<SessionEnd> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    GdbStub::accept(&listener)?.run(cpu)
+}
+
+impl GdbStub {
+    /// Accepts one debugger connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - Socket the debugger connects to.
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+
+    /// Serves debugger requests until the session ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu` - The CPU to debug; halted before the first request is read.
+    ///
+    /// # Returns
+    ///
+    /// How the session ended, or an I/O error from the connection.
+    pub fn run(&mut self, cpu: &mut Cpu) -> io::Result<SessionEnd> {
+        cpu.request_halt();
+        if let Stop::Ended(end) = self.run_until_halted(cpu)? {
+            return Ok(end);
+        }
+
+        loop {
+            let Some(packet) = self.read_packet()? else {
+                cpu.resume(false);
+                return Ok(SessionEnd::Detached);
+            };
+            let (cmd, args) = packet.split_at(packet.len().min(1));
+            let reply = match cmd {
+                "?" => stop_reply(SIGTRAP),
+                "g" => read_registers(cpu),
+                "G" => write_registers(cpu, args),
+                "p" => read_register(cpu, args),
+                "P" => write_register(cpu, args),
+                "m" => read_memory(cpu, args),
+                "M" => write_memory(cpu, args),
+                "Z" | "z" => breakpoint(cpu, cmd == "Z", args),
+                "c" | "s" => {
+                    if let Some(addr) = parse_hex(args) {
+                        set_reg_value(cpu, REG_PC, addr);
+                    }
+                    cpu.resume(cmd == "s");
+                    match self.run_until_halted(cpu)? {
+                        Stop::Halted(signal) => stop_reply(signal),
+                        Stop::Ended(end) => return Ok(end),
+                    }
+                }
+                "D" => {
+                    self.write_packet("OK")?;
+                    cpu.resume(false);
+                    return Ok(SessionEnd::Detached);
+                }
+                "k" => return Ok(SessionEnd::Killed),
+                "H" => "OK".to_string(),
+                "q" => query(args),
+                _ => String::new(),
+            };
+            self.write_packet(&reply)?;
+        }
+    }
+
+    /// Ticks the CPU until it halts, the program ends, or the debugger interrupts it.
+    ///
+    /// Ends reported here (exit or error) are also sent to the debugger.
+    fn run_until_halted(&mut self, cpu: &mut Cpu) -> io::Result<Stop> {
+        let mut signal = SIGTRAP;
+        let mut cycles = 0u64;
+        while !cpu.is_halted() {
+            if let Err(e) = cpu.tick() {
+                self.write_packet(&format!("X{:02x}", SIGSEGV))?;
+                return Ok(Stop::Ended(SessionEnd::Faulted(e)));
+            }
+            if let Some(code) = cpu.take_exit() {
+                self.write_packet(&format!("W{:02x}", code & 0xFF))?;
+                return Ok(Stop::Ended(SessionEnd::Exited(code)));
+            }
+            cycles += 1;
+            if cycles.is_multiple_of(INTERRUPT_POLL_CYCLES) && self.interrupted()? {
+                cpu.request_halt();
+                signal = SIGINT;
+            }
+        }
+        Ok(Stop::Halted(signal))
+    }
+
+    /// Returns `true` if the debugger has sent a `^C` since the last check.
+    fn interrupted(&mut self) -> io::Result<bool> {
+        self.stream.set_nonblocking(true)?;
+        let mut byte = [0u8; 1];
+        let result = match self.stream.read(&mut byte) {
+            Ok(1) => Ok(byte[0] == INTERRUPT),
+            Ok(_) => Ok(false),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        };
+        self.stream.set_nonblocking(false)?;
+        result
+    }
+
+    /// Reads the next `$data#cs` packet and acknowledges it.
+    ///
+    /// # Returns
+    ///
+    /// The packet data, or `None` if the debugger closed the connection.
+    fn read_packet(&mut self) -> io::Result<Option<String>> {
+        loop {
+            // Skip acknowledgements and stray interrupts until a packet starts.
+            match self.read_byte()? {
+                None => return Ok(None),
+                Some(b'$') => {}
+                Some(_) => continue,
+            }
+            let mut data = Vec::new();
+            loop {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(b'#') => break,
+                    Some(b) => data.push(b),
+                }
+            }
+            let mut checksum = [0u8; 2];
+            self.stream.read_exact(&mut checksum)?;
+            let expected = std::str::from_utf8(&checksum)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok());
+            if expected == Some(checksum_of(&data)) {
+                self.stream.write_all(b"+")?;
+                return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+            }
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    /// Reads one byte, or `None` at end of stream.
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0u8; 1];
+        match self.stream.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+
+    /// Sends `data` as a `$data#cs` packet.
+    fn write_packet(&mut self, data: &str) -> io::Result<()> {
+        let packet = format!("${}#{:02x}", data, checksum_of(data.as_bytes()));
+        self.stream.write_all(packet.as_bytes())?;
+        self.stream.flush()
+    }
+}
+
+/// Returns the modulo-256 sum of `data`.
+fn checksum_of(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+/// Formats a stop reply for `signal`.
+fn stop_reply(signal: u8) -> String {
+    format!("S{:02x}", signal)
+}
+
+/// Answers a general query (`q...`).
+fn query(args: &str) -> String {
+    if args.starts_with("Supported") {
+        format!("PacketSize={:x}", PACKET_SIZE)
+    } else if args == "Attached" {
+        "1".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Parses a big-endian hex number.
+fn parse_hex(s: &str) -> Option<u64> {
+    u64::from_str_radix(s, 16).ok()
+}
+
+/// Encodes a register value as 16 hex digits in target (little-endian) byte order.
+fn encode_reg(val: u64) -> String {
+    val.to_le_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Decodes 16 hex digits in target byte order.
+fn decode_reg(hex: &str) -> Option<u64> {
+    let bytes = decode_bytes(hex)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Decodes a hex string into bytes.
+fn decode_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Reads GDB register `n`.
+fn reg_value(cpu: &Cpu, n: usize) -> Option<u64> {
+    match n {
+        0..REG_PC => Some(cpu.regs.read(n)),
+        REG_PC => Some(cpu.next_commit_pc()),
+        REG_F0..REG_CSR0 => Some(cpu.regs.read_f(n - REG_F0)),
+        _ if n - REG_CSR0 < CSR_COUNT => Some(cpu.csr_read((n - REG_CSR0) as u32)),
+        _ => None,
+    }
+}
+
+/// Writes GDB register `n`; returns `false` if there is no such register.
+fn set_reg_value(cpu: &mut Cpu, n: usize, val: u64) -> bool {
+    match n {
+        0 => {}
+        1..REG_PC => cpu.regs.write(n, val),
+        REG_PC => {
+            cpu.pc = val;
+            cpu.retire_check.resync();
+        }
+        REG_F0..REG_CSR0 => cpu.regs.write_f(n - REG_F0, val),
+        _ if n - REG_CSR0 < CSR_COUNT => cpu.csr_write((n - REG_CSR0) as u32, val),
+        _ => return false,
+    }
+    true
+}
+
+/// `g`: x0–x31 followed by `pc`.
+fn read_registers(cpu: &Cpu) -> String {
+    (0..=REG_PC)
+        .map(|n| encode_reg(reg_value(cpu, n).unwrap_or(0)))
+        .collect()
+}
+
+/// `G XX...`: writes x0–x31 and `pc` (as many as are given).
+fn write_registers(cpu: &mut Cpu, args: &str) -> String {
+    for n in 0..=REG_PC {
+        let Some(hex) = args.get(n * 16..(n + 1) * 16) else {
+            break;
+        };
+        let Some(val) = decode_reg(hex) else {
+            return "E01".to_string();
+        };
+        set_reg_value(cpu, n, val);
+    }
+    "OK".to_string()
+}
+
+/// `p n`: one register.
+fn read_register(cpu: &Cpu, args: &str) -> String {
+    parse_hex(args)
+        .and_then(|n| reg_value(cpu, n as usize))
+        .map_or_else(|| "E01".to_string(), encode_reg)
+}
+
+/// `P n=XX...`: writes one register.
+fn write_register(cpu: &mut Cpu, args: &str) -> String {
+    let parsed = args
+        .split_once('=')
+        .and_then(|(n, hex)| Some((parse_hex(n)? as usize, decode_reg(hex)?)));
+    match parsed {
+        Some((n, val)) if set_reg_value(cpu, n, val) => "OK".to_string(),
+        _ => "E01".to_string(),
+    }
+}
+
+/// Parses `addr,len`.
+fn parse_range(s: &str) -> Option<(u64, u64)> {
+    let (addr, len) = s.split_once(',')?;
+    Some((parse_hex(addr)?, parse_hex(len)?))
+}
+
+/// `m addr,len`: reads memory.
+///
+/// Reads a page at a time through [`Cpu::peek_mem`], so the guest's TLBs and PTE
+/// A/D bits are not disturbed. Replies with at most as many bytes as fit in
+/// [`PACKET_SIZE`] and stops before the first unreadable page; the debugger asks
+/// again for the rest of a short read.
+fn read_memory(cpu: &mut Cpu, args: &str) -> String {
+    let Some((addr, len)) = parse_range(args) else {
+        return "E01".to_string();
+    };
+    let page_size = 1u64 << cpu.mmu.page_shift();
+    let mut remaining = len.min((PACKET_SIZE / 2) as u64);
+    let mut vaddr = addr;
+    let mut out = String::with_capacity(remaining as usize * 2);
+    while remaining > 0 {
+        let chunk = remaining.min(page_size - (vaddr & (page_size - 1)));
+        let Ok(bytes) = cpu.peek_mem(vaddr, chunk as usize) else {
+            // A partial read is allowed; an unreadable first page is an error.
+            return if out.is_empty() {
+                "E14".to_string()
+            } else {
+                out
+            };
+        };
+        out.extend(bytes.iter().map(|b| format!("{:02x}", b)));
+        vaddr = vaddr.wrapping_add(chunk);
+        remaining -= chunk;
+    }
+    out
+}
+
+/// `M addr,len:XX...`: writes memory, all or nothing, through [`Cpu::poke_mem`].
+fn write_memory(cpu: &mut Cpu, args: &str) -> String {
+    let parsed = args
+        .split_once(':')
+        .and_then(|(range, hex)| Some((parse_range(range)?, decode_bytes(hex)?)));
+    let Some(((addr, len), data)) = parsed else {
+        return "E01".to_string();
+    };
+    if data.len() as u64 != len {
+        return "E01".to_string();
+    }
+    match cpu.poke_mem(addr, &data) {
+        Ok(()) => "OK".to_string(),
+        Err(_) => "E14".to_string(),
+    }
+}
+
+/// `Z type,addr,kind` / `z type,addr,kind`: inserts or removes a breakpoint.
+///
+/// Software (`0`) and hardware (`1`) breakpoints are both served by fetch;
+/// watchpoints are not supported.
+fn breakpoint(cpu: &mut Cpu, insert: bool, args: &str) -> String {
+    let mut fields = args.split(',');
+    let (Some(kind), Some(addr)) = (fields.next(), fields.next().and_then(parse_hex)) else {
+        return "E01".to_string();
+    };
+    if kind != "0" && kind != "1" {
+        return String::new();
+    }
+    if insert {
+        cpu.set_breakpoint(addr);
+    } else {
+        cpu.clear_breakpoint(addr);
+    }
+    "OK".to_string()
+}
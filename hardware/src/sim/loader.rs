@@ -4,6 +4,7 @@
 //! 1. **Binary loading:** Reads kernel, firmware, or bare-metal binaries from disk into a byte buffer.
 //! 2. **Kernel boot:** Loads OpenSBI, kernel image, and DTB at fixed addresses and sets PC and privilege.
 //! 3. **Bare-metal fallback:** When no OpenSBI is present, sets up MRET trampoline and MEPC for direct boot.
+//! 4. **ELF loading:** Places the `PT_LOAD` segments of an ELF64 executable at their physical addresses.
 
 use crate::config::Config;
 use crate::core::Cpu;
@@ -11,6 +12,8 @@ use crate::core::arch::csr;
 use crate::core::arch::mode::PrivilegeMode;
 use crate::isa::abi;
 use crate::isa::privileged::opcodes as sys_ops;
+use crate::sim::symbols::{read_u16, read_u32, read_u64};
+use crate::soc::interconnect::Bus;
 use std::fs;
 use std::process;
 
@@ -32,6 +35,85 @@ pub fn load_binary(path: &str) -> Vec<u8> {
     })
 }
 
+/// ELF machine number for RISC-V.
+const EM_RISCV: u16 = 0xF3;
+
+/// Program header type of a loadable segment.
+const PT_LOAD: u32 = 1;
+
+/// Loads the `PT_LOAD` segments of a little-endian ELF64 RISC-V executable onto the bus.
+///
+/// Each segment's file bytes are written at its physical address (`p_paddr`) and the
+/// remainder of its memory size (BSS) is zero-filled.
+///
+/// # Arguments
+///
+/// * `bus` - Bus to write the segments through.
+/// * `bytes` - The whole ELF file.
+///
+/// # Returns
+///
+/// The entry point, or an error if the image is not a RISC-V ELF64 LE executable, is
+/// truncated, has header offsets or sizes that overflow, has a segment whose file size
+/// exceeds its memory size, or has a segment outside every device range.
+pub fn load_elf(bus: &mut Bus, bytes: &[u8]) -> Result<u64, String> {
+    if bytes.len() < 64 || &bytes[..4] != b"\x7fELF" {
+        return Err("not an ELF image".to_string());
+    }
+    if bytes[4] != 2 || bytes[5] != 1 {
+        return Err("only little-endian ELF64 is supported".to_string());
+    }
+    if read_u16(bytes, 0x12)? != EM_RISCV {
+        return Err("not a RISC-V executable".to_string());
+    }
+    let entry = read_u64(bytes, 0x18)?;
+    let phoff = read_u64(bytes, 0x20)? as usize;
+    let phentsize = read_u16(bytes, 0x36)? as usize;
+    let phnum = read_u16(bytes, 0x38)? as usize;
+
+    for i in 0..phnum {
+        let ph = i
+            .checked_mul(phentsize)
+            .and_then(|off| off.checked_add(phoff))
+            .ok_or("program header table out of range")?;
+        if read_u32(bytes, ph)? != PT_LOAD {
+            continue;
+        }
+        let offset = read_u64(bytes, ph + 0x08)? as usize;
+        let paddr = read_u64(bytes, ph + 0x18)?;
+        let filesz = read_u64(bytes, ph + 0x20)?;
+        let memsz = read_u64(bytes, ph + 0x28)?;
+        if filesz > memsz {
+            return Err(format!(
+                "segment at {:#x} has file size {:#x} larger than memory size {:#x}",
+                paddr, filesz, memsz
+            ));
+        }
+        if memsz == 0 {
+            continue;
+        }
+        let end = paddr
+            .checked_add(memsz)
+            .ok_or_else(|| format!("segment at {:#x} wraps the address space", paddr))?;
+        if !bus.is_valid_address(paddr) || !bus.is_valid_address(end - 1) {
+            return Err(format!(
+                "segment [{:#x}, {:#x}) is not backed by memory",
+                paddr, end
+            ));
+        }
+        let data = offset
+            .checked_add(filesz as usize)
+            .and_then(|file_end| bytes.get(offset..file_end))
+            .ok_or_else(|| format!("truncated ELF at offset {:#x}", offset))?;
+        bus.load_binary_at(data, paddr);
+        let bss = (memsz - filesz) as usize;
+        if bss > 0 {
+            bus.load_binary_at(&vec![0; bss], paddr + filesz);
+        }
+    }
+    Ok(entry)
+}
+
 /// Sets up kernel loading: places OpenSBI, kernel image, and DTB in RAM and initializes CPU state.
 ///
 /// If OpenSBI is found, loads it at `ram_base`, kernel at `ram_base + 0x200000`, DTB at `ram_base + 0x2200000`,
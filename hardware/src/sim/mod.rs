@@ -1,6 +1,12 @@
 //! Simulation utilities and program loading.
 //!
-//! Provides utilities for loading binaries into memory and setting up
-//! the initial system state for simulation.
+//! Provides utilities for loading binaries into memory, setting up
+//! the initial system state for simulation, resolving addresses to symbols,
+//! exporting pipeline timelines in the Chrome tracing (Perfetto) format,
+//! serving a GDB remote debugger, and servicing bare-metal system calls.
 
+pub mod gdbstub;
 pub mod loader;
+pub mod perfetto;
+pub mod semihosting;
+pub mod symbols;
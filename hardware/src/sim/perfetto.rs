@@ -0,0 +1,317 @@
+//! Chrome Tracing (Perfetto) Export.
+//!
+//! This module writes a timeline of the pipeline in the Chrome trace-event JSON format,
+//! viewable in Perfetto or `chrome://tracing`. It provides:
+//! 1. **Instruction lifetimes:** One complete (`"X"`) event per instruction, from the cycle it
+//!    enters IF/ID until it leaves MEM/WB (retired or trapped) or is squashed.
+//! 2. **Lanes:** Overlapping instructions are spread over thread lanes so each row stays
+//!    properly nested.
+//! 3. **Counters:** IPC, L1 miss rates, and branch accuracy over each sampling window.
+//!
+//! The tracer is driven by the per-cycle pipeline state: call [`PerfettoTracer::observe`]
+//! from a [`CycleHook`](crate::core::cpu::CycleHook) and [`PerfettoTracer::finish`] at exit.
+//! One simulated cycle is one microsecond on the timeline.
+
+use std::io::{self, Write};
+
+use serde_json::{Value, json};
+
+use crate::core::Cpu;
+use crate::isa::disasm::disassemble;
+
+/// Default number of cycles between counter samples.
+pub const DEFAULT_SAMPLE_INTERVAL: u64 = 1000;
+
+/// Process id used for every event.
+const PID: u32 = 0;
+
+/// Pipeline latches in program order, oldest first.
+const STAGES: [&str; 4] = ["MEM/WB", "EX/MEM", "ID/EX", "IF/ID"];
+
+/// An instruction currently held in a pipeline latch.
+#[derive(Clone, Copy, Debug)]
+struct InFlight {
+    pc: u64,
+    inst: u32,
+    /// Index into [`STAGES`] of the latch it was last seen in.
+    stage: usize,
+    /// Whether it carried a trap when last seen.
+    trap: bool,
+    start: u64,
+    lane: usize,
+}
+
+/// Counter values at the start of the current sampling window.
+#[derive(Clone, Copy, Debug, Default)]
+struct Sample {
+    cycles: u64,
+    retired: u64,
+    icache_hits: u64,
+    icache_misses: u64,
+    dcache_hits: u64,
+    dcache_misses: u64,
+    bp_correct: u64,
+    bp_wrong: u64,
+}
+
+impl Sample {
+    fn of(cpu: &Cpu) -> Self {
+        let s = &cpu.stats;
+        Self {
+            cycles: s.cycles,
+            retired: s.instructions_retired,
+            icache_hits: s.icache_hits,
+            icache_misses: s.icache_misses,
+            dcache_hits: s.dcache_hits,
+            dcache_misses: s.dcache_misses,
+            bp_correct: s.branch_predictions,
+            bp_wrong: s.branch_mispredictions,
+        }
+    }
+}
+
+/// Returns `part / (part + rest)`, or zero for an empty window.
+fn ratio(part: u64, rest: u64) -> f64 {
+    let total = part + rest;
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Streaming writer of a Chrome trace-event JSON array.
+pub struct PerfettoTracer {
+    sink: Box<dyn Write + Send>,
+    interval: u64,
+    in_flight: Vec<InFlight>,
+    lanes: Vec<bool>,
+    last: Option<Sample>,
+    cycle: u64,
+    events: u64,
+    error: Option<io::Error>,
+}
+
+impl PerfettoTracer {
+    /// Creates a tracer and writes the opening of the event array.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Destination of the JSON trace (e.g. a file).
+    /// * `interval` - Cycles between counter samples (at least 1).
+    pub fn new(sink: Box<dyn Write + Send>, interval: u64) -> Self {
+        let mut tracer = Self {
+            sink,
+            interval: interval.max(1),
+            in_flight: Vec::new(),
+            lanes: Vec::new(),
+            last: None,
+            cycle: 0,
+            events: 0,
+            error: None,
+        };
+        tracer.write_raw(b"[\n");
+        tracer.emit(json!({
+            "name": "process_name", "ph": "M", "pid": PID,
+            "args": { "name": "riscv-sim pipeline" },
+        }));
+        tracer
+    }
+
+    /// Records the pipeline state at the start of a cycle.
+    ///
+    /// Instructions are matched to the previous cycle's latches by PC and encoding,
+    /// in program order; an instruction that is no longer present ends its event.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu` - CPU state before the cycle executes.
+    pub fn observe(&mut self, cpu: &Cpu) {
+        self.cycle = cpu.stats.cycles;
+
+        let mut latched = Vec::new();
+        latched.extend(
+            cpu.mem_wb
+                .entries
+                .iter()
+                .map(|e| (0, e.pc, e.inst, e.trap.is_some())),
+        );
+        latched.extend(
+            cpu.ex_mem
+                .entries
+                .iter()
+                .map(|e| (1, e.pc, e.inst, e.trap.is_some())),
+        );
+        latched.extend(
+            cpu.id_ex
+                .entries
+                .iter()
+                .map(|e| (2, e.pc, e.inst, e.trap.is_some())),
+        );
+        latched.extend(
+            cpu.if_id
+                .entries
+                .iter()
+                .map(|e| (3, e.pc, e.inst, e.trap.is_some())),
+        );
+
+        let previous = std::mem::take(&mut self.in_flight);
+        let mut next = 0;
+        let mut started = Vec::new();
+        for (stage, pc, inst, trap) in latched {
+            // An instruction only moves toward MEM/WB, so it matches an older-or-same latch.
+            let found = previous[next..]
+                .iter()
+                .position(|f| f.pc == pc && f.inst == inst && f.stage >= stage);
+            match found {
+                Some(offset) => {
+                    for gone in &previous[next..next + offset] {
+                        self.end(gone);
+                    }
+                    let mut kept = previous[next + offset];
+                    kept.stage = stage;
+                    kept.trap = trap;
+                    self.in_flight.push(kept);
+                    next += offset + 1;
+                }
+                None => {
+                    self.in_flight.push(InFlight {
+                        pc,
+                        inst,
+                        stage,
+                        trap,
+                        start: self.cycle,
+                        lane: 0,
+                    });
+                    started.push(self.in_flight.len() - 1);
+                }
+            }
+        }
+        for gone in &previous[next..] {
+            self.end(gone);
+        }
+        // Lanes freed this cycle are reusable by instructions starting in it.
+        for idx in started {
+            self.in_flight[idx].lane = self.alloc_lane();
+        }
+
+        self.sample_counters(cpu);
+    }
+
+    /// Ends every in-flight instruction and closes the event array.
+    ///
+    /// # Returns
+    ///
+    /// The first write error encountered while tracing, if any.
+    pub fn finish(&mut self) -> io::Result<()> {
+        for gone in std::mem::take(&mut self.in_flight) {
+            self.end(&gone);
+        }
+        self.write_raw(b"\n]\n");
+        if let Err(e) = self.sink.flush() {
+            self.error.get_or_insert(e);
+        }
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Emits the lifetime event of an instruction that left the pipeline this cycle.
+    fn end(&mut self, f: &InFlight) {
+        let outcome = match (f.stage, f.trap) {
+            (_, true) => "trapped",
+            (0, false) => "retired",
+            _ => "squashed",
+        };
+        self.lanes[f.lane] = false;
+        self.emit(json!({
+            "name": disassemble(f.inst), "cat": outcome, "ph": "X",
+            "ts": f.start, "dur": self.cycle - f.start, "pid": PID, "tid": f.lane,
+            "args": {
+                "pc": format!("{:#x}", f.pc),
+                "inst": format!("{:#010x}", f.inst),
+                "last_stage": STAGES[f.stage],
+            },
+        }));
+    }
+
+    /// Returns the lowest free lane, naming a new one if all are busy.
+    fn alloc_lane(&mut self) -> usize {
+        if let Some(lane) = self.lanes.iter().position(|busy| !busy) {
+            self.lanes[lane] = true;
+            return lane;
+        }
+        let lane = self.lanes.len();
+        self.lanes.push(true);
+        self.emit(json!({
+            "name": "thread_name", "ph": "M", "pid": PID, "tid": lane,
+            "args": { "name": format!("lane {}", lane) },
+        }));
+        lane
+    }
+
+    /// Emits counter events when a sampling window closes.
+    fn sample_counters(&mut self, cpu: &Cpu) {
+        let now = Sample::of(cpu);
+        let Some(last) = self.last else {
+            self.last = Some(now);
+            return;
+        };
+        let cycles = now.cycles.wrapping_sub(last.cycles);
+        if cycles < self.interval {
+            return;
+        }
+        self.last = Some(now);
+
+        let ts = now.cycles;
+        let ipc = now.retired.wrapping_sub(last.retired) as f64 / cycles as f64;
+        let l1i = ratio(
+            now.icache_misses - last.icache_misses,
+            now.icache_hits - last.icache_hits,
+        );
+        let l1d = ratio(
+            now.dcache_misses - last.dcache_misses,
+            now.dcache_hits - last.dcache_hits,
+        );
+        let bp = ratio(
+            now.bp_correct - last.bp_correct,
+            now.bp_wrong - last.bp_wrong,
+        );
+        self.emit(
+            json!({ "name": "IPC", "ph": "C", "ts": ts, "pid": PID, "args": { "ipc": ipc } }),
+        );
+        self.emit(json!({
+            "name": "Cache miss rate", "ph": "C", "ts": ts, "pid": PID,
+            "args": { "l1i": l1i, "l1d": l1d },
+        }));
+        self.emit(json!({
+            "name": "Branch accuracy", "ph": "C", "ts": ts, "pid": PID,
+            "args": { "accuracy": bp },
+        }));
+    }
+
+    /// Appends one event to the array.
+    fn emit(&mut self, event: Value) {
+        if self.events > 0 {
+            self.write_raw(b",\n");
+        }
+        self.events += 1;
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = serde_json::to_writer(&mut self.sink, &event) {
+            self.error = Some(e.into());
+        }
+    }
+
+    /// Writes raw bytes, latching the first error.
+    fn write_raw(&mut self, bytes: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.sink.write_all(bytes) {
+            self.error = Some(e);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Direct-Mode Semihosting.
+//!
+//! In direct mode there is no kernel to service `ecall`, so bare-metal programs make Linux
+//! system calls that the simulator carries out on the host. The call number is taken from
+//! `a7` and the arguments from `a0` upward, as on Linux; the result (or a negated `errno`) is written to `a0`
+//! and execution resumes after the `ecall`. It provides:
+//! 1. **Console I/O:** `read` from fd 0 and `write` to fds 1 and 2, backed by host stdio by
+//!    default and redirectable with [`Cpu::set_semihost_stdin`] and
+//!    [`Cpu::set_semihost_stdout`]. Host input arrives through the console reader shared
+//!    with the UART, since that reader keeps stdin locked once started.
+//! 2. **Heap:** `brk` moves a program break through the RAM above the initial stack pointer.
+//! 3. **File status:** `fstat` reports the standard streams as character devices, so C
+//!    libraries buffer them line by line.
+//! 4. **Exit:** `exit` and `exit_group` end the run with `a0` as the exit code. The older
+//!    convention of `a0 = 93` with the code in `a1` is still honored for unknown `a7`.
+//!
+//! Unknown calls return `-ENOSYS`.
+
+use std::io::{self, Read, Write};
+
+use crate::core::Cpu;
+use crate::isa::abi;
+use crate::isa::privileged::opcodes as sys_ops;
+use crate::soc::devices::uart::stdin_receiver;
+
+/// Linux RISC-V system call numbers.
+const SYS_READ: u64 = 63;
+const SYS_WRITE: u64 = 64;
+const SYS_FSTAT: u64 = 80;
+const SYS_EXIT_GROUP: u64 = 94;
+const SYS_BRK: u64 = 214;
+
+/// `errno` values returned (negated) in `a0`.
+const EIO: i64 = 5;
+const EBADF: i64 = 9;
+const EFAULT: i64 = 14;
+const ENOSYS: i64 = 38;
+
+/// Standard stream file descriptors.
+const STDIN_FD: u64 = 0;
+const STDOUT_FD: u64 = 1;
+const STDERR_FD: u64 = 2;
+
+/// Largest transfer serviced by one `read` or `write`; longer requests complete partially.
+const MAX_TRANSFER: u64 = 1 << 20;
+
+/// Size of the RV64 Linux `struct stat`.
+const STAT_SIZE: usize = 128;
+/// Offset of `st_mode` within `struct stat`.
+const STAT_MODE_OFFSET: usize = 16;
+/// `st_mode` of the standard streams: a character device, read/write by owner.
+const STAT_MODE_CHR: u32 = 0o020_620;
+
+/// Destination for the program's standard output.
+pub type ConsoleSink = Box<dyn Write + Send>;
+/// Source of the program's standard input.
+pub type ConsoleSource = Box<dyn Read + Send>;
+
+/// Host standard input, read through the process-wide console reader.
+///
+/// A `read` blocks until a full line, a full buffer, or end of input, as a terminal
+/// in canonical mode would deliver it.
+struct HostStdin;
+
+impl Read for HostStdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let rx = stdin_receiver()
+            .lock()
+            .map_err(|_| io::Error::other("console reader poisoned"))?;
+        let mut n = 0;
+        while n < buf.len() {
+            let Ok(byte) = rx.recv() else {
+                break;
+            };
+            buf[n] = byte;
+            n += 1;
+            if byte == b'\n' {
+                break;
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Host-side state of the semihosting layer.
+pub struct Semihost {
+    stdin: ConsoleSource,
+    stdout: ConsoleSink,
+    /// Lowest program break; `brk` never moves below it.
+    heap_start: u64,
+    /// Highest program break (end of RAM).
+    heap_end: u64,
+    /// Current program break.
+    brk: u64,
+}
+
+impl Semihost {
+    /// Creates the semihosting state with host stdio and an empty heap.
+    ///
+    /// # Arguments
+    ///
+    /// * `heap_start` - Initial program break; rounded up to a 4 KiB boundary.
+    /// * `heap_end` - Address the break may not move past.
+    pub fn new(heap_start: u64, heap_end: u64) -> Self {
+        let heap_start = heap_start.next_multiple_of(4096).min(heap_end);
+        Self {
+            stdin: Box::new(HostStdin),
+            stdout: Box::new(io::stdout()),
+            heap_start,
+            heap_end,
+            brk: heap_start,
+        }
+    }
+
+    /// Moves the program break to `addr` if it lies within the heap.
+    ///
+    /// # Returns
+    ///
+    /// The new break, or the unchanged one if `addr` is out of range (Linux semantics,
+    /// which makes `brk(0)` a query).
+    fn set_brk(&mut self, addr: u64) -> u64 {
+        if (self.heap_start..=self.heap_end).contains(&addr) {
+            self.brk = addr;
+        }
+        self.brk
+    }
+}
+
+impl Cpu {
+    /// Routes the program's standard output (fd 1) to `sink`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Writer receiving the bytes of every `write(1, ...)`.
+    pub fn set_semihost_stdout(&mut self, sink: ConsoleSink) {
+        self.semihost.stdout = sink;
+    }
+
+    /// Reads the program's standard input (fd 0) from `source`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Reader supplying the bytes of every `read(0, ...)`.
+    pub fn set_semihost_stdin(&mut self, source: ConsoleSource) {
+        self.semihost.stdin = source;
+    }
+
+    /// Services a direct-mode `ecall` and resumes at the following instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `epc` - PC of the `ecall`.
+    pub(crate) fn semihost_call(&mut self, epc: u64) {
+        let nr = self.regs.read(abi::REG_A7);
+        let a0 = self.regs.read(abi::REG_A0);
+        let a1 = self.regs.read(abi::REG_A1);
+        let a2 = self.regs.read(abi::REG_A2);
+
+        let ret = match nr {
+            sys_ops::SYS_EXIT | SYS_EXIT_GROUP => {
+                self.exit_code = Some(a0);
+                return;
+            }
+            SYS_WRITE => self.sys_write(a0, a1, a2),
+            SYS_READ => self.sys_read(a0, a1, a2),
+            SYS_FSTAT => self.sys_fstat(a0, a1),
+            SYS_BRK => self.semihost.set_brk(a0) as i64,
+            _ if a0 == sys_ops::SYS_EXIT => {
+                self.exit_code = Some(a1);
+                return;
+            }
+            _ => -ENOSYS,
+        };
+        if self.trace {
+            eprintln!("WB  semihost call {} -> {}", nr, ret);
+        }
+        self.regs.write(abi::REG_A0, ret as u64);
+        self.pc = epc.wrapping_add(4);
+    }
+
+    /// `write(fd, buf, count)` to host stdout or stderr.
+    fn sys_write(&mut self, fd: u64, buf: u64, count: u64) -> i64 {
+        if fd != STDOUT_FD && fd != STDERR_FD {
+            return -EBADF;
+        }
+        let Ok(data) = self.read_mem(buf, count.min(MAX_TRANSFER) as usize) else {
+            return -EFAULT;
+        };
+        let result = if fd == STDOUT_FD {
+            let out = &mut self.semihost.stdout;
+            out.write_all(&data).and_then(|()| out.flush())
+        } else {
+            io::stderr().write_all(&data)
+        };
+        match result {
+            Ok(()) => data.len() as i64,
+            Err(_) => -EIO,
+        }
+    }
+
+    /// `read(fd, buf, count)` from host stdin; returns 0 at end of input.
+    fn sys_read(&mut self, fd: u64, buf: u64, count: u64) -> i64 {
+        if fd != STDIN_FD {
+            return -EBADF;
+        }
+        let mut data = vec![0; count.min(MAX_TRANSFER) as usize];
+        let n = match self.semihost.stdin.read(&mut data) {
+            Ok(n) => n,
+            Err(_) => return -EIO,
+        };
+        match self.write_mem(buf, &data[..n]) {
+            Ok(()) => n as i64,
+            Err(_) => -EFAULT,
+        }
+    }
+
+    /// `fstat(fd, statbuf)`; only the standard streams exist.
+    fn sys_fstat(&mut self, fd: u64, statbuf: u64) -> i64 {
+        if fd > STDERR_FD {
+            return -EBADF;
+        }
+        let mut stat = [0u8; STAT_SIZE];
+        stat[STAT_MODE_OFFSET..STAT_MODE_OFFSET + 4].copy_from_slice(&STAT_MODE_CHR.to_le_bytes());
+        match self.write_mem(statbuf, &stat) {
+            Ok(()) => 0,
+            Err(_) => -EFAULT,
+        }
+    }
+}
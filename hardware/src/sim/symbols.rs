@@ -0,0 +1,243 @@
+//! Symbol Map Loading and Address Lookup.
+//!
+//! This module maps program addresses to function names for human-readable reports. It provides:
+//! 1. **nm parsing:** Reads `nm` / `nm -S` text output (`ADDR [SIZE] TYPE NAME` per line).
+//! 2. **ELF parsing:** Reads the `.symtab` of a little-endian ELF64 image (function and label symbols).
+//! 3. **Lookup:** Binary-searches the sorted table and formats addresses as `name+0xoff`.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// ELF section type of a static symbol table.
+const SHT_SYMTAB: u32 = 2;
+
+/// Size of one `Elf64_Sym` entry in bytes.
+const ELF64_SYM_SIZE: usize = 24;
+
+/// Size of one `Elf64_Shdr` entry in bytes.
+const ELF64_SHDR_SIZE: usize = 64;
+
+/// Symbol types kept from ELF images: `STT_NOTYPE` (assembly labels) and `STT_FUNC`.
+const ELF_CODE_SYMBOL_TYPES: [u8; 2] = [0, 2];
+
+/// A named address range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    /// Start address.
+    pub addr: u64,
+    /// Size in bytes (`0` when unknown; the symbol then extends to the next one).
+    pub size: u64,
+    /// Symbol name.
+    pub name: String,
+}
+
+/// Address-sorted symbol table.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// Builds a table from unsorted symbols.
+    ///
+    /// Symbols sharing an address keep the first one given.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - Symbols in any order.
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        symbols.sort_by_key(|s| s.addr);
+        symbols.dedup_by_key(|s| s.addr);
+        Self { symbols }
+    }
+
+    /// Loads a symbol file, detecting ELF images by their magic number.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - ELF image or `nm` output.
+    ///
+    /// # Returns
+    ///
+    /// The table, or a message describing why the file could not be read.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if bytes.starts_with(b"\x7fELF") {
+            Self::from_elf(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+        } else {
+            Ok(Self::from_nm(&String::from_utf8_lossy(&bytes)))
+        }
+    }
+
+    /// Parses `nm` output.
+    ///
+    /// Accepts `ADDR TYPE NAME` and `ADDR SIZE TYPE NAME` (`nm -S`) lines; undefined
+    /// symbols and unparsable lines are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Contents of the `nm` listing.
+    pub fn from_nm(text: &str) -> Self {
+        let symbols = text
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (addr, size, name) = match fields.as_slice() {
+                    [addr, _kind, name] => (addr, "0", name),
+                    [addr, size, _kind, name] => (addr, *size, name),
+                    _ => return None,
+                };
+                Some(Symbol {
+                    addr: u64::from_str_radix(addr, 16).ok()?,
+                    size: u64::from_str_radix(size, 16).ok()?,
+                    name: (*name).to_string(),
+                })
+            })
+            .collect();
+        Self::new(symbols)
+    }
+
+    /// Parses the `.symtab` section of a little-endian ELF64 image.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The whole ELF file.
+    ///
+    /// # Returns
+    ///
+    /// The table, or an error if the image is not ELF64 LE or is truncated.
+    pub fn from_elf(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 64 || &bytes[..4] != b"\x7fELF" {
+            return Err("not an ELF image".to_string());
+        }
+        if bytes[4] != 2 || bytes[5] != 1 {
+            return Err("only little-endian ELF64 is supported".to_string());
+        }
+        let shoff = read_u64(bytes, 0x28)? as usize;
+        let shnum = read_u16(bytes, 0x3C)? as usize;
+        let section = |i: usize| shoff + i * ELF64_SHDR_SIZE;
+
+        let mut symbols = Vec::new();
+        for i in 0..shnum {
+            let sh = section(i);
+            if read_u32(bytes, sh + 4)? != SHT_SYMTAB {
+                continue;
+            }
+            let off = read_u64(bytes, sh + 0x18)? as usize;
+            let size = read_u64(bytes, sh + 0x20)? as usize;
+            let strtab = section(read_u32(bytes, sh + 0x28)? as usize);
+            let str_off = read_u64(bytes, strtab + 0x18)? as usize;
+
+            for sym in (off..off + size).step_by(ELF64_SYM_SIZE) {
+                let name_off = read_u32(bytes, sym)? as usize;
+                let kind = bytes.get(sym + 4).ok_or("truncated symbol table")? & 0xF;
+                let shndx = read_u16(bytes, sym + 6)?;
+                if name_off == 0 || shndx == 0 || !ELF_CODE_SYMBOL_TYPES.contains(&kind) {
+                    continue;
+                }
+                let name = c_str(bytes, str_off + name_off)?;
+                // Skip mapping symbols and local labels such as `$x` or `.L0`.
+                if name.starts_with('$') || name.starts_with(".L") {
+                    continue;
+                }
+                symbols.push(Symbol {
+                    addr: read_u64(bytes, sym + 8)?,
+                    size: read_u64(bytes, sym + 16)?,
+                    name,
+                });
+            }
+        }
+        Ok(Self::new(symbols))
+    }
+
+    /// Returns the number of symbols.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Returns `true` if the table holds no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Finds the symbol containing `addr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to resolve (typically a PC).
+    ///
+    /// # Returns
+    ///
+    /// The symbol and the offset of `addr` into it, or `None` if `addr` precedes every
+    /// symbol or lies past the end of a sized one.
+    pub fn lookup(&self, addr: u64) -> Option<(&Symbol, u64)> {
+        let idx = self
+            .symbols
+            .partition_point(|s| s.addr <= addr)
+            .checked_sub(1)?;
+        let sym = &self.symbols[idx];
+        let offset = addr - sym.addr;
+        if sym.size != 0 && offset >= sym.size {
+            return None;
+        }
+        Some((sym, offset))
+    }
+
+    /// Formats `addr` for a report: `0x80000010 <main+0x10>`, or just the hex address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to format.
+    pub fn format(&self, addr: u64) -> SymbolizedAddr<'_> {
+        SymbolizedAddr { table: self, addr }
+    }
+}
+
+/// An address displayed with its containing symbol, if known.
+pub struct SymbolizedAddr<'a> {
+    table: &'a SymbolTable,
+    addr: u64,
+}
+
+impl fmt::Display for SymbolizedAddr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.addr)?;
+        match self.table.lookup(self.addr) {
+            Some((sym, 0)) => write!(f, " <{}>", sym.name),
+            Some((sym, off)) => write!(f, " <{}+{:#x}>", sym.name, off),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads `N` little-endian bytes at `off`, failing on truncation.
+fn read_le<const N: usize>(bytes: &[u8], off: usize) -> Result<[u8; N], String> {
+    off.checked_add(N)
+        .and_then(|end| bytes.get(off..end))
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| format!("truncated ELF at offset {:#x}", off))
+}
+
+/// Reads a little-endian `u16` at `off`, failing on truncation.
+pub(crate) fn read_u16(bytes: &[u8], off: usize) -> Result<u16, String> {
+    read_le(bytes, off).map(u16::from_le_bytes)
+}
+
+/// Reads a little-endian `u32` at `off`, failing on truncation.
+pub(crate) fn read_u32(bytes: &[u8], off: usize) -> Result<u32, String> {
+    read_le(bytes, off).map(u32::from_le_bytes)
+}
+
+/// Reads a little-endian `u64` at `off`, failing on truncation.
+pub(crate) fn read_u64(bytes: &[u8], off: usize) -> Result<u64, String> {
+    read_le(bytes, off).map(u64::from_le_bytes)
+}
+
+/// Reads a NUL-terminated string starting at `off`.
+fn c_str(bytes: &[u8], off: usize) -> Result<String, String> {
+    let tail = bytes.get(off..).ok_or("string table out of range")?;
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    Ok(String::from_utf8_lossy(&tail[..end]).into_owned())
+}
@@ -2,20 +2,48 @@
 //!
 //! This module builds the complete SoC from configuration. It performs:
 //! 1. **Bus setup:** Creates the interconnect with configured width and latency.
-//! 2. **Device registration:** Instantiates RAM, UART, VirtIO disk, CLINT, PLIC, SysCon, and RTC.
-//! 3. **Memory controller:** Selects simple or DRAM controller based on config.
+//! 2. **Device registration:** Instantiates RAM, UART, VirtIO disk, CLINT, PLIC, SysCon, RTC, and optional NVRAM.
+//! 3. **Memory controller:** Selects simple, DRAM, or HBM timing based on config (one controller per
+//!    interleaved channel when `memory.channels > 1`), plus the fill bandwidth queue.
 //! 4. **Binary loading:** Optionally loads a disk image from path and kernel via `load_binary_at`.
 
-use crate::config::{Config, MemoryController as MemControllerType};
-use crate::soc::devices::{Clint, GoldfishRtc, Plic, SysCon, Uart, VirtioBlock};
+use crate::config::{
+    ChannelInterleave, Config, MemoryConfig, MemoryController as MemControllerType,
+};
+use crate::soc::devices::{Clint, GoldfishRtc, Nvram, Plic, SysCon, Uart, VirtioBlock};
 use crate::soc::interconnect::Bus;
 use crate::soc::memory::Memory;
 use crate::soc::memory::buffer::DramBuffer;
-use crate::soc::memory::controller::{DramController, MemoryController, SimpleController};
+use crate::soc::memory::controller::{
+    BandwidthQueue, DramController, HbmController, InterleavedController, MemoryController,
+    SimpleController,
+};
 use std::fs;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
+/// Interleaving block size in bytes for [`ChannelInterleave::Line`].
+const INTERLEAVE_LINE_BYTES: u64 = 64;
+
+/// Builds the timing model for a single memory channel.
+fn build_controller(memory: &MemoryConfig) -> Box<dyn MemoryController + Send + Sync> {
+    match memory.controller {
+        MemControllerType::Dram => Box::new(
+            DramController::new(memory.t_cas, memory.t_ras, memory.t_pre)
+                .with_banks(memory.dram_banks, memory.dram_row_bytes)
+                .with_refresh(memory.t_refi, memory.t_rfc),
+        ),
+        MemControllerType::Hbm => Box::new(HbmController::new(
+            memory.hbm_channels,
+            memory.t_cas,
+            memory.t_ras,
+            memory.t_pre,
+            memory.hbm_burst_cycles,
+        )),
+        MemControllerType::Simple => Box::new(SimpleController::new(memory.row_miss_latency)),
+    }
+}
+
 /// Top-level system instance containing the bus, memory controller, and exit flag.
 ///
 /// Holds the interconnect (`Bus`), the main memory controller (for DRAM/simple timing),
@@ -25,6 +53,8 @@ pub struct System {
     pub bus: Bus,
     /// Main memory controller (boxed for dynamic dispatch; `Send + Sync` for multi-threaded simulation).
     pub mem_controller: Box<dyn MemoryController + Send + Sync>,
+    /// Fill-bandwidth queue in front of main memory (disabled when configured bandwidth is 0).
+    pub mem_bandwidth: BandwidthQueue,
     /// Atomic exit code: when not `u64::MAX`, simulation should stop and use this as exit code.
     pub exit_request: Arc<AtomicU64>,
 }
@@ -33,7 +63,8 @@ impl System {
     /// Builds a new system from configuration and optional disk image path.
     ///
     /// Creates the bus, RAM, UART, VirtIO disk (loading `disk_path` if non-empty), CLINT, PLIC,
-    /// SysCon, and Goldfish RTC. The memory controller is chosen from `config.memory.controller`.
+    /// SysCon, and Goldfish RTC (unless `config.system.rtc_enabled` is false), plus an NVRAM when `config.system.nvram_size` is nonzero
+    /// (loaded from `nvram_path` if set; an unreadable file leaves it zeroed). The memory controller is chosen from `config.memory.controller`.
     ///
     /// # Arguments
     ///
@@ -56,7 +87,9 @@ impl System {
         let uart = Uart::new(uart_base, config.system.uart_to_stderr);
 
         let clint_addr = config.system.clint_base;
-        let clint = Clint::new(clint_addr, config.system.clint_divider);
+        let mut clint =
+            Clint::new(clint_addr, config.system.clint_divider).with_harts(config.system.harts);
+        clint.set_min_interval(config.system.clint_min_interval);
 
         let plic_addr = 0x0c00_0000;
         let plic = Plic::new(plic_addr);
@@ -74,35 +107,68 @@ impl System {
         let syscon_addr = config.system.syscon_base;
         let syscon = SysCon::new(syscon_addr, exit_request.clone());
 
-        let rtc = GoldfishRtc::new(0x101000);
-
         bus.add_device(Box::new(mem));
         bus.add_device(Box::new(uart));
         bus.add_device(Box::new(disk));
         bus.add_device(Box::new(clint));
         bus.add_device(Box::new(plic));
         bus.add_device(Box::new(syscon));
-        bus.add_device(Box::new(rtc));
 
-        let mem_controller: Box<dyn MemoryController + Send + Sync> = match config.memory.controller
+        if config.system.rtc_enabled {
+            let rtc = GoldfishRtc::new(config.system.rtc_base)
+                .with_ns_per_tick(config.system.rtc_ns_per_tick);
+            bus.add_device(Box::new(rtc));
+        }
+
+        if config.system.nvram_size > 0 {
+            let base = config.system.nvram_base;
+            let size = config.system.nvram_size;
+            let nvram = match &config.system.nvram_path {
+                Some(path) => Nvram::from_file(base, size, path, config.system.nvram_writeback)
+                    .unwrap_or_else(|e| {
+                        eprintln!("[NVRAM] Cannot read {}: {}", path, e);
+                        Nvram::new(base, size)
+                    }),
+                None => Nvram::new(base, size),
+            };
+            bus.add_device(Box::new(nvram));
+        }
+
+        let mem_controller: Box<dyn MemoryController + Send + Sync> = if config.memory.channels > 1
         {
-            MemControllerType::Dram => Box::new(DramController::new(
-                config.memory.t_cas,
-                config.memory.t_ras,
-                config.memory.t_pre,
-            )),
-            MemControllerType::Simple => {
-                Box::new(SimpleController::new(config.memory.row_miss_latency))
-            }
+            let granularity = match config.memory.channel_interleave {
+                ChannelInterleave::Line => INTERLEAVE_LINE_BYTES,
+                ChannelInterleave::Page => config.memory.page_size,
+            };
+            let channels = (0..config.memory.channels)
+                .map(|_| build_controller(&config.memory))
+                .collect();
+            Box::new(InterleavedController::new(channels, granularity))
+        } else {
+            build_controller(&config.memory)
         };
 
         Self {
             bus,
             mem_controller,
+            mem_bandwidth: BandwidthQueue::new(config.memory.bandwidth_bytes_per_cycle),
             exit_request,
         }
     }
 
+    /// Builds a system with no devices, RAM, or pending traffic.
+    ///
+    /// Holds the place of the shared system in the harts of an
+    /// [`Smp`](crate::core::smp::Smp) that are not currently being ticked.
+    pub(crate) fn detached(config: &Config) -> Self {
+        Self {
+            bus: Bus::new(config.system.bus_width, config.system.bus_latency),
+            mem_controller: build_controller(&config.memory),
+            mem_bandwidth: BandwidthQueue::new(0),
+            exit_request: Arc::new(AtomicU64::new(u64::MAX)),
+        }
+    }
+
     /// Loads a binary into memory at the given physical address.
     ///
     /// # Arguments
@@ -122,6 +188,13 @@ impl System {
         self.bus.tick()
     }
 
+    /// Shuts down all devices, letting persistent ones (e.g., NVRAM) write back their state.
+    ///
+    /// Call once when the simulation ends, before the process exits.
+    pub fn shutdown(&mut self) {
+        self.bus.shutdown();
+    }
+
     /// Returns the requested exit code if a device has requested shutdown.
     ///
     /// # Returns
@@ -6,37 +6,85 @@
 //!
 //! # Memory Map
 //!
-//! * `0x0000`: MSIP (Machine Software Interrupt Pending)
-//! * `0x4000`: MTIMECMP (Machine Time Compare)
-//! * `0xBFF8`: MTIME (Machine Time)
+//! * `0x0000 + 4 * hart`: MSIP (Machine Software Interrupt Pending)
+//! * `0x4000 + 8 * hart`: MTIMECMP (Machine Time Compare)
+//! * `0xBFF8`: MTIME (Machine Time), shared by all harts
+//!
+//! # Interrupt Coalescing
+//!
+//! An optional minimum interval (in timer ticks) bounds how often the timer
+//! interrupt may be raised. Expirations inside the window are not lost: the
+//! line rises once when the window closes, delivering a single coalesced
+//! interrupt. This is a performance-study knob and is disabled by default.
 
 use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 
-/// Offset for the Machine Software Interrupt Pending register.
+/// Offset for the Machine Software Interrupt Pending register of hart 0.
 const MSIP_OFFSET: u64 = 0x0000;
-/// Offset for the Machine Time Compare register.
+/// Offset for the Machine Time Compare register of hart 0.
 const MTIMECMP_OFFSET: u64 = 0x4000;
 /// Offset for the Machine Time register.
 const MTIME_OFFSET: u64 = 0xBFF8;
 
+/// Timer and software-interrupt state of one hart.
+#[derive(Clone, Serialize, Deserialize)]
+struct HartTimer {
+    /// Machine time compare register.
+    mtimecmp: u64,
+    /// Machine software interrupt pending register.
+    msip: u32,
+    /// Whether the timer interrupt line is currently raised.
+    timer_line: bool,
+    /// Earliest `mtime` at which the timer interrupt may be raised again.
+    next_fire: u64,
+}
+
+impl Default for HartTimer {
+    fn default() -> Self {
+        Self {
+            mtimecmp: u64::MAX,
+            msip: 0,
+            timer_line: false,
+            next_fire: 0,
+        }
+    }
+}
+
+/// A register selected by a CLINT offset.
+enum Reg {
+    Msip(usize),
+    /// MTIMECMP of a hart; `true` selects the upper word.
+    Mtimecmp(usize, bool),
+    /// MTIME; `true` selects the upper word.
+    Mtime(bool),
+}
+
+/// Returns the upper or lower 32 bits of a 64-bit register.
+fn half(val: u64, high: bool) -> u32 {
+    if high { (val >> 32) as u32 } else { val as u32 }
+}
+
 /// CLINT device structure.
+#[derive(Serialize, Deserialize)]
 pub struct Clint {
     /// Base physical address of the device.
     base_addr: u64,
     /// Current machine time counter.
     mtime: u64,
-    /// Machine time compare register.
-    mtimecmp: u64,
-    /// Machine software interrupt pending register.
-    msip: u32,
+    /// Per-hart compare and software-interrupt registers, indexed by hart ID.
+    harts: Vec<HartTimer>,
     /// Divider to scale CPU cycles to timer ticks.
     divider: u64,
     /// Internal counter for the divider.
     counter: u64,
+    /// Minimum timer ticks between timer-interrupt assertions (0 = no coalescing).
+    min_interval: u64,
 }
 
 impl Clint {
-    /// Creates a new CLINT device.
+    /// Creates a new CLINT device serving a single hart.
     ///
     /// # Arguments
     ///
@@ -46,10 +94,66 @@ impl Clint {
         Self {
             base_addr,
             mtime: 0,
-            mtimecmp: u64::MAX,
-            msip: 0,
+            harts: vec![HartTimer::default()],
             divider: if divider == 0 { 1 } else { divider },
             counter: 0,
+            min_interval: 0,
+        }
+    }
+
+    /// Gives the CLINT one MSIP and MTIMECMP register per hart.
+    ///
+    /// # Arguments
+    ///
+    /// * `harts` - Number of harts; at least one is always present.
+    pub fn with_harts(mut self, harts: usize) -> Self {
+        self.harts.resize(harts.max(1), HartTimer::default());
+        self
+    }
+
+    /// Sets the minimum number of timer ticks between timer interrupts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - Coalescing window in `mtime` ticks; `0` disables coalescing.
+    pub fn set_min_interval(&mut self, ticks: u64) {
+        self.min_interval = ticks;
+    }
+
+    /// Returns the current value of the `mtime` counter.
+    pub fn mtime(&self) -> u64 {
+        self.mtime
+    }
+
+    /// Returns the interrupt lines driven into one hart.
+    ///
+    /// # Returns
+    ///
+    /// `(timer, software)` pending state; both `false` for a hart the CLINT does not serve.
+    pub fn hart_lines(&self, hart: usize) -> (bool, bool) {
+        self.harts
+            .get(hart)
+            .map_or((false, false), |h| (h.timer_line, h.msip & 1 != 0))
+    }
+
+    /// Lets every hart's timer fire again as soon as it expires, after `mtime` is written.
+    fn rearm(&mut self) {
+        for hart in &mut self.harts {
+            hart.next_fire = 0;
+        }
+    }
+
+    /// Decodes a register offset; `None` for reserved offsets and absent harts.
+    fn decode(&self, offset: u64) -> Option<Reg> {
+        let harts = self.harts.len() as u64;
+        if offset == MTIME_OFFSET || offset == MTIME_OFFSET + 4 {
+            Some(Reg::Mtime(offset != MTIME_OFFSET))
+        } else if offset >= MTIMECMP_OFFSET {
+            let rel = offset - MTIMECMP_OFFSET;
+            (rel / 8 < harts).then_some(Reg::Mtimecmp((rel / 8) as usize, rel & 4 != 0))
+        } else {
+            let rel = offset - MSIP_OFFSET;
+            (rel & 3 == 0 && rel / 4 < harts).then_some(Reg::Msip((rel / 4) as usize))
         }
     }
 }
@@ -83,22 +187,20 @@ impl Device for Clint {
     ///
     /// Handles reads to MSIP, and the lower/upper halves of MTIME and MTIMECMP.
     fn read_u32(&mut self, offset: u64) -> u32 {
-        match offset {
-            MSIP_OFFSET => self.msip,
-            MTIMECMP_OFFSET => self.mtimecmp as u32,
-            val if val == MTIMECMP_OFFSET + 4 => (self.mtimecmp >> 32) as u32,
-            MTIME_OFFSET => self.mtime as u32,
-            val if val == MTIME_OFFSET + 4 => (self.mtime >> 32) as u32,
-            _ => 0,
+        match self.decode(offset) {
+            Some(Reg::Msip(hart)) => self.harts[hart].msip,
+            Some(Reg::Mtimecmp(hart, high)) => half(self.harts[hart].mtimecmp, high),
+            Some(Reg::Mtime(high)) => half(self.mtime, high),
+            None => 0,
         }
     }
 
     /// Reads a double-word (64-bit) from the device.
     fn read_u64(&mut self, offset: u64) -> u64 {
-        match offset {
-            MSIP_OFFSET => self.msip as u64,
-            MTIMECMP_OFFSET => self.mtimecmp,
-            MTIME_OFFSET => self.mtime,
+        match self.decode(offset) {
+            Some(Reg::Msip(hart)) => self.harts[hart].msip as u64,
+            Some(Reg::Mtimecmp(hart, false)) => self.harts[hart].mtimecmp,
+            Some(Reg::Mtime(false)) => self.mtime,
             _ => 0,
         }
     }
@@ -112,28 +214,37 @@ impl Device for Clint {
     ///
     /// Handles writes to MSIP, and the lower/upper halves of MTIME and MTIMECMP.
     fn write_u32(&mut self, offset: u64, val: u32) {
-        match offset {
-            MSIP_OFFSET => self.msip = val & 1,
-            MTIMECMP_OFFSET => {
-                self.mtimecmp = (self.mtimecmp & 0xFFFF_FFFF_0000_0000) | (val as u64)
+        match self.decode(offset) {
+            Some(Reg::Msip(hart)) => self.harts[hart].msip = val & 1,
+            Some(Reg::Mtimecmp(hart, high)) => {
+                let cmp = &mut self.harts[hart].mtimecmp;
+                *cmp = if high {
+                    (*cmp & 0x0000_0000_FFFF_FFFF) | ((val as u64) << 32)
+                } else {
+                    (*cmp & 0xFFFF_FFFF_0000_0000) | (val as u64)
+                };
             }
-            o if o == MTIMECMP_OFFSET + 4 => {
-                self.mtimecmp = (self.mtimecmp & 0x0000_0000_FFFF_FFFF) | ((val as u64) << 32)
+            Some(Reg::Mtime(high)) => {
+                self.mtime = if high {
+                    (self.mtime & 0x0000_0000_FFFF_FFFF) | ((val as u64) << 32)
+                } else {
+                    (self.mtime & 0xFFFF_FFFF_0000_0000) | (val as u64)
+                };
+                self.rearm();
             }
-            MTIME_OFFSET => self.mtime = (self.mtime & 0xFFFF_FFFF_0000_0000) | (val as u64),
-            o if o == MTIME_OFFSET + 4 => {
-                self.mtime = (self.mtime & 0x0000_0000_FFFF_FFFF) | ((val as u64) << 32)
-            }
-            _ => {}
+            None => {}
         }
     }
 
     /// Writes a double-word (64-bit) to the device.
     fn write_u64(&mut self, offset: u64, val: u64) {
-        match offset {
-            MSIP_OFFSET => self.msip = (val as u32) & 1,
-            MTIMECMP_OFFSET => self.mtimecmp = val,
-            MTIME_OFFSET => self.mtime = val,
+        match self.decode(offset) {
+            Some(Reg::Msip(hart)) => self.harts[hart].msip = (val as u32) & 1,
+            Some(Reg::Mtimecmp(hart, false)) => self.harts[hart].mtimecmp = val,
+            Some(Reg::Mtime(false)) => {
+                self.mtime = val;
+                self.rearm();
+            }
             _ => {}
         }
     }
@@ -141,7 +252,9 @@ impl Device for Clint {
     /// Advances the device state by one cycle.
     ///
     /// Increments the `mtime` counter based on the configured divider.
-    /// Returns `true` if an interrupt condition is met (timer or software).
+    /// Returns `true` if an interrupt condition is met (timer or software) on any hart.
+    /// With a minimum interval set, a timer expiration within the window of the
+    /// previous assertion is held until the window closes.
     fn tick(&mut self) -> bool {
         self.counter += 1;
         if self.counter >= self.divider {
@@ -149,6 +262,37 @@ impl Device for Clint {
             self.counter = 0;
         }
 
-        self.mtime >= self.mtimecmp || (self.msip & 1) != 0
+        let mut raised = false;
+        for hart in &mut self.harts {
+            if self.mtime < hart.mtimecmp {
+                hart.timer_line = false;
+            } else if !hart.timer_line && self.mtime >= hart.next_fire {
+                hart.timer_line = true;
+                hart.next_fire = self.mtime.saturating_add(self.min_interval);
+            }
+            raised |= hart.timer_line || (hart.msip & 1) != 0;
+        }
+        raised
+    }
+
+    /// Returns a shared reference to the CLINT.
+    fn as_clint(&self) -> Option<&Clint> {
+        Some(self)
+    }
+
+    /// Saves `mtime`, every hart's `mtimecmp` and MSIP, and the tick prescaler.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(self)
+    }
+
+    /// Checks that the state decodes.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        decode_state::<Self>(state).map(|_| ())
+    }
+
+    /// Restores the timer and software-interrupt state.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        *self = decode_state(state)?;
+        Ok(())
     }
 }
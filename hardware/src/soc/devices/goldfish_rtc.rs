@@ -1,34 +1,73 @@
 //! Goldfish Real-Time Clock (RTC).
 //!
 //! A virtual RTC device commonly used in Android emulators (QEMU).
-//! It provides the current system time in nanoseconds.
+//! It provides wall-clock time in nanoseconds since the Unix epoch. The clock
+//! starts from the host's time when the device is created and then advances
+//! with simulated time, so guests see a clock consistent with the cycles they ran.
 //!
 //! # Memory Map
 //!
-//! * `0x00`: Time (Low 32 bits)
-//! * `0x04`: Time (High 32 bits)
+//! * `0x00`: Time (Low 32 bits); reading it latches the high word
+//! * `0x04`: Time (High 32 bits), as latched by the last low-word read
+//!
+//! Writing the high word and then the low word sets the time.
 
 use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Time register, low 32 bits.
+const REG_TIME_LOW: u64 = 0x00;
+
+/// Time register, high 32 bits.
+const REG_TIME_HIGH: u64 = 0x04;
+
 /// Goldfish RTC device structure.
+#[derive(Serialize, Deserialize)]
 pub struct GoldfishRtc {
     /// Base physical address of the device.
     base_addr: u64,
+    /// Current time in nanoseconds since the Unix epoch.
+    time_ns: u64,
+    /// Nanoseconds added on every tick.
+    ns_per_tick: u64,
+    /// High word captured by the last TIME_LOW read.
+    latched_high: u32,
+    /// High word written ahead of a TIME_LOW write.
+    pending_high: u32,
 }
 
 impl GoldfishRtc {
-    /// Creates a new Goldfish RTC device.
+    /// Creates a new Goldfish RTC device seeded from the host clock.
+    ///
+    /// The clock advances one nanosecond per tick; see
+    /// [`with_ns_per_tick`](Self::with_ns_per_tick).
+    ///
+    /// # Arguments
+    ///
+    /// * `base_addr` - The base physical address of the RTC.
     pub fn new(base_addr: u64) -> Self {
-        Self { base_addr }
+        let time_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self {
+            base_addr,
+            time_ns,
+            ns_per_tick: 1,
+            latched_high: 0,
+            pending_high: 0,
+        }
     }
 
-    /// Retrieves the current system time in nanoseconds.
-    fn get_time_ns(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64
+    /// Sets how many nanoseconds the clock advances per tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `ns` - Nanoseconds per simulated cycle.
+    pub fn with_ns_per_tick(mut self, ns: u64) -> Self {
+        self.ns_per_tick = ns;
+        self
     }
 }
 
@@ -54,12 +93,15 @@ impl Device for GoldfishRtc {
 
     /// Reads a word (32-bit) from the device.
     ///
-    /// Returns the lower or upper 32 bits of the current nanosecond timestamp.
+    /// Reading the low word latches the high word, so a low-then-high read
+    /// pair forms one consistent timestamp even if the clock ticks in between.
     fn read_u32(&mut self, offset: u64) -> u32 {
-        let time = self.get_time_ns();
         match offset {
-            0x00 => time as u32,
-            0x04 => (time >> 32) as u32,
+            REG_TIME_LOW => {
+                self.latched_high = (self.time_ns >> 32) as u32;
+                self.time_ns as u32
+            }
+            REG_TIME_HIGH => self.latched_high,
             _ => 0,
         }
     }
@@ -68,9 +110,8 @@ impl Device for GoldfishRtc {
     ///
     /// Returns the full 64-bit nanosecond timestamp.
     fn read_u64(&mut self, offset: u64) -> u64 {
-        let time = self.get_time_ns();
         match offset {
-            0x00 => time,
+            REG_TIME_LOW => self.time_ns,
             _ => 0,
         }
     }
@@ -79,13 +120,49 @@ impl Device for GoldfishRtc {
     fn write_u8(&mut self, _offset: u64, _val: u8) {}
     /// Writes a half-word (unimplemented).
     fn write_u16(&mut self, _offset: u64, _val: u16) {}
-    /// Writes a word (unimplemented).
-    fn write_u32(&mut self, _offset: u64, _val: u32) {}
-    /// Writes a double-word (unimplemented).
-    fn write_u64(&mut self, _offset: u64, _val: u64) {}
+
+    /// Writes a word (32-bit) to the device.
+    ///
+    /// The high word is held until the low word is written, which sets the time.
+    fn write_u32(&mut self, offset: u64, val: u32) {
+        match offset {
+            REG_TIME_HIGH => self.pending_high = val,
+            REG_TIME_LOW => self.time_ns = ((self.pending_high as u64) << 32) | val as u64,
+            _ => {}
+        }
+    }
+
+    /// Writes a double-word (64-bit) to the device, setting the time.
+    fn write_u64(&mut self, offset: u64, val: u64) {
+        if offset == REG_TIME_LOW {
+            self.time_ns = val;
+        }
+    }
+
+    /// Advances the clock by one tick.
+    fn tick(&mut self) -> bool {
+        self.time_ns = self.time_ns.wrapping_add(self.ns_per_tick);
+        false
+    }
 
     /// Returns the Interrupt Request (IRQ) ID associated with this device.
     fn get_irq_id(&self) -> Option<u32> {
         Some(11)
     }
+
+    /// Saves the clock and the latched high word.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(self)
+    }
+
+    /// Checks that the state decodes.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        decode_state::<Self>(state).map(|_| ())
+    }
+
+    /// Restores the clock and the latched high word.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        *self = decode_state(state)?;
+        Ok(())
+    }
 }
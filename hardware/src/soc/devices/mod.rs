@@ -2,7 +2,7 @@
 //!
 //! This module contains implementations of various hardware devices
 //! found in the SoC, such as timers (CLINT), interrupt controllers (PLIC),
-//! serial ports (UART), and block devices (VirtIO).
+//! serial ports (UART), block devices (VirtIO), and nonvolatile memory (NVRAM).
 
 /// Core Local Interruptor (timer and software interrupt controller).
 pub mod clint;
@@ -10,6 +10,9 @@ pub mod clint;
 /// Goldfish RTC (Real-Time Clock) device.
 pub mod goldfish_rtc;
 
+/// Nonvolatile RAM persisted to a host file.
+pub mod nvram;
+
 /// Platform-Level Interrupt Controller (PLIC).
 pub mod plic;
 
@@ -24,6 +27,7 @@ pub mod virtio_disk;
 
 pub use clint::Clint;
 pub use goldfish_rtc::GoldfishRtc;
+pub use nvram::Nvram;
 pub use plic::Plic;
 pub use syscon::SysCon;
 pub use uart::Uart;
@@ -0,0 +1,217 @@
+//! Nonvolatile RAM (NVRAM / EEPROM).
+//!
+//! A small byte-addressable memory whose contents survive across runs. It
+//! performs the following:
+//! 1. **Load:** Contents are read from a host file at construction (missing files start zeroed).
+//! 2. **Access:** The array is mapped directly on the bus; any width is a plain little-endian access.
+//! 3. **Writeback:** When enabled, modified contents are written back to the host file on shutdown.
+//!
+//! Unlike the VirtIO disk this device is not block-oriented: guests read and write
+//! it with ordinary loads and stores, as they would a configuration EEPROM.
+
+use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// NVRAM device structure.
+pub struct Nvram {
+    /// Base physical address of the device.
+    base_addr: u64,
+    /// Device contents; the length is the size of the mapped region.
+    data: Vec<u8>,
+    /// Host file backing the contents, if any.
+    backing: Option<PathBuf>,
+    /// Whether modified contents are written back to `backing` on shutdown.
+    writeback: bool,
+    /// Set by any guest write since the last persist.
+    dirty: bool,
+}
+
+impl Nvram {
+    /// Creates a zero-filled NVRAM with no backing file.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_addr` - The base physical address.
+    /// * `size` - Size of the device in bytes.
+    pub fn new(base_addr: u64, size: usize) -> Self {
+        Self {
+            base_addr,
+            data: vec![0; size],
+            backing: None,
+            writeback: false,
+            dirty: false,
+        }
+    }
+
+    /// Creates an NVRAM backed by a host file.
+    ///
+    /// The file's contents are copied in, truncated or zero-padded to `size`.
+    /// A missing file is treated as empty, so the first writeback creates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_addr` - The base physical address.
+    /// * `size` - Size of the device in bytes.
+    /// * `path` - Host file holding the persistent contents.
+    /// * `writeback` - Whether to write modified contents back on shutdown.
+    ///
+    /// # Returns
+    ///
+    /// The device, or the I/O error from reading an existing file.
+    pub fn from_file(
+        base_addr: u64,
+        size: usize,
+        path: impl Into<PathBuf>,
+        writeback: bool,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        let mut dev = Self::new(base_addr, size);
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let n = bytes.len().min(size);
+                dev.data[..n].copy_from_slice(&bytes[..n]);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        dev.backing = Some(path);
+        dev.writeback = writeback;
+        Ok(dev)
+    }
+
+    /// Returns the current device contents.
+    pub fn contents(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Writes the contents to the backing file if they changed since the last persist.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if nothing needed writing or the write succeeded.
+    pub fn persist(&mut self) -> io::Result<()> {
+        let Some(path) = &self.backing else {
+            return Ok(());
+        };
+        if self.dirty {
+            fs::write(path, &self.data)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Reads `N` bytes at `offset`; bytes past the end read as zero.
+    fn read_bytes<const N: usize>(&self, offset: u64) -> [u8; N] {
+        let mut buf = [0u8; N];
+        for (i, b) in buf.iter_mut().enumerate() {
+            if let Some(v) = self.data.get(offset as usize + i) {
+                *b = *v;
+            }
+        }
+        buf
+    }
+
+    /// Writes `bytes` at `offset`; bytes past the end are dropped.
+    fn store_bytes(&mut self, offset: u64, bytes: &[u8]) {
+        for (i, b) in bytes.iter().enumerate() {
+            if let Some(slot) = self.data.get_mut(offset as usize + i) {
+                *slot = *b;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Decodes checkpointed contents, which must match the array size.
+    fn decode_array(&self, state: &[u8]) -> Result<Vec<u8>, String> {
+        let data: Vec<u8> = decode_state(state)?;
+        if data.len() != self.data.len() {
+            return Err(format!(
+                "NVRAM size mismatch: checkpoint has {:#x} bytes, device has {:#x}",
+                data.len(),
+                self.data.len()
+            ));
+        }
+        Ok(data)
+    }
+}
+
+impl Device for Nvram {
+    /// Returns the device name.
+    fn name(&self) -> &str {
+        "NVRAM"
+    }
+
+    /// Returns the address range (Base, Size).
+    fn address_range(&self) -> (u64, u64) {
+        (self.base_addr, self.data.len() as u64)
+    }
+
+    /// Reads a byte.
+    fn read_u8(&mut self, offset: u64) -> u8 {
+        self.read_bytes::<1>(offset)[0]
+    }
+    /// Reads a half-word (Little Endian).
+    fn read_u16(&mut self, offset: u64) -> u16 {
+        u16::from_le_bytes(self.read_bytes(offset))
+    }
+    /// Reads a word (Little Endian).
+    fn read_u32(&mut self, offset: u64) -> u32 {
+        u32::from_le_bytes(self.read_bytes(offset))
+    }
+    /// Reads a double-word (Little Endian).
+    fn read_u64(&mut self, offset: u64) -> u64 {
+        u64::from_le_bytes(self.read_bytes(offset))
+    }
+
+    /// Writes a byte.
+    fn write_u8(&mut self, offset: u64, val: u8) {
+        self.store_bytes(offset, &[val]);
+    }
+    /// Writes a half-word (Little Endian).
+    fn write_u16(&mut self, offset: u64, val: u16) {
+        self.store_bytes(offset, &val.to_le_bytes());
+    }
+    /// Writes a word (Little Endian).
+    fn write_u32(&mut self, offset: u64, val: u32) {
+        self.store_bytes(offset, &val.to_le_bytes());
+    }
+    /// Writes a double-word (Little Endian).
+    fn write_u64(&mut self, offset: u64, val: u64) {
+        self.store_bytes(offset, &val.to_le_bytes());
+    }
+
+    /// Writes a slice of bytes (used for image loading).
+    fn write_bytes(&mut self, offset: u64, data: &[u8]) {
+        self.store_bytes(offset, data);
+    }
+
+    /// Persists modified contents when writeback is enabled.
+    fn shutdown(&mut self) {
+        if !self.writeback {
+            return;
+        }
+        if let Err(e) = self.persist() {
+            eprintln!("[NVRAM] Writeback failed: {}", e);
+        }
+    }
+
+    /// Saves the array contents.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(&self.data)
+    }
+
+    /// Checks that the state decodes to an array of this device's size.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        self.decode_array(state).map(|_| ())
+    }
+
+    /// Restores the array contents; they are written back on shutdown like guest stores.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        self.data = self.decode_array(state)?;
+        self.dirty = true;
+        Ok(())
+    }
+}
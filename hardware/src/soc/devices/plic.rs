@@ -1,7 +1,10 @@
 //! Platform-Level Interrupt Controller (PLIC).
 //!
 //! The PLIC arbitrates global external interrupts and distributes them to
-//! interrupt targets (HART contexts). It complies with the RISC-V PLIC specification.
+//! interrupt targets (HART contexts). It complies with the RISC-V PLIC specification:
+//! a context is notified when an enabled source is pending with a priority above
+//! its threshold, reading its claim register returns and takes the highest-priority
+//! pending source, and writing that ID back completes it so the source can fire again.
 //!
 //! # Memory Map
 //!
@@ -11,6 +14,8 @@
 //! * `0x200000`: Priority Thresholds and Claim/Complete Registers
 
 use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 
 /// Base offset for PLIC priority registers (one per interrupt source).
 const PLIC_PRIORITY_BASE: u64 = 0x000000;
@@ -31,19 +36,21 @@ const NUM_CONTEXTS: usize = 2;
 const ENABLE_WORDS_PER_CONTEXT: usize = 32;
 
 /// PLIC device structure.
+#[derive(Serialize, Deserialize)]
 pub struct Plic {
     /// Base physical address of the device.
     base_addr: u64,
-    /// Interrupt source priorities (1-1023).
+    /// Interrupt source priorities (1-1023; 0 disables the source).
     priorities: Vec<u32>,
     /// Pending interrupt bits (bitmap).
     pending: Vec<u32>,
+    /// Sources claimed and not yet completed (bitmap); their gateways hold
+    /// further requests until completion.
+    in_service: Vec<u32>,
     /// Interrupt enable bits per context: enables[ctx][word].
     enables: Vec<Vec<u32>>,
     /// Priority thresholds per context.
     thresholds: Vec<u32>,
-    /// Claim/Complete registers per context.
-    claims: Vec<u32>,
 }
 
 impl Plic {
@@ -53,20 +60,23 @@ impl Plic {
             base_addr,
             priorities: vec![0; 1024],
             pending: vec![0; 32],
+            in_service: vec![0; 32],
             enables: vec![vec![0u32; ENABLE_WORDS_PER_CONTEXT]; NUM_CONTEXTS],
             thresholds: vec![0; NUM_CONTEXTS],
-            claims: vec![0; NUM_CONTEXTS],
         }
     }
 
     /// Updates the pending status of interrupts based on external signals.
     ///
+    /// Lines are level-triggered: a source is pending while its line is
+    /// asserted, except between its claim and completion.
+    ///
     /// # Arguments
     ///
     /// * `mask` - A 64-bit mask where set bits indicate active interrupt lines.
     pub fn update_irqs(&mut self, mask: u64) {
-        self.pending[0] = (mask & 0xFFFFFFFF) as u32;
-        self.pending[1] = (mask >> 32) as u32;
+        self.pending[0] = (mask & 0xFFFFFFFF) as u32 & !self.in_service[0];
+        self.pending[1] = (mask >> 32) as u32 & !self.in_service[1];
     }
 
     /// Checks for pending interrupts that exceed the priority threshold.
@@ -76,57 +86,20 @@ impl Plic {
     /// A tuple `(meip, seip)` indicating if a Machine External Interrupt
     /// or Supervisor External Interrupt is pending.
     pub fn check_interrupts(&mut self) -> (bool, bool) {
-        let mut meip = false;
-        let mut seip = false;
-
-        if self.has_qualified_irq(0) {
-            meip = true;
-            self.claims[0] = self.calc_max_id(0);
-        } else {
-            self.claims[0] = 0;
-        }
-
-        if self.has_qualified_irq(1) {
-            seip = true;
-            self.claims[1] = self.calc_max_id(1);
-        } else {
-            self.claims[1] = 0;
-        }
-
-        (meip, seip)
-    }
-
-    /// Determines if a context has any pending interrupt above its threshold.
-    fn has_qualified_irq(&self, ctx: usize) -> bool {
-        let threshold = self.thresholds[ctx];
-        let num_words = std::cmp::min(self.pending.len(), self.enables[ctx].len());
-
-        for word in 0..num_words {
-            let active = self.pending[word] & self.enables[ctx][word];
-            if active == 0 {
-                continue;
-            }
-            for bit in 0..32 {
-                let irq_id = word * 32 + bit;
-                if irq_id == 0 {
-                    continue; // IRQ 0 is reserved
-                }
-                if (active & (1 << bit)) != 0 && irq_id < self.priorities.len() {
-                    if self.priorities[irq_id] > threshold {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        (
+            self.max_pending(0, self.thresholds[0]) != 0,
+            self.max_pending(1, self.thresholds[1]) != 0,
+        )
     }
 
-    /// Calculates the ID of the highest priority pending interrupt for a context.
-    fn calc_max_id(&self, ctx: usize) -> u32 {
-        let threshold = self.thresholds[ctx];
+    /// Returns the pending, enabled source of highest priority above `floor`
+    /// for a context, or 0 if there is none.
+    ///
+    /// Ties go to the lowest source ID.
+    fn max_pending(&self, ctx: usize, floor: u32) -> u32 {
         let num_words = std::cmp::min(self.pending.len(), self.enables[ctx].len());
 
-        let mut max_prio = 0;
+        let mut max_prio = floor;
         let mut max_id = 0;
 
         for word in 0..num_words {
@@ -137,11 +110,11 @@ impl Plic {
             for bit in 0..32 {
                 let irq_id = word * 32 + bit;
                 if irq_id == 0 {
-                    continue;
+                    continue; // IRQ 0 is reserved
                 }
                 if (active & (1 << bit)) != 0 && irq_id < self.priorities.len() {
                     let prio = self.priorities[irq_id];
-                    if prio > max_prio && prio > threshold {
+                    if prio > max_prio {
                         max_prio = prio;
                         max_id = irq_id as u32;
                     }
@@ -150,6 +123,30 @@ impl Plic {
         }
         max_id
     }
+
+    /// Claims the highest-priority pending interrupt for a context.
+    ///
+    /// The claim is not affected by the threshold. The source stops being
+    /// pending and stays in service until completed.
+    fn claim(&mut self, ctx: usize) -> u32 {
+        let id = self.max_pending(ctx, 0);
+        if id > 0 {
+            let bit = 1 << (id % 32);
+            self.pending[id as usize / 32] &= !bit;
+            self.in_service[id as usize / 32] |= bit;
+        }
+        id
+    }
+
+    /// Completes the interrupt `id` for a context, re-arming its gateway.
+    ///
+    /// Completions for sources not enabled in the context are ignored.
+    fn complete(&mut self, ctx: usize, id: u32) {
+        let (word, bit) = (id as usize / 32, 1 << (id % 32));
+        if id > 0 && word < self.in_service.len() && (self.enables[ctx][word] & bit) != 0 {
+            self.in_service[word] &= !bit;
+        }
+    }
 }
 
 impl Device for Plic {
@@ -187,18 +184,12 @@ impl Device for Plic {
         } else if offset >= PLIC_CONTEXT_BASE {
             let ctx = (offset - PLIC_CONTEXT_BASE) as usize / 0x1000;
             let reg = offset & 0xFFF;
-            if ctx < 2 {
+            if ctx < NUM_CONTEXTS {
                 if reg == 0 {
                     return self.thresholds[ctx];
                 }
                 if reg == 4 {
-                    let claim = self.claims[ctx];
-                    if claim > 0 {
-                        let idx = claim as usize / 32;
-                        let bit = 1 << (claim % 32);
-                        self.pending[idx] &= !bit;
-                    }
-                    return claim;
+                    return self.claim(ctx);
                 }
             }
         }
@@ -225,12 +216,12 @@ impl Device for Plic {
         } else if offset >= PLIC_CONTEXT_BASE {
             let ctx = (offset - PLIC_CONTEXT_BASE) as usize / 0x1000;
             let reg = offset & 0xFFF;
-            if ctx < 2 {
+            if ctx < NUM_CONTEXTS {
                 if reg == 0 {
                     self.thresholds[ctx] = val;
                 }
                 if reg == 4 {
-                    self.claims[ctx] = 0;
+                    self.complete(ctx, val);
                 }
             }
         }
@@ -274,4 +265,20 @@ impl Device for Plic {
     fn as_plic_mut(&mut self) -> Option<&mut Plic> {
         Some(self)
     }
+
+    /// Saves priorities, pending and claimed sources, enables, and thresholds.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(self)
+    }
+
+    /// Checks that the state decodes.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        decode_state::<Self>(state).map(|_| ())
+    }
+
+    /// Restores the interrupt routing and pending state.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        *self = decode_state(state)?;
+        Ok(())
+    }
 }
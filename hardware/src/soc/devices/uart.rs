@@ -1,14 +1,20 @@
 //! Universal Asynchronous Receiver-Transmitter (UART).
 //!
 //! Implements a 16550-compatible UART device for serial communication.
-//! Handles standard registers (RBR, THR, IER, IIR, LCR, LSR) and integrates
-//! with stdin/stdout for console I/O.
+//! Handles the full NS16550 register map (RBR/THR, IER, IIR/FCR, LCR, MCR,
+//! LSR, MSR, SCR, and the DLL/DLM divisor latch behind LCR.DLAB), the receive
+//! FIFO with its FCR trigger level, MCR loopback, and integrates
+//! with stdin/stdout for console I/O. Input comes from a single process-wide
+//! stdin reader thread, started the first time a UART polls for input, so
+//! every byte typed reaches exactly one receive FIFO.
 
 use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::io::{self, Read, Write};
-use std::sync::Mutex;
 use std::sync::mpsc::{Receiver, channel};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
 
 /// Receiver Buffer Register (Read) / Divisor Latch Low (DLAB=1).
@@ -41,8 +47,26 @@ const IIR_THRE: u8 = 0x02;
 /// Interrupt Identity Register: Receiver Data Available interrupt.
 const IIR_RDA: u8 = 0x04;
 
-/// Interrupt Identity Register: Interrupt ID mask (bits 7:6).
-const IIR_ID_MASK: u8 = 0xC0;
+/// Interrupt Identity Register: Character Timeout interrupt (FIFO mode).
+const IIR_RX_TIMEOUT: u8 = 0x0C;
+
+/// Interrupt Identity Register: FIFOs enabled (bits 7:6).
+const IIR_FIFOS_ENABLED: u8 = 0xC0;
+
+/// FIFO Control Register: Enable the transmit and receive FIFOs.
+const FCR_FIFO_ENABLE: u8 = 0x01;
+
+/// FIFO Control Register: Clear the receive FIFO.
+const FCR_CLEAR_RX: u8 = 0x02;
+
+/// Receive FIFO depth in FIFO mode (one byte in 16450 mode).
+const RX_FIFO_DEPTH: usize = 16;
+
+/// Receive FIFO trigger levels selected by FCR bits 7:6.
+const RX_TRIGGER_LEVELS: [usize; 4] = [1, 4, 8, 14];
+
+/// Ticks without receive activity before a character-timeout interrupt.
+const RX_TIMEOUT_TICKS: u32 = 1024;
 
 /// Line Status Register: Data ready bit (receiver has data).
 const LSR_DATA_READY: u8 = 0x01;
@@ -65,20 +89,82 @@ const IER_RDA: u8 = 0x01;
 /// Interrupt Enable Register: Transmitter Holding Register Empty interrupt enable.
 const IER_THRE: u8 = 0x02;
 
+/// Interrupt Enable Register: Implemented bits (RDA, THRE, line status, modem status).
+const IER_MASK: u8 = 0x0F;
+
+/// Modem Control Register: Data Terminal Ready.
+const MCR_DTR: u8 = 0x01;
+
+/// Modem Control Register: Request To Send.
+const MCR_RTS: u8 = 0x02;
+
+/// Modem Control Register: Auxiliary output 1.
+const MCR_OUT1: u8 = 0x04;
+
+/// Modem Control Register: Auxiliary output 2.
+const MCR_OUT2: u8 = 0x08;
+
+/// Modem Control Register: Loopback mode (THR feeds the receiver).
+const MCR_LOOP: u8 = 0x10;
+
+/// Modem Status Register: Clear To Send.
+const MSR_CTS: u8 = 0x10;
+
+/// Modem Status Register: Data Set Ready.
+const MSR_DSR: u8 = 0x20;
+
+/// Modem Status Register: Ring Indicator.
+const MSR_RI: u8 = 0x40;
+
+/// Modem Status Register: Data Carrier Detect.
+const MSR_DCD: u8 = 0x80;
+
 /// Threshold for flushing transmit buffer to stdout (4 KiB).
 const TX_BUFFER_FLUSH_THRESHOLD: usize = 4096;
 
+/// Bytes read from stdin by the background reader thread.
+static STDIN_RX: OnceLock<Mutex<Receiver<u8>>> = OnceLock::new();
+
+/// Returns the stdin byte stream, spawning the reader thread on first use.
+///
+/// The thread blocks on stdin so the simulation never does; UARTs drain the
+/// channel without waiting. The thread holds the stdin lock for good, so any
+/// other reader of the host console (such as semihosting) must use this channel.
+pub(crate) fn stdin_receiver() -> &'static Mutex<Receiver<u8>> {
+    STDIN_RX.get_or_init(|| {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 1];
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            while handle.read_exact(&mut buffer).is_ok() {
+                if tx.send(buffer[0]).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(rx)
+    })
+}
+
 /// UART device structure.
 ///
-/// Simulates a 16550 UART. It spawns a background thread to capture `stdin`
-/// for input and writes output directly to `stdout`.
+/// Simulates a 16550 UART. It polls the shared `stdin` reader for input and
+/// writes output directly to `stdout`.
+#[derive(Serialize, Deserialize)]
 pub struct Uart {
     /// Base physical address of the device.
     base_addr: u64,
-    /// Queue for received bytes (from stdin).
+    /// Receive FIFO as seen by the guest.
     rx_queue: VecDeque<u8>,
-    /// Channel receiver for stdin thread. Wrapped in Mutex for Sync.
-    rx_receiver: Mutex<Receiver<u8>>,
+    /// Bytes arrived from the host and not yet in the receive FIFO.
+    rx_pending: VecDeque<u8>,
+    /// FIFOs enabled (FCR bit 0).
+    fifo_enabled: bool,
+    /// Receive FIFO level that raises the data-available interrupt.
+    rx_trigger: usize,
+    /// Ticks since the receive FIFO last changed.
+    rx_idle_ticks: u32,
     /// Interrupt Enable Register.
     ier: u8,
     /// Line Control Register.
@@ -106,28 +192,18 @@ pub struct Uart {
 impl Uart {
     /// Creates a new UART device.
     ///
-    /// Spawns a background thread to read from stdin.
-    ///
     /// # Arguments
     ///
     /// * `base_addr` - The base physical address of the UART device.
     /// * `to_stderr` - When true, write output to stderr instead of stdout (for Python API).
     pub fn new(base_addr: u64, to_stderr: bool) -> Self {
-        let (tx, rx) = channel();
-
-        thread::spawn(move || {
-            let mut buffer = [0u8; 1];
-            let stdin = io::stdin();
-            let mut handle = stdin.lock();
-            while handle.read_exact(&mut buffer).is_ok() {
-                let _ = tx.send(buffer[0]);
-            }
-        });
-
         Self {
             base_addr,
             rx_queue: VecDeque::new(),
-            rx_receiver: Mutex::new(rx),
+            rx_pending: VecDeque::new(),
+            fifo_enabled: false,
+            rx_trigger: 1,
+            rx_idle_ticks: 0,
             ier: 0,
             lcr: 0,
             mcr: 0,
@@ -142,21 +218,59 @@ impl Uart {
         }
     }
 
-    /// Polls the stdin receiver and populates the RX queue.
+    /// Queues a received byte as if it arrived on the serial line.
+    ///
+    /// Lets embedders and tests feed input without going through stdin.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The byte to append to the receive FIFO.
+    pub fn push_rx(&mut self, byte: u8) {
+        self.rx_pending.push_back(byte);
+        self.fill_rx_fifo();
+    }
+
+    /// Polls the stdin receiver and queues any new input.
     fn check_stdin(&mut self) {
-        if let Ok(rx) = self.rx_receiver.lock() {
+        if let Ok(rx) = stdin_receiver().lock() {
             while let Ok(byte) = rx.try_recv() {
-                self.rx_queue.push_back(byte);
+                self.rx_pending.push_back(byte);
+            }
+        }
+    }
+
+    /// Returns the receive FIFO depth for the current FIFO mode.
+    fn rx_depth(&self) -> usize {
+        if self.fifo_enabled { RX_FIFO_DEPTH } else { 1 }
+    }
+
+    /// Moves pending input into the receive FIFO while it has room.
+    ///
+    /// The host side is flow-controlled, so input waits instead of overrunning.
+    fn fill_rx_fifo(&mut self) {
+        while self.rx_queue.len() < self.rx_depth() {
+            match self.rx_pending.pop_front() {
+                Some(byte) => {
+                    self.rx_queue.push_back(byte);
+                    self.rx_idle_ticks = 0;
+                }
+                None => break,
             }
         }
     }
 
     /// Calculates the Interrupt Identity Register (IIR) value.
     ///
-    /// Determines the highest priority pending interrupt.
+    /// Determines the highest priority pending interrupt: received data
+    /// (at the trigger level, or a character timeout below it), then THR empty.
     fn update_interrupts(&mut self) -> u8 {
         if (self.ier & IER_RDA) != 0 && !self.rx_queue.is_empty() {
-            return IIR_RDA;
+            if self.rx_queue.len() >= self.rx_trigger {
+                return IIR_RDA;
+            }
+            if self.rx_idle_ticks >= RX_TIMEOUT_TICKS {
+                return IIR_RX_TIMEOUT;
+            }
         }
 
         if (self.ier & IER_THRE) != 0 && self.thre_ip {
@@ -227,7 +341,10 @@ impl Uart {
         if self.dlab_set() {
             (self.div & 0xFF) as u8
         } else {
-            self.rx_queue.pop_front().unwrap_or(0)
+            let byte = self.rx_queue.pop_front().unwrap_or(0);
+            self.rx_idle_ticks = 0;
+            self.fill_rx_fifo();
+            byte
         }
     }
 
@@ -250,7 +367,35 @@ impl Uart {
         if iir == IIR_THRE {
             self.thre_ip = false;
         }
-        IIR_ID_MASK | iir
+        if self.fifo_enabled {
+            IIR_FIFOS_ENABLED | iir
+        } else {
+            iir
+        }
+    }
+
+    /// Reads Modem Status Register (MSR).
+    ///
+    /// In loopback mode the modem inputs mirror the MCR outputs; otherwise no
+    /// modem is attached and every input reads as inactive.
+    fn read_msr(&self) -> u8 {
+        if (self.mcr & MCR_LOOP) == 0 {
+            return 0;
+        }
+        let mut msr = 0;
+        if (self.mcr & MCR_RTS) != 0 {
+            msr |= MSR_CTS;
+        }
+        if (self.mcr & MCR_DTR) != 0 {
+            msr |= MSR_DSR;
+        }
+        if (self.mcr & MCR_OUT1) != 0 {
+            msr |= MSR_RI;
+        }
+        if (self.mcr & MCR_OUT2) != 0 {
+            msr |= MSR_DCD;
+        }
+        msr
     }
 
     /// Reads Line Status Register (LSR).
@@ -270,6 +415,9 @@ impl Uart {
     fn write_thr_or_dll(&mut self, val: u8) {
         if self.dlab_set() {
             self.div = (self.div & 0xFF00) | (val as u16);
+        } else if (self.mcr & MCR_LOOP) != 0 {
+            self.push_rx(val);
+            self.thre_ip = true;
         } else {
             if self.check_char_for_panic(val) {
                 self.flush_buffer();
@@ -286,6 +434,25 @@ impl Uart {
         }
     }
 
+    /// Writes FIFO Control Register (FCR).
+    ///
+    /// Enables or disables the FIFOs, clears the receive FIFO on request, and
+    /// selects the receive trigger level. Disabling the FIFOs also clears them.
+    fn write_fcr(&mut self, val: u8) {
+        let enable = (val & FCR_FIFO_ENABLE) != 0;
+        if enable != self.fifo_enabled || (val & FCR_CLEAR_RX) != 0 {
+            self.rx_queue.clear();
+        }
+        self.fifo_enabled = enable;
+        self.rx_trigger = if enable {
+            RX_TRIGGER_LEVELS[(val >> 6) as usize]
+        } else {
+            1
+        };
+        self.rx_idle_ticks = 0;
+        self.fill_rx_fifo();
+    }
+
     /// Writes Interrupt Enable Register (IER) or Divisor Latch High (DLM).
     ///
     /// The register accessed depends on the DLAB bit in the LCR.
@@ -293,7 +460,7 @@ impl Uart {
         if self.dlab_set() {
             self.div = (self.div & 0x00FF) | ((val as u16) << 8);
         } else {
-            self.ier = val;
+            self.ier = val & IER_MASK;
             if (self.ier & IER_THRE) != 0 {
                 self.thre_ip = true;
             }
@@ -327,7 +494,7 @@ impl Device for Uart {
             REG_LCR => self.lcr,
             REG_MCR => self.mcr,
             REG_LSR => self.read_lsr(),
-            REG_MSR => 0,
+            REG_MSR => self.read_msr(),
             REG_SCR => self.scr,
             _ => 0,
         }
@@ -351,7 +518,7 @@ impl Device for Uart {
         match offset {
             REG_THR => self.write_thr_or_dll(val),
             REG_IER => self.write_ier_or_dlm(val),
-            REG_FCR => {}
+            REG_FCR => self.write_fcr(val),
             REG_LCR => self.lcr = val,
             REG_MCR => self.mcr = val,
             REG_SCR => self.scr = val,
@@ -380,6 +547,10 @@ impl Device for Uart {
         if self.tick_count == 0 {
             self.check_stdin();
         }
+        self.fill_rx_fifo();
+        if !self.rx_queue.is_empty() {
+            self.rx_idle_ticks = self.rx_idle_ticks.saturating_add(1);
+        }
 
         let iir = self.update_interrupts();
         (iir & IIR_NO_INTERRUPT) == 0
@@ -394,4 +565,20 @@ impl Device for Uart {
     fn as_uart_mut(&mut self) -> Option<&mut Uart> {
         Some(self)
     }
+
+    /// Saves the register state and both FIFOs.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(self)
+    }
+
+    /// Checks that the state decodes.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        decode_state::<Self>(state).map(|_| ())
+    }
+
+    /// Restores the registers and any buffered input or output.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        *self = decode_state(state)?;
+        Ok(())
+    }
 }
@@ -1,10 +1,16 @@
 //! VirtIO Block Device (MMIO).
 //!
 //! Implements a VirtIO block device over Memory-Mapped I/O (MMIO) for disk access.
-//! Supports the legacy VirtIO interface required by the Linux kernel.
+//! Presents the version 2 (modern) MMIO transport with a single request queue.
+//! Each notify walks the available ring, services the descriptor chain of every
+//! new request (read, write, flush, or get-ID) against the disk image, writes the
+//! request status byte, publishes the chain on the used ring, and raises the
+//! used-buffer interrupt. Writing 0 to the status register resets the device.
 
 use crate::soc::devices::Device;
 use crate::soc::memory::buffer::DramBuffer;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::sync::Arc;
 
@@ -116,6 +122,36 @@ const VRING_DESC_F_WRITE: u16 = 2;
 /// Disk sector size in bytes (512 bytes per sector).
 const SECTOR_SIZE: u64 = 512;
 
+/// Block request type: read sectors into the guest.
+const VIRTIO_BLK_T_IN: u32 = 0;
+
+/// Block request type: write sectors from the guest.
+const VIRTIO_BLK_T_OUT: u32 = 1;
+
+/// Block request type: flush the write cache.
+const VIRTIO_BLK_T_FLUSH: u32 = 4;
+
+/// Block request type: read the device ID string.
+const VIRTIO_BLK_T_GET_ID: u32 = 8;
+
+/// Request status: success.
+const VIRTIO_BLK_S_OK: u8 = 0;
+
+/// Request status: I/O error (e.g. sectors past the end of the disk).
+const VIRTIO_BLK_S_IOERR: u8 = 1;
+
+/// Request status: unsupported request type.
+const VIRTIO_BLK_S_UNSUPP: u8 = 2;
+
+/// Device feature (bits 32-63): VIRTIO_F_VERSION_1, the modern interface.
+const VIRTIO_F_VERSION_1_HIGH: u32 = 1;
+
+/// Device ID string returned for `VIRTIO_BLK_T_GET_ID`.
+const DEVICE_ID_STRING: &[u8] = b"riscv-sim-disk";
+
+/// Interrupt status: the used ring was updated.
+const INTERRUPT_USED_BUFFER: u32 = 1;
+
 /// VirtIO Block device structure.
 ///
 /// Implements a memory-mapped block device compliant with the VirtIO specification.
@@ -139,6 +175,8 @@ pub struct VirtioBlock {
     queue_ready: u32,
     /// Queue notify register (triggers processing).
     queue_notify: u32,
+    /// Selected queue; only queue 0 exists.
+    queue_sel: u32,
 
     /// Queue Descriptor Table address (Low 32 bits).
     queue_desc_low: u32,
@@ -167,6 +205,23 @@ pub struct VirtioBlock {
 unsafe impl Send for VirtioBlock {}
 unsafe impl Sync for VirtioBlock {}
 
+/// Checkpointed state of a [`VirtioBlock`]: the transport registers and the disk contents.
+#[derive(Serialize, Deserialize)]
+struct VirtioState {
+    disk_image: Vec<u8>,
+    status: u32,
+    queue_num: u32,
+    queue_ready: u32,
+    queue_notify: u32,
+    queue_sel: u32,
+    queue_desc: (u32, u32),
+    queue_avail: (u32, u32),
+    queue_used: (u32, u32),
+    interrupt_status: u32,
+    last_avail_idx: u16,
+    features_sel: (u32, u32),
+}
+
 impl VirtioBlock {
     /// Creates a new VirtIO Block device.
     ///
@@ -185,6 +240,7 @@ impl VirtioBlock {
             queue_num: 0,
             queue_ready: 0,
             queue_notify: 0,
+            queue_sel: 0,
             queue_desc_low: 0,
             queue_desc_high: 0,
             queue_avail_low: 0,
@@ -260,10 +316,146 @@ impl VirtioBlock {
         self.ram.write_slice(offset, data);
     }
 
+    /// Returns the device to its reset state, keeping the disk image.
+    fn reset(&mut self) {
+        self.status = 0;
+        self.queue_num = 0;
+        self.queue_ready = 0;
+        self.queue_notify = 0;
+        self.queue_sel = 0;
+        self.queue_desc_low = 0;
+        self.queue_desc_high = 0;
+        self.queue_avail_low = 0;
+        self.queue_avail_high = 0;
+        self.queue_used_low = 0;
+        self.queue_used_high = 0;
+        self.interrupt_status = 0;
+        self.last_avail_idx = 0;
+        self.device_features_sel = 0;
+        self.driver_features_sel = 0;
+    }
+
+    /// Reads a little-endian `u16` from guest memory.
+    fn dma_read_u16(&self, addr: u64) -> u16 {
+        u16::from_le_bytes(self.dma_read(addr, 2).try_into().unwrap())
+    }
+
+    /// Reads the descriptor chain starting at `head`.
+    ///
+    /// # Returns
+    ///
+    /// `(addr, len, flags)` of each descriptor in order. The walk stops at an
+    /// out-of-range index or after `queue_num` descriptors, so a looping chain
+    /// cannot hang the device.
+    fn read_chain(&self, desc_addr: u64, head: u16) -> Vec<(u64, u32, u16)> {
+        let mut descriptors = Vec::new();
+        let mut current_idx = head;
+
+        while descriptors.len() < self.queue_num as usize {
+            if current_idx as u32 >= self.queue_num {
+                println!(
+                    "[VirtIO] Error: Descriptor index {} out of bounds (Queue Size {})",
+                    current_idx, self.queue_num
+                );
+                break;
+            }
+
+            let addr_offset = desc_addr + (current_idx as u64 * DESC_SIZE);
+            let addr = u64::from_le_bytes(
+                self.dma_read(addr_offset + DESC_OFFSET_ADDR, 8)
+                    .try_into()
+                    .unwrap(),
+            );
+            let len = u32::from_le_bytes(
+                self.dma_read(addr_offset + DESC_OFFSET_LEN, 4)
+                    .try_into()
+                    .unwrap(),
+            );
+            let flags = self.dma_read_u16(addr_offset + DESC_OFFSET_FLAGS);
+            let next = self.dma_read_u16(addr_offset + DESC_OFFSET_NEXT);
+
+            descriptors.push((addr, len, flags));
+
+            if (flags & VRING_DESC_F_NEXT) == 0 {
+                break;
+            }
+            current_idx = next;
+        }
+        descriptors
+    }
+
+    /// Services one block request.
+    ///
+    /// The chain is a 16-byte request header, zero or more data buffers, and a
+    /// one-byte status buffer.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written into device-writable buffers, including the
+    /// status byte, for the used ring.
+    fn service_request(&mut self, descriptors: &[(u64, u32, u16)]) -> u32 {
+        if descriptors.len() < 2 {
+            println!("[VirtIO] Error: Request chain too short");
+            return 0;
+        }
+        let (h_addr, _, _) = descriptors[0];
+        let (s_addr, _, _) = descriptors[descriptors.len() - 1];
+        let data = &descriptors[1..descriptors.len() - 1];
+
+        let header = self.dma_read(h_addr, 16);
+        let type_val = u32::from_le_bytes(header[0..4].try_into().unwrap());
+        let sector = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        let sector_offset = sector.saturating_mul(SECTOR_SIZE);
+        let data_len: u64 = data.iter().map(|&(_, len, _)| len as u64).sum();
+        let in_range = sector_offset
+            .checked_add(data_len)
+            .is_some_and(|end| end <= self.disk_image.len() as u64);
+
+        let mut len_written = 0;
+        let status = match type_val {
+            VIRTIO_BLK_T_IN | VIRTIO_BLK_T_OUT if !in_range => VIRTIO_BLK_S_IOERR,
+            VIRTIO_BLK_T_IN => {
+                let mut disk_offset = sector_offset as usize;
+                for &(d_addr, d_len, d_flags) in data {
+                    if (d_flags & VRING_DESC_F_WRITE) != 0 {
+                        let end = disk_offset + d_len as usize;
+                        self.dma_write(d_addr, &self.disk_image[disk_offset..end]);
+                        len_written += d_len;
+                    }
+                    disk_offset += d_len as usize;
+                }
+                VIRTIO_BLK_S_OK
+            }
+            VIRTIO_BLK_T_OUT => {
+                let mut disk_offset = sector_offset as usize;
+                for &(d_addr, d_len, _) in data {
+                    let bytes = self.dma_read(d_addr, d_len as usize);
+                    self.disk_image[disk_offset..disk_offset + bytes.len()].copy_from_slice(&bytes);
+                    disk_offset += d_len as usize;
+                }
+                VIRTIO_BLK_S_OK
+            }
+            // Writes land in the image immediately, so there is nothing to flush.
+            VIRTIO_BLK_T_FLUSH => VIRTIO_BLK_S_OK,
+            VIRTIO_BLK_T_GET_ID => {
+                if let Some(&(d_addr, d_len, _)) = data.first() {
+                    let len = DEVICE_ID_STRING.len().min(d_len as usize);
+                    self.dma_write(d_addr, &DEVICE_ID_STRING[..len]);
+                    len_written += len as u32;
+                }
+                VIRTIO_BLK_S_OK
+            }
+            _ => VIRTIO_BLK_S_UNSUPP,
+        };
+
+        self.dma_write(s_addr, &[status]);
+        len_written + 1
+    }
+
     /// Processes the VirtQueue.
     ///
-    /// Reads descriptors from the Available Ring, executes the requests (Read/Write),
-    /// and updates the Used Ring. This is triggered by a write to the Queue Notify register.
+    /// Reads descriptors from the Available Ring, executes the requests, and
+    /// updates the Used Ring. This is triggered by a write to the Queue Notify register.
     fn process_queue(&mut self) {
         if self.queue_num == 0 {
             return;
@@ -273,132 +465,37 @@ impl VirtioBlock {
         let avail_addr = ((self.queue_avail_high as u64) << 32) | (self.queue_avail_low as u64);
         let used_addr = ((self.queue_used_high as u64) << 32) | (self.queue_used_low as u64);
 
-        let avail_idx = u16::from_le_bytes(self.dma_read(avail_addr + 2, 2).try_into().unwrap());
+        let avail_idx = self.dma_read_u16(avail_addr + 2);
+        let mut completed = false;
 
         while self.last_avail_idx != avail_idx {
             let ring_offset = 4 + (self.last_avail_idx as u64 % self.queue_num as u64) * 2;
-            let head_idx = u16::from_le_bytes(
-                self.dma_read(avail_addr + ring_offset, 2)
-                    .try_into()
-                    .unwrap(),
-            );
+            let head_idx = self.dma_read_u16(avail_addr + ring_offset);
+            self.last_avail_idx = self.last_avail_idx.wrapping_add(1);
 
             if head_idx as u32 >= self.queue_num {
                 println!(
                     "[VirtIO] Error: Head descriptor index {} out of bounds (Queue Size {})",
                     head_idx, self.queue_num
                 );
-                self.last_avail_idx = self.last_avail_idx.wrapping_add(1);
                 continue;
             }
 
-            let mut current_idx = head_idx;
-            let mut descriptors = Vec::new();
-
-            loop {
-                if current_idx as u32 >= self.queue_num {
-                    println!(
-                        "[VirtIO] Error: Descriptor index {} out of bounds (Queue Size {})",
-                        current_idx, self.queue_num
-                    );
-                    break;
-                }
-
-                let addr_offset = desc_addr + (current_idx as u64 * DESC_SIZE);
-                let addr = u64::from_le_bytes(
-                    self.dma_read(addr_offset + DESC_OFFSET_ADDR, 8)
-                        .try_into()
-                        .unwrap(),
-                );
-                let len = u32::from_le_bytes(
-                    self.dma_read(addr_offset + DESC_OFFSET_LEN, 4)
-                        .try_into()
-                        .unwrap(),
-                );
-                let flags = u16::from_le_bytes(
-                    self.dma_read(addr_offset + DESC_OFFSET_FLAGS, 2)
-                        .try_into()
-                        .unwrap(),
-                );
-                let next = u16::from_le_bytes(
-                    self.dma_read(addr_offset + DESC_OFFSET_NEXT, 2)
-                        .try_into()
-                        .unwrap(),
-                );
-
-                descriptors.push((addr, len, flags));
-
-                if (flags & VRING_DESC_F_NEXT) == 0 {
-                    break;
-                }
-                current_idx = next;
-            }
-
-            let mut len_written = 0;
-            if descriptors.len() >= 3 {
-                let (h_addr, _, _) = descriptors[0];
-                let header = self.dma_read(h_addr, 16);
-                let type_val = u32::from_le_bytes(header[0..4].try_into().unwrap());
-                let sector = u64::from_le_bytes(header[8..16].try_into().unwrap());
-                let is_write = (type_val & 1) != 0;
-
-                let (s_addr, _, _) = descriptors[descriptors.len() - 1];
-
-                let sector_offset = (sector * SECTOR_SIZE) as usize;
-                let mut current_offset = 0;
-
-                if is_write {
-                    let mut current_disk_offset = sector_offset;
-
-                    for i in 1..descriptors.len() - 1 {
-                        let (d_addr, d_len, _) = descriptors[i];
-
-                        let data = self.dma_read(d_addr, d_len as usize);
-                        if current_disk_offset + data.len() <= self.disk_image.len() {
-                            self.disk_image[current_disk_offset..current_disk_offset + data.len()]
-                                .copy_from_slice(&data);
-                        }
-                        current_disk_offset += d_len as usize;
-                        len_written += d_len;
-                    }
-                } else {
-                    for i in 1..descriptors.len() - 1 {
-                        let (d_addr, d_len, d_flags) = descriptors[i];
-                        if (d_flags & VRING_DESC_F_WRITE) != 0 {
-                            if sector_offset + current_offset < self.disk_image.len() {
-                                let available =
-                                    self.disk_image.len() - (sector_offset + current_offset);
-                                let copy_len = std::cmp::min(d_len as usize, available);
-                                self.dma_write(
-                                    d_addr,
-                                    &self.disk_image[sector_offset + current_offset
-                                        ..sector_offset + current_offset + copy_len],
-                                );
-                                len_written += copy_len as u32;
-                            }
-                        }
-                        current_offset += d_len as usize;
-                    }
-                }
-
-                self.dma_write(s_addr, &[0]);
-                if is_write {
-                    len_written = 0;
-                }
-            }
+            let descriptors = self.read_chain(desc_addr, head_idx);
+            let len_written = self.service_request(&descriptors);
 
             let used_idx_addr = used_addr + 2;
-            let current_used =
-                u16::from_le_bytes(self.dma_read(used_idx_addr, 2).try_into().unwrap());
+            let current_used = self.dma_read_u16(used_idx_addr);
             let used_elem = used_addr + 4 + (current_used as u64 % self.queue_num as u64) * 8;
 
             self.dma_write(used_elem, &u32::from(head_idx).to_le_bytes());
-            self.dma_write(used_elem + 4, &(len_written as u32).to_le_bytes());
+            self.dma_write(used_elem + 4, &len_written.to_le_bytes());
             self.dma_write(used_idx_addr, &current_used.wrapping_add(1).to_le_bytes());
-
-            self.last_avail_idx = self.last_avail_idx.wrapping_add(1);
+            completed = true;
+        }
+        if completed {
+            self.interrupt_status |= INTERRUPT_USED_BUFFER;
         }
-        self.interrupt_status |= 1;
     }
 }
 
@@ -424,12 +521,12 @@ impl Device for VirtioBlock {
             REG_VENDOR_ID => VIRTIO_MMIO_VENDOR_ID_VALUE,
             REG_DEVICE_FEATURES => {
                 if self.device_features_sel == 1 {
-                    1
+                    VIRTIO_F_VERSION_1_HIGH
                 } else {
                     0
                 }
             }
-            REG_QUEUE_NUM_MAX => QUEUE_NUM_MAX_VALUE,
+            REG_QUEUE_NUM_MAX if self.queue_sel == 0 => QUEUE_NUM_MAX_VALUE,
             REG_QUEUE_READY => self.queue_ready,
             REG_INTERRUPT_STATUS => self.interrupt_status,
             REG_STATUS => self.status,
@@ -456,7 +553,7 @@ impl Device for VirtioBlock {
             REG_DEVICE_FEATURES_SEL => self.device_features_sel = val,
             REG_DRIVER_FEATURES => {}
             REG_DRIVER_FEATURES_SEL => self.driver_features_sel = val,
-            REG_QUEUE_SEL => {}
+            REG_QUEUE_SEL => self.queue_sel = val,
             REG_QUEUE_NUM => self.queue_num = val,
             REG_QUEUE_READY => self.queue_ready = val,
             REG_QUEUE_NOTIFY => {
@@ -464,6 +561,7 @@ impl Device for VirtioBlock {
                 self.process_queue();
             }
             REG_INTERRUPT_ACK => self.interrupt_status &= !val,
+            REG_STATUS if val == 0 => self.reset(),
             REG_STATUS => self.status = val,
             REG_QUEUE_DESC_LOW => self.queue_desc_low = val,
             REG_QUEUE_DESC_HIGH => self.queue_desc_high = val,
@@ -504,11 +602,52 @@ impl Device for VirtioBlock {
     ///
     /// Returns true if an interrupt is pending.
     fn tick(&mut self) -> bool {
-        (self.interrupt_status & 1) != 0
+        (self.interrupt_status & INTERRUPT_USED_BUFFER) != 0
     }
 
     /// Returns the Interrupt Request (IRQ) ID associated with this device.
     fn get_irq_id(&self) -> Option<u32> {
         Some(1)
     }
+
+    /// Saves the transport registers and the disk image, including guest writes.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        encode_state(&VirtioState {
+            disk_image: self.disk_image.clone(),
+            status: self.status,
+            queue_num: self.queue_num,
+            queue_ready: self.queue_ready,
+            queue_notify: self.queue_notify,
+            queue_sel: self.queue_sel,
+            queue_desc: (self.queue_desc_low, self.queue_desc_high),
+            queue_avail: (self.queue_avail_low, self.queue_avail_high),
+            queue_used: (self.queue_used_low, self.queue_used_high),
+            interrupt_status: self.interrupt_status,
+            last_avail_idx: self.last_avail_idx,
+            features_sel: (self.device_features_sel, self.driver_features_sel),
+        })
+    }
+
+    /// Checks that the state decodes.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        decode_state::<VirtioState>(state).map(|_| ())
+    }
+
+    /// Restores the transport registers and the disk image.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        let state: VirtioState = decode_state(state)?;
+        self.disk_image = state.disk_image;
+        self.status = state.status;
+        self.queue_num = state.queue_num;
+        self.queue_ready = state.queue_ready;
+        self.queue_notify = state.queue_notify;
+        self.queue_sel = state.queue_sel;
+        (self.queue_desc_low, self.queue_desc_high) = state.queue_desc;
+        (self.queue_avail_low, self.queue_avail_high) = state.queue_avail;
+        (self.queue_used_low, self.queue_used_high) = state.queue_used;
+        self.interrupt_status = state.interrupt_status;
+        self.last_avail_idx = state.last_avail_idx;
+        (self.device_features_sel, self.driver_features_sel) = state.features_sel;
+        Ok(())
+    }
 }
@@ -1,12 +1,47 @@
 //! System interconnect (bus) for memory and MMIO access.
 //!
 //! This module implements the bus that routes physical address accesses to devices. It provides:
-//! 1. **Device registration:** Devices are added by address range and sorted for lookup.
+//! 1. **Device registration:** Devices are added by address range and sorted for lookup;
+//!    a device whose range overlaps one already registered is rejected.
 //! 2. **Access routing:** Read/write by address with last-device hint for throughput.
 //! 3. **Tick and IRQ:** Each device is ticked; PLIC aggregates IRQs for timer and external.
-//! 4. **Load and RAM pointer:** Binary loading and raw RAM pointer for CPU DMA-style access.
+//! 4. **Shutdown:** Devices are notified once at exit so persistent state can be written back.
+//! 5. **Load and RAM pointer:** Binary loading and raw RAM pointer for CPU DMA-style access.
+//! 6. **Checkpointing:** Device state is collected and restored by name and base address.
 
-use super::devices::Device;
+use super::devices::{Clint, Device};
+use super::traits::DeviceSnapshot;
+use std::fmt;
+
+/// A device could not be registered because its address range overlaps another device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressOverlap {
+    /// Name of the device being added.
+    pub device: String,
+    /// `(base, size)` of the device being added.
+    pub range: (u64, u64),
+    /// Name of the registered device it collides with.
+    pub existing: String,
+    /// `(base, size)` of the registered device.
+    pub existing_range: (u64, u64),
+}
+
+impl fmt::Display for AddressOverlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (base, size) = self.range;
+        let (ex_base, ex_size) = self.existing_range;
+        write!(
+            f,
+            "{} [{:#x}, {:#x}) overlaps {} [{:#x}, {:#x})",
+            self.device,
+            base,
+            base.wrapping_add(size),
+            self.existing,
+            ex_base,
+            ex_base.wrapping_add(ex_size)
+        )
+    }
+}
 
 /// System bus connecting CPU and devices; routes accesses by physical address.
 ///
@@ -51,12 +86,54 @@ impl Bus {
     /// # Arguments
     ///
     /// * `dev` - The device to add (must implement `Device` and be `Send + Sync`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device's address range overlaps a registered device; use
+    /// [`Bus::try_add_device`] to handle the error instead.
     pub fn add_device(&mut self, dev: Box<dyn Device + Send + Sync>) {
+        self.try_add_device(dev)
+            .unwrap_or_else(|e| panic!("invalid device address map: {e}"));
+    }
+
+    /// Registers a device on the bus unless its address range overlaps a registered device.
+    ///
+    /// Ranges are half-open, so a device may start exactly where another ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev` - The device to add (must implement `Device` and be `Send + Sync`).
+    ///
+    /// # Returns
+    ///
+    /// `Err` naming both devices and their ranges if `[base, base + size)` intersects
+    /// an existing device; the bus is left unchanged.
+    pub fn try_add_device(
+        &mut self,
+        dev: Box<dyn Device + Send + Sync>,
+    ) -> Result<(), AddressOverlap> {
+        let (base, size) = dev.address_range();
+        let end = base.saturating_add(size);
+        if size > 0 {
+            for existing in &self.devices {
+                let (ex_base, ex_size) = existing.address_range();
+                if ex_size > 0 && base < ex_base.saturating_add(ex_size) && ex_base < end {
+                    return Err(AddressOverlap {
+                        device: dev.name().to_string(),
+                        range: (base, size),
+                        existing: existing.name().to_string(),
+                        existing_range: (ex_base, ex_size),
+                    });
+                }
+            }
+        }
+
         self.devices.push(dev);
         self.devices.sort_by_key(|d| d.address_range().0);
         self.ram_idx = self.devices.iter().position(|d| d.name() == "DRAM");
         self.uart_idx = self.devices.iter().position(|d| d.name() == "UART0");
         self.last_device_idx = 0;
+        Ok(())
     }
 
     /// Returns the number of cycles to transfer the given number of bytes on this bus.
@@ -119,6 +196,18 @@ impl Bus {
         false
     }
 
+    /// Returns whether `paddr` is backed by main memory rather than a device register.
+    ///
+    /// # Arguments
+    ///
+    /// * `paddr` - Physical address to check.
+    pub fn is_memory(&self, paddr: u64) -> bool {
+        self.devices.iter().any(|dev| {
+            let (start, size) = dev.address_range();
+            dev.is_memory() && paddr >= start && paddr < start + size
+        })
+    }
+
     /// Advances all devices by one tick and updates PLIC; returns IRQ flags.
     ///
     /// # Returns
@@ -152,6 +241,88 @@ impl Bus {
         (timer_irq, meip, seip)
     }
 
+    /// Returns the CLINT interrupt lines of one hart.
+    ///
+    /// # Returns
+    ///
+    /// `(timer, software)` pending state; both `false` without a CLINT.
+    pub fn clint_lines(&self, hart: usize) -> (bool, bool) {
+        self.clint()
+            .map_or((false, false), |clint| clint.hart_lines(hart))
+    }
+
+    /// Returns the CLINT's `mtime`, or `None` if the bus has no CLINT.
+    pub fn clint_mtime(&self) -> Option<u64> {
+        self.clint().map(Clint::mtime)
+    }
+
+    /// Finds the CLINT among the attached devices.
+    fn clint(&self) -> Option<&Clint> {
+        self.devices.iter().find_map(|dev| dev.as_clint())
+    }
+
+    /// Notifies every device that the simulation is shutting down.
+    pub fn shutdown(&mut self) {
+        for dev in &mut self.devices {
+            dev.shutdown();
+        }
+    }
+
+    /// Collects the state of every device that supports checkpoints.
+    pub fn save_device_states(&self) -> Vec<DeviceSnapshot> {
+        self.devices
+            .iter()
+            .filter_map(|dev| {
+                dev.save_state().map(|state| DeviceSnapshot {
+                    name: dev.name().to_string(),
+                    base: dev.address_range().0,
+                    state,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that every snapshot matches a device that accepts it, without restoring any.
+    ///
+    /// # Returns
+    ///
+    /// The index of each snapshot's device, or an error naming the first snapshot with no
+    /// matching device or that the device rejects.
+    fn check_device_states(&self, snapshots: &[DeviceSnapshot]) -> Result<Vec<usize>, String> {
+        snapshots
+            .iter()
+            .map(|snap| {
+                let idx = self
+                    .devices
+                    .iter()
+                    .position(|d| d.name() == snap.name && d.address_range().0 == snap.base)
+                    .ok_or_else(|| format!("no {} at {:#x} to restore", snap.name, snap.base))?;
+                self.devices[idx]
+                    .check_state(&snap.state)
+                    .map_err(|e| format!("{} at {:#x}: {}", snap.name, snap.base, e))?;
+                Ok(idx)
+            })
+            .collect()
+    }
+
+    /// Restores device state saved by [`save_device_states`](Self::save_device_states).
+    ///
+    /// Every snapshot is checked before any device changes, so an error leaves all devices
+    /// (RAM included) as they were. Devices without a snapshot keep their current state.
+    ///
+    /// # Returns
+    ///
+    /// An error naming the first snapshot with no matching device or that the device rejects.
+    pub fn load_device_states(&mut self, snapshots: &[DeviceSnapshot]) -> Result<(), String> {
+        let targets = self.check_device_states(snapshots)?;
+        for (snap, idx) in snapshots.iter().zip(targets) {
+            self.devices[idx]
+                .load_state(&snap.state)
+                .map_err(|e| format!("{} at {:#x}: {}", snap.name, snap.base, e))?;
+        }
+        Ok(())
+    }
+
     /// Returns whether the UART device has detected a kernel panic pattern (for test harnesses).
     ///
     /// # Returns
@@ -2,7 +2,13 @@
 //!
 //! This module provides:
 //! 1. **SimpleController:** Fixed latency per access (no row-buffer modeling).
-//! 2. **DramController:** Row-buffer-aware latency (CAS, RAS, precharge) for DRAM-style timing.
+//! 2. **DramController:** Row-buffer-aware latency (CAS, RAS, precharge) for DRAM-style timing,
+//!    with an open row per bank and periodic refresh windows that block accesses.
+//! 3. **BandwidthQueue:** Rate-limited fill channel; back-to-back misses queue behind each other.
+//! 4. **HbmController:** Stacked memory split into hashed pseudo-channels, each with its own
+//!    row buffer and transfer queue, so independent streams overlap instead of serializing.
+//! 5. **InterleavedController:** Independent memory channels, each with its own controller,
+//!    selected by address at line or page granularity.
 //!
 //! Controllers are `Send + Sync` for use with the Python bindings and multi-threaded simulation.
 
@@ -20,6 +26,29 @@ pub trait MemoryController: Send + Sync {
     ///
     /// Latency in simulation cycles.
     fn access_latency(&mut self, addr: u64) -> u64;
+
+    /// Enqueues a line fill that reached main memory and returns its queueing delay.
+    ///
+    /// Unlike [`access_latency`](Self::access_latency), this is only called for accesses that
+    /// miss every cache level. Controllers without internal queues use the default (no delay).
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Physical address of the fill.
+    /// * `now` - Cycle at which the request arrives.
+    ///
+    /// # Returns
+    ///
+    /// Cycles the request waits before the controller can start it.
+    fn queue_delay(&mut self, _addr: u64, _now: u64) -> u64 {
+        0
+    }
+
+    /// Closes every open row and idles every queue, as at power-on.
+    ///
+    /// Called when a checkpoint is taken or restored so that both continue with the
+    /// same timing. Stateless controllers use the default (no-op).
+    fn reset(&mut self) {}
 }
 
 /// Fixed-latency memory controller; every access takes the same number of cycles.
@@ -48,18 +77,29 @@ impl MemoryController for SimpleController {
     }
 }
 
-/// DRAM-style controller with row buffer; models CAS, RAS, and precharge latencies.
+/// DRAM-style controller with a row buffer per bank; models CAS, RAS, and precharge latencies.
+///
+/// Addresses map as `row : bank : column`: consecutive `row_bytes` chunks interleave across
+/// banks, so a sequential stream stays in open rows while a stride of `row_bytes * banks`
+/// lands every access in a new row of the same bank. With refresh enabled, every `t_refi`
+/// cycles all banks are closed and the controller is blocked for `t_rfc` cycles.
 pub struct DramController {
-    last_row: Option<u64>,
+    open_rows: Vec<Option<u64>>,
+    row_bytes: u64,
     t_cas: u64,
     t_ras: u64,
     t_pre: u64,
-    row_mask: u64,
+    t_refi: u64,
+    t_rfc: u64,
+    refresh_epoch: u64,
 }
 
 impl DramController {
     /// Creates a DRAM controller with the given timing parameters (in cycles).
     ///
+    /// The controller starts with a single bank of 2 KiB rows and refresh disabled; see
+    /// [`with_banks`](Self::with_banks) and [`with_refresh`](Self::with_refresh).
+    ///
     /// # Arguments
     ///
     /// * `t_cas` - Column access strobe latency.
@@ -71,28 +111,322 @@ impl DramController {
     /// A new `DramController` with no row currently open.
     pub fn new(t_cas: u64, t_ras: u64, t_pre: u64) -> Self {
         Self {
-            last_row: None,
+            open_rows: vec![None],
+            row_bytes: 2048,
             t_cas,
             t_ras,
             t_pre,
-            row_mask: !2047,
+            t_refi: 0,
+            t_rfc: 0,
+            refresh_epoch: 0,
         }
     }
+
+    /// Sets the bank count and row size.
+    ///
+    /// # Arguments
+    ///
+    /// * `banks` - Number of banks, each with its own open row (clamped to at least 1).
+    /// * `row_bytes` - Row (page) size in bytes (clamped to at least 1).
+    pub fn with_banks(mut self, banks: usize, row_bytes: u64) -> Self {
+        self.open_rows = vec![None; banks.max(1)];
+        self.row_bytes = row_bytes.max(1);
+        self
+    }
+
+    /// Enables periodic refresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `t_refi` - Cycles between refresh commands; `0` disables refresh.
+    /// * `t_rfc` - Cycles each refresh blocks the controller.
+    pub fn with_refresh(mut self, t_refi: u64, t_rfc: u64) -> Self {
+        self.t_refi = t_refi;
+        self.t_rfc = t_rfc;
+        self
+    }
+
+    /// Returns the number of banks.
+    pub fn bank_count(&self) -> usize {
+        self.open_rows.len()
+    }
+
+    /// Returns the `(bank, row)` that `addr` maps to.
+    pub fn bank_and_row(&self, addr: u64) -> (usize, u64) {
+        let chunk = addr / self.row_bytes;
+        let banks = self.open_rows.len() as u64;
+        ((chunk % banks) as usize, chunk / banks)
+    }
 }
 
 impl MemoryController for DramController {
     fn access_latency(&mut self, addr: u64) -> u64 {
-        let row = addr & self.row_mask;
-        match self.last_row {
+        let (bank, row) = self.bank_and_row(addr);
+        match self.open_rows[bank] {
+            Some(open_row) if open_row == row => self.t_cas,
+            Some(_) => {
+                self.open_rows[bank] = Some(row);
+                self.t_pre + self.t_ras + self.t_cas
+            }
+            None => {
+                self.open_rows[bank] = Some(row);
+                self.t_ras + self.t_cas
+            }
+        }
+    }
+
+    fn queue_delay(&mut self, _addr: u64, now: u64) -> u64 {
+        if self.t_refi == 0 {
+            return 0;
+        }
+        // Refresh precharges every bank, so the first request of each interval finds
+        // all rows closed.
+        let epoch = now / self.t_refi;
+        if epoch != self.refresh_epoch {
+            self.refresh_epoch = epoch;
+            self.open_rows.fill(None);
+        }
+        let phase = now % self.t_refi;
+        if epoch > 0 && phase < self.t_rfc {
+            self.t_rfc - phase
+        } else {
+            0
+        }
+    }
+
+    fn reset(&mut self) {
+        self.open_rows.fill(None);
+        self.refresh_epoch = 0;
+    }
+}
+
+/// Rate-limited memory channel that serializes line fills.
+///
+/// The channel transfers at most `bytes_per_cycle` bytes per cycle. A request arriving while
+/// an earlier transfer is still in flight waits for the channel to drain, so a burst of misses
+/// sees growing latency instead of each paying the same fixed cost.
+pub struct BandwidthQueue {
+    bytes_per_cycle: u64,
+    busy_until: u64,
+}
+
+impl BandwidthQueue {
+    /// Creates a bandwidth queue with the given transfer rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes_per_cycle` - Channel throughput; `0` disables the model (unlimited bandwidth).
+    ///
+    /// # Returns
+    ///
+    /// A new `BandwidthQueue` with an idle channel.
+    pub fn new(bytes_per_cycle: u64) -> Self {
+        Self {
+            bytes_per_cycle,
+            busy_until: 0,
+        }
+    }
+
+    /// Returns `true` if the bandwidth model is active.
+    pub fn is_enabled(&self) -> bool {
+        self.bytes_per_cycle > 0
+    }
+
+    /// Enqueues a transfer and returns the cycles it waits before the channel is free.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Cycle at which the request arrives.
+    /// * `bytes` - Transfer size in bytes (typically one cache line).
+    ///
+    /// # Returns
+    ///
+    /// Queueing delay in cycles (`0` when the channel is idle or the model is disabled).
+    pub fn request(&mut self, now: u64, bytes: u64) -> u64 {
+        if !self.is_enabled() {
+            return 0;
+        }
+        let start = self.busy_until.max(now);
+        self.busy_until = start + bytes.div_ceil(self.bytes_per_cycle);
+        start - now
+    }
+
+    /// Drains the channel, as at power-on.
+    pub fn reset(&mut self) {
+        self.busy_until = 0;
+    }
+}
+
+/// Bytes transferred per memory request (one cache line).
+const HBM_LINE_BYTES: u64 = 64;
+
+/// Row (page) size of a single HBM pseudo-channel in bytes.
+const HBM_ROW_BYTES: u64 = 1024;
+
+/// Per-pseudo-channel state of an [`HbmController`].
+#[derive(Clone, Copy, Default)]
+struct HbmChannel {
+    open_row: Option<u64>,
+    busy_until: u64,
+}
+
+/// HBM-style stacked memory controller with hashed pseudo-channels.
+///
+/// Line addresses are XOR-folded onto `channels` pseudo-channels. Each channel keeps its own
+/// open row (CAS/RAS/precharge timing as in [`DramController`]) and its own transfer queue,
+/// so a burst of misses spread across channels completes in parallel, while misses that
+/// collide on one channel queue behind each other for `burst_cycles` per line.
+pub struct HbmController {
+    channels: Vec<HbmChannel>,
+    t_cas: u64,
+    t_ras: u64,
+    t_pre: u64,
+    burst_cycles: u64,
+}
+
+impl HbmController {
+    /// Creates an HBM controller with the given channel count and timing (in cycles).
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - Number of pseudo-channels (clamped to at least 1).
+    /// * `t_cas` - Column access strobe latency.
+    /// * `t_ras` - Row access strobe latency.
+    /// * `t_pre` - Precharge latency.
+    /// * `burst_cycles` - Cycles one line transfer occupies a pseudo-channel.
+    ///
+    /// # Returns
+    ///
+    /// A new `HbmController` with every channel idle and no row open.
+    pub fn new(channels: usize, t_cas: u64, t_ras: u64, t_pre: u64, burst_cycles: u64) -> Self {
+        Self {
+            channels: vec![HbmChannel::default(); channels.max(1)],
+            t_cas,
+            t_ras,
+            t_pre,
+            burst_cycles,
+        }
+    }
+
+    /// Returns the number of pseudo-channels.
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns the pseudo-channel serving `addr`.
+    ///
+    /// Consecutive lines interleave across channels; higher address bits are folded in so
+    /// power-of-two strides do not all land on the same channel.
+    pub fn channel_of(&self, addr: u64) -> usize {
+        let line = addr / HBM_LINE_BYTES;
+        let hash = line ^ (line >> 7) ^ (line >> 14);
+        (hash % self.channels.len() as u64) as usize
+    }
+}
+
+impl MemoryController for HbmController {
+    fn access_latency(&mut self, addr: u64) -> u64 {
+        let row = addr / HBM_ROW_BYTES;
+        let ch = self.channel_of(addr);
+        let channel = &mut self.channels[ch];
+        match channel.open_row {
             Some(open_row) if open_row == row => self.t_cas,
             Some(_) => {
-                self.last_row = Some(row);
+                channel.open_row = Some(row);
                 self.t_pre + self.t_ras + self.t_cas
             }
             None => {
-                self.last_row = Some(row);
+                channel.open_row = Some(row);
                 self.t_ras + self.t_cas
             }
         }
     }
+
+    fn queue_delay(&mut self, addr: u64, now: u64) -> u64 {
+        let ch = self.channel_of(addr);
+        let channel = &mut self.channels[ch];
+        let start = channel.busy_until.max(now);
+        channel.busy_until = start + self.burst_cycles;
+        start - now
+    }
+
+    fn reset(&mut self) {
+        self.channels.fill(HbmChannel::default());
+    }
+}
+
+/// Multi-channel memory with addresses interleaved across independent controllers.
+///
+/// Each `granularity`-byte block maps to channel `(addr / granularity) % channels`, and the
+/// channel bits are removed before the address reaches that channel's controller. A channel
+/// serves one request at a time for its full access latency, so requests to different
+/// channels overlap while requests to the same channel queue behind each other.
+pub struct InterleavedController {
+    channels: Vec<Box<dyn MemoryController + Send + Sync>>,
+    granularity: u64,
+    /// Cycle at which each channel finishes its current request.
+    busy_until: Vec<u64>,
+    /// Latency of the last access seen by each channel.
+    last_latency: Vec<u64>,
+}
+
+impl InterleavedController {
+    /// Creates an interleaved controller over the given per-channel controllers.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - One controller per channel (must not be empty).
+    /// * `granularity` - Interleaving block size in bytes (clamped to at least 1).
+    ///
+    /// # Returns
+    ///
+    /// A new `InterleavedController` with every channel idle.
+    pub fn new(channels: Vec<Box<dyn MemoryController + Send + Sync>>, granularity: u64) -> Self {
+        assert!(!channels.is_empty(), "at least one memory channel");
+        let n = channels.len();
+        Self {
+            channels,
+            granularity: granularity.max(1),
+            busy_until: vec![0; n],
+            last_latency: vec![0; n],
+        }
+    }
+
+    /// Returns the number of channels.
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns the channel serving `addr` and the address within that channel.
+    pub fn channel_of(&self, addr: u64) -> (usize, u64) {
+        let n = self.channels.len() as u64;
+        let block = addr / self.granularity;
+        let local = (block / n) * self.granularity + addr % self.granularity;
+        ((block % n) as usize, local)
+    }
+}
+
+impl MemoryController for InterleavedController {
+    fn access_latency(&mut self, addr: u64) -> u64 {
+        let (ch, local) = self.channel_of(addr);
+        let latency = self.channels[ch].access_latency(local);
+        self.last_latency[ch] = latency;
+        latency
+    }
+
+    fn queue_delay(&mut self, addr: u64, now: u64) -> u64 {
+        let (ch, local) = self.channel_of(addr);
+        let ready = now + self.channels[ch].queue_delay(local, now);
+        let start = self.busy_until[ch].max(ready);
+        self.busy_until[ch] = start + self.last_latency[ch];
+        start - now
+    }
+
+    fn reset(&mut self) {
+        for channel in &mut self.channels {
+            channel.reset();
+        }
+        self.busy_until.fill(0);
+        self.last_latency.fill(0);
+    }
 }
@@ -13,8 +13,22 @@ pub mod controller;
 
 use self::buffer::DramBuffer;
 use crate::soc::devices::Device;
+use crate::soc::traits::{decode_state, encode_state};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Granularity at which RAM is checkpointed; all-zero chunks are left out.
+const CHECKPOINT_CHUNK: usize = 4096;
+
+/// Checkpointed RAM contents.
+#[derive(Serialize, Deserialize)]
+struct RamState {
+    /// Buffer size in bytes.
+    size: usize,
+    /// `(offset, bytes)` of every chunk holding a nonzero byte.
+    chunks: Vec<(usize, Vec<u8>)>,
+}
+
 /// System Memory structure.
 pub struct Memory {
     /// Shared reference to the underlying memory buffer.
@@ -55,6 +69,35 @@ impl Memory {
     pub fn as_mut_ptr(&mut self) -> *mut u8 {
         self.buffer.as_mut_ptr()
     }
+
+    /// Decodes checkpointed RAM contents and validates them against this buffer.
+    ///
+    /// # Returns
+    ///
+    /// The state, or an error for a size mismatch or a chunk that is misplaced,
+    /// out of order, or of the wrong length.
+    fn decode_ram(&self, state: &[u8]) -> Result<RamState, String> {
+        let state: RamState = decode_state(state)?;
+        if state.size != self.buffer.len() {
+            return Err(format!(
+                "RAM size mismatch: checkpoint has {:#x} bytes, memory has {:#x}",
+                state.size,
+                self.buffer.len()
+            ));
+        }
+        let mut next = 0;
+        for (offset, data) in &state.chunks {
+            let well_formed = *offset >= next
+                && offset % CHECKPOINT_CHUNK == 0
+                && *offset < state.size
+                && data.len() == CHECKPOINT_CHUNK.min(state.size - offset);
+            if !well_formed {
+                return Err(format!("malformed RAM chunk at offset {offset:#x}"));
+            }
+            next = offset + CHECKPOINT_CHUNK;
+        }
+        Ok(state)
+    }
 }
 
 impl Device for Memory {
@@ -119,8 +162,51 @@ impl Device for Memory {
         self.load(data, offset as usize);
     }
 
+    /// RAM supports atomic memory operations.
+    fn is_memory(&self) -> bool {
+        true
+    }
+
     /// Downcasts the device to a mutable Memory reference.
     fn as_memory_mut(&mut self) -> Option<&mut Memory> {
         Some(self)
     }
+
+    /// Saves the RAM contents, omitting chunks that are entirely zero.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        let bytes = self.buffer.read_slice(0, self.buffer.len());
+        let chunks = bytes
+            .chunks(CHECKPOINT_CHUNK)
+            .enumerate()
+            .filter(|(_, chunk)| chunk.iter().any(|&b| b != 0))
+            .map(|(i, chunk)| (i * CHECKPOINT_CHUNK, chunk.to_vec()))
+            .collect();
+        encode_state(&RamState {
+            size: self.buffer.len(),
+            chunks,
+        })
+    }
+
+    /// Checks the RAM size and that every chunk lies on a chunk boundary, in order.
+    fn check_state(&self, state: &[u8]) -> Result<(), String> {
+        self.decode_ram(state).map(|_| ())
+    }
+
+    /// Restores the RAM contents; chunks absent from the checkpoint are zeroed.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        let state = self.decode_ram(state)?;
+        let zero = [0u8; CHECKPOINT_CHUNK];
+        let mut saved = state.chunks.into_iter().peekable();
+        for offset in (0..state.size).step_by(CHECKPOINT_CHUNK) {
+            let len = CHECKPOINT_CHUNK.min(state.size - offset);
+            match saved.next_if(|(o, _)| *o == offset) {
+                Some((_, data)) => self.buffer.write_slice(offset, &data),
+                None if self.buffer.read_slice(offset, len).iter().any(|&b| b != 0) => {
+                    self.buffer.write_slice(offset, &zero[..len])
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
 }
@@ -4,12 +4,35 @@
 //! 1. **Identification:** `name` and `address_range` for bus routing.
 //! 2. **Access:** Byte, half, word, and doubleword read/write at device-relative offsets.
 //! 3. **Lifecycle:** Optional `tick` and IRQ reporting for timer and interrupt devices.
-//! 4. **Downcasting:** Optional casts to `Plic`, `Uart`, or `Memory` for device-specific access.
+//! 4. **Downcasting:** Optional casts to `Clint`, `Plic`, `Uart`, or `Memory` for device-specific access.
+//! 5. **Checkpointing:** Optional save and restore of device state.
 //!
 //! All implementors must be `Send + Sync` for use with the Python bindings and multi-threaded simulation.
 
-use crate::soc::devices::{Plic, Uart};
+use crate::soc::devices::{Clint, Plic, Uart};
 use crate::soc::memory::Memory;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Saved state of one bus device, as stored in a checkpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceSnapshot {
+    /// Device name (see [`Device::name`]).
+    pub name: String,
+    /// Base address of the device.
+    pub base: u64,
+    /// Opaque state produced by [`Device::save_state`].
+    pub state: Vec<u8>,
+}
+
+/// Encodes device state for [`Device::save_state`].
+pub(crate) fn encode_state<T: Serialize>(state: &T) -> Option<Vec<u8>> {
+    bincode::serialize(state).ok()
+}
+
+/// Decodes device state for [`Device::load_state`].
+pub(crate) fn decode_state<T: DeserializeOwned>(state: &[u8]) -> Result<T, String> {
+    bincode::deserialize(state).map_err(|e| e.to_string())
+}
 
 /// Trait for memory-mapped I/O devices attached to the system bus.
 ///
@@ -52,7 +75,32 @@ pub trait Device: Send + Sync {
     fn get_irq_id(&self) -> Option<u32> {
         None
     }
+    /// Returns `true` if this device is main memory; only memory supports LR/SC and AMOs.
+    fn is_memory(&self) -> bool {
+        false
+    }
+    /// Called once when the simulation shuts down; persistent devices write back state here.
+    fn shutdown(&mut self) {}
 
+    /// Serializes the device state for a checkpoint; `None` if the device has no state.
+    fn save_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+    /// Checks that [`load_state`](Self::load_state) would accept `state`, without applying it.
+    fn check_state(&self, _state: &[u8]) -> Result<(), String> {
+        Err(format!("{} does not support checkpoints", self.name()))
+    }
+    /// Restores state produced by [`save_state`](Self::save_state) on an identically configured device.
+    ///
+    /// Cannot fail for a `state` that [`check_state`](Self::check_state) accepted.
+    fn load_state(&mut self, _state: &[u8]) -> Result<(), String> {
+        Err(format!("{} does not support checkpoints", self.name()))
+    }
+
+    /// Returns a reference as `Clint` if this device is the CLINT; otherwise `None`.
+    fn as_clint(&self) -> Option<&Clint> {
+        None
+    }
     /// Returns a mutable reference as `Plic` if this device is the PLIC; otherwise `None`.
     fn as_plic_mut(&mut self) -> Option<&mut Plic> {
         None
@@ -5,9 +5,77 @@
 //! 2. **Instruction mix:** Counts by category (ALU, load, store, branch, system, FP).
 //! 3. **Branch prediction:** Lookups, mispredictions, and accuracy.
 //! 4. **Stalls:** Memory, control, and data hazard stall counts.
-//! 5. **Cache hierarchy:** Hit/miss counts for L1-I, L1-D, L2, and L3.
+//! 5. **Cache hierarchy:** Hit/miss counts for L1-I, L1-D, L2, and L3, with MPKI and
+//!    an AMAT estimate per level.
+//! 6. **MMU:** ITLB/DTLB hit/miss counts and hardware page walks.
+//! 7. **Host timing:** A measurement window so MIPS/kHz exclude setup time, plus a
+//!    rolling-window [`ProgressMeter`] for periodic throughput readouts.
+//! 8. **Reporting:** A sectioned text report, and the same metrics as JSON for sweeps.
 
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::config::{CacheConfig, CacheHierarchyConfig};
+use crate::core::units::mmu::MmuStats;
+
+/// Configured hit latencies of the enabled cache levels, used for AMAT estimates.
+///
+/// A level is `None` when it is disabled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheLatencies {
+    /// L1 instruction cache latency.
+    pub l1_i: Option<u64>,
+    /// L1 data cache latency.
+    pub l1_d: Option<u64>,
+    /// L2 (data half when split) latency.
+    pub l2: Option<u64>,
+    /// Instruction half of a split L2.
+    pub l2_i: Option<u64>,
+    /// L3 latency.
+    pub l3: Option<u64>,
+}
+
+impl CacheLatencies {
+    /// Collects the latencies of the enabled levels of a cache hierarchy.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - Cache hierarchy configuration.
+    pub fn from_config(cache: &CacheHierarchyConfig) -> Self {
+        let latency = |c: &CacheConfig| c.enabled.then_some(c.latency);
+        Self {
+            l1_i: latency(&cache.l1_i),
+            l1_d: latency(&cache.l1_d),
+            l2: latency(&cache.l2),
+            l2_i: if cache.l2_split {
+                latency(&cache.l2_i)
+            } else {
+                None
+            },
+            l3: latency(&cache.l3),
+        }
+    }
+}
+
+/// Average memory access time, in cycles, seen at each enabled cache level.
+///
+/// Each level's AMAT is its hit latency plus its miss rate times the AMAT of
+/// the next enabled level, ending at the measured average main memory latency.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Amat {
+    /// AMAT of instruction fetches at the L1-I.
+    pub l1_i: Option<f64>,
+    /// AMAT of loads and stores at the L1-D.
+    pub l1_d: Option<f64>,
+    /// AMAT at the L2 (data half when split).
+    pub l2: Option<f64>,
+    /// AMAT at the instruction half of a split L2.
+    pub l2_i: Option<f64>,
+    /// AMAT at the L3.
+    pub l3: Option<f64>,
+}
 
 /// Simulation statistics structure tracking all performance metrics.
 ///
@@ -15,10 +83,21 @@ use std::time::Instant;
 /// branch prediction, stalls, and execution time for performance analysis.
 #[derive(Clone)]
 pub struct SimStats {
-    start_time: Instant,
+    /// Host time at which the measurement window opened (`None` until started).
+    start_time: Option<Instant>,
+    /// Host time at which the measurement window closed (`None` while running).
+    stop_time: Option<Instant>,
+    /// Retired-instruction count when the measurement window opened.
+    start_instructions: u64,
+    /// Cycle count when the measurement window opened.
+    start_cycles: u64,
     /// Total simulator cycles elapsed.
+    ///
+    /// Backs the `cycle`/`mcycle` CSRs; wraps modulo 2^64 rather than overflowing.
     pub cycles: u64,
     /// Number of instructions committed (retired).
+    ///
+    /// Backs the `instret`/`minstret` CSRs; wraps modulo 2^64 rather than overflowing.
     pub instructions_retired: u64,
 
     /// Count of integer load instructions retired.
@@ -47,6 +126,10 @@ pub struct SimStats {
     pub branch_predictions: u64,
     /// Number of branch predictions that were wrong (mispredictions).
     pub branch_mispredictions: u64,
+    /// Resolved branches and jumps, keyed by category (see [`SimStats::record_branch`]).
+    pub branches_by_type: BTreeMap<&'static str, u64>,
+    /// Mispredicted branches and jumps, keyed by category.
+    pub mispredicts_by_type: BTreeMap<&'static str, u64>,
 
     /// Cycles spent in user (U) mode.
     pub cycles_user: u64,
@@ -54,6 +137,8 @@ pub struct SimStats {
     pub cycles_kernel: u64,
     /// Cycles spent in machine (M) mode.
     pub cycles_machine: u64,
+    /// Cycles the hart spent idle in WFI with no pipeline activity.
+    pub cycles_idle: u64,
 
     /// Stall cycles due to memory (cache/memory not ready).
     pub stalls_mem: u64,
@@ -61,9 +146,15 @@ pub struct SimStats {
     pub stalls_control: u64,
     /// Stall cycles due to data hazards (RAW dependencies).
     pub stalls_data: u64,
+    /// Stall cycles waiting on multi-cycle FP operations.
+    pub stalls_fpu: u64,
+    /// Stall cycles waiting on multi-cycle integer divides and remainders.
+    pub stalls_div: u64,
 
     /// Number of traps (exceptions or interrupts) taken.
     pub traps_taken: u64,
+    /// Traps taken, keyed by cause name (see [`Trap::name`](crate::common::Trap::name)).
+    pub traps_by_cause: BTreeMap<&'static str, u64>,
 
     /// L1 instruction cache hit count.
     pub icache_hits: u64,
@@ -77,17 +168,70 @@ pub struct SimStats {
     pub l2_hits: u64,
     /// L2 cache miss count.
     pub l2_misses: u64,
+    /// Instruction-half L2 hit count (split L2 only).
+    pub l2_i_hits: u64,
+    /// Instruction-half L2 miss count (split L2 only).
+    pub l2_i_misses: u64,
     /// L3 cache hit count.
     pub l3_hits: u64,
     /// L3 cache miss count.
     pub l3_misses: u64,
+    /// Cycles L3 accesses spent waiting for a free access port.
+    pub l3_port_stall_cycles: u64,
+    /// Number of L3 accesses that found every port busy.
+    pub l3_port_stalls: u64,
+
+    /// L1-I lines invalidated by snooped data stores.
+    pub icache_snoop_invalidations: u64,
+    /// L1-D misses issued while another miss was outstanding.
+    pub mshr_overlapped_misses: u64,
+    /// L1-D accesses that joined a miss already in flight to the same line.
+    pub mshr_merged_misses: u64,
+    /// Cycles L1-D misses spent waiting for a free MSHR.
+    pub stalls_mshr_full: u64,
+    /// L1 misses served by swapping the line back from a victim cache.
+    pub victim_hits: u64,
+
+    /// Accesses that missed every cache level and went to main memory.
+    pub mem_accesses: u64,
+    /// Cycles spent on main memory accesses (queueing, transit, and DRAM latency).
+    pub mem_access_cycles: u64,
+    /// Configured cache latencies, for [`SimStats::amat`].
+    pub cache_latencies: CacheLatencies,
+
+    /// Cycles main memory requests spent waiting for fill bandwidth.
+    pub mem_queue_cycles: u64,
+    /// Number of main memory requests that had to wait for fill bandwidth.
+    pub mem_queued_requests: u64,
+
+    /// Instruction pairs issued as a single macro-op (second half counted).
+    pub fused_pairs: u64,
+
+    /// L1-I lines allocated by fetches later squashed by a misprediction.
+    pub wrong_path_icache_fills: u64,
+    /// L1-D lines allocated by squashed wrong-path loads (when modeled).
+    pub wrong_path_dcache_fills: u64,
+
+    /// Instruction fetches translated by an ITLB hit.
+    pub itlb_hits: u64,
+    /// Instruction fetches that missed the ITLB.
+    pub itlb_misses: u64,
+    /// Loads and stores translated by a DTLB hit.
+    pub dtlb_hits: u64,
+    /// Loads and stores that missed the DTLB.
+    pub dtlb_misses: u64,
+    /// Hardware page-table walks.
+    pub page_walks: u64,
 }
 
 impl Default for SimStats {
     /// Returns the default value.
     fn default() -> Self {
         Self {
-            start_time: Instant::now(),
+            start_time: None,
+            stop_time: None,
+            start_instructions: 0,
+            start_cycles: 0,
             cycles: 0,
             instructions_retired: 0,
             inst_load: 0,
@@ -102,36 +246,239 @@ impl Default for SimStats {
             inst_fp_div_sqrt: 0,
             branch_predictions: 0,
             branch_mispredictions: 0,
+            branches_by_type: BTreeMap::new(),
+            mispredicts_by_type: BTreeMap::new(),
             cycles_user: 0,
             cycles_kernel: 0,
             cycles_machine: 0,
+            cycles_idle: 0,
             stalls_mem: 0,
             stalls_control: 0,
             stalls_data: 0,
+            stalls_fpu: 0,
+            stalls_div: 0,
             traps_taken: 0,
+            traps_by_cause: BTreeMap::new(),
             icache_hits: 0,
             icache_misses: 0,
             dcache_hits: 0,
             dcache_misses: 0,
             l2_hits: 0,
             l2_misses: 0,
+            l2_i_hits: 0,
+            l2_i_misses: 0,
             l3_hits: 0,
             l3_misses: 0,
+            l3_port_stall_cycles: 0,
+            l3_port_stalls: 0,
+            icache_snoop_invalidations: 0,
+            mshr_overlapped_misses: 0,
+            mshr_merged_misses: 0,
+            stalls_mshr_full: 0,
+            victim_hits: 0,
+            mem_accesses: 0,
+            mem_access_cycles: 0,
+            cache_latencies: CacheLatencies::default(),
+            mem_queue_cycles: 0,
+            mem_queued_requests: 0,
+            fused_pairs: 0,
+            wrong_path_icache_fills: 0,
+            wrong_path_dcache_fills: 0,
+            itlb_hits: 0,
+            itlb_misses: 0,
+            dtlb_hits: 0,
+            dtlb_misses: 0,
+            page_walks: 0,
         }
     }
 }
 
 /// Section names for selective stats output.
 ///
-/// Valid section identifiers: `"summary"`, `"core"`, `"instruction_mix"`, `"branch"`, `"memory"`.
-/// Pass an empty slice to `print_sections` to print all sections.
-pub const STATS_SECTIONS: &[&str] = &["summary", "core", "instruction_mix", "branch", "memory"];
+/// Valid section identifiers: `"summary"`, `"core"`, `"instruction_mix"`, `"branch"`, `"memory"`,
+/// `"mmu"`. Pass an empty slice to `print_sections` to print all sections.
+pub const STATS_SECTIONS: &[&str] = &[
+    "summary",
+    "core",
+    "instruction_mix",
+    "branch",
+    "memory",
+    "mmu",
+];
 
 impl SimStats {
+    /// Copies the MMU's TLB and page-walk counters into these statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `mmu` - Counters maintained by [`Mmu::translate`](crate::core::units::mmu::Mmu::translate).
+    pub fn record_mmu(&mut self, mmu: &MmuStats) {
+        self.itlb_hits = mmu.itlb_hits;
+        self.itlb_misses = mmu.itlb_misses;
+        self.dtlb_hits = mmu.dtlb_hits;
+        self.dtlb_misses = mmu.dtlb_misses;
+        self.page_walks = mmu.page_walks;
+    }
+
+    /// Counts one resolved branch or jump, in aggregate and by category.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - `"cond_taken"`, `"cond_nottaken"`, `"call"`, `"return"`,
+    ///   `"indirect"` or `"jump"` (direct, non-linking `jal`).
+    /// * `mispredicted` - Whether fetch predicted the wrong next PC.
+    pub fn record_branch(&mut self, category: &'static str, mispredicted: bool) {
+        *self.branches_by_type.entry(category).or_insert(0) += 1;
+        if mispredicted {
+            self.branch_mispredictions += 1;
+            *self.mispredicts_by_type.entry(category).or_insert(0) += 1;
+        } else {
+            self.branch_predictions += 1;
+        }
+    }
+
+    /// Opens the host-time measurement window.
+    ///
+    /// Records the current host time and counter values so that the reported
+    /// MIPS and simulated frequency cover only work done after this call,
+    /// excluding binary loading and other setup. Calling it again restarts
+    /// the window.
+    pub fn start_measurement(&mut self) {
+        self.start_time = Some(Instant::now());
+        self.stop_time = None;
+        self.start_instructions = self.instructions_retired;
+        self.start_cycles = self.cycles;
+    }
+
+    /// Closes the host-time measurement window.
+    ///
+    /// Subsequent reports use the frozen window length. Has no effect if the
+    /// window was never opened or is already closed.
+    pub fn stop_measurement(&mut self) {
+        if self.start_time.is_some() && self.stop_time.is_none() {
+            self.stop_time = Some(Instant::now());
+        }
+    }
+
+    /// Returns whether the measurement window has been opened.
+    pub fn is_measuring(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    /// Returns the host time spent inside the measurement window.
+    ///
+    /// # Returns
+    ///
+    /// The elapsed window length, or zero if measurement never started.
+    pub fn host_elapsed(&self) -> Duration {
+        match (self.start_time, self.stop_time) {
+            (Some(start), Some(stop)) => stop.duration_since(start),
+            (Some(start), None) => start.elapsed(),
+            (None, _) => Duration::ZERO,
+        }
+    }
+
+    /// Returns simulated millions of instructions per host second over the window.
+    ///
+    /// # Returns
+    ///
+    /// MIPS, or `0.0` if no host time has elapsed.
+    pub fn mips(&self) -> f64 {
+        let seconds = self.host_elapsed().as_secs_f64();
+        if seconds <= 0.0 {
+            return 0.0;
+        }
+        let insts = self
+            .instructions_retired
+            .wrapping_sub(self.start_instructions);
+        (insts as f64 / seconds) / 1_000_000.0
+    }
+
+    /// Returns simulated kilocycles per host second over the window.
+    ///
+    /// # Returns
+    ///
+    /// Simulated frequency in kHz, or `0.0` if no host time has elapsed.
+    pub fn khz(&self) -> f64 {
+        let seconds = self.host_elapsed().as_secs_f64();
+        if seconds <= 0.0 {
+            return 0.0;
+        }
+        let cycles = self.cycles.wrapping_sub(self.start_cycles);
+        (cycles as f64 / seconds) / 1000.0
+    }
+
+    /// Returns misses per thousand retired instructions.
+    ///
+    /// # Arguments
+    ///
+    /// * `misses` - Miss count of a cache or TLB (e.g. `dcache_misses`).
+    ///
+    /// # Returns
+    ///
+    /// MPKI, or `0.0` before any instruction has retired.
+    pub fn mpki(&self, misses: u64) -> f64 {
+        if self.instructions_retired == 0 {
+            return 0.0;
+        }
+        misses as f64 * 1000.0 / self.instructions_retired as f64
+    }
+
+    /// Returns the average main memory access latency in cycles.
+    pub fn mem_latency(&self) -> f64 {
+        if self.mem_accesses == 0 {
+            return 0.0;
+        }
+        self.mem_access_cycles as f64 / self.mem_accesses as f64
+    }
+
+    /// Estimates the average memory access time at each enabled cache level.
+    ///
+    /// Uses the configured latencies in [`SimStats::cache_latencies`], the
+    /// observed miss rates, and the measured main memory latency.
+    pub fn amat(&self) -> Amat {
+        let miss_rate = |hits: u64, misses: u64| {
+            let total = hits + misses;
+            if total == 0 {
+                0.0
+            } else {
+                misses as f64 / total as f64
+            }
+        };
+        let level = |latency: Option<u64>, hits, misses, below: f64| {
+            latency.map(|l| l as f64 + miss_rate(hits, misses) * below)
+        };
+        let lat = self.cache_latencies;
+
+        let l3 = level(lat.l3, self.l3_hits, self.l3_misses, self.mem_latency());
+        let below_l2 = l3.unwrap_or_else(|| self.mem_latency());
+        let l2 = level(lat.l2, self.l2_hits, self.l2_misses, below_l2);
+        let l2_i = level(lat.l2_i, self.l2_i_hits, self.l2_i_misses, below_l2);
+        let l1_d = level(
+            lat.l1_d,
+            self.dcache_hits,
+            self.dcache_misses,
+            l2.unwrap_or(below_l2),
+        );
+        let l1_i = level(
+            lat.l1_i,
+            self.icache_hits,
+            self.icache_misses,
+            l2_i.or(l2).unwrap_or(below_l2),
+        );
+        Amat {
+            l1_i,
+            l1_d,
+            l2,
+            l2_i,
+            l3,
+        }
+    }
+
     /// Prints only the requested statistics sections to stdout.
     ///
     /// Each element of `sections` should be one of `"summary"`, `"core"`, `"instruction_mix"`,
-    /// `"branch"`, or `"memory"`. Pass an empty slice to print all sections (same as `print()`).
+    /// `"branch"`, `"memory"`, or `"mmu"`. Pass an empty slice to print all sections (same as `print()`).
     ///
     /// # Arguments
     ///
@@ -145,8 +492,7 @@ impl SimStats {
     /// - All floating-point divisions use these protected values
     pub fn print_sections(&self, sections: &[String]) {
         let want = |s: &str| sections.is_empty() || sections.iter().any(|x| x == s);
-        let duration = self.start_time.elapsed();
-        let seconds = duration.as_secs_f64();
+        let seconds = self.host_elapsed().as_secs_f64();
         let cyc = if self.cycles == 0 { 1 } else { self.cycles };
         let instr = if self.instructions_retired == 0 {
             1
@@ -157,8 +503,8 @@ impl SimStats {
         if want("summary") {
             let ipc = self.instructions_retired as f64 / cyc as f64;
             let cpi = cyc as f64 / instr as f64;
-            let mips = (self.instructions_retired as f64 / seconds) / 1_000_000.0;
-            let khz = (self.cycles as f64 / seconds) / 1000.0;
+            let mips = self.mips();
+            let khz = self.khz();
             println!("\n==========================================================");
             println!("RISC-V SYSTEM SIMULATION STATISTICS");
             println!("==========================================================");
@@ -188,6 +534,13 @@ impl SimStats {
                 self.cycles_machine,
                 (self.cycles_machine as f64 / cyc as f64) * 100.0
             );
+            if self.cycles_idle > 0 {
+                println!(
+                    "  cycles.idle            {} ({:.2}%)",
+                    self.cycles_idle,
+                    (self.cycles_idle as f64 / cyc as f64) * 100.0
+                );
+            }
             println!(
                 "  stalls.memory          {} ({:.2}%)",
                 self.stalls_mem,
@@ -203,6 +556,29 @@ impl SimStats {
                 self.stalls_data,
                 (self.stalls_data as f64 / cyc as f64) * 100.0
             );
+            if self.stalls_fpu > 0 {
+                println!(
+                    "  stalls.fpu             {} ({:.2}%)",
+                    self.stalls_fpu,
+                    (self.stalls_fpu as f64 / cyc as f64) * 100.0
+                );
+            }
+            if self.stalls_div > 0 {
+                println!(
+                    "  stalls.div             {} ({:.2}%)",
+                    self.stalls_div,
+                    (self.stalls_div as f64 / cyc as f64) * 100.0
+                );
+            }
+            if self.fused_pairs > 0 {
+                println!("  fused.pairs            {}", self.fused_pairs);
+            }
+            if self.traps_taken > 0 {
+                println!("  traps.taken            {}", self.traps_taken);
+                for (cause, count) in &self.traps_by_cause {
+                    println!("    {:<28} {}", cause, count);
+                }
+            }
             println!("----------------------------------------------------------");
         }
         if want("instruction_mix") {
@@ -253,6 +629,16 @@ impl SimStats {
             println!("  bp.lookups             {}", bp_total);
             println!("  bp.mispredicts         {}", bp_miss);
             println!("  bp.accuracy            {:.2}%", bp_acc);
+            for (category, &count) in &self.branches_by_type {
+                let miss = self.mispredicts_by_type.get(category).copied().unwrap_or(0);
+                println!(
+                    "    {:<20} {:>10} | mispredicts: {:<10} ({:.2}%)",
+                    category,
+                    count,
+                    miss,
+                    100.0 * miss as f64 / count as f64
+                );
+            }
             println!("----------------------------------------------------------");
         }
         if want("memory") {
@@ -264,18 +650,94 @@ impl SimStats {
                     0.0
                 };
                 println!(
-                    "  {:<6} accesses: {:<10} | hits: {:<10} | miss_rate: {:.2}%",
+                    "  {:<6} accesses: {:<10} | hits: {:<10} | miss_rate: {:.2}% | mpki: {:.2}",
                     name,
                     total,
                     hits,
-                    100.0 - rate
+                    100.0 - rate,
+                    self.mpki(misses)
                 );
             };
             println!("MEMORY HIERARCHY");
             print_cache("L1-I", self.icache_hits, self.icache_misses);
             print_cache("L1-D", self.dcache_hits, self.dcache_misses);
-            print_cache("L2", self.l2_hits, self.l2_misses);
+            if self.l2_i_hits + self.l2_i_misses > 0 {
+                print_cache("L2-I", self.l2_i_hits, self.l2_i_misses);
+                print_cache("L2-D", self.l2_hits, self.l2_misses);
+            } else {
+                print_cache("L2", self.l2_hits, self.l2_misses);
+            }
             print_cache("L3", self.l3_hits, self.l3_misses);
+            let amat = self.amat();
+            let levels = [
+                ("L1-I", amat.l1_i),
+                ("L1-D", amat.l1_d),
+                (if amat.l2_i.is_some() { "L2-D" } else { "L2" }, amat.l2),
+                ("L2-I", amat.l2_i),
+                ("L3", amat.l3),
+            ];
+            let amat_line: Vec<String> = levels
+                .iter()
+                .filter_map(|&(name, cycles)| cycles.map(|c| format!("{name}: {c:.2}")))
+                .collect();
+            if !amat_line.is_empty() {
+                println!("  amat.cycles            {}", amat_line.join(" | "));
+            }
+            if self.mem_accesses > 0 {
+                println!("  mem.avg_latency        {:.2}", self.mem_latency());
+            }
+            if self.l3_port_stalls > 0 {
+                println!(
+                    "  l3.port_stall_cycles   {} ({} accesses)",
+                    self.l3_port_stall_cycles, self.l3_port_stalls
+                );
+            }
+            if self.icache_snoop_invalidations > 0 {
+                println!(
+                    "  l1i.snoop_invalidations {}",
+                    self.icache_snoop_invalidations
+                );
+            }
+            if self.mshr_overlapped_misses + self.mshr_merged_misses > 0 {
+                println!(
+                    "  mshr.overlapped        {} (merged: {}, full stall cycles: {})",
+                    self.mshr_overlapped_misses, self.mshr_merged_misses, self.stalls_mshr_full
+                );
+            }
+            if self.victim_hits > 0 {
+                println!("  victim.hits            {}", self.victim_hits);
+            }
+            if self.mem_queued_requests > 0 {
+                println!(
+                    "  dram.queue_cycles      {} ({} requests)",
+                    self.mem_queue_cycles, self.mem_queued_requests
+                );
+            }
+            if self.wrong_path_icache_fills + self.wrong_path_dcache_fills > 0 {
+                println!(
+                    "  wrong_path.fills       L1-I: {} | L1-D: {}",
+                    self.wrong_path_icache_fills, self.wrong_path_dcache_fills
+                );
+            }
+            println!("----------------------------------------------------------");
+        }
+        if want("mmu") {
+            let print_tlb = |name: &str, hits: u64, misses: u64| {
+                let total = hits + misses;
+                let rate = if total > 0 {
+                    (hits as f64 / total as f64) * 100.0
+                } else {
+                    0.0
+                };
+                println!(
+                    "  {:<6} accesses: {:<10} | hits: {:<10} | hit_rate: {:.2}%",
+                    name, total, hits, rate
+                );
+            };
+            println!("MMU");
+            print_tlb("ITLB", self.itlb_hits, self.itlb_misses);
+            print_tlb("DTLB", self.dtlb_hits, self.dtlb_misses);
+            println!("  page_walks             {}", self.page_walks);
         }
         println!("==========================================================");
     }
@@ -286,4 +748,184 @@ impl SimStats {
     pub fn print(&self) {
         self.print_sections(&[]);
     }
+
+    /// Returns every counter and derived metric as a JSON document.
+    ///
+    /// The top-level keys are the [`STATS_SECTIONS`], and each section uses the same
+    /// metric names as the text report (`sim_ipc`, `cycles.user`, `bp.accuracy`, ...).
+    /// Every key is always present, so the schema does not depend on which counters are
+    /// nonzero; AMAT is `null` for a disabled cache level. Rates are percentages, as in
+    /// the text report.
+    pub fn to_json(&self) -> String {
+        let cyc = self.cycles.max(1) as f64;
+        let instr = self.instructions_retired.max(1) as f64;
+        let percent = |part: u64, whole: u64| {
+            if whole == 0 {
+                0.0
+            } else {
+                100.0 * part as f64 / whole as f64
+            }
+        };
+        let bp_lookups = self.branch_predictions + self.branch_mispredictions;
+        let by_type: BTreeMap<_, _> = self
+            .branches_by_type
+            .iter()
+            .map(|(&category, &count)| {
+                let miss = self.mispredicts_by_type.get(category).copied().unwrap_or(0);
+                (category, json!({ "count": count, "mispredicts": miss }))
+            })
+            .collect();
+        let cache = |hits: u64, misses: u64, amat: Option<f64>| {
+            json!({
+                "accesses": hits + misses,
+                "hits": hits,
+                "misses": misses,
+                "miss_rate": percent(misses, hits + misses),
+                "mpki": self.mpki(misses),
+                "amat": amat,
+            })
+        };
+        let tlb = |hits: u64, misses: u64| {
+            json!({
+                "accesses": hits + misses,
+                "hits": hits,
+                "misses": misses,
+                "hit_rate": percent(hits, hits + misses),
+            })
+        };
+        let amat = self.amat();
+
+        let stats = json!({
+            "summary": {
+                "host_seconds": self.host_elapsed().as_secs_f64(),
+                "sim_cycles": self.cycles,
+                "sim_freq_khz": self.khz(),
+                "sim_insts": self.instructions_retired,
+                "sim_ipc": self.instructions_retired as f64 / cyc,
+                "sim_cpi": cyc / instr,
+                "sim_mips": self.mips(),
+            },
+            "core": {
+                "cycles.user": self.cycles_user,
+                "cycles.kernel": self.cycles_kernel,
+                "cycles.machine": self.cycles_machine,
+                "cycles.idle": self.cycles_idle,
+                "stalls.memory": self.stalls_mem,
+                "stalls.control": self.stalls_control,
+                "stalls.data": self.stalls_data,
+                "stalls.fpu": self.stalls_fpu,
+                "stalls.div": self.stalls_div,
+                "fused.pairs": self.fused_pairs,
+                "traps.taken": self.traps_taken,
+                "traps.by_cause": self.traps_by_cause,
+            },
+            "instruction_mix": {
+                "op.alu": self.inst_alu,
+                "op.load": self.inst_load,
+                "op.store": self.inst_store,
+                "op.branch": self.inst_branch,
+                "op.system": self.inst_system,
+                "op.fp_load": self.inst_fp_load,
+                "op.fp_store": self.inst_fp_store,
+                "op.fp_arith": self.inst_fp_arith,
+                "op.fp_fma": self.inst_fp_fma,
+                "op.fp_div_sqrt": self.inst_fp_div_sqrt,
+            },
+            "branch": {
+                "bp.lookups": bp_lookups,
+                "bp.mispredicts": self.branch_mispredictions,
+                "bp.accuracy": percent(self.branch_predictions, bp_lookups),
+                "bp.by_type": by_type,
+            },
+            "memory": {
+                "L1-I": cache(self.icache_hits, self.icache_misses, amat.l1_i),
+                "L1-D": cache(self.dcache_hits, self.dcache_misses, amat.l1_d),
+                "L2": cache(self.l2_hits, self.l2_misses, amat.l2),
+                "L2-I": cache(self.l2_i_hits, self.l2_i_misses, amat.l2_i),
+                "L3": cache(self.l3_hits, self.l3_misses, amat.l3),
+                "mem.accesses": self.mem_accesses,
+                "mem.access_cycles": self.mem_access_cycles,
+                "mem.avg_latency": self.mem_latency(),
+                "l3.port_stall_cycles": self.l3_port_stall_cycles,
+                "l3.port_stalls": self.l3_port_stalls,
+                "l1i.snoop_invalidations": self.icache_snoop_invalidations,
+                "mshr.overlapped": self.mshr_overlapped_misses,
+                "mshr.merged": self.mshr_merged_misses,
+                "mshr.full_stall_cycles": self.stalls_mshr_full,
+                "victim.hits": self.victim_hits,
+                "dram.queue_cycles": self.mem_queue_cycles,
+                "dram.queued_requests": self.mem_queued_requests,
+                "wrong_path.icache_fills": self.wrong_path_icache_fills,
+                "wrong_path.dcache_fills": self.wrong_path_dcache_fills,
+            },
+            "mmu": {
+                "ITLB": tlb(self.itlb_hits, self.itlb_misses),
+                "DTLB": tlb(self.dtlb_hits, self.dtlb_misses),
+                "page_walks": self.page_walks,
+            },
+        });
+        serde_json::to_string_pretty(&stats).expect("statistics serialize to JSON")
+    }
+}
+
+/// Rolling-window throughput meter for periodic progress readouts.
+///
+/// Each call to [`ProgressMeter::sample`] reports the instantaneous rate since
+/// the previous sample rather than the run-wide average, so slow phases (e.g.
+/// boot vs. steady-state) are visible while the simulation runs.
+pub struct ProgressMeter {
+    /// Host time of the previous sample.
+    last_time: Instant,
+    /// Retired-instruction count at the previous sample.
+    last_instructions: u64,
+    /// Cycle count at the previous sample.
+    last_cycles: u64,
+    /// Minimum host time between reported samples.
+    interval: Duration,
+}
+
+impl ProgressMeter {
+    /// Creates a meter that reports at most once per `interval`.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - Current statistics, used as the initial sample.
+    /// * `interval` - Minimum host time between reported samples.
+    pub fn new(stats: &SimStats, interval: Duration) -> Self {
+        Self {
+            last_time: Instant::now(),
+            last_instructions: stats.instructions_retired,
+            last_cycles: stats.cycles,
+            interval,
+        }
+    }
+
+    /// Takes a sample if at least one interval has passed since the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - Current statistics.
+    ///
+    /// # Returns
+    ///
+    /// `Some((mips, khz))` for the window since the previous sample, or `None`
+    /// if the interval has not yet elapsed.
+    pub fn sample(&mut self, stats: &SimStats) -> Option<(f64, f64)> {
+        let now = Instant::now();
+        let seconds = now.duration_since(self.last_time).as_secs_f64();
+        if seconds < self.interval.as_secs_f64() || seconds <= 0.0 {
+            return None;
+        }
+        let insts = stats
+            .instructions_retired
+            .wrapping_sub(self.last_instructions);
+        let cycles = stats.cycles.wrapping_sub(self.last_cycles);
+        self.last_time = now;
+        self.last_instructions = stats.instructions_retired;
+        self.last_cycles = stats.cycles;
+        Some((
+            (insts as f64 / seconds) / 1_000_000.0,
+            (cycles as f64 / seconds) / 1000.0,
+        ))
+    }
 }
@@ -0,0 +1,70 @@
+pub struct ElfBuilder {
+    entry: u64,
+    machine: u16,
+    /// `(p_type, p_paddr, file bytes, p_memsz)` of each program header.
+    segments: Vec<(u32, u64, Vec<u8>, u64)>,
+}
+
+impl ElfBuilder {
+    pub fn new(entry: u64) -> Self {
+        Self {
+            entry,
+            machine: 0xF3,
+            segments: Vec::new(),
+        }
+    }
+
+    pub fn machine(mut self, machine: u16) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    /// Adds a `PT_LOAD` segment; `memsz` beyond `data.len()` is BSS.
+    pub fn load(mut self, paddr: u64, data: &[u8], memsz: u64) -> Self {
+        self.segments.push((1, paddr, data.to_vec(), memsz));
+        self
+    }
+
+    /// Adds a non-loadable segment (`PT_NOTE`).
+    pub fn note(mut self, paddr: u64, data: &[u8]) -> Self {
+        self.segments
+            .push((4, paddr, data.to_vec(), data.len() as u64));
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        const EHDR_SIZE: usize = 64;
+        const PHDR_SIZE: usize = 56;
+
+        let mut elf = vec![0u8; EHDR_SIZE];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2; // ELFCLASS64
+        elf[5] = 1; // little-endian
+        elf[6] = 1; // EV_CURRENT
+        elf[0x10..0x12].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        elf[0x12..0x14].copy_from_slice(&self.machine.to_le_bytes());
+        elf[0x18..0x20].copy_from_slice(&self.entry.to_le_bytes());
+        elf[0x20..0x28].copy_from_slice(&(EHDR_SIZE as u64).to_le_bytes());
+        elf[0x34..0x36].copy_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        elf[0x38..0x3A].copy_from_slice(&(self.segments.len() as u16).to_le_bytes());
+
+        let mut offset = (EHDR_SIZE + PHDR_SIZE * self.segments.len()) as u64;
+        for (kind, paddr, data, memsz) in &self.segments {
+            let mut ph = vec![0u8; PHDR_SIZE];
+            ph[..4].copy_from_slice(&kind.to_le_bytes());
+            ph[0x08..0x10].copy_from_slice(&offset.to_le_bytes());
+            // Virtual addresses differ from physical ones to catch a loader using p_vaddr.
+            ph[0x10..0x18].copy_from_slice(&(paddr | 0xFFFF_FFC0_0000_0000).to_le_bytes());
+            ph[0x18..0x20].copy_from_slice(&paddr.to_le_bytes());
+            ph[0x20..0x28].copy_from_slice(&(data.len() as u64).to_le_bytes());
+            ph[0x28..0x30].copy_from_slice(&memsz.to_le_bytes());
+            elf.extend(ph);
+            offset += data.len() as u64;
+        }
+        for (_, _, data, _) in &self.segments {
+            elf.extend(data);
+        }
+        elf
+    }
+}
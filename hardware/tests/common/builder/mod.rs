@@ -1,2 +1,3 @@
+pub mod elf;
 pub mod instruction;
 pub mod pipeline_state;
@@ -3,6 +3,7 @@ use riscv_core::config::Config;
 use riscv_core::core::Cpu;
 use riscv_core::soc::System;
 use riscv_core::soc::interconnect::Bus;
+use riscv_core::soc::memory::controller::BandwidthQueue;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
@@ -12,18 +13,23 @@ pub struct TestContext {
 
 impl TestContext {
     pub fn new() -> Self {
+        Self::from_config(&Config::default())
+    }
+
+    /// Build a context whose CPU is constructed from `config` (mock bus and memory controller).
+    pub fn from_config(config: &Config) -> Self {
         let _ = env_logger::builder().is_test(true).try_init();
 
-        let config = Config::default();
         let bus = Bus::new(8, 0);
 
         let system = System {
             bus,
             mem_controller: Box::new(MockMemoryController::new(1)),
+            mem_bandwidth: BandwidthQueue::new(0),
             exit_request: Arc::new(AtomicU64::new(u64::MAX)),
         };
 
-        let mut cpu = Cpu::new(system, &config);
+        let mut cpu = Cpu::new(system, config).unwrap();
 
         // In tests, bypass the expensive simulate_memory_access path.
         // The default mmio_base == ram_base (0x8000_0000), which routes all
@@ -127,6 +127,10 @@ impl Device for MockMemory {
         }
     }
 
+    fn is_memory(&self) -> bool {
+        true
+    }
+
     fn as_memory_mut(&mut self) -> Option<&mut Memory> {
         // We cannot downcast to real Memory because we are not it.
         // Return None.
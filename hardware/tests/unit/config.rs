@@ -0,0 +1,178 @@
+//! Configuration Validation Tests.
+//!
+//! Verifies `Config::from_json` and `Config::validate`:
+//!   1. The default configuration is valid
+//!   2. An unknown branch predictor name is a parse error naming the variant
+//!   3. Every broken invariant is reported, not just the first
+//!   4. Line sizes must not shrink from an inner to an outer cache level
+//!   5. Cache policy, prefetcher, and memory controller names parse into their enums,
+//!      and a misspelled name is a parse error
+//!   6. Sizes accept decimal, `0x` hex, and K/M/G suffixes; anything else is a parse error
+
+use riscv_core::config::{
+    BranchPredictor, CacheConfig, Config, ConfigError, MemoryController, Prefetcher,
+    ReplacementPolicy, parse_size,
+};
+
+/// Builds a minimal JSON config with the given `pipeline` section.
+fn json_with_pipeline(pipeline: &str) -> String {
+    format!(
+        r#"{{"general": {{}}, "system": {{}}, "memory": {{}}, "cache": {{
+            "l1_i": {{}}, "l1_d": {{}}, "l2": {{}}, "l3": {{}}
+        }}, "pipeline": {pipeline}}}"#
+    )
+}
+
+/// Builds a minimal JSON config with the given `memory` and `l1_d` sections.
+fn json_with_memory_and_l1_d(memory: &str, l1_d: &str) -> String {
+    format!(
+        r#"{{"general": {{}}, "system": {{}}, "memory": {memory}, "cache": {{
+            "l1_i": {{}}, "l1_d": {l1_d}, "l2": {{}}, "l3": {{}}
+        }}, "pipeline": {{}}}}"#
+    )
+}
+
+/// Returns the violations of an invalid config, failing if it is valid.
+fn violations(config: &Config) -> Vec<String> {
+    match config.validate() {
+        Err(ConfigError::Invalid(v)) => v,
+        other => panic!("expected violations, got {other:?}"),
+    }
+}
+
+#[test]
+fn default_config_is_valid() {
+    assert_eq!(Config::default().validate(), Ok(()));
+    let config = Config::from_json(&json_with_pipeline(r#"{"branch_predictor": "GShare"}"#))
+        .expect("valid JSON config");
+    assert_eq!(config.pipeline.branch_predictor, BranchPredictor::GShare);
+}
+
+#[test]
+fn unknown_branch_predictor_is_rejected() {
+    let err = Config::from_json(&json_with_pipeline(r#"{"branch_predictor": "Oracle"}"#))
+        .expect_err("unknown predictor must not fall back silently");
+    let ConfigError::Parse(msg) = &err else {
+        panic!("expected a parse error, got {err:?}");
+    };
+    assert!(msg.contains("unknown variant `Oracle`"), "{msg}");
+    assert!(
+        msg.contains("Perceptron"),
+        "lists the known variants: {msg}"
+    );
+}
+
+#[test]
+fn all_violations_are_listed() {
+    let mut config = Config::default();
+    config.system.kernel_offset = config.memory.ram_size as u64;
+    config.memory.tlb_size = 0;
+    config.pipeline.btb_size = 1000;
+    config.pipeline.branch_predictor = BranchPredictor::Tage;
+    config.pipeline.tage.table_size = 3;
+    config.pipeline.tage.loop_table_size = 4;
+
+    let v = violations(&config);
+    let has = |needle: &str| v.iter().any(|m| m.contains(needle));
+    assert!(has("system.kernel_offset"), "{v:?}");
+    assert!(has("memory.tlb_size"), "{v:?}");
+    assert!(has("pipeline.btb_size (1000)"), "{v:?}");
+    assert!(has("pipeline.tage.table_size (3)"), "{v:?}");
+    assert!(!has("loop_table_size"), "{v:?}");
+
+    let text = config.validate().unwrap_err().to_string();
+    assert_eq!(text.lines().count(), 1 + v.len(), "one line per violation");
+}
+
+#[test]
+fn cache_geometry_and_line_nesting_are_checked() {
+    let cache = |line_bytes| CacheConfig {
+        enabled: true,
+        size_bytes: 4096,
+        line_bytes,
+        ways: 2,
+        ..Default::default()
+    };
+    let mut config = Config::default();
+    config.cache.l1_d = cache(64);
+    config.cache.l2 = cache(32);
+    config.cache.l1_i = CacheConfig {
+        size_bytes: 3000,
+        ..cache(32)
+    };
+
+    let v = violations(&config);
+    assert!(
+        v.contains(&"cache.l2.line_bytes (32) is smaller than cache.l1_d.line_bytes (64)".into()),
+        "{v:?}"
+    );
+    assert!(v.iter().any(|m| m.starts_with("cache.l1_i: ")), "{v:?}");
+
+    config.cache.l2 = cache(64);
+    config.cache.l1_i = cache(32);
+    assert_eq!(config.validate(), Ok(()), "outer lines may be larger");
+}
+
+#[test]
+fn cache_and_memory_enum_names_parse() {
+    let config = Config::from_json(&json_with_memory_and_l1_d(
+        r#"{"controller": "Dram"}"#,
+        r#"{"policy": "PLRU", "prefetcher": "Stride"}"#,
+    ))
+    .expect("valid JSON config");
+    assert_eq!(config.memory.controller, MemoryController::Dram);
+    assert_eq!(config.cache.l1_d.policy, ReplacementPolicy::Plru);
+    assert_eq!(config.cache.l1_d.prefetcher, Prefetcher::Stride);
+}
+
+#[test]
+fn misspelled_enum_names_are_rejected() {
+    let cases = [
+        (r#"{}"#, r#"{"policy": "LRUu"}"#, "LRUu"),
+        (r#"{}"#, r#"{"prefetcher": "Strided"}"#, "Strided"),
+        (r#"{"controller": "DDR"}"#, r#"{}"#, "DDR"),
+    ];
+    for (memory, l1_d, name) in cases {
+        let err = Config::from_json(&json_with_memory_and_l1_d(memory, l1_d))
+            .expect_err("misspelled name must not fall back to a default");
+        let ConfigError::Parse(msg) = &err else {
+            panic!("expected a parse error, got {err:?}");
+        };
+        assert!(msg.contains(&format!("unknown variant `{name}`")), "{msg}");
+    }
+}
+
+#[test]
+fn size_spellings_resolve_to_the_same_byte_count() {
+    for text in ["128M", "0x8000000", "134217728", "128MiB", "131072K"] {
+        assert_eq!(parse_size(text), Ok(134_217_728), "{text}");
+    }
+    assert_eq!(parse_size("2G"), Ok(2 << 30));
+    assert_eq!(parse_size("32k"), Ok(32 << 10));
+}
+
+#[test]
+fn config_sizes_accept_strings_and_integers() {
+    let config = Config::from_json(&json_with_memory_and_l1_d(
+        r#"{"ram_size": "128M"}"#,
+        r#"{"size_bytes": "32K", "line_bytes": 64}"#,
+    ))
+    .expect("valid JSON config");
+    assert_eq!(config.memory.ram_size, 128 << 20);
+    assert_eq!(config.cache.l1_d.size_bytes, 32 << 10);
+    assert_eq!(config.cache.l1_d.line_bytes, 64);
+}
+
+#[test]
+fn unparseable_size_is_rejected() {
+    assert!(parse_size("128Q").is_err());
+    assert!(parse_size("0xZZ").is_err());
+    assert!(parse_size("99999999999G").is_err(), "overflow");
+
+    let err = Config::from_json(&json_with_memory_and_l1_d(r#"{"ram_size": "lots"}"#, "{}"))
+        .expect_err("must not fall back to the default size");
+    let ConfigError::Parse(msg) = &err else {
+        panic!("expected a parse error, got {err:?}");
+    };
+    assert!(msg.contains(r#"invalid size "lots""#), "{msg}");
+}
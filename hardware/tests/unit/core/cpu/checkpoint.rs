@@ -0,0 +1,157 @@
+//! Checkpoint and Restore Tests.
+//!
+//! Verifies `Cpu::save_checkpoint` and `Cpu::load_checkpoint`:
+//!   1. Restoring and re-running reproduces the registers and RAM of the run that
+//!      continued after the save, on the same CPU or a freshly built one
+//!   2. Files that are not checkpoints, or that do not fit the system, are rejected
+//!      before any CPU, RAM, or device state changes
+
+use crate::common::harness::TestContext;
+use riscv_core::core::cpu::checkpoint::CheckpointError;
+use riscv_core::soc::devices::Nvram;
+use riscv_core::soc::memory::Memory;
+use riscv_core::soc::memory::buffer::DramBuffer;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const BASE: u64 = 0x8000_0000;
+const RAM_SIZE: usize = 0x1000;
+/// Address the loop stores its counter to.
+const SLOT: u64 = BASE + 0x200;
+/// Base of the NVRAM added by [`context_with_nvram`].
+const NVRAM_BASE: u64 = 0x9000_0000;
+
+/// Counts in `x5`, round-trips it through RAM, and accumulates it in `x8`.
+const PROGRAM: [u32; 6] = [
+    0x0000_0317, // auipc x6, 0
+    0x0012_8293, // loop: addi x5, x5, 1
+    0x2053_3023, //       sd   x5, 0x200(x6)
+    0x2003_3383, //       ld   x7, 0x200(x6)
+    0x0074_0433, //       add  x8, x8, x7
+    0xFF1F_F06F, //       jal  x0, loop
+];
+
+/// Returns a per-test host path, removing any leftover file from a previous run.
+fn temp_path(tag: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ckpt_{}_{}.bin", std::process::id(), tag));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// Builds a CPU with `ram_size` bytes of real DRAM at `BASE` holding the loop.
+fn context(ram_size: usize) -> TestContext {
+    let mut tc = TestContext::new();
+    let ram = Memory::new(Arc::new(DramBuffer::new(ram_size)), BASE);
+    tc.cpu.bus.bus.add_device(Box::new(ram));
+    tc.load_program(BASE, &PROGRAM)
+}
+
+/// Builds [`context`] with an NVRAM of `nvram_size` bytes after the RAM.
+fn context_with_nvram(nvram_size: usize) -> TestContext {
+    let mut tc = context(RAM_SIZE);
+    let nvram = Nvram::new(NVRAM_BASE, nvram_size);
+    tc.cpu.bus.bus.add_device(Box::new(nvram));
+    tc
+}
+
+/// Integer registers, PC, and the stored counter.
+fn snapshot(tc: &mut TestContext) -> (Vec<u64>, u64, u64) {
+    let regs = (0..32).map(|i| tc.get_reg(i)).collect();
+    (regs, tc.cpu.pc, tc.cpu.bus.bus.read_u64(SLOT))
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Deterministic resume
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn restore_replays_the_same_cycles() {
+    let path = temp_path("replay");
+    let mut tc = context(RAM_SIZE);
+    tc.run(200);
+    tc.cpu.save_checkpoint(&path).unwrap();
+    let saved = snapshot(&mut tc);
+
+    tc.run(300);
+    let expected = snapshot(&mut tc);
+    assert_ne!(expected, saved, "the loop made progress after the save");
+
+    tc.cpu.load_checkpoint(&path).unwrap();
+    assert_eq!(snapshot(&mut tc), saved);
+    tc.run(300);
+    assert_eq!(snapshot(&mut tc), expected);
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn restore_into_fresh_cpu_matches_continued_run() {
+    let path = temp_path("fresh");
+    let mut tc = context(RAM_SIZE);
+    tc.run(150);
+    tc.cpu.save_checkpoint(&path).unwrap();
+    let cycles = tc.cpu.stats.cycles;
+    tc.run(400);
+    let expected = snapshot(&mut tc);
+
+    let mut restored = context(RAM_SIZE);
+    restored.cpu.load_checkpoint(&path).unwrap();
+    assert_eq!(restored.cpu.stats.cycles, cycles);
+    restored.run(400);
+    assert_eq!(snapshot(&mut restored), expected);
+    assert_eq!(
+        restored.cpu.stats.instructions_retired,
+        tc.cpu.stats.instructions_retired
+    );
+    let _ = fs::remove_file(&path);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Rejected files
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn non_checkpoint_file_is_rejected() {
+    let path = temp_path("garbage");
+    fs::write(&path, b"definitely not a checkpoint file").unwrap();
+    let mut tc = context(RAM_SIZE);
+    let err = tc.cpu.load_checkpoint(&path).unwrap_err();
+    assert!(matches!(err, CheckpointError::Format(_)), "{err}");
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn ram_size_mismatch_is_rejected_before_cpu_state_changes() {
+    let path = temp_path("mismatch");
+    let mut tc = context(RAM_SIZE);
+    tc.run(100);
+    tc.cpu.save_checkpoint(&path).unwrap();
+
+    let mut other = context(RAM_SIZE * 2);
+    let err = other.cpu.load_checkpoint(&path).unwrap_err();
+    assert!(matches!(err, CheckpointError::Mismatch(_)), "{err}");
+    assert_eq!(other.cpu.pc, BASE);
+    assert_eq!(other.get_reg(5), 0);
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn late_device_mismatch_leaves_ram_untouched() {
+    let path = temp_path("late_mismatch");
+    let mut tc = context_with_nvram(0x100);
+    tc.run(100);
+    tc.cpu.save_checkpoint(&path).unwrap();
+
+    // The RAM snapshot comes first and would zero this word; the NVRAM size differs.
+    let mut other = context_with_nvram(0x200);
+    other.cpu.bus.bus.write_u64(SLOT, 0xDEAD_BEEF);
+    let err = other.cpu.load_checkpoint(&path).unwrap_err();
+    assert!(matches!(err, CheckpointError::Mismatch(_)), "{err}");
+    assert_eq!(
+        other.cpu.bus.bus.read_u64(SLOT),
+        0xDEAD_BEEF,
+        "RAM untouched"
+    );
+    assert_eq!(other.cpu.pc, BASE);
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,126 @@
+//! Commit Hook Tests.
+//!
+//! Verifies `Cpu::set_commit_hook`:
+//!   1. Loads seen by the hook match the retired-load statistic
+//!   2. Each record carries the destination write and memory access
+//!   3. Wrong-path instructions squashed by a taken branch never reach the hook
+
+use crate::common::harness::TestContext;
+use riscv_core::core::cpu::commit_hook::{CommitInfo, MemAccess, RegWrite};
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+/// `jal x0, 0` — spin in place.
+const SPIN: u32 = 0x0000_006F;
+
+/// Installs a hook that records every commit into the returned buffer.
+fn record_commits(tc: &mut TestContext) -> Arc<Mutex<Vec<CommitInfo>>> {
+    let commits = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&commits);
+    tc.cpu
+        .set_commit_hook(Box::new(move |info| sink.lock().unwrap().push(info)));
+    commits
+}
+
+/// Program with two loads on the committed path and one on a squashed path.
+fn load_program() -> TestContext {
+    TestContext::new().with_memory(0x1000, BASE).load_program(
+        BASE,
+        &[
+            0x0000_0317, // auipc x6, 0
+            0x0050_0293, // addi  x5, x0, 5
+            0x1053_2023, // sw    x5, 0x100(x6)
+            0x1003_2383, // lw    x7, 0x100(x6)
+            0x0000_0463, // beq   x0, x0, +8
+            0x1003_2403, // lw    x8, 0x100(x6) (skipped)
+            0x1003_3483, // ld    x9, 0x100(x6)
+            SPIN,
+        ],
+    )
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Load count
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn hook_load_count_matches_stats() {
+    let mut tc = load_program();
+    let loads = Arc::new(Mutex::new(0u64));
+    let counter = Arc::clone(&loads);
+    tc.cpu.set_commit_hook(Box::new(move |info| {
+        if info.mem.is_some_and(|m| m.read && m.stored.is_none()) {
+            *counter.lock().unwrap() += 1;
+        }
+    }));
+
+    tc.run(100);
+
+    let loads = *loads.lock().unwrap();
+    assert_eq!(loads, 2);
+    assert_eq!(loads, tc.cpu.stats.inst_load);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Commit records
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn records_carry_writeback_and_memory_effect() {
+    let mut tc = load_program();
+    let commits = record_commits(&mut tc);
+
+    tc.run(100);
+
+    let commits = commits.lock().unwrap();
+    assert_eq!(commits[1].pc, BASE + 4);
+    assert_eq!(commits[1].inst, 0x0050_0293);
+    assert_eq!(commits[1].rd, Some(RegWrite::Int(5, 5)));
+    assert_eq!(commits[1].mem, None);
+
+    assert_eq!(commits[2].rd, None);
+    assert_eq!(
+        commits[2].mem,
+        Some(MemAccess {
+            addr: BASE + 0x100,
+            size: 4,
+            read: false,
+            stored: Some(5),
+        })
+    );
+
+    assert_eq!(commits[3].rd, Some(RegWrite::Int(7, 5)));
+    assert_eq!(
+        commits[3].mem,
+        Some(MemAccess {
+            addr: BASE + 0x100,
+            size: 4,
+            read: true,
+            stored: None,
+        })
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Squashed instructions
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn squashed_instructions_do_not_fire_hook() {
+    let mut tc = load_program();
+    let commits = record_commits(&mut tc);
+
+    tc.run(100);
+
+    let commits = commits.lock().unwrap();
+    let pcs: Vec<u64> = commits.iter().take(6).map(|c| c.pc).collect();
+    assert_eq!(
+        pcs,
+        [BASE, BASE + 4, BASE + 8, BASE + 12, BASE + 16, BASE + 24]
+    );
+    assert!(
+        commits.iter().all(|c| c.pc != BASE + 20),
+        "wrong-path lw never commits"
+    );
+    assert_eq!(tc.get_reg(8), 0);
+}
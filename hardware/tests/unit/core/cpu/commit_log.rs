@@ -0,0 +1,108 @@
+//! Commit Log Tests.
+//!
+//! Verifies `Cpu::set_commit_log`:
+//!   1. Each committed instruction produces one Spike-format line with its
+//!      privilege, PC, encoding, and register or memory writeback
+//!   2. Wrong-path instructions squashed by a taken branch are not logged
+
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+/// `jal x0, 0` — spin in place.
+const SPIN: u32 = 0x0000_006F;
+
+/// `Write` sink sharing its buffer with the test.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuf {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+#[test]
+fn committed_instructions_are_logged_in_spike_format() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE).load_program(
+        BASE,
+        &[
+            0x0000_0317, // auipc x6, 0
+            0x0050_0293, // addi  x5, x0, 5
+            0x1053_2023, // sw    x5, 0x100(x6)
+            0x1003_2383, // lw    x7, 0x100(x6)
+        ],
+    );
+    tc.cpu.bus.bus.write_u16(BASE + 16, 0x440D); // c.li x8, 3
+    tc.cpu.bus.bus.write_u32(BASE + 18, SPIN);
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    let buf = SharedBuf::default();
+    tc.cpu.set_commit_log(Box::new(buf.clone()));
+
+    tc.run(100);
+
+    let lines = buf.lines();
+    assert_eq!(
+        &lines[..6],
+        [
+            "core   0: 3 0x0000000080000000 (0x00000317) x6  0x0000000080000000",
+            "core   0: 3 0x0000000080000004 (0x00500293) x5  0x0000000000000005",
+            "core   0: 3 0x0000000080000008 (0x10532023) mem 0x0000000080000100 0x00000005",
+            "core   0: 3 0x000000008000000c (0x10032383) x7  0x0000000000000005 mem 0x0000000080000100",
+            "core   0: 3 0x0000000080000010 (0x440d) x8  0x0000000000000003",
+            "core   0: 3 0x0000000080000012 (0x0000006f)",
+        ]
+    );
+    assert_eq!(
+        lines.len() as u64,
+        tc.cpu.stats.instructions_retired,
+        "one line per retired instruction"
+    );
+}
+
+#[test]
+fn squashed_instructions_are_not_logged() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE).load_program(
+        BASE,
+        &[
+            0x0000_0463, // beq  x0, x0, +8
+            0x0010_0493, // addi x9, x0, 1 (skipped)
+            0x0020_0513, // addi x10, x0, 2
+            SPIN,
+        ],
+    );
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    let buf = SharedBuf::default();
+    tc.cpu.set_commit_log(Box::new(buf.clone()));
+
+    tc.run(100);
+
+    let lines = buf.lines();
+    assert_eq!(lines[0], "core   0: 3 0x0000000080000000 (0x00000463)");
+    assert_eq!(
+        lines[1],
+        "core   0: 3 0x0000000080000008 (0x00200513) x10 0x0000000000000002"
+    );
+    assert!(
+        lines.iter().all(|l| !l.contains("0x0000000080000004")),
+        "wrong-path addi never commits"
+    );
+    assert_eq!(tc.get_reg(9), 0);
+}
@@ -0,0 +1,68 @@
+//! Cycle Hook Tests.
+//!
+//! Verifies `Cpu::set_cycle_hook`:
+//!   1. The hook runs exactly once per tick
+//!   2. The hook sees the CPU state before the cycle executes
+//!   3. `clear_cycle_hook` stops further invocations
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+
+/// Context running a tight `addi x1, x1, 1; jal x0, -4` loop.
+fn looping_ctx() -> TestContext {
+    let addi = InstructionBuilder::new().addi(1, 1, 1).build();
+    let jal = InstructionBuilder::new().jal(0, -4).build();
+    TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[addi, jal])
+}
+
+#[test]
+fn hook_called_once_per_tick() {
+    let mut tc = looping_ctx();
+    let calls = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&calls);
+    tc.cpu.set_cycle_hook(Box::new(move |_cpu| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    tc.run(50);
+
+    assert_eq!(calls.load(Ordering::Relaxed), 50);
+    assert_eq!(tc.cpu.stats.cycles, 50);
+}
+
+#[test]
+fn hook_observes_pre_cycle_state() {
+    let mut tc = looping_ctx();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    tc.cpu.set_cycle_hook(Box::new(move |cpu| {
+        log.lock().unwrap().push(cpu.stats.cycles);
+    }));
+
+    tc.run(5);
+
+    assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn cleared_hook_stops_running() {
+    let mut tc = looping_ctx();
+    let calls = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&calls);
+    tc.cpu.set_cycle_hook(Box::new(move |_cpu| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    tc.run(10);
+    assert!(tc.cpu.clear_cycle_hook().is_some());
+    tc.run(10);
+
+    assert_eq!(calls.load(Ordering::Relaxed), 10);
+    assert!(tc.cpu.clear_cycle_hook().is_none());
+}
@@ -0,0 +1,109 @@
+//! Trap Delegation Tests.
+//!
+//! Verifies that `Cpu::trap` picks the handler mode from `medeleg`/`mideleg`:
+//!   1. Delegated exceptions below M-mode fill the S-mode CSR group
+//!   2. Non-delegated exceptions fill `mepc`/`mcause`/`mtval` and enter M-mode
+//!   3. `csr_write` keeps only the legally delegatable bits
+
+use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+
+const MTVEC: u64 = 0x8000_1000;
+const STVEC: u64 = 0x8000_2000;
+const EPC: u64 = 0x8000_0040;
+const LOAD_PAGE_FAULT: u64 = 13;
+const ECALL_FROM_S: u64 = 9;
+const ECALL_FROM_M: u64 = 11;
+
+/// Hart in `privilege` with both trap vectors installed.
+fn ctx(privilege: PrivilegeMode) -> TestContext {
+    let mut tc = TestContext::new();
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = privilege;
+    tc.cpu.csrs.mtvec = MTVEC;
+    tc.cpu.csrs.stvec = STVEC;
+    tc
+}
+
+#[test]
+fn delegated_load_page_fault_taken_in_s_mode() {
+    let mut tc = ctx(PrivilegeMode::User);
+    tc.cpu.csr_write(csr::MEDELEG, 1 << LOAD_PAGE_FAULT);
+
+    tc.cpu.trap(Trap::LoadPageFault(0xdead_b000), EPC);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Supervisor);
+    assert_eq!(tc.cpu.pc, STVEC);
+    assert_eq!(tc.cpu.csrs.scause, LOAD_PAGE_FAULT);
+    assert_eq!(tc.cpu.csrs.sepc, EPC);
+    assert_eq!(tc.cpu.csrs.stval, 0xdead_b000);
+    assert_eq!(tc.cpu.csrs.mcause, 0, "M-mode trap state untouched");
+    assert_eq!(tc.cpu.csrs.mepc, 0);
+}
+
+#[test]
+fn undelegated_load_page_fault_taken_in_m_mode() {
+    let mut tc = ctx(PrivilegeMode::User);
+
+    tc.cpu.trap(Trap::LoadPageFault(0xdead_b000), EPC);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.pc, MTVEC, "stvec alone does not redirect the trap");
+    assert_eq!(tc.cpu.csrs.mcause, LOAD_PAGE_FAULT);
+    assert_eq!(tc.cpu.csrs.mepc, EPC);
+    assert_eq!(tc.cpu.csrs.mtval, 0xdead_b000);
+    assert_eq!(tc.cpu.csrs.scause, 0);
+}
+
+#[test]
+fn ecall_from_s_not_delegated_reaches_m_mode() {
+    let mut tc = ctx(PrivilegeMode::Supervisor);
+    tc.cpu.csr_write(csr::MEDELEG, 1 << LOAD_PAGE_FAULT);
+
+    tc.cpu.trap(Trap::EnvironmentCallFromSMode, EPC);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.csrs.mcause, ECALL_FROM_S);
+    assert_eq!(
+        (tc.cpu.csrs.mstatus & csr::MSTATUS_MPP) >> csr::MSTATUS_MPP_SHIFT,
+        PrivilegeMode::Supervisor.to_u8() as u64
+    );
+}
+
+#[test]
+fn ecall_from_m_never_delegated() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.csr_write(csr::MEDELEG, u64::MAX);
+    assert_eq!(tc.cpu.csrs.medeleg & (1 << ECALL_FROM_M), 0);
+
+    tc.cpu.trap(Trap::EnvironmentCallFromMMode, EPC);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.pc, MTVEC);
+    assert_eq!(tc.cpu.csrs.mcause, ECALL_FROM_M);
+    assert_eq!(tc.cpu.csrs.mepc, EPC);
+    assert_eq!(tc.cpu.csrs.sepc, 0);
+}
+
+#[test]
+fn m_mode_exceptions_ignore_delegation() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.csr_write(csr::MEDELEG, 1 << LOAD_PAGE_FAULT);
+
+    tc.cpu.trap(Trap::LoadPageFault(0x1000), EPC);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.csrs.mcause, LOAD_PAGE_FAULT);
+}
+
+#[test]
+fn mideleg_keeps_only_supervisor_interrupts() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.csr_write(csr::MIDELEG, u64::MAX);
+    assert_eq!(
+        tc.cpu.csrs.mideleg,
+        csr::MIP_SSIP | csr::MIP_STIP | csr::MIP_SEIP
+    );
+}
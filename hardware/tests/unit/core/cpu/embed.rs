@@ -0,0 +1,165 @@
+//! Embedding API Tests.
+//!
+//! Verifies the public accessors on `Cpu`:
+//!   1. `step` advances until an instruction retires and reports traps
+//!   2. `read_mem`/`write_mem` round-trip and fault on unmapped addresses, and
+//!      `peek_mem`/`poke_mem` leave the TLBs and PTE A/D bits untouched
+//!   3. `read_reg`/`write_reg` and `read_csr`/`write_csr`
+
+use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+
+const BASE: u64 = 0x8000_0000;
+/// Address with no device behind it.
+const UNMAPPED: u64 = 0x2000_0000;
+
+// ══════════════════════════════════════════════════════════
+// 1. Stepping
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn step_retires_the_next_instruction() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE).load_program(
+        BASE,
+        &[
+            0x02A0_0293, // addi x5, x0, 42
+            0x0012_8313, // addi x6, x5, 1
+        ],
+    );
+
+    let first = tc.cpu.step().unwrap();
+    assert_eq!(first.retired, 1);
+    assert_eq!(first.trap, None);
+    assert!(first.cycles > 1, "the first instruction fills the pipeline");
+    assert_eq!(tc.cpu.read_reg(5), 42);
+
+    let second = tc.cpu.step().unwrap();
+    assert_eq!(second.retired, 1);
+    assert_eq!(tc.cpu.read_reg(6), 43);
+}
+
+#[test]
+fn step_reports_a_taken_trap() {
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[0x0000_0073]); // ecall
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = riscv_core::core::arch::mode::PrivilegeMode::Machine;
+    tc.cpu.write_csr(csr::MTVEC, BASE + 0x100);
+
+    let step = tc.cpu.step().unwrap();
+    assert_eq!(step.trap, Some(Trap::EnvironmentCallFromMMode));
+    assert_eq!(tc.cpu.read_csr(csr::MEPC), BASE);
+    assert_eq!(tc.cpu.pc, BASE + 0x100);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Memory
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn memory_round_trips_through_translation() {
+    let mut tc = TestContext::new().with_memory(0x2000, BASE);
+    tc.cpu.write_mem(BASE + 0xFFE, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(tc.cpu.read_mem(BASE + 0xFFE, 4).unwrap(), [1, 2, 3, 4]);
+    assert_eq!(tc.cpu.bus.bus.read_u32(BASE + 0xFFE), 0x0403_0201);
+}
+
+#[test]
+fn unmapped_memory_faults() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    assert_eq!(
+        tc.cpu.read_mem(UNMAPPED, 4),
+        Err(Trap::LoadAccessFault(UNMAPPED))
+    );
+    assert_eq!(
+        tc.cpu.write_mem(UNMAPPED, &[0xAA]),
+        Err(Trap::StoreAccessFault(UNMAPPED))
+    );
+}
+
+#[test]
+fn write_crossing_into_unmapped_memory_writes_nothing() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    let end = BASE + 0x1000;
+    assert_eq!(
+        tc.cpu.write_mem(end - 2, &[0xAA; 4]),
+        Err(Trap::StoreAccessFault(end))
+    );
+    assert_eq!(tc.cpu.read_mem(end - 2, 2).unwrap(), [0, 0]);
+}
+
+/// Virtual address of a gigapage mapped onto `BASE` by [`paged_ctx`].
+const PAGED_VA: u64 = 0x4000_0000;
+/// Root page table of [`paged_ctx`].
+const ROOT: u64 = BASE + 0x1000;
+/// Valid, readable, and writable, with A and D clear.
+const PTE_RW_CLEAN: u64 = 0x07;
+
+/// S-mode context with Sv39 on and `PAGED_VA` mapped by a clean gigapage PTE.
+fn paged_ctx() -> TestContext {
+    let mut tc = TestContext::new().with_memory(0x2000, BASE);
+    tc.cpu
+        .bus
+        .bus
+        .write_u64(ROOT + 8, ((BASE >> 12) << 10) | PTE_RW_CLEAN);
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (ROOT >> 12);
+    tc
+}
+
+#[test]
+fn peek_and_poke_leave_translation_state_untouched() {
+    let mut tc = paged_ctx();
+    tc.cpu.poke_mem(PAGED_VA + 0x10, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(tc.cpu.peek_mem(PAGED_VA + 0x10, 4).unwrap(), [1, 2, 3, 4]);
+    assert_eq!(tc.cpu.bus.bus.read_u32(BASE + 0x10), 0x0403_0201);
+
+    assert_eq!(tc.cpu.bus.bus.read_u64(ROOT + 8) & 0xFF, PTE_RW_CLEAN);
+    assert_eq!(tc.cpu.mmu.stats, Default::default());
+    assert_eq!(tc.cpu.mmu.dtlb_paddr(PAGED_VA, 0), None);
+
+    // A guest-visible access through the same mapping does set A.
+    tc.cpu.read_mem(PAGED_VA + 0x10, 4).unwrap();
+    assert_ne!(tc.cpu.bus.bus.read_u64(ROOT + 8) & 0xFF, PTE_RW_CLEAN);
+}
+
+#[test]
+fn poke_crossing_into_unmapped_memory_writes_nothing() {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    let end = BASE + 0x1000;
+    assert_eq!(
+        tc.cpu.poke_mem(end - 2, &[0xAA; 4]),
+        Err(Trap::StoreAccessFault(end))
+    );
+    assert_eq!(tc.cpu.peek_mem(end - 2, 2).unwrap(), [0, 0]);
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Registers and CSRs
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn register_accessors() {
+    let mut tc = TestContext::new();
+    tc.cpu.write_reg(10, 0xDEAD_BEEF);
+    tc.cpu.write_reg(0, 5);
+    assert_eq!(tc.cpu.read_reg(10), 0xDEAD_BEEF);
+    assert_eq!(tc.cpu.read_reg(0), 0);
+}
+
+#[test]
+fn csr_accessors_apply_write_rules() {
+    let mut tc = TestContext::new();
+    tc.cpu.write_csr(csr::MSCRATCH, 0x1234);
+    assert_eq!(tc.cpu.read_csr(csr::MSCRATCH), 0x1234);
+    tc.cpu.write_csr(csr::MEPC, 0x8000_0003);
+    assert_eq!(
+        tc.cpu.read_csr(csr::MEPC),
+        0x8000_0002,
+        "mepc[0] is read-only zero"
+    );
+}
@@ -0,0 +1,106 @@
+//! # CPU Top-Level
+//!
+//! This module covers behavior of the `Cpu` object itself rather than an
+//! individual pipeline stage or functional unit.
+
+/// Unit tests for the embedder cycle hook.
+///
+/// This module verifies that an installed hook runs once per tick, observes
+/// the pre-cycle state, and can be removed again.
+pub mod cycle_hook;
+
+/// Unit tests for reverse stepping.
+///
+/// This module verifies that `step_back` restores registers, RAM, and the PC
+/// of previously committed instructions within the configured history depth.
+pub mod step_back;
+
+/// Unit tests for the split L2 option.
+///
+/// This module verifies that instruction- and data-origin misses use separate
+/// L2 halves when `cache.l2_split` is set, and share one cache otherwise.
+pub mod split_l2;
+
+/// Unit tests for trap tracing.
+///
+/// This module verifies that each taken exception or interrupt is logged with
+/// its full context and counted per cause in the statistics.
+pub mod trap_trace;
+
+/// Unit tests for retire-order verification.
+///
+/// This module verifies that commits follow program order, that the retired
+/// count matches the committed tally, and that violations surface as errors.
+pub mod retire_check;
+
+/// Unit tests for typed simulation errors.
+///
+/// This module verifies that `tick` reports fatal direct-mode traps as
+/// `SimError::UnhandledTrap` carrying the trap and its PC.
+pub mod sim_error;
+
+/// Unit tests for the machine monitor.
+///
+/// This module verifies that an exception with no trap vector installed is
+/// reported with its cause, instruction, and registers, and halts the run.
+pub mod monitor;
+
+/// Unit tests for data write watches.
+///
+/// This module verifies that stores overlapping a watched location report the
+/// old and new watched value with the writing PC, including partial-width stores.
+pub mod watch_write;
+
+/// Unit tests for the built-in SBI.
+///
+/// This module verifies that S-mode `ecall`s dispatch to legacy v0.1 or v0.2
+/// extension handlers by `a7` and return their results in the right registers.
+pub mod sbi;
+
+/// Unit tests for trap delegation.
+///
+/// This module verifies that `medeleg`/`mideleg` route each trap to S-mode or
+/// M-mode and that the matching CSR group records it.
+pub mod delegation;
+
+/// Unit tests for trap values.
+///
+/// This module verifies that faulting addresses and illegal encodings are
+/// written to `stval`/`mtval` for the mode that takes the trap.
+pub mod trap_value;
+
+/// Unit tests for trap vector modes.
+///
+/// This module verifies that vectored `mtvec`/`stvec` offset interrupt
+/// handlers by cause while exceptions still land on the base address.
+pub mod trap_vector;
+
+/// Unit tests for commit hooks.
+///
+/// This module verifies that the commit hook sees each retired instruction with
+/// its register and memory effects, and never sees squashed instructions.
+pub mod commit_hook;
+
+/// Unit tests for the commit log.
+///
+/// This module verifies that committed instructions are logged one per line
+/// in Spike's format and that squashed instructions never appear.
+pub mod commit_log;
+
+/// Unit tests for checkpoint and restore.
+///
+/// This module verifies that a restored run repeats the cycles that followed
+/// the save exactly, and that foreign or mismatched files are rejected.
+pub mod checkpoint;
+
+/// Unit tests for the embedding API.
+///
+/// This module verifies stepping to the next retirement, translated memory
+/// access, and the public register and CSR accessors.
+pub mod embed;
+
+/// Unit tests for the hotspot profiler.
+///
+/// This module verifies that per-PC commit counts rank a loop body first,
+/// add up to the retired total, and are not kept unless enabled.
+pub mod profile;
@@ -0,0 +1,117 @@
+//! Machine Monitor Tests.
+//!
+//! Verifies `general.monitor_mode`:
+//!   1. A fault with `mtvec` unset halts with `UnhandledTrap` instead of jumping to 0
+//!   2. The report names the cause, the faulting instruction, and the registers
+//!   3. An installed trap vector, or a disabled monitor, takes the trap normally
+//!   4. In direct mode, semihosting calls pass through and other faults halt
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::{SimError, Trap};
+use riscv_core::config::Config;
+use riscv_core::core::arch::mode::PrivilegeMode;
+
+const BASE: u64 = 0x8000_0000;
+/// `ecall` encoding.
+const ECALL: u32 = 0x0000_0073;
+/// `ebreak` encoding.
+const EBREAK: u32 = 0x0010_0073;
+
+/// `lui t0, 0x40000; addi t1, zero, 42; ecall` — the `ecall` traps from M-mode.
+fn faulting_program() -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().lui(5, 0x40000).build(),
+        b().addi(6, 0, 42).build(),
+        ECALL,
+        b().addi(28, 0, 1).build(),
+    ]
+}
+
+/// Builds a bare-metal, M-mode, non-direct context running [`faulting_program`].
+fn monitor_ctx(monitor_mode: bool) -> TestContext {
+    let mut config = Config::default();
+    config.general.direct_mode = false;
+    config.general.monitor_mode = monitor_mode;
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &faulting_program());
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc
+}
+
+/// Ticks until the first error, or `None` if none occurs within the budget.
+fn first_error(tc: &mut TestContext) -> Option<SimError> {
+    (0..100).find_map(|_| tc.cpu.tick().err())
+}
+
+#[test]
+fn fault_without_trap_vector_halts_in_monitor() {
+    let mut tc = monitor_ctx(true);
+    let err = first_error(&mut tc).expect("monitor must halt");
+
+    assert_eq!(
+        err,
+        SimError::UnhandledTrap(Trap::EnvironmentCallFromMMode, BASE + 8)
+    );
+    assert_eq!(tc.cpu.take_exit(), Some(1));
+    assert_eq!(tc.cpu.csrs.mepc, 0, "no trap state was written");
+    assert_eq!(tc.get_reg(28), 0, "nothing after the fault ran");
+}
+
+#[test]
+fn monitor_report_decodes_the_fault() {
+    let mut tc = monitor_ctx(true);
+    first_error(&mut tc).expect("monitor must halt");
+
+    let report = tc
+        .cpu
+        .monitor_report(&Trap::EnvironmentCallFromMMode, 11, 0, BASE + 8);
+    assert!(report.contains("mtvec = 0"), "{report}");
+    assert!(
+        report.contains("EnvironmentCallFromMMode (mcause 11)"),
+        "{report}"
+    );
+    assert!(report.contains("pc    : 0x80000008"), "{report}");
+    assert!(report.contains("ecall"), "{report}");
+    assert!(report.contains("t0=0x0000000040000000"), "{report}");
+    assert!(report.contains("t1=0x000000000000002a"), "{report}");
+}
+
+#[test]
+fn installed_vector_or_disabled_monitor_takes_the_trap() {
+    let mut tc = monitor_ctx(true);
+    tc.cpu.csrs.mtvec = BASE + 0x100;
+    tc.cpu
+        .bus
+        .bus
+        .write_u32(BASE + 0x100, InstructionBuilder::new().jal(0, 0).build());
+    assert_eq!(first_error(&mut tc), None);
+    assert_eq!(tc.cpu.csrs.mepc, BASE + 8);
+
+    let mut tc = monitor_ctx(false);
+    assert_eq!(first_error(&mut tc), None);
+    assert_eq!(tc.cpu.csrs.mepc, BASE + 8, "trap vectored to mtvec = 0");
+}
+
+#[test]
+fn direct_mode_monitor_halts_on_faults_but_not_semihosting() {
+    let mut config = Config::default();
+    config.general.monitor_mode = true;
+    let mut program = faulting_program();
+    program.push(EBREAK);
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    assert!(tc.cpu.monitor_mode && tc.cpu.direct_mode);
+
+    let err = first_error(&mut tc).expect("monitor must halt");
+    assert_eq!(
+        err,
+        SimError::UnhandledTrap(Trap::Breakpoint(BASE + 16), BASE + 16)
+    );
+    assert_eq!(tc.get_reg(28), 1, "the ecall was semihosted");
+    assert_eq!(tc.cpu.take_exit(), Some(1));
+}
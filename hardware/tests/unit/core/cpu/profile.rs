@@ -0,0 +1,96 @@
+//! Hotspot Profiler Tests.
+//!
+//! Verifies the per-PC profile enabled by `general.profile`:
+//!   1. A counted loop's body PCs dominate the instruction ranking
+//!   2. Per-PC counts add up to the retired-instruction total
+//!   3. No profile is kept when profiling is disabled
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+
+const BASE: u64 = 0x8000_0000;
+const ITERATIONS: i32 = 100;
+/// An all-ones word is not a valid encoding; it ends the run.
+const ILLEGAL: u32 = 0xFFFF_FFFF;
+
+/// Counts `x6` up `ITERATIONS` times, then faults.
+fn loop_program() -> [u32; 5] {
+    [
+        InstructionBuilder::new().addi(5, 0, ITERATIONS).build(),
+        InstructionBuilder::new().addi(6, 6, 1).build(), // loop:
+        InstructionBuilder::new().addi(5, 5, -1).build(),
+        InstructionBuilder::new().bne(5, 0, -8).build(),
+        ILLEGAL,
+    ]
+}
+
+/// Runs the loop to completion with profiling set to `profile`.
+fn run_loop(profile: bool) -> TestContext {
+    let mut config = Config::default();
+    config.general.profile = profile;
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &loop_program());
+    tc.run(5_000);
+    assert_eq!(tc.get_reg(6), ITERATIONS as u64, "loop ran to completion");
+    tc
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Hotspot ranking
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn loop_body_dominates_the_profile() {
+    let tc = run_loop(true);
+    let profile = tc.cpu.profile().expect("profiling enabled");
+
+    let top = profile.top_by_instructions(3);
+    let pcs: Vec<u64> = top.iter().map(|&(pc, _)| pc).collect();
+    assert_eq!(pcs, [BASE + 4, BASE + 8, BASE + 12]);
+    for (_, p) in &top {
+        assert_eq!(p.instructions, ITERATIONS as u64);
+    }
+    assert_eq!(profile.get(BASE).unwrap().instructions, 1);
+    assert_eq!(
+        profile.get(BASE + 16),
+        None,
+        "faulting instruction never commits"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Totals
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn per_pc_counts_sum_to_retired_instructions() {
+    let tc = run_loop(true);
+    let profile = tc.cpu.profile().unwrap();
+
+    let total: u64 = profile
+        .top_by_instructions(usize::MAX)
+        .iter()
+        .map(|(_, p)| p.instructions)
+        .sum();
+    assert_eq!(total, tc.cpu.stats.instructions_retired);
+    assert_eq!(profile.len(), 4);
+
+    let stalls: u64 = profile
+        .top_by_stalls(usize::MAX)
+        .iter()
+        .map(|(_, p)| p.stall_cycles)
+        .sum();
+    assert!(stalls < tc.cpu.stats.cycles);
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Disabled
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn disabled_profiler_records_nothing() {
+    let tc = run_loop(false);
+    assert!(tc.cpu.profile().is_none());
+}
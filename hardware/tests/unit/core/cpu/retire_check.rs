@@ -0,0 +1,151 @@
+//! Retire-Order Verification Tests.
+//!
+//! Verifies the `general.verify_retire_order` checker:
+//!   1. A branchy program (loop, call, return, taken branch) retires exactly its
+//!      architecturally executed instructions, with NOP padding skipped
+//!   2. `mret` commits and is counted like any other instruction
+//!   3. Out-of-order commits and retire-count drift are reported as tick errors
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::SimError;
+use riscv_core::config::Config;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::pipeline::latches::MemWbEntry;
+use riscv_core::core::pipeline::stages::wb_stage;
+
+const BASE: u64 = 0x8000_0000;
+const NOP: u32 = 0x0000_0013;
+const WFI: u32 = 0x1050_0073;
+const MRET: u32 = 0x3020_0073;
+/// `csrrw x0, mepc, x5`.
+const CSRW_MEPC_X5: u32 = 0x3412_9073;
+/// `mstatus.MPP` field set to Machine.
+const MSTATUS_MPP_M: u64 = 3 << 11;
+
+/// Builds an M-mode context (so `wfi` is legal) with the checker enabled.
+fn checked_ctx(width: usize) -> TestContext {
+    let mut config = Config::default();
+    config.general.verify_retire_order = true;
+    config.pipeline.width = width;
+    let mut tc = TestContext::from_config(&config).with_memory(0x1000, BASE);
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc
+}
+
+/// Runs until the program parks in `wfi`, failing on any checker error.
+fn run_to_wfi(tc: &mut TestContext) {
+    for _ in 0..400 {
+        tc.cpu.tick().expect("retire check must pass");
+    }
+    assert!(tc.cpu.wfi_waiting, "program should end parked in WFI");
+}
+
+/// Loop three times around a body containing a NOP, call a leaf function,
+/// return, take a forward branch over one instruction, and park in WFI.
+fn branchy_program() -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().addi(5, 0, 3).build(),   // 0x00
+        b().addi(6, 0, 0).build(),   // 0x04
+        b().addi(6, 6, 2).build(),   // 0x08  loop:
+        NOP,                         // 0x0C
+        b().addi(5, 5, -1).build(),  // 0x10
+        b().bne(5, 0, -12).build(),  // 0x14  -> loop
+        b().jal(1, 16).build(),      // 0x18  -> leaf
+        b().beq(0, 0, 8).build(),    // 0x1C  -> park
+        b().addi(6, 6, 100).build(), // 0x20  skipped
+        WFI,                         // 0x24  park:
+        b().addi(7, 0, 7).build(),   // 0x28  leaf:
+        b().jalr(0, 1, 0).build(),   // 0x2C  -> 0x1C
+    ]
+}
+
+/// Instructions architecturally executed by [`branchy_program`], excluding NOPs:
+/// 2 setup + 3 x 3 loop body + jal + leaf (2) + beq + wfi.
+const BRANCHY_RETIRED: u64 = 2 + 3 * 3 + 1 + 2 + 1 + 1;
+
+#[test]
+fn branchy_program_retires_each_instruction_once() {
+    for width in [1, 2] {
+        let mut tc = checked_ctx(width).load_program(BASE, &branchy_program());
+        run_to_wfi(&mut tc);
+
+        assert_eq!(tc.get_reg(6), 6, "width {width}: loop ran three times");
+        assert_eq!(tc.get_reg(7), 7, "width {width}: leaf executed");
+        assert_eq!(
+            tc.cpu.stats.instructions_retired, BRANCHY_RETIRED,
+            "width {width}: retired count"
+        );
+        assert_eq!(tc.cpu.retire_check.committed(), BRANCHY_RETIRED);
+    }
+}
+
+#[test]
+fn mret_is_committed_and_counted() {
+    let b = InstructionBuilder::new;
+    let program = [
+        b().auipc(5, 0).build(),
+        b().addi(5, 5, 20).build(),
+        CSRW_MEPC_X5,
+        MRET,
+        b().addi(6, 0, 1).build(), // skipped by mret
+        WFI,
+    ];
+    let mut tc = checked_ctx(1).load_program(BASE, &program);
+    tc.cpu.csrs.mstatus |= MSTATUS_MPP_M;
+    run_to_wfi(&mut tc);
+
+    assert_eq!(tc.get_reg(6), 0);
+    assert_eq!(tc.cpu.stats.instructions_retired, 5);
+}
+
+#[test]
+fn out_of_order_commit_is_reported() {
+    let mut tc = checked_ctx(2);
+    let addi = InstructionBuilder::new().addi(5, 5, 1).build();
+    let entry = |pc| MemWbEntry {
+        pc,
+        inst: addi,
+        inst_size: 4,
+        ..Default::default()
+    };
+    tc.cpu.mem_wb.entries = vec![entry(BASE + 8), entry(BASE + 4)];
+    wb_stage(&mut tc.cpu);
+
+    let err = tc.cpu.retire_check.take_violation().expect("violation");
+    assert!(
+        err.contains("out-of-order commit at pc=0x80000004"),
+        "{err}"
+    );
+}
+
+#[test]
+fn retire_count_drift_fails_tick() {
+    let mut tc = checked_ctx(1).load_program(BASE, &branchy_program());
+    for _ in 0..20 {
+        tc.cpu.tick().unwrap();
+    }
+    tc.cpu.stats.instructions_retired += 1;
+
+    let err = (0..50)
+        .find_map(|_| tc.cpu.tick().err())
+        .expect("drift must be detected at the next commit");
+    let SimError::RetireCheck(msg) = err else {
+        panic!("expected a retire-check error, got {err:?}");
+    };
+    assert!(msg.contains("retire count mismatch"), "{msg}");
+}
+
+#[test]
+fn disabled_checker_records_nothing() {
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &branchy_program());
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    run_to_wfi(&mut tc);
+
+    assert!(!tc.cpu.retire_check.is_enabled());
+    assert_eq!(tc.cpu.retire_check.committed(), 0);
+    assert_eq!(tc.cpu.stats.instructions_retired, BRANCHY_RETIRED);
+}
@@ -0,0 +1,114 @@
+//! Built-in SBI Tests.
+//!
+//! Verifies `general.builtin_sbi` dispatch of S-mode `ecall`s on `a7`:
+//!   1. A legacy v0.1 console putchar writes the UART and returns only in `a0`
+//!   2. A v0.2 TIME set_timer programs `stimecmp` and returns SBI_SUCCESS/0 in `a0`/`a1`
+//!   3. BASE probe reports TIME as present; unknown extensions are not supported
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use crate::common::mocks::memory::MockMemory;
+use riscv_core::config::Config;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::cpu::sbi::{EXT_BASE, EXT_TIME, SBI_ERR_NOT_SUPPORTED, SBI_SUCCESS};
+
+const BASE: u64 = 0x8000_0000;
+const ECALL: u32 = 0x0000_0073;
+const A0: u32 = 10;
+const A1: u32 = 11;
+const A6: u32 = 16;
+const A7: u32 = 17;
+/// Value preloaded into `a1` to detect whether a call overwrote it.
+const A1_SENTINEL: i32 = 0x55;
+
+/// `li rd, value` for a 32-bit value below `0x8000_0000` (`lui` + `addi`).
+fn li(rd: u32, value: u32) -> [u32; 2] {
+    let b = InstructionBuilder::new;
+    let hi = (value + 0x800) >> 12;
+    let lo = value.wrapping_sub(hi << 12) as i32;
+    [b().lui(rd, hi as i32).build(), b().addi(rd, rd, lo).build()]
+}
+
+/// Runs `program` followed by a spin loop in S-mode with the built-in SBI enabled.
+fn run_in_s_mode(program: &[u32]) -> TestContext {
+    let mut config = Config::default();
+    config.general.builtin_sbi = true;
+    config.general.direct_mode = false;
+    let uart_base = config.system.uart_base;
+
+    let mut code = program.to_vec();
+    code.push(InstructionBuilder::new().jal(0, 0).build());
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &code);
+    tc.cpu
+        .bus
+        .bus
+        .add_device(Box::new(MockMemory::new(0x100, uart_base)));
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.run(200);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Supervisor, "no trap to M");
+    assert_eq!(tc.cpu.stats.traps_taken, 0);
+    tc
+}
+
+#[test]
+fn legacy_putchar_writes_console_and_returns_in_a0() {
+    let b = InstructionBuilder::new;
+    let mut tc = run_in_s_mode(&[
+        b().addi(A1, 0, A1_SENTINEL).build(),
+        b().addi(A0, 0, 'A' as i32).build(),
+        b().addi(A7, 0, 1).build(),
+        ECALL,
+    ]);
+
+    let uart_base = Config::default().system.uart_base;
+    assert_eq!(tc.cpu.bus.bus.read_u8(uart_base), b'A');
+    assert_eq!(tc.get_reg(A0 as usize), 0);
+    assert_eq!(
+        tc.get_reg(A1 as usize),
+        A1_SENTINEL as u64,
+        "v0.1 calls leave a1 alone"
+    );
+}
+
+#[test]
+fn v02_time_set_timer_programs_stimecmp() {
+    let b = InstructionBuilder::new;
+    let mut program = li(A7, EXT_TIME as u32).to_vec();
+    program.extend([
+        b().addi(A6, 0, 0).build(),
+        b().addi(A0, 0, 1000).build(),
+        b().addi(A1, 0, A1_SENTINEL).build(),
+        ECALL,
+    ]);
+    let tc = run_in_s_mode(&program);
+
+    assert_eq!(tc.cpu.csrs.stimecmp, 1000);
+    assert_eq!(tc.get_reg(A0 as usize), SBI_SUCCESS as u64);
+    assert_eq!(tc.get_reg(A1 as usize), 0);
+}
+
+#[test]
+fn v02_probe_and_unsupported_extension() {
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().addi(A7, 0, EXT_BASE as i32).build(),
+        b().addi(A6, 0, 3).build(), // probe_extension
+    ];
+    program.extend(li(A0, EXT_TIME as u32));
+    program.extend([
+        ECALL,
+        b().addi(18, A0, 0).build(),
+        b().addi(19, A1, 0).build(),
+    ]);
+    program.extend(li(A7, 0x0ABC_DEF0));
+    program.push(ECALL);
+    let tc = run_in_s_mode(&program);
+
+    assert_eq!(tc.get_reg(18), SBI_SUCCESS as u64);
+    assert_eq!(tc.get_reg(19), 1, "TIME is probed as present");
+    assert_eq!(tc.get_reg(A0 as usize), SBI_ERR_NOT_SUPPORTED as u64);
+    assert_eq!(tc.get_reg(A1 as usize), 0);
+}
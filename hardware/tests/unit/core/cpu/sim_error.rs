@@ -0,0 +1,70 @@
+//! Typed Simulation Error Tests.
+//!
+//! Verifies that `Cpu::tick` reports failures as `SimError` values:
+//!   1. A fault in direct mode returns `UnhandledTrap` with the trap and its PC
+//!   2. A trap raised outside writeback (the simulator panic CSR) is returned too
+//!   3. The error is reported once; later ticks return `Ok`
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::{SimError, Trap};
+
+const BASE: u64 = 0x8000_0000;
+/// An all-ones word is not a valid encoding.
+const ILLEGAL: u32 = 0xFFFF_FFFF;
+/// `csrrw x0, 0x8ff (simulator panic), x5`.
+const CSRW_SIM_PANIC_X5: u32 = 0x8FF2_9073;
+
+/// Ticks until the first error, failing if none occurs.
+fn first_error(tc: &mut TestContext) -> SimError {
+    (0..100)
+        .find_map(|_| tc.cpu.tick().err())
+        .expect("tick should fail")
+}
+
+#[test]
+fn direct_mode_fault_returns_unhandled_trap() {
+    let program = [InstructionBuilder::new().addi(5, 0, 1).build(), ILLEGAL];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    let err = first_error(&mut tc);
+    assert_eq!(
+        err,
+        SimError::UnhandledTrap(Trap::IllegalInstruction(ILLEGAL), BASE + 4)
+    );
+    assert_eq!(
+        err.to_string(),
+        "unhandled trap IllegalInstruction(0xffffffff) at PC 0x80000004"
+    );
+    assert_eq!(tc.get_reg(5), 1, "older instruction still committed");
+    assert_eq!(tc.cpu.take_exit(), Some(1));
+}
+
+#[test]
+fn requested_trap_returns_unhandled_trap() {
+    let program = [
+        InstructionBuilder::new().addi(5, 0, 7).build(),
+        CSRW_SIM_PANIC_X5,
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    let err = first_error(&mut tc);
+    assert!(
+        matches!(err, SimError::UnhandledTrap(Trap::RequestedTrap(7), _)),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn error_is_reported_once() {
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[ILLEGAL]);
+
+    first_error(&mut tc);
+    assert!(tc.cpu.tick().is_ok());
+}
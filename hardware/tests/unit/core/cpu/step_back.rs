@@ -0,0 +1,183 @@
+//! Reverse Stepping Tests.
+//!
+//! Verifies `Cpu::step_back` against a real RAM-backed system:
+//!   1. Register, memory, and PC deltas are undone in reverse commit order
+//!   2. Re-executing after stepping back reproduces the same state
+//!   3. History is bounded by `undo_depth` and disabled at zero
+//!   4. A misaligned store split across non-contiguous frames is undone in full
+
+use crate::common::builder::instruction::InstructionBuilder;
+use riscv_core::config::Config;
+use riscv_core::core::Cpu;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::soc::System;
+
+const BASE: u64 = 0x8000_0000;
+const DATA: u64 = BASE + 0x800;
+const POISON: u32 = 0xAAAA_AAAA;
+const SPIN_PC: u64 = BASE + 16;
+/// Enough cycles to reach the spin loop with uncached DRAM fetch latency.
+const RUN_CYCLES: u64 = 4000;
+
+/// Builds a CPU with 1 MiB of RAM running:
+/// `addi x5, x0, 7; sw x5, 0(x10); addi x5, x5, 1; sw x5, 4(x10); j .`
+fn cpu_with_history(undo_depth: usize) -> Cpu {
+    let mut config = Config::default();
+    config.memory.ram_size = 1 << 20;
+    config.general.undo_depth = undo_depth;
+    let mut cpu = Cpu::new(System::new(&config, ""), &config).unwrap();
+
+    let program = [
+        InstructionBuilder::new().addi(5, 0, 7).build(),
+        InstructionBuilder::new().sw(10, 5, 0).build(),
+        InstructionBuilder::new().addi(5, 5, 1).build(),
+        InstructionBuilder::new().sw(10, 5, 4).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    for (i, inst) in program.iter().enumerate() {
+        cpu.bus.bus.write_u32(BASE + 4 * i as u64, *inst);
+    }
+    cpu.bus.bus.write_u32(DATA, POISON);
+    cpu.bus.bus.write_u32(DATA + 4, POISON);
+    cpu.regs.write(10, DATA);
+    cpu.pc = BASE;
+    cpu
+}
+
+fn run(cpu: &mut Cpu, cycles: u64) {
+    for _ in 0..cycles {
+        cpu.tick().unwrap();
+    }
+}
+
+/// Steps back over the spin loop until the last record is the final store.
+fn rewind_spin(cpu: &mut Cpu) {
+    while cpu.undo.last().is_some_and(|r| r.pc == SPIN_PC) {
+        assert!(cpu.step_back());
+    }
+}
+
+#[test]
+fn step_back_restores_registers_memory_and_pc() {
+    let mut cpu = cpu_with_history(64);
+    run(&mut cpu, RUN_CYCLES);
+    assert_eq!(cpu.regs.read(5), 8);
+    assert_eq!(cpu.bus.bus.read_u32(DATA), 7);
+    assert_eq!(cpu.bus.bus.read_u32(DATA + 4), 8);
+
+    rewind_spin(&mut cpu);
+
+    assert!(cpu.step_back()); // sw x5, 4(x10)
+    assert_eq!(cpu.pc, BASE + 12);
+    assert_eq!(cpu.bus.bus.read_u32(DATA + 4), POISON);
+    assert_eq!(cpu.regs.read(5), 8);
+
+    assert!(cpu.step_back()); // addi x5, x5, 1
+    assert_eq!(cpu.pc, BASE + 8);
+    assert_eq!(cpu.regs.read(5), 7);
+
+    assert!(cpu.step_back()); // sw x5, 0(x10)
+    assert_eq!(cpu.pc, BASE + 4);
+    assert_eq!(cpu.bus.bus.read_u32(DATA), POISON);
+
+    assert!(cpu.step_back()); // addi x5, x0, 7
+    assert_eq!(cpu.pc, BASE);
+    assert_eq!(cpu.regs.read(5), 0);
+
+    assert!(!cpu.step_back(), "history exhausted");
+}
+
+#[test]
+fn re_execution_after_step_back_matches() {
+    let mut cpu = cpu_with_history(64);
+    run(&mut cpu, RUN_CYCLES);
+    rewind_spin(&mut cpu);
+    assert!(cpu.step_back());
+    assert!(cpu.step_back());
+    assert_eq!(cpu.regs.read(5), 7);
+
+    run(&mut cpu, RUN_CYCLES);
+
+    assert_eq!(cpu.regs.read(5), 8);
+    assert_eq!(cpu.bus.bus.read_u32(DATA), 7);
+    assert_eq!(cpu.bus.bus.read_u32(DATA + 4), 8);
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut cpu = cpu_with_history(3);
+    run(&mut cpu, RUN_CYCLES);
+
+    assert_eq!(cpu.undo.len(), 3);
+    for _ in 0..3 {
+        assert!(cpu.step_back());
+    }
+    assert!(!cpu.step_back());
+}
+
+#[test]
+fn zero_depth_disables_history() {
+    let mut cpu = cpu_with_history(0);
+    run(&mut cpu, RUN_CYCLES);
+
+    assert!(!cpu.undo.is_enabled());
+    assert!(cpu.undo.is_empty());
+    assert!(!cpu.step_back());
+    assert_eq!(cpu.regs.read(5), 8);
+}
+
+/// Sv39 root, level-1, and level-0 page tables.
+const ROOT: u64 = BASE + 0x1_0000;
+const L1: u64 = BASE + 0x1_1000;
+const L0: u64 = BASE + 0x1_2000;
+/// Frames backing the virtual pages at `SPLIT_VA` and `SPLIT_VA + 0x1000`; not adjacent.
+const FRAME_A: u64 = BASE + 0x2_0000;
+const FRAME_B: u64 = BASE + 0x4_0000;
+const SPLIT_VA: u64 = 0x4000_0000;
+/// PTE flags: valid, and valid + RWX + accessed + dirty.
+const PTE_V: u64 = 0x01;
+const PTE_LEAF: u64 = 0xCF;
+
+/// Builds an S-mode CPU with Sv39 on, RAM identity-mapped by a gigapage, and
+/// `SPLIT_VA`'s two pages mapped to `FRAME_A` and `FRAME_B`, running:
+/// `sw x5, 0(x10); j .` with `x10` two bytes before the page boundary.
+fn cpu_with_split_store() -> Cpu {
+    let mut cpu = cpu_with_history(64);
+    let pte = |pa: u64, flags: u64| ((pa >> 12) << 10) | flags;
+    let bus = &mut cpu.bus.bus;
+    bus.write_u64(ROOT + 8 * 2, pte(BASE, PTE_LEAF));
+    bus.write_u64(ROOT + 8, pte(L1, PTE_V));
+    bus.write_u64(L1, pte(L0, PTE_V));
+    bus.write_u64(L0, pte(FRAME_A, PTE_LEAF));
+    bus.write_u64(L0 + 8, pte(FRAME_B, PTE_LEAF));
+    bus.write_u16(FRAME_A + 0xFFE, 0xAAAA);
+    bus.write_u16(FRAME_B, 0xAAAA);
+    bus.write_u32(BASE, InstructionBuilder::new().sw(10, 5, 0).build());
+    bus.write_u32(BASE + 4, InstructionBuilder::new().jal(0, 0).build());
+
+    cpu.direct_mode = false;
+    cpu.privilege = PrivilegeMode::Supervisor;
+    cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (ROOT >> 12);
+    cpu.regs.write(5, 0x1122_3344);
+    cpu.regs.write(10, SPLIT_VA + 0xFFE);
+    cpu
+}
+
+#[test]
+fn split_store_across_frames_is_undone() {
+    let mut cpu = cpu_with_split_store();
+    run(&mut cpu, RUN_CYCLES);
+    assert_eq!(cpu.bus.bus.read_u16(FRAME_A + 0xFFE), 0x3344);
+    assert_eq!(cpu.bus.bus.read_u16(FRAME_B), 0x1122);
+
+    while cpu.undo.last().is_some_and(|r| r.pc != BASE) {
+        assert!(cpu.step_back());
+    }
+    assert_eq!(cpu.undo.last().unwrap().mem.len(), 2, "one run per frame");
+    assert!(cpu.step_back());
+
+    assert_eq!(cpu.pc, BASE);
+    assert_eq!(cpu.bus.bus.read_u16(FRAME_A + 0xFFE), 0xAAAA);
+    assert_eq!(cpu.bus.bus.read_u16(FRAME_B), 0xAAAA);
+}
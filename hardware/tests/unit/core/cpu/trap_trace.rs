@@ -0,0 +1,124 @@
+//! Trap Trace Tests.
+//!
+//! Verifies `Cpu::set_trap_trace` and the per-cause trap counters:
+//!   1. Exceptions and interrupts each produce one line with cause, code, epc,
+//!      tval, privilege transition, and handler address
+//!   2. `stats.traps_by_cause` counts each taken trap by name
+
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+/// Zeroed Sv39 root page table: every translation faults.
+const ROOT_PT: u64 = BASE + 0x1000;
+/// Unmapped virtual address the S-mode hart starts fetching from.
+const VIRT_PC: u64 = 0x4000_0000;
+/// `jal x0, 0` — spin in place.
+const SPIN: u32 = 0x0000_006F;
+/// Supervisor timer interrupt code (vectored offset is 4 * code).
+const STI_CODE: u64 = 5;
+
+/// `Write` sink sharing its buffer with the test.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuf {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+/// S-mode hart with paging on and an empty page table, vectored `mtvec` at `BASE`.
+fn faulting_ctx() -> TestContext {
+    let mut tc = TestContext::new()
+        .with_memory(0x2000, BASE)
+        .load_program(BASE, &[SPIN]);
+    tc.cpu.bus.bus.write_u32(BASE + 4 * STI_CODE, SPIN);
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.cpu.csrs.mtvec = BASE | 1;
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (ROOT_PT >> 12);
+    tc.cpu.pc = VIRT_PC;
+    tc
+}
+
+#[test]
+fn page_fault_and_timer_interrupt_are_traced() {
+    let mut tc = faulting_ctx();
+    let buf = SharedBuf::default();
+    tc.cpu.set_trap_trace(Box::new(buf.clone()));
+
+    tc.run(50);
+    assert_eq!(
+        tc.cpu.privilege,
+        PrivilegeMode::Machine,
+        "page fault lands in the M-mode handler"
+    );
+
+    // Arm the supervisor timer while the handler spins with MIE set.
+    tc.cpu.csrs.mie = csr::MIP_STIP;
+    tc.cpu.csrs.mstatus |= csr::MSTATUS_MIE;
+    tc.cpu.csrs.stimecmp = 1;
+    tc.run(50);
+
+    let lines = buf.lines();
+    assert_eq!(lines.len(), 2, "trace: {lines:#?}");
+
+    let fault = &lines[0];
+    assert!(fault.contains("cause=InstructionPageFault"), "{fault}");
+    assert!(fault.contains("code=0xc "), "{fault}");
+    assert!(fault.contains(&format!("epc={VIRT_PC:#x} ")), "{fault}");
+    assert!(fault.contains(&format!("tval={VIRT_PC:#x} ")), "{fault}");
+    assert!(fault.contains("priv=Supervisor->Machine"), "{fault}");
+    assert!(fault.contains(&format!("handler={BASE:#x}")), "{fault}");
+
+    let irq = &lines[1];
+    assert!(irq.contains("cause=SupervisorTimerInterrupt"), "{irq}");
+    assert!(irq.contains("code=0x8000000000000005 "), "{irq}");
+    assert!(irq.contains(&format!("epc={BASE:#x} ")), "{irq}");
+    assert!(irq.contains("tval=0x0 "), "{irq}");
+    assert!(irq.contains("priv=Machine->Machine"), "{irq}");
+    assert!(
+        irq.contains(&format!("handler={:#x}", BASE + 4 * STI_CODE)),
+        "{irq}"
+    );
+
+    let by_cause = &tc.cpu.stats.traps_by_cause;
+    assert_eq!(by_cause.get("InstructionPageFault"), Some(&1));
+    assert_eq!(by_cause.get("SupervisorTimerInterrupt"), Some(&1));
+    assert_eq!(tc.cpu.stats.traps_taken, 2);
+}
+
+#[test]
+fn cleared_trace_stops_logging_but_still_counts() {
+    let mut tc = faulting_ctx();
+    let buf = SharedBuf::default();
+    tc.cpu.set_trap_trace(Box::new(buf.clone()));
+    assert!(tc.cpu.clear_trap_trace().is_some());
+
+    tc.run(50);
+
+    assert!(buf.lines().is_empty());
+    assert_eq!(
+        tc.cpu.stats.traps_by_cause.get("InstructionPageFault"),
+        Some(&1)
+    );
+}
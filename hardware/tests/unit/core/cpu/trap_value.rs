@@ -0,0 +1,82 @@
+//! Trap Value Tests.
+//!
+//! Verifies that taken traps record their payload in the target mode's `xtval`:
+//!   1. A store page fault leaves the faulting virtual address in `stval`
+//!   2. An illegal instruction leaves its encoding in `mtval`
+//!   3. A breakpoint leaves the `ebreak` address in `mtval`
+
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+
+const BASE: u64 = 0x8000_0000;
+/// Sv39 root page table mapping only the 1 GiB gigapage at `BASE`.
+const ROOT_PT: u64 = BASE + 0x1000;
+/// Trap handler address for both `mtvec` and `stvec`.
+const HANDLER: u64 = BASE + 0x100;
+/// Unmapped virtual address targeted by the faulting store.
+const FAULT_VA: u64 = 0x4000_0000;
+/// `jal x0, 0` — spin in place.
+const SPIN: u32 = 0x0000_006F;
+/// Store page fault cause code.
+const STORE_PAGE_FAULT: u64 = 15;
+
+/// Hart in `privilege` running `program` at `BASE` with handlers spinning at `HANDLER`.
+fn ctx(privilege: PrivilegeMode, program: &[u32]) -> TestContext {
+    let mut tc = TestContext::new()
+        .with_memory(0x2000, BASE)
+        .load_program(BASE, program);
+    tc.cpu.bus.bus.write_u32(HANDLER, SPIN);
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = privilege;
+    tc.cpu.csrs.mtvec = HANDLER;
+    tc.cpu.csrs.stvec = HANDLER;
+    tc
+}
+
+#[test]
+fn store_page_fault_sets_stval_to_faulting_address() {
+    let program = [
+        0x4000_02B7, // lui x5, 0x40000
+        0x0002_B023, // sd x0, 0(x5)
+        SPIN,
+    ];
+    let mut tc = ctx(PrivilegeMode::Supervisor, &program);
+    // Gigapage VA BASE -> PA BASE, RWX with A/D set; everything else unmapped.
+    let pte = ((BASE >> 12) << 10) | 0xCF;
+    tc.cpu.bus.bus.write_u64(ROOT_PT + 2 * 8, pte);
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (ROOT_PT >> 12);
+    tc.cpu.csr_write(csr::MEDELEG, 1 << STORE_PAGE_FAULT);
+
+    tc.run(100);
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Supervisor);
+    assert_eq!(tc.cpu.csrs.scause, STORE_PAGE_FAULT);
+    assert_eq!(tc.cpu.csrs.sepc, BASE + 4);
+    assert_eq!(tc.cpu.csrs.stval, FAULT_VA);
+    assert_eq!(tc.cpu.csrs.mtval, 0, "M-mode tval untouched");
+}
+
+#[test]
+fn illegal_instruction_sets_mtval_to_encoding() {
+    const ILLEGAL: u32 = 0xFFFF_FFFF;
+    let mut tc = ctx(PrivilegeMode::Machine, &[ILLEGAL, SPIN]);
+
+    tc.run(100);
+
+    assert_eq!(tc.cpu.csrs.mcause, 2);
+    assert_eq!(tc.cpu.csrs.mepc, BASE);
+    assert_eq!(tc.cpu.csrs.mtval, ILLEGAL as u64);
+}
+
+#[test]
+fn breakpoint_sets_mtval_to_pc() {
+    const EBREAK: u32 = 0x0010_0073;
+    let mut tc = ctx(PrivilegeMode::Machine, &[0x0000_0013, EBREAK, SPIN]);
+
+    tc.run(100);
+
+    assert_eq!(tc.cpu.csrs.mcause, 3);
+    assert_eq!(tc.cpu.csrs.mepc, BASE + 4);
+    assert_eq!(tc.cpu.csrs.mtval, BASE + 4);
+}
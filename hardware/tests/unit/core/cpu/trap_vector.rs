@@ -0,0 +1,69 @@
+//! Trap Vector Mode Tests.
+//!
+//! Verifies that `Cpu::trap` decodes the MODE field of `mtvec`/`stvec`:
+//!   1. Vectored mode sends interrupts to `base + 4 * cause`
+//!   2. Exceptions always go to `base`, in either mode
+//!   3. Reserved MODE values are WARL-mapped to direct mode
+
+use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+
+const MTVEC_BASE: u64 = 0x8000_1000;
+const STVEC_BASE: u64 = 0x8000_2000;
+const EPC: u64 = 0x8000_0040;
+
+fn ctx(privilege: PrivilegeMode) -> TestContext {
+    let mut tc = TestContext::new();
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = privilege;
+    tc.cpu
+        .csr_write(csr::MTVEC, MTVEC_BASE | csr::TVEC_MODE_VECTORED);
+    tc.cpu
+        .csr_write(csr::STVEC, STVEC_BASE | csr::TVEC_MODE_VECTORED);
+    tc
+}
+
+#[test]
+fn vectored_mtvec_timer_interrupt_lands_at_offset() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.trap(Trap::MachineTimerInterrupt, EPC);
+    assert_eq!(tc.cpu.pc, MTVEC_BASE + 4 * 7);
+    assert_eq!(tc.cpu.csrs.mcause, (1 << 63) | 7);
+}
+
+#[test]
+fn vectored_mtvec_ecall_lands_at_base() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.trap(Trap::EnvironmentCallFromMMode, EPC);
+    assert_eq!(tc.cpu.pc, MTVEC_BASE);
+    assert_eq!(tc.cpu.csrs.mcause, 11);
+}
+
+#[test]
+fn vectored_stvec_delegated_interrupt_lands_at_offset() {
+    let mut tc = ctx(PrivilegeMode::User);
+    tc.cpu.csr_write(csr::MIDELEG, csr::MIP_STIP);
+    tc.cpu.trap(Trap::SupervisorTimerInterrupt, EPC);
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Supervisor);
+    assert_eq!(tc.cpu.pc, STVEC_BASE + 4 * 5);
+}
+
+#[test]
+fn direct_mtvec_interrupt_lands_at_base() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.csr_write(csr::MTVEC, MTVEC_BASE);
+    tc.cpu.trap(Trap::MachineTimerInterrupt, EPC);
+    assert_eq!(tc.cpu.pc, MTVEC_BASE);
+}
+
+#[test]
+fn reserved_mode_reads_back_as_direct() {
+    let mut tc = ctx(PrivilegeMode::Machine);
+    tc.cpu.csr_write(csr::MTVEC, MTVEC_BASE | 3);
+    assert_eq!(tc.cpu.csrs.mtvec, MTVEC_BASE | csr::TVEC_MODE_DIRECT);
+
+    tc.cpu.trap(Trap::MachineTimerInterrupt, EPC);
+    assert_eq!(tc.cpu.pc, MTVEC_BASE);
+}
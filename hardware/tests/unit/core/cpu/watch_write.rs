@@ -0,0 +1,170 @@
+//! Write Watch Tests.
+//!
+//! Verifies `Cpu::watch_write` against a real RAM-backed system:
+//!   1. A store to the watched location reports its old and new values and PC
+//!   2. Partial-width stores report the whole watched value; disjoint stores are ignored
+//!   3. A store wider than the watch reports only the watched bytes
+//!   4. `unwatch_write` stops further callbacks
+//!   5. A store drained early by `fence.i` is reported like any other
+
+use crate::common::builder::instruction::InstructionBuilder;
+use riscv_core::config::Config;
+use riscv_core::core::Cpu;
+use riscv_core::core::cpu::watch::WriteEvent;
+use riscv_core::soc::System;
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+const DATA: u64 = BASE + 0x400;
+const INITIAL: u64 = 0x1122_3344_5566_7788;
+/// Enough cycles to reach the spin loop with uncached DRAM fetch latency.
+const RUN_CYCLES: u64 = 4000;
+
+/// Byte store `sb rs2, imm(rs1)`.
+fn sb(rs1: u32, rs2: u32, imm: i32) -> u32 {
+    InstructionBuilder::new()
+        .sw(rs1, rs2, imm)
+        .funct3(0)
+        .build()
+}
+
+/// Stores a byte into `DATA + 2`, a doubleword to `DATA + 8`, then all ones
+/// over `DATA`, and spins.
+fn store_program() -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().auipc(5, 0).build(),      // 0x00  x5 = BASE
+        b().addi(6, 0, 0xAB).build(), // 0x04
+        sb(5, 6, 0x402),              // 0x08
+        b().addi(7, 0, -1).build(),   // 0x0C
+        b().sd(5, 7, 0x408).build(),  // 0x10  outside the watch
+        b().sd(5, 7, 0x400).build(),  // 0x14
+        b().jal(0, 0).build(),        // 0x18
+    ]
+}
+
+/// `fence.i` encoding.
+const FENCE_I: u32 = 0x0000_100F;
+
+/// Stores a word into `DATA` in the same 2-wide bundle as a `fence.i`, which
+/// performs the store before it reaches the memory stage, then spins.
+fn fence_program() -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().auipc(5, 0).build(),      // 0x00  x5 = BASE
+        b().addi(6, 0, 0x5A).build(), // 0x04
+        b().sw(5, 6, 0x400).build(),  // 0x08
+        FENCE_I,                      // 0x0C
+        b().jal(0, 0).build(),        // 0x10
+    ]
+}
+
+/// CPU with 1 MiB of RAM, `INITIAL` at `DATA`, and the store program loaded.
+fn store_cpu() -> Cpu {
+    cpu_with(&Config::default(), &store_program())
+}
+
+/// CPU built from `config` with 1 MiB of RAM, `INITIAL` at `DATA`, and `program` loaded.
+fn cpu_with(config: &Config, program: &[u32]) -> Cpu {
+    let mut config = config.clone();
+    config.memory.ram_size = 1 << 20;
+    let mut cpu = Cpu::new(System::new(&config, ""), &config).unwrap();
+    for (i, inst) in program.iter().enumerate() {
+        cpu.bus.bus.write_u32(BASE + 4 * i as u64, *inst);
+    }
+    cpu.bus.bus.write_u64(DATA, INITIAL);
+    cpu.pc = BASE;
+    cpu
+}
+
+fn run(cpu: &mut Cpu) {
+    for _ in 0..RUN_CYCLES {
+        cpu.tick().unwrap();
+    }
+}
+
+/// Installs a watch that records every event into the returned log.
+fn record(cpu: &mut Cpu, addr: u64, size: u8) -> (usize, Arc<Mutex<Vec<WriteEvent>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&log);
+    let id = cpu.watch_write(
+        addr,
+        size,
+        Box::new(move |ev| sink.lock().unwrap().push(ev)),
+    );
+    (id, log)
+}
+
+#[test]
+fn watch_reports_old_new_and_pc() {
+    let mut cpu = store_cpu();
+    let (_, log) = record(&mut cpu, DATA, 8);
+    run(&mut cpu);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            WriteEvent {
+                addr: DATA,
+                pc: BASE + 0x08,
+                old: INITIAL,
+                new: 0x1122_3344_55AB_7788,
+            },
+            WriteEvent {
+                addr: DATA,
+                pc: BASE + 0x14,
+                old: 0x1122_3344_55AB_7788,
+                new: u64::MAX,
+            },
+        ]
+    );
+}
+
+#[test]
+fn narrow_watch_sees_only_its_bytes() {
+    let mut cpu = store_cpu();
+    let (_, log) = record(&mut cpu, DATA + 4, 4);
+    run(&mut cpu);
+
+    // The byte store at `DATA + 2` misses the watch; the doubleword store covers it.
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![WriteEvent {
+            addr: DATA + 4,
+            pc: BASE + 0x14,
+            old: 0x1122_3344,
+            new: 0xFFFF_FFFF,
+        }]
+    );
+}
+
+#[test]
+fn unwatch_stops_callbacks() {
+    let mut cpu = store_cpu();
+    let (id, log) = record(&mut cpu, DATA, 8);
+    assert!(cpu.unwatch_write(id));
+    assert!(!cpu.unwatch_write(id), "already removed");
+    run(&mut cpu);
+
+    assert!(log.lock().unwrap().is_empty());
+    assert_eq!(cpu.bus.bus.read_u64(DATA), u64::MAX, "stores still land");
+}
+
+#[test]
+fn store_drained_by_fence_i_is_reported() {
+    let mut config = Config::default();
+    config.pipeline.width = 2;
+    let mut cpu = cpu_with(&config, &fence_program());
+    let (_, log) = record(&mut cpu, DATA, 4);
+    run(&mut cpu);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![WriteEvent {
+            addr: DATA,
+            pc: BASE + 0x08,
+            old: 0x5566_7788,
+            new: 0x5A,
+        }]
+    );
+}
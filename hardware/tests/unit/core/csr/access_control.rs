@@ -108,6 +108,18 @@ fn csr_satp_mode_sv39_preserved() {
     assert_eq!(read_back & csr::SATP_PPN_MASK, 0x12345);
 }
 
+/// Verifies that the `satp` register correctly preserves the Sv48 paging mode.
+#[test]
+fn csr_satp_mode_sv48_preserved() {
+    let mut csrs = Csrs::default();
+    let sv48_satp = (csr::SATP_MODE_SV48 << csr::SATP_MODE_SHIFT) | 0x12345;
+    csrs.write(csr::SATP, sv48_satp);
+    let read_back = csrs.read(csr::SATP);
+    let mode = (read_back >> csr::SATP_MODE_SHIFT) & csr::SATP_MODE_MASK;
+    assert_eq!(mode, csr::SATP_MODE_SV48);
+    assert_eq!(read_back & csr::SATP_PPN_MASK, 0x12345);
+}
+
 /// Verifies that the `satp` register correctly preserves the Bare (no translation) mode.
 #[test]
 fn csr_satp_mode_bare_preserved() {
@@ -124,7 +136,7 @@ fn csr_satp_mode_bare_preserved() {
 #[test]
 fn csr_satp_invalid_mode_becomes_bare() {
     let mut csrs = Csrs::default();
-    // Mode value 5 is not valid (only 0=bare, 8=sv39)
+    // Mode value 5 is not valid (only 0=bare, 8=sv39, 9=sv48)
     let invalid_satp = (5u64 << csr::SATP_MODE_SHIFT) | 0xABC;
     csrs.write(csr::SATP, invalid_satp);
     let read_back = csrs.read(csr::SATP);
@@ -172,3 +184,23 @@ fn csr_clone() {
     let cloned = csrs.clone();
     assert_eq!(cloned.read(csr::MSTATUS), 0xABCD);
 }
+
+/// Verifies that `fflags` and `frm` are views of the low and rounding-mode fields of `fcsr`.
+#[test]
+fn csr_fcsr_aliases() {
+    let mut csrs = Csrs::default();
+    csrs.write(csr::FCSR, 0xFFFF);
+    assert_eq!(
+        csrs.read(csr::FCSR),
+        0xFF,
+        "only frm and fflags are implemented"
+    );
+    assert_eq!(csrs.read(csr::FFLAGS), 0x1F);
+    assert_eq!(csrs.read(csr::FRM), 0x7);
+
+    csrs.write(csr::FRM, 0x1);
+    assert_eq!(csrs.read(csr::FCSR), (0x1 << 5) | 0x1F);
+
+    csrs.write(csr::FFLAGS, 0);
+    assert_eq!(csrs.read(csr::FCSR), 0x1 << 5);
+}
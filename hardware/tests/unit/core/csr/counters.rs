@@ -8,8 +8,48 @@
 //! - Counters can be incremented correctly.
 //! - Counters handle maximum `u64` values.
 //! - Counters wrap around correctly on overflow.
+//! - The RV32 high-half CSRs (`cycleh`, `instreth`, ...) recombine with the
+//!   low halves into the full 64-bit counter value.
+//! - `time` reads the CLINT's `mtime` while `cycle` keeps counting core cycles.
+//! - `mcounteren`/`scounteren` gate S- and U-mode counter reads.
+//! - `mhpmcounter`s count the statistic their `mhpmevent` selects.
 
-use riscv_core::core::arch::csr::Csrs;
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::config::Config;
+use riscv_core::core::arch::csr::{self, Csrs};
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::pipeline::latches::IdExEntry;
+use riscv_core::core::pipeline::signals::{ControlSignals, CsrOp};
+use riscv_core::core::pipeline::stages::execute_stage;
+use riscv_core::soc::devices::Clint;
+
+/// Executes `csrrs x5, addr, x0` through the execute stage and returns the value read.
+fn read_csr(tc: &mut TestContext, addr: u32) -> u64 {
+    read_csr_checked(tc, addr).expect("counter read trapped")
+}
+
+/// Like [`read_csr`], but returns the trap raised instead of a value.
+fn read_csr_checked(tc: &mut TestContext, addr: u32) -> Result<u64, Trap> {
+    tc.cpu.id_ex.entries = vec![IdExEntry {
+        pc: 0x8000_0000,
+        inst: (addr << 20) | (2 << 12) | (5 << 7) | 0x73,
+        inst_size: 4,
+        rd: 5,
+        ctrl: ControlSignals {
+            reg_write: true,
+            is_system: true,
+            csr_addr: addr,
+            csr_op: CsrOp::Rs,
+            ..Default::default()
+        },
+        ..Default::default()
+    }];
+    execute_stage(&mut tc.cpu);
+    let entry = &tc.cpu.ex_mem.entries[0];
+    entry.trap.clone().map_or(Ok(entry.alu), Err)
+}
 
 /// Tests basic increment functionality for cycle and instruction counters.
 #[test]
@@ -44,3 +84,176 @@ fn counters_wrapping() {
     csrs.cycle = csrs.cycle.wrapping_add(1);
     assert_eq!(csrs.cycle, 0);
 }
+
+/// Reads `cycleh`/`instreth` after a count above 2^32 and recombines the halves.
+#[test]
+fn counters_high_halves_recombine() {
+    let mut tc = TestContext::new();
+    let cycles = 0x0000_0012_3456_789A;
+    let retired = 0x0000_0001_8000_0001;
+    tc.cpu.stats.cycles = cycles;
+    tc.cpu.stats.instructions_retired = retired;
+
+    let cycle_hi = read_csr(&mut tc, csr::CYCLEH);
+    let cycle_lo = read_csr(&mut tc, csr::CYCLE) & 0xFFFF_FFFF;
+    assert_eq!(cycle_hi, 0x12);
+    assert_eq!((cycle_hi << 32) | cycle_lo, cycles);
+
+    let mcycle_hi = read_csr(&mut tc, csr::MCYCLEH);
+    assert_eq!(mcycle_hi, cycle_hi);
+
+    let instret_hi = read_csr(&mut tc, csr::INSTRETH);
+    let instret_lo = read_csr(&mut tc, csr::INSTRET) & 0xFFFF_FFFF;
+    assert_eq!((instret_hi << 32) | instret_lo, retired);
+    assert_eq!(read_csr(&mut tc, csr::MINSTRETH), instret_hi);
+}
+
+/// The cycle counter wraps modulo 2^64 instead of overflowing the host type.
+#[test]
+fn counters_cycle_wraps_in_tick() {
+    let mut tc = TestContext::new();
+    tc.cpu.stats.cycles = u64::MAX;
+    tc.run(1);
+    assert_eq!(tc.cpu.stats.cycles, 0);
+}
+
+/// With a CLINT divider of 10, `time` advances once per ten cycles and `cycle` once per cycle.
+#[test]
+fn counters_time_reads_clint_mtime() {
+    const BASE: u64 = 0x8000_0000;
+    const CYCLES: u64 = 5_000;
+    let spin = InstructionBuilder::new().jal(0, 0).build();
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[spin]);
+    tc.cpu
+        .bus
+        .bus
+        .add_device(Box::new(Clint::new(0x0200_0000, 10)));
+    tc.cpu.clint_divider = 1;
+
+    tc.run(CYCLES);
+    assert_eq!(tc.cpu.stats.cycles, CYCLES);
+
+    let cycle = read_csr(&mut tc, csr::CYCLE);
+    let time = read_csr(&mut tc, csr::TIME);
+    assert_eq!(cycle, CYCLES);
+    assert!(
+        time.abs_diff(CYCLES / 10) <= 1,
+        "time {time} should track cycles / 10"
+    );
+    assert_eq!(time, tc.cpu.bus.bus.clint_mtime().unwrap());
+}
+
+/// `rdcycle` (`csrrs x5, cycle, x0`) as encoded by [`read_csr`].
+const RDCYCLE: u32 = (csr::CYCLE << 20) | (2 << 12) | (5 << 7) | 0x73;
+
+/// A user-mode `rdcycle` is illegal unless `mcounteren.CY` is set.
+#[test]
+fn counters_user_rdcycle_requires_mcounteren() {
+    let mut tc = TestContext::new();
+    tc.cpu.privilege = PrivilegeMode::User;
+    tc.cpu.stats.cycles = 1234;
+    tc.cpu.csr_write(csr::SCOUNTEREN, csr::COUNTEREN_CY);
+    tc.cpu.csr_write(csr::MCOUNTEREN, 0);
+    assert_eq!(
+        read_csr_checked(&mut tc, csr::CYCLE),
+        Err(Trap::IllegalInstruction(RDCYCLE))
+    );
+
+    tc.cpu.csr_write(csr::MCOUNTEREN, csr::COUNTEREN_CY);
+    assert_eq!(read_csr_checked(&mut tc, csr::CYCLE), Ok(1234));
+}
+
+/// U-mode also needs `scounteren`; S-mode needs only `mcounteren`; M-mode is never gated.
+#[test]
+fn counters_enable_bits_apply_per_privilege() {
+    let mut tc = TestContext::new();
+    tc.cpu
+        .csr_write(csr::MCOUNTEREN, csr::COUNTEREN_TM | csr::COUNTEREN_IR);
+    tc.cpu.csr_write(csr::SCOUNTEREN, csr::COUNTEREN_IR);
+
+    tc.cpu.privilege = PrivilegeMode::User;
+    assert!(read_csr_checked(&mut tc, csr::INSTRET).is_ok());
+    assert!(read_csr_checked(&mut tc, csr::TIME).is_err());
+    assert!(read_csr_checked(&mut tc, csr::CYCLEH).is_err());
+
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    assert!(read_csr_checked(&mut tc, csr::TIME).is_ok());
+    assert!(read_csr_checked(&mut tc, csr::CYCLE).is_err());
+    assert!(read_csr_checked(&mut tc, csr::HPMCOUNTER3).is_err());
+
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.cpu.csr_write(csr::MCOUNTEREN, 0);
+    assert!(read_csr_checked(&mut tc, csr::CYCLE).is_ok());
+    assert_eq!(tc.cpu.csrs.mcounteren, 0);
+}
+
+/// Encodes a Zicsr instruction (`funct3` 1 = `csrrw`, 2 = `csrrs`).
+fn csr_inst(funct3: u32, rd: u32, addr: u32, rs1: u32) -> u32 {
+    (addr << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x73
+}
+
+/// `mhpmcounter3` programmed for L1-D misses counts every miss of a streaming loop.
+#[test]
+fn counters_hpm_counts_dcache_misses() {
+    const CODE: u64 = 0x1000;
+    const DATA: u64 = 0x8000_0000;
+    const ITERS: u64 = 200;
+    // SBI cache event: L1-D, read, miss.
+    const L1D_READ_MISS: i32 = 0x1_0001;
+
+    let mut config = Config::default();
+    config.cache.l1_d.enabled = true;
+    config.cache.l1_d.size_bytes = 1024;
+
+    let b = InstructionBuilder::new;
+    let program = [
+        b().lui(5, L1D_READ_MISS >> 12).build(),
+        b().addi(5, 5, L1D_READ_MISS & 0xFFF).build(),
+        csr_inst(1, 0, csr::MHPMEVENT3, 5),
+        // loop: ld x1, 0(x10); addi x10, x10, 64; addi x11, x11, -1; bne x11, x0, loop
+        b().ld(1, 10, 0).build(),
+        b().addi(10, 10, 64).build(),
+        b().addi(11, 11, -1).build(),
+        b().bne(11, 0, -12).build(),
+        csr_inst(2, 12, csr::MHPMCOUNTER3, 0),
+        b().jal(0, 0).build(),
+    ];
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, CODE)
+        .with_memory(ITERS as usize * 64, DATA)
+        .load_program(CODE, &program);
+    tc.cpu.mmio_base = DATA;
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.set_reg(10, DATA);
+    tc.set_reg(11, ITERS);
+
+    let mut cycles = 0;
+    while tc.get_reg(12) == 0 {
+        assert!(cycles < 100_000, "loop did not finish");
+        tc.cpu.tick().unwrap();
+        cycles += 1;
+    }
+
+    assert_eq!(tc.get_reg(12), tc.cpu.stats.dcache_misses);
+    assert_eq!(tc.get_reg(12), ITERS);
+    assert_eq!(tc.cpu.csrs.mhpmevent[0], L1D_READ_MISS as u64);
+}
+
+/// Writing a counter sets its value; unprogrammed counters hold what was written.
+#[test]
+fn counters_hpm_write_sets_value() {
+    let mut tc = TestContext::new();
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.cpu.csr_write(csr::MHPMCOUNTER31, 42);
+    tc.cpu.stats.cycles = 1000;
+    assert_eq!(read_csr(&mut tc, csr::MHPMCOUNTER31), 42);
+
+    // Cycle event (0x1): counts on from the written value.
+    tc.cpu.csr_write(csr::MHPMEVENT3, 0x1);
+    tc.cpu.csr_write(csr::MHPMCOUNTER3, 10);
+    tc.cpu.stats.cycles += 5;
+    assert_eq!(read_csr(&mut tc, csr::MHPMCOUNTER3), 15);
+    assert_eq!(read_csr(&mut tc, csr::HPMCOUNTER3), 15);
+}
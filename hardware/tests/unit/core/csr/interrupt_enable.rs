@@ -0,0 +1,72 @@
+//! # Interrupt Enable/Pending CSR Tests
+//!
+//! This module verifies that `mie` and `mip` are backed by real storage when
+//! accessed through the CSR instructions:
+//! - `csrrs` on `mie` is visible on readback.
+//! - Unimplemented `mie` bits are WARL-masked to zero.
+//! - Software writes to `mip` cannot clear the hardware-owned MTIP bit.
+
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::csr;
+use riscv_core::core::pipeline::latches::IdExEntry;
+use riscv_core::core::pipeline::signals::{ControlSignals, CsrOp};
+use riscv_core::core::pipeline::stages::execute_stage;
+
+/// Executes `csrrs x5, addr, x6` (with `x6 = src`) through the execute stage
+/// and returns the old value it read.
+fn csrrs(tc: &mut TestContext, addr: u32, src: u64) -> u64 {
+    tc.cpu.id_ex.entries = vec![IdExEntry {
+        pc: 0x8000_0000,
+        inst: (addr << 20) | (6 << 15) | (2 << 12) | (5 << 7) | 0x73,
+        inst_size: 4,
+        rs1: 6,
+        rd: 5,
+        rv1: src,
+        ctrl: ControlSignals {
+            reg_write: true,
+            is_system: true,
+            csr_addr: addr,
+            csr_op: CsrOp::Rs,
+            ..Default::default()
+        },
+        ..Default::default()
+    }];
+    execute_stage(&mut tc.cpu);
+    tc.cpu.ex_mem.entries[0].alu
+}
+
+/// Verifies that enable bits set with `csrrs` are read back by a later `csrrs`.
+#[test]
+fn csrrs_mie_round_trips() {
+    let mut tc = TestContext::new();
+    let enables = csr::MIE_MTIE | csr::MIE_MSIP | csr::MIE_SEIP;
+
+    assert_eq!(csrrs(&mut tc, csr::MIE, enables), 0);
+    assert_eq!(csrrs(&mut tc, csr::MIE, csr::MIE_STIE), enables);
+    assert_eq!(csrrs(&mut tc, csr::MIE, 0), enables | csr::MIE_STIE);
+}
+
+/// Verifies that writes to unimplemented `mie` bits are dropped.
+#[test]
+fn mie_warl_masks_unimplemented_bits() {
+    let mut tc = TestContext::new();
+    tc.cpu.csr_write(csr::MIE, u64::MAX);
+    assert_eq!(csrrs(&mut tc, csr::MIE, 0), csr::MIE_WRITABLE);
+}
+
+/// Verifies that software can set SSIP/STIP/SEIP but not clear MTIP.
+#[test]
+fn mip_software_bits_writable_mtip_hardware_owned() {
+    let mut tc = TestContext::new();
+    tc.cpu.csrs.mip = csr::MIP_MTIP;
+
+    tc.cpu
+        .csr_write(csr::MIP, csr::MIP_SSIP | csr::MIP_STIP | csr::MIP_SEIP);
+    assert_eq!(
+        csrrs(&mut tc, csr::MIP, 0),
+        csr::MIP_MTIP | csr::MIP_SSIP | csr::MIP_STIP | csr::MIP_SEIP
+    );
+
+    tc.cpu.csr_write(csr::MIP, 0);
+    assert_eq!(csrrs(&mut tc, csr::MIP, 0), csr::MIP_MTIP);
+}
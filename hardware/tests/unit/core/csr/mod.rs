@@ -21,3 +21,15 @@ pub mod counters;
 /// This module verifies the logic for trap delegation, vector modes,
 /// and interrupt enable/pending bits within the RISC-V architecture.
 pub mod trap_setup;
+
+/// Unit tests for configurable CSR reset values.
+///
+/// This module verifies that the `csr_reset` config table is parsed, validated
+/// against known CSRs, and applied before the first instruction executes.
+pub mod reset_values;
+
+/// Unit tests for the `mie` and `mip` interrupt enable/pending registers.
+///
+/// This module verifies CSR-instruction readback and the WARL masks that keep
+/// hardware-owned pending bits out of software's reach.
+pub mod interrupt_enable;
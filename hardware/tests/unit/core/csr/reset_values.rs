@@ -0,0 +1,129 @@
+//! CSR Reset Value Tests.
+//!
+//! Verifies the `csr_reset` configuration table:
+//!   1. Overrides are applied at CPU construction, before the first tick
+//!   2. Hex and decimal address keys are accepted
+//!   3. Unknown or malformed addresses are rejected at config load, as are
+//!      CSRs the register file does not hold (counters, `sie`, `sip`)
+//!   4. Overrides are legalized as a `csrw` would be
+
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+use riscv_core::core::arch::csr;
+
+fn config_from_json(csr_reset: &str) -> Result<Config, serde_json::Error> {
+    let json = format!(
+        r#"{{
+            "general": {{}},
+            "system": {{}},
+            "memory": {{}},
+            "cache": {{
+                "l1_i": {{}}, "l1_d": {{}}, "l2": {{}}, "l3": {{}}
+            }},
+            "pipeline": {{}},
+            "csr_reset": {csr_reset}
+        }}"#
+    );
+    serde_json::from_str(&json)
+}
+
+#[test]
+fn custom_mtvec_present_before_first_instruction() {
+    let config = config_from_json(r#"{ "0x305": 2147491840 }"#).unwrap();
+    let tc = TestContext::from_config(&config);
+
+    assert_eq!(tc.cpu.stats.cycles, 0);
+    assert_eq!(tc.cpu.csrs.mtvec, 0x8000_2000);
+}
+
+#[test]
+fn decimal_keys_and_multiple_entries() {
+    // 0x340 = mscratch, 0x302 = medeleg
+    let config = config_from_json(r#"{ "832": 7, "0x302": 256 }"#).unwrap();
+    let tc = TestContext::from_config(&config);
+
+    assert_eq!(tc.cpu.csrs.read(csr::MSCRATCH), 7);
+    assert_eq!(tc.cpu.csrs.medeleg, 0x100);
+}
+
+#[test]
+fn override_replaces_builtin_default() {
+    let mut config = Config::default();
+    config.csr_reset.insert(csr::MSTATUS, 0);
+    let tc = TestContext::from_config(&config);
+
+    assert_eq!(tc.cpu.csrs.mstatus, 0);
+}
+
+#[test]
+fn empty_table_keeps_defaults() {
+    let config = config_from_json("{}").unwrap();
+    let tc = TestContext::from_config(&config);
+
+    assert_eq!(tc.cpu.csrs.mtvec, 0);
+    // Direct mode enables the FPU on the program's behalf.
+    assert_eq!(
+        tc.cpu.csrs.mstatus,
+        csr::MSTATUS_DEFAULT_RV64 | csr::MSTATUS_FS_INIT
+    );
+}
+
+#[test]
+fn unknown_csr_address_rejected() {
+    let err = config_from_json(r#"{ "0x7ff": 1 }"#).unwrap_err();
+    assert!(err.to_string().contains("unknown CSR"), "{err}");
+}
+
+#[test]
+fn csrs_not_held_in_register_file_rejected() {
+    // cycle, time, instret, mcycle, minstret, sie, sip
+    for addr in [
+        "0xc00", "0xc01", "0xc02", "0xb00", "0xb02", "0x104", "0x144",
+    ] {
+        let err = config_from_json(&format!(r#"{{ "{addr}": 1 }}"#)).unwrap_err();
+        assert!(err.to_string().contains("unknown CSR"), "{addr}: {err}");
+    }
+}
+
+#[test]
+fn malformed_csr_address_rejected() {
+    let err = config_from_json(r#"{ "mtvec": 1 }"#).unwrap_err();
+    assert!(err.to_string().contains("invalid CSR address"), "{err}");
+}
+
+#[test]
+fn misa_advertises_b_only_with_zba_and_zbb() {
+    let tc = TestContext::new();
+    assert_ne!(tc.cpu.csrs.misa & csr::MISA_EXT_B, 0);
+
+    let mut config = Config::default();
+    config.pipeline.zbb = false;
+    let tc = TestContext::from_config(&config);
+    assert_eq!(tc.cpu.csrs.misa & csr::MISA_EXT_B, 0);
+
+    let mut config = Config::default();
+    config.pipeline.zba = false;
+    let tc = TestContext::from_config(&config);
+    assert_eq!(tc.cpu.csrs.misa & csr::MISA_EXT_B, 0);
+}
+
+#[test]
+fn overrides_are_legalized_like_csrw() {
+    let mut config = Config::default();
+    // Reserved mtvec mode 3, a non-delegable ECALL-from-M bit, and an unimplemented
+    // mie bit.
+    config.csr_reset.insert(csr::MTVEC, 0x8000_2003);
+    config.csr_reset.insert(csr::MEDELEG, 1 << 11 | 1 << 8);
+    config.csr_reset.insert(csr::MIE, 1 << 15 | csr::MIE_MTIE);
+    config.csr_reset.insert(csr::MSTATUS, csr::MSTATUS_SUM);
+    let tc = TestContext::from_config(&config);
+
+    assert_eq!(tc.cpu.csrs.mtvec, 0x8000_2000);
+    assert_eq!(tc.cpu.csrs.medeleg, 1 << 8);
+    assert_eq!(tc.cpu.csrs.mie, csr::MIE_MTIE);
+    assert_eq!(
+        tc.cpu.csrs.sstatus,
+        csr::MSTATUS_SUM,
+        "sstatus follows mstatus"
+    );
+}
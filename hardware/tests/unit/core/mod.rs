@@ -1,4 +1,6 @@
 pub mod arch;
+pub mod cpu;
 pub mod csr;
 pub mod pipeline;
+pub mod smp;
 pub mod units;
@@ -0,0 +1,108 @@
+//! Multi-cycle Integer Divide Tests.
+//!
+//! Verifies that `pipeline.div_latency` holds the pipeline in execute:
+//! 1. A tight divide loop slows by `div_latency - 1` cycles per iteration
+//! 2. The early-exit divider finishes small quotients sooner
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+
+const BASE: u64 = 0x8000_0000;
+
+/// Iterations of the divide loop.
+const ITERS: u64 = 20;
+
+/// Encodes an M-extension `OP` instruction (`funct7 = 1`).
+fn m_op(funct3: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    (1 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x33
+}
+
+fn div(rd: u32, rs1: u32, rs2: u32) -> u32 {
+    m_op(0b100, rd, rs1, rs2)
+}
+
+fn remu(rd: u32, rs1: u32, rs2: u32) -> u32 {
+    m_op(0b111, rd, rs1, rs2)
+}
+
+/// Runs `ITERS` iterations of `op; addi x8, x8, -1; bne x8, x0, loop` with
+/// `x6 = dividend` and `x7 = divisor`, returning the CPU once the loop exits.
+fn run_loop(config: &Config, op: u32, dividend: u64, divisor: u64) -> TestContext {
+    let b = InstructionBuilder::new;
+    let program = [
+        op,
+        b().addi(8, 8, -1).build(),
+        b().bne(8, 0, -8).build(),
+        b().jal(0, 0).build(),
+    ];
+    let mut tc = TestContext::from_config(config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    tc.set_reg(6, dividend);
+    tc.set_reg(7, divisor);
+    tc.set_reg(8, ITERS);
+
+    for _ in 0..10_000 {
+        if tc.cpu.stats.instructions_retired >= 3 * ITERS {
+            break;
+        }
+        tc.cpu.tick().expect("tick");
+    }
+    assert_eq!(tc.get_reg(8), 0, "loop ran to completion");
+    tc
+}
+
+fn config_with(div_latency: u64, div_early_exit: bool) -> Config {
+    let mut config = Config::default();
+    config.pipeline.div_latency = div_latency;
+    config.pipeline.div_early_exit = div_early_exit;
+    config
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Fixed latency
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn divide_loop_grows_by_latency_per_iteration() {
+    let op = div(5, 6, 7);
+    let fast = run_loop(&config_with(1, false), op, 1000, 7);
+    let slow = run_loop(&config_with(30, false), op, 1000, 7);
+
+    assert_eq!(slow.cpu.stats.cycles - fast.cpu.stats.cycles, 29 * ITERS);
+    assert_eq!(slow.cpu.stats.stalls_div, 29 * ITERS);
+    assert_eq!(fast.cpu.stats.stalls_div, 0);
+    assert_eq!(slow.get_reg(5), 142);
+}
+
+#[test]
+fn remainder_pays_divide_latency() {
+    let tc = run_loop(&config_with(12, false), remu(5, 6, 7), 1000, 7);
+
+    assert_eq!(tc.cpu.stats.stalls_div, 11 * ITERS);
+    assert_eq!(tc.get_reg(5), 6);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Early-exit divider
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn early_exit_scales_with_quotient_width() {
+    let op = div(5, 6, 7);
+    let small = run_loop(&config_with(64, true), op, 9, 3);
+    let wide = run_loop(&config_with(64, true), op, u64::MAX >> 1, 1);
+
+    // 9 / 3 can produce at most 3 quotient bits; i64::MAX / 1 needs all 63.
+    assert_eq!(small.cpu.stats.stalls_div, 2 * ITERS);
+    assert_eq!(wide.cpu.stats.stalls_div, 62 * ITERS);
+}
+
+#[test]
+fn early_exit_divide_by_zero_is_single_cycle() {
+    let tc = run_loop(&config_with(64, true), div(5, 6, 7), 1000, 0);
+
+    assert_eq!(tc.cpu.stats.stalls_div, 0);
+    assert_eq!(tc.get_reg(5), u64::MAX);
+}
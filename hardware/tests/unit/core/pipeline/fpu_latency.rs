@@ -0,0 +1,134 @@
+//! Multi-cycle FPU Latency Tests.
+//!
+//! Verifies that the `[fpu]` latency table holds the pipeline in execute:
+//! 1. A dependent FADD after FDIV retires exactly the configured latency later
+//! 2. FSQRT, FMUL and FMA each charge their own latency to `stalls_fpu`
+//! 3. Ops outside the table stay single-cycle
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+
+const BASE: u64 = 0x8000_0000;
+
+/// OP-FP major opcode.
+const OP_FP: u32 = 0x53;
+
+/// Encodes a double-precision OP-FP instruction with dynamic rounding.
+fn fp_d(funct7: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (0b111 << 12) | (rd << 7) | OP_FP
+}
+
+fn fadd_d(rd: u32, rs1: u32, rs2: u32) -> u32 {
+    fp_d(0b000_0001, rd, rs1, rs2)
+}
+
+fn fmul_d(rd: u32, rs1: u32, rs2: u32) -> u32 {
+    fp_d(0b000_1001, rd, rs1, rs2)
+}
+
+fn fdiv_d(rd: u32, rs1: u32, rs2: u32) -> u32 {
+    fp_d(0b000_1101, rd, rs1, rs2)
+}
+
+fn fsqrt_d(rd: u32, rs1: u32) -> u32 {
+    fp_d(0b010_1101, rd, rs1, 0)
+}
+
+fn fmadd_d(rd: u32, rs1: u32, rs2: u32, rs3: u32) -> u32 {
+    (rs3 << 27) | (0b01 << 25) | (rs2 << 20) | (rs1 << 15) | (0b111 << 12) | (rd << 7) | 0x43
+}
+
+/// Runs `op` followed by a dependent `fadd.d f4, f3, f1` and returns the CPU
+/// once both have retired, with `f1 = 6.0` and `f2 = 3.0` on entry.
+fn run_pair(config: &Config, op: u32) -> TestContext {
+    let spin = InstructionBuilder::new().jal(0, 0).build();
+    let mut tc = TestContext::from_config(config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[op, fadd_d(4, 3, 1), spin]);
+    tc.cpu.regs.write_f(1, 6.0f64.to_bits());
+    tc.cpu.regs.write_f(2, 3.0f64.to_bits());
+
+    for _ in 0..500 {
+        if tc.cpu.stats.instructions_retired >= 2 {
+            break;
+        }
+        tc.cpu.tick().expect("tick");
+    }
+    assert_eq!(tc.cpu.stats.instructions_retired, 2, "both ops retire");
+    tc
+}
+
+fn config_with(set: impl FnOnce(&mut Config)) -> Config {
+    let mut config = Config::default();
+    set(&mut config);
+    config
+}
+
+fn single_cycle() -> Config {
+    config_with(|c| {
+        c.fpu.fdiv_latency = 1;
+        c.fpu.fsqrt_latency = 1;
+        c.fpu.fmul_latency = 1;
+        c.fpu.fma_latency = 1;
+    })
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. FDIV → dependent FADD
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn fdiv_then_dependent_fadd_costs_configured_latency() {
+    let op = fdiv_d(3, 1, 2);
+    let fast = run_pair(&single_cycle(), op);
+    let slow = run_pair(&config_with(|c| c.fpu.fdiv_latency = 17), op);
+
+    assert_eq!(slow.cpu.stats.cycles - fast.cpu.stats.cycles, 16);
+    assert_eq!(slow.cpu.stats.stalls_fpu, 16);
+    assert_eq!(fast.cpu.stats.stalls_fpu, 0);
+    assert_eq!(f64::from_bits(slow.cpu.regs.read_f(4)), 8.0, "6 / 3 + 6");
+}
+
+#[test]
+fn zero_latency_is_single_cycle() {
+    let op = fdiv_d(3, 1, 2);
+    let zero = run_pair(&config_with(|c| c.fpu.fdiv_latency = 0), op);
+    let one = run_pair(&single_cycle(), op);
+
+    assert_eq!(zero.cpu.stats.cycles, one.cpu.stats.cycles);
+    assert_eq!(zero.cpu.stats.stalls_fpu, 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Per-op latencies
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn each_op_charges_its_own_latency() {
+    let config = config_with(|c| {
+        c.fpu.fdiv_latency = 30;
+        c.fpu.fsqrt_latency = 9;
+        c.fpu.fmul_latency = 4;
+        c.fpu.fma_latency = 6;
+    });
+
+    assert_eq!(run_pair(&config, fsqrt_d(3, 1)).cpu.stats.stalls_fpu, 8);
+    assert_eq!(run_pair(&config, fmul_d(3, 1, 2)).cpu.stats.stalls_fpu, 3);
+    assert_eq!(
+        run_pair(&config, fmadd_d(3, 1, 2, 1)).cpu.stats.stalls_fpu,
+        5
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Single-cycle ops
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn fadd_is_not_in_latency_table() {
+    let tc = run_pair(&Config::default(), fadd_d(3, 1, 2));
+
+    assert_eq!(tc.cpu.stats.stalls_fpu, 0);
+    assert_eq!(f64::from_bits(tc.cpu.regs.read_f(4)), 15.0, "6 + 3 + 6");
+}
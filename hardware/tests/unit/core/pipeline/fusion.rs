@@ -0,0 +1,139 @@
+//! Macro-Op Fusion Tests.
+//!
+//! Verifies the fusion idiom table and its effect on the front end:
+//! 1. Recognized idioms (far jump, constant build, indexed add, compare-and-branch)
+//! 2. Near misses that must not fuse (different registers, large shifts)
+//! 3. An `auipc`/`jalr` pair occupies a single fetch slot and is marked fused
+//! 4. The fused pair still computes the architecturally correct result
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::core::pipeline::fusion::is_fusible;
+use riscv_core::core::pipeline::stages::{decode_stage, fetch_stage};
+
+const BASE: u64 = 0x8000_0000;
+
+fn slli(rd: u32, rs1: u32, shamt: i32) -> u32 {
+    InstructionBuilder::new()
+        .opcode(0x13)
+        .rd(rd)
+        .rs1(rs1)
+        .funct3(1)
+        .imm(shamt)
+        .build()
+}
+
+/// Builds a width-1 context running `auipc x5, 0; jalr x1, 16(x5)` at `BASE`,
+/// with `addi x6, x0, 1` and a spin loop at the jump target.
+fn far_call_ctx(fusion: bool) -> TestContext {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    tc.cpu.pipeline_width = 1;
+    tc.cpu.fusion_enabled = fusion;
+
+    let auipc = InstructionBuilder::new().auipc(5, 0).build();
+    let jalr = InstructionBuilder::new().jalr(1, 5, 16).build();
+    let addi = InstructionBuilder::new().addi(6, 0, 1).build();
+    let spin = InstructionBuilder::new().jal(0, 0).build();
+    let tc = tc.load_program(BASE + 16, &[addi, spin]);
+    tc.load_program(BASE, &[auipc, jalr])
+}
+
+#[test]
+fn recognizes_fusion_idioms() {
+    let b = InstructionBuilder::new;
+
+    assert!(is_fusible(
+        b().auipc(5, 0).build(),
+        b().jalr(1, 5, 8).build()
+    ));
+    assert!(is_fusible(
+        b().lui(5, 0x12345).build(),
+        b().addi(5, 5, 0x678).build()
+    ));
+    assert!(is_fusible(
+        b().lui(5, 0x12345).build(),
+        b().addiw(5, 5, 1).build()
+    ));
+    assert!(is_fusible(
+        b().auipc(7, 1).build(),
+        b().addi(7, 7, -4).build()
+    ));
+    assert!(is_fusible(slli(5, 10, 3), b().add(5, 5, 11).build()));
+    assert!(is_fusible(slli(5, 10, 1), b().add(5, 11, 5).build()));
+    assert!(is_fusible(
+        b().slt(5, 10, 11).build(),
+        b().bne(5, 0, 16).build()
+    ));
+    assert!(is_fusible(
+        b().sltiu(5, 10, 4).build(),
+        b().beq(5, 0, -8).build()
+    ));
+}
+
+#[test]
+fn rejects_near_miss_pairs() {
+    let b = InstructionBuilder::new;
+
+    // jalr through a different base register.
+    assert!(!is_fusible(
+        b().auipc(5, 0).build(),
+        b().jalr(1, 6, 8).build()
+    ));
+    // addi into a different destination.
+    assert!(!is_fusible(
+        b().lui(5, 1).build(),
+        b().addi(6, 5, 1).build()
+    ));
+    // Shift amount beyond the scaled-index range.
+    assert!(!is_fusible(slli(5, 10, 4), b().add(5, 5, 11).build()));
+    // add writing somewhere other than the shifted register.
+    assert!(!is_fusible(slli(5, 10, 2), b().add(6, 5, 11).build()));
+    // Branch comparing against a register other than x0.
+    assert!(!is_fusible(
+        b().slt(5, 10, 11).build(),
+        b().bne(5, 7, 16).build()
+    ));
+    // First half writing x0 never fuses.
+    assert!(!is_fusible(
+        b().auipc(0, 0).build(),
+        b().jalr(1, 0, 8).build()
+    ));
+    // Non-candidate first instruction.
+    assert!(!is_fusible(
+        b().lw(5, 6, 0).build(),
+        b().addi(5, 5, 1).build()
+    ));
+}
+
+#[test]
+fn auipc_jalr_pair_fetches_into_one_slot() {
+    let mut tc = far_call_ctx(true);
+
+    fetch_stage(&mut tc.cpu);
+    assert_eq!(tc.cpu.if_id.entries.len(), 2, "pair should share one slot");
+
+    decode_stage(&mut tc.cpu);
+    let entries = &tc.cpu.id_ex.entries;
+    assert_eq!(entries.len(), 2, "fused pair should issue together");
+    assert!(!entries[0].ctrl.fused);
+    assert!(entries[1].ctrl.fused);
+    assert_eq!(tc.cpu.stats.fused_pairs, 1);
+}
+
+#[test]
+fn fusion_disabled_fetches_one_instruction() {
+    let mut tc = far_call_ctx(false);
+
+    fetch_stage(&mut tc.cpu);
+    assert_eq!(tc.cpu.if_id.entries.len(), 1);
+}
+
+#[test]
+fn fused_far_call_links_and_jumps() {
+    let mut tc = far_call_ctx(true);
+    tc.run(40);
+
+    assert_eq!(tc.get_reg(1), BASE + 8, "link register");
+    assert_eq!(tc.get_reg(6), 1, "jump target executed");
+    assert_eq!(tc.cpu.stats.fused_pairs, 1);
+}
@@ -0,0 +1,110 @@
+//! Non-Speculative MMIO Load Tests.
+//!
+//! Verifies that loads from device regions with read side effects (the UART
+//! receive buffer pops its FIFO) only reach the device once they will commit:
+//! 1. A wrong-path load squashed by a branch misprediction never reads the device
+//! 2. A device load already performed in MEM is not squashed and replayed by an interrupt
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::csr;
+use riscv_core::soc::devices::Uart;
+
+const BASE: u64 = 0x8000_0000;
+const HANDLER: u64 = BASE + 0x100;
+const UART_BASE: u64 = 0x1000_0000;
+/// Receiver Buffer Register (read pops the RX FIFO).
+const UART_RBR: u64 = UART_BASE;
+/// Line Status Register; bit 0 is "data ready".
+const UART_LSR: u64 = UART_BASE + 5;
+
+/// `csrrw x0, mip, x0` — clears the software-writable pending bits.
+const CLEAR_MIP: u32 = 0x3440_1073;
+const MRET: u32 = 0x3020_0073;
+
+/// `lbu rd, 0(rs1)`.
+fn lbu(rd: u32, rs1: u32) -> u32 {
+    InstructionBuilder::new()
+        .opcode(0x03)
+        .rd(rd)
+        .rs1(rs1)
+        .funct3(0b100)
+        .imm(0)
+        .build()
+}
+
+/// Builds a context with a UART holding `rx` in its receive FIFO and x10 = UART base.
+fn uart_ctx(rx: &[u8]) -> TestContext {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    let mut uart = Uart::new(UART_BASE, true);
+    for &b in rx {
+        uart.push_rx(b);
+    }
+    tc.cpu.bus.bus.add_device(Box::new(uart));
+    tc.set_reg(10, UART_BASE);
+    tc
+}
+
+#[test]
+fn wrong_path_uart_load_does_not_pop_fifo() {
+    let mut tc = uart_ctx(b"A");
+    tc.cpu.wrong_path_pollution = true;
+
+    // beq x0, x0, +12 is statically predicted not-taken for a forward branch, so the
+    // fall-through lbu is fetched down the wrong path and squashed at resolution.
+    let program = [
+        InstructionBuilder::new().beq(0, 0, 12).build(),
+        lbu(5, 10),
+        InstructionBuilder::new().jal(0, 0).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    let mut tc = tc.load_program(BASE, &program);
+    tc.run(40);
+
+    assert_eq!(tc.get_reg(5), 0, "squashed load must not write back");
+    assert_eq!(
+        tc.cpu.bus.bus.read_u8(UART_LSR) & 1,
+        1,
+        "byte still pending"
+    );
+    assert_eq!(tc.cpu.bus.bus.read_u8(UART_RBR), b'A');
+}
+
+#[test]
+fn interrupt_after_mem_does_not_replay_uart_load() {
+    let program = [
+        lbu(5, 10),
+        InstructionBuilder::new().addi(6, 0, 1).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    let tc = uart_ctx(b"AB").load_program(HANDLER, &[CLEAR_MIP, MRET]);
+    let mut tc = tc.load_program(BASE, &program);
+    tc.cpu.direct_mode = false;
+    tc.cpu.csrs.mtvec = HANDLER;
+    tc.cpu.csrs.mstatus |= csr::MSTATUS_MIE;
+    tc.cpu.csrs.mie |= csr::MIP_SSIP;
+
+    // Step until the load has performed its device read in MEM, then raise an
+    // interrupt before it reaches writeback.
+    for _ in 0..20 {
+        tc.cpu.tick().unwrap();
+        if tc.cpu.mem_wb.entries.iter().any(|e| e.pc == BASE) {
+            break;
+        }
+    }
+    assert!(tc.cpu.mem_wb.entries.iter().any(|e| e.pc == BASE));
+    tc.cpu.csrs.mip |= csr::MIP_SSIP;
+    tc.run(60);
+
+    assert_eq!(tc.get_reg(6), 1, "execution resumed after the handler");
+    assert_eq!(
+        tc.get_reg(5),
+        b'A' as u64,
+        "load observes the first byte once"
+    );
+    assert_eq!(
+        tc.cpu.bus.bus.read_u8(UART_RBR),
+        b'B',
+        "second byte not consumed"
+    );
+}
@@ -1,3 +1,9 @@
+pub mod div_latency;
+pub mod fpu_latency;
+pub mod fusion;
 pub mod hazards;
+pub mod mmio_loads;
 pub mod stages;
+pub mod superscalar;
 pub mod wfi;
+pub mod wrong_path;
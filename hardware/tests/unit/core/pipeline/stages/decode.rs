@@ -14,6 +14,11 @@
 //!   7. Fetch-trap propagation
 //!   8. Illegal instruction trap generation
 //!   9. Intra-bundle hazard detection (superscalar)
+//!  10. AMO ordering bits (aq / rl)
+//!  11. Zihintntl hints tag the following memory access
+//!  12. FP conversions select signed/unsigned variants from `rs2`
+//!  13. Zbb bit-manipulation encodings
+//!  14. Zba address generation encodings
 
 use crate::common::builder::instruction::InstructionBuilder;
 use crate::common::harness::TestContext;
@@ -40,6 +45,7 @@ fn decode_one(tc: &mut TestContext, inst: u32) -> riscv_core::core::pipeline::la
         pred_taken: false,
         pred_target: 0,
         trap: None,
+        ..Default::default()
     }];
     decode_stage(&mut tc.cpu);
     assert!(
@@ -60,6 +66,7 @@ fn decode_expect_nop(tc: &mut TestContext, inst: u32) {
         pred_taken: false,
         pred_target: 0,
         trap: None,
+        ..Default::default()
     }];
     decode_stage(&mut tc.cpu);
     assert!(
@@ -521,6 +528,7 @@ fn fetch_trap_propagates_to_id_ex() {
         pred_taken: false,
         pred_target: 0,
         trap: Some(trap.clone()),
+        ..Default::default()
     }];
 
     decode_stage(&mut tc.cpu);
@@ -583,6 +591,7 @@ fn branch_prediction_metadata_forwarded() {
         pred_taken: true,
         pred_target: 0x8000_0008,
         trap: None,
+        ..Default::default()
     }];
 
     decode_stage(&mut tc.cpu);
@@ -617,6 +626,7 @@ fn multiple_independent_instructions_decoded() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -625,6 +635,7 @@ fn multiple_independent_instructions_decoded() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -659,6 +670,7 @@ fn intra_bundle_raw_hazard_stalls_second() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -667,6 +679,7 @@ fn intra_bundle_raw_hazard_stalls_second() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -704,6 +717,7 @@ fn intra_bundle_no_hazard_on_different_registers() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -712,6 +726,7 @@ fn intra_bundle_no_hazard_on_different_registers() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -740,6 +755,7 @@ fn intra_bundle_hazard_on_rs2() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -748,6 +764,7 @@ fn intra_bundle_hazard_on_rs2() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -778,6 +795,7 @@ fn writes_to_x0_do_not_cause_hazard() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -786,6 +804,7 @@ fn writes_to_x0_do_not_cause_hazard() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -821,6 +840,7 @@ fn pc_and_inst_size_forwarded() {
         pred_taken: false,
         pred_target: 0,
         trap: None,
+        ..Default::default()
     }];
 
     decode_stage(&mut tc.cpu);
@@ -849,6 +869,7 @@ fn nop_between_real_instructions_is_skipped() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
         IfIdEntry {
             pc: 0x8000_0004,
@@ -857,6 +878,7 @@ fn nop_between_real_instructions_is_skipped() {
             pred_taken: false,
             pred_target: 0,
             trap: None,
+            ..Default::default()
         },
     ];
 
@@ -933,3 +955,333 @@ fn empty_if_id_produces_empty_id_ex() {
         "Empty IF/ID should produce empty ID/EX"
     );
 }
+
+// ══════════════════════════════════════════════════════════
+// 20. Zbc carry-less multiply
+// ══════════════════════════════════════════════════════════
+
+const CLMUL_A0_A1_A2: u32 = 0x0AC5_9533;
+const CLMULR_A0_A1_A2: u32 = 0x0AC5_A533;
+const CLMULH_A0_A1_A2: u32 = 0x0AC5_B533;
+
+#[test]
+fn zbc_decodes_when_enabled() {
+    let mut tc = ctx();
+    let id = decode_one(&mut tc, CLMUL_A0_A1_A2);
+    assert!(id.trap.is_none());
+    assert!(matches!(id.ctrl.alu, AluOp::Clmul));
+    assert!(matches!(id.ctrl.b_src, OpBSrc::Reg2), "CLMUL uses Reg2");
+    assert!(id.ctrl.reg_write);
+
+    let id = decode_one(&mut tc, CLMULH_A0_A1_A2);
+    assert!(matches!(id.ctrl.alu, AluOp::Clmulh));
+
+    let id = decode_one(&mut tc, CLMULR_A0_A1_A2);
+    assert!(matches!(id.ctrl.alu, AluOp::Clmulr));
+}
+
+#[test]
+fn zbc_traps_when_disabled() {
+    let mut tc = ctx();
+    tc.cpu.zbc_enabled = false;
+    let id = decode_one(&mut tc, CLMUL_A0_A1_A2);
+    assert!(
+        id.trap.is_some(),
+        "CLMUL must be illegal when Zbc is disabled"
+    );
+}
+
+#[test]
+fn zbc_word_variant_is_illegal() {
+    let mut tc = ctx();
+    // Same fields under OP-32 (opcode 0x3B) have no Zbc encoding
+    let id = decode_one(&mut tc, (CLMUL_A0_A1_A2 & !0x7F) | 0x3B);
+    assert!(id.trap.is_some(), "CLMULW does not exist");
+}
+
+// ══════════════════════════════════════════════════════════
+// 21. AMO ordering bits (aq / rl)
+// ══════════════════════════════════════════════════════════
+
+/// `amoswap.w a0, a2, (a1)` with both ordering bits clear.
+const AMOSWAP_W: u32 = 0x08C5_A52F;
+const AQ_BIT: u32 = 1 << 26;
+const RL_BIT: u32 = 1 << 25;
+
+#[test]
+fn amo_ordering_bits_decode_into_control_signals() {
+    let mut tc = ctx();
+    let cases = [
+        (AMOSWAP_W, false, false),
+        (AMOSWAP_W | AQ_BIT, true, false),
+        (AMOSWAP_W | RL_BIT, false, true),
+        (AMOSWAP_W | AQ_BIT | RL_BIT, true, true),
+    ];
+    for (inst, aq, rl) in cases {
+        let id = decode_one(&mut tc, inst);
+        assert!(id.trap.is_none(), "{inst:#010x} must decode");
+        assert_eq!(id.ctrl.aq, aq, "aq for {inst:#010x}");
+        assert_eq!(id.ctrl.rl, rl, "rl for {inst:#010x}");
+    }
+}
+
+#[test]
+fn lr_sc_accept_ordering_bits() {
+    let mut tc = ctx();
+    // lr.w.aq a0, (a1) and sc.w.rl a0, a2, (a1)
+    let lr_aq = 0x1405_A52F;
+    let sc_rl = 0x1AC5_A52F;
+
+    let id = decode_one(&mut tc, lr_aq);
+    assert!(id.trap.is_none());
+    assert!(id.ctrl.aq && !id.ctrl.rl);
+
+    let id = decode_one(&mut tc, sc_rl);
+    assert!(id.trap.is_none());
+    assert!(!id.ctrl.aq && id.ctrl.rl);
+}
+
+// ══════════════════════════════════════════════════════════
+// 22. Zihintntl non-temporal hints
+// ══════════════════════════════════════════════════════════
+
+/// `ntl.all` (`add x0, x0, x5`).
+const NTL_ALL: u32 = 0x0050_0033;
+/// `ntl.p1` (`add x0, x0, x2`).
+const NTL_P1: u32 = 0x0020_0033;
+/// `lw a0, 0(a1)`.
+const LW_A0_A1: u32 = 0x0005_A503;
+
+/// Decodes `insts` as consecutive single-instruction cycles starting at 0x8000_0000.
+fn decode_sequence(tc: &mut TestContext, insts: &[u32]) -> Vec<u8> {
+    insts
+        .iter()
+        .enumerate()
+        .map(|(i, &inst)| {
+            tc.cpu.if_id.entries = vec![IfIdEntry {
+                pc: 0x8000_0000 + 4 * i as u64,
+                inst,
+                inst_size: 4,
+                pred_taken: false,
+                pred_target: 0,
+                trap: None,
+                ..Default::default()
+            }];
+            decode_stage(&mut tc.cpu);
+            tc.cpu.id_ex.entries.remove(0).ctrl.ntl_levels
+        })
+        .collect()
+}
+
+#[test]
+fn ntl_hint_tags_following_memory_access() {
+    let mut tc = ctx();
+    tc.cpu.ntl_enabled = true;
+    assert_eq!(decode_sequence(&mut tc, &[NTL_ALL, LW_A0_A1]), [0, 3]);
+
+    let mut tc = ctx();
+    tc.cpu.ntl_enabled = true;
+    assert_eq!(decode_sequence(&mut tc, &[NTL_P1, LW_A0_A1]), [0, 1]);
+}
+
+#[test]
+fn ntl_hint_applies_only_to_the_next_instruction() {
+    let mut tc = ctx();
+    tc.cpu.ntl_enabled = true;
+    let add = InstructionBuilder::new().add(6, 6, 7).build();
+    assert_eq!(
+        decode_sequence(&mut tc, &[NTL_ALL, add, LW_A0_A1]),
+        [0, 0, 0]
+    );
+}
+
+#[test]
+fn ntl_hint_ignored_when_disabled() {
+    let mut tc = ctx();
+    let levels = decode_sequence(&mut tc, &[NTL_ALL, LW_A0_A1]);
+    assert_eq!(levels, [0, 0]);
+}
+
+// ══════════════════════════════════════════════════════════
+// 23. FP <-> integer conversion variants
+// ══════════════════════════════════════════════════════════
+
+/// Encode an OP-FP conversion with the given `funct7` and `rs2` selector.
+fn fcvt(funct7: u32, rs2: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (1 << 15) | (0b111 << 12) | (5 << 7) | 0x53
+}
+
+#[test]
+fn fcvt_to_int_decodes_signedness_from_rs2() {
+    let mut tc = ctx();
+    for funct7 in [0b110_0000, 0b110_0001] {
+        let ops: Vec<AluOp> = (0..4)
+            .map(|rs2| decode_one(&mut tc, fcvt(funct7, rs2)).ctrl.alu)
+            .collect();
+        assert!(matches!(
+            ops[..],
+            [AluOp::FCvtWS, AluOp::FCvtWuS, AluOp::FCvtLS, AluOp::FCvtLuS]
+        ));
+    }
+}
+
+#[test]
+fn fcvt_from_int_decodes_signedness_from_rs2() {
+    let mut tc = ctx();
+    for funct7 in [0b110_1000, 0b110_1001] {
+        let ops: Vec<AluOp> = (0..4)
+            .map(|rs2| decode_one(&mut tc, fcvt(funct7, rs2)).ctrl.alu)
+            .collect();
+        assert!(matches!(
+            ops[..],
+            [AluOp::FCvtSW, AluOp::FCvtSWu, AluOp::FCvtSL, AluOp::FCvtSLu]
+        ));
+    }
+}
+
+#[test]
+fn fcvt_reserved_rs2_is_illegal() {
+    let mut tc = ctx();
+    let inst = fcvt(0b110_0000, 4);
+    assert_eq!(
+        decode_one(&mut tc, inst).trap,
+        Some(riscv_core::common::Trap::IllegalInstruction(inst))
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 24. Zbb basic bit-manipulation
+// ══════════════════════════════════════════════════════════
+
+const CLZ_A0_A1: u32 = 0x6005_9513;
+const CPOPW_A0_A1: u32 = 0x6025_951B;
+const SEXT_H_A0_A1: u32 = 0x6055_9513;
+const ORC_B_A0_A1: u32 = 0x2875_D513;
+const REV8_A0_A1: u32 = 0x6B85_D513;
+const MAX_A0_A1_A2: u32 = 0x0AC5_E533;
+const MINU_A0_A1_A2: u32 = 0x0AC5_D533;
+const ZEXT_H_A0_A1: u32 = 0x0805_C53B;
+
+#[test]
+fn zbb_unary_ops_decode() {
+    let mut tc = ctx();
+    let id = decode_one(&mut tc, CLZ_A0_A1);
+    assert!(id.trap.is_none());
+    assert!(matches!(id.ctrl.alu, AluOp::Clz));
+    assert!(id.ctrl.reg_write);
+
+    let id = decode_one(&mut tc, CPOPW_A0_A1);
+    assert!(matches!(id.ctrl.alu, AluOp::Cpop));
+    assert!(id.ctrl.is_rv32, "CPOPW counts the low word");
+
+    assert!(matches!(
+        decode_one(&mut tc, SEXT_H_A0_A1).ctrl.alu,
+        AluOp::SextH
+    ));
+    assert!(matches!(
+        decode_one(&mut tc, ORC_B_A0_A1).ctrl.alu,
+        AluOp::OrcB
+    ));
+    assert!(matches!(
+        decode_one(&mut tc, REV8_A0_A1).ctrl.alu,
+        AluOp::Rev8
+    ));
+}
+
+#[test]
+fn zbb_register_ops_decode() {
+    let mut tc = ctx();
+    let id = decode_one(&mut tc, MAX_A0_A1_A2);
+    assert!(matches!(id.ctrl.alu, AluOp::Max));
+    assert!(matches!(id.ctrl.b_src, OpBSrc::Reg2));
+    assert!(matches!(
+        decode_one(&mut tc, MINU_A0_A1_A2).ctrl.alu,
+        AluOp::Minu
+    ));
+
+    let id = decode_one(&mut tc, ZEXT_H_A0_A1);
+    assert!(matches!(id.ctrl.alu, AluOp::ZextH));
+    assert!(!id.ctrl.is_rv32, "ZEXT.H produces a 64-bit result");
+}
+
+#[test]
+fn zbb_does_not_shadow_base_shifts() {
+    let mut tc = ctx();
+    // srai a0, a1, 8 / slli a0, a1, 8
+    assert!(matches!(
+        decode_one(&mut tc, 0x4085_D513).ctrl.alu,
+        AluOp::Sra
+    ));
+    assert!(matches!(
+        decode_one(&mut tc, 0x0085_9513).ctrl.alu,
+        AluOp::Sll
+    ));
+    // CLMUL shares funct7 with MIN/MAX.
+    assert!(matches!(
+        decode_one(&mut tc, CLMUL_A0_A1_A2).ctrl.alu,
+        AluOp::Clmul
+    ));
+}
+
+#[test]
+fn zbb_traps_when_disabled() {
+    let mut tc = ctx();
+    tc.cpu.zbb_enabled = false;
+    for inst in [CLZ_A0_A1, REV8_A0_A1, MAX_A0_A1_A2, ZEXT_H_A0_A1] {
+        assert!(
+            decode_one(&mut tc, inst).trap.is_some(),
+            "{inst:#010x} must be illegal when Zbb is disabled"
+        );
+    }
+}
+
+// ══════════════════════════════════════════════════════════
+// 25. Zba address generation
+// ══════════════════════════════════════════════════════════
+
+const SH1ADD_A0_A1_A2: u32 = 0x20C5_A533;
+const SH3ADD_A0_A1_A2: u32 = 0x20C5_E533;
+const ADD_UW_A0_A1_A2: u32 = 0x08C5_853B;
+const SLLI_UW_A0_A1_3: u32 = 0x0835_951B;
+
+#[test]
+fn zba_ops_decode() {
+    let mut tc = ctx();
+    let id = decode_one(&mut tc, SH1ADD_A0_A1_A2);
+    assert!(id.trap.is_none());
+    assert!(matches!(id.ctrl.alu, AluOp::Sh1Add));
+    assert!(matches!(id.ctrl.b_src, OpBSrc::Reg2));
+    assert!(matches!(
+        decode_one(&mut tc, SH3ADD_A0_A1_A2).ctrl.alu,
+        AluOp::Sh3Add
+    ));
+
+    let id = decode_one(&mut tc, ADD_UW_A0_A1_A2);
+    assert!(matches!(id.ctrl.alu, AluOp::AddUw));
+    assert!(!id.ctrl.is_rv32, "ADD.UW produces a 64-bit result");
+
+    let id = decode_one(&mut tc, SLLI_UW_A0_A1_3);
+    assert!(matches!(id.ctrl.alu, AluOp::SlliUw));
+    assert!(matches!(id.ctrl.b_src, OpBSrc::Imm));
+    assert!(!id.ctrl.is_rv32);
+}
+
+#[test]
+fn zba_traps_when_disabled() {
+    let mut tc = ctx();
+    tc.cpu.zba_enabled = false;
+    for inst in [SH1ADD_A0_A1_A2, ADD_UW_A0_A1_A2, SLLI_UW_A0_A1_3] {
+        assert!(
+            decode_one(&mut tc, inst).trap.is_some(),
+            "{inst:#010x} must be illegal when Zba is disabled"
+        );
+    }
+}
+
+#[test]
+fn zba_shadd_word_form_is_illegal() {
+    let mut tc = ctx();
+    // SH1ADD fields under OP-32 are SH1ADD.UW, which is not implemented.
+    let id = decode_one(&mut tc, (SH1ADD_A0_A1_A2 & !0x7F) | 0x3B);
+    assert!(id.trap.is_some());
+}
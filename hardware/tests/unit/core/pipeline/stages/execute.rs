@@ -12,12 +12,21 @@
 //!   9. Trap propagation without ALU execution
 //!  10. Store data routing (store_data = forwarded rs2)
 //!  11. Multiple entries and flush-remaining semantics
+//!  12. FENCE.I drains in-flight stores before refetching
+//!  13. FP exception flags accrue into `fcsr` and read back through `fflags`
+//!  14. FP rounding mode resolved from the `rm` field or `frm`
+//!  15. FCVT.S.D / FCVT.D.S precision conversions and NaN-boxed sources
+//!  18. SFENCE.VMA honours its address and ASID operands
+//!  19. `mstatus.FS` — FP instructions and FP CSRs trap while Off; FP writes set Dirty
 
 use crate::common::builder::instruction::InstructionBuilder;
 use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::core::arch::csr;
 use riscv_core::core::pipeline::latches::{IdExEntry, IfIdEntry};
-use riscv_core::core::pipeline::signals::{AluOp, ControlSignals, MemWidth, OpASrc, OpBSrc};
+use riscv_core::core::pipeline::signals::{AluOp, ControlSignals, CsrOp, MemWidth, OpASrc, OpBSrc};
 use riscv_core::core::pipeline::stages::execute_stage;
+use riscv_core::core::units::fpu::Fpu;
 
 // ══════════════════════════════════════════════════════════
 // Helpers
@@ -976,3 +985,375 @@ fn rv32_sub_negative_result_sign_extends() {
     // SUBW: 0 - 1 = -1 in 32-bit, sign-extended to 64-bit = 0xFFFF_FFFF_FFFF_FFFF
     assert_eq!(ex.alu as i64, -1, "SUBW negative sign-extends to 64 bits");
 }
+
+// ══════════════════════════════════════════════════════════
+// 14. FENCE.I orders prior stores before instruction fetch
+// ══════════════════════════════════════════════════════════
+
+const FENCE_I: u32 = 0x0000_100F;
+
+/// Self-modifying code: a store rewrites the instruction after `fence.i`.
+///
+/// With a 2-wide pipeline the store and `fence.i` issue in the same bundle, so the
+/// store has not reached the memory stage when `fence.i` redirects fetch.
+#[test]
+fn fence_i_drains_same_bundle_store_before_refetch() {
+    let target = PC + 8;
+    let patched = InstructionBuilder::new().addi(7, 0, 42).build();
+    let program = [
+        InstructionBuilder::new().sw(5, 6, 0).build(),
+        FENCE_I,
+        InstructionBuilder::new().addi(7, 0, 1).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, PC)
+        .load_program(PC, &program);
+    tc.cpu.pipeline_width = 2;
+    tc.set_reg(5, target);
+    tc.set_reg(6, patched as u64);
+    tc.run(30);
+
+    assert_eq!(tc.cpu.bus.bus.read_u32(target), patched);
+    assert_eq!(
+        tc.get_reg(7),
+        42,
+        "fetch after FENCE.I must see the stored instruction"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 15. FP exception flags accrue into fcsr
+// ══════════════════════════════════════════════════════════
+
+/// Build an IdExEntry for a double-precision FP operation writing `rd`.
+fn fp_entry(alu: AluOp, a: f64, b: f64, rd: usize) -> IdExEntry {
+    let mut entry = alu_entry(alu, a.to_bits(), b.to_bits(), rd);
+    entry.ctrl.reg_write = false;
+    entry.ctrl.fp_reg_write = true;
+    entry
+}
+
+/// Build an IdExEntry for `csrrs rd, addr, x0`.
+fn csr_read_entry(addr: u32, rd: usize) -> IdExEntry {
+    IdExEntry {
+        pc: PC,
+        inst: (addr << 20) | (2 << 12) | ((rd as u32) << 7) | 0x73,
+        inst_size: INST_SIZE,
+        rd,
+        ctrl: ControlSignals {
+            reg_write: true,
+            is_system: true,
+            csr_addr: addr,
+            csr_op: CsrOp::Rs,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn fdiv_by_zero_sets_dz_readable_through_fflags() {
+    let mut tc = ctx();
+    let ex = exec_one(&mut tc, fp_entry(AluOp::FDiv, 1.0, 0.0, 3));
+    assert_eq!(f64::from_bits(ex.alu), f64::INFINITY);
+
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FFLAGS, 5));
+    assert_eq!(ex.alu, 1 << 3, "fflags.DZ");
+}
+
+#[test]
+fn fp_flags_accumulate_until_cleared() {
+    let mut tc = ctx();
+    exec_one(&mut tc, fp_entry(AluOp::FDiv, 1.0, 3.0, 3)); // NX
+    exec_one(&mut tc, fp_entry(AluOp::FDiv, 0.0, 0.0, 3)); // NV
+    exec_one(&mut tc, fp_entry(AluOp::FAdd, 1.0, 2.0, 3)); // exact: no new flags
+
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FCSR, 5));
+    assert_eq!(ex.alu, (1 << 4) | 1, "NV | NX");
+
+    tc.cpu.csr_write(csr::FFLAGS, 0);
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FFLAGS, 5));
+    assert_eq!(ex.alu, 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 16. FP rounding mode resolution
+// ══════════════════════════════════════════════════════════
+
+/// Build an IdExEntry for `fcvt.w.d x5, f1, rm` converting `x`.
+fn fcvt_w_d_entry(x: f64, rm: u32) -> IdExEntry {
+    let mut entry = alu_entry(AluOp::FCvtWS, x.to_bits(), 0, 5);
+    entry.inst = (0x61 << 25) | (1 << 15) | (rm << 12) | (5 << 7) | 0x53;
+    entry
+}
+
+#[test]
+fn fcvt_static_rm_selects_rounding() {
+    let mut tc = ctx();
+    assert_eq!(
+        exec_one(&mut tc, fcvt_w_d_entry(-2.5, 0b001)).alu as i64,
+        -2
+    ); // RTZ
+    assert_eq!(
+        exec_one(&mut tc, fcvt_w_d_entry(-2.5, 0b010)).alu as i64,
+        -3
+    ); // RDN
+    assert_eq!(
+        exec_one(&mut tc, fcvt_w_d_entry(-2.5, 0b011)).alu as i64,
+        -2
+    ); // RUP
+}
+
+#[test]
+fn fcvt_dynamic_rm_reads_frm() {
+    let mut tc = ctx();
+    tc.cpu.csr_write(csr::FRM, 0b010); // RDN
+    assert_eq!(exec_one(&mut tc, fcvt_w_d_entry(2.5, 0b111)).alu as i64, 2);
+    tc.cpu.csr_write(csr::FRM, 0b011); // RUP
+    assert_eq!(exec_one(&mut tc, fcvt_w_d_entry(2.5, 0b111)).alu as i64, 3);
+}
+
+#[test]
+fn reserved_rm_raises_illegal_instruction() {
+    let mut tc = ctx();
+    let entry = fcvt_w_d_entry(2.5, 0b101);
+    let inst = entry.inst;
+    let ex = exec_one(&mut tc, entry);
+    assert_eq!(ex.trap, Some(Trap::IllegalInstruction(inst)));
+
+    tc.cpu.csr_write(csr::FRM, 0b110);
+    let ex = exec_one(&mut tc, fcvt_w_d_entry(2.5, 0b111));
+    assert!(
+        matches!(ex.trap, Some(Trap::IllegalInstruction(_))),
+        "DYN with a reserved frm is illegal"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 17. FP precision conversions
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn fcvt_d_s_widens_boxed_single() {
+    let mut tc = ctx();
+    let ex = exec_one(&mut tc, alu_entry(AluOp::FCvtDS, Fpu::box_f32(1.5), 0, 5));
+    assert_eq!(f64::from_bits(ex.alu), 1.5);
+}
+
+#[test]
+fn fcvt_d_s_unboxed_source_is_canonical_nan() {
+    let mut tc = ctx();
+    // Upper word zero: the source reads as the canonical NaN, not 1.5.
+    let raw = 1.5f32.to_bits() as u64;
+    let ex = exec_one(&mut tc, alu_entry(AluOp::FCvtDS, raw, 0, 5));
+    assert_eq!(ex.alu, 0x7ff8_0000_0000_0000);
+}
+
+#[test]
+fn fcvt_s_d_narrows_and_boxes() {
+    let mut tc = ctx();
+    let mut entry = alu_entry(AluOp::FCvtSD, 1.5f64.to_bits(), 0, 5);
+    entry.ctrl.is_rv32 = true;
+    let ex = exec_one(&mut tc, entry);
+    assert_eq!(ex.alu, Fpu::box_f32(1.5));
+}
+
+/// Build an IdExEntry for `fcvt.s.d f5, f1, rm` converting `x`.
+fn fcvt_s_d_entry(x: f64, rm: u32) -> IdExEntry {
+    let mut entry = fp_entry(AluOp::FCvtSD, x, 0.0, 5);
+    entry.ctrl.is_rv32 = true;
+    entry.inst = (0x20 << 25) | (1 << 20) | (1 << 15) | (rm << 12) | (5 << 7) | 0x53;
+    entry
+}
+
+#[test]
+fn fcvt_s_d_rounds_with_rm_and_raises_nx() {
+    let mut tc = ctx();
+    let x = 1.0 + 2f64.powi(-30);
+    assert_eq!(
+        exec_one(&mut tc, fcvt_s_d_entry(x, 0b010)).alu,
+        Fpu::box_f32(1.0)
+    ); // RDN
+    assert_eq!(
+        exec_one(&mut tc, fcvt_s_d_entry(x, 0b011)).alu,
+        Fpu::box_f32(1.0 + f32::EPSILON)
+    ); // RUP
+
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FFLAGS, 5));
+    assert_eq!(ex.alu, 1, "fflags.NX");
+}
+
+#[test]
+fn fcvt_s_d_overflow_raises_of_and_nx() {
+    let mut tc = ctx();
+    let ex = exec_one(&mut tc, fcvt_s_d_entry(1e39, 0b000));
+    assert_eq!(ex.alu, Fpu::box_f32(f32::INFINITY));
+
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FFLAGS, 5));
+    assert_eq!(ex.alu, (1 << 2) | 1, "OF | NX");
+}
+
+#[test]
+fn fcvt_d_s_signaling_nan_raises_nv() {
+    let mut tc = ctx();
+    let snan = Fpu::box_f32(f32::from_bits(0x7f80_0001));
+    let mut entry = alu_entry(AluOp::FCvtDS, snan, 0, 5);
+    entry.ctrl.fp_reg_write = true;
+    entry.ctrl.reg_write = false;
+    let ex = exec_one(&mut tc, entry);
+    assert_eq!(ex.alu, 0x7ff8_0000_0000_0000);
+
+    let ex = exec_one(&mut tc, csr_read_entry(csr::FFLAGS, 5));
+    assert_eq!(ex.alu, 1 << 4, "fflags.NV");
+}
+
+// ══════════════════════════════════════════════════════════
+// 18. SFENCE.VMA selective invalidation
+// ══════════════════════════════════════════════════════════
+
+/// Build an SFENCE.VMA with the given register indices and forwarded values.
+fn sfence_entry(rs1: usize, rv1: u64, rs2: usize, rv2: u64) -> IdExEntry {
+    IdExEntry {
+        pc: PC,
+        inst: 0x1200_0073 | ((rs2 as u32) << 20) | ((rs1 as u32) << 15),
+        inst_size: INST_SIZE,
+        rs1,
+        rs2,
+        rv1,
+        rv2,
+        ctrl: ControlSignals {
+            is_system: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// PTE bits for a readable leaf, optionally global.
+fn leaf(global: bool) -> u64 {
+    0b11 | if global { 1 << 5 } else { 0 }
+}
+
+#[test]
+fn sfence_vma_asid_evicts_only_that_address_space() {
+    let mut tc = ctx();
+    let dtlb = &mut tc.cpu.mmu.dtlb;
+    dtlb.insert(0x10, 0x100, leaf(false), 1, 0);
+    dtlb.insert(0x11, 0x101, leaf(false), 2, 0);
+    dtlb.insert(0x12, 0x102, leaf(true), 1, 0);
+
+    exec_one(&mut tc, sfence_entry(0, 0, 11, 1));
+
+    let dtlb = &tc.cpu.mmu.dtlb;
+    assert_eq!(dtlb.lookup(0x10, 1), None, "ASID 1 entry evicted");
+    assert!(dtlb.lookup(0x11, 2).is_some(), "ASID 2 entry kept");
+    assert!(dtlb.lookup(0x12, 1).is_some(), "global entry kept");
+}
+
+#[test]
+fn sfence_vma_address_evicts_only_that_page() {
+    let mut tc = ctx();
+    tc.cpu.mmu.dtlb.insert(0x10, 0x100, leaf(false), 1, 0);
+    tc.cpu.mmu.dtlb.insert(0x11, 0x101, leaf(true), 1, 0);
+    tc.cpu.mmu.itlb.insert(0x11, 0x101, leaf(true), 1, 0);
+
+    exec_one(&mut tc, sfence_entry(10, 0x11 << 12, 0, 0));
+
+    assert!(tc.cpu.mmu.dtlb.lookup(0x10, 1).is_some());
+    assert_eq!(
+        tc.cpu.mmu.dtlb.lookup(0x11, 1),
+        None,
+        "global entries match any address fence"
+    );
+    assert_eq!(tc.cpu.mmu.itlb.lookup(0x11, 1), None, "ITLB is fenced too");
+}
+
+#[test]
+fn sfence_vma_x0_x0_flushes_everything() {
+    let mut tc = ctx();
+    tc.cpu.mmu.dtlb.insert(0x10, 0x100, leaf(false), 1, 0);
+    tc.cpu.mmu.dtlb.insert(0x12, 0x102, leaf(true), 2, 0);
+
+    exec_one(&mut tc, sfence_entry(0, 0, 0, 0));
+
+    assert_eq!(tc.cpu.mmu.dtlb.lookup(0x10, 1), None);
+    assert_eq!(tc.cpu.mmu.dtlb.lookup(0x12, 2), None);
+}
+
+#[test]
+fn satp_asid_switch_keeps_tagged_entries() {
+    let mut tc = ctx();
+    let sv39 = csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT;
+    tc.cpu
+        .csr_write(csr::SATP, sv39 | (1 << csr::SATP_ASID_SHIFT));
+    tc.cpu.mmu.dtlb.insert(0x10, 0x100, leaf(false), 1, 0);
+
+    tc.cpu
+        .csr_write(csr::SATP, sv39 | (2 << csr::SATP_ASID_SHIFT) | 0x80);
+    assert!(
+        tc.cpu.mmu.dtlb.lookup(0x10, 1).is_some(),
+        "ASID switch keeps entries"
+    );
+
+    // Reusing the current ASID for a new root flushes.
+    tc.cpu
+        .csr_write(csr::SATP, sv39 | (2 << csr::SATP_ASID_SHIFT) | 0x90);
+    assert_eq!(tc.cpu.mmu.dtlb.lookup(0x10, 1), None);
+}
+
+// ══════════════════════════════════════════════════════════
+// 19. mstatus.FS gating and dirty tracking
+// ══════════════════════════════════════════════════════════
+
+/// `fadd.d f1, f2, f3` with dynamic rounding.
+const FADD_D: u32 = (0x01 << 25) | (3 << 20) | (2 << 15) | (7 << 12) | (1 << 7) | 0x53;
+
+fn set_fs(tc: &mut TestContext, fs: u64) {
+    tc.cpu.csrs.mstatus = (tc.cpu.csrs.mstatus & !csr::MSTATUS_FS) | fs;
+}
+
+fn fs(tc: &TestContext) -> u64 {
+    tc.cpu.csrs.mstatus & csr::MSTATUS_FS
+}
+
+#[test]
+fn fadd_traps_when_fs_off() {
+    let mut tc = ctx();
+    set_fs(&mut tc, csr::MSTATUS_FS_OFF);
+    let mut entry = fp_entry(AluOp::FAdd, 1.0, 1.0 / 3.0, 1);
+    entry.inst = FADD_D;
+    let ex = exec_one(&mut tc, entry);
+    assert_eq!(ex.trap, Some(Trap::IllegalInstruction(FADD_D)));
+    assert_eq!(tc.cpu.csrs.fcsr, 0, "no flags accrued");
+    assert_eq!(fs(&tc), csr::MSTATUS_FS_OFF);
+}
+
+#[test]
+fn fflags_access_traps_when_fs_off() {
+    let mut tc = ctx();
+    set_fs(&mut tc, csr::MSTATUS_FS_OFF);
+    let entry = csr_read_entry(csr::FFLAGS, 5);
+    let inst = entry.inst;
+    let ex = exec_one(&mut tc, entry);
+    assert_eq!(ex.trap, Some(Trap::IllegalInstruction(inst)));
+}
+
+#[test]
+fn fadd_with_fs_clean_leaves_fs_dirty() {
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, PC)
+        .load_program(PC, &[FADD_D, InstructionBuilder::new().nop().build()]);
+    set_fs(&mut tc, csr::MSTATUS_FS_CLEAN);
+    tc.cpu.regs.write_f(2, 1.5f64.to_bits());
+    tc.cpu.regs.write_f(3, 2.0f64.to_bits());
+    tc.run(10);
+    assert_eq!(tc.cpu.regs.read_f(1), 3.5f64.to_bits());
+    assert_eq!(fs(&tc), csr::MSTATUS_FS_DIRTY);
+    assert_eq!(
+        tc.cpu.csrs.sstatus & csr::MSTATUS_FS,
+        csr::MSTATUS_FS_DIRTY,
+        "sstatus view follows"
+    );
+}
@@ -9,11 +9,17 @@
 //!   6. Misaligned PC trap — odd PC generates InstructionAddressMisaligned
 //!   7. Superscalar fetch — multiple instructions per cycle
 //!   8. Stop-on-control-flow — stops fetching after branch/jump
+//!   9. Branch types — BTB-recorded returns use the RAS through the full pipeline
+//!  10. Page-crossing fetch — each half of a straddling instruction is translated
 
 use crate::common::builder::instruction::InstructionBuilder;
 use crate::common::harness::TestContext;
+use riscv_core::common::Trap;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
 use riscv_core::core::pipeline::stages::fetch_stage;
 use riscv_core::core::units::bru::BranchPredictor;
+use riscv_core::core::units::bru::btb::BranchType;
 
 // ══════════════════════════════════════════════════════════
 // Helpers
@@ -275,7 +281,7 @@ fn jalr_ret_uses_ras_prediction() {
     tc.cpu.branch_predictor.on_call(
         MEM_BASE + 0x50, // call site
         return_addr,     // return address
-        MEM_BASE,        // call target
+        Some(MEM_BASE),  // call target
     );
 
     let entries = fetch(&mut tc);
@@ -491,3 +497,151 @@ fn fetch_replaces_previous_if_id() {
         "Stale entries replaced"
     );
 }
+
+// ══════════════════════════════════════════════════════════
+// 15. Branch types steer JALR prediction
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn jalr_recorded_as_return_uses_ras_over_btb() {
+    let mut tc = ctx();
+    let ret_inst = InstructionBuilder::new().jalr(0, 5, 0).build();
+    write_inst(&mut tc, 0, ret_inst);
+
+    let return_addr = MEM_BASE + 0x100;
+    tc.cpu
+        .branch_predictor
+        .update_btb(MEM_BASE, MEM_BASE + 0x200, BranchType::Return);
+    tc.cpu
+        .branch_predictor
+        .on_call(MEM_BASE + 0x50, return_addr, None);
+
+    let entries = fetch(&mut tc);
+    assert!(entries[0].pred_taken);
+    assert_eq!(
+        entries[0].pred_target, return_addr,
+        "RAS, not stale BTB target"
+    );
+    assert_eq!(tc.cpu.branch_predictor.predict_return(), None, "RAS popped");
+}
+
+#[test]
+fn jalr_recorded_as_indirect_uses_btb() {
+    let mut tc = ctx();
+    let jalr = InstructionBuilder::new().jalr(0, 10, 0).build();
+    write_inst(&mut tc, 0, jalr);
+
+    let target = MEM_BASE + 0x200;
+    tc.cpu
+        .branch_predictor
+        .update_btb(MEM_BASE, target, BranchType::IndirectJump);
+    tc.cpu
+        .branch_predictor
+        .on_call(MEM_BASE + 0x50, MEM_BASE + 0x100, None);
+
+    let entries = fetch(&mut tc);
+    assert!(entries[0].pred_taken);
+    assert_eq!(entries[0].pred_target, target);
+    assert_eq!(
+        tc.cpu.branch_predictor.predict_return(),
+        Some(MEM_BASE + 0x100),
+        "RAS untouched"
+    );
+}
+
+#[test]
+fn returns_to_two_call_sites_predicted_by_ras() {
+    let b = InstructionBuilder::new;
+    let func = MEM_BASE + 16;
+    let ret_pc = func + 4;
+    let program = [
+        b().jal(1, 16).build(), // call func
+        b().jal(1, 12).build(), // call func again
+        b().jal(0, 0).build(),  // spin
+        b().nop().build(),
+        b().addi(10, 10, 1).build(), // func:
+        b().jalr(0, 1, 0).build(),   // ret
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(MEM_SIZE, MEM_BASE)
+        .load_program(MEM_BASE, &program);
+
+    // Instructions in ID/EX at the start of a cycle are on the correct path.
+    let mut returns = Vec::new();
+    for _ in 0..200 {
+        for e in tc.cpu.id_ex.entries.iter().filter(|e| e.pc == ret_pc) {
+            returns.push((e.pred_taken, e.pred_target));
+        }
+        tc.cpu.tick().expect("tick");
+    }
+    returns.dedup();
+
+    assert_eq!(tc.get_reg(10), 2, "func ran twice");
+    assert_eq!(
+        returns,
+        vec![(true, MEM_BASE + 4), (true, MEM_BASE + 8)],
+        "both returns predicted from the RAS"
+    );
+    assert_eq!(
+        tc.cpu.branch_predictor.branch_type(ret_pc),
+        Some(BranchType::Return)
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 10. Page-crossing fetch
+// ══════════════════════════════════════════════════════════
+
+const PT_BASE: u64 = 0x8000_0000;
+/// Physical page backing virtual page 0.
+const PAGE0_PA: u64 = PT_BASE + 0x5000;
+/// Physical page backing virtual page 1, placed *below* page 0's frame.
+const PAGE1_PA: u64 = PT_BASE + 0x3000;
+/// A 32-bit instruction whose upper half lies on virtual page 1.
+const CROSS_PC: u64 = 0xFFE;
+
+/// S-mode hart under Sv39 mapping virtual pages 0 and 1 to non-adjacent frames.
+///
+/// `page1_mapped` controls whether virtual page 1 has a valid PTE.
+fn cross_page_ctx(page1_mapped: bool) -> TestContext {
+    let (root, l1, l0) = (PT_BASE, PT_BASE + 0x1000, PT_BASE + 0x2000);
+    let mut tc = TestContext::new().with_memory(0x8000, PT_BASE);
+    let leaf = |pa: u64| ((pa >> 12) << 10) | 0xCF;
+    tc.cpu.bus.bus.write_u64(root, ((l1 >> 12) << 10) | 1);
+    tc.cpu.bus.bus.write_u64(l1, ((l0 >> 12) << 10) | 1);
+    tc.cpu.bus.bus.write_u64(l0, leaf(PAGE0_PA));
+    if page1_mapped {
+        tc.cpu.bus.bus.write_u64(l0 + 8, leaf(PAGE1_PA));
+    }
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (root >> 12);
+    tc.cpu.pc = CROSS_PC;
+    tc
+}
+
+#[test]
+fn cross_page_instruction_reads_both_frames() {
+    let mut tc = cross_page_ctx(true);
+    let inst = InstructionBuilder::new().addi(5, 0, 0x123).build();
+    tc.cpu.bus.bus.write_u16(PAGE0_PA + 0xFFE, inst as u16);
+    tc.cpu.bus.bus.write_u16(PAGE1_PA, (inst >> 16) as u16);
+
+    let entries = fetch(&mut tc);
+    assert_eq!(entries[0].pc, CROSS_PC);
+    assert!(entries[0].trap.is_none());
+    assert_eq!(entries[0].inst, inst);
+    assert_eq!(entries[0].inst_size, 4);
+}
+
+#[test]
+fn cross_page_instruction_faults_on_unmapped_second_page() {
+    let mut tc = cross_page_ctx(false);
+    let inst = InstructionBuilder::new().addi(5, 0, 0x123).build();
+    tc.cpu.bus.bus.write_u16(PAGE0_PA + 0xFFE, inst as u16);
+
+    let entries = fetch(&mut tc);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].pc, CROSS_PC);
+    assert_eq!(entries[0].trap, Some(Trap::InstructionPageFault(0x1000)));
+}
@@ -9,11 +9,32 @@
 //!   6. Atomic operations — LR/SC pair, AMO variants
 //!   7. MEM/WB metadata — PC, inst, rd, ctrl forwarded correctly
 //!   8. FP load NaN-boxing — single-precision FP loads set upper 32 bits
+//!   9. Loads to x0 — access, faults, and MMIO side effects still occur
+//!  10. AMO ordering bits — a release AMO drains older stores before it issues, and
+//!      an acquire AMO keeps younger instructions from issuing with it
+//!  11. Exception priority — misaligned atomics to unmapped pages report the
+//!      exception selected by `memory.misaligned_priority`
+//!  12. Reservations — the set covers `memory.reservation_bytes`, and is lost when
+//!      its L1-D line is evicted or any store writes into it
+//!  13. Non-temporal accesses — a Zihintntl-hinted load does not evict a hot line
+//!  14. Unmapped physical addresses — loads and stores raise access faults
+//!  15. Misaligned accesses — split into byte accesses under `memory.misaligned_access =
+//!      "Emulate"`, or raising address-misaligned under `"Trap"`
+//!  16. Atomics on devices — LR/SC and AMOs outside memory raise access faults
 
 use crate::common::harness::TestContext;
-use riscv_core::core::pipeline::latches::ExMemEntry;
-use riscv_core::core::pipeline::signals::{AtomicOp, ControlSignals, MemWidth};
-use riscv_core::core::pipeline::stages::mem_stage;
+use riscv_core::common::error::Trap;
+use riscv_core::config::{CacheConfig, MisalignedAccess, MisalignedPriority};
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::pipeline::latches::{ExMemEntry, IdExEntry};
+use riscv_core::core::pipeline::signals::{AtomicOp, ControlSignals, MemWidth, OpASrc, OpBSrc};
+use riscv_core::core::pipeline::stages::{execute_stage, mem_stage};
+use riscv_core::core::units::cache::CacheSim;
+use riscv_core::soc::devices::Uart;
+use riscv_core::soc::traits::Device;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // ══════════════════════════════════════════════════════════
 // Helpers
@@ -46,6 +67,7 @@ fn load_entry(rd: usize, addr: u64, width: MemWidth, signed: bool) -> ExMemEntry
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -64,6 +86,7 @@ fn store_entry(addr: u64, data: u64, width: MemWidth) -> ExMemEntry {
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -81,6 +104,7 @@ fn passthrough_entry(rd: usize, alu: u64) -> ExMemEntry {
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -107,6 +131,7 @@ fn atomic_entry(
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -291,6 +316,7 @@ fn passthrough_preserves_metadata() {
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     };
     let wb = mem_one(&mut tc, entry);
     assert_eq!(wb.pc, 0xABCD_1234, "PC preserved");
@@ -338,6 +364,7 @@ fn trap_does_not_perform_memory_access() {
             ..Default::default()
         },
         trap: Some(trap),
+        ras_checkpoint: Default::default(),
     };
     mem_one(&mut tc, entry);
 
@@ -434,7 +461,7 @@ fn sc_word_fails_without_reservation() {
 #[test]
 fn sc_word_fails_with_wrong_address() {
     let mut tc = ctx();
-    tc.cpu.load_reservation = Some(MEM_BASE + 8); // different address
+    tc.cpu.load_reservation = Some(MEM_BASE + 0x40); // outside the reservation set
     tc.cpu.bus.bus.write_u32(MEM_BASE, 0);
 
     let entry = atomic_entry(1, MEM_BASE, 99, MemWidth::Word, AtomicOp::Sc);
@@ -761,6 +788,7 @@ fn fp_single_load_nan_boxes() {
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     };
     let wb = mem_one(&mut tc, entry);
 
@@ -787,3 +815,505 @@ fn lr_word_sign_extends_negative() {
         "LR.W sign-extends negative word"
     );
 }
+
+// ══════════════════════════════════════════════════════════
+// 13. Loads to x0 — access still performed
+// ══════════════════════════════════════════════════════════
+
+const POISON_BASE: u64 = 0x2000_0000;
+
+/// MMIO device that counts reads, modelling a read-to-clear register.
+struct ReadCounter {
+    reads: Arc<AtomicUsize>,
+}
+
+impl ReadCounter {
+    fn bump(&self) {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Device for ReadCounter {
+    fn name(&self) -> &str {
+        "COUNTER"
+    }
+    fn address_range(&self) -> (u64, u64) {
+        (POISON_BASE, 0x100)
+    }
+    fn read_u8(&mut self, _offset: u64) -> u8 {
+        self.bump();
+        0
+    }
+    fn read_u16(&mut self, _offset: u64) -> u16 {
+        self.bump();
+        0
+    }
+    fn read_u32(&mut self, _offset: u64) -> u32 {
+        self.bump();
+        0
+    }
+    fn read_u64(&mut self, _offset: u64) -> u64 {
+        self.bump();
+        0
+    }
+    fn write_u8(&mut self, _offset: u64, _val: u8) {}
+    fn write_u16(&mut self, _offset: u64, _val: u16) {}
+    fn write_u32(&mut self, _offset: u64, _val: u32) {}
+    fn write_u64(&mut self, _offset: u64, _val: u64) {}
+}
+
+#[test]
+fn load_to_x0_from_poisoned_address_faults() {
+    let mut tc = ctx();
+    let poisoned = POISON_BASE + 0x40;
+
+    let wb = mem_one(&mut tc, load_entry(0, poisoned, MemWidth::Word, true));
+
+    assert_eq!(
+        wb.trap,
+        Some(Trap::LoadAccessFault(poisoned)),
+        "lw x0 from an unmapped address must still raise an access fault"
+    );
+}
+
+#[test]
+fn load_to_x0_triggers_mmio_read_side_effect() {
+    let mut tc = ctx();
+    let reads = Arc::new(AtomicUsize::new(0));
+    tc.cpu.bus.bus.add_device(Box::new(ReadCounter {
+        reads: Arc::clone(&reads),
+    }));
+
+    let wb = mem_one(&mut tc, load_entry(0, POISON_BASE, MemWidth::Word, true));
+
+    assert!(wb.trap.is_none());
+    assert_eq!(
+        reads.load(Ordering::SeqCst),
+        1,
+        "lw x0 from MMIO must still perform the device read"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 14. AMO ordering bits
+// ══════════════════════════════════════════════════════════
+
+/// Returns `entry` as the execute stage receives it: address in `rs1`, data in `rs2`.
+fn decoded(entry: &ExMemEntry) -> IdExEntry {
+    IdExEntry {
+        pc: entry.pc,
+        inst: entry.inst,
+        inst_size: entry.inst_size,
+        rs1: 1,
+        rs2: 2,
+        rd: entry.rd,
+        rv1: entry.alu,
+        rv2: entry.store_data,
+        ctrl: ControlSignals {
+            a_src: OpASrc::Reg1,
+            b_src: OpBSrc::Zero,
+            ..entry.ctrl
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn amoswap_rl_observes_prior_store() {
+    let mut tc = ctx();
+    tc.cpu.bus.bus.write_u32(MEM_BASE, 0);
+
+    let st = store_entry(MEM_BASE, 0x1234, MemWidth::Word);
+    let mut amo = atomic_entry(1, MEM_BASE, 0x5678, MemWidth::Word, AtomicOp::Swap);
+    amo.pc = PC + INST_SIZE;
+    amo.ctrl.rl = true;
+
+    tc.cpu.id_ex.entries = vec![decoded(&st), decoded(&amo)];
+    execute_stage(&mut tc.cpu);
+    assert_eq!(
+        tc.cpu.bus.bus.read_u32(MEM_BASE),
+        0x1234,
+        "the older store is performed before amoswap.w.rl issues"
+    );
+
+    mem_stage(&mut tc.cpu);
+
+    assert_eq!(tc.cpu.mem_wb.entries.len(), 2);
+    assert_eq!(
+        tc.cpu.mem_wb.entries[1].load_data, 0x1234,
+        "amoswap.w.rl must see the older store as the old value"
+    );
+    assert_eq!(
+        tc.cpu.bus.bus.read_u32(MEM_BASE),
+        0x5678,
+        "swap result lands after the drained store"
+    );
+}
+
+#[test]
+fn amoswap_aq_holds_back_younger_load() {
+    let mut tc = ctx();
+    let mut amo = atomic_entry(1, MEM_BASE, 0x5678, MemWidth::Word, AtomicOp::Swap);
+    amo.ctrl.aq = true;
+    let mut ld = load_entry(2, MEM_BASE + 8, MemWidth::Word, true);
+    ld.pc = PC + INST_SIZE;
+
+    tc.cpu.id_ex.entries = vec![decoded(&amo), decoded(&ld)];
+    execute_stage(&mut tc.cpu);
+
+    assert_eq!(
+        tc.cpu.ex_mem.entries.len(),
+        1,
+        "the load does not issue alongside amoswap.w.aq"
+    );
+    assert_eq!(tc.cpu.pc, PC + INST_SIZE, "fetch resumes at the load");
+    assert!(tc.cpu.if_id.entries.is_empty());
+}
+
+// ══════════════════════════════════════════════════════════
+// 15. Exception priority: misaligned and page fault together
+// ══════════════════════════════════════════════════════════
+
+/// Unmapped virtual address, two bytes into a word (misaligned for W/D).
+const UNMAPPED_MISALIGNED: u64 = 0x4000_0002;
+
+/// S-mode context with Sv39 on and a zeroed root table at `MEM_BASE`, so every
+/// translation page-faults.
+fn paged_ctx(order: MisalignedPriority) -> TestContext {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (MEM_BASE >> 12);
+    tc.cpu.misaligned_priority = order;
+    tc
+}
+
+fn amoadd_entry(addr: u64) -> ExMemEntry {
+    let mut e = atomic_entry(1, addr, 1, MemWidth::Word, AtomicOp::Add);
+    e.ctrl.mem_write = true;
+    e
+}
+
+#[test]
+fn misaligned_amo_before_translation_reports_misaligned() {
+    let mut tc = paged_ctx(MisalignedPriority::BeforeTranslation);
+    let wb = mem_one(&mut tc, amoadd_entry(UNMAPPED_MISALIGNED));
+    assert_eq!(
+        wb.trap,
+        Some(Trap::StoreAddressMisaligned(UNMAPPED_MISALIGNED))
+    );
+}
+
+#[test]
+fn misaligned_amo_after_translation_reports_page_fault() {
+    let mut tc = paged_ctx(MisalignedPriority::AfterTranslation);
+    let wb = mem_one(&mut tc, amoadd_entry(UNMAPPED_MISALIGNED));
+    assert_eq!(wb.trap, Some(Trap::StorePageFault(UNMAPPED_MISALIGNED)));
+}
+
+#[test]
+fn misaligned_lr_reports_load_misaligned() {
+    let mut tc = paged_ctx(MisalignedPriority::BeforeTranslation);
+    let lr = atomic_entry(1, UNMAPPED_MISALIGNED, 0, MemWidth::Double, AtomicOp::Lr);
+    let wb = mem_one(&mut tc, lr);
+    assert_eq!(
+        wb.trap,
+        Some(Trap::LoadAddressMisaligned(UNMAPPED_MISALIGNED))
+    );
+}
+
+#[test]
+fn misaligned_amo_to_mapped_memory_still_traps_after_translation() {
+    let mut tc = ctx();
+    tc.cpu.misaligned_priority = MisalignedPriority::AfterTranslation;
+    tc.cpu.bus.bus.write_u32(MEM_BASE, 7);
+    let wb = mem_one(&mut tc, amoadd_entry(MEM_BASE + 2));
+    assert_eq!(wb.trap, Some(Trap::StoreAddressMisaligned(MEM_BASE + 2)));
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 7, "no memory update");
+}
+
+#[test]
+fn misaligned_plain_load_reports_only_page_fault() {
+    // Ordinary loads handle misalignment in hardware, so only the fault remains.
+    let mut tc = paged_ctx(MisalignedPriority::BeforeTranslation);
+    let wb = mem_one(
+        &mut tc,
+        load_entry(1, UNMAPPED_MISALIGNED, MemWidth::Word, true),
+    );
+    assert_eq!(wb.trap, Some(Trap::LoadPageFault(UNMAPPED_MISALIGNED)));
+}
+
+// ══════════════════════════════════════════════════════════
+// 16. Reservation set size and loss on L1-D eviction
+// ══════════════════════════════════════════════════════════
+
+/// Context whose RAM accesses go through a 256-byte direct-mapped L1-D
+/// (64-byte lines, 4 sets), so `MEM_BASE` and `MEM_BASE + 0x100` conflict.
+fn cached_ctx() -> TestContext {
+    let mut tc = ctx();
+    tc.cpu.mmio_base = MEM_BASE;
+    tc.cpu.l1_d_cache = CacheSim::try_new(&CacheConfig {
+        enabled: true,
+        size_bytes: 256,
+        line_bytes: 64,
+        ways: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    tc
+}
+
+fn lr_then_sc(tc: &mut TestContext, between: Vec<ExMemEntry>) -> u64 {
+    tc.cpu.bus.bus.write_u32(MEM_BASE, 5);
+    mem_one(
+        tc,
+        atomic_entry(1, MEM_BASE, 0, MemWidth::Word, AtomicOp::Lr),
+    );
+    for entry in between {
+        mem_one(tc, entry);
+    }
+    mem_one(
+        tc,
+        atomic_entry(1, MEM_BASE, 9, MemWidth::Word, AtomicOp::Sc),
+    )
+    .load_data
+}
+
+#[test]
+fn sc_succeeds_while_reserved_line_stays_cached() {
+    let mut tc = cached_ctx();
+    let other_set = load_entry(2, MEM_BASE + 0x40, MemWidth::Word, false);
+    assert_eq!(lr_then_sc(&mut tc, vec![other_set]), 0, "SC succeeds");
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 9);
+}
+
+#[test]
+fn evicting_reserved_line_fails_sc() {
+    let mut tc = cached_ctx();
+    let conflicting = load_entry(2, MEM_BASE + 0x100, MemWidth::Word, false);
+    assert_eq!(lr_then_sc(&mut tc, vec![conflicting]), 1, "SC fails");
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 5, "memory untouched");
+    assert_eq!(tc.cpu.load_reservation, None);
+}
+
+#[test]
+fn reservation_set_covers_configured_granule() {
+    let mut tc = ctx();
+    tc.cpu.load_reservation = Some(MEM_BASE);
+    let wb = mem_one(
+        &mut tc,
+        atomic_entry(1, MEM_BASE + 4, 1, MemWidth::Word, AtomicOp::Sc),
+    );
+    assert_eq!(wb.load_data, 0, "default line-sized set includes +4");
+
+    let mut tc = ctx();
+    tc.cpu.reservation_bytes = 4;
+    tc.cpu.load_reservation = Some(MEM_BASE);
+    let wb = mem_one(
+        &mut tc,
+        atomic_entry(1, MEM_BASE + 4, 1, MemWidth::Word, AtomicOp::Sc),
+    );
+    assert_eq!(wb.load_data, 1, "4-byte set excludes +4");
+}
+
+#[test]
+fn store_to_other_word_in_reserved_line_fails_sc() {
+    let mut tc = ctx();
+    let same_line = store_entry(MEM_BASE + 0x38, 0xAB, MemWidth::Word);
+    assert_eq!(lr_then_sc(&mut tc, vec![same_line]), 1, "SC fails");
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 5, "memory untouched");
+}
+
+#[test]
+fn store_to_next_line_keeps_reservation() {
+    let mut tc = ctx();
+    let next_line = store_entry(MEM_BASE + 0x40, 0xAB, MemWidth::Word);
+    assert_eq!(lr_then_sc(&mut tc, vec![next_line]), 0, "SC succeeds");
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 9);
+}
+
+// ══════════════════════════════════════════════════════════
+// 17. Non-temporal (Zihintntl) accesses
+// ══════════════════════════════════════════════════════════
+
+/// Loads a hot line, then a conflicting line with the given hint level count,
+/// and reports whether the hot line survived in L1-D.
+fn hot_line_survives(ntl_levels: u8) -> bool {
+    let mut tc = cached_ctx();
+    mem_one(&mut tc, load_entry(1, MEM_BASE, MemWidth::Word, false));
+    assert!(tc.cpu.l1_d_cache.contains(MEM_BASE));
+
+    let mut streaming = load_entry(2, MEM_BASE + 0x100, MemWidth::Word, false);
+    streaming.ctrl.ntl_levels = ntl_levels;
+    tc.cpu.bus.bus.write_u32(MEM_BASE + 0x100, 0x1234);
+    let wb = mem_one(&mut tc, streaming);
+    assert_eq!(wb.load_data, 0x1234, "hinted load still returns data");
+    tc.cpu.l1_d_cache.contains(MEM_BASE)
+}
+
+#[test]
+fn normal_load_evicts_conflicting_hot_line() {
+    assert!(!hot_line_survives(0));
+}
+
+#[test]
+fn non_temporal_load_keeps_hot_line() {
+    assert!(hot_line_survives(1));
+    assert!(hot_line_survives(3));
+}
+
+// ══════════════════════════════════════════════════════════
+// 18. Unmapped physical addresses
+// ══════════════════════════════════════════════════════════
+
+/// Physical address outside every device range in `ctx()`.
+const UNMAPPED: u64 = 0x2000_0000;
+
+#[test]
+fn load_from_unmapped_address_faults() {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    let wb = mem_one(&mut tc, load_entry(5, UNMAPPED, MemWidth::Word, false));
+    assert_eq!(wb.trap, Some(Trap::LoadAccessFault(UNMAPPED)));
+}
+
+#[test]
+fn store_to_unmapped_address_faults() {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    let wb = mem_one(&mut tc, store_entry(UNMAPPED, 0xAB, MemWidth::Byte));
+    assert_eq!(wb.trap, Some(Trap::StoreAccessFault(UNMAPPED)));
+}
+
+#[test]
+fn load_from_mapped_address_does_not_fault() {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    tc.cpu.bus.bus.write_u32(MEM_BASE + 0xFFC, 0xCAFE);
+    let wb = mem_one(
+        &mut tc,
+        load_entry(5, MEM_BASE + 0xFFC, MemWidth::Word, false),
+    );
+    assert_eq!(wb.trap, None);
+    assert_eq!(wb.load_data, 0xCAFE);
+}
+
+// ══════════════════════════════════════════════════════════
+// 19. Misaligned access emulation
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn emulated_misaligned_double_load_spans_cache_line() {
+    let mut tc = cached_ctx();
+    let addr = MEM_BASE + 0x3D; // bytes 0x3D..0x45 straddle the line at 0x40
+    tc.cpu.bus.bus.write_u64(addr, 0x8877_6655_4433_2211);
+
+    let wb = mem_one(&mut tc, load_entry(1, addr, MemWidth::Double, false));
+    assert_eq!(wb.trap, None);
+    assert_eq!(wb.load_data, 0x8877_6655_4433_2211);
+    assert_eq!(tc.cpu.stats.dcache_misses, 2, "both lines accessed");
+}
+
+#[test]
+fn emulated_misaligned_half_load_sign_extends() {
+    let mut tc = ctx();
+    tc.cpu.bus.bus.write_u16(MEM_BASE + 0x11, 0x8001);
+    let wb = mem_one(
+        &mut tc,
+        load_entry(1, MEM_BASE + 0x11, MemWidth::Half, true),
+    );
+    assert_eq!(wb.load_data, 0xFFFF_FFFF_FFFF_8001);
+}
+
+#[test]
+fn emulated_misaligned_store_writes_every_byte() {
+    let mut tc = ctx();
+    let wb = mem_one(
+        &mut tc,
+        store_entry(MEM_BASE + 0x7, 0xDEAD_BEEF, MemWidth::Word),
+    );
+    assert_eq!(wb.trap, None);
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE + 0x7), 0xDEAD_BEEF);
+    assert_eq!(tc.cpu.bus.bus.read_u8(MEM_BASE + 0xB), 0);
+}
+
+#[test]
+fn emulated_store_faults_on_unmapped_second_page_without_writing() {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    let addr = MEM_BASE + MEM_SIZE as u64 - 2;
+    let wb = mem_one(&mut tc, store_entry(addr, 0xDEAD_BEEF, MemWidth::Word));
+    assert_eq!(
+        wb.trap,
+        Some(Trap::StoreAccessFault(MEM_BASE + MEM_SIZE as u64))
+    );
+    assert_eq!(tc.cpu.bus.bus.read_u16(addr), 0, "first page untouched");
+}
+
+#[test]
+fn trap_mode_raises_misaligned_for_plain_accesses() {
+    let mut tc = ctx();
+    tc.cpu.misaligned_access = MisalignedAccess::Trap;
+    tc.cpu.bus.bus.write_u32(MEM_BASE, 7);
+
+    let wb = mem_one(&mut tc, load_entry(1, MEM_BASE + 2, MemWidth::Word, false));
+    assert_eq!(wb.trap, Some(Trap::LoadAddressMisaligned(MEM_BASE + 2)));
+
+    let wb = mem_one(&mut tc, store_entry(MEM_BASE + 1, 0xFF, MemWidth::Half));
+    assert_eq!(wb.trap, Some(Trap::StoreAddressMisaligned(MEM_BASE + 1)));
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 7, "no memory update");
+
+    // Naturally aligned accesses are unaffected.
+    let wb = mem_one(&mut tc, load_entry(1, MEM_BASE, MemWidth::Word, false));
+    assert_eq!(wb.trap, None);
+    assert_eq!(wb.load_data, 7);
+}
+
+// ══════════════════════════════════════════════════════════
+// 20. Atomics on devices
+// ══════════════════════════════════════════════════════════
+
+/// UART placed in the unmapped hole of `ctx()`.
+fn uart_ctx() -> TestContext {
+    let mut tc = ctx();
+    tc.cpu
+        .bus
+        .bus
+        .add_device(Box::new(Uart::new(UNMAPPED, true)));
+    tc
+}
+
+#[test]
+fn amoadd_to_uart_faults_without_touching_registers() {
+    let mut tc = uart_ctx();
+    tc.cpu.bus.bus.write_u8(UNMAPPED + 7, 0x5A);
+    let wb = mem_one(
+        &mut tc,
+        atomic_entry(1, UNMAPPED + 4, 0x0100_0000, MemWidth::Word, AtomicOp::Add),
+    );
+    assert_eq!(wb.trap, Some(Trap::StoreAccessFault(UNMAPPED + 4)));
+    assert_eq!(tc.cpu.bus.bus.read_u8(UNMAPPED + 7), 0x5A, "SCR unchanged");
+}
+
+#[test]
+fn lr_to_uart_raises_load_access_fault() {
+    let mut tc = uart_ctx();
+    let wb = mem_one(
+        &mut tc,
+        atomic_entry(1, UNMAPPED, 0, MemWidth::Word, AtomicOp::Lr),
+    );
+    assert_eq!(wb.trap, Some(Trap::LoadAccessFault(UNMAPPED)));
+    assert_eq!(tc.cpu.load_reservation, None);
+}
+
+#[test]
+fn amoadd_to_memory_still_performs() {
+    let mut tc = uart_ctx();
+    tc.cpu.bus.bus.write_u32(MEM_BASE, 1);
+    let wb = mem_one(
+        &mut tc,
+        atomic_entry(1, MEM_BASE, 2, MemWidth::Word, AtomicOp::Add),
+    );
+    assert_eq!(wb.trap, None);
+    assert_eq!(tc.cpu.bus.bus.read_u32(MEM_BASE), 3);
+}
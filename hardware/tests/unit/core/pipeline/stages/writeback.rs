@@ -9,8 +9,12 @@
 //!   6. PC trace updated — (pc, inst) pushed to trace buffer
 //!   7. Multiple entries all retire
 //!   8. NOP / zero instruction not counted
+//!   9. Interrupt routing — `mideleg` selects the target mode
+//!  10. Machine timer interrupt — taken only when MTIE and MTIP are both set
 
 use crate::common::harness::TestContext;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
 use riscv_core::core::pipeline::latches::MemWbEntry;
 use riscv_core::core::pipeline::signals::{AluOp, ControlSignals, MemWidth};
 use riscv_core::core::pipeline::stages::wb_stage;
@@ -35,12 +39,14 @@ fn alu_wb(rd: usize, alu: u64) -> MemWbEntry {
         rd,
         alu,
         load_data: 0,
+        stored: None,
         ctrl: ControlSignals {
             reg_write: true,
             alu: AluOp::Add,
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -53,6 +59,7 @@ fn load_wb(rd: usize, load_data: u64) -> MemWbEntry {
         rd,
         alu: 0x1000, // load address (not used for writeback value)
         load_data,
+        stored: None,
         ctrl: ControlSignals {
             reg_write: true,
             mem_read: true,
@@ -60,6 +67,7 @@ fn load_wb(rd: usize, load_data: u64) -> MemWbEntry {
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -72,12 +80,14 @@ fn jump_wb(rd: usize, pc: u64) -> MemWbEntry {
         rd,
         alu: 0,
         load_data: 0,
+        stored: None,
         ctrl: ControlSignals {
             reg_write: true,
             jump: true,
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -90,12 +100,14 @@ fn store_wb() -> MemWbEntry {
         rd: 0,
         alu: 0x1000,
         load_data: 0,
+        stored: None,
         ctrl: ControlSignals {
             mem_write: true,
             width: MemWidth::Word,
             ..Default::default()
         },
         trap: None,
+        ras_checkpoint: Default::default(),
     }
 }
 
@@ -187,6 +199,7 @@ fn load_data_preferred_over_alu() {
         rd: 3,
         alu: 0x1111, // address, not the value to write
         load_data: 0x2222,
+        stored: None,
         ctrl: ControlSignals {
             reg_write: true,
             mem_read: true,
@@ -196,6 +209,7 @@ fn load_data_preferred_over_alu() {
         inst_size: INST_SIZE,
         pc: PC,
         trap: None,
+        ras_checkpoint: Default::default(),
     };
     wb_one(&mut tc, entry);
     assert_eq!(
@@ -632,3 +646,129 @@ fn fp_load_increments_inst_fp_load() {
     wb_one(&mut tc, entry);
     assert_eq!(tc.cpu.stats.inst_fp_load, before + 1);
 }
+
+// ══════════════════════════════════════════════════════════
+// 15. Interrupt routing via mideleg
+// ══════════════════════════════════════════════════════════
+
+/// Context in system mode with trap vectors installed.
+fn irq_ctx(privilege: PrivilegeMode) -> TestContext {
+    let mut tc = ctx();
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = privilege;
+    tc.cpu.csrs.mtvec = 0x8000_1000;
+    tc.cpu.csrs.stvec = 0x8000_2000;
+    tc
+}
+
+#[test]
+fn delegated_stip_taken_in_s_mode() {
+    let mut tc = irq_ctx(PrivilegeMode::User);
+    tc.cpu.csrs.mideleg = csr::MIP_STIP;
+    tc.cpu.csrs.mie = csr::MIE_STIE;
+    tc.cpu.csrs.mip = csr::MIP_STIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Supervisor);
+    assert_eq!(tc.cpu.csrs.scause, (1 << 63) | 5);
+    assert_eq!(tc.cpu.csrs.sepc, PC);
+    assert_eq!(tc.cpu.pc, 0x8000_2000);
+    assert_eq!(tc.cpu.csrs.mcause, 0, "M-mode trap state untouched");
+}
+
+#[test]
+fn undelegated_stip_taken_in_m_mode() {
+    let mut tc = irq_ctx(PrivilegeMode::Supervisor);
+    tc.cpu.csrs.mie = csr::MIE_STIE;
+    tc.cpu.csrs.mip = csr::MIP_STIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.csrs.mcause, (1 << 63) | 5);
+    assert_eq!(tc.cpu.pc, 0x8000_1000);
+}
+
+#[test]
+fn delegated_interrupt_masked_in_m_mode() {
+    let mut tc = irq_ctx(PrivilegeMode::Machine);
+    tc.cpu.csrs.mstatus |= csr::MSTATUS_MIE | csr::MSTATUS_SIE;
+    tc.cpu.csrs.mideleg = csr::MIP_STIP;
+    tc.cpu.csrs.mie = csr::MIE_STIE;
+    tc.cpu.csrs.mip = csr::MIP_STIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.regs.read(1), 42, "instruction retires normally");
+    assert_eq!(tc.cpu.csrs.scause, 0);
+}
+
+#[test]
+fn delegated_interrupt_requires_sie_in_s_mode() {
+    let mut tc = irq_ctx(PrivilegeMode::Supervisor);
+    tc.cpu.csrs.mideleg = csr::MIP_STIP;
+    tc.cpu.csrs.mie = csr::MIE_STIE;
+    tc.cpu.csrs.mip = csr::MIP_STIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+    assert_eq!(tc.cpu.csrs.scause, 0, "SIE clear blocks the interrupt");
+
+    tc.cpu.csrs.mstatus |= csr::MSTATUS_SIE;
+    wb_one(&mut tc, alu_wb(1, 42));
+    assert_eq!(tc.cpu.csrs.scause, (1 << 63) | 5);
+}
+
+#[test]
+fn m_destined_interrupt_beats_higher_priority_s_destined() {
+    // SEIP outranks STIP in the fixed order, but M-mode targets come first.
+    let mut tc = irq_ctx(PrivilegeMode::User);
+    tc.cpu.csrs.mideleg = csr::MIP_SEIP;
+    tc.cpu.csrs.mie = csr::MIE_SEIP | csr::MIE_STIE;
+    tc.cpu.csrs.mip = csr::MIP_SEIP | csr::MIP_STIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.csrs.mcause, (1 << 63) | 5);
+}
+
+// ══════════════════════════════════════════════════════════
+// 16. Machine timer interrupt gating
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn mtip_without_mtie_not_taken() {
+    let mut tc = irq_ctx(PrivilegeMode::User);
+    tc.cpu.csrs.mip = csr::MIP_MTIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::User);
+    assert_eq!(tc.cpu.csrs.mcause, 0);
+}
+
+#[test]
+fn mtie_without_mtip_not_taken() {
+    let mut tc = irq_ctx(PrivilegeMode::User);
+    tc.cpu.csrs.mie = csr::MIE_MTIE;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::User);
+    assert_eq!(tc.cpu.csrs.mcause, 0);
+}
+
+#[test]
+fn mtip_with_mtie_taken_in_m_mode() {
+    let mut tc = irq_ctx(PrivilegeMode::User);
+    tc.cpu.csrs.mie = csr::MIE_MTIE;
+    tc.cpu.csrs.mip = csr::MIP_MTIP;
+
+    wb_one(&mut tc, alu_wb(1, 42));
+
+    assert_eq!(tc.cpu.privilege, PrivilegeMode::Machine);
+    assert_eq!(tc.cpu.csrs.mcause, (1 << 63) | 7);
+    assert_eq!(tc.cpu.pc, 0x8000_1000);
+}
@@ -0,0 +1,125 @@
+//! Superscalar Pipeline Tests.
+//!
+//! Verifies that `pipeline.width` widens the whole in-order pipeline:
+//! 1. Fetch groups stop at a page boundary and pay one access per L1-I line
+//! 2. A straight-line ALU loop runs markedly faster at width 2 than at width 1
+//! 3. Intra-bundle dependencies still produce architecturally correct results
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::core::pipeline::stages::fetch_stage;
+
+const BASE: u64 = 0x8000_0000;
+
+/// Runs eight independent `addi`s followed by a backward `jal` for `cycles`.
+fn run_alu_loop(width: usize, cycles: u64) -> TestContext {
+    let b = InstructionBuilder::new;
+    let mut body: Vec<u32> = (0..8).map(|i| b().addi(5 + i, 5 + i, 1).build()).collect();
+    body.push(b().jal(0, -32).build());
+
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &body);
+    tc.cpu.pipeline_width = width;
+    tc.run(cycles);
+    tc
+}
+
+fn ipc(tc: &TestContext) -> f64 {
+    tc.cpu.stats.instructions_retired as f64 / tc.cpu.stats.cycles as f64
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Fetch groups
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn fetch_group_stops_at_page_boundary() {
+    let nop = InstructionBuilder::new().addi(0, 0, 0).build();
+    let page_end = BASE + 0x1000;
+    let mut tc = TestContext::new()
+        .with_memory(0x2000, BASE)
+        .load_program(page_end - 8, &[nop, nop, nop, nop]);
+    tc.cpu.pipeline_width = 4;
+    tc.cpu.pc = page_end - 8;
+
+    fetch_stage(&mut tc.cpu);
+
+    assert_eq!(tc.cpu.if_id.entries.len(), 2, "group ends with the page");
+    assert_eq!(tc.cpu.pc, page_end, "next group starts the new page");
+}
+
+#[test]
+fn fetch_group_pays_one_access_per_line() {
+    let nop = InstructionBuilder::new().addi(0, 0, 0).build();
+    let stalls = |width: usize| {
+        let mut tc = TestContext::new()
+            .with_memory(0x1000, BASE)
+            .load_program(BASE, &[nop; 4]);
+        tc.cpu.pipeline_width = width;
+        tc.cpu.pc = BASE;
+        fetch_stage(&mut tc.cpu);
+        tc.cpu.stall_cycles
+    };
+
+    assert_eq!(stalls(4), stalls(1), "one line, one access");
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Throughput
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn width_two_raises_ipc_of_alu_loop() {
+    let narrow = run_alu_loop(1, 4000);
+    let wide = run_alu_loop(2, 4000);
+
+    assert!(
+        ipc(&wide) > ipc(&narrow) * 1.5,
+        "IPC width 1: {:.3}, width 2: {:.3}",
+        ipc(&narrow),
+        ipc(&wide)
+    );
+    assert!(ipc(&wide) > 0.75, "IPC width 2: {:.3}", ipc(&wide));
+}
+
+#[test]
+fn wide_alu_loop_keeps_counters_in_step() {
+    let wide = run_alu_loop(2, 4000);
+    let retired_iterations = wide.get_reg(5);
+
+    // Every counter advanced by the same number of completed iterations (±1
+    // for the one in flight).
+    for reg in 6..13 {
+        let r = wide.get_reg(reg);
+        assert!(
+            r.abs_diff(retired_iterations) <= 1,
+            "x{reg} = {r}, x5 = {retired_iterations}"
+        );
+    }
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Intra-bundle dependencies
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn dependent_chain_in_one_bundle_is_correct() {
+    let b = InstructionBuilder::new;
+    let program = [
+        b().addi(5, 0, 3).build(),
+        b().add(6, 5, 5).build(),
+        b().add(7, 6, 5).build(),
+        b().addi(8, 7, 1).build(),
+        b().jal(0, 0).build(),
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    tc.cpu.pipeline_width = 4;
+    tc.run(100);
+
+    assert_eq!(tc.get_reg(6), 6);
+    assert_eq!(tc.get_reg(7), 9);
+    assert_eq!(tc.get_reg(8), 10);
+}
@@ -5,15 +5,21 @@
 //! 2. Sets cpu.wfi_pc correctly (to next instruction)
 //! 3. Resumes correctly upon interrupt
 //! 4. Edge cases: different privilege modes, interrupt configurations, etc.
+//! 5. `trap_on_wfi`: a committed WFI ends a direct-mode run with a distinct exit code
+//! 6. Idling: a sleeping hart runs no stages and counts `cycles_idle` until woken
 
+use crate::common::builder::instruction::InstructionBuilder;
 use crate::common::harness::TestContext;
+use riscv_core::common::constants::UNEXPECTED_WFI_EXIT_CODE;
 use riscv_core::common::error::Trap;
+use riscv_core::config::Config;
 use riscv_core::core::arch::csr;
 use riscv_core::core::arch::mode::PrivilegeMode;
 use riscv_core::core::pipeline::latches::IdExEntry;
 use riscv_core::core::pipeline::signals::ControlSignals;
 use riscv_core::core::pipeline::stages::{execute_stage, wb_stage};
 use riscv_core::isa::privileged::opcodes as sys_ops;
+use riscv_core::soc::devices::clint::Clint;
 
 const PC: u64 = 0x8000_0000;
 const INST_SIZE: u64 = 4;
@@ -386,3 +392,97 @@ fn wfi_sets_trap_on_illegal_instruction() {
     // This tests the decoder/execute interaction if we pass a bad WFI encoding?
     // No, WFI is specific opcode.
 }
+
+/// Runs `addi x5, x0, 7; wfi` in M-mode with `general.trap_on_wfi` set.
+fn run_wfi_program(direct_mode: bool) -> TestContext {
+    let mut config = Config::default();
+    config.general.trap_on_wfi = true;
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, PC)
+        .load_program(
+            PC,
+            &[InstructionBuilder::new().addi(5, 0, 7).build(), WFI_INST],
+        );
+    tc.cpu.direct_mode = direct_mode;
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.run(50);
+    tc
+}
+
+#[test]
+fn trap_on_wfi_exits_with_diagnostic_code() {
+    let mut tc = run_wfi_program(true);
+
+    assert_eq!(tc.cpu.take_exit(), Some(UNEXPECTED_WFI_EXIT_CODE));
+    assert!(!tc.cpu.wfi_waiting, "hart must not be left sleeping");
+    assert_eq!(tc.get_reg(5), 7, "older instructions commit first");
+    assert_eq!(
+        tc.cpu.stats.instructions_retired, 2,
+        "the WFI itself commits"
+    );
+}
+
+#[test]
+fn trap_on_wfi_keeps_sleep_outside_direct_mode() {
+    let mut tc = run_wfi_program(false);
+
+    assert_eq!(tc.cpu.take_exit(), None);
+    assert!(tc.cpu.wfi_waiting, "OS mode still sleeps on WFI");
+}
+
+/// CLINT base used by the idle tests.
+const CLINT_BASE: u64 = 0x0200_0000;
+
+/// `csrrw x0, mie, rs1`.
+fn csrw_mie(rs1: u32) -> u32 {
+    (csr::MIE << 20) | (rs1 << 15) | (1 << 12) | 0x73
+}
+
+/// Arms the CLINT for `mtime == wake_at`, enables MTIE (with `mstatus.MIE`
+/// clear), sleeps in WFI, then sets x5 = 42 and spins.
+fn run_timer_wake_program(wake_at: i32, cycles: u64) -> TestContext {
+    let b = || InstructionBuilder::new();
+    let mut tc = ctx().with_memory(0x1000, PC).load_program(
+        PC,
+        &[
+            b().lui(6, ((CLINT_BASE + 0x4000) >> 12) as i32).build(),
+            b().addi(7, 0, wake_at).build(),
+            b().sd(6, 7, 0).build(),
+            b().addi(7, 0, csr::MIE_MTIE as i32).build(),
+            csrw_mie(7),
+            WFI_INST,
+            b().addi(5, 0, 42).build(),
+            b().jal(0, 0).build(),
+        ],
+    );
+    tc.cpu
+        .bus
+        .bus
+        .add_device(Box::new(Clint::new(CLINT_BASE, 1)));
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.run(cycles);
+    tc
+}
+
+#[test]
+fn sleeping_hart_idles_until_timer_fires() {
+    let tc = run_timer_wake_program(400, 200);
+
+    assert!(tc.cpu.wfi_waiting, "timer has not fired yet");
+    assert_eq!(tc.get_reg(5), 0, "nothing past the WFI runs");
+    assert!(tc.cpu.stats.cycles_idle > 100, "idle cycles are counted");
+    assert!(tc.cpu.stats.cycles_idle < tc.cpu.stats.cycles);
+}
+
+#[test]
+fn timer_interrupt_resumes_pipeline_at_wfi_pc() {
+    let tc = run_timer_wake_program(400, 600);
+
+    assert!(!tc.cpu.wfi_waiting, "timer interrupt wakes the hart");
+    assert_eq!(tc.cpu.wfi_pc, PC + 6 * INST_SIZE);
+    assert_eq!(tc.get_reg(5), 42, "execution continues after the WFI");
+    assert_eq!(tc.cpu.csrs.mcause, 0, "MIE clear: wake without a trap");
+    let idle = tc.cpu.stats.cycles_idle;
+    assert!((300..400).contains(&idle), "idle cycles: {idle}");
+}
@@ -0,0 +1,92 @@
+//! Wrong-Path Cache Pollution Tests.
+//!
+//! Verifies that work squashed by a branch misprediction leaves cache state behind:
+//! 1. Wrong-path fetches stay resident in L1-I and are counted separately, including
+//!    those fetched in the branch's own group and squashed from its ID/EX bundle
+//! 2. Wrong-path loads allocate into L1-D only when pollution modeling is enabled
+//! 3. Squashed loads never write their destination register
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::config::CacheConfig;
+use riscv_core::core::units::cache::CacheSim;
+
+const BASE: u64 = 0x8000_0000;
+/// Branch sits in the last word of line 0 so the fall-through starts line 1.
+const BRANCH_PC: u64 = BASE + 0x3C;
+const WRONG_PATH_PC: u64 = BASE + 0x40;
+const TARGET_PC: u64 = BASE + 0x80;
+const DATA_ADDR: u64 = BASE + 0x800;
+
+/// Builds a context where a forward `beq x0, x0` is statically predicted not-taken,
+/// so the fall-through `lw x5, 0(x6)` is fetched down the wrong path.
+fn mispredict_ctx(pollution: bool) -> TestContext {
+    let enabled = CacheConfig {
+        enabled: true,
+        ..CacheConfig::default()
+    };
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    tc.cpu.mmio_base = BASE;
+    tc.cpu.l1_i_cache = CacheSim::try_new(&enabled).unwrap();
+    tc.cpu.l1_d_cache = CacheSim::try_new(&enabled).unwrap();
+    tc.cpu.wrong_path_pollution = pollution;
+
+    let beq = InstructionBuilder::new()
+        .beq(0, 0, (TARGET_PC - BRANCH_PC) as i32)
+        .build();
+    let lw = InstructionBuilder::new().lw(5, 6, 0).build();
+    let spin = InstructionBuilder::new().jal(0, 0).build();
+    let mut tc = tc.load_program(WRONG_PATH_PC, &[lw]);
+    tc = tc.load_program(TARGET_PC, &[spin]);
+    tc = tc.load_program(BRANCH_PC, &[beq]);
+    tc.set_reg(6, DATA_ADDR);
+    tc.cpu.bus.bus.write_u32(DATA_ADDR, 0xDEAD_BEEF);
+    tc
+}
+
+#[test]
+fn wrong_path_fetch_stays_resident() {
+    let mut tc = mispredict_ctx(false);
+    tc.run(100);
+
+    assert!(tc.cpu.stats.branch_mispredictions >= 1);
+    assert!(
+        tc.cpu.l1_i_cache.contains(WRONG_PATH_PC),
+        "squashed fall-through line remains in L1-I"
+    );
+    assert_eq!(tc.cpu.stats.wrong_path_icache_fills, 1);
+}
+
+#[test]
+fn same_bundle_wrong_path_fetch_is_counted() {
+    let mut tc = mispredict_ctx(false);
+    // The branch and the fall-through load share a fetch group; a later group
+    // is fetched before the branch resolves.
+    tc.cpu.pipeline_width = 2;
+    tc.run(100);
+
+    assert!(tc.cpu.stats.branch_mispredictions >= 1);
+    assert!(tc.cpu.l1_i_cache.contains(WRONG_PATH_PC));
+    assert_eq!(tc.cpu.stats.wrong_path_icache_fills, 1);
+}
+
+#[test]
+fn wrong_path_load_pollutes_dcache_when_enabled() {
+    let mut tc = mispredict_ctx(true);
+    tc.run(100);
+
+    assert!(tc.cpu.l1_d_cache.contains(DATA_ADDR));
+    assert_eq!(tc.cpu.stats.wrong_path_dcache_fills, 1);
+    assert_eq!(tc.cpu.stats.dcache_misses, 0, "hit/miss stats untouched");
+    assert_eq!(tc.get_reg(5), 0, "squashed load never writes back");
+}
+
+#[test]
+fn wrong_path_load_ignored_when_disabled() {
+    let mut tc = mispredict_ctx(false);
+    tc.run(100);
+
+    assert!(!tc.cpu.l1_d_cache.contains(DATA_ADDR));
+    assert_eq!(tc.cpu.stats.wrong_path_dcache_fills, 0);
+    assert_eq!(tc.get_reg(5), 0);
+}
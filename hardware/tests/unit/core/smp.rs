@@ -0,0 +1,196 @@
+//! Multi-Hart Tests.
+//!
+//! Verifies `Smp`:
+//!   1. Each hart reads its own index from `mhartid`
+//!   2. A write to a secondary hart's CLINT MSIP wakes it from WFI and
+//!      delivers a machine software interrupt
+//!   3. A store by one hart breaks another hart's LR reservation on the same
+//!      reservation set, so the interleaved SC fails
+
+use crate::common::builder::instruction::InstructionBuilder;
+use riscv_core::config::Config;
+use riscv_core::core::arch::csr;
+use riscv_core::core::smp::Smp;
+use riscv_core::soc::System;
+
+const BASE: u64 = 0x8000_0000;
+/// `wfi` encoding.
+const WFI: u32 = 0x1050_0073;
+/// `mret` encoding.
+const MRET: u32 = 0x3020_0073;
+/// MSIP register of hart 1 (CLINT base + 4).
+const CLINT_UPPER: i32 = 0x2000;
+/// Machine software interrupt cause.
+const MSI_CAUSE: u64 = (1 << 63) | 3;
+
+const T0: u32 = 5;
+const T1: u32 = 6;
+const T2: u32 = 7;
+const T3: u32 = 28;
+const T4: u32 = 29;
+const S0: u32 = 8;
+const S1: u32 = 9;
+
+/// Encodes a Zicsr instruction.
+fn csr_op(funct3: u32, rd: u32, csr: u32, rs1: u32) -> u32 {
+    (csr << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x73
+}
+
+/// Hart 0 raises hart 1's MSIP and spins. Hart 1 enables MSIE, waits in WFI,
+/// then enables interrupts; its handler sets `s0` and clears MSIP, and the
+/// code after the WFI sets `s1`.
+fn ipi_program() -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        csr_op(2, T0, csr::MHARTID, 0),   // 0x00 csrr  t0, mhartid
+        b().bne(T0, 0, 0x14).build(),     // 0x04 bnez  t0, secondary
+        b().lui(T1, CLINT_UPPER).build(), // 0x08 lui   t1, CLINT
+        b().addi(T2, 0, 1).build(),       // 0x0c li    t2, 1
+        b().sw(T1, T2, 4).build(),        // 0x10 sw    t2, 4(t1)
+        b().jal(0, 0).build(),            // 0x14 j     .
+        b().auipc(T3, 0).build(),         // 0x18 secondary: auipc t3, 0
+        b().addi(T3, T3, 0x28).build(),   // 0x1c addi  t3, t3, handler
+        csr_op(1, 0, csr::MTVEC, T3),     // 0x20 csrw  mtvec, t3
+        b().addi(T4, 0, 8).build(),       // 0x24 li    t4, MSIE
+        csr_op(1, 0, csr::MIE, T4),       // 0x28 csrw  mie, t4
+        WFI,                              // 0x2c wfi
+        csr_op(6, 0, csr::MSTATUS, 8),    // 0x30 csrsi mstatus, MIE
+        b().addi(S1, 0, 1).build(),       // 0x34 li    s1, 1
+        b().jal(0, 0).build(),            // 0x38 j     .
+        b().nop().build(),                // 0x3c
+        b().addi(S0, 0, 42).build(),      // 0x40 handler: li s0, 42
+        b().lui(T1, CLINT_UPPER).build(), // 0x44 lui   t1, CLINT
+        b().sw(T1, 0, 4).build(),         // 0x48 sw    zero, 4(t1)
+        MRET,                             // 0x4c mret
+    ]
+}
+
+/// Encodes `lr.w rd, (rs1)`.
+fn lr_w(rd: u32, rs1: u32) -> u32 {
+    (0b00010 << 27) | (rs1 << 15) | (0b010 << 12) | (rd << 7) | 0x2F
+}
+
+/// Encodes `sc.w rd, rs2, (rs1)`.
+fn sc_w(rd: u32, rs2: u32, rs1: u32) -> u32 {
+    (0b00011 << 27) | (rs2 << 20) | (rs1 << 15) | (0b010 << 12) | (rd << 7) | 0x2F
+}
+
+/// Hart 0 reserves the word at `BASE + 0x1000`, raises flag 1, waits for flag 2, and
+/// attempts an SC into `s0`. Hart 1 waits for flag 1, stores 7 at `BASE + 0x1000 +
+/// store_offset`, and raises flag 2. The flags sit on lines of their own.
+fn lr_sc_program(store_offset: i32) -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().auipc(T1, 1).build(),             // 0x00 auipc t1, 0x1000
+        csr_op(2, T0, csr::MHARTID, 0),       // 0x04 csrr  t0, mhartid
+        b().bne(T0, 0, 0x20).build(),         // 0x08 bnez  t0, secondary
+        lr_w(T2, T1),                         // 0x0c lr.w  t2, (t1)
+        b().addi(T3, 0, 1).build(),           // 0x10 li    t3, 1
+        b().sw(T1, T3, 0x100).build(),        // 0x14 sw    t3, flag1
+        b().lw(T4, T1, 0x200).build(),        // 0x18 lw    t4, flag2
+        b().beq(T4, 0, -4).build(),           // 0x1c beqz  t4, .-4
+        sc_w(S0, T3, T1),                     // 0x20 sc.w  s0, t3, (t1)
+        b().jal(0, 0).build(),                // 0x24 j     .
+        b().lw(T4, T1, 0x100).build(),        // 0x28 secondary: lw t4, flag1
+        b().beq(T4, 0, -4).build(),           // 0x2c beqz  t4, .-4
+        b().addi(T3, 0, 7).build(),           // 0x30 li    t3, 7
+        b().sw(T1, T3, store_offset).build(), // 0x34 sw    t3, store_offset(t1)
+        b().addi(T3, 0, 1).build(),           // 0x38 li    t3, 1
+        b().sw(T1, T3, 0x200).build(),        // 0x3c sw    t3, flag2
+        b().jal(0, 0).build(),                // 0x40 j     .
+    ]
+}
+
+/// Two harts in machine mode sharing a system running `program`.
+fn two_harts_running(program: &[u32]) -> Smp {
+    let mut config = Config::default();
+    config.general.direct_mode = false;
+    config.general.start_pc = BASE;
+    config.memory.ram_size = 4 * 1024 * 1024;
+    config.system.harts = 2;
+    let mut smp = Smp::new(System::new(&config, ""), &config).unwrap();
+
+    let bytes: Vec<u8> = program.iter().flat_map(|i| i.to_le_bytes()).collect();
+    smp.hart(0).bus.load_binary_at(&bytes, BASE);
+    smp
+}
+
+/// Two harts in machine mode sharing a system running [`ipi_program`].
+fn two_harts() -> Smp {
+    let mut config = Config::default();
+    config.general.direct_mode = false;
+    config.general.start_pc = BASE;
+    config.memory.ram_size = 4 * 1024 * 1024;
+    config.system.harts = 2;
+    let mut smp = Smp::new(System::new(&config, ""), &config).unwrap();
+
+    let bytes: Vec<u8> = ipi_program().iter().flat_map(|i| i.to_le_bytes()).collect();
+    smp.hart(0).bus.load_binary_at(&bytes, BASE);
+    smp
+}
+
+#[test]
+fn mhartid_reports_hart_index() {
+    let mut smp = two_harts();
+    assert_eq!(smp.len(), 2);
+    assert_eq!(smp.hart(0).read_csr(csr::MHARTID), 0);
+    assert_eq!(smp.hart(1).read_csr(csr::MHARTID), 1);
+}
+
+#[test]
+fn msip_wakes_secondary_hart_from_wfi() {
+    let mut smp = two_harts();
+    for _ in 0..20_000 {
+        smp.tick().unwrap();
+    }
+
+    let hart1 = smp.hart(1);
+    assert_eq!(hart1.read_reg(S0 as usize), 42, "software interrupt taken");
+    assert_eq!(
+        hart1.read_reg(S1 as usize),
+        1,
+        "execution continued past WFI"
+    );
+    assert_eq!(hart1.read_csr(csr::MCAUSE), MSI_CAUSE);
+    assert_eq!(
+        hart1.read_csr(csr::MIP) & csr::MIP_MSIP,
+        0,
+        "handler cleared MSIP"
+    );
+
+    let hart0 = smp.hart(0);
+    assert_eq!(hart0.read_reg(S0 as usize), 0, "hart 0 took no interrupt");
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Reservations across harts
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn remote_store_breaks_reservation() {
+    let mut smp = two_harts_running(&lr_sc_program(0));
+    for _ in 0..20_000 {
+        smp.tick().unwrap();
+    }
+
+    let hart0 = smp.hart(0);
+    assert_eq!(hart0.read_reg(S0 as usize), 1, "SC failed");
+    assert_eq!(
+        hart0.bus.bus.read_u32(BASE + 0x1000),
+        7,
+        "hart 1's store kept"
+    );
+}
+
+#[test]
+fn remote_store_to_other_line_keeps_reservation() {
+    let mut smp = two_harts_running(&lr_sc_program(0x40));
+    for _ in 0..20_000 {
+        smp.tick().unwrap();
+    }
+
+    let hart0 = smp.hart(0);
+    assert_eq!(hart0.read_reg(S0 as usize), 0, "SC succeeded");
+    assert_eq!(hart0.bus.bus.read_u32(BASE + 0x1000), 1);
+    assert_eq!(hart0.bus.bus.read_u32(BASE + 0x1040), 7);
+}
@@ -0,0 +1,43 @@
+//! ALU Address Generation Tests (Zba).
+//!
+//! Hand-computed vectors for SH1ADD, SH2ADD, SH3ADD, ADD.UW, and SLLI.UW
+//! covering scaled indexing and zero-extension of the index register.
+//!
+//! Reference: RISC-V Bit-Manipulation ISA-extensions, Zba chapter.
+
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::alu::Alu;
+
+fn alu(op: AluOp, a: u64, b: u64) -> u64 {
+    Alu::execute(op, a, b, 0, false)
+}
+
+#[test]
+fn shadd_scales_index() {
+    assert_eq!(alu(AluOp::Sh1Add, 5, 100), 110);
+    assert_eq!(alu(AluOp::Sh2Add, 5, 100), 120);
+    assert_eq!(alu(AluOp::Sh3Add, 5, 100), 5 * 8 + 100);
+}
+
+#[test]
+fn sh3add_negative_index_wraps() {
+    let idx = -2i64 as u64;
+    assert_eq!(alu(AluOp::Sh3Add, idx, 0x1000), 0x1000 - 16);
+}
+
+#[test]
+fn add_uw_ignores_upper_bits_of_rs1() {
+    assert_eq!(alu(AluOp::AddUw, 0xDEAD_BEEF_0000_0010, 0x1000), 0x1010);
+    assert_eq!(
+        alu(AluOp::AddUw, 0xFFFF_FFFF_FFFF_FFFF, 1),
+        0x1_0000_0000,
+        "low word is zero-extended, not sign-extended"
+    );
+}
+
+#[test]
+fn slli_uw_zero_extends_then_shifts() {
+    assert_eq!(alu(AluOp::SlliUw, 0xFFFF_FFFF_8000_0000, 2), 0x2_0000_0000);
+    // The shift amount is the low six bits of the immediate.
+    assert_eq!(alu(AluOp::SlliUw, 1, 0x080 | 40), 1 << 40);
+}
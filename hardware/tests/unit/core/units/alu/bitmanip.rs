@@ -0,0 +1,85 @@
+//! ALU Basic Bit-Manipulation Tests (Zbb).
+//!
+//! Hand-computed vectors for the Zbb operations covering:
+//!   - Count operations on zero, all-ones, and the `.W` forms
+//!   - Signed vs. unsigned MIN/MAX ordering
+//!   - Byte permutation (ORC.B, REV8)
+//!   - Sign and zero extension of bytes and halfwords
+//!
+//! Reference: RISC-V Bit-Manipulation ISA-extensions, Zbb chapter.
+
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::alu::Alu;
+
+fn unary(op: AluOp, a: u64) -> u64 {
+    Alu::execute(op, a, 0, 0, false)
+}
+
+fn unary_w(op: AluOp, a: u64) -> u64 {
+    Alu::execute(op, a, 0, 0, true)
+}
+
+fn binary(op: AluOp, a: u64, b: u64) -> u64 {
+    Alu::execute(op, a, b, 0, false)
+}
+
+#[test]
+fn clz_counts_leading_zeros() {
+    assert_eq!(unary(AluOp::Clz, 0), 64);
+    assert_eq!(unary(AluOp::Clz, 1), 63);
+    assert_eq!(unary(AluOp::Clz, 1 << 63), 0);
+    assert_eq!(unary_w(AluOp::Clz, 0), 32);
+    assert_eq!(
+        unary_w(AluOp::Clz, 0xFFFF_FFFF_0000_8000),
+        16,
+        "upper word ignored"
+    );
+}
+
+#[test]
+fn ctz_counts_trailing_zeros() {
+    assert_eq!(unary(AluOp::Ctz, 0), 64);
+    assert_eq!(unary(AluOp::Ctz, 0x100), 8);
+    assert_eq!(unary_w(AluOp::Ctz, 0x1_0000_0000), 32);
+}
+
+#[test]
+fn cpop_counts_set_bits() {
+    assert_eq!(unary(AluOp::Cpop, 0xFF), 8);
+    assert_eq!(unary(AluOp::Cpop, u64::MAX), 64);
+    assert_eq!(unary_w(AluOp::Cpop, u64::MAX), 32);
+}
+
+#[test]
+fn min_max_signed_vs_unsigned() {
+    let neg = -5i64 as u64;
+    assert_eq!(binary(AluOp::Max, neg, 3), 3);
+    assert_eq!(binary(AluOp::Min, neg, 3), neg);
+    assert_eq!(binary(AluOp::Maxu, neg, 3), neg);
+    assert_eq!(binary(AluOp::Minu, neg, 3), 3);
+}
+
+#[test]
+fn rev8_reverses_bytes() {
+    assert_eq!(
+        unary(AluOp::Rev8, 0x0102_0304_0506_0708),
+        0x0807_0605_0403_0201
+    );
+}
+
+#[test]
+fn orc_b_combines_within_bytes() {
+    assert_eq!(
+        unary(AluOp::OrcB, 0x0001_0080_0000_1000),
+        0x00FF_00FF_0000_FF00
+    );
+    assert_eq!(unary(AluOp::OrcB, 0), 0);
+}
+
+#[test]
+fn sign_and_zero_extension() {
+    assert_eq!(unary(AluOp::SextB, 0x1234_5680), 0xFFFF_FFFF_FFFF_FF80);
+    assert_eq!(unary(AluOp::SextB, 0x7F), 0x7F);
+    assert_eq!(unary(AluOp::SextH, 0x0001_8000), 0xFFFF_FFFF_FFFF_8000);
+    assert_eq!(unary(AluOp::ZextH, 0xFFFF_FFFF_FFFF_8000), 0x8000);
+}
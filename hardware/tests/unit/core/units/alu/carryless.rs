@@ -0,0 +1,138 @@
+//! ALU Carry-Less Multiplication Tests (Zbc).
+//!
+//! Hand-computed vectors for CLMUL, CLMULH, and CLMULR covering:
+//!   - Small polynomials whose products are easy to verify by hand
+//!   - Top-bit operands that cross the 64-bit product boundary
+//!   - All-ones operands (squares spread bits to even positions)
+//!   - The CRC-32 generator polynomial squared
+//!
+//! Reference: RISC-V Bit-Manipulation ISA-extensions, Zbc chapter.
+
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::alu::Alu;
+
+const TOP: u64 = 1 << 63;
+
+/// CRC-32 (IEEE 802.3) generator polynomial including the x^32 term.
+const CRC32_POLY: u64 = 0x1_04C1_1DB7;
+
+fn clmul(a: u64, b: u64) -> u64 {
+    Alu::execute(AluOp::Clmul, a, b, 0, false)
+}
+
+fn clmulh(a: u64, b: u64) -> u64 {
+    Alu::execute(AluOp::Clmulh, a, b, 0, false)
+}
+
+fn clmulr(a: u64, b: u64) -> u64 {
+    Alu::execute(AluOp::Clmulr, a, b, 0, false)
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. CLMUL (low half)
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn clmul_by_zero_and_one() {
+    assert_eq!(clmul(0xDEAD_BEEF, 0), 0);
+    assert_eq!(clmul(0xDEAD_BEEF, 1), 0xDEAD_BEEF);
+    assert_eq!(clmul(1, 0xDEAD_BEEF), 0xDEAD_BEEF);
+}
+
+#[test]
+fn clmul_no_carry_between_partial_products() {
+    // (x + 1)^2 = x^2 + 1 over GF(2): 3 * 3 = 5, not 9
+    assert_eq!(clmul(3, 3), 5);
+    // 0b1011 * 0b110 = 0b10110 ^ 0b101100 = 0b111010
+    assert_eq!(clmul(0b1011, 0b110), 0b11_1010);
+}
+
+#[test]
+fn clmul_is_commutative() {
+    let a = 0x0123_4567_89AB_CDEF;
+    let b = 0xFEDC_BA98_7654_3210;
+    assert_eq!(clmul(a, b), clmul(b, a));
+    assert_eq!(clmulh(a, b), clmulh(b, a));
+}
+
+#[test]
+fn clmul_crc32_polynomial_squared() {
+    // Squaring over GF(2) moves bit i to bit 2i
+    assert_eq!(clmul(CRC32_POLY, CRC32_POLY), 0x0010_5001_0151_4515);
+    assert_eq!(clmulh(CRC32_POLY, CRC32_POLY), 0x1);
+}
+
+#[test]
+fn clmul_crc32_polynomial_fold() {
+    assert_eq!(clmul(0x04C1_1DB7, 0xF0F0_F0F0), 0x0387_7CA7_74F0_0BD0);
+    assert_eq!(clmulh(0x04C1_1DB7, 0xF0F0_F0F0), 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. CLMULH (high half)
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn clmulh_small_operands_are_zero() {
+    assert_eq!(clmulh(3, 3), 0);
+    assert_eq!(clmulh(u64::MAX, 1), 0);
+}
+
+#[test]
+fn clmulh_top_bits() {
+    // x^63 * x^63 = x^126 → bit 62 of the high half
+    assert_eq!(clmul(TOP, TOP), 0);
+    assert_eq!(clmulh(TOP, TOP), 1 << 62);
+    assert_eq!(clmulh(TOP, 2), 1);
+}
+
+#[test]
+fn clmulh_all_ones() {
+    // (Σ x^i)^2 = Σ x^(2i) → every even bit of the 127-bit product
+    assert_eq!(clmul(u64::MAX, u64::MAX), 0x5555_5555_5555_5555);
+    assert_eq!(clmulh(u64::MAX, u64::MAX), 0x5555_5555_5555_5555);
+}
+
+#[test]
+fn clmulh_ghash_reduction_constant() {
+    assert_eq!(clmul(0xC200_0000_0000_0001, 0x87), 0x4E00_0000_0000_0087);
+    assert_eq!(clmulh(0xC200_0000_0000_0001, 0x87), 0x63);
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. CLMULR (reversed, bits 126..63)
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn clmulr_top_bits() {
+    assert_eq!(clmulr(TOP, TOP), TOP);
+    assert_eq!(clmulr(1, 1), 0);
+}
+
+#[test]
+fn clmulr_all_ones() {
+    assert_eq!(clmulr(u64::MAX, u64::MAX), 0xAAAA_AAAA_AAAA_AAAA);
+}
+
+#[test]
+fn clmulr_ghash_reduction_constant() {
+    assert_eq!(clmulr(0xC200_0000_0000_0001, 0x87), 0xC6);
+}
+
+#[test]
+fn clmulr_matches_bit_reversed_clmul() {
+    let a = 0x0123_4567_89AB_CDEF;
+    let b = 0x0F1E_2D3C_4B5A_6978;
+    assert_eq!(
+        clmulr(a, b),
+        clmul(a.reverse_bits(), b.reverse_bits()).reverse_bits()
+    );
+}
+
+#[test]
+fn clmul_ignores_rv32_flag() {
+    assert_eq!(
+        Alu::execute(AluOp::Clmul, u64::MAX, u64::MAX, 0, true),
+        clmul(u64::MAX, u64::MAX)
+    );
+}
@@ -1,3 +1,6 @@
+pub mod address;
 pub mod arithmetic;
+pub mod bitmanip;
+pub mod carryless;
 pub mod logic;
 pub mod shifts;
@@ -1,9 +1,11 @@
 //! Branch Target Buffer (BTB) Tests.
 //!
 //! Verifies lookup/update semantics, tag matching, aliasing behaviour,
-//! and capacity-related edge cases for the direct-mapped BTB.
+//! capacity-related edge cases for the direct-mapped BTB, and the branch
+//! type recorded with each entry.
 
-use riscv_core::core::units::bru::btb::Btb;
+use crate::common::builder::instruction::InstructionBuilder;
+use riscv_core::core::units::bru::btb::{BranchType, Btb};
 
 // ══════════════════════════════════════════════════════════
 // 1. Basic lookup/update
@@ -18,15 +20,15 @@ fn lookup_empty_returns_none() {
 #[test]
 fn update_then_lookup() {
     let mut btb = Btb::new(16);
-    btb.update(0x1000, 0x2000);
+    btb.update(0x1000, 0x2000, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1000), Some(0x2000));
 }
 
 #[test]
 fn update_overwrites_previous_target() {
     let mut btb = Btb::new(16);
-    btb.update(0x1000, 0x2000);
-    btb.update(0x1000, 0x3000);
+    btb.update(0x1000, 0x2000, BranchType::Conditional);
+    btb.update(0x1000, 0x3000, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1000), Some(0x3000), "Latest update should win");
 }
 
@@ -37,7 +39,7 @@ fn update_overwrites_previous_target() {
 #[test]
 fn lookup_wrong_pc_returns_none() {
     let mut btb = Btb::new(16);
-    btb.update(0x1000, 0x2000);
+    btb.update(0x1000, 0x2000, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1004), None, "Different PC should not match");
 }
 
@@ -48,8 +50,8 @@ fn lookup_after_aliasing_eviction() {
     let mut btb = Btb::new(4); // 4 entries → index = (pc >> 2) & 3
     let pc_a = 0x1000; // index = (0x1000 >> 2) & 3 = 0
     let pc_b = 0x1010; // index = (0x1010 >> 2) & 3 = 0 (same index!)
-    btb.update(pc_a, 0xAAAA);
-    btb.update(pc_b, 0xBBBB);
+    btb.update(pc_a, 0xAAAA, BranchType::Conditional);
+    btb.update(pc_b, 0xBBBB, BranchType::Conditional);
     assert_eq!(btb.lookup(pc_a), None, "pc_a evicted by pc_b (same index)");
     assert_eq!(btb.lookup(pc_b), Some(0xBBBB));
 }
@@ -61,10 +63,10 @@ fn lookup_after_aliasing_eviction() {
 #[test]
 fn multiple_entries_non_conflicting() {
     let mut btb = Btb::new(64);
-    btb.update(0x1000, 0xA);
-    btb.update(0x1004, 0xB);
-    btb.update(0x1008, 0xC);
-    btb.update(0x100C, 0xD);
+    btb.update(0x1000, 0xA, BranchType::Conditional);
+    btb.update(0x1004, 0xB, BranchType::Conditional);
+    btb.update(0x1008, 0xC, BranchType::Conditional);
+    btb.update(0x100C, 0xD, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1000), Some(0xA));
     assert_eq!(btb.lookup(0x1004), Some(0xB));
     assert_eq!(btb.lookup(0x1008), Some(0xC));
@@ -82,7 +84,7 @@ fn index_wraps_around() {
     let mut btb = Btb::new(8);
     for i in 0u64..8 {
         let pc = i * 4; // Each goes to a unique index
-        btb.update(pc, 0x1000 + i);
+        btb.update(pc, 0x1000 + i, BranchType::Conditional);
     }
     for i in 0u64..8 {
         let pc = i * 4;
@@ -99,7 +101,7 @@ fn fill_entire_btb() {
     let size = 32;
     let mut btb = Btb::new(size);
     for i in 0..size as u64 {
-        btb.update(i * 4, 0xF000 + i);
+        btb.update(i * 4, 0xF000 + i, BranchType::Conditional);
     }
     for i in 0..size as u64 {
         assert_eq!(btb.lookup(i * 4), Some(0xF000 + i));
@@ -113,7 +115,7 @@ fn fill_entire_btb() {
 #[test]
 fn lookup_pc_zero() {
     let mut btb = Btb::new(16);
-    btb.update(0, 0x4000);
+    btb.update(0, 0x4000, BranchType::Conditional);
     assert_eq!(btb.lookup(0), Some(0x4000));
 }
 
@@ -121,21 +123,21 @@ fn lookup_pc_zero() {
 fn lookup_high_address() {
     let mut btb = Btb::new(16);
     let high_pc = 0x8000_0000_0000_0000;
-    btb.update(high_pc, 0xDEAD);
+    btb.update(high_pc, 0xDEAD, BranchType::Conditional);
     assert_eq!(btb.lookup(high_pc), Some(0xDEAD));
 }
 
 #[test]
 fn target_zero_is_valid() {
     let mut btb = Btb::new(16);
-    btb.update(0x1000, 0);
+    btb.update(0x1000, 0, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1000), Some(0), "Target address 0 is valid");
 }
 
 #[test]
 fn target_max_is_valid() {
     let mut btb = Btb::new(16);
-    btb.update(0x1000, u64::MAX);
+    btb.update(0x1000, u64::MAX, BranchType::Conditional);
     assert_eq!(btb.lookup(0x1000), Some(u64::MAX));
 }
 
@@ -154,7 +156,7 @@ fn loop_branch_updates_consistently() {
     assert_eq!(btb.lookup(branch_pc), None);
 
     // After resolution, update the BTB
-    btb.update(branch_pc, target);
+    btb.update(branch_pc, target, BranchType::Conditional);
 
     // Subsequent lookups hit
     for _ in 0..10 {
@@ -168,12 +170,74 @@ fn switching_targets() {
     let mut btb = Btb::new(64);
     let pc = 0x2000;
 
-    btb.update(pc, 0xA000);
+    btb.update(pc, 0xA000, BranchType::IndirectJump);
     assert_eq!(btb.lookup(pc), Some(0xA000));
 
-    btb.update(pc, 0xB000);
+    btb.update(pc, 0xB000, BranchType::IndirectJump);
     assert_eq!(btb.lookup(pc), Some(0xB000));
 
-    btb.update(pc, 0xC000);
+    btb.update(pc, 0xC000, BranchType::IndirectJump);
     assert_eq!(btb.lookup(pc), Some(0xC000));
 }
+
+// ══════════════════════════════════════════════════════════
+// 7. Branch types
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn branch_type_stored_with_entry() {
+    let mut btb = Btb::new(64);
+    assert_eq!(btb.branch_type(0x1000), None, "miss has no type");
+
+    btb.update(0x1000, 0x2000, BranchType::Return);
+    assert_eq!(btb.branch_type(0x1000), Some(BranchType::Return));
+    assert_eq!(btb.branch_type(0x1004), None, "tag must match");
+
+    btb.update(0x1000, 0x3000, BranchType::Call);
+    assert_eq!(btb.branch_type(0x1000), Some(BranchType::Call));
+}
+
+#[test]
+fn branch_type_evicted_with_entry() {
+    let mut btb = Btb::new(4);
+    btb.update(0x1000, 0xAAAA, BranchType::Return);
+    btb.update(0x1010, 0xBBBB, BranchType::DirectJump);
+    assert_eq!(btb.branch_type(0x1000), None);
+    assert_eq!(btb.branch_type(0x1010), Some(BranchType::DirectJump));
+}
+
+#[test]
+fn classify_uses_link_register_hints() {
+    let b = InstructionBuilder::new;
+    let classify = BranchType::classify;
+
+    assert_eq!(
+        classify(b().beq(1, 2, 8).build()),
+        Some(BranchType::Conditional)
+    );
+    assert_eq!(classify(b().jal(1, 16).build()), Some(BranchType::Call));
+    assert_eq!(
+        classify(b().jal(5, 16).build()),
+        Some(BranchType::Call),
+        "t0 links"
+    );
+    assert_eq!(
+        classify(b().jal(0, 16).build()),
+        Some(BranchType::DirectJump)
+    );
+    assert_eq!(
+        classify(b().jalr(0, 1, 0).build()),
+        Some(BranchType::Return)
+    );
+    assert_eq!(
+        classify(b().jalr(0, 5, 0).build()),
+        Some(BranchType::Return)
+    );
+    assert_eq!(classify(b().jalr(1, 10, 0).build()), Some(BranchType::Call));
+    assert_eq!(classify(b().jalr(1, 1, 0).build()), Some(BranchType::Call));
+    assert_eq!(
+        classify(b().jalr(0, 10, 0).build()),
+        Some(BranchType::IndirectJump)
+    );
+    assert_eq!(classify(b().addi(1, 1, 4).build()), None);
+}
@@ -0,0 +1,116 @@
+//! ITTAGE-style Indirect Predictor Tests.
+//!
+//! Verifies that the indirect predictor:
+//!   1. Predicts nothing until trained, then a stable single target
+//!   2. Learns targets that alternate or cycle with the path history
+//!   3. Beats the single-target BTB on multi-target jumps, end to end
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::core::units::bru::btb::{BranchType, Btb};
+use riscv_core::core::units::bru::ittage::IndirectPredictor;
+
+const PC: u64 = 0x8000_1000;
+
+/// Feeds `targets` to the predictor and a BTB in order, returning how many
+/// of the last `scored` each predicted correctly.
+fn score(targets: &[u64], scored: usize) -> (usize, usize) {
+    let mut ind = IndirectPredictor::new();
+    let mut btb = Btb::new(64);
+    let (mut ind_hits, mut btb_hits) = (0, 0);
+    for (i, &target) in targets.iter().enumerate() {
+        if i >= targets.len() - scored {
+            ind_hits += usize::from(ind.predict(PC) == Some(target));
+            btb_hits += usize::from(btb.lookup(PC) == Some(target));
+        }
+        ind.update(PC, target);
+        btb.update(PC, target, BranchType::IndirectJump);
+    }
+    (ind_hits, btb_hits)
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Single target
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn untrained_predicts_none() {
+    assert_eq!(IndirectPredictor::new().predict(PC), None);
+}
+
+#[test]
+fn stable_target_learned() {
+    let targets = vec![0x8000_2000; 50];
+    let (ind, btb) = score(&targets, 40);
+    assert_eq!(ind, 40);
+    assert_eq!(btb, 40);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. History-correlated targets
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn alternating_targets_beat_btb() {
+    let targets: Vec<u64> = (0..200)
+        .map(|i| if i % 2 == 0 { 0x8000_2000 } else { 0x8000_3040 })
+        .collect();
+    let (ind, btb) = score(&targets, 100);
+
+    assert_eq!(btb, 0, "a single-target BTB always holds the other target");
+    assert!(ind >= 95, "indirect predictor hit {ind}/100");
+}
+
+#[test]
+fn cycling_targets_learned() {
+    let cycle = [0x8000_2000, 0x8000_2100, 0x8000_2200, 0x8000_2300];
+    let targets: Vec<u64> = (0..400).map(|i| cycle[i % cycle.len()]).collect();
+    let (ind, btb) = score(&targets, 100);
+
+    assert_eq!(btb, 0);
+    assert!(ind >= 95, "indirect predictor hit {ind}/100");
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Pipeline
+// ══════════════════════════════════════════════════════════
+
+/// Loops over `jalr x0, 0(x7)` whose target alternates between two blocks,
+/// each of which swaps `x7`/`x8` and jumps back.
+#[test]
+fn alternating_jalr_mostly_predicted_in_pipeline() {
+    const BASE: u64 = 0x8000_0000;
+    let b = InstructionBuilder::new;
+    let program = [
+        b().jalr(0, 7, 0).build(),   // 0x00: dispatch
+        b().nop().build(),           // 0x04
+        b().addi(10, 10, 1).build(), // 0x08: block A
+        b().jal(0, 12).build(),      // 0x0C: -> swap
+        b().addi(11, 11, 1).build(), // 0x10: block B
+        b().jal(0, 4).build(),       // 0x14: -> swap
+        b().xor(7, 7, 8).build(),    // 0x18: swap x7, x8
+        b().xor(8, 7, 8).build(),
+        b().xor(7, 7, 8).build(),
+        b().jal(0, -36).build(), // -> dispatch
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    tc.set_reg(7, BASE + 0x08);
+    tc.set_reg(8, BASE + 0x10);
+
+    tc.run(4000);
+
+    let dispatches = tc.get_reg(10) + tc.get_reg(11);
+    assert!(dispatches > 100, "loop ran {dispatches} times");
+    assert!(
+        tc.get_reg(10).abs_diff(tc.get_reg(11)) <= 1,
+        "targets alternate"
+    );
+    // BTB-only prediction would miss every dispatch.
+    assert!(
+        tc.cpu.stats.branch_mispredictions < dispatches / 10,
+        "{} mispredictions over {dispatches} dispatches",
+        tc.cpu.stats.branch_mispredictions
+    );
+}
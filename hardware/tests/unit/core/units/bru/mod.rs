@@ -1,3 +1,4 @@
 pub mod btb;
+pub mod ittage;
 pub mod predictors;
 pub mod ras;
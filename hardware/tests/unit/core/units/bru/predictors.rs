@@ -259,6 +259,49 @@ fn tage_adapts_to_pattern_change() {
     assert!(t2, "Should adapt to taken after retraining");
 }
 
+/// Runs `trips` trips of a loop branch taken `n` times then not taken,
+/// returning per trip whether the exit and every body iteration were predicted.
+fn run_loop_trips(bp: &mut TagePredictor, pc: u64, n: usize, trips: usize) -> Vec<(bool, bool)> {
+    (0..trips)
+        .map(|_| {
+            let mut body_ok = true;
+            for _ in 0..n {
+                body_ok &= bp.predict_branch(pc).0;
+                bp.update_branch(pc, true, Some(0x2000));
+            }
+            let exit_ok = !bp.predict_branch(pc).0;
+            bp.update_branch(pc, false, None);
+            (exit_ok, body_ok)
+        })
+        .collect()
+}
+
+/// The loop predictor learns a trip count longer than any history and
+/// predicts the exit once confident.
+#[test]
+fn tage_loop_predictor_predicts_exit() {
+    let mut bp = default_tage();
+    let results = run_loop_trips(&mut bp, 0x1000, 100, 10);
+
+    assert!(!results[0].0, "cold exit is mispredicted");
+    for (trip, &(exit_ok, body_ok)) in results.iter().enumerate().skip(6) {
+        assert!(exit_ok, "trip {trip}: exit predicted");
+        assert!(body_ok, "trip {trip}: body predicted");
+    }
+}
+
+/// A changed trip count drops confidence, and the new count is learned.
+#[test]
+fn tage_loop_predictor_relearns_trip_count() {
+    let mut bp = default_tage();
+    let pc = 0x1000;
+    run_loop_trips(&mut bp, pc, 100, 8);
+
+    let results = run_loop_trips(&mut bp, pc, 70, 10);
+    assert!(!results[0].0, "old count predicts no exit at 70");
+    assert!(results[9].0, "new count learned");
+}
+
 // ══════════════════════════════════════════════════════════
 // 5. Tournament Predictor
 // ══════════════════════════════════════════════════════════
@@ -354,7 +397,7 @@ fn all_predictors_use_btb() {
 fn all_predictors_use_ras() {
     let call_pc = 0x1000;
     let ret_addr = 0x1004;
-    let call_target = 0x2000;
+    let call_target = Some(0x2000);
 
     let mut static_bp = StaticPredictor::new(64, 8);
     static_bp.on_call(call_pc, ret_addr, call_target);
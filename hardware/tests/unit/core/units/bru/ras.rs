@@ -1,8 +1,12 @@
 //! Return Address Stack (RAS) Tests.
 //!
 //! Verifies push/pop/top semantics, overflow behaviour, underflow safety,
-//! and correct LIFO ordering for return address prediction.
+//! and correct LIFO ordering for return address prediction, plus checkpoint
+//! repair of wrong-path updates in the pipeline and of updates squashed by a trap.
 
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::core::arch::mode::PrivilegeMode;
 use riscv_core::core::units::bru::ras::Ras;
 
 // ══════════════════════════════════════════════════════════
@@ -179,3 +183,166 @@ fn push_max_address() {
     ras.push(u64::MAX);
     assert_eq!(ras.pop(), Some(u64::MAX));
 }
+
+// ══════════════════════════════════════════════════════════
+// 7. Checkpoint repair
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn restore_undoes_wrong_path_push() {
+    let mut ras = Ras::new(8);
+    ras.push(0x1000);
+    let cp = ras.checkpoint();
+    ras.push(0xBAD0);
+    ras.push(0xBAD4);
+    ras.restore(cp);
+    assert_eq!(ras.pop(), Some(0x1000));
+    assert_eq!(ras.pop(), None);
+}
+
+#[test]
+fn restore_undoes_pop_then_overwriting_push() {
+    let mut ras = Ras::new(8);
+    ras.push(0x1000);
+    ras.push(0x2000);
+    let cp = ras.checkpoint();
+    // Wrong-path return then call: the push reuses the popped slot.
+    ras.pop();
+    ras.push(0xBAD0);
+    ras.restore(cp);
+    assert_eq!(ras.pop(), Some(0x2000));
+    assert_eq!(ras.pop(), Some(0x1000));
+}
+
+#[test]
+fn restore_repairs_overwritten_top_when_full() {
+    let mut ras = Ras::new(2);
+    ras.push(0x1000);
+    ras.push(0x2000);
+    let cp = ras.checkpoint();
+    ras.push(0xBAD0); // Full: overwrites the top in place.
+    ras.restore(cp);
+    assert_eq!(ras.top(), Some(0x2000));
+}
+
+const BASE: u64 = 0x8000_0000;
+const WFI: u32 = 0x1050_0073;
+
+/// A call into a leaf that branches over a call the cold predictor fetches
+/// down the wrong path, then returns via a RAS prediction.
+fn program(wrong_path: u32) -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().jal(1, 16).build(),     // 0x00 call leaf
+        b().addi(7, 0, 7).build(),  // 0x04 return lands here
+        WFI,                        // 0x08
+        b().addi(0, 0, 0).build(),  // 0x0C
+        b().addi(5, 0, 1).build(),  // 0x10 leaf:
+        b().bne(5, 0, 12).build(),  // 0x14 -> 0x20, predicted not taken
+        wrong_path,                 // 0x18 wrong path
+        b().addi(6, 0, 99).build(), // 0x1C wrong path
+        b().jalr(0, 1, 0).build(),  // 0x20 ret
+    ]
+}
+
+/// Runs `program` to WFI and returns the number of mispredictions.
+fn mispredictions(wrong_path: u32) -> u64 {
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program(wrong_path));
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    for _ in 0..200 {
+        tc.cpu.tick().unwrap();
+    }
+    assert!(tc.cpu.wfi_waiting, "program should park in WFI");
+    assert_eq!(tc.get_reg(6), 0, "wrong path must not commit");
+    assert_eq!(tc.get_reg(7), 7);
+    tc.cpu.stats.branch_mispredictions
+}
+
+#[test]
+fn wrong_path_call_does_not_corrupt_return_prediction() {
+    let wrong_path_call = InstructionBuilder::new().jal(1, 0x100).build();
+    let nop = InstructionBuilder::new().addi(0, 0, 0).build();
+
+    // Cold jal and cold bne mispredict; the return must not.
+    assert_eq!(mispredictions(nop), 2);
+    assert_eq!(
+        mispredictions(wrong_path_call),
+        2,
+        "the wrong-path push of 0x8000001C must be undone before the ret is fetched"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 8. Repair on traps
+// ══════════════════════════════════════════════════════════
+
+const MRET: u32 = 0x3020_0073;
+/// `csrw mepc, x5`.
+const CSRW_MEPC_T0: u32 = 0x3412_9073;
+const ILLEGAL: u32 = 0xFFFF_FFFF;
+
+/// A call into a leaf whose first instruction traps while the call behind it
+/// is already fetched; the handler resumes at the leaf's return.
+fn trap_program(after_fault: u32) -> Vec<u32> {
+    let b = InstructionBuilder::new;
+    vec![
+        b().jal(1, 32).build(),    // 0x00 call leaf
+        b().addi(7, 0, 7).build(), // 0x04 return lands here
+        WFI,                       // 0x08
+        b().addi(0, 0, 0).build(), // 0x0C
+        b().addi(0, 0, 0).build(), // 0x10
+        b().addi(0, 0, 0).build(), // 0x14
+        b().addi(0, 0, 0).build(), // 0x18
+        b().addi(0, 0, 0).build(), // 0x1C
+        ILLEGAL,                   // 0x20 leaf: traps
+        after_fault,               // 0x24 squashed by the trap
+        b().addi(0, 0, 0).build(), // 0x28
+        b().jalr(0, 1, 0).build(), // 0x2C ret
+    ]
+}
+
+/// Runs `trap_program` to WFI and returns the number of mispredicted returns.
+fn return_mispredictions(after_fault: u32) -> u64 {
+    let b = InstructionBuilder::new;
+    // The handler resumes at the ret, 0x14 bytes before it.
+    let handler = [
+        b().auipc(5, 0).build(),
+        b().addi(5, 5, -0x14).build(),
+        CSRW_MEPC_T0,
+        MRET,
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE + 0x40, &handler)
+        .load_program(BASE, &trap_program(after_fault));
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.cpu.csrs.mtvec = BASE + 0x40;
+    for _ in 0..200 {
+        tc.cpu.tick().unwrap();
+    }
+    assert!(tc.cpu.wfi_waiting, "program should park in WFI");
+    assert_eq!(tc.cpu.csrs.mcause, 2, "illegal instruction");
+    assert_eq!(tc.get_reg(7), 7);
+    tc.cpu
+        .stats
+        .mispredicts_by_type
+        .get("return")
+        .copied()
+        .unwrap_or(0)
+}
+
+#[test]
+fn trap_undoes_call_fetched_behind_the_faulting_instruction() {
+    let call = InstructionBuilder::new().jal(1, 0x100).build();
+    let nop = InstructionBuilder::new().addi(0, 0, 0).build();
+
+    assert_eq!(return_mispredictions(nop), 0);
+    assert_eq!(
+        return_mispredictions(call),
+        0,
+        "the squashed push of 0x80000028 must not steer the ret"
+    );
+}
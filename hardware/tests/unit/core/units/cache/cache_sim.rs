@@ -2,16 +2,21 @@
 //!
 //! Verifies the set-associative cache simulator with configurable replacement
 //! policies and prefetchers. Tests exercise hit/miss logic, write-back penalties,
-//! flushing, and disabled-cache behavior.
+//! write-through and no-write-allocate stores, seeded random replacement, the victim
+//! cache, flushing, disabled-cache behavior, and rejection of degenerate geometries
+//! (also when building a CPU).
 //!
 //! The CacheSim is constructed directly from CacheConfig — no full CPU needed.
 //!
 //! Reference: Phase 3 — Memory Subsystem Verification.
 
 use riscv_core::config::{
-    CacheConfig, Prefetcher as PrefetcherType, ReplacementPolicy as PolicyType,
+    AllocPolicy, CacheConfig, Config, ConfigError, Prefetcher as PrefetcherType,
+    ReplacementPolicy as PolicyType, WritePolicy,
 };
-use riscv_core::core::units::cache::CacheSim;
+use riscv_core::core::Cpu;
+use riscv_core::core::units::cache::{CacheConfigError, CacheSim};
+use riscv_core::soc::System;
 
 // ──────────────────────────────────────────────────────────
 // Helper: build a simple test cache
@@ -36,6 +41,11 @@ fn test_config() -> CacheConfig {
         line_bytes: 64,
         ways: 2,
         policy: PolicyType::Lru,
+        rng_seed: 1,
+        mshrs: 0,
+        victim_entries: 0,
+        write_policy: WritePolicy::WriteBack,
+        alloc_policy: AllocPolicy::WriteAllocate,
         latency: 1,
         prefetcher: PrefetcherType::None,
         prefetch_table_size: 64,
@@ -54,7 +64,7 @@ const NEXT_LEVEL_LATENCY: u64 = 10;
 /// Returns (false, 0) because install_line has no dirty victim to write back.
 #[test]
 fn cold_miss_returns_miss_no_penalty() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
     let (hit, penalty) = cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
 
     assert!(!hit, "First access should be a miss");
@@ -68,7 +78,7 @@ fn cold_miss_returns_miss_no_penalty() {
 /// Second access to the same address should be a hit with 0 penalty.
 #[test]
 fn warm_hit_returns_hit_zero_penalty() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Cold miss to install line.
     cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
@@ -82,7 +92,7 @@ fn warm_hit_returns_hit_zero_penalty() {
 /// Access to a different offset within the same cache line should hit.
 #[test]
 fn same_line_different_offset_hits() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Access byte 0 of a line.
     cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
@@ -100,7 +110,7 @@ fn same_line_different_offset_hits() {
 /// The third access should miss (evicting the LRU line).
 #[test]
 fn set_conflict_eviction() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Config: 2 sets, 2 ways, line_bytes=64.
     // Set index = (addr / 64) % 2
@@ -147,7 +157,7 @@ fn set_conflict_eviction() {
 /// penalty equal to next_level_latency.
 #[test]
 fn dirty_writeback_penalty_on_eviction() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Write to addr_a → installs dirty line in set 0, way 0.
     cache.access(0, true, NEXT_LEVEL_LATENCY);
@@ -169,7 +179,7 @@ fn dirty_writeback_penalty_on_eviction() {
 /// The write-back penalty should still occur.
 #[test]
 fn dirty_bit_persists_across_reads() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Write addr_a (dirty).
     cache.access(0, true, NEXT_LEVEL_LATENCY);
@@ -189,7 +199,7 @@ fn dirty_bit_persists_across_reads() {
 /// Evicting a clean (read-only) line incurs no write-back penalty.
 #[test]
 fn clean_eviction_no_penalty() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Read addr_a (clean).
     cache.access(0, false, NEXT_LEVEL_LATENCY);
@@ -208,7 +218,7 @@ fn clean_eviction_no_penalty() {
 /// After flushing, previously cached dirty lines become misses.
 #[test]
 fn flush_invalidates_dirty_lines() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Write to install a dirty line.
     cache.access(0x1000, true, NEXT_LEVEL_LATENCY);
@@ -225,7 +235,7 @@ fn flush_invalidates_dirty_lines() {
 /// Flush only invalidates dirty lines; clean lines survive.
 #[test]
 fn flush_preserves_clean_lines() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     // Read (clean).
     cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
@@ -247,7 +257,7 @@ fn flush_preserves_clean_lines() {
 fn disabled_cache_always_returns_false_zero() {
     let mut config = test_config();
     config.enabled = false;
-    let mut cache = CacheSim::new(&config);
+    let mut cache = CacheSim::try_new(&config).unwrap();
 
     let (hit, penalty) = cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
     assert!(!hit);
@@ -264,7 +274,7 @@ fn disabled_cache_always_returns_false_zero() {
 fn disabled_cache_contains_nothing() {
     let mut config = test_config();
     config.enabled = false;
-    let mut cache = CacheSim::new(&config);
+    let mut cache = CacheSim::try_new(&config).unwrap();
 
     cache.access(0x1000, false, NEXT_LEVEL_LATENCY);
     assert!(!cache.contains(0x1000));
@@ -277,7 +287,7 @@ fn disabled_cache_contains_nothing() {
 /// `contains` mirrors the hit/miss status of the cache.
 #[test]
 fn contains_mirrors_hit_status() {
-    let mut cache = CacheSim::new(&test_config());
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
 
     assert!(!cache.contains(0x2000), "Should not contain before access");
 
@@ -310,13 +320,18 @@ fn different_line_size_32b() {
         line_bytes: 32,
         ways: 2,
         policy: PolicyType::Lru,
+        rng_seed: 1,
+        mshrs: 0,
+        victim_entries: 0,
+        write_policy: WritePolicy::WriteBack,
+        alloc_policy: AllocPolicy::WriteAllocate,
         latency: 1,
         prefetcher: PrefetcherType::None,
         prefetch_table_size: 64,
         prefetch_degree: 1,
     };
     // num_lines = 256/32 = 8, num_sets = 8/2 = 4, line_bytes = 32.
-    let mut cache = CacheSim::new(&config);
+    let mut cache = CacheSim::try_new(&config).unwrap();
 
     cache.access(0x100, false, NEXT_LEVEL_LATENCY);
     // 0x100 + 16 is in the same 32-byte line → should hit.
@@ -337,13 +352,18 @@ fn different_line_size_128b() {
         line_bytes: 128,
         ways: 2,
         policy: PolicyType::Lru,
+        rng_seed: 1,
+        mshrs: 0,
+        victim_entries: 0,
+        write_policy: WritePolicy::WriteBack,
+        alloc_policy: AllocPolicy::WriteAllocate,
         latency: 1,
         prefetcher: PrefetcherType::None,
         prefetch_table_size: 64,
         prefetch_degree: 1,
     };
     // num_lines = 1024/128 = 8, num_sets = 8/2 = 4, line_bytes = 128.
-    let mut cache = CacheSim::new(&config);
+    let mut cache = CacheSim::try_new(&config).unwrap();
 
     cache.access(0x200, false, NEXT_LEVEL_LATENCY);
     // 0x200 + 100 is within the same 128-byte line → hit.
@@ -354,3 +374,344 @@ fn different_line_size_128b() {
     let (hit, _) = cache.access(0x200 + 128, false, NEXT_LEVEL_LATENCY);
     assert!(!hit, "Different 128-byte line should miss");
 }
+
+// ══════════════════════════════════════════════════════════
+// 10. Degenerate Geometry Validation
+// ══════════════════════════════════════════════════════════
+
+/// Builds `test_config()` with the given geometry and returns the error.
+fn geometry_error(size_bytes: usize, line_bytes: usize, ways: usize) -> CacheConfigError {
+    let config = CacheConfig {
+        size_bytes,
+        line_bytes,
+        ways,
+        ..test_config()
+    };
+    match CacheSim::try_new(&config) {
+        Ok(_) => panic!("{size_bytes}B / {line_bytes}B x {ways} should be rejected"),
+        Err(e) => e,
+    }
+}
+
+/// Zero associativity would divide by zero when computing sets.
+#[test]
+fn zero_ways_is_rejected() {
+    assert_eq!(geometry_error(256, 64, 0), CacheConfigError::ZeroWays);
+}
+
+/// Zero or non-power-of-two lines cannot be indexed by shifting.
+#[test]
+fn invalid_line_size_is_rejected() {
+    assert_eq!(
+        geometry_error(256, 0, 2),
+        CacheConfigError::InvalidLineSize(0)
+    );
+    assert_eq!(
+        geometry_error(240, 48, 1),
+        CacheConfigError::InvalidLineSize(48)
+    );
+}
+
+/// A line larger than the whole cache leaves no sets.
+#[test]
+fn line_larger_than_cache_is_rejected() {
+    assert_eq!(
+        geometry_error(32, 64, 1),
+        CacheConfigError::LineLargerThanCache {
+            line_bytes: 64,
+            size_bytes: 32,
+        }
+    );
+    assert_eq!(
+        geometry_error(0, 64, 1),
+        CacheConfigError::LineLargerThanCache {
+            line_bytes: 64,
+            size_bytes: 0,
+        }
+    );
+}
+
+/// Set counts must be whole, non-zero powers of two.
+#[test]
+fn invalid_set_count_is_rejected() {
+    // 384 / (64 * 2) = 3 sets
+    assert_eq!(
+        geometry_error(384, 64, 2),
+        CacheConfigError::InvalidSetCount {
+            size_bytes: 384,
+            line_bytes: 64,
+            ways: 2,
+        }
+    );
+    // 128 / (64 * 4) = 0 sets
+    assert!(matches!(
+        geometry_error(128, 64, 4),
+        CacheConfigError::InvalidSetCount { .. }
+    ));
+    // 320 is not a multiple of 64 * 2
+    assert!(matches!(
+        geometry_error(320, 64, 2),
+        CacheConfigError::InvalidSetCount { .. }
+    ));
+}
+
+/// Building a CPU reports a degenerate cache as a configuration error naming the level.
+#[test]
+fn cpu_construction_rejects_degenerate_cache() {
+    let mut config = Config::default();
+    config.memory.ram_size = 1 << 20;
+    config.cache.l1_d = CacheConfig {
+        ways: 0,
+        ..test_config()
+    };
+    match Cpu::new(System::new(&config, ""), &config) {
+        Ok(_) => panic!("zero-way L1-D should be rejected"),
+        Err(e) => assert_eq!(
+            e,
+            ConfigError::Invalid(vec!["cache.l1_d: ways must be at least 1".to_string()])
+        ),
+    }
+}
+
+/// Disabled caches are never indexed, so their geometry is not checked.
+#[test]
+fn disabled_cache_skips_validation() {
+    let config = CacheConfig {
+        enabled: false,
+        size_bytes: 0,
+        line_bytes: 0,
+        ways: 0,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).expect("disabled cache builds");
+    assert_eq!(cache.access(0x1000, false, NEXT_LEVEL_LATENCY), (false, 0));
+}
+
+/// Valid geometries report their set count.
+#[test]
+fn num_sets_for_valid_geometry() {
+    assert_eq!(CacheSim::num_sets(&test_config()), Ok(2));
+}
+
+// ══════════════════════════════════════════════════════════
+// 11. Watched-Line Eviction
+// ══════════════════════════════════════════════════════════
+
+/// Evicting the watched line (any offset within it) is reported exactly once.
+#[test]
+fn watched_line_eviction_is_reported_once() {
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
+    cache.access(0x08, false, NEXT_LEVEL_LATENCY);
+    cache.watch_line(Some(0x08));
+
+    // Set 0 conflicts: 128 fills the second way, 256 evicts the LRU line 0.
+    cache.access(128, false, NEXT_LEVEL_LATENCY);
+    assert!(
+        !cache.take_watched_eviction(),
+        "watched line still resident"
+    );
+    cache.access(256, false, NEXT_LEVEL_LATENCY);
+
+    assert!(cache.take_watched_eviction());
+    assert!(
+        !cache.take_watched_eviction(),
+        "report is cleared once taken"
+    );
+}
+
+/// Evicting other lines, or flushing with nothing watched, reports nothing.
+#[test]
+fn unwatched_evictions_are_ignored() {
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
+    cache.watch_line(Some(64)); // set 1, never touched
+    for addr in [0, 128, 256] {
+        cache.access(addr, false, NEXT_LEVEL_LATENCY);
+    }
+    assert!(!cache.take_watched_eviction());
+
+    cache.watch_line(None);
+    cache.flush();
+    assert!(!cache.take_watched_eviction());
+}
+
+// ══════════════════════════════════════════════════════════
+// 12. Write Policies
+// ══════════════════════════════════════════════════════════
+
+/// A write-through store pays the next-level latency on a miss and on a hit,
+/// and never leaves a dirty line to write back.
+#[test]
+fn write_through_store_always_propagates_latency() {
+    let config = CacheConfig {
+        write_policy: WritePolicy::WriteThrough,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+
+    assert_eq!(
+        cache.access(0, true, NEXT_LEVEL_LATENCY),
+        (false, NEXT_LEVEL_LATENCY)
+    );
+    assert_eq!(
+        cache.access(0, true, NEXT_LEVEL_LATENCY),
+        (true, NEXT_LEVEL_LATENCY)
+    );
+    assert_eq!(
+        cache.access(0, false, NEXT_LEVEL_LATENCY),
+        (true, 0),
+        "loads are not charged"
+    );
+
+    // Evicting the stored line costs no write-back.
+    cache.access(128, false, NEXT_LEVEL_LATENCY);
+    let (hit, penalty) = cache.access(256, false, NEXT_LEVEL_LATENCY);
+    assert!(!hit);
+    assert_eq!(penalty, 0, "write-through lines are never dirty");
+}
+
+/// A no-write-allocate store miss does not populate the set; a later load
+/// still misses and allocates normally.
+#[test]
+fn no_write_allocate_store_miss_does_not_populate() {
+    let config = CacheConfig {
+        alloc_policy: AllocPolicy::NoWriteAllocate,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+
+    assert_eq!(
+        cache.access(0, true, NEXT_LEVEL_LATENCY),
+        (false, NEXT_LEVEL_LATENCY),
+        "store miss is performed at the next level"
+    );
+    assert!(!cache.contains(0), "store miss must not allocate");
+
+    assert!(!cache.access(0, false, NEXT_LEVEL_LATENCY).0);
+    assert!(cache.contains(0), "load miss allocates");
+    assert_eq!(
+        cache.access(0, true, NEXT_LEVEL_LATENCY),
+        (true, 0),
+        "write-back store hit updates the line in place"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 13. Seeded Random Replacement
+// ══════════════════════════════════════════════════════════
+
+/// Replays a cyclic sweep over five lines of a single 4-way set and returns
+/// the hit/miss sequence.
+fn sweep_pattern(policy: PolicyType, rng_seed: u64) -> Vec<bool> {
+    let config = CacheConfig {
+        ways: 4,
+        policy,
+        rng_seed,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+    (0..100)
+        .map(|i| cache.access((i % 5) * 64, false, NEXT_LEVEL_LATENCY).0)
+        .collect()
+}
+
+/// The same seed reproduces the same evictions; LRU thrashes on the sweep
+/// while random replacement keeps some lines.
+#[test]
+fn random_replacement_is_deterministic_per_seed() {
+    let first = sweep_pattern(PolicyType::Random, 42);
+    assert_eq!(first, sweep_pattern(PolicyType::Random, 42));
+
+    let lru = sweep_pattern(PolicyType::Lru, 42);
+    assert!(lru.iter().all(|&hit| !hit), "LRU misses every access");
+    assert!(first.iter().any(|&hit| hit), "random keeps some lines");
+    assert_ne!(first, lru);
+}
+
+/// Empty ways are filled before the policy picks a victim, so a set is never
+/// evicted from until it is full.
+#[test]
+fn random_replacement_fills_empty_ways_first() {
+    let config = CacheConfig {
+        ways: 4,
+        policy: PolicyType::Random,
+        rng_seed: 7,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+    for line in 0..4 {
+        cache.access(line * 64, false, NEXT_LEVEL_LATENCY);
+    }
+    assert!((0..4).all(|line| cache.contains(line * 64)));
+}
+
+// ══════════════════════════════════════════════════════════
+// 14. Invalidation
+// ══════════════════════════════════════════════════════════
+
+/// Invalidating drops only the addressed line and reports whether it was present.
+#[test]
+fn invalidate_drops_only_matching_line() {
+    let mut cache = CacheSim::try_new(&test_config()).unwrap();
+    cache.access(0, false, NEXT_LEVEL_LATENCY);
+    cache.access(128, false, NEXT_LEVEL_LATENCY);
+
+    assert!(cache.invalidate(8), "any offset in the line matches");
+    assert!(!cache.contains(0));
+    assert!(cache.contains(128));
+    assert!(!cache.invalidate(0), "already invalid");
+}
+
+// ══════════════════════════════════════════════════════════
+// 15. Victim Cache
+// ══════════════════════════════════════════════════════════
+
+/// Cycles three lines through one set of the 2-way test cache and returns the
+/// number of plain misses and victim hits.
+fn conflict_stream(victim_entries: usize) -> (usize, usize) {
+    let config = CacheConfig {
+        victim_entries,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+    let (mut misses, mut victim_hits) = (0, 0);
+    for i in 0..30 {
+        let (hit, _) = cache.access((i % 3) * 128, false, NEXT_LEVEL_LATENCY);
+        if cache.take_victim_hit() {
+            victim_hits += 1;
+        } else if !hit {
+            misses += 1;
+        }
+    }
+    (misses, victim_hits)
+}
+
+/// A conflict stream that thrashes a 2-way set misses every time without a
+/// victim cache; with one, only the three cold misses reach the next level.
+#[test]
+fn victim_cache_converts_conflict_misses() {
+    assert_eq!(conflict_stream(0), (30, 0));
+    assert_eq!(conflict_stream(2), (3, 27));
+}
+
+/// A dirty line keeps its dirty state through the victim cache and is only
+/// written back once it leaves the buffer.
+#[test]
+fn victim_cache_defers_dirty_write_back() {
+    let config = CacheConfig {
+        victim_entries: 1,
+        ..test_config()
+    };
+    let mut cache = CacheSim::try_new(&config).unwrap();
+    cache.access(0, true, NEXT_LEVEL_LATENCY);
+    cache.access(128, false, NEXT_LEVEL_LATENCY);
+
+    let (_, penalty) = cache.access(256, false, NEXT_LEVEL_LATENCY);
+    assert_eq!(penalty, 0, "dirty line 0 moves to the victim cache");
+
+    let (_, penalty) = cache.access(384, false, NEXT_LEVEL_LATENCY);
+    assert_eq!(
+        penalty, NEXT_LEVEL_LATENCY,
+        "line 128 displaces dirty line 0 from the buffer"
+    );
+    assert!(!cache.take_victim_hit());
+}
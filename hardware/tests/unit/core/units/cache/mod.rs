@@ -1,2 +1,5 @@
 pub mod cache_sim;
+pub mod mshr;
 pub mod policies;
+pub mod ports;
+pub mod snoop;
@@ -0,0 +1,143 @@
+//! MSHR (Non-Blocking Cache) Unit Tests.
+//!
+//! Verifies the MshrFile pool (allocation, merging, back-pressure, scoreboard)
+//! and that independent L1-D load misses overlap in the pipeline.
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use crate::common::mocks::memory::MockMemoryController;
+use riscv_core::config::Config;
+use riscv_core::core::units::cache::mshr::{MshrFile, MshrGrant};
+
+// ══════════════════════════════════════════════════════════
+// 1. MshrFile
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn mshr_hits_need_no_entry() {
+    let mut m = MshrFile::new(2);
+    assert!(m.is_enabled());
+    assert_eq!(m.request(0x1000, 0, 0), None);
+    assert!(!MshrFile::new(0).is_enabled());
+}
+
+#[test]
+fn mshr_distinct_misses_overlap() {
+    let mut m = MshrFile::new(2);
+    let first = m.request(0x1000, 10, 50).unwrap();
+    let second = m.request(0x2000, 11, 50).unwrap();
+
+    assert_eq!((first.wait, first.ready, first.overlapped), (0, 60, false));
+    assert_eq!(
+        (second.wait, second.ready, second.overlapped),
+        (0, 61, true)
+    );
+    assert_eq!(m.outstanding(11), 2);
+    assert_eq!(m.outstanding(60), 1);
+}
+
+#[test]
+fn mshr_same_line_merges() {
+    let mut m = MshrFile::new(1);
+    m.request(0x1000, 0, 40);
+    let merged = m.request(0x1000, 5, 0).unwrap();
+    assert_eq!(
+        merged,
+        MshrGrant {
+            wait: 0,
+            ready: 40,
+            merged: true,
+            overlapped: true,
+        }
+    );
+    assert_eq!(m.request(0x1000, 40, 0), None, "line has arrived");
+}
+
+#[test]
+fn mshr_full_pool_waits_for_earliest() {
+    let mut m = MshrFile::new(2);
+    m.request(0x1000, 0, 30);
+    m.request(0x2000, 0, 20);
+    let third = m.request(0x3000, 5, 20).unwrap();
+    assert_eq!(third.wait, 15, "waits for the miss completing at 20");
+    assert_eq!(third.ready, 40);
+}
+
+#[test]
+fn mshr_scoreboard_tracks_destination_registers() {
+    let mut m = MshrFile::new(1);
+    m.mark_pending(5, false, 30);
+    assert_eq!(m.operand_wait(5, false, 10), 20);
+    assert_eq!(m.operand_wait(5, true, 10), 0, "f5 is a different register");
+    assert_eq!(m.operand_wait(6, false, 10), 0);
+    assert_eq!(m.operand_wait(5, false, 30), 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Overlapped misses in the pipeline
+// ══════════════════════════════════════════════════════════
+
+const CODE_BASE: u64 = 0x1000;
+const DATA_BASE: u64 = 0x8000_0000;
+const RAM_LATENCY: u64 = 50;
+
+/// Runs two independent loads from distinct lines followed by their sum, and
+/// returns the cycles until the sum is written.
+fn two_miss_cycles(mshrs: usize) -> (u64, TestContext) {
+    let mut config = Config::default();
+    config.cache.l1_d.enabled = true;
+    config.cache.l1_d.size_bytes = 4096;
+    config.cache.l1_d.ways = 2;
+    config.cache.l1_d.mshrs = mshrs;
+
+    let program = [
+        InstructionBuilder::new().ld(1, 10, 0).build(),
+        InstructionBuilder::new().ld(2, 10, 0x400).build(),
+        InstructionBuilder::new().add(3, 1, 2).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, CODE_BASE)
+        .with_memory(0x1000, DATA_BASE)
+        .load_program(CODE_BASE, &program);
+    tc.cpu.bus.mem_controller = Box::new(MockMemoryController::new(RAM_LATENCY));
+    // Only the data region takes the cached path.
+    tc.cpu.mmio_base = DATA_BASE;
+    tc.cpu.bus.bus.write_u64(DATA_BASE, 7);
+    tc.cpu.bus.bus.write_u64(DATA_BASE + 0x400, 35);
+    tc.set_reg(10, DATA_BASE);
+
+    let mut cycles = 0;
+    while tc.get_reg(3) != 42 {
+        assert!(cycles < 1000, "sum never written");
+        tc.cpu.tick().unwrap();
+        cycles += 1;
+    }
+    (cycles, tc)
+}
+
+#[test]
+fn independent_load_misses_overlap() {
+    let (serial, blocking) = two_miss_cycles(0);
+    let (overlapped, tc) = two_miss_cycles(4);
+
+    assert_eq!(blocking.cpu.stats.mshr_overlapped_misses, 0);
+    assert_eq!(tc.cpu.stats.dcache_misses, 2);
+    assert_eq!(tc.cpu.stats.mshr_overlapped_misses, 1);
+    assert!(
+        overlapped + RAM_LATENCY / 2 < serial,
+        "overlapped {overlapped} cycles vs serial {serial}"
+    );
+}
+
+#[test]
+fn single_mshr_serializes_misses() {
+    let (serial, _) = two_miss_cycles(0);
+    let (one, tc) = two_miss_cycles(1);
+
+    assert!(tc.cpu.stats.stalls_mshr_full > 0);
+    assert!(
+        one + 5 >= serial,
+        "one MSHR {one} cycles vs serial {serial}"
+    );
+}
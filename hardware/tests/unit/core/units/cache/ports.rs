@@ -0,0 +1,88 @@
+//! Cache Access Port Unit Tests.
+//!
+//! Verifies the CachePorts arbiter (occupancy, queueing, disabled model)
+//! and that L3 port contention is charged and counted by the CPU.
+
+use crate::common::harness::TestContext;
+use riscv_core::common::{AccessType, PhysAddr};
+use riscv_core::config::Config;
+use riscv_core::core::units::cache::ports::CachePorts;
+
+// ══════════════════════════════════════════════════════════
+// 1. CachePorts
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn ports_disabled_never_stall() {
+    let mut p = CachePorts::new(0, 4);
+    assert!(!p.is_enabled());
+    for _ in 0..8 {
+        assert_eq!(p.request(0), 0);
+    }
+}
+
+#[test]
+fn ports_accept_up_to_port_count_concurrently() {
+    let mut p = CachePorts::new(2, 4);
+    assert_eq!(p.request(10), 0);
+    assert_eq!(p.request(10), 0);
+    assert_eq!(p.request(10), 4, "third access waits for the first port");
+    assert_eq!(p.request(10), 4, "fourth access takes the second port");
+    assert_eq!(p.request(10), 8);
+}
+
+#[test]
+fn ports_free_up_over_time() {
+    let mut p = CachePorts::new(1, 4);
+    assert_eq!(p.request(0), 0); // busy until cycle 4
+    assert_eq!(p.request(1), 3);
+    assert_eq!(p.request(100), 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. L3 port contention in the hierarchy
+// ══════════════════════════════════════════════════════════
+
+fn l3_only_config(ports: usize, cycles: u64) -> Config {
+    let mut config = Config::default();
+    config.cache.l3.enabled = true;
+    config.cache.l3.size_bytes = 65536;
+    config.cache.l3.ways = 4;
+    config.cache.l3_ports = ports;
+    config.cache.l3_port_cycles = cycles;
+    config
+}
+
+#[test]
+fn l3_oversubscribed_ports_charge_contention_stall() {
+    const ACCESSES: u64 = 6;
+    let line = |i: u64| PhysAddr::new(0x8000_0000 + i * 0x1000);
+
+    let mut tc = TestContext::from_config(&l3_only_config(0, 4));
+    let unlimited: u64 = (0..ACCESSES)
+        .map(|i| tc.cpu.simulate_memory_access(line(i), AccessType::Read))
+        .sum();
+    assert_eq!(tc.cpu.stats.l3_port_stalls, 0);
+
+    let mut tc = TestContext::from_config(&l3_only_config(2, 4));
+    let limited: u64 = (0..ACCESSES)
+        .map(|i| tc.cpu.simulate_memory_access(line(i), AccessType::Read))
+        .sum();
+
+    // Two ports, all accesses in the same cycle: pairs wait 0, 4, 8 cycles.
+    assert_eq!(tc.cpu.stats.l3_port_stalls, ACCESSES - 2);
+    assert_eq!(tc.cpu.stats.l3_port_stall_cycles, 4 + 4 + 8 + 8);
+    assert_eq!(limited, unlimited + tc.cpu.stats.l3_port_stall_cycles);
+}
+
+#[test]
+fn l3_ports_from_config_default_to_unlimited() {
+    let config: Config = serde_json::from_str(
+        r#"{"general": {}, "system": {}, "memory": {}, "pipeline": {},
+            "cache": {"l1_i": {}, "l1_d": {}, "l2": {}, "l3": {}, "l3_ports": 2}}"#,
+    )
+    .unwrap();
+    assert_eq!(config.cache.l3_ports, 2);
+    assert_eq!(config.cache.l3_port_cycles, 1);
+    assert_eq!(Config::default().cache.l3_ports, 0);
+}
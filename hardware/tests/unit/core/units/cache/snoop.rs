@@ -0,0 +1,66 @@
+//! I-Cache Snoop Unit Tests.
+//!
+//! Verifies that data stores invalidate matching L1-I lines when
+//! `icache_snoop` is enabled, and leave them alone otherwise.
+
+use crate::common::harness::TestContext;
+use riscv_core::common::{AccessType, PhysAddr};
+use riscv_core::config::Config;
+
+const CODE: u64 = 0x8000_0000;
+
+fn snoop_config(icache_snoop: bool) -> Config {
+    let mut config = Config::default();
+    config.cache.l1_i.enabled = true;
+    config.cache.l1_i.size_bytes = 4096;
+    config.cache.l1_d.enabled = true;
+    config.cache.l1_d.size_bytes = 4096;
+    config.cache.icache_snoop = icache_snoop;
+    config
+}
+
+#[test]
+fn store_to_cached_code_invalidates_icache_line() {
+    let mut tc = TestContext::from_config(&snoop_config(true));
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Fetch);
+    assert!(tc.cpu.l1_i_cache.contains(CODE));
+
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE + 4), AccessType::Write);
+
+    assert!(!tc.cpu.l1_i_cache.contains(CODE));
+    assert_eq!(tc.cpu.stats.icache_snoop_invalidations, 1);
+
+    // The next fetch of the modified line misses.
+    let misses = tc.cpu.stats.icache_misses;
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Fetch);
+    assert_eq!(tc.cpu.stats.icache_misses, misses + 1);
+}
+
+#[test]
+fn stores_to_other_lines_and_loads_do_not_snoop() {
+    let mut tc = TestContext::from_config(&snoop_config(true));
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Fetch);
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Read);
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE + 0x40), AccessType::Write);
+
+    assert!(tc.cpu.l1_i_cache.contains(CODE));
+    assert_eq!(tc.cpu.stats.icache_snoop_invalidations, 0);
+}
+
+#[test]
+fn snoop_disabled_keeps_stale_icache_line() {
+    let mut tc = TestContext::from_config(&snoop_config(false));
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Fetch);
+    tc.cpu
+        .simulate_memory_access(PhysAddr::new(CODE), AccessType::Write);
+
+    assert!(tc.cpu.l1_i_cache.contains(CODE));
+    assert_eq!(tc.cpu.stats.icache_snoop_invalidations, 0);
+}
@@ -0,0 +1,60 @@
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::fpu::Fpu;
+
+/// FCLASS.S on a NaN-boxed f32 bit pattern.
+fn fclass_s(bits: u32) -> u64 {
+    Fpu::execute(
+        AluOp::FClass,
+        Fpu::box_f32(f32::from_bits(bits)),
+        0,
+        0,
+        true,
+    )
+}
+
+/// FCLASS.D on an f64 bit pattern.
+fn fclass_d(bits: u64) -> u64 {
+    Fpu::execute(AluOp::FClass, bits, 0, 0, false)
+}
+
+#[test]
+fn test_fclass_s_all_classes() {
+    assert_eq!(fclass_s(f32::NEG_INFINITY.to_bits()), 1 << 0);
+    assert_eq!(fclass_s((-1.5f32).to_bits()), 1 << 1);
+    assert_eq!(fclass_s(0x8000_0001), 1 << 2, "negative subnormal");
+    assert_eq!(fclass_s((-0.0f32).to_bits()), 1 << 3);
+    assert_eq!(fclass_s(0.0f32.to_bits()), 1 << 4);
+    assert_eq!(fclass_s(0x0000_0001), 1 << 5, "positive subnormal");
+    assert_eq!(fclass_s(1.5f32.to_bits()), 1 << 6);
+    assert_eq!(fclass_s(f32::INFINITY.to_bits()), 1 << 7);
+    assert_eq!(fclass_s(0x7f80_0001), 1 << 8, "signaling NaN");
+    assert_eq!(fclass_s(0x7fc0_0000), 1 << 9, "quiet NaN");
+}
+
+#[test]
+fn test_fclass_d_all_classes() {
+    assert_eq!(fclass_d(f64::NEG_INFINITY.to_bits()), 1 << 0);
+    assert_eq!(fclass_d((-1.5f64).to_bits()), 1 << 1);
+    assert_eq!(fclass_d(0x8000_0000_0000_0001), 1 << 2);
+    assert_eq!(fclass_d((-0.0f64).to_bits()), 1 << 3);
+    assert_eq!(fclass_d(0.0f64.to_bits()), 1 << 4);
+    assert_eq!(fclass_d(0x0000_0000_0000_0001), 1 << 5);
+    assert_eq!(fclass_d(1.5f64.to_bits()), 1 << 6);
+    assert_eq!(fclass_d(f64::INFINITY.to_bits()), 1 << 7);
+    assert_eq!(fclass_d(0x7ff0_0000_0000_0001), 1 << 8, "signaling NaN");
+    assert_eq!(fclass_d(0x7ff8_0000_0000_0000), 1 << 9, "quiet NaN");
+}
+
+#[test]
+fn test_fclass_s_improperly_boxed_is_quiet_nan() {
+    // Upper bits not all ones: the operand reads as the canonical NaN.
+    let raw = 1.0f32.to_bits() as u64;
+    assert_eq!(Fpu::execute(AluOp::FClass, raw, 0, 0, true), 1 << 9);
+}
+
+#[test]
+fn test_fclass_raises_no_flags() {
+    let snan = Fpu::box_f32(f32::from_bits(0x7f80_0001));
+    let (_, flags) = Fpu::execute_full(AluOp::FClass, snan, 0, 0, true);
+    assert!(flags.is_empty());
+}
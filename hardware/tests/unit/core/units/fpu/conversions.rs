@@ -0,0 +1,157 @@
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::fpu::Fpu;
+use riscv_core::core::units::fpu::exception_flags::FpFlags;
+use riscv_core::core::units::fpu::rounding_modes::RoundingMode;
+
+/// Converts the f64 `x` with `op` under RTZ, returning `(result, flags)`.
+fn cvt_d(op: AluOp, x: f64) -> (u64, FpFlags) {
+    Fpu::execute_rm(op, x.to_bits(), 0, 0, false, RoundingMode::Rtz)
+}
+
+#[test]
+fn test_fcvt_wu_negative_saturates_to_zero() {
+    assert_eq!(cvt_d(AluOp::FCvtWuS, -1.0), (0, FpFlags::NV));
+    assert_eq!(cvt_d(AluOp::FCvtLuS, -1.0), (0, FpFlags::NV));
+
+    let (r, flags) = Fpu::execute_rm(
+        AluOp::FCvtWuS,
+        Fpu::box_f32(-1.0),
+        0,
+        0,
+        true,
+        RoundingMode::Rtz,
+    );
+    assert_eq!((r, flags), (0, FpFlags::NV));
+}
+
+#[test]
+fn test_fcvt_wu_small_negative_rounds_to_zero_inexact() {
+    // -0.5 truncates to 0, which is representable: inexact, not invalid.
+    assert_eq!(cvt_d(AluOp::FCvtWuS, -0.5), (0, FpFlags::NX));
+}
+
+#[test]
+fn test_fcvt_wu_result_is_sign_extended() {
+    let (r, flags) = cvt_d(AluOp::FCvtWuS, 3_000_000_000.0);
+    assert_eq!(r, 3_000_000_000u32 as i32 as i64 as u64);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn test_fcvt_unsigned_overflow_saturates() {
+    assert_eq!(
+        cvt_d(AluOp::FCvtWuS, 4_294_967_296.0),
+        (u64::MAX, FpFlags::NV)
+    );
+    assert_eq!(cvt_d(AluOp::FCvtLuS, 1.0e20), (u64::MAX, FpFlags::NV));
+    assert_eq!(cvt_d(AluOp::FCvtLuS, f64::NAN), (u64::MAX, FpFlags::NV));
+}
+
+#[test]
+fn test_fcvt_lu_above_i64_range() {
+    let x = 2f64.powi(63) + 2048.0;
+    assert_eq!(cvt_d(AluOp::FCvtLuS, x), (x as u64, FpFlags::NONE));
+}
+
+#[test]
+fn test_fcvt_d_lu_large_value() {
+    let v = 0xFFFF_FFFF_FFFF_F800u64;
+    let r = Fpu::execute(AluOp::FCvtSLu, v, 0, 0, false);
+    assert_eq!(f64::from_bits(r), 18_446_744_073_709_549_568.0);
+}
+
+#[test]
+fn test_fcvt_d_wu_ignores_upper_bits() {
+    let r = Fpu::execute(AluOp::FCvtSWu, 0xFFFF_FFFF_FFFF_FFFF, 0, 0, false);
+    assert_eq!(f64::from_bits(r), 4_294_967_295.0);
+}
+
+/// Narrows the f64 `x` with FCVT.S.D under `rm`, returning `(result, flags)`.
+fn cvt_s_d(x: f64, rm: RoundingMode) -> (u64, FpFlags) {
+    Fpu::execute_rm(AluOp::FCvtSD, x.to_bits(), 0, 0, true, rm)
+}
+
+#[test]
+fn test_fcvt_s_d_rounds_with_rm() {
+    let x = -(1.0 + 2f64.powi(-30));
+    let down = Fpu::box_f32(-(1.0 + f32::EPSILON));
+    assert_eq!(
+        cvt_s_d(x, RoundingMode::Rne),
+        (Fpu::box_f32(-1.0), FpFlags::NX)
+    );
+    assert_eq!(
+        cvt_s_d(x, RoundingMode::Rtz),
+        (Fpu::box_f32(-1.0), FpFlags::NX)
+    );
+    assert_eq!(cvt_s_d(x, RoundingMode::Rdn), (down, FpFlags::NX));
+    assert_eq!(
+        cvt_s_d(x, RoundingMode::Rup),
+        (Fpu::box_f32(-1.0), FpFlags::NX)
+    );
+    assert_eq!(
+        cvt_s_d(1.5, RoundingMode::Rup),
+        (Fpu::box_f32(1.5), FpFlags::NONE)
+    );
+}
+
+#[test]
+fn test_fcvt_s_d_overflow_and_underflow() {
+    let of = FpFlags::OF | FpFlags::NX;
+    assert_eq!(
+        cvt_s_d(1e39, RoundingMode::Rne),
+        (Fpu::box_f32(f32::INFINITY), of)
+    );
+    assert_eq!(
+        cvt_s_d(1e39, RoundingMode::Rtz),
+        (Fpu::box_f32(f32::MAX), of)
+    );
+    // Just above the largest single: only rounding up reaches infinity.
+    let x = f32::MAX as f64 * (1.0 + 2f64.powi(-30));
+    assert_eq!(
+        cvt_s_d(x, RoundingMode::Rne),
+        (Fpu::box_f32(f32::MAX), FpFlags::NX)
+    );
+    assert_eq!(
+        cvt_s_d(x, RoundingMode::Rup),
+        (Fpu::box_f32(f32::INFINITY), of)
+    );
+
+    let uf = FpFlags::UF | FpFlags::NX;
+    assert_eq!(cvt_s_d(1e-50, RoundingMode::Rne), (Fpu::box_f32(0.0), uf));
+    let min = Fpu::box_f32(f32::from_bits(1));
+    assert_eq!(cvt_s_d(1e-50, RoundingMode::Rup), (min, uf));
+}
+
+#[test]
+fn test_fcvt_precision_signaling_nan_raises_nv() {
+    let snan_d = f64::from_bits(0x7ff0_0000_0000_0001);
+    let qnan_s = Fpu::box_f32(f32::from_bits(0x7fc0_0000));
+    assert_eq!(cvt_s_d(snan_d, RoundingMode::Rne), (qnan_s, FpFlags::NV));
+    assert_eq!(
+        cvt_s_d(f64::NAN, RoundingMode::Rne),
+        (qnan_s, FpFlags::NONE)
+    );
+
+    let snan_s = Fpu::box_f32(f32::from_bits(0x7f80_0001));
+    let (r, flags) = Fpu::execute_rm(AluOp::FCvtDS, snan_s, 0, 0, false, RoundingMode::Rne);
+    assert_eq!((r, flags), (0x7ff8_0000_0000_0000, FpFlags::NV));
+}
+
+#[test]
+fn test_fcvt_precision_flush_to_zero() {
+    // A double that narrows to a single subnormal is flushed and underflows.
+    let (r, flags) = Fpu::execute_ftz(
+        AluOp::FCvtSD,
+        1e-40f64.to_bits(),
+        0,
+        0,
+        true,
+        RoundingMode::Rne,
+    );
+    assert_eq!((r, flags), (Fpu::box_f32(0.0), FpFlags::UF | FpFlags::NX));
+
+    // A subnormal single source reads as zero.
+    let sub = Fpu::box_f32(-f32::from_bits(1));
+    let (r, flags) = Fpu::execute_ftz(AluOp::FCvtDS, sub, 0, 0, false, RoundingMode::Rne);
+    assert_eq!((r, flags), ((-0.0f64).to_bits(), FpFlags::NONE));
+}
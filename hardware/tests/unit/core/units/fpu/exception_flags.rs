@@ -1,7 +1,7 @@
 //! RISC-V Exception Flag tests.
 //!
 //! These tests verify that `Fpu::execute_full()` returns both the
-//! result and the correct accrued exception flags (NV, DZ, OF, NX).
+//! result and the correct accrued exception flags (NV, DZ, OF, UF, NX).
 
 use riscv_core::core::pipeline::signals::AluOp;
 use riscv_core::core::units::fpu::Fpu;
@@ -128,3 +128,95 @@ fn test_fpflags_bits() {
     assert_eq!(FpFlags::NX.bits(), 0b00001);
     assert_eq!(FpFlags::NONE.bits(), 0);
 }
+
+#[test]
+fn test_exception_nx_on_rounded_division() {
+    let (_, flags) = Fpu::execute_full(AluOp::FDiv, 1.0f64.to_bits(), 3.0f64.to_bits(), 0, false);
+    assert_eq!(flags, FpFlags::NX, "1/3 is rounded");
+
+    let (_, flags) = Fpu::execute_full(AluOp::FDiv, 1.0f64.to_bits(), 4.0f64.to_bits(), 0, false);
+    assert!(flags.is_empty(), "1/4 is exact");
+}
+
+#[test]
+fn test_exception_nx_on_rounded_add_f32() {
+    let big = Fpu::box_f32(16_777_216.0); // 2^24
+    let one = Fpu::box_f32(1.0);
+    let (_, flags) = Fpu::execute_full(AluOp::FAdd, big, one, 0, true);
+    assert_eq!(flags, FpFlags::NX, "2^24 + 1 is not representable in f32");
+}
+
+#[test]
+fn test_exception_uf_on_tiny_inexact_result() {
+    let tiny = f64::MIN_POSITIVE.to_bits();
+    let third = (1.0f64 / 3.0).to_bits();
+    let (_, flags) = Fpu::execute_full(AluOp::FMul, tiny, third, 0, false);
+    assert!(flags.contains(FpFlags::UF));
+    assert!(flags.contains(FpFlags::NX));
+}
+
+#[test]
+fn test_exception_nv_on_ordered_compare_with_qnan() {
+    let nan = Fpu::box_f32(f32::NAN);
+    let one = Fpu::box_f32(1.0);
+    let (_, flags) = Fpu::execute_full(AluOp::FLt, nan, one, 0, true);
+    assert_eq!(flags, FpFlags::NV, "FLT is a signaling comparison");
+    let (_, flags) = Fpu::execute_full(AluOp::FEq, nan, one, 0, true);
+    assert!(flags.is_empty(), "FEQ is quiet for qNaN");
+}
+
+#[test]
+fn test_sign_injection_raises_no_flags() {
+    let snan = Fpu::box_f32(f32::from_bits(0x7F80_0001));
+    let one = Fpu::box_f32(1.0);
+    let (_, flags) = Fpu::execute_full(AluOp::FSgnJ, snan, one, 0, true);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn test_exception_nx_on_rounded_fma_f32() {
+    let x = Fpu::box_f32(1.0 + f32::EPSILON);
+    let (_, flags) = Fpu::execute_full(AluOp::FMAdd, x, x, Fpu::box_f32(0.0), true);
+    assert_eq!(
+        flags,
+        FpFlags::NX,
+        "(1 + 2^-23)^2 needs 47 significand bits"
+    );
+
+    let (two, three, one) = (Fpu::box_f32(2.0), Fpu::box_f32(3.0), Fpu::box_f32(1.0));
+    for op in [AluOp::FMAdd, AluOp::FMSub, AluOp::FNMAdd, AluOp::FNMSub] {
+        let (_, flags) = Fpu::execute_full(op, two, three, one, true);
+        assert!(flags.is_empty(), "{op:?} of 2, 3, 1 is exact");
+    }
+}
+
+#[test]
+fn test_exception_nx_on_rounded_fma_f64() {
+    let x = (1.0 + f64::EPSILON).to_bits();
+    let (_, flags) = Fpu::execute_full(AluOp::FMAdd, x, x, (-1.0f64).to_bits(), false);
+    assert_eq!(
+        flags,
+        FpFlags::NX,
+        "2^-51 + 2^-104 needs 54 significand bits"
+    );
+
+    // The residual 2^-104 is exact even though the rounded product cancels to zero.
+    let c = (1.0 + 2.0 * f64::EPSILON).to_bits();
+    let (result, flags) = Fpu::execute_full(AluOp::FMSub, x, x, c, false);
+    assert_eq!(f64::from_bits(result), 2f64.powi(-104));
+    assert!(flags.is_empty(), "single-rounding result is exact");
+}
+
+#[test]
+fn test_exception_nx_on_rounded_int_to_float() {
+    let (_, flags) = Fpu::execute_full(AluOp::FCvtSW, 16_777_217, 0, 0, true);
+    assert_eq!(flags, FpFlags::NX, "2^24 + 1 is not representable in f32");
+    let (result, flags) = Fpu::execute_full(AluOp::FCvtSW, 16_777_216, 0, 0, true);
+    assert_eq!(result, Fpu::box_f32(16_777_216.0));
+    assert!(flags.is_empty());
+
+    let (_, flags) = Fpu::execute_full(AluOp::FCvtSL, (1u64 << 53) + 1, 0, 0, false);
+    assert_eq!(flags, FpFlags::NX, "2^53 + 1 is not representable in f64");
+    let (_, flags) = Fpu::execute_full(AluOp::FCvtSL, (-1i64) as u64, 0, 0, false);
+    assert!(flags.is_empty());
+}
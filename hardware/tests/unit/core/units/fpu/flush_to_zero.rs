@@ -0,0 +1,77 @@
+//! Flush-to-zero (FTZ/DAZ) tests.
+//!
+//! These tests verify that `Fpu::execute_ftz()` flushes subnormal results to
+//! zero with UF raised and reads subnormal inputs as zero, while the default
+//! `Fpu::execute_full()` path keeps full IEEE 754 subnormal support.
+
+use riscv_core::core::pipeline::signals::AluOp;
+use riscv_core::core::units::fpu::Fpu;
+use riscv_core::core::units::fpu::exception_flags::FpFlags;
+use riscv_core::core::units::fpu::rounding_modes::RoundingMode;
+
+#[test]
+fn test_ftz_off_preserves_subnormal_result_f32() {
+    // MIN_POSITIVE / 4 is exactly representable as a subnormal.
+    let a = Fpu::box_f32(f32::MIN_POSITIVE);
+    let b = Fpu::box_f32(0.25);
+    let (result, _flags) = Fpu::execute_full(AluOp::FMul, a, b, 0, true);
+
+    let res = f32::from_bits(result as u32);
+    assert!(res.is_subnormal(), "IEEE mode keeps the subnormal result");
+    assert_eq!(res, f32::MIN_POSITIVE / 4.0);
+}
+
+#[test]
+fn test_ftz_on_flushes_subnormal_result_f32() {
+    let a = Fpu::box_f32(f32::MIN_POSITIVE);
+    let b = Fpu::box_f32(0.25);
+    let (result, flags) = Fpu::execute_ftz(AluOp::FMul, a, b, 0, true, RoundingMode::Rne);
+
+    assert_eq!(result, Fpu::box_f32(0.0), "flushed to +0, NaN-boxed");
+    assert!(flags.contains(FpFlags::UF), "UF must be set when flushing");
+    assert!(flags.contains(FpFlags::NX), "NX must accompany UF");
+}
+
+#[test]
+fn test_ftz_on_flushes_subnormal_result_f64_keeps_sign() {
+    let a = f64::MIN_POSITIVE.to_bits();
+    let b = (-0.5f64).to_bits();
+
+    let (ieee, _) = Fpu::execute_full(AluOp::FMul, a, b, 0, false);
+    assert!(f64::from_bits(ieee).is_subnormal());
+
+    let (result, flags) = Fpu::execute_ftz(AluOp::FMul, a, b, 0, false, RoundingMode::Rne);
+    assert_eq!(result, (-0.0f64).to_bits(), "flushed to -0");
+    assert!(flags.contains(FpFlags::UF));
+}
+
+#[test]
+fn test_ftz_treats_subnormal_inputs_as_zero() {
+    let tiny = Fpu::box_f32(f32::MIN_POSITIVE / 2.0);
+    let one = Fpu::box_f32(1.0);
+
+    let (ieee, _) = Fpu::execute_full(AluOp::FEq, tiny, Fpu::box_f32(0.0), 0, true);
+    assert_eq!(ieee, 0, "IEEE mode: subnormal input is not zero");
+
+    let (eq, _) = Fpu::execute_ftz(
+        AluOp::FEq,
+        tiny,
+        Fpu::box_f32(0.0),
+        0,
+        true,
+        RoundingMode::Rne,
+    );
+    assert_eq!(eq, 1, "DAZ: subnormal input equals zero");
+
+    let (sum, flags) = Fpu::execute_ftz(AluOp::FAdd, tiny, one, 0, true, RoundingMode::Rne);
+    assert_eq!(sum, one);
+    assert!(!flags.contains(FpFlags::UF), "normal result raises no UF");
+}
+
+#[test]
+fn test_ftz_leaves_sign_injection_bits_untouched() {
+    let tiny = Fpu::box_f32(f32::MIN_POSITIVE / 2.0);
+    let (result, flags) = Fpu::execute_ftz(AluOp::FSgnJ, tiny, tiny, 0, true, RoundingMode::Rne);
+    assert_eq!(result, tiny);
+    assert!(flags.is_empty());
+}
@@ -1,3 +1,6 @@
+pub mod classify;
+pub mod conversions;
 pub mod exception_flags;
+pub mod flush_to_zero;
 pub mod nan_handling;
 pub mod rounding_modes;
@@ -91,3 +91,70 @@ fn test_f64_nan_boxing_not_applicable() {
     let res = Fpu::execute(AluOp::FAdd, d_val1, d_val2, 0, false);
     assert_eq!(f64::from_bits(res), 3.0);
 }
+
+#[test]
+fn test_unboxed_operand_reads_as_canonical_nan() {
+    let canonical = Fpu::box_f32(f32::from_bits(0x7fc0_0000));
+    let one = Fpu::box_f32(1.0);
+    // Zeroed upper word: low bits encode 2.0 but must not be used.
+    let unboxed = 2.0f32.to_bits() as u64;
+
+    assert_eq!(Fpu::execute(AluOp::FAdd, unboxed, one, 0, true), canonical);
+    assert_eq!(Fpu::execute(AluOp::FMul, one, unboxed, 0, true), canonical);
+    assert_eq!(Fpu::execute(AluOp::FEq, unboxed, unboxed, 0, true), 0);
+    // Sign injection operates on the canonical NaN, not the low bits.
+    assert_eq!(
+        Fpu::execute(AluOp::FSgnJN, unboxed, one, 0, true),
+        Fpu::box_f32(f32::from_bits(0xffc0_0000))
+    );
+}
+
+#[test]
+fn test_fmin_fmax_signed_zero() {
+    let pz = Fpu::box_f32(0.0);
+    let nz = Fpu::box_f32(-0.0);
+    for (a, b) in [(nz, pz), (pz, nz)] {
+        assert_eq!(Fpu::execute(AluOp::FMin, a, b, 0, true), nz);
+        assert_eq!(Fpu::execute(AluOp::FMax, a, b, 0, true), pz);
+    }
+
+    let pz = 0.0f64.to_bits();
+    let nz = (-0.0f64).to_bits();
+    for (a, b) in [(nz, pz), (pz, nz)] {
+        assert_eq!(Fpu::execute(AluOp::FMin, a, b, 0, false), nz);
+        assert_eq!(Fpu::execute(AluOp::FMax, a, b, 0, false), pz);
+    }
+}
+
+#[test]
+fn test_fmin_quiet_nan_returns_other_without_flags() {
+    let qnan = f64::NAN.to_bits();
+    let one = 1.0f64.to_bits();
+    let (r, flags) = Fpu::execute_full(AluOp::FMin, qnan, one, 0, false);
+    assert_eq!(f64::from_bits(r), 1.0);
+    assert!(flags.is_empty(), "quiet NaN inputs do not raise NV");
+}
+
+#[test]
+fn test_fmin_fmax_signaling_nan_raises_nv() {
+    use riscv_core::core::units::fpu::exception_flags::FpFlags;
+
+    let snan_s = Fpu::box_f32(f32::from_bits(0x7f80_0001));
+    let one_s = Fpu::box_f32(1.0);
+    let (r, flags) = Fpu::execute_full(AluOp::FMin, snan_s, one_s, 0, true);
+    assert_eq!(r, one_s);
+    assert_eq!(flags, FpFlags::NV);
+
+    let snan_d = 0x7ff0_0000_0000_0001u64;
+    let one_d = 1.0f64.to_bits();
+    let (r, flags) = Fpu::execute_full(AluOp::FMax, one_d, snan_d, 0, false);
+    assert_eq!(r, one_d);
+    assert_eq!(flags, FpFlags::NV);
+
+    let (r, flags) = Fpu::execute_full(AluOp::FMax, snan_d, snan_d, 0, false);
+    assert_eq!(
+        r, 0x7ff8_0000_0000_0000,
+        "two NaNs produce the canonical NaN"
+    );
+    assert_eq!(flags, FpFlags::NV);
+}
@@ -1,10 +1,13 @@
 //! RISC-V Rounding Mode tests.
 //!
 //! These tests verify that `Fpu::execute_with_rm()` correctly applies
-//! each of the five RISC-V rounding modes.
+//! each of the five RISC-V rounding modes, in single and double precision
+//! and for the fused multiply-add family, and that `Fpu::execute_rm()`
+//! reports the flags of the result rounded under the requested mode.
 
 use riscv_core::core::pipeline::signals::AluOp;
 use riscv_core::core::units::fpu::Fpu;
+use riscv_core::core::units::fpu::exception_flags::FpFlags;
 use riscv_core::core::units::fpu::rounding_modes::RoundingMode;
 
 /// Helper: box two f32 values, execute with rounding mode, unbox result.
@@ -184,3 +187,244 @@ fn rounding_mode_irrelevant_for_sign_injection() {
         }
     }
 }
+
+// ══════════════════════════════════════════════════════════
+// 8. FP-to-integer conversions honor the rounding mode
+// ══════════════════════════════════════════════════════════
+
+/// Helper: FCVT.W.S of `x` under `rm`, returning the sign-extended result.
+fn fcvt_w_s(x: f32, rm: RoundingMode) -> i64 {
+    Fpu::execute_rm(AluOp::FCvtWS, Fpu::box_f32(x), 0, 0, true, rm).0 as i64
+}
+
+#[test]
+fn test_fcvt_rounding_tie() {
+    // 2.5 is a tie: RNE picks the even neighbour, RMM the larger magnitude.
+    assert_eq!(fcvt_w_s(2.5, RoundingMode::Rtz), 2);
+    assert_eq!(fcvt_w_s(2.5, RoundingMode::Rne), 2);
+    assert_eq!(fcvt_w_s(2.5, RoundingMode::Rdn), 2);
+    assert_eq!(fcvt_w_s(2.5, RoundingMode::Rup), 3);
+    assert_eq!(fcvt_w_s(2.5, RoundingMode::Rmm), 3);
+}
+
+#[test]
+fn test_fcvt_rounding_negative_gives_distinct_results() {
+    assert_eq!(fcvt_w_s(-2.7, RoundingMode::Rtz), -2);
+    assert_eq!(fcvt_w_s(-2.7, RoundingMode::Rne), -3);
+    assert_eq!(fcvt_w_s(-2.5, RoundingMode::Rdn), -3);
+    assert_eq!(fcvt_w_s(-2.5, RoundingMode::Rne), -2);
+    assert_eq!(fcvt_w_s(-2.5, RoundingMode::Rup), -2);
+}
+
+#[test]
+fn test_fcvt_saturates_and_flags() {
+    let (r, flags) = Fpu::execute_rm(
+        AluOp::FCvtWS,
+        Fpu::box_f32(3.0e9),
+        0,
+        0,
+        true,
+        RoundingMode::Rtz,
+    );
+    assert_eq!(r as i64, i32::MAX as i64);
+    assert_eq!(flags, FpFlags::NV);
+
+    let (r, flags) = Fpu::execute_rm(
+        AluOp::FCvtLS,
+        f64::NAN.to_bits(),
+        0,
+        0,
+        false,
+        RoundingMode::Rtz,
+    );
+    assert_eq!(r, i64::MAX as u64, "NaN converts to the maximum integer");
+    assert_eq!(flags, FpFlags::NV);
+
+    let (_, flags) = Fpu::execute_rm(
+        AluOp::FCvtWS,
+        Fpu::box_f32(2.5),
+        0,
+        0,
+        true,
+        RoundingMode::Rtz,
+    );
+    assert_eq!(flags, FpFlags::NX);
+}
+
+#[test]
+fn test_resolve_dynamic_and_reserved() {
+    assert_eq!(RoundingMode::resolve(0b001, 0b011), Some(RoundingMode::Rtz));
+    assert_eq!(RoundingMode::resolve(0b111, 0b011), Some(RoundingMode::Rup));
+    assert_eq!(RoundingMode::resolve(0b101, 0b000), None);
+    assert_eq!(RoundingMode::resolve(0b111, 0b110), None, "invalid frm");
+}
+
+// ══════════════════════════════════════════════════════════
+// 9. Flags follow the directed result
+// ══════════════════════════════════════════════════════════
+
+/// Helper: FADD.S of `a` and `b` under `rm`, returning the result and flags.
+fn fadd_f32_flags(a: f32, b: f32, rm: RoundingMode) -> (f32, FpFlags) {
+    let (r, flags) = Fpu::execute_rm(AluOp::FAdd, Fpu::box_f32(a), Fpu::box_f32(b), 0, true, rm);
+    (f32::from_bits(r as u32), flags)
+}
+
+#[test]
+fn test_round_up_past_max_overflows() {
+    // MAX + ulp/4 rounds back to MAX to nearest, but past it rounding up.
+    let quarter_ulp = 2f32.powi(102);
+    assert_eq!(
+        fadd_f32_flags(f32::MAX, quarter_ulp, RoundingMode::Rne),
+        (f32::MAX, FpFlags::NX)
+    );
+    assert_eq!(
+        fadd_f32_flags(f32::MAX, quarter_ulp, RoundingMode::Rup),
+        (f32::INFINITY, FpFlags::OF | FpFlags::NX)
+    );
+    assert_eq!(
+        fadd_f32_flags(f32::MAX, quarter_ulp, RoundingMode::Rtz),
+        (f32::MAX, FpFlags::NX)
+    );
+}
+
+#[test]
+fn test_round_toward_zero_saturates_overflow() {
+    assert_eq!(
+        fadd_f32_flags(f32::MAX, f32::MAX, RoundingMode::Rne),
+        (f32::INFINITY, FpFlags::OF | FpFlags::NX)
+    );
+    assert_eq!(
+        fadd_f32_flags(f32::MAX, f32::MAX, RoundingMode::Rtz),
+        (f32::MAX, FpFlags::OF | FpFlags::NX)
+    );
+    assert_eq!(
+        fadd_f32_flags(-f32::MAX, -f32::MAX, RoundingMode::Rup),
+        (-f32::MAX, FpFlags::OF | FpFlags::NX)
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 10. Double precision and FMA honor directed modes
+// ══════════════════════════════════════════════════════════
+
+/// Helper: a double-precision `op` of `a` and `b` under `rm`.
+fn f64_rm(op: AluOp, a: f64, b: f64, rm: RoundingMode) -> f64 {
+    f64::from_bits(Fpu::execute_with_rm(
+        op,
+        a.to_bits(),
+        b.to_bits(),
+        0,
+        false,
+        rm,
+    ))
+}
+
+#[test]
+fn fadd_d_rounds_up_and_down() {
+    let tiny = 2f64.powi(-60);
+    assert_eq!(f64_rm(AluOp::FAdd, 1.0, tiny, RoundingMode::Rne), 1.0);
+    assert_eq!(f64_rm(AluOp::FAdd, 1.0, tiny, RoundingMode::Rdn), 1.0);
+    assert_eq!(
+        f64_rm(AluOp::FAdd, 1.0, tiny, RoundingMode::Rup),
+        1.0f64.next_up()
+    );
+
+    assert_eq!(f64_rm(AluOp::FAdd, -1.0, -tiny, RoundingMode::Rtz), -1.0);
+    assert_eq!(f64_rm(AluOp::FAdd, -1.0, -tiny, RoundingMode::Rup), -1.0);
+    assert_eq!(
+        f64_rm(AluOp::FAdd, -1.0, -tiny, RoundingMode::Rdn),
+        (-1.0f64).next_down()
+    );
+}
+
+#[test]
+fn fdiv_d_rounds_up_and_down() {
+    let up = f64_rm(AluOp::FDiv, 1.0, 3.0, RoundingMode::Rup);
+    let down = f64_rm(AluOp::FDiv, 1.0, 3.0, RoundingMode::Rdn);
+    assert_eq!(up, down.next_up());
+    assert!(
+        1.0 / 3.0 == down || 1.0 / 3.0 == up,
+        "one side is the nearest"
+    );
+    assert_eq!(f64_rm(AluOp::FDiv, 1.0, 3.0, RoundingMode::Rtz), down);
+
+    // Exact quotients are unaffected.
+    assert_eq!(f64_rm(AluOp::FDiv, 1.0, 4.0, RoundingMode::Rup), 0.25);
+    assert_eq!(f64_rm(AluOp::FDiv, 1.0, 4.0, RoundingMode::Rdn), 0.25);
+}
+
+#[test]
+fn fmul_d_overflow_saturates_toward_zero() {
+    let (r, flags) = Fpu::execute_rm(
+        AluOp::FMul,
+        f64::MAX.to_bits(),
+        2f64.to_bits(),
+        0,
+        false,
+        RoundingMode::Rtz,
+    );
+    assert_eq!(
+        (f64::from_bits(r), flags),
+        (f64::MAX, FpFlags::OF | FpFlags::NX)
+    );
+    assert_eq!(
+        f64_rm(AluOp::FMul, f64::MAX, 2.0, RoundingMode::Rup),
+        f64::INFINITY
+    );
+    assert_eq!(
+        f64_rm(AluOp::FMul, -f64::MAX, 2.0, RoundingMode::Rup),
+        -f64::MAX
+    );
+}
+
+#[test]
+fn rmm_breaks_double_and_single_ties_away_from_zero() {
+    // 1 + 2^-53 is exactly halfway between 1.0 and the next double.
+    let half_ulp = 2f64.powi(-53);
+    assert_eq!(f64_rm(AluOp::FAdd, 1.0, half_ulp, RoundingMode::Rne), 1.0);
+    assert_eq!(
+        f64_rm(AluOp::FAdd, 1.0, half_ulp, RoundingMode::Rmm),
+        1.0f64.next_up()
+    );
+    assert_eq!(
+        fadd_f32_rm(1.0, 2f32.powi(-24), RoundingMode::Rmm),
+        1.0f32.next_up()
+    );
+}
+
+#[test]
+fn fmadd_rounds_under_directed_modes() {
+    // 1 * 1 + 2^-80 is 1.0 to nearest in both precisions.
+    let fma_d = |rm| {
+        let (a, c) = (1f64.to_bits(), 2f64.powi(-80).to_bits());
+        f64::from_bits(Fpu::execute_with_rm(AluOp::FMAdd, a, a, c, false, rm))
+    };
+    assert_eq!(fma_d(RoundingMode::Rne), 1.0);
+    assert_eq!(fma_d(RoundingMode::Rup), 1.0f64.next_up());
+    assert_eq!(fma_d(RoundingMode::Rdn), 1.0);
+
+    let fma_s = |op, rm| {
+        let (a, c) = (Fpu::box_f32(1.0), Fpu::box_f32(2f32.powi(-80)));
+        f32::from_bits(Fpu::execute_with_rm(op, a, a, c, true, rm) as u32)
+    };
+    assert_eq!(fma_s(AluOp::FMAdd, RoundingMode::Rne), 1.0);
+    assert_eq!(fma_s(AluOp::FMAdd, RoundingMode::Rup), 1.0f32.next_up());
+    assert_eq!(fma_s(AluOp::FMAdd, RoundingMode::Rdn), 1.0);
+    // FMSUB: 1 - 2^-80 rounds down, or to 1.0 toward +inf.
+    assert_eq!(fma_s(AluOp::FMSub, RoundingMode::Rdn), 1.0f32.next_down());
+    assert_eq!(fma_s(AluOp::FMSub, RoundingMode::Rup), 1.0);
+}
+
+#[test]
+fn fadd_s_rounds_up_past_a_double_rounding_tie() {
+    // 1 + 2^-80 is 1.0 once rounded to double; RUP must still round up.
+    assert_eq!(
+        fadd_f32_rm(1.0, 2f32.powi(-80), RoundingMode::Rup),
+        1.0f32.next_up()
+    );
+    assert_eq!(fadd_f32_rm(-1.0, -2f32.powi(-80), RoundingMode::Rtz), -1.0);
+    assert_eq!(
+        fadd_f32_rm(-1.0, -2f32.powi(-80), RoundingMode::Rdn),
+        (-1.0f32).next_down()
+    );
+}
@@ -1,3 +1,4 @@
 pub mod atomic;
 pub mod ordering;
+pub mod priority;
 pub mod unaligned;
@@ -0,0 +1,54 @@
+//! Exception priority Unit Tests.
+//!
+//! Verifies the synchronous exception ordering and the platform-selected
+//! placement of address-misaligned exceptions.
+
+use riscv_core::common::error::Trap;
+use riscv_core::config::MisalignedPriority;
+use riscv_core::core::units::lsu::priority::{exception_rank, prioritize};
+
+const BEFORE: MisalignedPriority = MisalignedPriority::BeforeTranslation;
+const AFTER: MisalignedPriority = MisalignedPriority::AfterTranslation;
+
+#[test]
+fn page_fault_outranks_access_fault() {
+    for order in [BEFORE, AFTER] {
+        assert!(
+            exception_rank(&Trap::LoadPageFault(0), order)
+                < exception_rank(&Trap::LoadAccessFault(0), order)
+        );
+        assert!(
+            exception_rank(&Trap::StorePageFault(0), order)
+                < exception_rank(&Trap::StoreAccessFault(0), order)
+        );
+    }
+}
+
+#[test]
+fn instruction_faults_outrank_memory_faults() {
+    let fetch = Trap::InstructionPageFault(0);
+    let illegal = Trap::IllegalInstruction(0);
+    let load = Trap::LoadPageFault(0);
+    assert!(exception_rank(&fetch, BEFORE) < exception_rank(&illegal, BEFORE));
+    assert!(exception_rank(&illegal, BEFORE) < exception_rank(&load, BEFORE));
+}
+
+#[test]
+fn misaligned_placement_follows_configuration() {
+    let misaligned = Some(Trap::StoreAddressMisaligned(0x1002));
+    let fault = Some(Trap::StoreAccessFault(0x1002));
+
+    assert_eq!(
+        prioritize(misaligned.clone(), fault.clone(), BEFORE),
+        misaligned
+    );
+    assert_eq!(prioritize(misaligned.clone(), fault.clone(), AFTER), fault);
+}
+
+#[test]
+fn single_candidate_is_reported() {
+    let fault = Some(Trap::LoadPageFault(8));
+    assert_eq!(prioritize(None, fault.clone(), BEFORE), fault);
+    assert_eq!(prioritize(fault.clone(), None, AFTER), fault);
+    assert_eq!(prioritize(None, None, BEFORE), None);
+}
@@ -1,3 +1,4 @@
 pub mod pmp;
 pub mod ptw;
+pub mod soft_tlb;
 pub mod tlb;
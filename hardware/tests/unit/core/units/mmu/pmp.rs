@@ -1,8 +1,16 @@
 //! PMP (Physical Memory Protection) Unit Tests.
 //!
 //! Verifies address matching (TOR, NA4, NAPOT), permission checks,
-//! M-mode bypass logic, and locking behaviour per RISC-V spec §3.7.
-
+//! M-mode bypass logic, and locking behaviour per RISC-V spec §3.7, plus the
+//! `pmpcfg`/`pmpaddr` CSR interface and enforcement on CPU accesses and
+//! page-table walks.
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::{AccessType, Trap, VirtAddr};
+use riscv_core::config::Config;
+use riscv_core::core::arch::csr;
+use riscv_core::core::arch::mode::PrivilegeMode;
 use riscv_core::core::units::mmu::pmp::{Pmp, PmpAddrMatch, PmpEntry, PmpResult};
 
 // ══════════════════════════════════════════════════════════
@@ -163,9 +171,9 @@ fn na4_matches_exactly_four_bytes() {
     let result = pmp.check(0x4004, 1, true, false, false, false);
     assert_eq!(result, PmpResult::NoMatch);
 
-    // Overlapping the end
+    // Overlapping the end: a partial match fails the access
     let result = pmp.check(0x4002, 4, true, false, false, false);
-    assert_eq!(result, PmpResult::NoMatch);
+    assert_eq!(result, PmpResult::Deny);
 }
 
 // ══════════════════════════════════════════════════════════
@@ -394,3 +402,234 @@ fn pmp_entry_match_mode_accessor() {
     };
     assert_eq!(entry_napot.match_mode(), PmpAddrMatch::Napot);
 }
+
+// ══════════════════════════════════════════════════════════
+// 10. CSR interface (WARL)
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn pmpcfg_packs_eight_entries_and_clears_write_without_read() {
+    let mut tc = TestContext::new();
+    let val = u64::from(A_NAPOT | R | W) | (u64::from(A_TOR | W | 0x60) << 8);
+    tc.cpu.csr_write(csr::PMPCFG0 + 2, val);
+
+    // Entry 9 asked for W without R and set reserved bits; both are dropped.
+    let expected = u64::from(A_NAPOT | R | W) | (u64::from(A_TOR) << 8);
+    assert_eq!(tc.cpu.csrs.read(csr::PMPCFG0 + 2), expected);
+    assert_eq!(tc.cpu.csrs.pmp.get_cfg(8), A_NAPOT | R | W);
+
+    // Odd-numbered pmpcfg registers do not exist on RV64.
+    tc.cpu.csr_write(csr::PMPCFG0 + 1, u64::MAX);
+    assert_eq!(tc.cpu.csrs.read(csr::PMPCFG0 + 1), 0);
+}
+
+#[test]
+fn pmpaddr_keeps_54_bits_and_respects_locked_tor() {
+    let mut tc = TestContext::new();
+    tc.cpu.csr_write(csr::PMPADDR63, u64::MAX);
+    assert_eq!(tc.cpu.csrs.read(csr::PMPADDR63), (1 << 54) - 1);
+
+    tc.cpu.csr_write(csr::PMPADDR0, 0x1000);
+    tc.cpu.csr_write(csr::PMPADDR0 + 1, 0x2000);
+    tc.cpu
+        .csr_write(csr::PMPCFG0, u64::from(A_TOR | R | L) << 8);
+
+    // Entry 1 is a locked TOR region, so its base (pmpaddr0) is frozen too.
+    tc.cpu.csr_write(csr::PMPADDR0, 0x1800);
+    assert_eq!(tc.cpu.csrs.read(csr::PMPADDR0), 0x1000);
+}
+
+// ══════════════════════════════════════════════════════════
+// 11. Enforcement on CPU accesses
+// ══════════════════════════════════════════════════════════
+
+const RAM: u64 = 0x8000_0000;
+
+/// A CPU over 64 KiB of RAM whose first 4 KiB are an RWX NAPOT region in entry 0.
+fn napot_ctx(privilege: PrivilegeMode) -> TestContext {
+    let mut tc = TestContext::new().with_memory(0x1_0000, RAM);
+    tc.cpu.privilege = privilege;
+    tc.cpu.csr_write(csr::PMPADDR0, (RAM >> 2) | 0x1FF);
+    tc.cpu
+        .csr_write(csr::PMPCFG0, u64::from(A_NAPOT | R | W | X));
+    tc
+}
+
+/// A context with `memory.pmp_open_when_inactive` set to `open`, over the RAM of [`napot_ctx`].
+fn inactive_ctx(open: bool, privilege: PrivilegeMode) -> TestContext {
+    let mut config = Config::default();
+    config.memory.pmp_open_when_inactive = open;
+    let mut tc = TestContext::from_config(&config).with_memory(0x1_0000, RAM);
+    tc.cpu.privilege = privilege;
+    tc
+}
+
+#[test]
+fn inactive_pmp_is_open_when_configured() {
+    let mut tc = inactive_ctx(true, PrivilegeMode::Supervisor);
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x8000), AccessType::Write, 8);
+    assert!(result.trap.is_none());
+}
+
+#[test]
+fn inactive_pmp_faults_supervisor_by_spec() {
+    let mut tc = inactive_ctx(false, PrivilegeMode::Supervisor);
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x8000), AccessType::Read, 8);
+    assert_eq!(result.trap, Some(Trap::LoadAccessFault(RAM + 0x8000)));
+
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x8000), AccessType::Read, 8);
+    assert!(result.trap.is_none());
+}
+
+#[test]
+fn napot_region_denies_supervisor_write_outside_range() {
+    let mut tc = napot_ctx(PrivilegeMode::Supervisor);
+
+    let inside = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0xFF8), AccessType::Write, 8);
+    assert!(inside.trap.is_none());
+
+    let outside = RAM + 0x1000;
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(outside), AccessType::Write, 8);
+    assert_eq!(result.trap, Some(Trap::StoreAccessFault(outside)));
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(outside), AccessType::Fetch, 2);
+    assert_eq!(result.trap, Some(Trap::InstructionAccessFault(outside)));
+}
+
+#[test]
+fn napot_region_leaves_machine_mode_unrestricted() {
+    let mut tc = napot_ctx(PrivilegeMode::Machine);
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x1000), AccessType::Write, 8);
+    assert!(result.trap.is_none());
+}
+
+#[test]
+fn locked_entry_blocks_machine_mode_write() {
+    let mut tc = TestContext::new().with_memory(0x1_0000, RAM);
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    tc.cpu.csr_write(csr::PMPADDR0, (RAM >> 2) | 0x1FF);
+    tc.cpu
+        .csr_write(csr::PMPCFG0, u64::from(A_NAPOT | R | X | L));
+
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x10), AccessType::Write, 8);
+    assert_eq!(result.trap, Some(Trap::StoreAccessFault(RAM + 0x10)));
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x10), AccessType::Read, 8);
+    assert!(result.trap.is_none());
+
+    // The lock also makes the entry immune to further writes.
+    tc.cpu
+        .csr_write(csr::PMPCFG0, u64::from(A_NAPOT | R | W | X));
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(RAM + 0x10), AccessType::Write, 8);
+    assert!(result.trap.is_some());
+}
+
+#[test]
+fn access_straddling_region_end_faults() {
+    let mut tc = napot_ctx(PrivilegeMode::Supervisor);
+    let last_word = RAM + 0xFFC;
+
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(last_word), AccessType::Read, 4);
+    assert!(result.trap.is_none());
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(last_word), AccessType::Read, 8);
+    assert_eq!(result.trap, Some(Trap::LoadAccessFault(last_word)));
+
+    // A partial match fails even in M-mode.
+    tc.cpu.privilege = PrivilegeMode::Machine;
+    let result = tc
+        .cpu
+        .translate(VirtAddr::new(last_word), AccessType::Read, 8);
+    assert_eq!(result.trap, Some(Trap::LoadAccessFault(last_word)));
+}
+
+#[test]
+fn doubleword_load_past_tor_boundary_faults() {
+    let b = InstructionBuilder::new;
+    let mut config = Config::default();
+    config.general.direct_mode = false;
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1_0000, RAM)
+        .load_program(RAM + 0x800, &[b().jal(0, 0).build()])
+        .load_program(RAM, &[b().ld(5, 10, 0).build(), b().jal(0, 0).build()]);
+    tc.cpu.direct_mode = false;
+    tc.cpu.csrs.mtvec = RAM + 0x800;
+    // Entry 0 grants [0, RAM + 0x1004): the load's first word only.
+    tc.cpu.csr_write(csr::PMPADDR0, (RAM + 0x1004) >> 2);
+    tc.cpu.csr_write(csr::PMPCFG0, u64::from(A_TOR | R | W | X));
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.set_reg(10, RAM + 0x1000);
+
+    tc.run(50);
+
+    assert_eq!(tc.cpu.csrs.mcause, 5, "load access fault");
+    assert_eq!(tc.cpu.csrs.mtval, RAM + 0x1000);
+    assert_eq!(tc.get_reg(5), 0);
+}
+
+/// Sv39 root, level-1, and level-0 tables, above the NAPOT region of [`napot_ctx`].
+const ROOT: u64 = RAM + 0x8000;
+const L1: u64 = RAM + 0x9000;
+const L0: u64 = RAM + 0xA000;
+/// Virtual page mapped onto the first page of RAM.
+const VA: u64 = 0x1000;
+
+/// An S-mode CPU under Sv39 mapping [`VA`] onto `RAM`, with PMP entry 0 granting
+/// only that page.
+fn paged_napot_ctx() -> TestContext {
+    let mut tc = napot_ctx(PrivilegeMode::Supervisor);
+    let pte = |pa: u64, flags: u64| ((pa >> 12) << 10) | flags;
+    let bus = &mut tc.cpu.bus.bus;
+    bus.write_u64(ROOT, pte(L1, 0x01));
+    bus.write_u64(L1, pte(L0, 0x01));
+    bus.write_u64(L0 + 8, pte(RAM, 0xCF));
+    tc.cpu.direct_mode = false;
+    tc.cpu.csrs.satp = (csr::SATP_MODE_SV39 << csr::SATP_MODE_SHIFT) | (ROOT >> 12);
+    tc
+}
+
+#[test]
+fn page_walk_faults_when_pmp_denies_the_tables() {
+    let mut tc = paged_napot_ctx();
+    let result = tc.cpu.translate(VirtAddr::new(VA), AccessType::Read, 8);
+    assert_eq!(result.trap, Some(Trap::LoadAccessFault(VA)));
+    let result = tc.cpu.translate(VirtAddr::new(VA), AccessType::Fetch, 2);
+    assert_eq!(result.trap, Some(Trap::InstructionAccessFault(VA)));
+}
+
+#[test]
+fn page_walk_succeeds_once_pmp_grants_the_tables() {
+    let mut tc = paged_napot_ctx();
+    // Entry 1: a read-only NAPOT region over the 16 KiB holding the tables.
+    tc.cpu.csr_write(csr::PMPADDR0 + 1, (ROOT >> 2) | 0x7FF);
+    tc.cpu.csr_write(
+        csr::PMPCFG0,
+        u64::from(A_NAPOT | R | W | X) | u64::from(A_NAPOT | R) << 8,
+    );
+
+    let result = tc.cpu.translate(VirtAddr::new(VA), AccessType::Read, 8);
+    assert_eq!(result.trap, None);
+    assert_eq!(result.paddr.val(), RAM);
+}
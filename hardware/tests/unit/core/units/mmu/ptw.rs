@@ -1,12 +1,17 @@
 //! Page Table Walker (PTW) Unit Tests.
 //!
-//! Verifies SV39 address translation logic:
+//! Verifies SV39 and SV48 address translation logic:
 //! - Page table walks (levels 2, 1, 0)
 //! - Superpages (2MB, 1GB)
-//! - Permission checks (R/W/X/U)
+//! - Permission checks (R/W/X/U), SUM and MXR on walks and TLB hits
 //! - Accessed/Dirty bit updates
+//! - Superpage A/D write-back and SFENCE.VMA remapping
 //! - Canonical address checks
 //! - Bare mode bypass
+//! - Configurable (16KB) base page size
+//! - SV48 four-level walks, terapages, and the 48-bit canonical check
+//! - Software-managed A/D bits (Svade) fault instead of updating
+//! - ITLB/DTLB hit, miss, and page-walk counters
 
 use crate::common::harness::TestContext;
 use riscv_core::common::{AccessType, Trap, VirtAddr};
@@ -525,6 +530,172 @@ fn supervisor_cannot_fetch_user_page() {
     );
 }
 
+/// Maps the gigapage at VA 0x8000_0000 with `perms` and returns its address.
+fn map_gigapage(bus: &mut Bus, perms: u64) -> VirtAddr {
+    let l2_idx = (0x8000_0000 >> 30) & 0x1FF;
+    write_pte(bus, ROOT_PPN, l2_idx, make_pte(ROOT_PPN + 0x40000, perms));
+    VirtAddr::new(0x8000_0000)
+}
+
+#[test]
+fn supervisor_store_to_user_page_needs_sum() {
+    let (mut mmu, mut csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let vaddr = map_gigapage(bus, R | W | U | A | D);
+
+    csrs.write(csr::SSTATUS, 0);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::StorePageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+
+    csrs.write(csr::SSTATUS, csr::MSTATUS_SUM);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none(), "Trap: {:?}", res.trap);
+}
+
+#[test]
+fn clearing_sum_faults_on_cached_user_translation() {
+    let (mut mmu, mut csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let vaddr = map_gigapage(bus, R | W | U | A | D);
+
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none(), "SUM set: {:?}", res.trap);
+
+    // The translation is now in the DTLB; SUM is re-checked on every hit.
+    csrs.write(csr::SSTATUS, 0);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+}
+
+#[test]
+fn mxr_makes_execute_only_page_readable() {
+    let (mut mmu, mut csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let vaddr = map_gigapage(bus, X | A);
+
+    csrs.write(csr::SSTATUS, 0);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+
+    csrs.write(csr::SSTATUS, csr::MSTATUS_MXR);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none(), "Trap: {:?}", res.trap);
+
+    // Cached now; clearing MXR makes the hit fault again.
+    csrs.write(csr::SSTATUS, 0);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+}
+
+#[test]
+fn mxr_does_not_make_pages_writable() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let vaddr = map_gigapage(bus, X | A | D);
+
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::StorePageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+}
+
+#[test]
+fn mstatus_sum_write_reaches_translation() {
+    let (_, csrs, mut tc) = setup_mmu();
+    tc.cpu.csrs.satp = csrs.satp;
+    let vaddr = map_gigapage(&mut tc.cpu.bus.bus, R | U | A);
+
+    tc.cpu.csr_write(csr::MSTATUS, 0);
+    let res = tc.cpu.mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &tc.cpu.csrs,
+        &mut tc.cpu.bus.bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+
+    tc.cpu.csr_write(csr::MSTATUS, csr::MSTATUS_SUM);
+    let res = tc.cpu.mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &tc.cpu.csrs,
+        &mut tc.cpu.bus.bus,
+    );
+    assert!(res.trap.is_none(), "Trap: {:?}", res.trap);
+}
+
 // ══════════════════════════════════════════════════════════
 // 7. Canonical Address Check
 // ══════════════════════════════════════════════════════════
@@ -557,3 +728,560 @@ fn non_canonical_address_faults() {
         res.trap
     );
 }
+
+// ══════════════════════════════════════════════════════════
+// 8. Superpage A/D Updates and SFENCE.VMA
+// ══════════════════════════════════════════════════════════
+
+/// VA 0x4020_0000: VPN[2]=1, VPN[1]=1, mapped by a megapage leaf at level 1.
+const MEGA_VA: u64 = 0x4020_0000;
+const MEGA_L2_IDX: u64 = (MEGA_VA >> 30) & 0x1FF;
+const MEGA_L1_IDX: u64 = (MEGA_VA >> 21) & 0x1FF;
+const MEGA_L1_PPN: u64 = ROOT_PPN + 1;
+
+/// Maps `MEGA_VA` through a level-1 leaf to `target_ppn` with `perms`.
+fn map_megapage(bus: &mut Bus, target_ppn: u64, perms: u64) {
+    write_pte(bus, ROOT_PPN, MEGA_L2_IDX, make_pte(MEGA_L1_PPN, 0));
+    write_pte(bus, MEGA_L1_PPN, MEGA_L1_IDX, make_pte(target_ppn, perms));
+}
+
+fn mega_l1_pte(bus: &mut Bus) -> u64 {
+    bus.read_u64((MEGA_L1_PPN << 12) + MEGA_L1_IDX * 8)
+}
+
+#[test]
+fn megapage_write_sets_dirty_on_level1_pte() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let target_ppn = ROOT_PPN + 0x200;
+    map_megapage(bus, target_ppn, R | W | A);
+    let root_before = bus.read_u64((ROOT_PPN << 12) + MEGA_L2_IDX * 8);
+
+    // A read caches the clean translation; the store must still set D.
+    let vaddr = VirtAddr::new(MEGA_VA + 0x1234);
+    for access in [AccessType::Read, AccessType::Write] {
+        let res = mmu.translate(vaddr, access, PrivilegeMode::Supervisor, &csrs, bus);
+        assert!(res.trap.is_none(), "{:?}: {:?}", access, res.trap);
+        assert_eq!(res.paddr.val(), (target_ppn << 12) + 0x1234);
+    }
+
+    assert_eq!(mega_l1_pte(bus) & D, D, "D set on the level-1 leaf");
+    assert_eq!(
+        bus.read_u64((ROOT_PPN << 12) + MEGA_L2_IDX * 8),
+        root_before,
+        "level-2 pointer PTE is untouched"
+    );
+}
+
+#[test]
+fn gigapage_write_sets_dirty_on_level2_pte() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let l2_idx = (0x8000_0000u64 >> 30) & 0x1FF;
+    let target_ppn = ROOT_PPN + 0x40000;
+    write_pte(bus, ROOT_PPN, l2_idx, make_pte(target_ppn, R | W));
+
+    let vaddr = VirtAddr::new(0x8000_0000);
+    for access in [AccessType::Read, AccessType::Write] {
+        let res = mmu.translate(vaddr, access, PrivilegeMode::Supervisor, &csrs, bus);
+        assert!(res.trap.is_none(), "{:?}: {:?}", access, res.trap);
+    }
+
+    let pte = bus.read_u64((ROOT_PPN << 12) + l2_idx * 8);
+    assert_eq!(pte & (A | D), A | D, "A and D set on the level-2 leaf");
+}
+
+#[test]
+fn store_to_cached_read_only_page_still_faults() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    map_megapage(bus, ROOT_PPN + 0x200, R | A);
+
+    let vaddr = VirtAddr::new(MEGA_VA);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none());
+
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(
+        matches!(res.trap, Some(Trap::StorePageFault(_))),
+        "Trap: {:?}",
+        res.trap
+    );
+    assert_eq!(mega_l1_pte(bus) & D, 0, "faulting store leaves D clear");
+}
+
+#[test]
+fn sfence_vma_picks_up_remapped_megapage() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let old_ppn = ROOT_PPN + 0x200;
+    let new_ppn = ROOT_PPN + 0x400;
+    map_megapage(bus, old_ppn, R | W | A);
+
+    let vaddr = VirtAddr::new(MEGA_VA);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert_eq!(res.paddr.val(), old_ppn << 12);
+    assert_eq!(mega_l1_pte(bus) & D, D);
+
+    // Break-before-make: software rewrites the leaf, then fences.
+    map_megapage(bus, new_ppn, R | W | A);
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert_eq!(res.paddr.val(), old_ppn << 12, "stale until SFENCE.VMA");
+
+    // SFENCE.VMA flushes both TLBs.
+    mmu.dtlb.flush();
+    mmu.itlb.flush();
+
+    let res = mmu.translate(
+        vaddr,
+        AccessType::Write,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none(), "Trap: {:?}", res.trap);
+    assert_eq!(res.paddr.val(), new_ppn << 12, "new mapping after fence");
+    assert_eq!(mega_l1_pte(bus) & D, D, "D set again on the new leaf");
+}
+
+// ══════════════════════════════════════════════════════════
+// 9. Configurable Base Page Size
+// ══════════════════════════════════════════════════════════
+
+const PAGE_16K_SHIFT: u64 = 14;
+/// Root table at `MEM_BASE`, counted in 16KB pages.
+const ROOT_PPN_16K: u64 = MEM_BASE >> PAGE_16K_SHIFT;
+
+/// Builds an MMU and SATP for 16KB base pages.
+fn setup_mmu_16k() -> (Mmu, Csrs, TestContext) {
+    let (mut mmu, mut csrs, tc) = setup_mmu();
+    mmu.set_page_size(1 << PAGE_16K_SHIFT);
+    csrs.write(csr::SATP, (csr::SATP_MODE_SV39 << 60) | ROOT_PPN_16K);
+    (mmu, csrs, tc)
+}
+
+/// Maps `va` with a three-level walk of 16KB tables onto base page `target_ppn`.
+fn map_16k_page(bus: &mut Bus, va: u64, target_ppn: u64) {
+    let idx = |level: u64| (va >> (PAGE_16K_SHIFT + 9 * level)) & 0x1FF;
+    let write = |bus: &mut Bus, table_ppn: u64, level: u64, pte: u64| {
+        bus.write_u64((table_ppn << PAGE_16K_SHIFT) + idx(level) * 8, pte);
+    };
+    let l1_table = ROOT_PPN_16K + 1;
+    let l0_table = ROOT_PPN_16K + 2;
+    write(bus, ROOT_PPN_16K, 2, make_pte(l1_table, 0));
+    write(bus, l1_table, 1, make_pte(l0_table, 0));
+    write(bus, l0_table, 0, make_pte(target_ppn, R | W | A | D));
+}
+
+#[test]
+fn page_16k_walk_translates_with_14_bit_offset() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_16k();
+    let bus = &mut tc.cpu.bus.bus;
+
+    // Offset 0x3234 only fits a 16KB page; with 4KB pages it would spill into the VPN.
+    let va = 0x4000_7234;
+    let target_ppn = ROOT_PPN_16K + 10;
+    map_16k_page(bus, va, target_ppn);
+
+    let res = mmu.translate(
+        VirtAddr::new(va),
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        &csrs,
+        bus,
+    );
+    assert!(res.trap.is_none(), "Trap: {:?}", res.trap);
+    assert_eq!(res.paddr.val(), (target_ppn << PAGE_16K_SHIFT) | 0x3234);
+    assert_eq!(mmu.vpn(va), va >> PAGE_16K_SHIFT);
+    assert_eq!(mmu.page_offset(va), 0x3234);
+}
+
+#[test]
+fn page_16k_tlb_entry_covers_the_whole_page() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_16k();
+    let bus = &mut tc.cpu.bus.bus;
+
+    let page = 0x4000_4000;
+    let target_ppn = ROOT_PPN_16K + 10;
+    map_16k_page(bus, page, target_ppn);
+    let read = |mmu: &mut Mmu, bus: &mut Bus, va: u64| {
+        mmu.translate(
+            VirtAddr::new(va),
+            AccessType::Read,
+            PrivilegeMode::Supervisor,
+            &csrs,
+            bus,
+        )
+    };
+
+    assert!(read(&mut mmu, bus, page).trap.is_none());
+    // Unmap the whole tree; only the TLB still knows the page.
+    bus.write_u64(
+        (ROOT_PPN_16K << PAGE_16K_SHIFT) + ((page >> 32) & 0x1FF) * 8,
+        0,
+    );
+
+    // The last word of the 16KB page hits the same TLB entry.
+    let last = read(&mut mmu, bus, page + 0x3FFC);
+    assert!(last.trap.is_none(), "Trap: {:?}", last.trap);
+    assert_eq!(last.paddr.val(), (target_ppn << PAGE_16K_SHIFT) | 0x3FFC);
+
+    // The next base page misses and walks to a fault.
+    let next = read(&mut mmu, bus, page + 0x4000);
+    assert!(
+        matches!(next.trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {:?}",
+        next.trap
+    );
+}
+
+#[test]
+fn page_16k_widens_the_canonical_range() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_16k();
+    let mut read = |va: u64| {
+        mmu.translate(
+            VirtAddr::new(va),
+            AccessType::Read,
+            PrivilegeMode::Supervisor,
+            &csrs,
+            &mut tc.cpu.bus.bus,
+        )
+        .trap
+    };
+
+    // 16KB pages translate 41 bits: bit 38 is now an ordinary VPN bit...
+    assert!(matches!(read(1 << 38), Some(Trap::LoadPageFault(_))));
+    // ...while bit 40 must be sign-extended.
+    assert!(matches!(read(1 << 40), Some(Trap::LoadAccessFault(_))));
+    assert!(matches!(read(!0 << 40), Some(Trap::LoadPageFault(_))));
+}
+
+#[test]
+fn page_size_is_applied_from_config_and_validated() {
+    use riscv_core::config::{Config, ConfigError};
+
+    let mut config = Config::default();
+    config.memory.page_size = 1 << PAGE_16K_SHIFT;
+    let tc = TestContext::from_config(&config);
+    assert_eq!(tc.cpu.mmu.page_shift(), PAGE_16K_SHIFT);
+    assert_eq!(Mmu::new(4).page_shift(), 12, "default stays 4KB");
+
+    config.memory.page_size = 12 * 1024;
+    let Err(ConfigError::Invalid(errors)) = config.validate() else {
+        panic!("non-power-of-two page size must be rejected");
+    };
+    assert!(errors[0].contains("memory.page_size (12288)"), "{errors:?}");
+}
+
+// ══════════════════════════════════════════════════════════
+// 10. SV48 (4 Levels)
+// ══════════════════════════════════════════════════════════
+
+/// High-canonical SV48 address: bit 47 set and sign-extended.
+const SV48_VA: u64 = 0xFFFF_8000_4020_1234;
+
+/// VPN index of `va` at `level` (0..=3).
+fn vpn_idx(va: u64, level: u64) -> u64 {
+    (va >> (12 + 9 * level)) & 0x1FF
+}
+
+/// Builds an MMU and SATP with SV48 translation rooted at `ROOT_PPN`.
+fn setup_mmu_sv48() -> (Mmu, Csrs, TestContext) {
+    let (mmu, mut csrs, tc) = setup_mmu();
+    csrs.write(csr::SATP, (csr::SATP_MODE_SV48 << 60) | ROOT_PPN);
+    (mmu, csrs, tc)
+}
+
+fn sv48_read(mmu: &mut Mmu, csrs: &Csrs, tc: &mut TestContext, va: u64) -> (u64, Option<Trap>) {
+    let res = mmu.translate(
+        VirtAddr::new(va),
+        AccessType::Read,
+        PrivilegeMode::Supervisor,
+        csrs,
+        &mut tc.cpu.bus.bus,
+    );
+    (res.paddr.val(), res.trap)
+}
+
+#[test]
+fn sv48_satp_mode_is_retained() {
+    let (_, csrs, _) = setup_mmu_sv48();
+    assert_eq!(
+        (csrs.read(csr::SATP) >> csr::SATP_MODE_SHIFT) & csr::SATP_MODE_MASK,
+        csr::SATP_MODE_SV48
+    );
+}
+
+#[test]
+fn sv48_4kb_page_walk() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_sv48();
+    let bus = &mut tc.cpu.bus.bus;
+
+    let (l2, l1, l0) = (ROOT_PPN + 1, ROOT_PPN + 2, ROOT_PPN + 3);
+    let target_ppn = ROOT_PPN + 10;
+    write_pte(bus, ROOT_PPN, vpn_idx(SV48_VA, 3), make_pte(l2, 0));
+    write_pte(bus, l2, vpn_idx(SV48_VA, 2), make_pte(l1, 0));
+    write_pte(bus, l1, vpn_idx(SV48_VA, 1), make_pte(l0, 0));
+    write_pte(
+        bus,
+        l0,
+        vpn_idx(SV48_VA, 0),
+        make_pte(target_ppn, R | W | A | D),
+    );
+
+    let (paddr, trap) = sv48_read(&mut mmu, &csrs, &mut tc, SV48_VA);
+    assert!(trap.is_none(), "Trap: {trap:?}");
+    assert_eq!(paddr, (target_ppn << 12) | 0x234);
+}
+
+#[test]
+fn sv48_gigapage_and_terapage_leaves() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_sv48();
+    let bus = &mut tc.cpu.bus.bus;
+
+    // Gigapage: leaf at level 2, PPN aligned to 2^18 pages.
+    let l2 = ROOT_PPN + 1;
+    let giga_ppn = 3 << 18;
+    write_pte(bus, ROOT_PPN, vpn_idx(SV48_VA, 3), make_pte(l2, 0));
+    write_pte(bus, l2, vpn_idx(SV48_VA, 2), make_pte(giga_ppn, R | A));
+    let (paddr, trap) = sv48_read(&mut mmu, &csrs, &mut tc, SV48_VA);
+    assert!(trap.is_none(), "Trap: {trap:?}");
+    assert_eq!(paddr, (giga_ppn << 12) | (SV48_VA & 0x3FFF_FFFF));
+
+    // Terapage: leaf at level 3, PPN aligned to 2^27 pages.
+    let tera_va = 0x0000_0123_4567_8ABC;
+    let tera_ppn = 1 << 27;
+    write_pte(
+        &mut tc.cpu.bus.bus,
+        ROOT_PPN,
+        vpn_idx(tera_va, 3),
+        make_pte(tera_ppn, R | A),
+    );
+    let (paddr, trap) = sv48_read(&mut mmu, &csrs, &mut tc, tera_va);
+    assert!(trap.is_none(), "Trap: {trap:?}");
+    assert_eq!(paddr, (tera_ppn << 12) | (tera_va & 0x7F_FFFF_FFFF));
+}
+
+#[test]
+fn sv48_misaligned_terapage_faults() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_sv48();
+    let va = 0x0000_0123_4567_8ABC;
+    write_pte(
+        &mut tc.cpu.bus.bus,
+        ROOT_PPN,
+        vpn_idx(va, 3),
+        make_pte((1 << 27) | 1, R | A),
+    );
+    let (_, trap) = sv48_read(&mut mmu, &csrs, &mut tc, va);
+    assert!(
+        matches!(trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {trap:?}"
+    );
+}
+
+#[test]
+fn sv48_canonical_check_uses_bit_47() {
+    let (mut mmu, csrs, mut tc) = setup_mmu_sv48();
+    // Bit 38 is an ordinary VPN bit under SV48 (unmapped, so a page fault)...
+    let (_, trap) = sv48_read(&mut mmu, &csrs, &mut tc, 1 << 38);
+    assert!(
+        matches!(trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {trap:?}"
+    );
+    // ...while bit 47 set without sign extension is non-canonical.
+    let (_, trap) = sv48_read(&mut mmu, &csrs, &mut tc, 1 << 47);
+    assert!(
+        matches!(trap, Some(Trap::LoadAccessFault(_))),
+        "Trap: {trap:?}"
+    );
+    let (_, trap) = sv48_read(&mut mmu, &csrs, &mut tc, 0x7FFF_8000_0000_0000);
+    assert!(
+        matches!(trap, Some(Trap::LoadAccessFault(_))),
+        "Trap: {trap:?}"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 11. A/D Update Policy
+// ══════════════════════════════════════════════════════════
+
+/// 4KB page at VA 0x4000_1000 mapped through a full three-level walk.
+const AD_VA: u64 = 0x4000_1000;
+const AD_L0_PPN: u64 = ROOT_PPN + 2;
+
+/// Maps `AD_VA` onto `ROOT_PPN + 10` with `perms`, returning the leaf PTE address.
+fn map_ad_page(bus: &mut Bus, perms: u64) -> u64 {
+    let l1 = ROOT_PPN + 1;
+    write_pte(bus, ROOT_PPN, vpn_idx(AD_VA, 2), make_pte(l1, 0));
+    write_pte(bus, l1, vpn_idx(AD_VA, 1), make_pte(AD_L0_PPN, 0));
+    write_pte(
+        bus,
+        AD_L0_PPN,
+        vpn_idx(AD_VA, 0),
+        make_pte(ROOT_PPN + 10, perms),
+    );
+    (AD_L0_PPN << 12) + vpn_idx(AD_VA, 0) * 8
+}
+
+fn translate_ad(mmu: &mut Mmu, csrs: &Csrs, bus: &mut Bus, access: AccessType) -> Option<Trap> {
+    mmu.translate(
+        VirtAddr::new(AD_VA),
+        access,
+        PrivilegeMode::Supervisor,
+        csrs,
+        bus,
+    )
+    .trap
+}
+
+#[test]
+fn hardware_ad_read_sets_accessed_only() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let pte_addr = map_ad_page(bus, R | W);
+
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Read).is_none());
+    let pte = bus.read_u64(pte_addr);
+    assert_eq!(pte & A, A, "read sets A");
+    assert_eq!(pte & D, 0, "read leaves D clear");
+
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Write).is_none());
+    assert_eq!(bus.read_u64(pte_addr) & D, D, "first store sets D");
+}
+
+#[test]
+fn svade_faults_on_clear_accessed_bit() {
+    use riscv_core::config::AdUpdate;
+
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    mmu.set_ad_update(AdUpdate::Fault);
+    let bus = &mut tc.cpu.bus.bus;
+    let pte_addr = map_ad_page(bus, R | W);
+
+    let trap = translate_ad(&mut mmu, &csrs, bus, AccessType::Read);
+    assert!(
+        matches!(trap, Some(Trap::LoadPageFault(AD_VA))),
+        "Trap: {trap:?}"
+    );
+    assert_eq!(bus.read_u64(pte_addr) & A, 0, "PTE is left untouched");
+}
+
+#[test]
+fn svade_faults_on_store_to_clean_page() {
+    use riscv_core::config::AdUpdate;
+
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    mmu.set_ad_update(AdUpdate::Fault);
+    let bus = &mut tc.cpu.bus.bus;
+    map_ad_page(bus, R | W | A);
+
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Read).is_none());
+    // The cached clean entry lacks W, so the store re-walks and faults on D=0.
+    let trap = translate_ad(&mut mmu, &csrs, bus, AccessType::Write);
+    assert!(
+        matches!(trap, Some(Trap::StorePageFault(AD_VA))),
+        "Trap: {trap:?}"
+    );
+
+    let mut tc2 = TestContext::new().with_memory(MEM_SIZE, MEM_BASE);
+    let bus = &mut tc2.cpu.bus.bus;
+    map_ad_page(bus, R | W | A | D);
+    mmu.itlb.flush();
+    mmu.dtlb.flush();
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Write).is_none());
+}
+
+#[test]
+fn ad_update_is_applied_from_config() {
+    use riscv_core::config::{AdUpdate, Config};
+
+    let mut config = Config::default();
+    config.memory.ad_update = AdUpdate::Fault;
+    let mut tc = TestContext::from_config(&config).with_memory(MEM_SIZE, MEM_BASE);
+    let csrs = setup_mmu().1;
+    map_ad_page(&mut tc.cpu.bus.bus, R | W);
+    let trap = translate_ad(
+        &mut tc.cpu.mmu,
+        &csrs,
+        &mut tc.cpu.bus.bus,
+        AccessType::Read,
+    );
+    assert!(
+        matches!(trap, Some(Trap::LoadPageFault(_))),
+        "Trap: {trap:?}"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 12. Translation Statistics
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn tlb_counters_track_hits_misses_and_walks() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    map_ad_page(bus, R | W | X | A | D);
+
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Read).is_none());
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Write).is_none());
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Fetch).is_none());
+    assert!(translate_ad(&mut mmu, &csrs, bus, AccessType::Fetch).is_none());
+
+    let s = mmu.stats;
+    assert_eq!((s.dtlb_hits, s.dtlb_misses), (1, 1));
+    assert_eq!((s.itlb_hits, s.itlb_misses), (1, 1));
+    assert_eq!(s.page_walks, 2, "one walk per TLB miss");
+}
+
+#[test]
+fn untranslated_accesses_are_not_counted() {
+    let (mut mmu, csrs, mut tc) = setup_mmu();
+    let bus = &mut tc.cpu.bus.bus;
+    let vaddr = VirtAddr::new(AD_VA);
+
+    mmu.translate(vaddr, AccessType::Read, PrivilegeMode::Machine, &csrs, bus);
+    mmu.translate(vaddr, AccessType::Fetch, PrivilegeMode::Machine, &csrs, bus);
+
+    assert_eq!(mmu.stats, Default::default());
+}
+
+#[test]
+fn cpu_stats_mirror_mmu_counters() {
+    let (_, csrs, mut tc) = setup_mmu();
+    tc.cpu.direct_mode = false;
+    tc.cpu.csrs = csrs;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    map_ad_page(&mut tc.cpu.bus.bus, R | W | A | D);
+
+    tc.cpu.translate(VirtAddr::new(AD_VA), AccessType::Read, 8);
+    tc.cpu.translate(VirtAddr::new(AD_VA), AccessType::Read, 8);
+
+    assert_eq!(tc.cpu.stats.dtlb_misses, 1);
+    assert_eq!(tc.cpu.stats.dtlb_hits, 1);
+    assert_eq!(tc.cpu.stats.page_walks, 1);
+    assert_eq!(tc.cpu.stats.itlb_hits + tc.cpu.stats.itlb_misses, 0);
+}
@@ -0,0 +1,192 @@
+//! Software-Managed TLB Tests.
+//!
+//! Verifies `memory.tlb_refill = "Software"`:
+//! - A TLB miss raises a TLB-miss exception instead of walking the page table
+//! - `stlbva`/`stlbw` install and remove translations
+//! - A guest handler refills the TLB and the faulting access then succeeds
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::common::{AccessType, Trap, VirtAddr};
+use riscv_core::config::{Config, TlbRefill};
+use riscv_core::core::arch::csr::{self, Csrs};
+use riscv_core::core::arch::mode::PrivilegeMode;
+use riscv_core::core::units::mmu::Mmu;
+
+// ══════════════════════════════════════════════════════════
+// Helpers
+// ══════════════════════════════════════════════════════════
+
+const BASE: u64 = 0x8000_0000;
+
+// PTE bits
+const V: u64 = 1 << 0;
+const R: u64 = 1 << 1;
+const W: u64 = 1 << 2;
+const X: u64 = 1 << 3;
+const A: u64 = 1 << 6;
+const D: u64 = 1 << 7;
+
+fn soft_mmu() -> (Mmu, Csrs, TestContext) {
+    let mut mmu = Mmu::new(8);
+    mmu.set_refill(TlbRefill::Software);
+    let mut csrs = Csrs::default();
+    csrs.write(csr::SATP, csr::SATP_MODE_SV39 << 60);
+    (mmu, csrs, TestContext::new().with_memory(0x1000, BASE))
+}
+
+fn translate(
+    mmu: &mut Mmu,
+    csrs: &Csrs,
+    tc: &mut TestContext,
+    va: u64,
+    access: AccessType,
+) -> Result<u64, Trap> {
+    let res = mmu.translate(
+        VirtAddr::new(va),
+        access,
+        PrivilegeMode::Supervisor,
+        csrs,
+        &mut tc.cpu.bus.bus,
+    );
+    match res.trap {
+        Some(trap) => Err(trap),
+        None => Ok(res.paddr.val()),
+    }
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. MMU
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn miss_raises_tlb_miss_per_access_type() {
+    let (mut mmu, csrs, mut tc) = soft_mmu();
+    let va = 0x4000_1000;
+    // Root PPN 0 is never read: there is no walker.
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Fetch),
+        Err(Trap::InstructionTlbMiss(va))
+    );
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Read),
+        Err(Trap::LoadTlbMiss(va))
+    );
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Write),
+        Err(Trap::StoreTlbMiss(va))
+    );
+}
+
+#[test]
+fn written_entry_translates_and_checks_permissions() {
+    let (mut mmu, csrs, mut tc) = soft_mmu();
+    let va = 0x4000_1234;
+    let ppn = 0x80042;
+    mmu.write_tlb(va, (ppn << 10) | V | R | A, 0);
+
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Read),
+        Ok((ppn << 12) | 0x234)
+    );
+    // Read-only: a store faults rather than missing again.
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Write),
+        Err(Trap::StorePageFault(va))
+    );
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Fetch),
+        Err(Trap::InstructionPageFault(va))
+    );
+
+    // Clearing V removes the mapping.
+    mmu.write_tlb(va, 0, 0);
+    assert_eq!(
+        translate(&mut mmu, &csrs, &mut tc, va, AccessType::Read),
+        Err(Trap::LoadTlbMiss(va))
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Guest Refill Handler
+// ══════════════════════════════════════════════════════════
+
+/// `csrrs rd, csr, x0`.
+fn csrr(rd: u32, csr: u32) -> u32 {
+    (csr << 20) | (2 << 12) | (rd << 7) | 0x73
+}
+
+/// `csrrw x0, csr, rs1`.
+fn csrw(csr: u32, rs1: u32) -> u32 {
+    (csr << 20) | (rs1 << 15) | (1 << 12) | 0x73
+}
+
+/// `slli`/`srli rd, rs1, shamt`.
+fn shift(funct3: u32, rd: u32, rs1: u32, shamt: i32) -> u32 {
+    InstructionBuilder::new()
+        .opcode(0x13)
+        .funct3(funct3)
+        .rd(rd)
+        .rs1(rs1)
+        .imm(shamt)
+        .build()
+}
+
+const HANDLER: u64 = BASE + 0x100;
+const DATA: u64 = BASE + 0x2000;
+const MRET: u32 = 0x3020_0073;
+
+#[test]
+fn handler_refills_and_faulting_load_retries() {
+    let mut config = Config::default();
+    config.general.direct_mode = false;
+    config.memory.tlb_refill = TlbRefill::Software;
+    let b = InstructionBuilder::new;
+
+    // S-mode guest, identity-mapped on demand: load from another page, then spin.
+    let guest = [
+        b().auipc(6, ((DATA - BASE) >> 12) as i32).build(),
+        b().ld(7, 6, 8).build(),
+        b().jal(0, 0).build(),
+    ];
+    // M-mode miss handler: map the faulting page 1:1 with RWX, count, retry.
+    let handler = [
+        csrr(5, csr::MTVAL),
+        csrw(csr::STLBVA, 5),
+        shift(5, 5, 5, 12),
+        shift(1, 5, 5, 10),
+        b().ori(5, 5, (V | R | W | X | A | D) as i32).build(),
+        csrw(csr::STLBW, 5),
+        csrr(18, csr::MCAUSE),
+        b().addi(9, 9, 1).build(),
+        MRET,
+    ];
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x4000, BASE)
+        .load_program(HANDLER, &handler)
+        .load_program(BASE, &guest);
+    tc.cpu.bus.bus.write_u64(DATA + 8, 0xDEAD_BEEF_CAFE);
+    tc.cpu.direct_mode = false;
+    tc.cpu.privilege = PrivilegeMode::Supervisor;
+    tc.cpu.csrs.mtvec = HANDLER;
+    tc.cpu.csr_write(csr::SATP, csr::SATP_MODE_SV39 << 60);
+    tc.cpu.csrs.mstatus |= 1 << 11; // MPP = S
+
+    for _ in 0..300 {
+        tc.cpu.tick().unwrap();
+    }
+
+    assert_eq!(
+        tc.cpu.privilege,
+        PrivilegeMode::Supervisor,
+        "back in the guest"
+    );
+    assert_eq!(tc.get_reg(7), 0xDEAD_BEEF_CAFE, "retried load succeeds");
+    assert_eq!(tc.get_reg(9), 2, "one fetch miss and one load miss");
+    assert_eq!(tc.get_reg(18), 25, "last miss was a load TLB miss");
+    assert_eq!(
+        tc.cpu.stats.traps_by_cause.get("InstructionTlbMiss"),
+        Some(&1)
+    );
+    assert_eq!(tc.cpu.stats.traps_by_cause.get("LoadTlbMiss"), Some(&1));
+}
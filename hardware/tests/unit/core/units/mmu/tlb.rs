@@ -6,6 +6,7 @@
 //! - Aliasing eviction (same index)
 //! - Capacity and full associativity (or lack thereof - TLB is direct mapped)
 //! - Flushing
+//! - ASID tagging, global entries, and selective SFENCE.VMA
 
 use riscv_core::core::units::mmu::tlb::Tlb;
 
@@ -19,6 +20,7 @@ const PTE_R: u64 = 1 << 1;
 const PTE_W: u64 = 1 << 2;
 const PTE_X: u64 = 1 << 3;
 const PTE_U: u64 = 1 << 4;
+const PTE_G: u64 = 1 << 5;
 
 /// Helper to create a PTE with specific permissions
 fn make_pte(r: bool, w: bool, x: bool, u: bool) -> u64 {
@@ -45,7 +47,7 @@ fn make_pte(r: bool, w: bool, x: bool, u: bool) -> u64 {
 #[test]
 fn lookup_miss_on_empty() {
     let tlb = Tlb::new(16);
-    assert_eq!(tlb.lookup(0x100), None);
+    assert_eq!(tlb.lookup(0x100, 0), None);
 }
 
 #[test]
@@ -55,9 +57,9 @@ fn insert_and_lookup_hit() {
     let ppn = 0x123;
     let pte = make_pte(true, false, true, false); // R=1, W=0, X=1, U=0
 
-    tlb.insert(vpn, ppn, pte);
+    tlb.insert(vpn, ppn, pte, 0, 0);
 
-    match tlb.lookup(vpn) {
+    match tlb.lookup(vpn, 0) {
         Some((found_ppn, r, w, x, u)) => {
             assert_eq!(found_ppn, ppn);
             assert_eq!(r, true);
@@ -78,23 +80,23 @@ fn permissions_extracted_correctly() {
     let mut tlb = Tlb::new(16);
 
     // R-only
-    tlb.insert(0x10, 0x100, make_pte(true, false, false, false));
-    let (_, r, w, x, u) = tlb.lookup(0x10).unwrap();
+    tlb.insert(0x10, 0x100, make_pte(true, false, false, false), 0, 0);
+    let (_, r, w, x, u) = tlb.lookup(0x10, 0).unwrap();
     assert_eq!((r, w, x, u), (true, false, false, false));
 
     // RW
-    tlb.insert(0x11, 0x101, make_pte(true, true, false, false));
-    let (_, r, w, x, u) = tlb.lookup(0x11).unwrap();
+    tlb.insert(0x11, 0x101, make_pte(true, true, false, false), 0, 0);
+    let (_, r, w, x, u) = tlb.lookup(0x11, 0).unwrap();
     assert_eq!((r, w, x, u), (true, true, false, false));
 
     // RX
-    tlb.insert(0x12, 0x102, make_pte(true, false, true, false));
-    let (_, r, w, x, u) = tlb.lookup(0x12).unwrap();
+    tlb.insert(0x12, 0x102, make_pte(true, false, true, false), 0, 0);
+    let (_, r, w, x, u) = tlb.lookup(0x12, 0).unwrap();
     assert_eq!((r, w, x, u), (true, false, true, false));
 
     // User bit
-    tlb.insert(0x13, 0x103, make_pte(true, true, true, true));
-    let (_, _, _, _, u) = tlb.lookup(0x13).unwrap();
+    tlb.insert(0x13, 0x103, make_pte(true, true, true, true), 0, 0);
+    let (_, _, _, _, u) = tlb.lookup(0x13, 0).unwrap();
     assert!(u);
 }
 
@@ -111,15 +113,15 @@ fn aliasing_eviction() {
     let vpn1 = 0;
     let vpn2 = size as u64;
 
-    tlb.insert(vpn1, 0x100, PTE_V | PTE_R);
-    assert!(tlb.lookup(vpn1).is_some());
+    tlb.insert(vpn1, 0x100, PTE_V | PTE_R, 0, 0);
+    assert!(tlb.lookup(vpn1, 0).is_some());
 
-    tlb.insert(vpn2, 0x200, PTE_V | PTE_R);
-    assert!(tlb.lookup(vpn2).is_some());
+    tlb.insert(vpn2, 0x200, PTE_V | PTE_R, 0, 0);
+    assert!(tlb.lookup(vpn2, 0).is_some());
 
     // vpn1 should have been evicted
     assert_eq!(
-        tlb.lookup(vpn1),
+        tlb.lookup(vpn1, 0),
         None,
         "Old entry should be evicted by alias"
     );
@@ -131,12 +133,12 @@ fn tag_mismatch() {
     let mut tlb = Tlb::new(size);
 
     // Insert at index 0
-    tlb.insert(0, 0x100, PTE_V | PTE_R);
+    tlb.insert(0, 0x100, PTE_V | PTE_R, 0, 0);
 
     // Lookup different VPN that maps to index 0
     let alias_vpn = size as u64;
     assert_eq!(
-        tlb.lookup(alias_vpn),
+        tlb.lookup(alias_vpn, 0),
         None,
         "Tag mismatch should result in miss"
     );
@@ -149,16 +151,16 @@ fn tag_mismatch() {
 #[test]
 fn flush_clears_entries() {
     let mut tlb = Tlb::new(16);
-    tlb.insert(0x1, 0x100, PTE_V | PTE_R);
-    tlb.insert(0x2, 0x200, PTE_V | PTE_R);
+    tlb.insert(0x1, 0x100, PTE_V | PTE_R, 0, 0);
+    tlb.insert(0x2, 0x200, PTE_V | PTE_R, 0, 0);
 
-    assert!(tlb.lookup(0x1).is_some());
-    assert!(tlb.lookup(0x2).is_some());
+    assert!(tlb.lookup(0x1, 0).is_some());
+    assert!(tlb.lookup(0x2, 0).is_some());
 
     tlb.flush();
 
-    assert_eq!(tlb.lookup(0x1), None);
-    assert_eq!(tlb.lookup(0x2), None);
+    assert_eq!(tlb.lookup(0x1, 0), None);
+    assert_eq!(tlb.lookup(0x2, 0), None);
 }
 
 // ══════════════════════════════════════════════════════════
@@ -171,14 +173,82 @@ fn fill_capacity() {
     let mut tlb = Tlb::new(size);
 
     for i in 0..size {
-        tlb.insert(i as u64, 0x1000 + i as u64, PTE_V | PTE_R);
+        tlb.insert(i as u64, 0x1000 + i as u64, PTE_V | PTE_R, 0, 0);
     }
 
     for i in 0..size {
         assert!(
-            tlb.lookup(i as u64).is_some(),
+            tlb.lookup(i as u64, 0).is_some(),
             "Entry {} should be present",
             i
         );
     }
 }
+
+// ══════════════════════════════════════════════════════════
+// 6. ASIDs and Selective Fences
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn lookup_requires_matching_asid() {
+    let mut tlb = Tlb::new(16);
+    tlb.insert(0x5, 0x100, PTE_V | PTE_R, 3, 0);
+
+    assert!(tlb.lookup(0x5, 3).is_some());
+    assert_eq!(tlb.lookup(0x5, 4), None, "other address spaces miss");
+}
+
+#[test]
+fn global_entry_matches_every_asid() {
+    let mut tlb = Tlb::new(16);
+    tlb.insert(0x5, 0x100, PTE_V | PTE_R | PTE_G, 3, 0);
+
+    assert!(tlb.lookup(0x5, 3).is_some());
+    assert!(tlb.lookup(0x5, 9).is_some());
+}
+
+#[test]
+fn sfence_asid_evicts_only_that_asid() {
+    let mut tlb = Tlb::new(16);
+    tlb.insert(0x1, 0x100, PTE_V | PTE_R, 1, 0);
+    tlb.insert(0x2, 0x200, PTE_V | PTE_R, 1, 0);
+    tlb.insert(0x3, 0x300, PTE_V | PTE_R, 2, 0);
+    tlb.insert(0x4, 0x400, PTE_V | PTE_R | PTE_G, 1, 0);
+
+    tlb.sfence(None, Some(1));
+
+    assert_eq!(tlb.lookup(0x1, 1), None);
+    assert_eq!(tlb.lookup(0x2, 1), None);
+    assert!(tlb.lookup(0x3, 2).is_some(), "ASID 2 survives");
+    assert!(tlb.lookup(0x4, 1).is_some(), "global entry survives");
+}
+
+#[test]
+fn sfence_address_and_asid_spares_global_and_other_asids() {
+    let mut tlb = Tlb::new(16);
+    tlb.insert(0x1, 0x100, PTE_V | PTE_R, 1, 0);
+    tlb.insert(0x2, 0x200, PTE_V | PTE_R, 1, 0);
+    tlb.insert(0x4, 0x400, PTE_V | PTE_R | PTE_G, 1, 0);
+
+    tlb.sfence(Some(0x1), Some(1));
+    tlb.sfence(Some(0x4), Some(1));
+
+    assert_eq!(tlb.lookup(0x1, 1), None);
+    assert!(tlb.lookup(0x2, 1).is_some(), "other page survives");
+    assert!(tlb.lookup(0x4, 1).is_some(), "global page survives");
+}
+
+#[test]
+fn sfence_address_evicts_every_base_page_of_superpage() {
+    let mut tlb = Tlb::new(16);
+    // Two base pages of one 2MB megapage (span of 512 VPNs).
+    tlb.insert(0x201, 0x1001, PTE_V | PTE_R, 1, 0x1FF);
+    tlb.insert(0x202, 0x1002, PTE_V | PTE_R, 1, 0x1FF);
+    tlb.insert(0x403, 0x3003, PTE_V | PTE_R, 1, 0x1FF);
+
+    tlb.sfence(Some(0x3FF), None);
+
+    assert_eq!(tlb.lookup(0x201, 1), None);
+    assert_eq!(tlb.lookup(0x202, 1), None);
+    assert!(tlb.lookup(0x403, 1).is_some(), "next megapage survives");
+}
@@ -2,7 +2,7 @@
 //!
 //! Verifies that the disassembler correctly converts common instruction
 //! encodings to human-readable mnemonics for RV64I, RV64M, RV64A,
-//! RV64F/D, and privileged instructions.
+//! RV64F/D, Zba, Zbb, Zbc, and privileged instructions.
 
 use riscv_core::isa::disasm::disassemble;
 
@@ -299,3 +299,44 @@ fn disasm_unknown() {
         text
     );
 }
+
+// ══════════════════════════════════════════════════════════
+// 13. Zbc carry-less multiply
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn disasm_clmul_variants() {
+    // funct7=0x05, funct3 = 1 / 3 / 2
+    assert!(disassemble(0x0AC5_9533).starts_with("clmul "));
+    assert!(disassemble(0x0AC5_B533).starts_with("clmulh "));
+    assert!(disassemble(0x0AC5_A533).starts_with("clmulr "));
+}
+
+// ══════════════════════════════════════════════════════════
+// 14. Zbb basic bit-manipulation
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn disasm_zbb_variants() {
+    assert_eq!(disassemble(0x6005_9513), "clz a0, a1");
+    assert_eq!(disassemble(0x6025_951B), "cpopw a0, a1");
+    assert_eq!(disassemble(0x6045_9513), "sext.b a0, a1");
+    assert_eq!(disassemble(0x2875_D513), "orc.b a0, a1");
+    assert_eq!(disassemble(0x6B85_D513), "rev8 a0, a1");
+    assert_eq!(disassemble(0x0AC5_C533), "min a0, a1, a2");
+    assert_eq!(disassemble(0x0AC5_F533), "maxu a0, a1, a2");
+    assert_eq!(disassemble(0x0805_C53B), "zext.h a0, a1");
+}
+
+// ══════════════════════════════════════════════════════════
+// 15. Zba address generation
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn disasm_zba_variants() {
+    assert_eq!(disassemble(0x20C5_A533), "sh1add a0, a1, a2");
+    assert_eq!(disassemble(0x20C5_C533), "sh2add a0, a1, a2");
+    assert_eq!(disassemble(0x20C5_E533), "sh3add a0, a1, a2");
+    assert_eq!(disassemble(0x08C5_853B), "add.uw a0, a1, a2");
+    assert_eq!(disassemble(0x0835_951B), "slli.uw a0, a1, 3");
+}
@@ -10,6 +10,12 @@
 /// and other shared data structures used across the emulator.
 pub mod common;
 
+/// Unit tests for configuration loading.
+///
+/// This module verifies that `Config::validate` reports every broken invariant
+/// and that unknown enum names are rejected instead of silently defaulted.
+pub mod config;
+
 /// Core definitions and fundamental logic for the unit system.
 ///
 /// This module provides the base structures, traits, and constants that form
@@ -30,6 +36,12 @@ pub mod isa;
 /// and memory controllers.
 pub mod soc;
 
+/// Unit tests for simulation utilities.
+///
+/// This module covers helpers used around the simulated machine, such as
+/// symbol maps for annotating traces and crash reports.
+pub mod sim;
+
 /// Unit tests for simulation statistics verification.
 ///
 /// This module contains tests that ensure the [`SimStats`](riscv_core::stats::SimStats) structure
@@ -0,0 +1,217 @@
+//! GDB Stub Unit Tests.
+//!
+//! Drives the stub over a loopback TCP connection the way `gdb` does after
+//! `target remote`:
+//! 1. Registers and memory are readable and writable while halted, and a read of
+//!    any length is answered within the packet size
+//! 2. A breakpoint stops the hart before the instruction executes
+//! 3. A single step executes exactly one instruction, from a new address if given
+//! 4. Breakpoints can be removed, and a kill ends the session
+
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+use riscv_core::sim::gdbstub::{GdbStub, SessionEnd};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+const BASE: u64 = 0x8000_0000;
+
+/// `addi rd, x0, imm`.
+fn addi(rd: u32, imm: u32) -> u32 {
+    (imm << 20) | (rd << 7) | 0x13
+}
+
+/// Minimal debugger side of the remote protocol.
+struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    /// Sends one packet and returns the stub's reply.
+    fn request(&mut self, data: &str) -> String {
+        let sum = data.bytes().fold(0u8, |s, b| s.wrapping_add(b));
+        write!(self.stream, "${}#{:02x}", data, sum).unwrap();
+        assert_eq!(self.byte(), b'+', "stub acknowledges {data:?}");
+        if data == "k" {
+            return String::new();
+        }
+        while self.byte() != b'$' {}
+        let mut reply = Vec::new();
+        loop {
+            match self.byte() {
+                b'#' => break,
+                b => reply.push(b),
+            }
+        }
+        let mut checksum = [0u8; 2];
+        self.stream.read_exact(&mut checksum).unwrap();
+        self.stream.write_all(b"+").unwrap();
+        String::from_utf8(reply).unwrap()
+    }
+
+    fn byte(&mut self) -> u8 {
+        let mut b = [0u8; 1];
+        self.stream.read_exact(&mut b).unwrap();
+        b[0]
+    }
+}
+
+/// Little-endian hex of a 64-bit register value, as GDB transfers it.
+fn reg_hex(val: u64) -> String {
+    val.to_le_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Runs `session` as the debugger against a CPU holding four `addi`s at `BASE`.
+///
+/// Returns how the session ended and the final CPU.
+fn debug_session(session: impl FnOnce(&mut Client) + Send + 'static) -> (SessionEnd, TestContext) {
+    debug_session_with(&Config::default(), session)
+}
+
+/// Runs `session` like [`debug_session`], on a CPU built from `config`.
+fn debug_session_with(
+    config: &Config,
+    session: impl FnOnce(&mut Client) + Send + 'static,
+) -> (SessionEnd, TestContext) {
+    let program = [addi(1, 5), addi(2, 7), addi(3, 9), addi(4, 11)];
+    let mut tc = TestContext::from_config(config)
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = thread::spawn(move || {
+        let mut client = Client {
+            stream: TcpStream::connect(addr).unwrap(),
+        };
+        session(&mut client);
+    });
+
+    let end = GdbStub::accept(&listener)
+        .unwrap()
+        .run(&mut tc.cpu)
+        .unwrap();
+    client.join().unwrap();
+    (end, tc)
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Registers and memory
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn halted_target_reports_registers_and_memory() {
+    let (end, mut tc) = debug_session(|gdb| {
+        assert_eq!(gdb.request("qAttached"), "1");
+        assert_eq!(gdb.request("?"), "S05");
+
+        let regs = gdb.request("g");
+        assert_eq!(regs.len(), 33 * 16, "x0-x31 and pc");
+        assert_eq!(&regs[32 * 16..], reg_hex(BASE));
+        assert_eq!(gdb.request("p20"), reg_hex(BASE), "pc is register 32");
+
+        let first = addi(1, 5).to_le_bytes();
+        let hex: String = first.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(gdb.request(&format!("m{:x},4", BASE)), hex);
+        assert_eq!(gdb.request("m10,4"), "E14", "unmapped memory");
+        let whole = gdb.request(&format!("m{:x},ffffffffffffffff", BASE));
+        assert_eq!(whole.len(), 2 * 0x1000, "read stops at the end of memory");
+
+        assert_eq!(gdb.request(&format!("P5={}", reg_hex(0x1234))), "OK");
+        assert_eq!(gdb.request("p5"), reg_hex(0x1234));
+        assert_eq!(gdb.request(&format!("M{:x},2:beef", BASE + 0x100)), "OK");
+        gdb.request("k");
+    });
+    assert!(matches!(end, SessionEnd::Killed));
+    assert_eq!(tc.get_reg(5), 0x1234);
+    assert_eq!(tc.cpu.bus.bus.read_u16(BASE + 0x100), 0xEFBE);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Breakpoints
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn breakpoint_stops_before_instruction() {
+    let (_, tc) = debug_session(|gdb| {
+        assert_eq!(gdb.request(&format!("Z0,{:x},4", BASE + 8)), "OK");
+        assert_eq!(gdb.request("c"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 8));
+        assert_eq!(gdb.request("p1"), reg_hex(5));
+        assert_eq!(gdb.request("p2"), reg_hex(7));
+        assert_eq!(gdb.request("p3"), reg_hex(0), "breakpoint not executed");
+        gdb.request("k");
+    });
+    assert_eq!(tc.get_reg(3), 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Single step
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn step_executes_one_instruction() {
+    let (_, tc) = debug_session(|gdb| {
+        assert_eq!(gdb.request("s"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 4));
+        assert_eq!(gdb.request("p1"), reg_hex(5));
+        assert_ne!(gdb.request("p2"), reg_hex(7), "second addi not executed");
+
+        assert_eq!(gdb.request("s"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 8));
+        assert_eq!(gdb.request("p2"), reg_hex(7));
+        gdb.request("k");
+    });
+    assert_eq!(tc.get_reg(3), 0);
+}
+
+#[test]
+fn step_at_address_resumes_there() {
+    let mut config = Config::default();
+    config.general.verify_retire_order = true;
+    let (end, tc) = debug_session_with(&config, |gdb| {
+        assert_eq!(gdb.request("s"), "S05");
+        assert_eq!(gdb.request(&format!("s{:x}", BASE + 12)), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 16));
+        assert_eq!(gdb.request("p4"), reg_hex(11));
+        gdb.request("k");
+    });
+    assert!(matches!(end, SessionEnd::Killed), "no retire-order fault");
+    assert_eq!(tc.get_reg(3), 0, "skipped instructions not executed");
+}
+
+#[test]
+fn step_from_breakpoint_executes_it() {
+    let (_, tc) = debug_session(|gdb| {
+        gdb.request(&format!("Z0,{:x},4", BASE + 4));
+        assert_eq!(gdb.request("c"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 4));
+        assert_eq!(gdb.request("s"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 8));
+        assert_eq!(gdb.request("p2"), reg_hex(7));
+        gdb.request("k");
+    });
+    assert_eq!(tc.get_reg(2), 7);
+}
+
+// ══════════════════════════════════════════════════════════
+// 4. Removing breakpoints
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn removed_breakpoint_no_longer_stops() {
+    let (_, tc) = debug_session(|gdb| {
+        gdb.request(&format!("Z0,{:x},4", BASE + 4));
+        gdb.request(&format!("Z0,{:x},4", BASE + 12));
+        assert_eq!(gdb.request(&format!("z0,{:x},4", BASE + 4)), "OK");
+        assert_eq!(gdb.request("c"), "S05");
+        assert_eq!(gdb.request("p20"), reg_hex(BASE + 12));
+        gdb.request("k");
+    });
+    assert_eq!(tc.get_reg(3), 9);
+    assert_eq!(tc.get_reg(4), 0);
+}
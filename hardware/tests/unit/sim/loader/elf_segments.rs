@@ -0,0 +1,144 @@
+//! ELF Segment Loading Unit Tests.
+//!
+//! Verifies that `load_elf` places program segments:
+//! 1. Each `PT_LOAD` segment's bytes land at its physical address
+//! 2. Memory past the file size (BSS) is zero-filled
+//! 3. Non-loadable program headers are skipped
+//! 4. A segment outside every device range is rejected
+//! 5. Overflowing header offsets and sizes, and file sizes past the memory size,
+//!    are rejected without panicking
+
+use crate::common::builder::elf::ElfBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::sim::loader::load_elf;
+
+const RAM_BASE: u64 = 0x8000_0000;
+const RAM_SIZE: usize = 0x1_0000;
+
+fn ctx() -> TestContext {
+    TestContext::new().with_memory(RAM_SIZE, RAM_BASE)
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. Segment placement
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn load_segments_land_at_physical_addresses() {
+    let mut tc = ctx();
+    let text = [0x13, 0x05, 0xA0, 0x02]; // addi a0, zero, 42
+    let data = [0xEF, 0xBE, 0xAD, 0xDE];
+    let elf = ElfBuilder::new(RAM_BASE)
+        .load(RAM_BASE, &text, text.len() as u64)
+        .load(RAM_BASE + 0x2000, &data, data.len() as u64)
+        .build();
+
+    load_elf(&mut tc.cpu.bus.bus, &elf).unwrap();
+
+    assert_eq!(tc.cpu.bus.bus.read_u32(RAM_BASE), 0x02A0_0513);
+    assert_eq!(tc.cpu.bus.bus.read_u32(RAM_BASE + 0x2000), 0xDEAD_BEEF);
+    assert_eq!(
+        tc.cpu.bus.bus.read_u32(RAM_BASE + 0x1000),
+        0,
+        "gap untouched"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. BSS
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn bss_is_zero_filled() {
+    let mut tc = ctx();
+    for off in 0..0x20 {
+        tc.cpu.bus.bus.write_u8(RAM_BASE + 0x100 + off, 0xFF);
+    }
+    let elf = ElfBuilder::new(RAM_BASE)
+        .load(RAM_BASE + 0x100, &[1, 2, 3, 4], 0x20)
+        .build();
+
+    load_elf(&mut tc.cpu.bus.bus, &elf).unwrap();
+
+    assert_eq!(tc.cpu.bus.bus.read_u32(RAM_BASE + 0x100), 0x0403_0201);
+    for off in 4..0x20 {
+        assert_eq!(
+            tc.cpu.bus.bus.read_u8(RAM_BASE + 0x100 + off),
+            0,
+            "byte {off}"
+        );
+    }
+    assert_eq!(
+        tc.cpu.bus.bus.read_u8(RAM_BASE + 0x120),
+        0,
+        "nothing written past memsz"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. Non-loadable headers
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn non_load_segments_are_skipped() {
+    let mut tc = ctx();
+    let elf = ElfBuilder::new(RAM_BASE)
+        .note(RAM_BASE + 0x400, &[0xAA; 8])
+        .build();
+
+    load_elf(&mut tc.cpu.bus.bus, &elf).unwrap();
+
+    assert_eq!(tc.cpu.bus.bus.read_u64(RAM_BASE + 0x400), 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 4. Unbacked segments
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn segment_outside_memory_is_rejected() {
+    let mut tc = ctx();
+    let elf = ElfBuilder::new(RAM_BASE)
+        .load(RAM_BASE + RAM_SIZE as u64 - 4, &[0; 4], 0x10)
+        .build();
+
+    let err = load_elf(&mut tc.cpu.bus.bus, &elf).unwrap_err();
+    assert!(err.contains("not backed by memory"), "{err}");
+}
+
+// ══════════════════════════════════════════════════════════
+// 5. Malformed headers
+// ══════════════════════════════════════════════════════════
+
+/// Offset of the first program header in an `ElfBuilder` image.
+const PHDR: usize = 64;
+
+/// Loads `elf` and returns the error it must produce.
+fn load_err(elf: &[u8]) -> String {
+    let mut tc = ctx();
+    load_elf(&mut tc.cpu.bus.bus, elf).unwrap_err()
+}
+
+#[test]
+fn file_size_past_memory_size_is_rejected() {
+    let elf = ElfBuilder::new(RAM_BASE).load(RAM_BASE, &[0; 8], 4).build();
+    let err = load_err(&elf);
+    assert!(err.contains("larger than memory size"), "{err}");
+}
+
+#[test]
+fn overflowing_header_fields_are_rejected() {
+    let elf = ElfBuilder::new(RAM_BASE).load(RAM_BASE, &[0; 4], 4).build();
+
+    let mut phoff = elf.clone();
+    phoff[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(load_err(&phoff).contains("truncated"));
+
+    let mut offset = elf.clone();
+    offset[PHDR + 0x08..PHDR + 0x10].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(load_err(&offset).contains("truncated"));
+
+    let mut memsz = elf;
+    memsz[PHDR + 0x28..PHDR + 0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(load_err(&memsz).contains("wraps the address space"));
+}
@@ -0,0 +1,68 @@
+//! ELF Entry Point Unit Tests.
+//!
+//! Verifies the ELF header handling of `load_elf`:
+//! 1. The header's entry point is returned and can start the CPU
+//! 2. Non-ELF, big-endian, and non-RISC-V images are rejected
+
+use crate::common::builder::elf::ElfBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::sim::loader::load_elf;
+
+const RAM_BASE: u64 = 0x8000_0000;
+
+// ══════════════════════════════════════════════════════════
+// 1. Entry point
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn entry_point_is_returned() {
+    let mut tc = TestContext::new().with_memory(0x1000, RAM_BASE);
+    let elf = ElfBuilder::new(RAM_BASE + 0x80)
+        .load(RAM_BASE, &[0; 0x100], 0x100)
+        .build();
+
+    let entry = load_elf(&mut tc.cpu.bus.bus, &elf).unwrap();
+    assert_eq!(entry, RAM_BASE + 0x80);
+}
+
+#[test]
+fn entry_point_starts_execution() {
+    let mut tc = TestContext::new().with_memory(0x1000, RAM_BASE);
+    let addi_a0_42 = 0x02A0_0513u32.to_le_bytes();
+    let elf = ElfBuilder::new(RAM_BASE + 0x40)
+        .load(RAM_BASE + 0x40, &addi_a0_42, 4)
+        .build();
+
+    tc.cpu.pc = load_elf(&mut tc.cpu.bus.bus, &elf).unwrap();
+    for _ in 0..10 {
+        tc.cpu.tick().unwrap();
+    }
+    assert_eq!(tc.get_reg(10), 42);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Header validation
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn non_elf_image_is_rejected() {
+    let mut tc = TestContext::new().with_memory(0x1000, RAM_BASE);
+    assert!(load_elf(&mut tc.cpu.bus.bus, &[0x13; 128]).is_err());
+    assert!(load_elf(&mut tc.cpu.bus.bus, b"\x7fELF").is_err());
+}
+
+#[test]
+fn big_endian_image_is_rejected() {
+    let mut tc = TestContext::new().with_memory(0x1000, RAM_BASE);
+    let mut elf = ElfBuilder::new(RAM_BASE).build();
+    elf[5] = 2; // ELFDATA2MSB
+    assert!(load_elf(&mut tc.cpu.bus.bus, &elf).is_err());
+}
+
+#[test]
+fn non_riscv_machine_is_rejected() {
+    let mut tc = TestContext::new().with_memory(0x1000, RAM_BASE);
+    let elf = ElfBuilder::new(RAM_BASE).machine(0x3E).build(); // EM_X86_64
+    let err = load_elf(&mut tc.cpu.bus.bus, &elf).unwrap_err();
+    assert!(err.contains("RISC-V"), "{err}");
+}
@@ -0,0 +1,13 @@
+//! # ELF Loader
+
+/// Unit tests for ELF segment placement.
+///
+/// This module verifies that `PT_LOAD` segments land at their physical
+/// addresses, BSS is zero-filled, and other program headers are ignored.
+pub mod elf_segments;
+
+/// Unit tests for the ELF entry point and header validation.
+///
+/// This module verifies that the entry point is returned and that images the
+/// loader cannot run are rejected with an error.
+pub mod entry_point;
@@ -0,0 +1,31 @@
+//! # Simulation Utilities
+
+/// Unit tests for symbol map loading.
+///
+/// This module verifies that `nm` listings and ELF symbol tables parse into a
+/// sorted table and that addresses resolve to the right function and offset.
+pub mod symbols;
+
+/// Unit tests for the Perfetto trace export.
+///
+/// This module verifies that the tracer emits a well-formed Chrome trace-event
+/// array with per-instruction lifetimes and periodic counter samples.
+pub mod perfetto;
+
+/// Unit tests for the ELF loader.
+///
+/// This module verifies that ELF64 executables load their segments at the
+/// right physical addresses and report their entry point.
+pub mod loader;
+
+/// Unit tests for the GDB remote stub.
+///
+/// This module verifies register and memory access, breakpoints, and single
+/// stepping over a loopback connection.
+pub mod gdbstub;
+
+/// Unit tests for direct-mode semihosting.
+///
+/// This module verifies that `ecall`s made by bare-metal programs perform
+/// console I/O, heap growth, and exit on the host.
+pub mod semihosting;
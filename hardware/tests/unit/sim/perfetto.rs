@@ -0,0 +1,133 @@
+//! Perfetto Trace Export Tests.
+//!
+//! Verifies `PerfettoTracer` driven from the cycle hook:
+//!   1. The output parses as a JSON array of trace events opened by process metadata
+//!   2. Retired instructions appear as complete events that never overlap within a lane
+//!   3. IPC, cache miss rate, and branch accuracy counters are sampled every interval
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::sim::perfetto::PerfettoTracer;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+const BASE: u64 = 0x8000_0000;
+const INTERVAL: u64 = 20;
+const CYCLES: u64 = 100;
+
+/// In-memory sink shared between the tracer and the test.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Traces `CYCLES` cycles of an `addi x1, x1, 1; jal x0, -4` loop and parses the output.
+fn traced_loop() -> Vec<Value> {
+    let b = InstructionBuilder::new;
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &[b().addi(1, 1, 1).build(), b().jal(0, -4).build()]);
+
+    let buf = SharedBuf::default();
+    let tracer = Arc::new(Mutex::new(PerfettoTracer::new(
+        Box::new(buf.clone()),
+        INTERVAL,
+    )));
+    let hook_tracer = Arc::clone(&tracer);
+    tc.cpu.set_cycle_hook(Box::new(move |cpu| {
+        hook_tracer.lock().unwrap().observe(cpu)
+    }));
+    tc.run(CYCLES);
+    tracer
+        .lock()
+        .unwrap()
+        .finish()
+        .expect("in-memory writes succeed");
+
+    let bytes = buf.0.lock().unwrap().clone();
+    serde_json::from_slice(&bytes).expect("trace must be a JSON event array")
+}
+
+/// Returns the events with phase `ph`.
+fn phase<'a>(events: &'a [Value], ph: &str) -> Vec<&'a Value> {
+    events.iter().filter(|e| e["ph"] == ph).collect()
+}
+
+#[test]
+fn trace_is_event_array_with_metadata() {
+    let events = traced_loop();
+    assert_eq!(events[0]["ph"], "M");
+    assert_eq!(events[0]["name"], "process_name");
+    for e in &events {
+        assert!(e["ph"].is_string() && e["name"].is_string(), "{e}");
+        assert_eq!(e["pid"], 0);
+    }
+    assert!(
+        phase(&events, "M")
+            .iter()
+            .any(|e| e["name"] == "thread_name" && e["args"]["name"] == "lane 0")
+    );
+}
+
+#[test]
+fn retired_instructions_are_nonoverlapping_complete_events() {
+    let events = traced_loop();
+    let complete = phase(&events, "X");
+    let retired: Vec<_> = complete.iter().filter(|e| e["cat"] == "retired").collect();
+    assert!(retired.len() >= 10, "loop retires many instructions");
+    assert!(retired.iter().any(
+        |e| e["name"].as_str().unwrap().starts_with("addi") && e["args"]["pc"] == "0x80000000"
+    ));
+
+    let mut by_lane: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+    for e in &complete {
+        let ts = e["ts"].as_u64().unwrap();
+        let dur = e["dur"].as_u64().unwrap();
+        assert!(ts + dur <= CYCLES, "{e}");
+        by_lane
+            .entry(e["tid"].as_u64().unwrap())
+            .or_default()
+            .push((ts, ts + dur));
+    }
+    for spans in by_lane.values_mut() {
+        spans.sort();
+        for pair in spans.windows(2) {
+            assert!(pair[0].1 <= pair[1].0, "lane overlap: {pair:?}");
+        }
+    }
+    for e in retired {
+        assert_eq!(e["args"]["last_stage"], "MEM/WB");
+        assert!(e["dur"].as_u64().unwrap() >= 4, "five-stage lifetime: {e}");
+    }
+}
+
+#[test]
+fn counters_are_sampled_every_interval() {
+    let events = traced_loop();
+    let counters = phase(&events, "C");
+    for name in ["IPC", "Cache miss rate", "Branch accuracy"] {
+        let ts: Vec<u64> = counters
+            .iter()
+            .filter(|e| e["name"] == name)
+            .map(|e| e["ts"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ts.len() as u64, (CYCLES - 1) / INTERVAL, "{name}");
+        assert!(
+            ts.windows(2).all(|w| w[1] - w[0] == INTERVAL),
+            "{name}: {ts:?}"
+        );
+    }
+    let last_ipc = counters.iter().rev().find(|e| e["name"] == "IPC").unwrap();
+    let ipc = last_ipc["args"]["ipc"].as_f64().unwrap();
+    assert!(ipc > 0.0 && ipc <= 1.0, "ipc {ipc}");
+}
@@ -0,0 +1,286 @@
+//! Semihosting Tests.
+//!
+//! Verifies direct-mode `ecall` servicing:
+//!   1. `write(1, buf, n)` sends the bytes to the program's standard output
+//!   2. `read(0, buf, n)` fills guest memory from standard input
+//!   3. `brk` returns a program break that grows on request
+//!   4. `exit` ends the run with `a0` as the code; unknown calls return `-ENOSYS`,
+//!      except the legacy `a0 = 93` exit with the code in `a1`
+//!   5. `read` from the host console still works after a UART has started polling it
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::soc::devices::Device;
+use riscv_core::soc::devices::uart::Uart;
+use std::io::{BufRead, BufReader, Cursor, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const BASE: u64 = 0x8000_0000;
+/// `ecall` encoding.
+const ECALL: u32 = 0x0000_0073;
+
+const A0: u32 = 10;
+const A1: u32 = 11;
+const A2: u32 = 12;
+const A7: u32 = 17;
+const S0: u32 = 8;
+const S1: u32 = 9;
+const S2: u32 = 18;
+const S3: u32 = 19;
+
+/// `Write` sink sharing its buffer with the test.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Appends `exit(code)` to `program`.
+fn exit_with(program: &mut Vec<u32>, code: i32) {
+    let b = InstructionBuilder::new;
+    program.extend([
+        b().addi(A0, 0, code).build(),
+        b().addi(A7, 0, 93).build(),
+        ECALL,
+    ]);
+}
+
+/// Runs `program` from `BASE` until it exits.
+fn run_program(tc: &mut TestContext) {
+    tc.run(1000);
+    assert!(tc.cpu.exit_code.is_some(), "program did not exit");
+}
+
+// ══════════════════════════════════════════════════════════
+// 1. write
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn write_to_stdout_produces_bytes() {
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().auipc(A1, 0).build(),
+        b().addi(A1, A1, 0x100).build(),
+        b().addi(A0, 0, 1).build(),
+        b().addi(A2, 0, 6).build(),
+        b().addi(A7, 0, 64).build(),
+        ECALL,
+        b().addi(S0, A0, 0).build(),
+    ];
+    exit_with(&mut program, 0);
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    for (i, &byte) in b"hello\n".iter().enumerate() {
+        tc.cpu.bus.bus.write_u8(BASE + 0x100 + i as u64, byte);
+    }
+    let out = SharedBuf::default();
+    tc.cpu.set_semihost_stdout(Box::new(out.clone()));
+
+    run_program(&mut tc);
+
+    assert_eq!(&*out.0.lock().unwrap(), b"hello\n");
+    assert_eq!(tc.get_reg(S0 as usize), 6, "write returns the byte count");
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. read
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn read_from_stdin_fills_buffer() {
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().auipc(A1, 0).build(),
+        b().addi(A1, A1, 0x100).build(),
+        b().addi(A0, 0, 0).build(),
+        b().addi(A2, 0, 16).build(),
+        b().addi(A7, 0, 63).build(),
+        ECALL,
+        b().addi(S0, A0, 0).build(),
+    ];
+    exit_with(&mut program, 0);
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    tc.cpu
+        .set_semihost_stdin(Box::new(Cursor::new(b"abc".to_vec())));
+
+    run_program(&mut tc);
+
+    assert_eq!(tc.get_reg(S0 as usize), 3);
+    assert_eq!(tc.cpu.bus.bus.read_u8(BASE + 0x100), b'a');
+    assert_eq!(tc.cpu.bus.bus.read_u8(BASE + 0x102), b'c');
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. brk
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn brk_grows_heap() {
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().addi(A7, 0, 214).build(),
+        b().addi(A0, 0, 0).build(),
+        ECALL,
+        b().addi(S1, A0, 0).build(),
+        b().lui(5, 1).build(),
+        b().add(A0, S1, 5).build(),
+        ECALL,
+        b().addi(S2, A0, 0).build(),
+        b().addi(A0, 0, 0).build(),
+        ECALL,
+        b().addi(S3, A0, 0).build(),
+    ];
+    exit_with(&mut program, 0);
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    run_program(&mut tc);
+
+    let start = tc.get_reg(S1 as usize);
+    assert!(start > BASE, "heap lies in RAM");
+    assert_eq!(start % 4096, 0);
+    assert_eq!(tc.get_reg(S2 as usize), start + 4096);
+    assert_eq!(tc.get_reg(S3 as usize), start + 4096, "brk(0) queries");
+}
+
+// ══════════════════════════════════════════════════════════
+// 4. exit and unknown calls
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn exit_code_and_unknown_call() {
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().addi(A7, 0, 1000).build(),
+        ECALL,
+        b().addi(S0, A0, 0).build(),
+    ];
+    exit_with(&mut program, 7);
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    run_program(&mut tc);
+
+    assert_eq!(tc.get_reg(S0 as usize) as i64, -38, "-ENOSYS");
+    assert_eq!(tc.cpu.exit_code, Some(7));
+}
+
+#[test]
+fn legacy_exit_convention() {
+    let b = InstructionBuilder::new;
+    let program = vec![
+        b().addi(A7, 0, 0).build(),
+        b().addi(A0, 0, 93).build(),
+        b().addi(A1, 0, 5).build(),
+        ECALL,
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    run_program(&mut tc);
+
+    assert_eq!(tc.cpu.exit_code, Some(5));
+}
+
+// ══════════════════════════════════════════════════════════
+// 5. Host console shared with the UART
+// ══════════════════════════════════════════════════════════
+
+/// Set in the child process that runs [`read_after_uart_polls_stdin`]'s guest.
+const CHILD_ENV: &str = "SEMIHOST_STDIN_CHILD";
+/// Line the child prints once the UART has started the console reader.
+const READY: &str = "uart-polled";
+
+/// Starts the console reader through a UART, then reads a line with semihosting.
+fn uart_then_read_child() {
+    let mut uart = Uart::new(0x1000_0000, false);
+    for _ in 0..300 {
+        uart.tick();
+    }
+    println!("{}", READY);
+    std::io::stdout().flush().unwrap();
+
+    let b = InstructionBuilder::new;
+    let mut program = vec![
+        b().auipc(A1, 0).build(),
+        b().addi(A1, A1, 0x100).build(),
+        b().addi(A0, 0, 0).build(),
+        b().addi(A2, 0, 16).build(),
+        b().addi(A7, 0, 63).build(),
+        ECALL,
+        b().addi(S0, A0, 0).build(),
+    ];
+    exit_with(&mut program, 0);
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+
+    run_program(&mut tc);
+
+    assert_eq!(tc.get_reg(S0 as usize), 4);
+    assert_eq!(tc.cpu.bus.bus.read_u8(BASE + 0x100), b'a');
+    assert_eq!(tc.cpu.bus.bus.read_u8(BASE + 0x103), b'\n');
+}
+
+/// The UART's reader thread keeps stdin locked, so this runs in a child process whose
+/// stdin the test controls.
+#[test]
+fn read_after_uart_polls_stdin() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        uart_then_read_child();
+        return;
+    }
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "unit::sim::semihosting::read_after_uart_polls_stdin",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.trim_end().ends_with(READY) {
+        line.clear();
+        assert_ne!(
+            stdout.read_line(&mut line).unwrap(),
+            0,
+            "child never polled"
+        );
+    }
+    child.stdin.take().unwrap().write_all(b"abc\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if Instant::now() > deadline {
+            child.kill().unwrap();
+            panic!("semihosted read deadlocked on stdin");
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    assert!(status.success(), "child guest read the wrong bytes");
+}
@@ -0,0 +1,138 @@
+//! Symbol Map Unit Tests.
+//!
+//! Verifies symbol loading and lookup:
+//! 1. `nm` and `nm -S` listings parse, skipping undefined symbols
+//! 2. A PC inside a function resolves to its name and offset
+//! 3. Sized symbols do not claim addresses past their end
+//! 4. ELF64 `.symtab` entries load, keeping functions and dropping sections/files
+//! 5. The CPU annotates PCs once a symbol file is configured
+
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+use riscv_core::sim::symbols::SymbolTable;
+use std::fs;
+
+const NM_LISTING: &str = "\
+0000000080000000 T _start
+0000000080000040 T main
+                 U memcpy
+0000000080000100 t helper
+0000000080002000 D table
+";
+
+#[test]
+fn nm_listing_resolves_pc_to_function_and_offset() {
+    let table = SymbolTable::from_nm(NM_LISTING);
+    assert_eq!(table.len(), 4, "undefined memcpy is skipped");
+
+    let (sym, off) = table.lookup(0x8000_0058).unwrap();
+    assert_eq!((sym.name.as_str(), off), ("main", 0x18));
+
+    let (sym, off) = table.lookup(0x8000_0100).unwrap();
+    assert_eq!((sym.name.as_str(), off), ("helper", 0));
+
+    assert!(table.lookup(0x7FFF_FFFC).is_none(), "below every symbol");
+    assert_eq!(
+        table.format(0x8000_0044).to_string(),
+        "0x80000044 <main+0x4>"
+    );
+    assert_eq!(table.format(0x8000_0000).to_string(), "0x80000000 <_start>");
+}
+
+#[test]
+fn nm_sizes_bound_lookup() {
+    let table = SymbolTable::from_nm(
+        "0000000080000040 0000000000000010 T main\n\
+         0000000080000100 0000000000000008 T helper\n",
+    );
+    assert_eq!(table.lookup(0x8000_004C).unwrap().1, 0xC);
+    assert!(table.lookup(0x8000_0050).is_none(), "past the end of main");
+    assert_eq!(table.format(0x8000_0050).to_string(), "0x80000050");
+}
+
+/// Builds a minimal ELF64 LE image with a `.symtab` (`_start`, `main`, a section
+/// symbol, and a file symbol) and its linked `.strtab`.
+fn tiny_elf() -> Vec<u8> {
+    let strtab = b"\0_start\0main\0file.c\0".to_vec();
+    let sym = |name: u32, info: u8, shndx: u16, value: u64, size: u64| {
+        let mut e = Vec::with_capacity(24);
+        e.extend_from_slice(&name.to_le_bytes());
+        e.push(info);
+        e.push(0);
+        e.extend_from_slice(&shndx.to_le_bytes());
+        e.extend_from_slice(&value.to_le_bytes());
+        e.extend_from_slice(&size.to_le_bytes());
+        e
+    };
+    let mut symtab = sym(0, 0, 0, 0, 0);
+    symtab.extend(sym(1, 0x10, 1, 0x8000_0000, 0)); // _start: GLOBAL NOTYPE
+    symtab.extend(sym(8, 0x12, 1, 0x8000_0040, 0x20)); // main: GLOBAL FUNC
+    symtab.extend(sym(0, 0x03, 1, 0x8000_0000, 0)); // SECTION
+    symtab.extend(sym(13, 0x04, 0xFFF1, 0, 0)); // FILE
+
+    let symtab_off = 64u64;
+    let strtab_off = symtab_off + symtab.len() as u64;
+    let shoff = strtab_off + strtab.len() as u64;
+
+    let mut elf = vec![0u8; 64];
+    elf[..4].copy_from_slice(b"\x7fELF");
+    elf[4] = 2; // ELFCLASS64
+    elf[5] = 1; // little-endian
+    elf[0x28..0x30].copy_from_slice(&shoff.to_le_bytes());
+    elf[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+    elf[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes());
+    elf.extend(&symtab);
+    elf.extend(&strtab);
+
+    let shdr = |kind: u32, off: u64, size: u64, link: u32| {
+        let mut h = vec![0u8; 64];
+        h[4..8].copy_from_slice(&kind.to_le_bytes());
+        h[0x18..0x20].copy_from_slice(&off.to_le_bytes());
+        h[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+        h[0x28..0x2C].copy_from_slice(&link.to_le_bytes());
+        h
+    };
+    elf.extend(shdr(0, 0, 0, 0));
+    elf.extend(shdr(2, symtab_off, symtab.len() as u64, 2));
+    elf.extend(shdr(3, strtab_off, strtab.len() as u64, 0));
+    elf
+}
+
+#[test]
+fn elf_symtab_loads_function_symbols() {
+    let table = SymbolTable::from_elf(&tiny_elf()).unwrap();
+    assert_eq!(table.len(), 2, "section and file symbols are dropped");
+
+    let (sym, off) = table.lookup(0x8000_0050).unwrap();
+    assert_eq!((sym.name.as_str(), off), ("main", 0x10));
+    assert!(
+        table.lookup(0x8000_0060).is_none(),
+        "main is 0x20 bytes long"
+    );
+    assert_eq!(table.lookup(0x8000_0008).unwrap().0.name, "_start");
+}
+
+#[test]
+fn elf_rejects_truncated_image() {
+    let mut elf = tiny_elf();
+    elf.truncate(100); // inside the symbol table; section headers are gone
+    assert!(SymbolTable::from_elf(&elf).is_err());
+    assert!(SymbolTable::from_elf(b"not an elf").is_err());
+}
+
+#[test]
+fn cpu_symbolizes_pcs_from_configured_file() {
+    let path = std::env::temp_dir().join(format!("symbols_{}.nm", std::process::id()));
+    fs::write(&path, NM_LISTING).unwrap();
+
+    let mut config = Config::default();
+    config.general.symbols = Some(path.to_string_lossy().into_owned());
+    let tc = TestContext::from_config(&config);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(tc.cpu.symbols.len(), 4);
+    assert_eq!(
+        tc.cpu.symbolize(0x8000_0104).to_string(),
+        "0x80000104 <helper+0x4>"
+    );
+}
@@ -1,7 +1,8 @@
 //! CLINT (Core Local Interruptor) Unit Tests.
 //!
 //! Verifies timer operation, MSIP/MTIME/MTIMECMP register read/write,
-//! divider-based tick counting, and interrupt generation.
+//! divider-based tick counting, interrupt generation, and timer-interrupt
+//! coalescing.
 
 use riscv_core::soc::devices::Device;
 use riscv_core::soc::devices::clint::Clint;
@@ -133,3 +134,86 @@ fn clint_unrecognized_offset_returns_zero() {
     assert_eq!(clint.read_u64(0x1000), 0);
     assert_eq!(clint.read_u32(0x1000), 0);
 }
+
+/// Runs a handler that re-arms `mtimecmp` to the current `mtime` (already expired)
+/// on every delivered timer interrupt, and counts deliveries (rising edges).
+fn count_storm_interrupts(min_interval: u64, ticks: usize) -> usize {
+    let mut clint = Clint::new(0, 1);
+    clint.set_min_interval(min_interval);
+    clint.write_u64(0x4000, 0);
+    let mut delivered = 0;
+    let mut line = false;
+    for _ in 0..ticks {
+        let irq = clint.tick();
+        if irq && !line {
+            delivered += 1;
+            // Handler: acknowledge, then immediately re-arm an expired compare.
+            clint.write_u64(0x4000, u64::MAX);
+            line = clint.tick();
+            let now = clint.read_u64(0xBFF8);
+            clint.write_u64(0x4000, now);
+        } else {
+            line = irq;
+        }
+    }
+    delivered
+}
+
+#[test]
+fn clint_coalescing_delivers_fewer_interrupts() {
+    let uncoalesced = count_storm_interrupts(0, 200);
+    let coalesced = count_storm_interrupts(20, 200);
+    assert!(uncoalesced >= 50, "storm without coalescing: {uncoalesced}");
+    assert!(
+        coalesced < uncoalesced / 4,
+        "coalesced {coalesced} vs uncoalesced {uncoalesced}"
+    );
+    assert!(coalesced >= 5, "coalesced interrupts are still delivered");
+}
+
+#[test]
+fn clint_coalesced_expiration_fires_when_window_closes() {
+    let mut clint = Clint::new(0, 1);
+    clint.set_min_interval(10);
+    clint.write_u64(0x4000, 1);
+    assert!(clint.tick(), "first expiration fires immediately");
+
+    // Acknowledge, then expire again at mtime 3: held until mtime 11.
+    clint.write_u64(0x4000, 3);
+    let fired_at = (0..20)
+        .find(|_| clint.tick())
+        .map(|_| clint.read_u64(0xBFF8));
+    assert_eq!(fired_at, Some(11));
+}
+
+#[test]
+fn clint_per_hart_registers_are_independent() {
+    let mut clint = Clint::new(0, 1).with_harts(2);
+    clint.write_u32(0x0004, 1);
+    clint.write_u64(0x4008, 5);
+
+    assert_eq!(clint.read_u32(0x0000), 0, "hart 0 MSIP untouched");
+    assert_eq!(
+        clint.read_u64(0x4000),
+        u64::MAX,
+        "hart 0 MTIMECMP untouched"
+    );
+    assert_eq!(clint.read_u64(0x4008), 5);
+
+    clint.tick();
+    assert_eq!(clint.hart_lines(0), (false, false));
+    assert_eq!(clint.hart_lines(1), (false, true));
+    for _ in 0..5 {
+        clint.tick();
+    }
+    assert_eq!(clint.hart_lines(1), (true, true));
+}
+
+#[test]
+fn clint_absent_hart_registers_read_zero() {
+    let mut clint = Clint::new(0, 1).with_harts(2);
+    clint.write_u32(0x0008, 1);
+    assert_eq!(clint.read_u32(0x0008), 0);
+    assert_eq!(clint.read_u64(0x4010), 0);
+    assert_eq!(clint.hart_lines(2), (false, false));
+}
@@ -1,10 +1,21 @@
 //! Goldfish RTC unit tests.
 //!
-//! Verifies basic device identification for the Goldfish real-time clock.
+//! Verifies device identification, latched 64-bit time reads, tick-driven
+//! advance, time setting, and config-driven mapping for the Goldfish
+//! real-time clock.
 
+use riscv_core::config::Config;
+use riscv_core::soc::System;
 use riscv_core::soc::devices::Device;
 use riscv_core::soc::devices::goldfish_rtc::GoldfishRtc;
 
+/// Reads the time the way the Linux driver does: low word, then high word.
+fn read_time(rtc: &mut GoldfishRtc) -> u64 {
+    let low = rtc.read_u32(0x0);
+    let high = rtc.read_u32(0x4);
+    ((high as u64) << 32) | low as u64
+}
+
 #[test]
 fn goldfish_rtc_name() {
     let rtc = GoldfishRtc::new(0x101000);
@@ -31,3 +42,51 @@ fn goldfish_rtc_read_time_low_nonzero() {
     let time_ns = ((_time_high as u64) << 32) | (time_low as u64);
     assert!(time_ns > 0, "Time since epoch should be > 0");
 }
+
+#[test]
+fn goldfish_rtc_time_increases_across_ticks() {
+    let mut rtc = GoldfishRtc::new(0).with_ns_per_tick(10);
+    let start = read_time(&mut rtc);
+    let mut prev = start;
+    for _ in 0..5 {
+        for _ in 0..100 {
+            rtc.tick();
+        }
+        let now = read_time(&mut rtc);
+        assert!(now > prev, "{} not after {}", now, prev);
+        prev = now;
+    }
+    assert_eq!(prev - start, 5 * 100 * 10);
+}
+
+#[test]
+fn goldfish_rtc_high_word_latched_by_low_read() {
+    let mut rtc = GoldfishRtc::new(0);
+    rtc.write_u32(0x4, 1);
+    rtc.write_u32(0x0, 0xffff_fffe);
+    assert_eq!(
+        rtc.read_u64(0x0),
+        0x1_ffff_fffe,
+        "time set by high then low"
+    );
+
+    let low = rtc.read_u32(0x0);
+    rtc.tick();
+    rtc.tick(); // carries into the high word
+    let high = rtc.read_u32(0x4);
+    assert_eq!(((high as u64) << 32) | low as u64, 0x1_ffff_fffe);
+    assert_eq!(read_time(&mut rtc), 0x2_0000_0000);
+}
+
+#[test]
+fn goldfish_rtc_mapped_from_config() {
+    let mut config = Config::default();
+    config.memory.ram_size = 0x1000;
+    config.system.rtc_base = 0x0030_0000;
+    let system = System::new(&config, "");
+    assert!(system.bus.is_valid_address(0x0030_0000));
+
+    config.system.rtc_enabled = false;
+    let system = System::new(&config, "");
+    assert!(!system.bus.is_valid_address(0x0030_0000));
+}
@@ -1,9 +1,9 @@
 //! Bus interconnect unit tests.
 //!
 //! Verifies device registration, address routing, read/write operations,
-//! transit time calculation, and valid address checks.
+//! transit time calculation, valid address checks, and overlap rejection.
 
-use riscv_core::soc::interconnect::Bus;
+use riscv_core::soc::interconnect::{AddressOverlap, Bus};
 use riscv_core::soc::memory::Memory;
 use riscv_core::soc::memory::buffer::DramBuffer;
 use std::sync::Arc;
@@ -175,3 +175,54 @@ fn get_ram_info_none_when_no_ram() {
     let mut bus = Bus::new(8, 0);
     assert!(bus.get_ram_info().is_none());
 }
+
+// ══════════════════════════════════════════════════════════
+// 8. Overlapping device ranges
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn overlapping_device_is_rejected() {
+    let mut bus = make_bus_with_ram(0x1000, 0x1000);
+    let mem = Memory::new(Arc::new(DramBuffer::new(0x1000)), 0x1800);
+    let err = bus.try_add_device(Box::new(mem)).unwrap_err();
+    assert_eq!(
+        err,
+        AddressOverlap {
+            device: "DRAM".to_string(),
+            range: (0x1800, 0x1000),
+            existing: "DRAM".to_string(),
+            existing_range: (0x1000, 0x1000),
+        }
+    );
+    let msg = err.to_string();
+    assert!(msg.contains("[0x1800, 0x2800)"), "{msg}");
+    assert!(msg.contains("[0x1000, 0x2000)"), "{msg}");
+    // The rejected device is not mapped.
+    bus.write_u8(0x1800, 0x5A);
+    assert_eq!(bus.read_u8(0x1800), 0x5A);
+    assert!(!bus.is_valid_address(0x2400));
+}
+
+#[test]
+#[should_panic(expected = "invalid device address map")]
+fn add_device_panics_on_overlap() {
+    let mut bus = make_bus_with_ram(0x1000, 0x1000);
+    let mem = Memory::new(Arc::new(DramBuffer::new(0x100)), 0x0F80);
+    bus.add_device(Box::new(mem));
+}
+
+#[test]
+fn adjacent_devices_are_accepted() {
+    let mut bus = make_bus_with_ram(0x1000, 0x1000);
+    let below = Memory::new(Arc::new(DramBuffer::new(0x1000)), 0x0000);
+    let above = Memory::new(Arc::new(DramBuffer::new(0x1000)), 0x2000);
+    assert!(bus.try_add_device(Box::new(below)).is_ok());
+    assert!(bus.try_add_device(Box::new(above)).is_ok());
+
+    bus.write_u8(0x0FFF, 1);
+    bus.write_u8(0x1000, 2);
+    bus.write_u8(0x2000, 3);
+    assert_eq!(bus.read_u8(0x0FFF), 1);
+    assert_eq!(bus.read_u8(0x1000), 2);
+    assert_eq!(bus.read_u8(0x2000), 3);
+}
@@ -1,9 +1,17 @@
 //! Memory Controller Unit Tests.
 //!
-//! Verifies SimpleController (fixed latency) and DramController
-//! (row-buffer-aware latency with CAS/RAS/precharge).
+//! Verifies SimpleController (fixed latency), DramController
+//! (row-buffer-aware latency with CAS/RAS/precharge, banks, and refresh),
+//! BandwidthQueue (rate-limited fill channel), HbmController (hashed
+//! pseudo-channels), and InterleavedController (multi-channel memory).
 
-use riscv_core::soc::memory::controller::{DramController, MemoryController, SimpleController};
+use crate::common::harness::TestContext;
+use riscv_core::common::{AccessType, PhysAddr};
+use riscv_core::config::{ChannelInterleave, MemoryConfig, MemoryController as ControllerKind};
+use riscv_core::soc::memory::controller::{
+    BandwidthQueue, DramController, HbmController, InterleavedController, MemoryController,
+    SimpleController,
+};
 
 // ══════════════════════════════════════════════════════════
 // 1. SimpleController
@@ -126,3 +134,316 @@ fn dram_high_latency() {
     assert_eq!(ctrl.access_latency(0), 20); // hit
     assert_eq!(ctrl.access_latency(0x1000), 90); // miss: 30+40+20
 }
+
+// ══════════════════════════════════════════════════════════
+// 7. DramController: Banks
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn dram_banks_keep_their_own_rows_open() {
+    let mut ctrl = DramController::new(5, 10, 8).with_banks(2, 2048);
+    assert_eq!(ctrl.bank_and_row(0x0000), (0, 0));
+    assert_eq!(ctrl.bank_and_row(0x0800), (1, 0));
+    assert_eq!(ctrl.bank_and_row(0x1000), (0, 1));
+
+    ctrl.access_latency(0x0000); // bank 0 cold
+    ctrl.access_latency(0x0800); // bank 1 cold
+    assert_eq!(ctrl.access_latency(0x0010), 5, "bank 0 row still open");
+    assert_eq!(ctrl.access_latency(0x0810), 5, "bank 1 row still open");
+    assert_eq!(ctrl.access_latency(0x1000), 23, "bank 0 switches rows");
+}
+
+#[test]
+fn dram_sequential_stream_beats_row_conflict_stride() {
+    const LINES: u64 = 256;
+    let total = |stride: u64| {
+        let mut ctrl = DramController::new(5, 10, 8).with_banks(8, 2048);
+        (0..LINES)
+            .map(|i| ctrl.access_latency(0x8000_0000 + i * stride))
+            .sum::<u64>()
+    };
+
+    // 64-byte lines: 32 lines per row, so only the first touch of each row activates.
+    let sequential = total(64);
+    // A stride of row_bytes * banks revisits bank 0 with a new row every time.
+    let strided = total(2048 * 8);
+
+    assert_eq!(
+        sequential,
+        8 * 15 + (LINES - 8) * 5,
+        "one cold row per bank"
+    );
+    assert_eq!(strided, 15 + (LINES - 1) * 23);
+    assert!(
+        strided > 3 * sequential,
+        "strided {} cycles vs sequential {}",
+        strided,
+        sequential
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+// 8. DramController: Refresh
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn dram_refresh_disabled_never_blocks() {
+    let mut ctrl = DramController::new(5, 10, 8);
+    for now in [0, 100, 7800, 1_000_000] {
+        assert_eq!(ctrl.queue_delay(0, now), 0);
+    }
+}
+
+#[test]
+fn dram_refresh_window_blocks_and_closes_rows() {
+    let mut ctrl = DramController::new(5, 10, 8).with_refresh(1000, 50);
+    assert_eq!(
+        ctrl.queue_delay(0, 10),
+        0,
+        "no refresh before the first interval"
+    );
+    ctrl.access_latency(0);
+    assert_eq!(ctrl.access_latency(0), 5);
+
+    assert_eq!(ctrl.queue_delay(0, 1000), 50, "waits out the whole refresh");
+    assert_eq!(ctrl.queue_delay(0, 1030), 20, "waits out the rest of it");
+    assert_eq!(ctrl.queue_delay(0, 1050), 0, "refresh complete");
+    assert_eq!(ctrl.access_latency(0), 15, "refresh closed the row");
+}
+
+// ══════════════════════════════════════════════════════════
+// 9. BandwidthQueue
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn bandwidth_disabled_never_queues() {
+    let mut q = BandwidthQueue::new(0);
+    assert!(!q.is_enabled());
+    for _ in 0..8 {
+        assert_eq!(q.request(0, 64), 0);
+    }
+}
+
+#[test]
+fn bandwidth_back_to_back_requests_queue() {
+    let mut q = BandwidthQueue::new(8);
+    assert_eq!(q.request(100, 64), 0); // idle channel
+    assert_eq!(q.request(100, 64), 8); // waits for first 64B at 8B/cycle
+    assert_eq!(q.request(100, 64), 16);
+}
+
+#[test]
+fn bandwidth_channel_drains_over_time() {
+    let mut q = BandwidthQueue::new(16);
+    assert_eq!(q.request(0, 64), 0); // busy until cycle 4
+    assert_eq!(q.request(2, 64), 2); // partially drained
+    assert_eq!(q.request(100, 64), 0); // fully drained
+}
+
+#[test]
+fn bandwidth_partial_beat_rounds_up() {
+    let mut q = BandwidthQueue::new(24);
+    assert_eq!(q.request(0, 64), 0);
+    assert_eq!(q.request(0, 64), 3); // ceil(64 / 24)
+}
+
+#[test]
+fn bandwidth_saturation_grows_aggregate_miss_latency() {
+    const MISSES: u64 = 16;
+    let line = |i: u64| PhysAddr::new(0x8000_0000 + i * 0x1_0000);
+
+    let mut tc = TestContext::new();
+    tc.cpu.bus.mem_bandwidth = BandwidthQueue::new(4);
+    let single = tc.cpu.simulate_memory_access(line(0), AccessType::Read);
+
+    let mut tc = TestContext::new();
+    tc.cpu.bus.mem_bandwidth = BandwidthQueue::new(4);
+    let total: u64 = (0..MISSES)
+        .map(|i| tc.cpu.simulate_memory_access(line(i), AccessType::Read))
+        .sum();
+
+    assert!(
+        total > MISSES * single,
+        "saturated channel: {} cycles for {} misses vs {} per idle miss",
+        total,
+        MISSES,
+        single
+    );
+    assert_eq!(tc.cpu.stats.mem_queued_requests, MISSES - 1);
+    assert!(tc.cpu.stats.mem_queue_cycles > 0);
+}
+
+// ══════════════════════════════════════════════════════════
+// 10. HbmController
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn hbm_sequential_lines_cover_every_channel() {
+    let ctrl = HbmController::new(8, 5, 10, 8, 4);
+    let mut seen = [false; 8];
+    for i in 0..8u64 {
+        seen[ctrl.channel_of(0x8000_0000 + i * 64)] = true;
+    }
+    assert!(
+        seen.iter().all(|&s| s),
+        "8 consecutive lines hit all 8 channels"
+    );
+}
+
+#[test]
+fn hbm_hash_spreads_power_of_two_stride() {
+    // A 8 KiB stride keeps the low line bits fixed; the fold must still spread it.
+    let ctrl = HbmController::new(8, 5, 10, 8, 4);
+    let channels: std::collections::HashSet<usize> =
+        (0..8u64).map(|i| ctrl.channel_of(i * 0x2000)).collect();
+    assert!(
+        channels.len() > 1,
+        "strided stream collapsed onto one channel"
+    );
+}
+
+#[test]
+fn hbm_row_buffer_is_per_channel() {
+    let mut ctrl = HbmController::new(2, 5, 10, 8, 4);
+    assert_ne!(ctrl.channel_of(0x0000), ctrl.channel_of(0x0040));
+    assert_eq!(ctrl.access_latency(0x0000), 15); // cold: ras+cas
+    assert_eq!(ctrl.access_latency(0x0040), 15); // other channel, also cold
+    assert_eq!(ctrl.access_latency(0x0080), 5); // back on channel 0, row open
+}
+
+#[test]
+fn hbm_same_channel_requests_queue() {
+    let mut ctrl = HbmController::new(4, 5, 10, 8, 4);
+    let a = 0x1000;
+    let b = (1..64u64)
+        .map(|i| a + i * 64)
+        .find(|&x| ctrl.channel_of(x) == ctrl.channel_of(a))
+        .unwrap();
+    assert_eq!(ctrl.queue_delay(a, 0), 0);
+    assert_eq!(ctrl.queue_delay(b, 0), 4, "waits for the first burst");
+    assert_eq!(ctrl.queue_delay(b, 100), 0, "channel drained");
+}
+
+#[test]
+fn hbm_streaming_outperforms_single_channel_dram() {
+    const LINES: u64 = 64;
+    const BURST: u64 = 4;
+    let addr = |i: u64| 0x8000_0000 + i * 64;
+
+    // Every request arrives at cycle 0; the stream completes when the last fill does.
+    let mut dram = DramController::new(5, 10, 8);
+    let mut channel = BandwidthQueue::new(64 / BURST);
+    let dram_done = (0..LINES)
+        .map(|i| channel.request(0, 64) + dram.access_latency(addr(i)))
+        .max()
+        .unwrap();
+
+    let mut hbm = HbmController::new(8, 5, 10, 8, BURST);
+    let hbm_done = (0..LINES)
+        .map(|i| hbm.queue_delay(addr(i), 0) + hbm.access_latency(addr(i)))
+        .max()
+        .unwrap();
+
+    assert!(
+        hbm_done * 4 < dram_done,
+        "HBM finished {} lines in {} cycles vs {} for single-channel DRAM",
+        LINES,
+        hbm_done,
+        dram_done
+    );
+}
+
+#[test]
+fn hbm_selected_from_config() {
+    let memory: MemoryConfig =
+        serde_json::from_str(r#"{"controller": "HBM", "hbm_channels": 4}"#).unwrap();
+    assert_eq!(memory.controller, ControllerKind::Hbm);
+    assert_eq!(memory.hbm_channels, 4);
+    assert_eq!(memory.hbm_burst_cycles, 4, "unset fields take defaults");
+}
+
+#[test]
+fn hbm_channel_collisions_count_as_queued_misses() {
+    let mut tc = TestContext::new();
+    tc.cpu.bus.mem_controller = Box::new(HbmController::new(1, 5, 10, 8, 4));
+    for i in 0..4u64 {
+        tc.cpu
+            .simulate_memory_access(PhysAddr::new(0x8000_0000 + i * 0x1_0000), AccessType::Read);
+    }
+    assert_eq!(tc.cpu.stats.mem_queued_requests, 3);
+    assert_eq!(tc.cpu.stats.mem_queue_cycles, 4 + 8 + 12);
+}
+
+// ══════════════════════════════════════════════════════════
+// 11. InterleavedController
+// ══════════════════════════════════════════════════════════
+
+/// Builds `channels` DRAM channels interleaved every `granularity` bytes.
+fn interleaved_dram(channels: usize, granularity: u64) -> InterleavedController {
+    InterleavedController::new(
+        (0..channels)
+            .map(|_| Box::new(DramController::new(5, 10, 8)) as Box<_>)
+            .collect(),
+        granularity,
+    )
+}
+
+#[test]
+fn interleave_maps_lines_and_pages() {
+    let lines = interleaved_dram(2, 64);
+    assert_eq!(lines.channel_count(), 2);
+    assert_eq!(lines.channel_of(0x00), (0, 0x00));
+    assert_eq!(lines.channel_of(0x48), (1, 0x08));
+    assert_eq!(lines.channel_of(0x80), (0, 0x40), "channel bit removed");
+
+    let pages = interleaved_dram(2, 4096);
+    assert_eq!(pages.channel_of(0x0fc0), (0, 0x0fc0));
+    assert_eq!(pages.channel_of(0x1000), (1, 0x0000));
+    assert_eq!(pages.channel_of(0x2010), (0, 0x1010));
+}
+
+#[test]
+fn interleave_same_channel_requests_serialize() {
+    let mut ctrl = interleaved_dram(2, 64);
+    let latency = ctrl.access_latency(0x00);
+    assert_eq!(ctrl.queue_delay(0x00, 0), 0);
+    ctrl.access_latency(0x40);
+    assert_eq!(ctrl.queue_delay(0x40, 0), 0, "other channel is idle");
+    ctrl.access_latency(0x80);
+    assert_eq!(ctrl.queue_delay(0x80, 0), latency, "waits for channel 0");
+}
+
+#[test]
+fn interleaved_stream_beats_single_channel() {
+    const LINES: u64 = 64;
+    // Every request arrives at cycle 0; the stream completes when the last fill does.
+    let finish = |mut ctrl: InterleavedController| {
+        (0..LINES)
+            .map(|i| {
+                let addr = 0x8000_0000 + i * 64;
+                let latency = ctrl.access_latency(addr);
+                ctrl.queue_delay(addr, 0) + latency
+            })
+            .max()
+            .unwrap()
+    };
+
+    let single = finish(interleaved_dram(1, 64));
+    let dual = finish(interleaved_dram(2, 64));
+    assert!(
+        dual * 3 < single * 2,
+        "two channels finished {} lines in {} cycles vs {} for one",
+        LINES,
+        dual,
+        single
+    );
+}
+
+#[test]
+fn interleave_selected_from_config() {
+    let memory: MemoryConfig =
+        serde_json::from_str(r#"{"channels": 2, "channel_interleave": "Page"}"#).unwrap();
+    assert_eq!(memory.channels, 2);
+    assert_eq!(memory.channel_interleave, ChannelInterleave::Page);
+    assert_eq!(MemoryConfig::default().channels, 1);
+}
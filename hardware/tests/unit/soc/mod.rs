@@ -2,6 +2,7 @@ pub mod clint_timer;
 pub mod goldfish_rtc;
 pub mod interconnect;
 pub mod memory;
+pub mod nvram;
 pub mod plic;
 pub mod uart;
 pub mod virtio;
@@ -0,0 +1,122 @@
+//! NVRAM unit tests.
+//!
+//! Verifies the persistent NVRAM device:
+//! 1. Contents load from the host file and are truncated or zero-padded to size
+//! 2. Byte and multi-byte accesses are little-endian and bounded by the region
+//! 3. A guest store followed by SysCon power-off and shutdown persists to the host file
+//! 4. Without writeback enabled, shutdown leaves the host file untouched
+
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::soc::devices::{Device, Nvram, SysCon};
+use std::fs;
+use std::path::PathBuf;
+
+const BASE: u64 = 0x8000_0000;
+const NVRAM_BASE: u64 = 0x0011_0000;
+const SYSCON_BASE: u64 = 0x0010_0000;
+
+/// Returns a per-test host path, removing any leftover file from a previous run.
+fn temp_path(tag: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("nvram_{}_{}.bin", std::process::id(), tag));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn nvram_loads_and_pads_host_file() {
+    let path = temp_path("load");
+    fs::write(&path, [0x11, 0x22, 0x33]).unwrap();
+
+    let mut nv = Nvram::from_file(NVRAM_BASE, 8, &path, false).unwrap();
+    assert_eq!(nv.name(), "NVRAM");
+    assert_eq!(nv.address_range(), (NVRAM_BASE, 8));
+    assert_eq!(nv.contents(), &[0x11, 0x22, 0x33, 0, 0, 0, 0, 0]);
+    assert_eq!(nv.read_u16(1), 0x3322);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn nvram_missing_file_starts_zeroed() {
+    let path = temp_path("missing");
+    let mut nv = Nvram::from_file(NVRAM_BASE, 16, &path, true).unwrap();
+    assert_eq!(nv.read_u64(0), 0);
+    assert!(!path.exists(), "opening must not create the file");
+}
+
+#[test]
+fn nvram_accesses_are_little_endian_and_bounded() {
+    let mut nv = Nvram::new(NVRAM_BASE, 8);
+    nv.write_u32(0, 0xDEAD_BEEF);
+    assert_eq!(nv.read_u8(0), 0xEF);
+    assert_eq!(nv.read_u64(0), 0xDEAD_BEEF);
+
+    // A double-word straddling the end keeps only the in-range bytes.
+    nv.write_u64(4, u64::MAX);
+    assert_eq!(nv.read_u64(4), 0xFFFF_FFFF);
+    assert_eq!(nv.contents().len(), 8);
+}
+
+/// Runs a guest that stores `0x5A` to NVRAM offset 4 and then powers off via SysCon.
+fn run_store_then_poweroff(nvram: Nvram) -> TestContext {
+    let mut tc = TestContext::new().with_memory(0x1000, BASE);
+    tc.cpu.bus.bus.add_device(Box::new(nvram));
+    let exit = tc.cpu.bus.exit_request.clone();
+    tc.cpu
+        .bus
+        .bus
+        .add_device(Box::new(SysCon::new(SYSCON_BASE, exit)));
+
+    let program = [
+        InstructionBuilder::new()
+            .lui(5, (NVRAM_BASE >> 12) as i32)
+            .build(),
+        InstructionBuilder::new().addi(6, 0, 0x5A).build(),
+        InstructionBuilder::new().sw(5, 6, 4).build(),
+        InstructionBuilder::new()
+            .lui(7, (SYSCON_BASE >> 12) as i32)
+            .build(),
+        InstructionBuilder::new().lui(8, 5).build(),
+        InstructionBuilder::new().addi(8, 8, 0x555).build(),
+        InstructionBuilder::new().sw(7, 8, 0).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    let mut tc = tc.load_program(BASE, &program);
+    tc.run(200);
+    assert_eq!(tc.cpu.take_exit(), Some(0), "guest should power off");
+    tc
+}
+
+#[test]
+fn nvram_store_persists_on_shutdown_with_writeback() {
+    let path = temp_path("writeback");
+    let nv = Nvram::from_file(NVRAM_BASE, 16, &path, true).unwrap();
+
+    let mut tc = run_store_then_poweroff(nv);
+    assert!(!path.exists(), "nothing is written before shutdown");
+
+    tc.cpu.bus.shutdown();
+    let persisted = fs::read(&path).unwrap();
+    assert_eq!(persisted.len(), 16);
+    assert_eq!(&persisted[4..8], &[0x5A, 0, 0, 0]);
+
+    // Reopening the file sees the guest's value.
+    let mut reopened = Nvram::from_file(NVRAM_BASE, 16, &path, false).unwrap();
+    assert_eq!(reopened.read_u32(4), 0x5A);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn nvram_without_writeback_leaves_host_file_untouched() {
+    let path = temp_path("readonly");
+    fs::write(&path, [0u8; 16]).unwrap();
+    let nv = Nvram::from_file(NVRAM_BASE, 16, &path, false).unwrap();
+
+    let mut tc = run_store_then_poweroff(nv);
+    tc.cpu.bus.shutdown();
+    assert_eq!(fs::read(&path).unwrap(), vec![0u8; 16]);
+
+    let _ = fs::remove_file(&path);
+}
@@ -50,3 +50,64 @@ fn plic_tick_no_interrupt() {
     plic.update_irqs(0);
     assert!(!plic.tick());
 }
+
+/// Enables sources 3 (priority 2) and 5 (priority 6) for the machine context
+/// and asserts both lines.
+fn two_source_plic() -> Plic {
+    let mut plic = Plic::new(0);
+    plic.write_u32(3 * 4, 2);
+    plic.write_u32(5 * 4, 6);
+    plic.write_u32(0x2000, (1 << 3) | (1 << 5));
+    plic.write_u32(0x200000, 0);
+    plic.update_irqs((1 << 3) | (1 << 5));
+    plic
+}
+
+#[test]
+fn plic_claims_in_priority_order() {
+    let mut plic = two_source_plic();
+    assert_eq!(plic.read_u32(0x200004), 5, "higher priority first");
+    assert_eq!(plic.read_u32(0x200004), 3);
+    assert_eq!(plic.read_u32(0x200004), 0, "nothing left to claim");
+}
+
+#[test]
+fn plic_claimed_source_waits_for_complete() {
+    let mut plic = two_source_plic();
+    assert_eq!(plic.read_u32(0x200004), 5);
+
+    // The line is still asserted, but the source is in service.
+    plic.update_irqs((1 << 3) | (1 << 5));
+    assert_eq!(plic.read_u32(0x1000) & (1 << 5), 0);
+    assert_eq!(plic.read_u32(0x200004), 3);
+    plic.update_irqs((1 << 3) | (1 << 5));
+    assert!(!plic.check_interrupts().0, "both sources in service");
+
+    plic.write_u32(0x200004, 5);
+    plic.update_irqs((1 << 3) | (1 << 5));
+    assert!(plic.check_interrupts().0, "completed source fires again");
+    assert_eq!(plic.read_u32(0x200004), 5);
+}
+
+#[test]
+fn plic_complete_of_disabled_source_is_ignored() {
+    let mut plic = two_source_plic();
+    assert_eq!(plic.read_u32(0x200004), 5);
+    plic.write_u32(0x2000, 1 << 3); // disable source 5
+    plic.write_u32(0x200004, 5);
+    plic.write_u32(0x2000, (1 << 3) | (1 << 5));
+    plic.update_irqs(1 << 5);
+    assert_eq!(plic.read_u32(0x1000) & (1 << 5), 0, "still in service");
+}
+
+#[test]
+fn plic_threshold_gates_notification_not_claim() {
+    let mut plic = two_source_plic();
+    plic.write_u32(0x200000, 6);
+    let (meip, _) = plic.check_interrupts();
+    assert!(!meip, "no source above threshold 6");
+    assert_eq!(plic.read_u32(0x200004), 5, "claim ignores the threshold");
+
+    plic.write_u32(0x200000, 1);
+    assert!(plic.check_interrupts().0, "source 3 clears threshold 1");
+}
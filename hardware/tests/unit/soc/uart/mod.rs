@@ -1 +1,3 @@
 pub mod fifo_watermarks;
+pub mod receive;
+pub mod registers;
@@ -0,0 +1,81 @@
+//! UART Receive Path Tests.
+//!
+//! Verifies the receive side of the UART with bytes injected into the RX FIFO:
+//! 1. Sequential RBR reads return the queued bytes in order and consume them
+//! 2. LSR data-ready tracks whether the FIFO holds data
+//! 3. With IER data-ready enabled, the receive interrupt reaches the PLIC
+
+use riscv_core::soc::devices::Device;
+use riscv_core::soc::devices::plic::Plic;
+use riscv_core::soc::devices::uart::Uart;
+use riscv_core::soc::interconnect::Bus;
+
+const UART_BASE: u64 = 0x1000_0000;
+const PLIC_BASE: u64 = 0x0c00_0000;
+const RBR: u64 = 0;
+const IER: u64 = 1;
+const IIR: u64 = 2;
+const LSR: u64 = 5;
+/// UART interrupt source on the PLIC.
+const UART_IRQ: u64 = 10;
+
+// ══════════════════════════════════════════════════════════
+// 1. FIFO Reads
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn rbr_reads_consume_fifo_in_order() {
+    let mut uart = Uart::new(UART_BASE, true);
+    for &b in b"ok\n" {
+        uart.push_rx(b);
+    }
+
+    for &expected in b"ok\n" {
+        assert_eq!(uart.read_u8(LSR) & 0x01, 0x01, "data ready");
+        assert_eq!(uart.read_u8(RBR), expected);
+    }
+    assert_eq!(uart.read_u8(LSR) & 0x01, 0, "FIFO empty");
+    assert_eq!(uart.read_u8(RBR), 0, "empty FIFO reads as zero");
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Receive Interrupt
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn data_ready_interrupt_follows_ier() {
+    let mut uart = Uart::new(UART_BASE, true);
+    uart.push_rx(b'x');
+    assert!(!uart.tick(), "masked while IER is clear");
+
+    uart.write_u8(IER, 0x01);
+    assert!(uart.tick());
+    assert_eq!(uart.read_u8(IIR) & 0x0f, 0x04, "receiver data available");
+
+    uart.read_u8(RBR);
+    assert!(!uart.tick(), "drained FIFO drops the interrupt");
+}
+
+#[test]
+fn receive_interrupt_reaches_plic() {
+    let mut bus = Bus::new(8, 0);
+    let mut uart = Uart::new(UART_BASE, true);
+    uart.write_u8(IER, 0x01);
+    uart.push_rx(b'x');
+    bus.add_device(Box::new(uart));
+
+    let mut plic = Plic::new(PLIC_BASE);
+    plic.write_u32(UART_IRQ * 4, 1); // priority
+    plic.write_u32(0x2000, 1 << UART_IRQ); // enable for the machine context
+    plic.write_u32(0x200000, 0); // threshold
+    bus.add_device(Box::new(plic));
+
+    let (_, meip, _) = bus.tick();
+    assert!(meip, "UART receive interrupt raises MEIP");
+    assert_eq!(bus.read_u32(PLIC_BASE + 0x200004), UART_IRQ as u32, "claim");
+
+    assert_eq!(bus.read_u8(UART_BASE + RBR), b'x');
+    bus.write_u32(PLIC_BASE + 0x200004, UART_IRQ as u32); // complete
+    let (_, meip, _) = bus.tick();
+    assert!(!meip, "no more input");
+}
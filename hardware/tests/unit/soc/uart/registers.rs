@@ -0,0 +1,143 @@
+//! NS16550 Register Map Tests.
+//!
+//! Verifies the registers a Linux 8250 driver probes:
+//! 1. Divisor latch access through LCR.DLAB
+//! 2. IIR identification and priority for THR-empty and receive interrupts
+//! 3. FCR FIFO enable, trigger level, and character timeout
+//! 4. MCR loopback
+
+use riscv_core::soc::devices::Device;
+use riscv_core::soc::devices::uart::Uart;
+
+const RBR: u64 = 0;
+const THR: u64 = 0;
+const DLL: u64 = 0;
+const IER: u64 = 1;
+const DLM: u64 = 1;
+const IIR: u64 = 2;
+const FCR: u64 = 2;
+const LCR: u64 = 3;
+const MCR: u64 = 4;
+const LSR: u64 = 5;
+const MSR: u64 = 6;
+
+// ══════════════════════════════════════════════════════════
+// 1. Divisor Latch
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn divisor_latch_shadows_rbr_and_ier() {
+    let mut uart = Uart::new(0, true);
+    uart.push_rx(b'z');
+    uart.write_u8(IER, 0x01);
+
+    uart.write_u8(LCR, 0x83); // DLAB, 8N1
+    uart.write_u8(DLL, 0x0c);
+    uart.write_u8(DLM, 0x01);
+    assert_eq!(uart.read_u8(DLL), 0x0c);
+    assert_eq!(uart.read_u8(DLM), 0x01);
+
+    uart.write_u8(LCR, 0x03);
+    assert_eq!(uart.read_u8(IER), 0x01, "IER untouched by DLM writes");
+    assert_eq!(uart.read_u8(RBR), b'z', "RBR untouched by DLL reads");
+    assert_eq!(uart.read_u8(LCR), 0x03);
+}
+
+#[test]
+fn ier_keeps_only_implemented_bits() {
+    let mut uart = Uart::new(0, true);
+    uart.write_u8(IER, 0xff);
+    assert_eq!(uart.read_u8(IER), 0x0f);
+}
+
+// ══════════════════════════════════════════════════════════
+// 2. Interrupt Identification
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn thre_interrupt_identified_and_cleared_by_iir_read() {
+    let mut uart = Uart::new(0, true);
+    uart.write_u8(IER, 0x02);
+    assert!(uart.tick(), "THR empty interrupt reaches the IRQ path");
+    assert_eq!(uart.read_u8(IIR), 0x02);
+    assert_eq!(uart.read_u8(IIR), 0x01, "reading IIR cleared THRE");
+    assert!(!uart.tick());
+
+    uart.write_u8(THR, b'.');
+    assert_eq!(uart.read_u8(IIR), 0x02, "THR write re-arms THRE");
+}
+
+#[test]
+fn receive_interrupt_outranks_thre() {
+    let mut uart = Uart::new(0, true);
+    uart.write_u8(IER, 0x03);
+    uart.push_rx(b'a');
+    assert_eq!(uart.read_u8(IIR), 0x04);
+    uart.read_u8(RBR);
+    assert_eq!(uart.read_u8(IIR), 0x02, "THRE once the data is read");
+}
+
+// ══════════════════════════════════════════════════════════
+// 3. FIFO Control
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn fcr_enable_reported_in_iir() {
+    let mut uart = Uart::new(0, true);
+    assert_eq!(uart.read_u8(IIR) & 0xc0, 0, "16450 mode after reset");
+    uart.write_u8(FCR, 0x01);
+    assert_eq!(uart.read_u8(IIR) & 0xc0, 0xc0, "FIFOs enabled");
+    uart.write_u8(FCR, 0x00);
+    assert_eq!(uart.read_u8(IIR) & 0xc0, 0);
+}
+
+#[test]
+fn fifo_holds_sixteen_bytes() {
+    let mut uart = Uart::new(0, true);
+    uart.write_u8(FCR, 0x01);
+    for b in 0..20u8 {
+        uart.push_rx(b);
+    }
+    uart.write_u8(FCR, 0x03); // clear RX: only the 16 resident bytes go
+    assert_eq!(uart.read_u8(RBR), 16, "later bytes were still on the line");
+}
+
+#[test]
+fn trigger_level_and_character_timeout() {
+    let mut uart = Uart::new(0, true);
+    uart.write_u8(FCR, 0x81); // FIFOs on, trigger at 8 bytes
+    uart.write_u8(IER, 0x01);
+    for b in 0..3 {
+        uart.push_rx(b);
+    }
+    assert!(!uart.tick(), "below the trigger level");
+    assert_eq!(uart.read_u8(LSR) & 0x01, 0x01, "data ready regardless");
+
+    let mut ticks = 1;
+    while !uart.tick() {
+        ticks += 1;
+        assert!(ticks < 10_000, "character timeout never fired");
+    }
+    assert_eq!(uart.read_u8(IIR), 0xcc, "character timeout");
+
+    for b in 3..8 {
+        uart.push_rx(b);
+    }
+    assert_eq!(uart.read_u8(IIR), 0xc4, "trigger level reached");
+}
+
+// ══════════════════════════════════════════════════════════
+// 4. Loopback
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn loopback_routes_thr_to_rbr_and_mcr_to_msr() {
+    let mut uart = Uart::new(0, true);
+    assert_eq!(uart.read_u8(MSR), 0);
+    uart.write_u8(MCR, 0x1a); // loop, RTS, OUT2
+    assert_eq!(uart.read_u8(MSR) & 0xf0, 0x90, "CTS and DCD");
+
+    uart.write_u8(THR, b'L');
+    assert_eq!(uart.read_u8(LSR) & 0x01, 0x01);
+    assert_eq!(uart.read_u8(RBR), b'L');
+}
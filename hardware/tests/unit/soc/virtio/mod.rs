@@ -1 +1,2 @@
+pub mod block_io;
 pub mod queue_descriptors;
@@ -1,9 +1,13 @@
 //! SimStats unit tests.
 //!
-//! Verifies default initialization, field mutation, and derived metric
-//! computation for the simulation statistics structure.
+//! Verifies default initialization, field mutation, derived metric
+//! computation, and the JSON report of the simulation statistics structure.
 
-use riscv_core::stats::SimStats;
+use crate::common::builder::instruction::InstructionBuilder;
+use crate::common::harness::TestContext;
+use riscv_core::config::Config;
+use riscv_core::stats::{CacheLatencies, ProgressMeter, SimStats};
+use std::time::Duration;
 
 #[test]
 fn default_stats_all_zero() {
@@ -78,6 +82,61 @@ fn stats_branch_prediction_accuracy() {
     assert!((accuracy - 0.9).abs() < 1e-10);
 }
 
+#[test]
+fn record_branch_updates_aggregate_and_category() {
+    let mut stats = SimStats::default();
+    stats.record_branch("cond_taken", false);
+    stats.record_branch("cond_taken", true);
+    stats.record_branch("return", false);
+
+    assert_eq!(stats.branch_predictions, 2);
+    assert_eq!(stats.branch_mispredictions, 1);
+    assert_eq!(stats.branches_by_type["cond_taken"], 2);
+    assert_eq!(stats.branches_by_type["return"], 1);
+    assert_eq!(stats.mispredicts_by_type["cond_taken"], 1);
+    assert!(!stats.mispredicts_by_type.contains_key("return"));
+}
+
+#[test]
+fn branch_categories_sum_to_aggregate() {
+    const BASE: u64 = 0x8000_0000;
+    let b = InstructionBuilder::new;
+    let program = [
+        b().addi(5, 0, 3).build(),  // 0x00
+        b().jal(1, 0x20).build(),   // 0x04: loop: call func
+        b().jalr(0, 7, 0).build(),  // 0x08: indirect -> 0x10
+        b().nop().build(),          // 0x0C
+        b().addi(5, 5, -1).build(), // 0x10
+        b().bne(5, 0, -16).build(), // 0x14: -> loop
+        b().jal(0, 8).build(),      // 0x18: -> 0x20
+        b().nop().build(),          // 0x1C
+        b().jal(0, 0).build(),      // 0x20: spin
+        b().jalr(0, 1, 0).build(),  // 0x24: func: ret
+    ];
+    let mut tc = TestContext::new()
+        .with_memory(0x1000, BASE)
+        .load_program(BASE, &program);
+    tc.set_reg(7, BASE + 0x10);
+    tc.run(300);
+
+    let stats = &tc.cpu.stats;
+    assert_eq!(stats.branches_by_type["cond_taken"], 2);
+    assert_eq!(stats.branches_by_type["cond_nottaken"], 1);
+    assert_eq!(stats.branches_by_type["call"], 3);
+    assert_eq!(stats.branches_by_type["return"], 3);
+    assert_eq!(stats.branches_by_type["indirect"], 3);
+    assert!(stats.branches_by_type["jump"] >= 2, "exit jump and spin");
+
+    let lookups: u64 = stats.branches_by_type.values().sum();
+    let misses: u64 = stats.mispredicts_by_type.values().sum();
+    assert_eq!(
+        lookups,
+        stats.branch_predictions + stats.branch_mispredictions
+    );
+    assert_eq!(misses, stats.branch_mispredictions);
+    assert!(misses > 0, "cold BTB mispredicts");
+}
+
 #[test]
 fn stats_cache_hit_rate() {
     let mut stats = SimStats::default();
@@ -151,5 +210,178 @@ fn stats_sections_constant_available() {
     assert!(STATS_SECTIONS.contains(&"instruction_mix"));
     assert!(STATS_SECTIONS.contains(&"branch"));
     assert!(STATS_SECTIONS.contains(&"memory"));
-    assert_eq!(STATS_SECTIONS.len(), 5);
+    assert!(STATS_SECTIONS.contains(&"mmu"));
+    assert_eq!(STATS_SECTIONS.len(), 6);
+}
+
+#[test]
+fn measurement_window_excludes_setup_time() {
+    const BASE: u64 = 0x8000_0000;
+    const IMAGE_BYTES: usize = 8 * 1024 * 1024;
+    let setup_delay = Duration::from_millis(200);
+
+    let mut tc = TestContext::new().with_memory(IMAGE_BYTES, BASE);
+    let image = vec![0u8; IMAGE_BYTES];
+    tc.cpu.bus.load_binary_at(&image, BASE);
+    // Stand-in for slow host-side setup (disk images, DTB generation, ...).
+    std::thread::sleep(setup_delay);
+
+    let looped = [
+        InstructionBuilder::new().addi(1, 1, 1).build(),
+        InstructionBuilder::new().jal(0, -4).build(),
+    ];
+    let mut tc = tc.load_program(BASE, &looped);
+    tc.cpu.start_measurement();
+    tc.run(2_000);
+    tc.cpu.stop_measurement();
+
+    let elapsed = tc.cpu.stats.host_elapsed();
+    assert!(
+        elapsed < setup_delay,
+        "window {:?} should not include the {:?} setup",
+        elapsed,
+        setup_delay
+    );
+    assert!(tc.cpu.stats.instructions_retired > 0);
+    assert!(tc.cpu.stats.mips() > 0.0);
+
+    // A closed window is frozen.
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(tc.cpu.stats.host_elapsed(), elapsed);
+}
+
+#[test]
+fn measurement_starts_on_first_tick() {
+    let mut tc = TestContext::new().with_memory(4096, 0x8000_0000);
+    assert!(!tc.cpu.stats.is_measuring());
+    assert_eq!(tc.cpu.stats.mips(), 0.0);
+    tc.run(1);
+    assert!(tc.cpu.stats.is_measuring());
+}
+
+#[test]
+fn progress_meter_reports_window_rate() {
+    let mut stats = SimStats::default();
+    let mut meter = ProgressMeter::new(&stats, Duration::from_millis(10));
+    assert!(meter.sample(&stats).is_none(), "interval not yet elapsed");
+
+    std::thread::sleep(Duration::from_millis(20));
+    stats.instructions_retired = 1_000_000;
+    stats.cycles = 2_000_000;
+    let (mips, khz) = meter.sample(&stats).expect("interval elapsed");
+    assert!(mips > 0.0);
+    assert!(
+        khz > mips * 1000.0,
+        "more cycles than instructions in window"
+    );
+
+    std::thread::sleep(Duration::from_millis(20));
+    let (mips, _) = meter.sample(&stats).expect("interval elapsed");
+    assert_eq!(mips, 0.0, "no new instructions in the second window");
+}
+
+#[test]
+fn mpki_is_zero_before_retirement() {
+    let mut stats = SimStats::default();
+    stats.dcache_misses = 10;
+    assert_eq!(stats.mpki(stats.dcache_misses), 0.0);
+    stats.instructions_retired = 4000;
+    assert_eq!(stats.mpki(stats.dcache_misses), 2.5);
+}
+
+#[test]
+fn amat_chains_enabled_levels_to_memory() {
+    let mut stats = SimStats::default();
+    stats.cache_latencies = CacheLatencies {
+        l1_d: Some(2),
+        l2: Some(10),
+        ..CacheLatencies::default()
+    };
+    stats.dcache_hits = 75;
+    stats.dcache_misses = 25;
+    stats.l2_hits = 20;
+    stats.l2_misses = 5;
+    stats.mem_accesses = 5;
+    stats.mem_access_cycles = 500;
+
+    let amat = stats.amat();
+    // L2: 10 + 0.2 * 100 = 30; L1-D: 2 + 0.25 * 30 = 9.5.
+    assert_eq!(amat.l2, Some(30.0));
+    assert_eq!(amat.l1_d, Some(9.5));
+    assert_eq!(amat.l1_i, None);
+    assert_eq!(amat.l3, None);
+}
+
+/// A loop that loads a new line every iteration misses the L1-D once per
+/// four instructions: 250 MPKI, less the handful of setup instructions.
+#[test]
+fn miss_heavy_loop_mpki_matches_hand_count() {
+    const CODE: u64 = 0x1000;
+    const DATA: u64 = 0x8000_0000;
+    const ITERS: u64 = 200;
+
+    let mut config = Config::default();
+    config.cache.l1_d.enabled = true;
+    config.cache.l1_d.size_bytes = 1024;
+    config.cache.l1_d.latency = 2;
+
+    // loop: ld x1, 0(x10); addi x10, x10, 64; addi x11, x11, -1; bne x11, x0, loop
+    let program = [
+        InstructionBuilder::new().ld(1, 10, 0).build(),
+        InstructionBuilder::new().addi(10, 10, 64).build(),
+        InstructionBuilder::new().addi(11, 11, -1).build(),
+        InstructionBuilder::new().bne(11, 0, -12).build(),
+        InstructionBuilder::new().jal(0, 0).build(),
+    ];
+    let mut tc = TestContext::from_config(&config)
+        .with_memory(0x1000, CODE)
+        .with_memory(ITERS as usize * 64, DATA)
+        .load_program(CODE, &program);
+    tc.cpu.mmio_base = DATA;
+    tc.set_reg(10, DATA);
+    tc.set_reg(11, ITERS);
+
+    let mut cycles = 0;
+    while tc.get_reg(11) != 0 || tc.cpu.stats.instructions_retired < 4 * ITERS {
+        assert!(cycles < 100_000, "loop did not finish");
+        tc.cpu.tick().unwrap();
+        cycles += 1;
+    }
+
+    let stats = &tc.cpu.stats;
+    assert_eq!(stats.dcache_misses, ITERS);
+    let expected = 1000.0 * ITERS as f64 / stats.instructions_retired as f64;
+    assert!((stats.mpki(stats.dcache_misses) - expected).abs() < 1e-9);
+    assert!((stats.mpki(stats.dcache_misses) - 250.0).abs() < 1.0);
+
+    // Every access misses, so the L1-D AMAT is its latency plus a memory access.
+    let amat = stats.amat().l1_d.unwrap();
+    assert!((amat - (2.0 + stats.mem_latency())).abs() < 1e-9);
+}
+
+#[test]
+fn json_report_round_trips_counters_and_derived_metrics() {
+    let mut stats = SimStats::default();
+    stats.record_branch("cond_taken", true);
+    stats.cycles = 2000;
+    stats.instructions_retired = 1000;
+    stats.branch_predictions = 90;
+    stats.branch_mispredictions = 10;
+    stats.dcache_hits = 80;
+    stats.dcache_misses = 20;
+
+    let json: serde_json::Value =
+        serde_json::from_str(&stats.to_json()).expect("to_json emits valid JSON");
+
+    assert_eq!(json["summary"]["sim_cycles"], stats.cycles);
+    assert_eq!(json["summary"]["sim_insts"], stats.instructions_retired);
+    assert_eq!(json["summary"]["sim_ipc"], 0.5);
+    assert_eq!(json["summary"]["sim_cpi"], 2.0);
+    assert_eq!(json["branch"]["bp.lookups"], 100);
+    assert_eq!(json["branch"]["bp.accuracy"], 90.0);
+    assert_eq!(json["branch"]["bp.by_type"]["cond_taken"]["mispredicts"], 1);
+    assert_eq!(json["memory"]["L1-D"]["misses"], 20);
+    assert_eq!(json["memory"]["L1-D"]["mpki"], stats.mpki(20));
+    assert!(json["memory"]["L3"]["amat"].is_null(), "disabled level");
+    assert_eq!(json["mmu"]["page_walks"], 0);
 }
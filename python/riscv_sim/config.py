@@ -21,30 +21,56 @@ Example:
 from __future__ import annotations
 
 from dataclasses import dataclass, field
-from typing import Any, Dict, List, Literal, Optional
-
-MemoryControllerT = Literal["Simple", "Dram"]
+from typing import Any, Dict, List, Literal, Optional, Union
+
+MemoryControllerT = Literal["Simple", "Dram", "Hbm"]
+ChannelInterleaveT = Literal["Line", "Page"]
+MisalignedPriorityT = Literal["BeforeTranslation", "AfterTranslation"]
+MisalignedAccessT = Literal["Emulate", "Trap"]
+TlbRefillT = Literal["Hardware", "Software"]
+AdUpdateT = Literal["Hardware", "Fault"]
 ReplacementPolicyT = Literal["LRU", "PLRU", "FIFO", "Random", "MRU"]
 PrefetcherT = Literal["None", "NextLine", "Stride", "Stream", "Tagged"]
+WritePolicyT = Literal["WriteBack", "WriteThrough"]
+AllocPolicyT = Literal["WriteAllocate", "NoWriteAllocate"]
 BranchPredictorT = Literal["Static", "GShare", "Perceptron", "TAGE", "Tournament"]
+# Byte count: an int, or a string such as "128M", "0x8000000", or "32K" (powers of 1024).
+SizeT = Union[int, str]
 
 
 @dataclass
 class GeneralConfig:
-    """General simulation settings (tracing, start PC, direct mode, initial stack pointer)."""
+    """General simulation settings (tracing, start PC, direct mode, initial stack pointer, undo depth)."""
     trace_instructions: bool = False
     start_pc: int = 0x8000_0000
     direct_mode: bool = True
     initial_sp: Optional[int] = None
+    undo_depth: int = 0
+    trace_traps: bool = False
+    verify_retire_order: bool = False
+    trap_on_wfi: bool = False
+    profile: bool = False
+    monitor_mode: bool = False
+    builtin_sbi: bool = False
+    symbols: Optional[str] = None
 
     def to_dict(self) -> Dict[str, Any]:
         d: Dict[str, Any] = {
             "trace_instructions": self.trace_instructions,
             "start_pc": self.start_pc,
             "direct_mode": self.direct_mode,
+            "undo_depth": self.undo_depth,
+            "trace_traps": self.trace_traps,
+            "verify_retire_order": self.verify_retire_order,
+            "trap_on_wfi": self.trap_on_wfi,
+            "profile": self.profile,
+            "monitor_mode": self.monitor_mode,
+            "builtin_sbi": self.builtin_sbi,
         }
         if self.initial_sp is not None:
             d["initial_sp"] = self.initial_sp
+        if self.symbols is not None:
+            d["symbols"] = self.symbols
         return d
 
 
@@ -60,7 +86,16 @@ class SystemConfig:
     bus_width: int = 8
     bus_latency: int = 4
     clint_divider: int = 10
+    clint_min_interval: int = 0
     uart_to_stderr: bool = False
+    rtc_enabled: bool = True
+    rtc_base: int = 0x0010_1000
+    rtc_ns_per_tick: int = 10
+    nvram_base: int = 0x0011_0000
+    nvram_size: int = 0
+    nvram_path: Optional[str] = None
+    nvram_writeback: bool = False
+    harts: int = 1
 
     def to_dict(self) -> Dict[str, Any]:
         return {
@@ -73,20 +108,45 @@ class SystemConfig:
             "bus_width": self.bus_width,
             "bus_latency": self.bus_latency,
             "clint_divider": self.clint_divider,
+            "clint_min_interval": self.clint_min_interval,
             "uart_to_stderr": self.uart_to_stderr,
+            "rtc_enabled": self.rtc_enabled,
+            "rtc_base": self.rtc_base,
+            "rtc_ns_per_tick": self.rtc_ns_per_tick,
+            "nvram_base": self.nvram_base,
+            "nvram_size": self.nvram_size,
+            "nvram_path": self.nvram_path,
+            "nvram_writeback": self.nvram_writeback,
+            "harts": self.harts,
         }
 
 
 @dataclass
 class MemoryConfig:
     """Main memory configuration (RAM size, controller type, DRAM timing, TLB size)."""
-    ram_size: int = 0x1000_0000
+    ram_size: SizeT = 0x1000_0000
     controller: MemoryControllerT = "Simple"
     t_cas: int = 14
     t_ras: int = 14
     t_pre: int = 14
     row_miss_latency: int = 120
+    dram_banks: int = 8
+    dram_row_bytes: int = 2048
+    t_refi: int = 7800
+    t_rfc: int = 350
+    channels: int = 1
+    channel_interleave: ChannelInterleaveT = "Line"
     tlb_size: int = 32
+    bandwidth_bytes_per_cycle: int = 0
+    hbm_channels: int = 8
+    hbm_burst_cycles: int = 4
+    misaligned_priority: MisalignedPriorityT = "BeforeTranslation"
+    misaligned_access: MisalignedAccessT = "Emulate"
+    reservation_bytes: int = 64
+    page_size: int = 4096
+    tlb_refill: TlbRefillT = "Hardware"
+    ad_update: AdUpdateT = "Hardware"
+    pmp_open_when_inactive: bool = True
 
     def to_dict(self) -> Dict[str, Any]:
         return {
@@ -96,7 +156,23 @@ class MemoryConfig:
             "t_ras": self.t_ras,
             "t_pre": self.t_pre,
             "row_miss_latency": self.row_miss_latency,
+            "dram_banks": self.dram_banks,
+            "dram_row_bytes": self.dram_row_bytes,
+            "t_refi": self.t_refi,
+            "t_rfc": self.t_rfc,
+            "channels": self.channels,
+            "channel_interleave": self.channel_interleave,
             "tlb_size": self.tlb_size,
+            "bandwidth_bytes_per_cycle": self.bandwidth_bytes_per_cycle,
+            "hbm_channels": self.hbm_channels,
+            "hbm_burst_cycles": self.hbm_burst_cycles,
+            "misaligned_priority": self.misaligned_priority,
+            "misaligned_access": self.misaligned_access,
+            "reservation_bytes": self.reservation_bytes,
+            "page_size": self.page_size,
+            "tlb_refill": self.tlb_refill,
+            "ad_update": self.ad_update,
+            "pmp_open_when_inactive": self.pmp_open_when_inactive,
         }
 
 
@@ -104,10 +180,15 @@ class MemoryConfig:
 class CacheConfig:
     """Single cache level (L1-I, L1-D, L2, L3)."""
     enabled: bool = False
-    size_bytes: int = 4096
-    line_bytes: int = 64
+    size_bytes: SizeT = 4096
+    line_bytes: SizeT = 64
     ways: int = 1
     policy: ReplacementPolicyT = "LRU"
+    rng_seed: int = 123456789
+    mshrs: int = 0
+    victim_entries: int = 0
+    write_policy: WritePolicyT = "WriteBack"
+    alloc_policy: AllocPolicyT = "WriteAllocate"
     latency: int = 1
     prefetcher: PrefetcherT = "None"
     prefetch_table_size: int = 0
@@ -120,6 +201,11 @@ class CacheConfig:
             "line_bytes": self.line_bytes,
             "ways": self.ways,
             "policy": self.policy,
+            "rng_seed": self.rng_seed,
+            "mshrs": self.mshrs,
+            "victim_entries": self.victim_entries,
+            "write_policy": self.write_policy,
+            "alloc_policy": self.alloc_policy,
             "latency": self.latency,
             "prefetcher": self.prefetcher,
             "prefetch_table_size": self.prefetch_table_size,
@@ -183,6 +269,14 @@ class PipelineConfig:
     branch_predictor: BranchPredictorT = "Static"
     btb_size: int = 256
     ras_size: int = 8
+    zba: bool = True
+    zbb: bool = True
+    zbc: bool = True
+    macro_op_fusion: bool = False
+    zihintntl: bool = False
+    flush_subnormals: bool = False
+    div_latency: int = 30
+    div_early_exit: bool = False
     tage: TageConfig = field(default_factory=TageConfig)
     perceptron: PerceptronConfig = field(default_factory=PerceptronConfig)
     tournament: TournamentConfig = field(default_factory=TournamentConfig)
@@ -193,12 +287,37 @@ class PipelineConfig:
             "branch_predictor": self.branch_predictor,
             "btb_size": self.btb_size,
             "ras_size": self.ras_size,
+            "zba": self.zba,
+            "zbb": self.zbb,
+            "zbc": self.zbc,
+            "macro_op_fusion": self.macro_op_fusion,
+            "zihintntl": self.zihintntl,
+            "flush_subnormals": self.flush_subnormals,
+            "div_latency": self.div_latency,
+            "div_early_exit": self.div_early_exit,
             "tage": self.tage.to_dict(),
             "perceptron": self.perceptron.to_dict(),
             "tournament": self.tournament.to_dict(),
         }
 
 
+@dataclass
+class FpuConfig:
+    """Floating-point unit latencies in cycles (0 or 1 is single-cycle)."""
+    fdiv_latency: int = 20
+    fsqrt_latency: int = 24
+    fmul_latency: int = 4
+    fma_latency: int = 5
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "fdiv_latency": self.fdiv_latency,
+            "fsqrt_latency": self.fsqrt_latency,
+            "fmul_latency": self.fmul_latency,
+            "fma_latency": self.fma_latency,
+        }
+
+
 @dataclass
 class CacheHierarchyConfig:
     """L1-I, L1-D, L2, L3 cache configuration."""
@@ -206,6 +325,12 @@ class CacheHierarchyConfig:
     l1_d: CacheConfig = field(default_factory=CacheConfig)
     l2: CacheConfig = field(default_factory=CacheConfig)
     l3: CacheConfig = field(default_factory=CacheConfig)
+    wrong_path_pollution: bool = False
+    l2_split: bool = False
+    icache_snoop: bool = False
+    l2_i: CacheConfig = field(default_factory=CacheConfig)
+    l3_ports: int = 0
+    l3_port_cycles: int = 1
 
     def to_dict(self) -> Dict[str, Any]:
         return {
@@ -213,6 +338,12 @@ class CacheHierarchyConfig:
             "l1_d": self.l1_d.to_dict(),
             "l2": self.l2.to_dict(),
             "l3": self.l3.to_dict(),
+            "wrong_path_pollution": self.wrong_path_pollution,
+            "l2_split": self.l2_split,
+            "icache_snoop": self.icache_snoop,
+            "l2_i": self.l2_i.to_dict(),
+            "l3_ports": self.l3_ports,
+            "l3_port_cycles": self.l3_port_cycles,
         }
 
 
@@ -228,6 +359,8 @@ class SimConfig:
     memory: MemoryConfig = field(default_factory=MemoryConfig)
     cache: CacheHierarchyConfig = field(default_factory=CacheHierarchyConfig)
     pipeline: PipelineConfig = field(default_factory=PipelineConfig)
+    fpu: FpuConfig = field(default_factory=FpuConfig)
+    csr_reset: Dict[int, int] = field(default_factory=dict)
 
     def to_dict(self) -> Dict[str, Any]:
         """Produce the nested dict expected by the Rust backend (JSON round-trip)."""
@@ -237,6 +370,8 @@ class SimConfig:
             "memory": self.memory.to_dict(),
             "cache": self.cache.to_dict(),
             "pipeline": self.pipeline.to_dict(),
+            "fpu": self.fpu.to_dict(),
+            "csr_reset": {hex(addr): val for addr, val in self.csr_reset.items()},
         }
 
     @classmethod
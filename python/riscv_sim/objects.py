@@ -242,7 +242,7 @@ def simulate(cpu, print_stats=True, stats_sections=None):
     """Run the CPU until the program exits.
     If print_stats is True (default), print stats after run (gem5-style).
     stats_sections: optional list of section names to print. If None, prints full dump.
-    Sections: "summary", "core", "instruction_mix", "branch", "memory".
+    Sections: "summary", "core", "instruction_mix", "branch", "memory", "mmu".
     Example: stats_sections=["summary", "memory"] for cycles + cache stats only (e.g. multisim sweep).
     """
     print("Starting simulation...")
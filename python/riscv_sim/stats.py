@@ -14,9 +14,15 @@ class StatsObject(dict):
 
     All stats from the backend are accessible as keys. Typical keys include:
     cycles, instructions_retired, ipc, icache_hits, icache_misses, dcache_hits,
-    dcache_misses, l2_hits, l2_misses, l3_hits, l3_misses, stalls_mem, stalls_control,
-    stalls_data, branch_predictions, branch_mispredictions, branch_accuracy_pct,
-    cycles_user, cycles_kernel, cycles_machine, traps_taken, inst_load, inst_store,
+    dcache_misses, l2_hits, l2_misses, l3_hits, l3_misses, icache_mpki, dcache_mpki,
+    l2_mpki, l3_mpki, amat, mem_accesses, mem_avg_latency, icache_snoop_invalidations,
+    mshr_overlapped_misses,
+    mshr_merged_misses, stalls_mshr_full, victim_hits, itlb_hits, itlb_misses,
+    dtlb_hits, dtlb_misses, page_walks, stalls_mem, stalls_control,
+    stalls_data, stalls_fpu, stalls_div,
+    branch_predictions, branch_mispredictions, branch_accuracy_pct,
+    branches_by_type, mispredicts_by_type,
+    cycles_user, cycles_kernel, cycles_machine, cycles_idle, traps_taken, inst_load, inst_store,
     inst_branch, inst_alu, inst_system, inst_fp_load, inst_fp_store, inst_fp_arith,
     inst_fp_fma, inst_fp_div_sqrt.
 